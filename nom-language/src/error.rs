@@ -1,25 +1,32 @@
 use std::fmt;
 
 use nom::{
-  error::{ContextError, ErrorKind, FromExternalError, ParseError},
+  error::{ContextError, ErrorKind, FromExternalError, MapInput, ParseError},
   ErrorConvert,
 };
 
 /// This error type accumulates errors and their position when backtracking
 /// through a parse tree. With some post processing,
 /// it can be used to display user friendly error messages
-#[derive(Clone, Debug, Eq, PartialEq)]
+///
+/// With the `serde` feature, it implements `Serialize`. It does not implement
+/// `Deserialize`, since the context strings are `&'static str`.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct VerboseError<I> {
   /// List of errors accumulated by `VerboseError`, containing the affected
   /// part of input data, and some context
   pub errors: Vec<(I, VerboseErrorKind)>,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 /// Error context for `VerboseError`
 pub enum VerboseErrorKind {
   /// Static string added by the `context` function
   Context(&'static str),
+  /// Context formatted on demand, added by combinators like `many_labeled`
+  Label(String),
   /// Indicates which character was expected by the `char` function
   Char(char),
   /// Error kind given by various nom parsers
@@ -50,6 +57,13 @@ impl<I> ContextError<I> for VerboseError<I> {
     other.errors.push((input, VerboseErrorKind::Context(ctx)));
     other
   }
+
+  fn add_lazy_context(input: I, ctx: &dyn fmt::Display, mut other: Self) -> Self {
+    other
+      .errors
+      .push((input, VerboseErrorKind::Label(ctx.to_string())));
+    other
+  }
 }
 
 impl<I, E> FromExternalError<I, E> for VerboseError<I> {
@@ -67,6 +81,7 @@ impl<I: fmt::Display> fmt::Display for VerboseError<I> {
         VerboseErrorKind::Nom(e) => writeln!(f, "{:?} at: {}", e, input)?,
         VerboseErrorKind::Char(c) => writeln!(f, "expected '{}' at: {}", c, input)?,
         VerboseErrorKind::Context(s) => writeln!(f, "in section '{}', at: {}", s, input)?,
+        VerboseErrorKind::Label(s) => writeln!(f, "in section '{}', at: {}", s, input)?,
       }
     }
 
@@ -100,6 +115,30 @@ impl From<VerboseError<&str>> for VerboseError<String> {
   }
 }
 
+impl<I> MapInput<I> for VerboseError<I> {
+  type Mapped<U> = VerboseError<U>;
+
+  fn map_input<U, F: FnMut(I) -> U>(self, mut f: F) -> VerboseError<U> {
+    VerboseError {
+      errors: self.errors.into_iter().map(|(i, e)| (f(i), e)).collect(),
+    }
+  }
+}
+
+impl<I: nom::Offset> VerboseError<I> {
+  /// Replaces the input positions with their offsets in `original`, the whole input given
+  /// to the parser, so the error does not borrow the input anymore.
+  pub fn offset_in(self, original: &I) -> VerboseError<usize> {
+    VerboseError {
+      errors: self
+        .errors
+        .into_iter()
+        .map(|(i, e)| (original.offset(&i), e))
+        .collect(),
+    }
+  }
+}
+
 impl<I> ErrorConvert<VerboseError<I>> for VerboseError<(I, usize)> {
   fn convert(self) -> VerboseError<I> {
     VerboseError {
@@ -135,6 +174,7 @@ pub fn convert_error<I: core::ops::Deref<Target = str>>(input: I, e: VerboseErro
           write!(&mut result, "{}: expected '{}', got empty input\n\n", i, c)
         }
         VerboseErrorKind::Context(s) => write!(&mut result, "{}: in {}, got empty input\n\n", i, s),
+        VerboseErrorKind::Label(s) => write!(&mut result, "{}: in {}, got empty input\n\n", i, s),
         VerboseErrorKind::Nom(e) => write!(&mut result, "{}: in {:?}, got empty input\n\n", i, e),
       }
     } else {
@@ -207,6 +247,18 @@ pub fn convert_error<I: core::ops::Deref<Target = str>>(input: I, e: VerboseErro
           caret = '^',
           column = column_number,
         ),
+        VerboseErrorKind::Label(s) => write!(
+          &mut result,
+          "{i}: at line {line_number}, in {context}:\n\
+             {line}\n\
+             {caret:>column$}\n\n",
+          i = i,
+          line_number = line_number,
+          context = s,
+          line = line,
+          caret = '^',
+          column = column_number,
+        ),
         VerboseErrorKind::Nom(e) => write!(
           &mut result,
           "{i}: at line {line_number}, in {nom_err:?}:\n\
@@ -260,3 +312,55 @@ fn issue_1027_convert_error_panic_nonempty() {
     "0: at line 1:\na\n ^\nexpected \'b\', got end of input\n\n"
   );
 }
+
+#[test]
+fn verbose_error_offsets() {
+  use nom::character::complete::char;
+  use nom::error::context;
+  use nom::sequence::preceded;
+  use nom::IResult;
+  use nom::Parser;
+
+  let input = "ab";
+
+  let result: IResult<_, _, VerboseError<&str>> =
+    context("pair", preceded(char('a'), char('c'))).parse(input);
+  let err = match result.unwrap_err() {
+    nom::Err::Error(e) => e,
+    _ => unreachable!(),
+  };
+
+  assert_eq!(
+    err.offset_in(&input).errors,
+    [
+      (1, VerboseErrorKind::Char('c')),
+      (0, VerboseErrorKind::Context("pair"))
+    ]
+  );
+}
+
+#[test]
+fn verbose_error_into_owned() {
+  use nom::character::complete::char;
+  use nom::error::context;
+  use nom::sequence::preceded;
+  use nom::IResult;
+  use nom::Parser;
+
+  let err = {
+    let input = String::from("ab");
+    let result: IResult<_, _, VerboseError<&str>> =
+      context("pair", preceded(char('a'), char('c'))).parse(input.as_str());
+    result.map_err(|e| e.into_owned()).unwrap_err()
+  };
+
+  assert_eq!(
+    err,
+    nom::Err::Error(VerboseError {
+      errors: vec![
+        (String::from("b"), VerboseErrorKind::Char('c')),
+        (String::from("ab"), VerboseErrorKind::Context("pair"))
+      ]
+    })
+  );
+}
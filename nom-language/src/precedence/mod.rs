@@ -3,6 +3,7 @@
 #[cfg(test)]
 mod tests;
 
+use nom::combinator::DepthLimit;
 use nom::error::{ErrorKind, FromExternalError, ParseError};
 use nom::{Check, Err, IResult, Input, Mode, OutputM, OutputMode, Parser};
 
@@ -217,88 +218,161 @@ where
   H3: Parser<I, Output = Binary<P3, Q>, Error = E>,
   Q: Ord + Copy,
 {
-  move |mut i| {
-    let mut operands = Vec::new();
-    let mut operators = Vec::new();
-    let mut i1 = i.clone();
-
-    'main: loop {
-      'prefix: loop {
-        match prefix.parse(i1.clone()) {
-          Err(Err::Error(_)) => break 'prefix,
-          Err(e) => return Err(e),
-          Ok((i2, o)) => {
-            // infinite loop check: the parser must always consume
-            if i2 == i1 {
-              return Err(Err::Error(E::from_error_kind(i1, ErrorKind::Precedence)));
-            }
-            i1 = i2;
-            operators.push(Operator::Prefix(o.value, o.precedence));
-          }
-        }
-      }
+  move |i| {
+    parse_precedence(
+      &mut prefix,
+      &mut postfix,
+      &mut binary,
+      &mut operand,
+      &mut fold,
+      i,
+    )
+  }
+}
 
-      let (i2, o) = match operand.parse(i1.clone()) {
-        Ok((i, o)) => (i, o),
-        Err(Err::Error(e)) => return Err(Err::Error(E::append(i, ErrorKind::Precedence, e))),
-        Err(e) => return Err(e),
-      };
-      i1 = i2;
-      operands.push(o);
+/// Parses an expression with operator precedence, like [precedence], and counts one
+/// level of nesting in a [DepthLimit] while parsing it.
+///
+/// Operators are folded with an explicit stack, but operands usually recurse into the
+/// expression parser, as with parenthesized sub-expressions. Sharing a [DepthLimit]
+/// between the recursive calls bounds that recursion: once the maximum depth is exceeded,
+/// this returns a failure with [ErrorKind::Depth] instead of overflowing the stack.
+///
+/// # Arguments
+/// * `limit` The nesting depth limit shared by the recursive parsers.
+/// * The other arguments are the same as in [precedence].
+///
+/// # Example
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult};
+/// use nom_language::precedence::{precedence_with_depth, unary_op, binary_op, Assoc, Operation};
+/// use nom::character::complete::digit1;
+/// use nom::combinator::{map_res, fail, DepthLimit};
+/// use nom::sequence::delimited;
+/// use nom::bytes::complete::tag;
+/// use nom::branch::alt;
+///
+/// fn parser<'a>(limit: &DepthLimit, i: &'a str) -> IResult<&'a str, i64> {
+///   precedence_with_depth(
+///     limit,
+///     unary_op(1, tag("-")),
+///     fail(),
+///     binary_op(2, Assoc::Left, tag("+")),
+///     alt((
+///       map_res(digit1, |s: &str| s.parse::<i64>()),
+///       delimited(tag("("), |i| parser(limit, i), tag(")")),
+///     )),
+///     |op: Operation<&str, &str, &str, i64>| {
+///       use nom_language::precedence::Operation::*;
+///       match op {
+///         Prefix("-", o) => Ok(-o),
+///         Binary(lhs, "+", rhs) => Ok(lhs + rhs),
+///         _ => Err("Invalid combination"),
+///       }
+///     }
+///   )(i)
+/// }
+///
+/// let limit = DepthLimit::new(3);
+/// assert_eq!(parser(&limit, "1+(2+(-3))"), Ok(("", 0)));
+/// assert_eq!(
+///   parser(&limit, "1+(2+(3+(4)))"),
+///   Err(Err::Failure(Error::new("4)))", ErrorKind::Depth)))
+/// );
+/// ```
+pub fn precedence_with_depth<'d, I, O, E, E2, F, G, H1, H3, H2, P1, P2, P3, Q>(
+  limit: &'d DepthLimit,
+  mut prefix: H1,
+  mut postfix: H2,
+  mut binary: H3,
+  mut operand: F,
+  mut fold: G,
+) -> impl FnMut(I) -> IResult<I, O, E> + 'd
+where
+  I: Clone + PartialEq,
+  E: ParseError<I> + FromExternalError<I, E2>,
+  F: Parser<I, Output = O, Error = E> + 'd,
+  G: FnMut(Operation<P1, P2, P3, O>) -> Result<O, E2> + 'd,
+  H1: Parser<I, Output = Unary<P1, Q>, Error = E> + 'd,
+  H2: Parser<I, Output = Unary<P2, Q>, Error = E> + 'd,
+  H3: Parser<I, Output = Binary<P3, Q>, Error = E> + 'd,
+  Q: Ord + Copy,
+{
+  move |i| {
+    limit
+      .nest(|i| {
+        parse_precedence(
+          &mut prefix,
+          &mut postfix,
+          &mut binary,
+          &mut operand,
+          &mut fold,
+          i,
+        )
+      })
+      .parse(i)
+  }
+}
 
-      'postfix: loop {
-        match postfix.parse(i1.clone()) {
-          Err(Err::Error(_)) => break 'postfix,
-          Err(e) => return Err(e),
-          Ok((i2, o)) => {
-            // infinite loop check: the parser must always consume
-            if i2 == i1 {
-              return Err(Err::Error(E::from_error_kind(i1, ErrorKind::Precedence)));
-            }
+fn parse_precedence<I, O, E, E2, F, G, H1, H3, H2, P1, P2, P3, Q>(
+  prefix: &mut H1,
+  postfix: &mut H2,
+  binary: &mut H3,
+  operand: &mut F,
+  fold: &mut G,
+  mut i: I,
+) -> IResult<I, O, E>
+where
+  I: Clone + PartialEq,
+  E: ParseError<I> + FromExternalError<I, E2>,
+  F: Parser<I, Output = O, Error = E>,
+  G: FnMut(Operation<P1, P2, P3, O>) -> Result<O, E2>,
+  H1: Parser<I, Output = Unary<P1, Q>, Error = E>,
+  H2: Parser<I, Output = Unary<P2, Q>, Error = E>,
+  H3: Parser<I, Output = Binary<P3, Q>, Error = E>,
+  Q: Ord + Copy,
+{
+  let mut operands = Vec::new();
+  let mut operators = Vec::new();
+  let mut i1 = i.clone();
 
-            while operators
-              .last()
-              .map(|op| op.precedence() <= o.precedence)
-              .unwrap_or(false)
-            {
-              let value = operands.pop().unwrap();
-              let operation = match operators.pop().unwrap() {
-                Operator::Prefix(op, _) => Operation::Prefix(op, value),
-                Operator::Postfix(op, _) => Operation::Postfix(value, op),
-                Operator::Binary(op, _, _) => match operands.pop() {
-                  Some(lhs) => Operation::Binary(lhs, op, value),
-                  None => return Err(Err::Error(E::from_error_kind(i1, ErrorKind::Precedence))),
-                },
-              };
-              let result = match fold(operation) {
-                Err(e) => {
-                  return Err(Err::Error(E::from_external_error(
-                    i,
-                    ErrorKind::Precedence,
-                    e,
-                  )))
-                }
-                Ok(r) => r,
-              };
-              operands.push(result);
-            }
-            i1 = i2;
-            operators.push(Operator::Postfix(o.value, o.precedence));
+  'main: loop {
+    'prefix: loop {
+      match prefix.parse(i1.clone()) {
+        Err(Err::Error(_)) => break 'prefix,
+        Err(e) => return Err(e),
+        Ok((i2, o)) => {
+          // infinite loop check: the parser must always consume
+          if i2 == i1 {
+            return Err(Err::Error(E::from_error_kind(i1, ErrorKind::Precedence)));
           }
+          i1 = i2;
+          operators.push(Operator::Prefix(o.value, o.precedence));
         }
       }
+    }
 
-      match binary.parse(i1.clone()) {
-        Err(Err::Error(_)) => break 'main,
+    let (i2, o) = match operand.parse(i1.clone()) {
+      Ok((i, o)) => (i, o),
+      Err(Err::Error(e)) => return Err(Err::Error(E::append(i, ErrorKind::Precedence, e))),
+      Err(e) => return Err(e),
+    };
+    i1 = i2;
+    operands.push(o);
+
+    'postfix: loop {
+      match postfix.parse(i1.clone()) {
+        Err(Err::Error(_)) => break 'postfix,
         Err(e) => return Err(e),
         Ok((i2, o)) => {
+          // infinite loop check: the parser must always consume
+          if i2 == i1 {
+            return Err(Err::Error(E::from_error_kind(i1, ErrorKind::Precedence)));
+          }
+
           while operators
             .last()
-            .map(|op| {
-              op.precedence() < o.precedence
-                || (o.assoc == Assoc::Left && op.precedence() == o.precedence)
-                || (op.is_postfix())
-            })
+            .map(|op| op.precedence() <= o.precedence)
             .unwrap_or(false)
           {
             let value = operands.pop().unwrap();
@@ -322,49 +396,88 @@ where
             };
             operands.push(result);
           }
-          operators.push(Operator::Binary(o.value, o.precedence, o.assoc));
           i1 = i2;
+          operators.push(Operator::Postfix(o.value, o.precedence));
         }
       }
-
-      // infinite loop check: either operand or operator must consume input
-      if i == i1 {
-        return Err(Err::Error(E::from_error_kind(i, ErrorKind::Precedence)));
-      }
-      i = i1.clone();
     }
 
-    while operators.len() > 0 {
-      let value = match operands.pop() {
-        Some(o) => o,
-        None => return Err(Err::Error(E::from_error_kind(i, ErrorKind::Precedence))),
-      };
-      let operation = match operators.pop().unwrap() {
-        Operator::Prefix(op, _) => Operation::Prefix(op, value),
-        Operator::Postfix(op, _) => Operation::Postfix(value, op),
-        Operator::Binary(op, _, _) => match operands.pop() {
-          Some(lhs) => Operation::Binary(lhs, op, value),
-          None => return Err(Err::Error(E::from_error_kind(i, ErrorKind::Precedence))),
-        },
-      };
-      let result = match fold(operation) {
-        Ok(r) => r,
-        Err(e) => {
-          return Err(Err::Error(E::from_external_error(
-            i,
-            ErrorKind::Precedence,
-            e,
-          )))
+    match binary.parse(i1.clone()) {
+      Err(Err::Error(_)) => break 'main,
+      Err(e) => return Err(e),
+      Ok((i2, o)) => {
+        while operators
+          .last()
+          .map(|op| {
+            op.precedence() < o.precedence
+              || (o.assoc == Assoc::Left && op.precedence() == o.precedence)
+              || (op.is_postfix())
+          })
+          .unwrap_or(false)
+        {
+          let value = operands.pop().unwrap();
+          let operation = match operators.pop().unwrap() {
+            Operator::Prefix(op, _) => Operation::Prefix(op, value),
+            Operator::Postfix(op, _) => Operation::Postfix(value, op),
+            Operator::Binary(op, _, _) => match operands.pop() {
+              Some(lhs) => Operation::Binary(lhs, op, value),
+              None => return Err(Err::Error(E::from_error_kind(i1, ErrorKind::Precedence))),
+            },
+          };
+          let result = match fold(operation) {
+            Err(e) => {
+              return Err(Err::Error(E::from_external_error(
+                i,
+                ErrorKind::Precedence,
+                e,
+              )))
+            }
+            Ok(r) => r,
+          };
+          operands.push(result);
         }
-      };
-      operands.push(result);
+        operators.push(Operator::Binary(o.value, o.precedence, o.assoc));
+        i1 = i2;
+      }
     }
 
-    if operands.len() == 1 {
-      return Ok((i1, operands.pop().unwrap()));
-    } else {
+    // infinite loop check: either operand or operator must consume input
+    if i == i1 {
       return Err(Err::Error(E::from_error_kind(i, ErrorKind::Precedence)));
     }
+    i = i1.clone();
+  }
+
+  while operators.len() > 0 {
+    let value = match operands.pop() {
+      Some(o) => o,
+      None => return Err(Err::Error(E::from_error_kind(i, ErrorKind::Precedence))),
+    };
+    let operation = match operators.pop().unwrap() {
+      Operator::Prefix(op, _) => Operation::Prefix(op, value),
+      Operator::Postfix(op, _) => Operation::Postfix(value, op),
+      Operator::Binary(op, _, _) => match operands.pop() {
+        Some(lhs) => Operation::Binary(lhs, op, value),
+        None => return Err(Err::Error(E::from_error_kind(i, ErrorKind::Precedence))),
+      },
+    };
+    let result = match fold(operation) {
+      Ok(r) => r,
+      Err(e) => {
+        return Err(Err::Error(E::from_external_error(
+          i,
+          ErrorKind::Precedence,
+          e,
+        )))
+      }
+    };
+    operands.push(result);
+  }
+
+  if operands.len() == 1 {
+    return Ok((i1, operands.pop().unwrap()));
+  } else {
+    return Err(Err::Error(E::from_error_kind(i, ErrorKind::Precedence)));
   }
 }
 
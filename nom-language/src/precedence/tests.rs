@@ -71,3 +71,39 @@ fn precedence_test() {
     )))
   );
 }
+
+#[test]
+fn precedence_with_depth_test() {
+  use crate::precedence::precedence_with_depth;
+  use nom::combinator::DepthLimit;
+
+  fn parser<'a>(limit: &DepthLimit, i: &'a str) -> IResult<&'a str, i64> {
+    precedence_with_depth(
+      limit,
+      unary_op(1, tag("-")),
+      fail(),
+      binary_op(2, Assoc::Left, tag("+")),
+      alt((
+        map_res(digit1, |s: &str| s.parse::<i64>()),
+        delimited(tag("("), |i| parser(limit, i), tag(")")),
+      )),
+      |op: Operation<&str, (), &str, i64>| {
+        use crate::precedence::Operation::*;
+        match op {
+          Prefix("-", o) => Ok(-o),
+          Binary(lhs, "+", rhs) => Ok(lhs + rhs),
+          _ => Err("Invalid combination"),
+        }
+      },
+    )(i)
+  }
+
+  let limit = DepthLimit::new(2);
+  assert_eq!(parser(&limit, "1+(2+3)"), Ok(("", 6)));
+  assert_eq!(parser(&limit, "(1)+(2)+(-3)"), Ok(("", 0)));
+  assert_eq!(
+    parser(&limit, "1+((2))"),
+    Err(Err::Failure(error_position!("2))", ErrorKind::Depth)))
+  );
+  assert_eq!(limit.depth(), 0);
+}
@@ -0,0 +1,540 @@
+//! # nom-parse-derive, derive macro for fixed layout binary records
+//!
+//! `#[derive(NomParse)]` implements [nom::record::NomParse] for a struct or an enum,
+//! parsing its fields in declaration order with the [nom::number] and [nom::bytes]
+//! parsers. It is reexported by nom as `nom::record::NomParse` with the `derive`
+//! feature.
+//!
+//! The parser of a field is chosen from its type:
+//! - `u8` to `u128`, `i8` to `i128`, `f32` and `f64` are read with the endianness of
+//!   the field, or else of the container, big endian by default
+//! - `[T; N]` reads `N` elements of type `T`
+//! - `&[u8]` reads bytes, and needs a `len` or `prefix` attribute
+//! - `Vec<T>` reads elements of type `T`, and needs a `count` or `prefix` attribute
+//! - `Option<T>` reads a `T` if the `cond` attribute is true
+//! - any other type is read with its own [NomParse](nom::record::NomParse) implementation
+//!
+//! Records with a lifetime parameter are parsed from `&'a [u8]`, so their fields can
+//! borrow the input, other records are parsed from any input with `u8` elements.
+//!
+//! ## Attributes
+//!
+//! On the struct or enum:
+//! - `#[nom(big)]`, `#[nom(little)]`: default endianness of the numbers
+//! - `#[nom(tag = b"...")]`: bytes that must precede the fields
+//! - `#[nom(selector = u8)]`: for enums, type of the discriminant read before the fields
+//!
+//! On an enum variant:
+//! - `#[nom(tag = pattern)]`: values of the discriminant selecting the variant
+//! - `#[nom(default)]`: variant selected by the values matched by no other variant
+//!
+//! On a field, where expressions can use the fields parsed before it by name:
+//! - `#[nom(big)]`, `#[nom(little)]`: endianness of the field
+//! - `#[nom(tag = b"...")]`: bytes that must precede the field
+//! - `#[nom(len = expr)]`: number of bytes of a `&[u8]` field
+//! - `#[nom(count = expr)]`: number of elements of a `Vec` field
+//! - `#[nom(prefix = u16)]`: the length of a `&[u8]` field or the number of elements of a
+//!   `Vec` field is read first, as a number of this type
+//! - `#[nom(cond = expr)]`: an `Option` field is only parsed if the expression is true
+//! - `#[nom(parse = expr)]`: the field is read with this parser
+//!
+//! ```rust
+//! use nom::error::{Error, ErrorKind};
+//! use nom::record::NomParse;
+//! use nom::Err;
+//!
+//! #[derive(Debug, PartialEq, NomParse)]
+//! #[nom(tag = b"PK", little)]
+//! struct Entry<'a> {
+//!   version: u16,
+//!   flags: u16,
+//!   name_len: u8,
+//!   #[nom(len = name_len)]
+//!   name: &'a [u8],
+//!   #[nom(cond = flags & 1 != 0)]
+//!   crc: Option<u32>,
+//!   #[nom(prefix = u8)]
+//!   extra: Vec<Field>,
+//! }
+//!
+//! #[derive(Debug, PartialEq, NomParse)]
+//! #[nom(selector = u8)]
+//! enum Field {
+//!   #[nom(tag = 1)]
+//!   Size(#[nom(big)] u16),
+//!   #[nom(tag = 2 | 3)]
+//!   Time { seconds: u32 },
+//!   #[nom(default)]
+//!   Unknown,
+//! }
+//!
+//! let data = b"PK\x0a\x00\x01\x00\x03abc\x78\x56\x34\x12\x02\x01\x01\x00\x09";
+//! assert_eq!(
+//!   Entry::parse(&data[..]),
+//!   Ok::<_, Err<Error<_>>>((&b""[..], Entry {
+//!     version: 10,
+//!     flags: 1,
+//!     name_len: 3,
+//!     name: &b"abc"[..],
+//!     crc: Some(0x12345678),
+//!     extra: vec![Field::Size(256), Field::Unknown],
+//!   }))
+//! );
+//!
+//! assert_eq!(
+//!   Entry::<'_>::parse(&b"ZIP"[..]),
+//!   Err(Err::Error(Error::new(&b"ZIP"[..], ErrorKind::Tag)))
+//! );
+//! ```
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{
+  parse_macro_input, Attribute, Data, DeriveInput, Expr, Fields, GenericArgument, GenericParam,
+  Lifetime, Pat, PathArguments, Type,
+};
+
+/// Derives [nom::record::NomParse], see the [crate] documentation
+#[proc_macro_derive(NomParse, attributes(nom))]
+pub fn derive_nom_parse(input: TokenStream) -> TokenStream {
+  let input = parse_macro_input!(input as DeriveInput);
+  match expand(input) {
+    Ok(tokens) => tokens.into(),
+    Err(e) => e.to_compile_error().into(),
+  }
+}
+
+#[derive(Clone, Copy)]
+enum Endian {
+  Big,
+  Little,
+}
+
+/// Attributes of the struct or enum
+struct ContainerAttrs {
+  endian: Endian,
+  tag: Option<Expr>,
+  selector: Option<Type>,
+}
+
+/// Attributes of an enum variant
+struct VariantAttrs {
+  tag: Option<Pat>,
+  default: bool,
+}
+
+/// Attributes of a field
+#[derive(Default)]
+struct FieldAttrs {
+  endian: Option<Endian>,
+  tag: Option<Expr>,
+  len: Option<Expr>,
+  count: Option<Expr>,
+  prefix: Option<Type>,
+  cond: Option<Expr>,
+  parse: Option<Expr>,
+}
+
+fn container_attrs(attrs: &[Attribute]) -> syn::Result<ContainerAttrs> {
+  let mut res = ContainerAttrs {
+    endian: Endian::Big,
+    tag: None,
+    selector: None,
+  };
+
+  for attr in attrs.iter().filter(|attr| attr.path().is_ident("nom")) {
+    attr.parse_nested_meta(|meta| {
+      if meta.path.is_ident("big") {
+        res.endian = Endian::Big;
+      } else if meta.path.is_ident("little") {
+        res.endian = Endian::Little;
+      } else if meta.path.is_ident("tag") {
+        res.tag = Some(meta.value()?.parse()?);
+      } else if meta.path.is_ident("selector") {
+        res.selector = Some(meta.value()?.parse()?);
+      } else {
+        return Err(meta.error("unknown nom attribute"));
+      }
+      Ok(())
+    })?;
+  }
+
+  Ok(res)
+}
+
+fn variant_attrs(attrs: &[Attribute]) -> syn::Result<VariantAttrs> {
+  let mut res = VariantAttrs {
+    tag: None,
+    default: false,
+  };
+
+  for attr in attrs.iter().filter(|attr| attr.path().is_ident("nom")) {
+    attr.parse_nested_meta(|meta| {
+      if meta.path.is_ident("tag") {
+        res.tag = Some(Pat::parse_multi(meta.value()?)?);
+      } else if meta.path.is_ident("default") {
+        res.default = true;
+      } else {
+        return Err(meta.error("unknown nom attribute"));
+      }
+      Ok(())
+    })?;
+  }
+
+  Ok(res)
+}
+
+fn field_attrs(attrs: &[Attribute]) -> syn::Result<FieldAttrs> {
+  let mut res = FieldAttrs::default();
+
+  for attr in attrs.iter().filter(|attr| attr.path().is_ident("nom")) {
+    attr.parse_nested_meta(|meta| {
+      if meta.path.is_ident("big") {
+        res.endian = Some(Endian::Big);
+      } else if meta.path.is_ident("little") {
+        res.endian = Some(Endian::Little);
+      } else if meta.path.is_ident("tag") {
+        res.tag = Some(meta.value()?.parse()?);
+      } else if meta.path.is_ident("len") {
+        res.len = Some(meta.value()?.parse()?);
+      } else if meta.path.is_ident("count") {
+        res.count = Some(meta.value()?.parse()?);
+      } else if meta.path.is_ident("prefix") {
+        res.prefix = Some(meta.value()?.parse()?);
+      } else if meta.path.is_ident("cond") {
+        res.cond = Some(meta.value()?.parse()?);
+      } else if meta.path.is_ident("parse") {
+        res.parse = Some(meta.value()?.parse()?);
+      } else {
+        return Err(meta.error("unknown nom attribute"));
+      }
+      Ok(())
+    })?;
+  }
+
+  Ok(res)
+}
+
+/// Returns the type argument of `ty` if it is `wrapper<T>`
+fn wrapped<'t>(ty: &'t Type, wrapper: &str) -> Option<&'t Type> {
+  let Type::Path(path) = ty else {
+    return None;
+  };
+  let segment = path.path.segments.last()?;
+  if segment.ident != wrapper {
+    return None;
+  }
+  let PathArguments::AngleBracketed(args) = &segment.arguments else {
+    return None;
+  };
+  match args.args.first()? {
+    GenericArgument::Type(ty) => Some(ty),
+    _ => None,
+  }
+}
+
+/// Returns `true` if `ty` is `&[u8]`
+fn is_byte_slice(ty: &Type) -> bool {
+  match ty {
+    Type::Reference(reference) => match &*reference.elem {
+      Type::Slice(slice) => matches!(&*slice.elem, Type::Path(p) if p.path.is_ident("u8")),
+      _ => false,
+    },
+    _ => false,
+  }
+}
+
+/// Returns the parser of a value of type `ty`, without attributes
+fn type_parser(ty: &Type, endian: Endian) -> TokenStream2 {
+  if let Type::Path(path) = ty {
+    if let Some(ident) = path.path.get_ident() {
+      let name = ident.to_string();
+      match name.as_str() {
+        "u8" | "i8" => {
+          return quote_spanned!(ty.span()=> ::nom::number::#ident::<_, __E>());
+        }
+        "u16" | "u32" | "u64" | "u128" | "i16" | "i32" | "i64" | "i128" | "f32" | "f64" => {
+          let prefix = match endian {
+            Endian::Big => "be",
+            Endian::Little => "le",
+          };
+          let function = format_ident!("{}_{}", prefix, name);
+          return quote_spanned!(ty.span()=> ::nom::number::#function::<_, __E>());
+        }
+        _ => {}
+      }
+    }
+  }
+
+  if let Type::Array(array) = ty {
+    let element = type_parser(&array.elem, endian);
+    return quote_spanned!(ty.span()=> ::nom::multi::array(#element));
+  }
+
+  quote_spanned!(ty.span()=> ::nom::record::record::<#ty, _, __E>())
+}
+
+/// Returns the parser of a field, following its attributes
+fn field_parser(ty: &Type, attrs: &FieldAttrs, endian: Endian) -> syn::Result<TokenStream2> {
+  let endian = attrs.endian.unwrap_or(endian);
+
+  let parser = if let Some(parse) = &attrs.parse {
+    quote!(#parse)
+  } else if let Some(cond) = &attrs.cond {
+    let Some(inner) = wrapped(ty, "Option") else {
+      return Err(syn::Error::new(ty.span(), "`cond` needs an `Option` field"));
+    };
+    let inner = field_parser(
+      inner,
+      &FieldAttrs {
+        endian: Some(endian),
+        len: attrs.len.clone(),
+        count: attrs.count.clone(),
+        prefix: attrs.prefix.clone(),
+        ..FieldAttrs::default()
+      },
+      endian,
+    )?;
+    quote_spanned!(ty.span()=> ::nom::combinator::cond(#cond, #inner))
+  } else if is_byte_slice(ty) {
+    if let Some(len) = &attrs.len {
+      quote_spanned!(ty.span()=> ::nom::bytes::take::<usize, _, __E>((#len) as usize))
+    } else if let Some(prefix) = &attrs.prefix {
+      let prefix = type_parser(prefix, endian);
+      quote_spanned!(ty.span()=> ::nom::multi::length_data(#prefix))
+    } else {
+      return Err(syn::Error::new(
+        ty.span(),
+        "a `&[u8]` field needs a `len` or `prefix` attribute",
+      ));
+    }
+  } else if let Some(element) = wrapped(ty, "Vec") {
+    let element = type_parser(element, endian);
+    if let Some(count) = &attrs.count {
+      quote_spanned!(ty.span()=> ::nom::multi::count(#element, (#count) as usize))
+    } else if let Some(prefix) = &attrs.prefix {
+      let prefix = type_parser(prefix, endian);
+      quote_spanned!(ty.span()=> ::nom::multi::length_count(#prefix, #element))
+    } else {
+      return Err(syn::Error::new(
+        ty.span(),
+        "a `Vec` field needs a `count` or `prefix` attribute",
+      ));
+    }
+  } else {
+    type_parser(ty, endian)
+  };
+
+  Ok(parser)
+}
+
+/// Returns the parser of a tag
+fn tag_parser(tag: &Expr) -> TokenStream2 {
+  quote_spanned!(tag.span()=> ::nom::bytes::tag::<&'static [u8], _, __E>(&#tag[..]))
+}
+
+/// Parses the fields in sequence, then evaluates to the value built by `constructor`,
+/// setting `tags` if a field is preceded by a tag
+fn parse_fields(
+  fields: &Fields,
+  endian: Endian,
+  constructor: TokenStream2,
+  tags: &mut bool,
+) -> syn::Result<TokenStream2> {
+  let mut steps = Vec::new();
+  let mut bindings = Vec::new();
+
+  for (index, field) in fields.iter().enumerate() {
+    let attrs = field_attrs(&field.attrs)?;
+    let binding = match &field.ident {
+      Some(ident) => ident.clone(),
+      None => format_ident!("__field{}", index),
+    };
+
+    if let Some(tag) = &attrs.tag {
+      *tags = true;
+      let tag = tag_parser(tag);
+      steps.push(quote! {
+        let (__input, _) = ::nom::Parser::process::<
+          ::nom::OutputM<::nom::Check, __OM::Error, __OM::Incomplete>,
+        >(&mut #tag, __input)?;
+      });
+    }
+
+    let parser = field_parser(&field.ty, &attrs, endian)?;
+    let ty = &field.ty;
+    steps.push(quote! {
+      let (__input, #binding): (_, #ty) = ::nom::Parser::process::<
+        ::nom::OutputM<::nom::Emit, __OM::Error, __OM::Incomplete>,
+      >(&mut #parser, __input)?;
+    });
+    bindings.push(binding);
+  }
+
+  let value = match fields {
+    Fields::Named(_) => quote!(#constructor { #(#bindings),* }),
+    Fields::Unnamed(_) => quote!(#constructor ( #(#bindings),* )),
+    Fields::Unit => quote!(#constructor),
+  };
+
+  Ok(quote! {
+    #(#steps)*
+    ::core::result::Result::Ok((
+      __input,
+      <__OM::Output as ::nom::Mode>::bind(move || #value),
+    ))
+  })
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+  let name = &input.ident;
+  let attrs = container_attrs(&input.attrs)?;
+
+  let mut lifetime: Option<&Lifetime> = None;
+  for param in &input.generics.params {
+    match param {
+      GenericParam::Lifetime(def) if lifetime.is_none() => lifetime = Some(&def.lifetime),
+      _ => {
+        return Err(syn::Error::new(
+          param.span(),
+          "NomParse can only be derived for types with at most one lifetime parameter",
+        ))
+      }
+    }
+  }
+
+  let mut tags = false;
+  let mut steps = Vec::new();
+  if let Some(tag) = &attrs.tag {
+    tags = true;
+    let tag = tag_parser(tag);
+    steps.push(quote! {
+      let (__input, _) = ::nom::Parser::process::<
+        ::nom::OutputM<::nom::Check, __OM::Error, __OM::Incomplete>,
+      >(&mut #tag, __input)?;
+    });
+  }
+
+  let body = match &input.data {
+    Data::Struct(data) => {
+      if attrs.selector.is_some() {
+        return Err(syn::Error::new(
+          name.span(),
+          "the `selector` attribute only applies to enums",
+        ));
+      }
+      parse_fields(&data.fields, attrs.endian, quote!(Self), &mut tags)?
+    }
+    Data::Enum(data) => {
+      let Some(selector) = &attrs.selector else {
+        return Err(syn::Error::new(
+          name.span(),
+          "an enum needs a `selector` attribute with the type of its discriminant",
+        ));
+      };
+      let selector = type_parser(selector, attrs.endian);
+
+      let mut arms = Vec::new();
+      let mut default = None;
+      for variant in &data.variants {
+        let variant_attrs = variant_attrs(&variant.attrs)?;
+        let ident = &variant.ident;
+        let fields = parse_fields(
+          &variant.fields,
+          attrs.endian,
+          quote!(Self::#ident),
+          &mut tags,
+        )?;
+
+        if variant_attrs.default {
+          if default.is_some() {
+            return Err(syn::Error::new(
+              variant.span(),
+              "only one variant can be the default",
+            ));
+          }
+          default = Some(quote!(_ => { #fields }));
+        } else if let Some(tag) = &variant_attrs.tag {
+          arms.push(quote!(#tag => { #fields }));
+        } else {
+          return Err(syn::Error::new(
+            variant.span(),
+            "a variant needs a `tag` or `default` attribute",
+          ));
+        }
+      }
+
+      let default = default.unwrap_or_else(|| {
+        quote! {
+          _ => ::core::result::Result::Err(::nom::Err::Error(
+            <__OM::Error as ::nom::Mode>::bind(|| {
+              <__E as ::nom::error::ParseError<_>>::from_error_kind(
+                __start,
+                ::nom::error::ErrorKind::Switch,
+              )
+            }),
+          )),
+        }
+      });
+
+      quote! {
+        let __start = ::core::clone::Clone::clone(&__input);
+        let (__input, __selector) = ::nom::Parser::process::<
+          ::nom::OutputM<::nom::Emit, __OM::Error, __OM::Incomplete>,
+        >(&mut #selector, __input)?;
+        #[allow(unreachable_patterns)]
+        match __selector {
+          #(#arms)*
+          #default
+        }
+      }
+    }
+    Data::Union(_) => {
+      return Err(syn::Error::new(
+        name.span(),
+        "NomParse cannot be derived for unions",
+      ))
+    }
+  };
+
+  let (impl_generics, input_type, bounds) = match lifetime {
+    Some(lifetime) => (
+      quote!(<#lifetime, __E>),
+      quote!(&#lifetime [u8]),
+      quote!(__E: ::nom::error::ParseError<&#lifetime [u8]>),
+    ),
+    None => {
+      let compare = if tags {
+        quote!(+ ::nom::Compare<&'static [u8]>)
+      } else {
+        quote!()
+      };
+      (
+        quote!(<__I, __E>),
+        quote!(__I),
+        quote! {
+          __I: ::nom::Input<Item = u8> #compare,
+          __E: ::nom::error::ParseError<__I>,
+        },
+      )
+    }
+  };
+  let (_, type_generics, _) = input.generics.split_for_impl();
+  Ok(quote! {
+    #[automatically_derived]
+    impl #impl_generics ::nom::record::NomParse<#input_type, __E> for #name #type_generics
+    where
+      #bounds
+    {
+      #[allow(non_camel_case_types, unused_parens)]
+      fn process<__OM: ::nom::OutputMode>(
+        __input: #input_type,
+      ) -> ::nom::PResult<__OM, #input_type, Self, __E> {
+        #(#steps)*
+        #body
+      }
+    }
+  })
+}
@@ -0,0 +1,150 @@
+use nom::bytes::take;
+use nom::error::{Error, ErrorKind};
+use nom::number::be_u16;
+use nom::record::{record, NomParse};
+use nom::{Err, Needed, Parser};
+
+#[derive(Debug, PartialEq, NomParse)]
+#[nom(tag = b"\x89BIN")]
+struct Header {
+  version: u8,
+  #[nom(little)]
+  flags: u32,
+  size: i16,
+  ratio: f32,
+  checksum: [u8; 2],
+}
+
+#[derive(Debug, PartialEq, NomParse)]
+#[nom(little)]
+struct Point(i16, #[nom(big)] i16);
+
+#[derive(Debug, PartialEq, NomParse)]
+struct Shape {
+  points_len: u8,
+  #[nom(count = points_len)]
+  points: Vec<Point>,
+  #[nom(tag = b"--")]
+  corners: [Point; 2],
+}
+
+#[derive(Debug, PartialEq, NomParse)]
+struct Chunk<'a> {
+  #[nom(prefix = u16)]
+  name: &'a [u8],
+  #[nom(parse = take(2usize).map(|b: &'a [u8]| b[0] ^ b[1]))]
+  parity: u8,
+  #[nom(cond = parity != 0, prefix = u8)]
+  extra: Option<Vec<u8>>,
+}
+
+#[derive(Debug, PartialEq, NomParse)]
+#[nom(selector = u16, little)]
+enum Command {
+  #[nom(tag = 0)]
+  Stop,
+  #[nom(tag = 1..=3)]
+  Move(Point),
+  #[nom(tag = 4)]
+  Wait {
+    #[nom(parse = be_u16())]
+    millis: u16,
+  },
+}
+
+#[test]
+fn fields() {
+  let data = b"\x89BIN\x02\x01\x00\x00\x00\xff\xfe\x3f\x80\x00\x00\xab\xcd";
+  assert_eq!(
+    Header::parse(&data[..]),
+    Ok::<_, Err<Error<_>>>((
+      &b""[..],
+      Header {
+        version: 2,
+        flags: 1,
+        size: -2,
+        ratio: 1.0,
+        checksum: [0xab, 0xcd],
+      }
+    ))
+  );
+
+  assert_eq!(
+    Header::parse(&data[..6]),
+    Err::<(&[u8], Header), Err<Error<_>>>(Err::Incomplete(Needed::new(3)))
+  );
+  assert_eq!(
+    Header::parse_complete(&data[..6]),
+    Err(Err::Error(Error::new(&data[5..6], ErrorKind::Eof)))
+  );
+  assert_eq!(
+    Header::parse_complete(&b"\x89PNG\x02"[..]),
+    Err(Err::Error(Error::new(&b"\x89PNG\x02"[..], ErrorKind::Tag)))
+  );
+}
+
+#[test]
+fn nested() {
+  let data = b"\x01\x01\x00\x00\x02--\x02\x00\x00\x01\x03\x00\x00\x04";
+  assert_eq!(
+    record::<Shape, _, Error<_>>().parse(&data[..]),
+    Ok((
+      &b""[..],
+      Shape {
+        points_len: 1,
+        points: vec![Point(1, 2)],
+        corners: [Point(2, 1), Point(3, 4)],
+      }
+    ))
+  );
+}
+
+#[test]
+fn borrowed() {
+  assert_eq!(
+    Chunk::parse(&b"\x00\x03abc\x01\x03\x02\x05\x06rest"[..]),
+    Ok::<_, Err<Error<_>>>((
+      &b"rest"[..],
+      Chunk {
+        name: &b"abc"[..],
+        parity: 2,
+        extra: Some(vec![5, 6]),
+      }
+    ))
+  );
+  assert_eq!(
+    Chunk::parse(&b"\x00\x01a\x07\x07\x02\x05\x06"[..]),
+    Ok::<_, Err<Error<_>>>((
+      &b"\x02\x05\x06"[..],
+      Chunk {
+        name: &b"a"[..],
+        parity: 0,
+        extra: None,
+      }
+    ))
+  );
+}
+
+#[test]
+fn variants() {
+  let mut commands = nom::multi::many0(record::<Command, _, Error<_>>());
+  assert_eq!(
+    commands.parse_complete(&b"\x02\x00\x01\x00\x00\x02\x04\x00\x01\x00\x00\x00"[..]),
+    Ok((
+      &b""[..],
+      vec![
+        Command::Move(Point(1, 2)),
+        Command::Wait { millis: 256 },
+        Command::Stop
+      ]
+    ))
+  );
+
+  assert_eq!(
+    Command::parse(&b"\x05\x00\x00"[..]),
+    Err(Err::Error(Error::new(
+      &b"\x05\x00\x00"[..],
+      ErrorKind::Switch
+    )))
+  );
+}
@@ -0,0 +1,166 @@
+//! Reports of the memory reserved by the repetition combinators
+//!
+//! With the `accounting` feature, the combinators building a `Vec` (`many0`, `many1`,
+//! `many_till`, `many_m_n`, `count`, `length_count`, `separated_list0`...) report the
+//! bytes they reserve to a hook installed on the current thread. This measures how much
+//! memory a grammar needs for a given message, or how much an adversarial message can make
+//! it allocate, without a custom global allocator.
+//!
+//! A report covers the capacity reserved for the elements of the vector, as
+//! `capacity * size_of::<T>()`: the memory owned by the elements themselves is not
+//! included. Parsers called in check mode, like the ones discarded by `recognize`, do not
+//! build their vectors, so they report nothing.
+//!
+//! ```rust
+//! # use nom::{IResult, Parser};
+//! use nom::accounting::measure;
+//! use nom::bytes::complete::tag;
+//! use nom::multi::many0;
+//!
+//! fn parser(s: &str) -> IResult<&str, Vec<&str>> {
+//!   many0(tag("abc")).parse(s)
+//! }
+//!
+//! let (res, bytes) = measure(|| parser("abcabcabc"));
+//! assert_eq!(res, Ok(("", vec!["abc", "abc", "abc"])));
+//! // `many0` starts with room for 4 elements
+//! assert_eq!(bytes, 4 * core::mem::size_of::<&str>());
+//! ```
+
+use std::boxed::Box;
+use std::cell::RefCell;
+
+/// Memory reserved by a combinator
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Allocation {
+  /// Name of the combinator, like `"many0"`
+  pub combinator: &'static str,
+  /// Number of bytes reserved
+  pub bytes: usize,
+}
+
+/// Hook receiving the allocations of the current thread
+pub type Hook = Box<dyn FnMut(Allocation)>;
+
+std::thread_local! {
+  static HOOK: RefCell<Option<Hook>> = const { RefCell::new(None) };
+}
+
+/// Installs the hook receiving the allocations made on the current thread, returning the
+/// previous one
+///
+/// ```rust
+/// # use nom::{IResult, Parser};
+/// use nom::accounting::{set_hook, take_hook, Allocation};
+/// use nom::bytes::complete::tag;
+/// use nom::multi::count;
+/// use std::sync::mpsc::channel;
+///
+/// fn parser(s: &str) -> IResult<&str, Vec<&str>> {
+///   count(tag("ab"), 2).parse(s)
+/// }
+///
+/// let (sender, receiver) = channel();
+/// set_hook(move |allocation| sender.send(allocation).unwrap());
+/// assert_eq!(parser("abab"), Ok(("", vec!["ab", "ab"])));
+/// take_hook();
+///
+/// assert_eq!(
+///   receiver.iter().collect::<Vec<_>>(),
+///   vec![Allocation { combinator: "count", bytes: 2 * core::mem::size_of::<&str>() }]
+/// );
+/// ```
+pub fn set_hook<F>(hook: F) -> Option<Hook>
+where
+  F: FnMut(Allocation) + 'static,
+{
+  HOOK.with(|h| h.borrow_mut().replace(Box::new(hook)))
+}
+
+/// Removes the hook of the current thread, returning it
+pub fn take_hook() -> Option<Hook> {
+  HOOK.with(|h| h.borrow_mut().take())
+}
+
+/// Applies `f`, returning its result with the number of bytes reserved by the combinators
+/// it called
+///
+/// The hook of the current thread does not receive these allocations, and is restored
+/// afterwards.
+pub fn measure<F, R>(f: F) -> (R, usize)
+where
+  F: FnOnce() -> R,
+{
+  let total = std::rc::Rc::new(core::cell::Cell::new(0usize));
+  let counter = total.clone();
+  let previous = set_hook(move |allocation| counter.set(counter.get() + allocation.bytes));
+
+  let res = f();
+
+  HOOK.with(|h| *h.borrow_mut() = previous);
+  (res, total.get())
+}
+
+/// Reports `bytes` reserved by `combinator` to the hook of the current thread
+///
+/// Allocations made by the hook itself, if it calls parsers, are not reported.
+pub(crate) fn record(combinator: &'static str, bytes: usize) {
+  if bytes == 0 {
+    return;
+  }
+
+  HOOK.with(|h| {
+    if let Ok(mut hook) = h.try_borrow_mut() {
+      if let Some(hook) = hook.as_mut() {
+        hook(Allocation { combinator, bytes });
+      }
+    }
+  });
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::bytes::complete::tag;
+  use crate::character::complete::digit1;
+  use crate::combinator::recognize;
+  use crate::multi::{length_count, many0, many_till, separated_list1};
+  use crate::number::complete::u8;
+  use crate::{IResult, Parser};
+  use std::vec::Vec;
+
+  #[test]
+  fn accounting() {
+    let size = core::mem::size_of::<&str>();
+
+    fn list(i: &str) -> IResult<&str, Vec<&str>> {
+      separated_list1(tag(","), digit1).parse(i)
+    }
+    // 1, 2, 4, 8 elements
+    let (res, bytes) = measure(|| list("1,2,3,4,5"));
+    assert_eq!(res.map(|(_, v)| v.len()), Ok(5));
+    assert_eq!(bytes, 8 * size);
+
+    // nothing is built in check mode
+    let (_, bytes) =
+      measure(|| recognize(many0(tag::<_, _, crate::error::Error<_>>("a"))).parse("aaa"));
+    assert_eq!(bytes, 0);
+
+    // the announced count is reserved upfront, even if the elements are missing
+    let (res, bytes) = measure(|| {
+      length_count(u8::<_, crate::error::Error<_>>, tag(&b"a"[..])).parse(&b"\x03aa"[..])
+    });
+    assert!(res.is_err());
+    assert_eq!(bytes, 3 * core::mem::size_of::<&[u8]>());
+
+    let (sender, receiver) = std::sync::mpsc::channel();
+    set_hook(move |allocation| sender.send(allocation).unwrap());
+    let (outer, inner) =
+      measure(|| many_till(tag::<_, _, crate::error::Error<_>>("a"), tag("b")).parse("aab"));
+    assert_eq!(outer, Ok(("", (vec!["a", "a"], "b"))));
+    assert_eq!(inner, 4 * size);
+    // the previous hook was restored and did not receive the measured allocations
+    assert!(take_hook().is_some());
+    assert_eq!(receiver.try_iter().count(), 0);
+  }
+}
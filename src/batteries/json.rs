@@ -0,0 +1,530 @@
+//! JSON parser following [RFC 8259](https://www.rfc-editor.org/rfc/rfc8259)
+//!
+//! [json_value] parses a JSON value surrounded by whitespace into a [Value], and
+//! [from_str] checks that it covers the whole text. They accept exactly the grammar of
+//! the RFC: no comments, trailing commas, single quoted strings, leading zeros or
+//! special numbers like `NaN`. Numbers are converted to `f64`, and objects keep their
+//! members in the order of the text, duplicates included.
+//!
+//! The parsers work with any error type implementing [ParseError] and [ContextError].
+//! Once an array, object or string is opened, errors are failures: they are not
+//! hidden by `alt`, and they point at the invalid token instead of the start of the
+//! value. Each value adds a `"array"`, `"object"`, `"string"` or `"number"` context,
+//! and arrays and objects nested more than [MAX_DEPTH] levels deep are rejected with
+//! [ErrorKind::Depth] instead of overflowing the stack.
+//!
+//! ```rust
+//! # use nom::{Err, Finish, Parser};
+//! use nom::batteries::json::{from_str, json_value, Value};
+//! use nom::error::{Error, ErrorKind, Report, StackError, StackFrame};
+//!
+//! let value = from_str::<Error<_>>(r#"{"name": "nom", "tags": ["parser", 8]}"#).unwrap();
+//! assert_eq!(value.get("name"), Some(&Value::String("nom".into())));
+//! assert_eq!(
+//!   value.get("tags").and_then(Value::as_array),
+//!   Some(&[Value::String("parser".into()), Value::Number(8.0)][..])
+//! );
+//!
+//! // the report of an error shows its line and column, with a message for the users
+//! // describing what was expected. A missing value fails on the first digit of a number
+//! let messages = |kind, _: &&str| match kind {
+//!   ErrorKind::Satisfy => Some("expected a value"),
+//!   _ => None,
+//! };
+//! let input = "{\n  \"a\": [1, 2,]\n}";
+//! let e = from_str::<Error<_>>(input).unwrap_err();
+//! let report = Report { input, error: e.expectation(&messages) };
+//! assert_eq!(
+//!   report.to_string(),
+//!   "2:14: expected a value, found `]`\n  \"a\": [1, 2,]\n             ^"
+//! );
+//!
+//! // the contexts tell which values enclose the error
+//! let Err(Err::Failure(e)) = json_value::<StackError<_, 8>>(r#"[{"a": "\q"}]"#) else {
+//!   unreachable!()
+//! };
+//! let frames: Vec<_> = e.frames().map(|(_, frame)| *frame).collect();
+//! assert_eq!(
+//!   frames,
+//!   [
+//!     StackFrame::Kind(ErrorKind::Escaped),
+//!     StackFrame::Context("string"),
+//!     StackFrame::Context("object"),
+//!     StackFrame::Context("array"),
+//!   ]
+//! );
+//! ```
+
+use crate::branch::alt;
+use crate::bytes::complete::{tag, take_while, take_while_m_n};
+use crate::character::complete::{char, digit0, digit1, one_of, satisfy};
+use crate::combinator::{all_consuming, cut, opt, recognize, value};
+use crate::error::{context, ContextError, ErrorKind, ParseError};
+use crate::internal::{Err, IResult, Parser};
+use crate::lib::std::string::String;
+use crate::lib::std::vec::Vec;
+use crate::multi::separated_list1;
+use crate::sequence::{delimited, preceded, separated_pair, terminated};
+use crate::Finish;
+
+/// Maximum number of arrays and objects enclosing a value
+pub const MAX_DEPTH: usize = 128;
+
+/// JSON value
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+  /// `null`
+  Null,
+  /// `true` or `false`
+  Bool(bool),
+  /// number, converted to the nearest `f64`
+  Number(f64),
+  /// string, with its escape sequences replaced
+  String(String),
+  /// array
+  Array(Vec<Value>),
+  /// object, with its members in the order of the text
+  Object(Vec<(String, Value)>),
+}
+
+impl Value {
+  /// Returns the value of the last member named `key` if this is an object
+  pub fn get(&self, key: &str) -> Option<&Value> {
+    match self {
+      Value::Object(members) => members.iter().rev().find(|(k, _)| k == key).map(|(_, v)| v),
+      _ => None,
+    }
+  }
+
+  /// Returns the boolean if this is a `Bool`
+  pub fn as_bool(&self) -> Option<bool> {
+    match self {
+      Value::Bool(b) => Some(*b),
+      _ => None,
+    }
+  }
+
+  /// Returns the number if this is a `Number`
+  pub fn as_f64(&self) -> Option<f64> {
+    match self {
+      Value::Number(n) => Some(*n),
+      _ => None,
+    }
+  }
+
+  /// Returns the string if this is a `String`
+  pub fn as_str(&self) -> Option<&str> {
+    match self {
+      Value::String(s) => Some(s),
+      _ => None,
+    }
+  }
+
+  /// Returns the elements if this is an `Array`
+  pub fn as_array(&self) -> Option<&[Value]> {
+    match self {
+      Value::Array(elements) => Some(elements),
+      _ => None,
+    }
+  }
+
+  /// Returns the members if this is an `Object`
+  pub fn as_object(&self) -> Option<&[(String, Value)]> {
+    match self {
+      Value::Object(members) => Some(members),
+      _ => None,
+    }
+  }
+
+  /// Returns true if this is `Null`
+  pub fn is_null(&self) -> bool {
+    matches!(self, Value::Null)
+  }
+}
+
+/// Parses a JSON text, which must be a single value surrounded by optional whitespace
+///
+/// ```rust
+/// use nom::batteries::json::{from_str, Value};
+/// use nom::error::{Error, ErrorKind};
+///
+/// assert_eq!(from_str::<Error<_>>(" [true, null] "), Ok(Value::Array(vec![Value::Bool(true), Value::Null])));
+/// assert_eq!(from_str::<Error<_>>("1 2"), Err(Error::new("2", ErrorKind::Eof)));
+/// ```
+pub fn from_str<'a, E>(input: &'a str) -> Result<Value, E>
+where
+  E: ParseError<&'a str> + ContextError<&'a str>,
+{
+  all_consuming(json_value)
+    .parse_complete(input)
+    .finish()
+    .map(|(_, value)| value)
+}
+
+/// Parses a JSON value and the whitespace around it
+///
+/// The input is considered complete: a number at the end of the input is not
+/// incomplete.
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}};
+/// use nom::batteries::json::{json_value, Value};
+///
+/// assert_eq!(
+///   json_value::<Error<_>>(" \"caf\\u00e9\" , 1"),
+///   Ok((", 1", Value::String("café".into())))
+/// );
+/// assert_eq!(json_value::<Error<_>>("-12.5e-1"), Ok(("", Value::Number(-1.25))));
+/// assert_eq!(json_value::<Error<_>>("nul"), Err(Err::Error(Error::new("nul", ErrorKind::Tag))));
+/// // once the string is opened, errors are failures
+/// assert_eq!(
+///   json_value::<Error<_>>("\"tab\there\""),
+///   Err(Err::Failure(Error::new("\there\"", ErrorKind::NoneOf)))
+/// );
+/// ```
+pub fn json_value<'a, E>(input: &'a str) -> IResult<&'a str, Value, E>
+where
+  E: ParseError<&'a str> + ContextError<&'a str>,
+{
+  padded(input, 0)
+}
+
+/// Parses a value surrounded by whitespace, enclosed by `depth` arrays and objects
+fn padded<'a, E>(input: &'a str, depth: usize) -> IResult<&'a str, Value, E>
+where
+  E: ParseError<&'a str> + ContextError<&'a str>,
+{
+  delimited(ws, |i| element(i, depth), ws).parse_complete(input)
+}
+
+/// Value, chosen by its first character
+fn element<'a, E>(input: &'a str, depth: usize) -> IResult<&'a str, Value, E>
+where
+  E: ParseError<&'a str> + ContextError<&'a str>,
+{
+  match input.as_bytes().first() {
+    Some(b'n') => value(Value::Null, tag("null")).parse_complete(input),
+    Some(b't') => value(Value::Bool(true), tag("true")).parse_complete(input),
+    Some(b'f') => value(Value::Bool(false), tag("false")).parse_complete(input),
+    Some(b'"') => context("string", string)
+      .map(Value::String)
+      .parse_complete(input),
+    Some(b'[') => context("array", |i| array(i, depth))
+      .map(Value::Array)
+      .parse_complete(input),
+    Some(b'{') => context("object", |i| object(i, depth))
+      .map(Value::Object)
+      .parse_complete(input),
+    _ => context("number", number)
+      .map(Value::Number)
+      .parse_complete(input),
+  }
+}
+
+/// Whitespace allowed between the tokens
+fn ws<'a, E: ParseError<&'a str>>(input: &'a str) -> IResult<&'a str, &'a str, E> {
+  take_while(|c| matches!(c, ' ' | '\t' | '\n' | '\r')).parse_complete(input)
+}
+
+/// Opening delimiter of an array or object, rejecting values nested too deeply
+fn open<'a, E: ParseError<&'a str>>(
+  input: &'a str,
+  c: char,
+  depth: usize,
+) -> IResult<&'a str, char, E> {
+  let (rest, c) = char(c)(input)?;
+  if depth >= MAX_DEPTH {
+    return Err(Err::Failure(E::from_error_kind(input, ErrorKind::Depth)));
+  }
+  Ok((rest, c))
+}
+
+/// Elements of an array, after the `[`
+///
+/// An element is required after each `,`, so a trailing comma is reported where the
+/// element is missing.
+fn array<'a, E>(input: &'a str, depth: usize) -> IResult<&'a str, Vec<Value>, E>
+where
+  E: ParseError<&'a str> + ContextError<&'a str>,
+{
+  preceded(
+    |i| open(i, '[', depth),
+    cut(alt((
+      value(Vec::new(), preceded(ws, char(']'))),
+      terminated(
+        separated_list1(char(','), cut(|i| padded(i, depth + 1))),
+        char(']'),
+      ),
+    ))),
+  )
+  .parse_complete(input)
+}
+
+/// Members of an object, after the `{`
+fn object<'a, E>(input: &'a str, depth: usize) -> IResult<&'a str, Vec<(String, Value)>, E>
+where
+  E: ParseError<&'a str> + ContextError<&'a str>,
+{
+  let member = separated_pair(
+    delimited(ws, context("string", string), ws),
+    char(':'),
+    |i| padded(i, depth + 1),
+  );
+
+  preceded(
+    |i| open(i, '{', depth),
+    cut(alt((
+      value(Vec::new(), preceded(ws, char('}'))),
+      terminated(separated_list1(char(','), cut(member)), char('}')),
+    ))),
+  )
+  .parse_complete(input)
+}
+
+/// `-?(0|[1-9][0-9]*)(\.[0-9]+)?([eE][+-]?[0-9]+)?`, rejecting numbers out of the range
+/// of `f64`
+fn number<'a, E: ParseError<&'a str>>(input: &'a str) -> IResult<&'a str, f64, E> {
+  let (rest, text) = recognize((
+    opt(char('-')),
+    alt((
+      tag("0"),
+      recognize((satisfy(|c| matches!(c, '1'..='9')), digit0)),
+    )),
+    opt((char('.'), cut(digit1))),
+    opt((one_of("eE"), opt(one_of("+-")), cut(digit1))),
+  ))
+  .parse_complete(input)?;
+
+  match text.parse::<f64>() {
+    Ok(n) if n.is_finite() => Ok((rest, n)),
+    _ => Err(Err::Failure(E::from_error_kind(input, ErrorKind::TooLarge))),
+  }
+}
+
+fn string<'a, E: ParseError<&'a str>>(input: &'a str) -> IResult<&'a str, String, E> {
+  let (mut rest, _) = char('"')(input)?;
+  let mut out = String::new();
+
+  loop {
+    let end = match rest.find(|c: char| c == '"' || c == '\\' || c < '\u{20}') {
+      Some(end) => end,
+      None => return Err(Err::Failure(E::from_char(&rest[rest.len()..], '"'))),
+    };
+    out.push_str(&rest[..end]);
+    rest = &rest[end..];
+
+    match rest.as_bytes()[0] {
+      b'"' => return Ok((&rest[1..], out)),
+      b'\\' => {
+        let (i, c) = escape(rest)?;
+        out.push(c);
+        rest = i;
+      }
+      _ => return Err(Err::Failure(E::from_error_kind(rest, ErrorKind::NoneOf))),
+    }
+  }
+}
+
+/// Escape sequence starting with `\`, combining surrogate pairs
+fn escape<'a, E: ParseError<&'a str>>(input: &'a str) -> IResult<&'a str, char, E> {
+  let invalid = || Err::Failure(E::from_error_kind(input, ErrorKind::Escaped));
+
+  let c = match input[1..].chars().next() {
+    Some('"') => '"',
+    Some('\\') => '\\',
+    Some('/') => '/',
+    Some('b') => '\u{8}',
+    Some('f') => '\u{c}',
+    Some('n') => '\n',
+    Some('r') => '\r',
+    Some('t') => '\t',
+    Some('u') => {
+      let (rest, high) = unicode(input).map_err(|_| invalid())?;
+      let code = match high {
+        0xD800..=0xDBFF => match unicode(rest) {
+          Ok((rest2, low @ 0xDC00..=0xDFFF)) => {
+            let code = 0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00);
+            return char::from_u32(code).map(|c| (rest2, c)).ok_or_else(invalid);
+          }
+          _ => return Err(invalid()),
+        },
+        code => code,
+      };
+      return char::from_u32(code).map(|c| (rest, c)).ok_or_else(invalid);
+    }
+    _ => return Err(invalid()),
+  };
+  Ok((&input[2..], c))
+}
+
+/// `\uXXXX`
+fn unicode(input: &str) -> IResult<&str, u32> {
+  let (rest, digits) = preceded(
+    tag("\\u"),
+    take_while_m_n(4, 4, |c: char| c.is_ascii_hexdigit()),
+  )
+  .parse_complete(input)?;
+  Ok((rest, u32::from_str_radix(digits, 16).unwrap()))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::error::Error;
+  use crate::lib::std::string::ToString;
+
+  fn parse(input: &str) -> Result<Value, Error<&str>> {
+    from_str(input)
+  }
+
+  fn code(input: &str) -> Option<ErrorKind> {
+    parse(input).err().map(|e| e.code)
+  }
+
+  #[test]
+  fn literals() {
+    assert_eq!(parse("null"), Ok(Value::Null));
+    assert_eq!(parse(" \t\r\ntrue\n"), Ok(Value::Bool(true)));
+    assert_eq!(parse("false"), Ok(Value::Bool(false)));
+    assert!(parse("True").is_err());
+    assert!(parse("").is_err());
+    assert!(parse(" ").is_err());
+    // only the four whitespace characters of the RFC are allowed
+    assert!(parse("\u{a0}null").is_err());
+    assert!(parse("null\u{c}").is_err());
+  }
+
+  #[test]
+  fn numbers() {
+    let n = |s| parse(s).ok().and_then(|v| v.as_f64());
+    assert_eq!(n("0"), Some(0.0));
+    assert_eq!(n("-0"), Some(-0.0));
+    assert_eq!(n("123"), Some(123.0));
+    assert_eq!(n("-1.5"), Some(-1.5));
+    assert_eq!(n("1e3"), Some(1000.0));
+    assert_eq!(n("1E+3"), Some(1000.0));
+    assert_eq!(n("25e-2"), Some(0.25));
+    assert_eq!(n("0.1e1"), Some(1.0));
+    assert_eq!(n("1e-400"), Some(0.0));
+
+    for invalid in [
+      "01",
+      "-",
+      "+1",
+      ".5",
+      "1.",
+      "1.e3",
+      "1e",
+      "1e+",
+      "0x10",
+      "NaN",
+      "-Infinity",
+      "- 1",
+    ] {
+      assert!(parse(invalid).is_err(), "{}", invalid);
+    }
+    assert_eq!(code("1e400"), Some(ErrorKind::TooLarge));
+    assert_eq!(code("-1e400"), Some(ErrorKind::TooLarge));
+  }
+
+  #[test]
+  fn strings() {
+    let s = |s| parse(s).map(|v| v.as_str().map(|s| s.to_string()));
+    assert_eq!(s(r#""""#), Ok(Some("".to_string())));
+    assert_eq!(s(r#""héllo""#), Ok(Some("héllo".to_string())));
+    assert_eq!(
+      s(r#""\"\\\/\b\f\n\r\t""#),
+      Ok(Some("\"\\/\u{8}\u{c}\n\r\t".to_string()))
+    );
+    assert_eq!(s(r#""\u0000é€""#), Ok(Some("\0é€".to_string())));
+    // surrogate pair
+    assert_eq!(s(r#""😀""#), Ok(Some("😀".to_string())));
+    // DEL and other characters above 0x1F are allowed unescaped
+    assert_eq!(s("\"\u{7f}\""), Ok(Some("\u{7f}".to_string())));
+
+    // lone or reversed surrogates
+    assert_eq!(code(r#""\ud83d""#), Some(ErrorKind::Escaped));
+    assert_eq!(code(r#""\ud83dx""#), Some(ErrorKind::Escaped));
+    assert_eq!(code(r#""\ude00\ud83d""#), Some(ErrorKind::Escaped));
+    assert_eq!(code(r#""\ud83dA""#), Some(ErrorKind::Escaped));
+    // invalid escapes
+    assert_eq!(code(r#""\x41""#), Some(ErrorKind::Escaped));
+    assert_eq!(code(r#""\u12""#), Some(ErrorKind::Escaped));
+    assert_eq!(code(r#""\u12g4""#), Some(ErrorKind::Escaped));
+    assert_eq!(code(r#""\'""#), Some(ErrorKind::Escaped));
+    assert_eq!(code("\"\\"), Some(ErrorKind::Escaped));
+    // control characters and unterminated strings
+    assert_eq!(
+      parse("\"a\nb\""),
+      Err(Error::new("\nb\"", ErrorKind::NoneOf))
+    );
+    assert_eq!(parse("\"abc"), Err(Error::new("", ErrorKind::Char)));
+    assert!(parse("'abc'").is_err());
+  }
+
+  #[test]
+  fn containers() {
+    assert_eq!(parse("[]"), Ok(Value::Array(vec![])));
+    assert_eq!(parse("[ ]"), Ok(Value::Array(vec![])));
+    assert_eq!(parse("{ }"), Ok(Value::Object(vec![])));
+    assert_eq!(
+      parse(r#" { "a" : [ 1 , { "b" : null } ] , "a" : "" } "#),
+      Ok(Value::Object(vec![
+        (
+          "a".to_string(),
+          Value::Array(vec![
+            Value::Number(1.0),
+            Value::Object(vec![("b".to_string(), Value::Null)])
+          ])
+        ),
+        ("a".to_string(), Value::String("".to_string())),
+      ]))
+    );
+    // the last duplicate wins
+    assert_eq!(
+      parse(r#"{"a": 1, "a": 2}"#).unwrap().get("a"),
+      Some(&Value::Number(2.0))
+    );
+
+    let doc = r#"[1, {"a": [2]}"#;
+    assert_eq!(parse(doc), Err(Error::new("", ErrorKind::Char)));
+    for invalid in [
+      "[1,]",
+      "[,1]",
+      "[1 2]",
+      "{\"a\" 1}",
+      "{\"a\":}",
+      "{a: 1}",
+      "{\"a\": 1,}",
+      "[1]]",
+      "{\"a\": 1}}",
+    ] {
+      assert!(parse(invalid).is_err(), "{}", invalid);
+    }
+    // errors in a container are failures, at the invalid token: a value is expected after
+    // the comma
+    assert_eq!(
+      json_value::<Error<_>>("[1, 2,]"),
+      Err(Err::Failure(Error::new("]", ErrorKind::Satisfy)))
+    );
+    assert_eq!(
+      json_value::<Error<_>>(r#"{"a": 1 "b": 2}"#),
+      Err(Err::Failure(Error::new(r#""b": 2}"#, ErrorKind::Char)))
+    );
+  }
+
+  #[test]
+  fn depth() {
+    let nested = |n| "[".repeat(n) + &"]".repeat(n);
+    assert!(parse(&nested(MAX_DEPTH)).is_ok());
+    let input = nested(MAX_DEPTH + 1);
+    assert_eq!(
+      parse(&input),
+      Err(Error::new(&input[MAX_DEPTH..], ErrorKind::Depth))
+    );
+
+    // deeply nested input does not overflow the stack
+    let input = "{\"a\":".repeat(100_000);
+    assert_eq!(code(&input), Some(ErrorKind::Depth));
+  }
+}
@@ -0,0 +1,6 @@
+//! Complete parsers for common languages
+//!
+//! Those parsers are ready to use, and written with the combinators of nom as examples
+//! of a full grammar, including its error handling.
+
+pub mod json;
@@ -36,12 +36,61 @@ use crate::Input;
 /// assert_eq!(parsed.0, 0x01);
 /// assert_eq!(parsed.1, 0x23);
 /// ```
-pub fn bits<I, O, E1, E2, P>(mut parser: P) -> impl FnMut(I) -> IResult<I, O, E2>
+pub fn bits<I, O, E1, E2, P>(parser: P) -> impl FnMut(I) -> IResult<I, O, E2>
 where
   E1: ParseError<(I, usize)> + ErrorConvert<E2>,
   E2: ParseError<I>,
   I: Input,
   P: Parser<(I, usize), Output = O, Error = E1>,
+{
+  bits_with(parser, ErrorConvert::convert)
+}
+
+/// Like [bits], converting the errors of the bit level parser with a closure instead of
+/// [ErrorConvert]
+///
+/// Error types that implement [MapInput](crate::error::MapInput) can drop the bit offset with
+/// `|e| e.map_input(|(i, _)| i)`.
+///
+/// ```
+/// use nom::bits::{bits_with, complete::take};
+/// use nom::error::{Error, ErrorKind, MapInput};
+/// use nom::{Err, IResult};
+///
+/// // an error type of the application, without `ErrorConvert` implementations
+/// #[derive(Debug, PartialEq)]
+/// struct BitError {
+///   byte: usize,
+///   bit: usize,
+/// }
+///
+/// fn parse(input: &[u8]) -> IResult<&[u8], (u8, u8), Error<&[u8]>> {
+///   bits_with((take(4usize), take(8usize)), |e: Error<(&[u8], usize)>| {
+///     e.map_input(|(i, _)| i)
+///   })(input)
+/// }
+///
+/// assert_eq!(parse(&[0x12, 0x34]), Ok((&[][..], (0x01, 0x23))));
+/// assert_eq!(parse(&[0x12]), Err(Err::Error(Error::new(&[0x12][..], ErrorKind::Eof))));
+///
+/// fn located(input: &[u8]) -> IResult<&[u8], u8, BitError> {
+///   bits_with(take(12usize), |e: Error<(&[u8], usize)>| BitError {
+///     byte: input.len() - e.input.0.len(),
+///     bit: e.input.1,
+///   })(input)
+/// }
+///
+/// assert_eq!(located(&[0xff]), Err(Err::Error(BitError { byte: 0, bit: 0 })));
+/// ```
+pub fn bits_with<I, O, E1, E2, P, C>(
+  mut parser: P,
+  mut convert: C,
+) -> impl FnMut(I) -> IResult<I, O, E2>
+where
+  E1: ParseError<(I, usize)>,
+  I: Input,
+  P: Parser<(I, usize), Output = O, Error = E1>,
+  C: FnMut(E1) -> E2,
 {
   move |input: I| match parser.parse((input, 0)) {
     Ok(((rest, offset), result)) => {
@@ -52,8 +101,8 @@ where
       Ok((rest.take_from(remaining_bytes_index), result))
     }
     Err(Err::Incomplete(n)) => Err(Err::Incomplete(n.map(|u| u.get() / 8 + 1))),
-    Err(Err::Error(e)) => Err(Err::Error(e.convert())),
-    Err(Err::Failure(e)) => Err(Err::Failure(e.convert())),
+    Err(Err::Error(e)) => Err(Err::Error(convert(e))),
+    Err(Err::Failure(e)) => Err(Err::Failure(convert(e))),
   }
 }
 
@@ -81,12 +130,45 @@ where
 ///
 /// assert_eq!(parse( input ), Ok(( &[][..], (0x01, 0x23, &[0xff, 0xff][..]) )));
 /// ```
-pub fn bytes<I, O, E1, E2, P>(mut parser: P) -> impl FnMut((I, usize)) -> IResult<(I, usize), O, E2>
+pub fn bytes<I, O, E1, E2, P>(parser: P) -> impl FnMut((I, usize)) -> IResult<(I, usize), O, E2>
 where
   E1: ParseError<I> + ErrorConvert<E2>,
   E2: ParseError<(I, usize)>,
   I: Input + Clone,
   P: Parser<I, Output = O, Error = E1>,
+{
+  bytes_with(parser, ErrorConvert::convert)
+}
+
+/// Like [bytes], converting the errors of the byte level parser with a closure instead of
+/// [ErrorConvert]
+///
+/// ```
+/// use nom::bits::{bits, bytes_with, complete::take};
+/// use nom::bytes::complete::tag;
+/// use nom::error::{Error, ErrorKind, MapInput};
+/// use nom::{Err, IResult};
+///
+/// fn parse(input: &[u8]) -> IResult<&[u8], (u8, &[u8])> {
+///   bits::<_, _, Error<(&[u8], usize)>, _, _>((
+///     take(4usize),
+///     bytes_with(tag(&b"ab"[..]), |e: Error<&[u8]>| e.map_input(|i| (i, 0))),
+///   ))(input)
+/// }
+///
+/// assert_eq!(parse(b"\x10ab"), Ok((&[][..], (0x01, &b"ab"[..]))));
+/// assert_eq!(parse(b"\x10ac"), Err(Err::Error(Error::new(&b"ac"[..], ErrorKind::Tag))));
+/// ```
+pub fn bytes_with<I, O, E1, E2, P, C>(
+  mut parser: P,
+  mut convert: C,
+) -> impl FnMut((I, usize)) -> IResult<(I, usize), O, E2>
+where
+  E1: ParseError<I>,
+  E2: ParseError<(I, usize)>,
+  I: Input + Clone,
+  P: Parser<I, Output = O, Error = E1>,
+  C: FnMut(E1) -> E2,
 {
   move |(input, offset): (I, usize)| {
     let inner = if offset % 8 != 0 {
@@ -102,8 +184,8 @@ where
         Some(v) => Err::Incomplete(Needed::new(v)),
         None => Err::Failure(E2::from_error_kind(i, ErrorKind::TooLarge)),
       }),
-      Err(Err::Error(e)) => Err(Err::Error(e.convert())),
-      Err(Err::Failure(e)) => Err(Err::Failure(e.convert())),
+      Err(Err::Error(e)) => Err(Err::Error(convert(e))),
+      Err(Err::Failure(e)) => Err(Err::Failure(convert(e))),
     }
   }
 }
@@ -171,4 +253,28 @@ mod test {
     let error = result.err().unwrap();
     assert_eq!("Parsing requires 2 bytes/chars", error.to_string());
   }
+
+  #[test]
+  /// `StackError` converts between the bit and byte level inputs
+  fn test_error_types_bits() {
+    use crate::error::{StackError, StackFrame};
+
+    let input = &[0x12][..];
+
+    let result: IResult<&[u8], u8, StackError<&[u8], 2>> =
+      bits::<_, _, StackError<(&[u8], usize), 2>, _, _>(crate::bits::complete::take(12usize))(
+        input,
+      );
+    match result {
+      Err(Err::Error(e)) => {
+        let mut frames = e.frames();
+        assert_eq!(
+          frames.next(),
+          Some(&(input, StackFrame::Kind(ErrorKind::Eof)))
+        );
+        assert_eq!(frames.next(), None);
+      }
+      _ => panic!("expected an error"),
+    }
+  }
 }
@@ -6,15 +6,23 @@ mod tests;
 use core::marker::PhantomData;
 
 use crate::error::ErrorKind;
-use crate::error::ParseError;
-use crate::internal::{Err, Mode, Parser};
+use crate::error::{FromExternalError, ParseError};
+use crate::internal::{Check, Err, Mode, Needed, OutputM, Parser};
+#[cfg(feature = "alloc")]
+use crate::lib::std::vec::Vec;
+use crate::traits::Input;
 
 /// Tests a list of parsers one by one until one succeeds.
 ///
 /// It takes as argument either a tuple or an array of parsers. If using a
-/// tuple, there is a maximum of 21 parsers. If you need more, it is possible to
+/// tuple, there is a maximum of 64 parsers. If you need more, it is possible to
 /// use an array.
 ///
+/// When the list of parsers is only known at runtime, like a keyword table or a
+/// grammar assembled from configuration, `alt` also accepts a mutable slice
+/// (`&mut [P]`) or a `Vec<P>` (with the `alloc` feature). Parsers of different
+/// types can be mixed by boxing them as `Box<dyn FnMut(I) -> IResult<I, O, E>>`.
+///
 /// ```rust
 /// # use nom::error_position;
 /// # use nom::{Err,error::ErrorKind, Needed, IResult, Parser};
@@ -38,6 +46,28 @@ use crate::internal::{Err, Mode, Parser};
 ///
 /// With a custom error type, it is possible to have alt return the error of the parser
 /// that went the farthest in the input data
+///
+/// Dispatching over a list of parsers built at runtime:
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+/// use nom::branch::alt;
+/// use nom::bytes::complete::tag;
+/// use nom::character::complete::digit1;
+///
+/// let keywords = ["let", "fn", "if"];
+/// let mut parser = alt(keywords.iter().map(|k| tag(*k)).collect::<Vec<_>>());
+///
+/// assert_eq!(parser.parse("fn main"), Ok((" main", "fn")));
+/// assert_eq!(parser.parse("while"), Err(Err::Error(Error::new("while", ErrorKind::Tag))));
+///
+/// // parsers of different types can be boxed
+/// let mut parser = alt(vec![
+///   Box::new(|i| tag("let").parse(i)) as Box<dyn FnMut(&'static str) -> IResult<_, _>>,
+///   Box::new(digit1),
+/// ]);
+/// assert_eq!(parser.parse("123;"), Ok((";", "123")));
+/// ```
 pub fn alt<List>(l: List) -> Choice<List> {
   Choice { parser: l }
 }
@@ -155,7 +185,12 @@ macro_rules! alt_trait_inner(
   );
 );
 
-alt_trait!(A B C D E F G H I J K L M N O P Q R S T U);
+alt_trait!(
+  A B C D E F G H I J K L M N O P
+  Q R S T U V W X Y Z A2 B2 C2 D2 E2 F2
+  G2 H2 I2 J2 K2 L2 M2 N2 O2 P2 Q2 R2 S2 T2 U2 V2
+  W2 X2 Y2 Z2 A3 B3 C3 D3 E3 F3 G3 H3 I3 J3 K3 L3
+);
 
 // Manually implement Alt for (A,), the 1-tuple type
 impl<Input, Output, Error: ParseError<Input>, A: Parser<Input, Output = Output, Error = Error>>
@@ -173,6 +208,42 @@ impl<Input, Output, Error: ParseError<Input>, A: Parser<Input, Output = Output,
   }
 }
 
+/// Applies each parser yielded by `branches` in order, until one of them succeeds.
+///
+/// Shared by the [alt()] implementations over arrays, slices and vectors.
+fn choice_iter<'a, OM, Input, Output, Error, A, It>(
+  branches: It,
+  input: Input,
+) -> crate::PResult<OM, Input, Output, Error>
+where
+  OM: crate::OutputMode,
+  Input: Clone,
+  Error: ParseError<Input>,
+  A: Parser<Input, Output = Output, Error = Error> + 'a,
+  It: Iterator<Item = &'a mut A>,
+{
+  let mut error = None;
+
+  for branch in branches {
+    match branch.process::<OM>(input.clone()) {
+      Err(Err::Error(e)) => match error {
+        None => error = Some(e),
+        Some(err) => error = Some(OM::Error::combine(err, e, |e1, e2| e1.or(e2))),
+      },
+      res => return res,
+    }
+  }
+
+  match error {
+    Some(e) => Err(Err::Error(OM::Error::map(e, |err| {
+      Error::append(input, ErrorKind::Alt, err)
+    }))),
+    None => Err(Err::Error(OM::Error::bind(|| {
+      Error::from_error_kind(input, ErrorKind::Alt)
+    }))),
+  }
+}
+
 impl<
     const N: usize,
     Input: Clone,
@@ -189,26 +260,7 @@ impl<
     &mut self,
     input: Input,
   ) -> crate::PResult<OM, Input, Self::Output, Self::Error> {
-    let mut error = None;
-
-    for branch in &mut self.parser {
-      match branch.process::<OM>(input.clone()) {
-        Err(Err::Error(e)) => match error {
-          None => error = Some(e),
-          Some(err) => error = Some(OM::Error::combine(err, e, |e1, e2| e1.or(e2))),
-        },
-        res => return res,
-      }
-    }
-
-    match error {
-      Some(e) => Err(Err::Error(OM::Error::map(e, |err| {
-        Error::append(input, ErrorKind::Alt, err)
-      }))),
-      None => Err(Err::Error(OM::Error::bind(|| {
-        Error::from_error_kind(input, ErrorKind::Alt)
-      }))),
-    }
+    choice_iter::<OM, _, _, _, _, _>(self.parser.iter_mut(), input)
   }
 }
 
@@ -227,26 +279,28 @@ impl<
     &mut self,
     input: Input,
   ) -> crate::PResult<OM, Input, Self::Output, Self::Error> {
-    let mut error = None;
-
-    for branch in self.parser.iter_mut() {
-      match branch.process::<OM>(input.clone()) {
-        Err(Err::Error(e)) => match error {
-          None => error = Some(e),
-          Some(err) => error = Some(OM::Error::combine(err, e, |e1, e2| e1.or(e2))),
-        },
-        res => return res,
-      }
-    }
+    choice_iter::<OM, _, _, _, _, _>(self.parser.iter_mut(), input)
+  }
+}
 
-    match error {
-      Some(e) => Err(Err::Error(OM::Error::map(e, |err| {
-        Error::append(input, ErrorKind::Alt, err)
-      }))),
-      None => Err(Err::Error(OM::Error::bind(|| {
-        Error::from_error_kind(input, ErrorKind::Alt)
-      }))),
-    }
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "alloc")))]
+impl<
+    Input: Clone,
+    Output,
+    Error: ParseError<Input>,
+    A: Parser<Input, Output = Output, Error = Error>,
+  > Parser<Input> for Choice<Vec<A>>
+{
+  type Output = Output;
+  type Error = Error;
+
+  #[inline]
+  fn process<OM: crate::OutputMode>(
+    &mut self,
+    input: Input,
+  ) -> crate::PResult<OM, Input, Self::Output, Self::Error> {
+    choice_iter::<OM, _, _, _, _, _>(self.parser.iter_mut(), input)
   }
 }
 
@@ -369,4 +423,1220 @@ permutation_trait!(
   FnS S s
   FnT T t
   FnU U u
+  FnV V v
+  FnW W w
+  FnX X x
+  FnY Y y
+  FnZ Z z
+  FnA2 A2 a2
+  FnB2 B2 b2
+  FnC2 C2 c2
+  FnD2 D2 d2
+  FnE2 E2 e2
+  FnF2 F2 f2
+  FnG2 G2 g2
+  FnH2 H2 h2
+  FnI2 I2 i2
+  FnJ2 J2 j2
+  FnK2 K2 k2
+  FnL2 L2 l2
+  FnM2 M2 m2
+  FnN2 N2 n2
+  FnO2 O2 o2
+  FnP2 P2 p2
+  FnQ2 Q2 q2
+  FnR2 R2 r2
+  FnS2 S2 s2
+  FnT2 T2 t2
+  FnU2 U2 u2
+  FnV2 V2 v2
+  FnW2 W2 w2
+  FnX2 X2 x2
+  FnY2 Y2 y2
+  FnZ2 Z2 z2
+  FnA3 A3 a3
+  FnB3 B3 b3
+  FnC3 C3 c3
+  FnD3 D3 d3
+  FnE3 E3 e3
+  FnF3 F3 f3
+  FnG3 G3 g3
+  FnH3 H3 h3
+  FnI3 I3 i3
+  FnJ3 J3 j3
+  FnK3 K3 k3
+  FnL3 L3 l3
+);
+
+/// Applies a list of parsers in any order, each at most once, where members marked
+/// with [optional] may be missing and members marked with [required] may not.
+///
+/// Like [permutation], this returns a tuple of the results in the order of the list,
+/// with an `Option` for the optional members. The parsers are applied greedily until
+/// none of the remaining ones succeeds. If one of the members that were already applied
+/// succeeds at that position, this returns an error with [ErrorKind::Unique] there, as
+/// the member appears twice. If a required member is missing, this returns an error with
+/// [ErrorKind::Permutation].
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+/// use nom::branch::{optional, permutation_opt, required};
+/// use nom::bytes::complete::tag;
+///
+/// fn flags(input: &str) -> IResult<&str, (&str, Option<&str>, Option<&str>)> {
+///   permutation_opt((required(tag("-n")), optional(tag("-v")), optional(tag("-q")))).parse(input)
+/// }
+///
+/// assert_eq!(flags("-q-n"), Ok(("", ("-n", None, Some("-q")))));
+/// assert_eq!(flags("-n-v;"), Ok((";", ("-n", Some("-v"), None))));
+/// assert_eq!(flags("-v-n-v"), Err(Err::Error(Error::new("-v", ErrorKind::Unique))));
+/// assert_eq!(flags("-v"), Err(Err::Error(Error::new("", ErrorKind::Tag))));
+/// ```
+pub fn permutation_opt<I, E: ParseError<I>, List>(
+  list: List,
+) -> PermutationOpt<List, crate::combinator::Success<(), E>, E> {
+  PermutationOpt {
+    members: list,
+    sep: crate::combinator::success(()),
+    e: PhantomData,
+  }
+}
+
+/// Like [permutation_opt], with a separator between the members.
+///
+/// A separator that is not followed by one of the members is not consumed.
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+/// use nom::branch::{optional, separated_permutation_opt};
+/// use nom::bytes::complete::tag;
+/// use nom::character::complete::char;
+///
+/// fn flags(input: &str) -> IResult<&str, (Option<&str>, Option<&str>)> {
+///   separated_permutation_opt(char(','), (optional(tag("ro")), optional(tag("sync")))).parse(input)
+/// }
+///
+/// assert_eq!(flags("sync,ro"), Ok(("", (Some("ro"), Some("sync")))));
+/// assert_eq!(flags("sync,"), Ok((",", (None, Some("sync")))));
+/// assert_eq!(flags("ro,sync,ro"), Err(Err::Error(Error::new("ro", ErrorKind::Unique))));
+/// ```
+pub fn separated_permutation_opt<I, E: ParseError<I>, S, List>(
+  sep: S,
+  list: List,
+) -> PermutationOpt<List, S, E>
+where
+  S: Parser<I, Error = E>,
+{
+  PermutationOpt {
+    members: list,
+    sep,
+    e: PhantomData,
+  }
+}
+
+/// Marks a member of [permutation_opt] as required: the permutation fails if the member
+/// is missing.
+pub fn required<P>(parser: P) -> Required<P> {
+  Required { parser }
+}
+
+/// Marks a member of [permutation_opt] as optional: it returns `None` if the member
+/// is missing.
+pub fn optional<P>(parser: P) -> Optional<P> {
+  Optional { parser }
+}
+
+/// Required member of [permutation_opt], created by [required]
+pub struct Required<P> {
+  parser: P,
+}
+
+/// Optional member of [permutation_opt], created by [optional]
+pub struct Optional<P> {
+  parser: P,
+}
+
+/// Member of a [permutation_opt] list, marked with [required] or [optional]
+pub trait PermutationMember<I> {
+  /// Output of the parser
+  type Output;
+  /// Value returned for the member, from the output if it was applied
+  type Value;
+  /// Error type of the parser
+  type Error: ParseError<I>;
+  /// Indicates whether the member can be missing
+  const OPTIONAL: bool;
+
+  /// Applies the parser of the member
+  fn process<OM: crate::OutputMode>(
+    &mut self,
+    input: I,
+  ) -> crate::PResult<OM, I, Self::Output, Self::Error>;
+
+  /// Produces the value of the member. This is only called without output for
+  /// optional members
+  fn value(output: Option<Self::Output>) -> Self::Value;
+}
+
+impl<I, P: Parser<I>> PermutationMember<I> for Required<P> {
+  type Output = <P as Parser<I>>::Output;
+  type Value = <P as Parser<I>>::Output;
+  type Error = <P as Parser<I>>::Error;
+  const OPTIONAL: bool = false;
+
+  #[inline(always)]
+  fn process<OM: crate::OutputMode>(
+    &mut self,
+    input: I,
+  ) -> crate::PResult<OM, I, Self::Output, Self::Error> {
+    self.parser.process::<OM>(input)
+  }
+
+  fn value(output: Option<Self::Output>) -> Self::Value {
+    match output {
+      Some(o) => o,
+      // required members are checked before the values are produced
+      None => unreachable!(),
+    }
+  }
+}
+
+impl<I, P: Parser<I>> PermutationMember<I> for Optional<P> {
+  type Output = <P as Parser<I>>::Output;
+  type Value = Option<<P as Parser<I>>::Output>;
+  type Error = <P as Parser<I>>::Error;
+  const OPTIONAL: bool = true;
+
+  #[inline(always)]
+  fn process<OM: crate::OutputMode>(
+    &mut self,
+    input: I,
+  ) -> crate::PResult<OM, I, Self::Output, Self::Error> {
+    self.parser.process::<OM>(input)
+  }
+
+  fn value(output: Option<Self::Output>) -> Self::Value {
+    output
+  }
+}
+
+/// Wrapping structure for the [permutation_opt] and [separated_permutation_opt]
+/// combinators implementation
+pub struct PermutationOpt<T, S, Error> {
+  members: T,
+  sep: S,
+  e: PhantomData<Error>,
+}
+
+macro_rules! permutation_opt_trait(
+  ($name1:ident $item1:ident $name2:ident $item2:ident $($name3:ident $item3:ident)*) => (
+    permutation_opt_trait!(__impl $name1 $item1, $name2 $item2; $($name3 $item3)*);
+  );
+  (__impl $($name:ident $item:ident),+; $name1:ident $item1:ident $($name2:ident $item2:ident)*) => (
+    permutation_opt_trait_impl!($($name $item),+);
+    permutation_opt_trait!(__impl $($name $item),+ , $name1 $item1; $($name2 $item2)*);
+  );
+  (__impl $($name:ident $item:ident),+;) => (
+    permutation_opt_trait_impl!($($name $item),+);
+  );
 );
+
+macro_rules! permutation_opt_trait_impl(
+  ($($name:ident $item:ident),+) => (
+    impl<
+      Input, Error, Sep, $($name),+
+    > Parser<Input> for PermutationOpt<( $($name),+ ), Sep, Error>
+    where
+    Input: Clone,
+    Error: ParseError<Input>,
+    Sep: Parser<Input, Error = Error>,
+    $($name: PermutationMember<Input, Error = Error>),+
+    {
+      type Output = ( $(<$name as PermutationMember<Input>>::Value),+ );
+      type Error = Error;
+
+      fn process<OM: crate::OutputMode>(
+        &mut self,
+        mut input: Input,
+      ) -> crate::PResult<OM, Input, Self::Output, Self::Error> {
+        let mut res = OM::Output::bind(|| {
+          ($(Option::<<$name as PermutationMember<Input>>::Output>::None),+)
+        });
+        $(let mut $item = false;)+
+        let mut first = true;
+        let mut err: Option<<OM::Error as Mode>::Output<Error>>;
+
+        loop {
+          err = None;
+          let i = if first {
+            input.clone()
+          } else {
+            match self
+              .sep
+              .process::<OutputM<Check, Check, OM::Incomplete>>(input.clone())
+            {
+              Ok((i, _)) => i,
+              Err(Err::Error(_)) => break,
+              Err(Err::Failure(e)) => return Err(Err::Failure(e)),
+              Err(Err::Incomplete(n)) => return Err(Err::Incomplete(n)),
+            }
+          };
+
+          permutation_opt_trait_inner!(0, self, input, i, first, res, err, $($item)+);
+
+          // none of the remaining members succeeded: a member that was already applied
+          // and succeeds here is a duplicate
+          permutation_opt_duplicate!(0, self, i, $($item)+);
+          break;
+        }
+
+        $(
+          if !$item && !<$name as PermutationMember<Input>>::OPTIONAL {
+            return Err(Err::Error(match err {
+              Some(err) => OM::Error::map(err, |err| Error::append(input, ErrorKind::Permutation, err)),
+              None => OM::Error::bind(|| Error::from_error_kind(input, ErrorKind::Permutation)),
+            }));
+          }
+        )+
+
+        Ok((input, OM::Output::map(res, |res| {
+          let ($($item),+) = res;
+          ($(<$name as PermutationMember<Input>>::value($item)),+)
+        })))
+      }
+    }
+  );
+);
+
+macro_rules! permutation_opt_trait_inner(
+  ($it:tt, $self:expr, $input:ident, $i:ident, $first:ident, $res:expr, $err:expr, $head:ident $($item:ident)*) => (
+    if !$head {
+      match $self.members.$it.process::<OM>($i.clone()) {
+        Ok((i, o)) => {
+          $input = i;
+          $res = OM::Output::combine($res, o, |mut res, o| {res.$it = Some(o); res});
+          $head = true;
+          $first = false;
+          continue;
+        }
+        Err(Err::Error(e)) => {
+          $err = Some(match $err {
+            None => e,
+            Some(err) => OM::Error::combine(err, e, |err, e| err.or(e))
+          });
+        }
+        Err(e) => return Err(e),
+      };
+    }
+    succ!($it, permutation_opt_trait_inner!($self, $input, $i, $first, $res, $err, $($item)*));
+  );
+  ($it:tt, $self:expr, $input:ident, $i:ident, $first:ident, $res:expr, $err:expr,) => ();
+);
+
+macro_rules! permutation_opt_duplicate(
+  ($it:tt, $self:expr, $i:ident, $head:ident $($item:ident)*) => (
+    if $head
+      && $self
+        .members
+        .$it
+        .process::<OutputM<Check, Check, OM::Incomplete>>($i.clone())
+        .is_ok()
+    {
+      return Err(Err::Error(OM::Error::bind(|| {
+        Error::from_error_kind($i, ErrorKind::Unique)
+      })));
+    }
+    succ!($it, permutation_opt_duplicate!($self, $i, $($item)*));
+  );
+  ($it:tt, $self:expr, $i:ident,) => ();
+);
+
+permutation_opt_trait!(
+  FnA a
+  FnB b
+  FnC c
+  FnD d
+  FnE e
+  FnF f
+  FnG g
+  FnH h
+  FnI i
+  FnJ j
+  FnK k
+  FnL l
+  FnM m
+  FnN n
+  FnO o
+  FnP p
+  FnQ q
+  FnR r
+  FnS s
+  FnT t
+  FnU u
+  FnV v
+  FnW w
+  FnX x
+  FnY y
+  FnZ z
+  FnA2 a2
+  FnB2 b2
+  FnC2 c2
+  FnD2 d2
+  FnE2 e2
+  FnF2 f2
+  FnG2 g2
+  FnH2 h2
+  FnI2 i2
+  FnJ2 j2
+  FnK2 k2
+  FnL2 l2
+  FnM2 m2
+  FnN2 n2
+  FnO2 o2
+  FnP2 p2
+  FnQ2 q2
+  FnR2 r2
+  FnS2 s2
+  FnT2 t2
+  FnU2 u2
+  FnV2 v2
+  FnW2 w2
+  FnX2 x2
+  FnY2 y2
+  FnZ2 z2
+  FnA3 a3
+  FnB3 b3
+  FnC3 c3
+  FnD3 d3
+  FnE3 e3
+  FnF3 f3
+  FnG3 g3
+  FnH3 h3
+  FnI3 i3
+  FnJ3 j3
+  FnK3 k3
+  FnL3 l3
+);
+
+/// Reads a discriminant with a first parser, then selects the parser to apply next from its value.
+///
+/// This is a faster alternative to a long [alt()] chain when the right branch can be
+/// determined from a prefix of the input, like an opcode byte in a binary message or a
+/// keyword. The `handler` function maps the discriminant to the follow-up parser, or
+/// returns `None` if there is no parser for it, in which case `dispatch` returns an
+/// error with [ErrorKind::Switch] at the position of the discriminant.
+///
+/// Every arm must return the same parser type. To mix different parsers, see the
+/// [dispatch!](crate::dispatch) macro.
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+/// use nom::branch::dispatch;
+/// use nom::bytes::complete::take;
+/// use nom::number::complete::u8;
+///
+/// fn message(input: &[u8]) -> IResult<&[u8], &[u8]> {
+///   dispatch(u8, |opcode| match opcode {
+///     0x01 => Some(take(2u8)),
+///     0x02 => Some(take(4u8)),
+///     _ => None,
+///   })
+///   .parse(input)
+/// }
+///
+/// assert_eq!(message(&b"\x01abcd"[..]), Ok((&b"cd"[..], &b"ab"[..])));
+/// assert_eq!(message(&b"\x02abcd"[..]), Ok((&b""[..], &b"abcd"[..])));
+/// assert_eq!(message(&b"\x03abcd"[..]), Err(Err::Error(Error::new(&b"\x03abcd"[..], ErrorKind::Switch))));
+/// ```
+pub fn dispatch<I, F, G, H>(selector: F, handler: G) -> Dispatch<F, G>
+where
+  I: Clone,
+  F: Parser<I>,
+  G: FnMut(<F as Parser<I>>::Output) -> Option<H>,
+  H: Parser<I, Error = <F as Parser<I>>::Error>,
+{
+  Dispatch { selector, handler }
+}
+
+/// Parser implementation for [dispatch()]
+pub struct Dispatch<F, G> {
+  selector: F,
+  handler: G,
+}
+
+impl<I, F, G, H> Parser<I> for Dispatch<F, G>
+where
+  I: Clone,
+  F: Parser<I>,
+  G: FnMut(<F as Parser<I>>::Output) -> Option<H>,
+  H: Parser<I, Error = <F as Parser<I>>::Error>,
+{
+  type Output = <H as Parser<I>>::Output;
+  type Error = <F as Parser<I>>::Error;
+
+  fn process<OM: crate::OutputMode>(
+    &mut self,
+    input: I,
+  ) -> crate::PResult<OM, I, Self::Output, Self::Error> {
+    let (i, discriminant) = self
+      .selector
+      .process::<crate::OutputM<crate::Emit, OM::Error, OM::Incomplete>>(input.clone())?;
+
+    match (self.handler)(discriminant) {
+      Some(mut parser) => parser.process::<OM>(i),
+      None => Err(Err::Error(OM::Error::bind(|| {
+        <F as Parser<I>>::Error::from_error_kind(input, ErrorKind::Switch)
+      }))),
+    }
+  }
+}
+
+/// Reads a discriminant with a first parser, then applies the parser of the first matching arm.
+///
+/// This is the macro form of [dispatch()](crate::branch::dispatch): the arms are written
+/// like in a `match` expression, and each arm can be a parser of a different type.
+/// If no arm matches, it returns an error with [ErrorKind::Switch](crate::error::ErrorKind::Switch)
+/// at the position of the discriminant.
+///
+/// The arm parsers are created on each call, and applied with
+/// [Parser::parse](crate::Parser::parse).
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+/// use nom::dispatch;
+/// use nom::bytes::complete::{tag, take};
+/// use nom::combinator::value;
+/// use nom::character::complete::alpha1;
+///
+/// fn command(input: &str) -> IResult<&str, usize> {
+///   dispatch!(alpha1;
+///     "push" => value(1, tag(" x")),
+///     "pop" => value(2, tag("")),
+///     name if name.starts_with("dup") => take(1u8).map(|s: &str| s.len()),
+///   )
+///   .parse(input)
+/// }
+///
+/// assert_eq!(command("push x"), Ok(("", 1)));
+/// assert_eq!(command("pop"), Ok(("", 2)));
+/// assert_eq!(command("dup2"), Ok(("", 1)));
+/// assert_eq!(command("jump 3"), Err(Err::Error(Error::new("jump 3", ErrorKind::Switch))));
+/// ```
+#[macro_export]
+macro_rules! dispatch (
+  ($selector:expr; $($pat:pat $(if $guard:expr)? => $arm:expr),+ $(,)?) => ({
+    let mut selector = $selector;
+    move |input| match $crate::Parser::parse(
+      &mut selector,
+      ::core::clone::Clone::clone(&input),
+    ) {
+      $crate::lib::std::result::Result::Ok((i, discriminant)) => {
+        #[allow(unreachable_patterns)]
+        match discriminant {
+          $($pat $(if $guard)? => $crate::Parser::parse(&mut $arm, i),)+
+          _ => $crate::lib::std::result::Result::Err($crate::Err::Error($crate::error::make_error(
+            input,
+            $crate::error::ErrorKind::Switch,
+          ))),
+        }
+      }
+      $crate::lib::std::result::Result::Err(e) => $crate::lib::std::result::Result::Err(e),
+    }
+  });
+);
+
+/// Consumes one byte and applies the parser found at its index in a 256 entries table.
+///
+/// This is a lookup table version of [dispatch()] for opcode interpreters and escape
+/// sequence parsers: selecting the branch is a single indexing operation, whatever the
+/// number of entries. The parser stored at `table[byte]` is applied on the input following
+/// the byte. If there is no entry for the byte, the `default` parser is applied on the input
+/// *starting* at the byte, so that it can still read it. Use [fail()](crate::combinator::fail)
+/// as default if every accepted byte has an entry.
+///
+/// All entries must have the same type: use function pointers or boxed parsers to mix
+/// different parsers. The table is usually built with [core::array::from_fn]. The
+/// [SwitchByte::unhandled] and [SwitchByte::is_exhaustive] methods can be used to check
+/// which bytes fall back to the default parser.
+///
+/// On empty input, it returns [Err::Incomplete] in streaming mode, and an error with
+/// [ErrorKind::Eof] in complete mode.
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+/// use nom::branch::switch_byte;
+/// use nom::combinator::{map, success};
+/// use nom::number::complete::{be_u16, u8};
+///
+/// #[derive(Clone, Debug, PartialEq)]
+/// enum Op {
+///   Nop,
+///   Push(u16),
+///   Jump(u8),
+///   Unknown(u8),
+/// }
+///
+/// fn nop(input: &[u8]) -> IResult<&[u8], Op> {
+///   success(Op::Nop).parse(input)
+/// }
+///
+/// fn push(input: &[u8]) -> IResult<&[u8], Op> {
+///   map(be_u16, Op::Push).parse(input)
+/// }
+///
+/// fn jump(input: &[u8]) -> IResult<&[u8], Op> {
+///   map(u8, Op::Jump).parse(input)
+/// }
+///
+/// fn op(input: &[u8]) -> IResult<&[u8], Op> {
+///   let table: [Option<fn(&[u8]) -> IResult<&[u8], Op>>; 256] =
+///     core::array::from_fn(|b| match b {
+///       0x00 => Some(nop as _),
+///       0x01 => Some(push as _),
+///       0x02 => Some(jump as _),
+///       _ => None,
+///     });
+///   // the default parser reads the opcode again
+///   switch_byte(table, map(u8, Op::Unknown)).parse_complete(input)
+/// }
+///
+/// assert_eq!(op(&b"\x01\x00\x03"[..]), Ok((&b""[..], Op::Push(3))));
+/// assert_eq!(op(&b"\x02\x10"[..]), Ok((&b""[..], Op::Jump(0x10))));
+/// assert_eq!(op(&b"\xffa"[..]), Ok((&b"a"[..], Op::Unknown(0xff))));
+/// assert_eq!(op(&b""[..]), Err(Err::Error(Error::new(&b""[..], ErrorKind::Eof))));
+/// ```
+pub fn switch_byte<I, P, D>(table: [Option<P>; 256], default: D) -> SwitchByte<P, D>
+where
+  I: Input<Item = u8>,
+  P: Parser<I>,
+  D: Parser<I, Output = <P as Parser<I>>::Output, Error = <P as Parser<I>>::Error>,
+{
+  SwitchByte { table, default }
+}
+
+/// Parser implementation for [switch_byte()]
+pub struct SwitchByte<P, D> {
+  table: [Option<P>; 256],
+  default: D,
+}
+
+impl<P, D> SwitchByte<P, D> {
+  /// Returns the bytes without an entry in the table, handled by the default parser
+  pub fn unhandled(&self) -> impl Iterator<Item = u8> + '_ {
+    (0..=u8::MAX).filter(move |b| self.table[*b as usize].is_none())
+  }
+
+  /// Returns `true` if every byte value has an entry in the table
+  pub fn is_exhaustive(&self) -> bool {
+    self.table.iter().all(Option::is_some)
+  }
+}
+
+impl<I, P, D> Parser<I> for SwitchByte<P, D>
+where
+  I: Input<Item = u8>,
+  P: Parser<I>,
+  D: Parser<I, Output = <P as Parser<I>>::Output, Error = <P as Parser<I>>::Error>,
+{
+  type Output = <P as Parser<I>>::Output;
+  type Error = <P as Parser<I>>::Error;
+
+  #[inline]
+  fn process<OM: crate::OutputMode>(
+    &mut self,
+    input: I,
+  ) -> crate::PResult<OM, I, Self::Output, Self::Error> {
+    let byte = match input.iter_elements().next() {
+      Some(b) => b,
+      None if input.is_streaming::<OM>() => return Err(Err::Incomplete(Needed::new(1))),
+      None => {
+        return Err(Err::Error(OM::Error::bind(|| {
+          <P as Parser<I>>::Error::from_error_kind(input, ErrorKind::Eof)
+        })))
+      }
+    };
+
+    match &mut self.table[byte as usize] {
+      Some(parser) => parser.process::<OM>(input.take_from(1)),
+      None => self.default.process::<OM>(input),
+    }
+  }
+}
+
+/// Selects the parser to apply from a runtime configuration value.
+///
+/// The `selector` function is called with the current input and returns a key, like a
+/// dialect or a strictness level. `select` then applies the parser associated with
+/// that key in the `parsers` list, which can be either a tuple of `(key, parser)`
+/// pairs (up to 64 of them, the parsers can be of different types), or an array,
+/// a mutable slice or a `Vec` (with the `alloc` feature) of such pairs.
+///
+/// The selector receives the input so that a custom input type can carry the
+/// configuration along with the data, but it is usually a closure capturing the
+/// configuration. This way, one grammar can serve multiple dialects, with only the
+/// rules that differ being selected at runtime.
+///
+/// If no key matches, it returns an error with [ErrorKind::Switch].
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+/// use nom::branch::select;
+/// use nom::bytes::complete::tag;
+/// use nom::character::complete::{alpha1, alphanumeric1};
+/// use nom::sequence::preceded;
+///
+/// #[derive(Clone, Copy, PartialEq)]
+/// enum Dialect {
+///   Strict,
+///   Lenient,
+/// }
+///
+/// fn identifier(dialect: Dialect) -> impl FnMut(&str) -> IResult<&str, &str> {
+///   move |input| {
+///     select(
+///       |_: &&str| dialect,
+///       (
+///         (Dialect::Strict, alpha1),
+///         (Dialect::Lenient, alphanumeric1),
+///       ),
+///     )
+///     .parse(input)
+///   }
+/// }
+///
+/// fn declaration(dialect: Dialect) -> impl FnMut(&str) -> IResult<&str, &str> {
+///   move |input| preceded(tag("let "), identifier(dialect)).parse(input)
+/// }
+///
+/// assert_eq!(declaration(Dialect::Strict)("let a1"), Ok(("1", "a")));
+/// assert_eq!(declaration(Dialect::Lenient)("let a1"), Ok(("", "a1")));
+/// ```
+pub fn select<I, K, S, List>(selector: S, parsers: List) -> Select<S, List>
+where
+  S: FnMut(&I) -> K,
+{
+  Select { selector, parsers }
+}
+
+/// Parser implementation for [select()]
+pub struct Select<S, List> {
+  selector: S,
+  parsers: List,
+}
+
+macro_rules! select_trait(
+  ($($it:tt $id:ident)+) => (
+    select_trait!(__impl []; $($it $id)+);
+  );
+  (__impl [$($current_it:tt $current_id:ident)*]; $it:tt $id:ident $($rest_it:tt $rest_id:ident)*) => (
+    select_trait_impl!($($current_it $current_id)* $it $id);
+    select_trait!(__impl [$($current_it $current_id)* $it $id]; $($rest_it $rest_id)*);
+  );
+  (__impl [$($current_it:tt $current_id:ident)*];) => ();
+);
+
+macro_rules! select_trait_impl(
+  ($($it:tt $id:ident)+) => (
+    impl<
+      Input, Output, Error: ParseError<Input>, Key: PartialEq, Sel: FnMut(&Input) -> Key,
+      $($id: Parser<Input, Output = Output, Error = Error>),+
+    > Parser<Input> for Select<Sel, ( $((Key, $id),)+ )> {
+      type Output = Output;
+      type Error = Error;
+
+      #[inline]
+      fn process<OM: crate::OutputMode>(
+        &mut self,
+        input: Input,
+      ) -> crate::PResult<OM, Input, Self::Output, Self::Error> {
+        let key = (self.selector)(&input);
+        $(
+          if self.parsers.$it.0 == key {
+            return self.parsers.$it.1.process::<OM>(input);
+          }
+        )+
+
+        Err(Err::Error(OM::Error::bind(|| {
+          Error::from_error_kind(input, ErrorKind::Switch)
+        })))
+      }
+    }
+  );
+);
+
+select_trait!(
+  0 A 1 B 2 C 3 D 4 E 5 F 6 G 7 H 8 I 9 J 10 K 11 L
+  12 M 13 N 14 O 15 P 16 Q 17 R 18 S 19 T 20 U 21 V 22 W 23 X
+  24 Y 25 Z 26 A2 27 B2 28 C2 29 D2 30 E2 31 F2 32 G2 33 H2 34 I2 35 J2
+  36 K2 37 L2 38 M2 39 N2 40 O2 41 P2 42 Q2 43 R2 44 S2 45 T2 46 U2 47 V2
+  48 W2 49 X2 50 Y2 51 Z2 52 A3 53 B3 54 C3 55 D3 56 E3 57 F3 58 G3 59 H3
+  60 I3 61 J3 62 K3 63 L3
+);
+
+/// Applies the parser associated with `key` in the `(key, parser)` pairs yielded by `parsers`.
+///
+/// Shared by the [select()] implementations over arrays, slices and vectors.
+fn select_iter<'a, OM, Input, Output, Error, Key, A, It>(
+  key: Key,
+  parsers: It,
+  input: Input,
+) -> crate::PResult<OM, Input, Output, Error>
+where
+  OM: crate::OutputMode,
+  Error: ParseError<Input>,
+  Key: PartialEq + 'a,
+  A: Parser<Input, Output = Output, Error = Error> + 'a,
+  It: Iterator<Item = &'a mut (Key, A)>,
+{
+  for (k, parser) in parsers {
+    if *k == key {
+      return parser.process::<OM>(input);
+    }
+  }
+
+  Err(Err::Error(OM::Error::bind(|| {
+    Error::from_error_kind(input, ErrorKind::Switch)
+  })))
+}
+
+impl<
+    const N: usize,
+    Input,
+    Output,
+    Error: ParseError<Input>,
+    Key: PartialEq,
+    Sel: FnMut(&Input) -> Key,
+    A: Parser<Input, Output = Output, Error = Error>,
+  > Parser<Input> for Select<Sel, [(Key, A); N]>
+{
+  type Output = Output;
+  type Error = Error;
+
+  #[inline]
+  fn process<OM: crate::OutputMode>(
+    &mut self,
+    input: Input,
+  ) -> crate::PResult<OM, Input, Self::Output, Self::Error> {
+    let key = (self.selector)(&input);
+    select_iter::<OM, _, _, _, _, _, _>(key, self.parsers.iter_mut(), input)
+  }
+}
+
+impl<
+    Input,
+    Output,
+    Error: ParseError<Input>,
+    Key: PartialEq,
+    Sel: FnMut(&Input) -> Key,
+    A: Parser<Input, Output = Output, Error = Error>,
+  > Parser<Input> for Select<Sel, &mut [(Key, A)]>
+{
+  type Output = Output;
+  type Error = Error;
+
+  #[inline]
+  fn process<OM: crate::OutputMode>(
+    &mut self,
+    input: Input,
+  ) -> crate::PResult<OM, Input, Self::Output, Self::Error> {
+    let key = (self.selector)(&input);
+    select_iter::<OM, _, _, _, _, _, _>(key, self.parsers.iter_mut(), input)
+  }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "alloc")))]
+impl<
+    Input,
+    Output,
+    Error: ParseError<Input>,
+    Key: PartialEq,
+    Sel: FnMut(&Input) -> Key,
+    A: Parser<Input, Output = Output, Error = Error>,
+  > Parser<Input> for Select<Sel, Vec<(Key, A)>>
+{
+  type Output = Output;
+  type Error = Error;
+
+  #[inline]
+  fn process<OM: crate::OutputMode>(
+    &mut self,
+    input: Input,
+  ) -> crate::PResult<OM, Input, Self::Output, Self::Error> {
+    let key = (self.selector)(&input);
+    select_iter::<OM, _, _, _, _, _, _>(key, self.parsers.iter_mut(), input)
+  }
+}
+
+/// Number of closest candidates kept by [UnrecognizedFormat]
+const SNIFF_CANDIDATES: usize = 3;
+
+/// Information about an input that no probe of [sniff()] recognized, reported through
+/// [FromExternalError].
+///
+/// It holds the formats whose probes came closest to recognizing the input, as the
+/// number of leading elements of the prefix they accepted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UnrecognizedFormat {
+  candidates: [(&'static str, usize); SNIFF_CANDIDATES],
+  len: usize,
+}
+
+impl UnrecognizedFormat {
+  fn new() -> Self {
+    UnrecognizedFormat {
+      candidates: [("", 0); SNIFF_CANDIDATES],
+      len: 0,
+    }
+  }
+
+  /// Records a candidate, keeping the closest ones in decreasing order of closeness
+  fn push(&mut self, name: &'static str, closeness: usize) {
+    if closeness == 0 {
+      return;
+    }
+
+    let mut position = self.len;
+    while position > 0 && self.candidates[position - 1].1 < closeness {
+      position -= 1;
+    }
+    if position == SNIFF_CANDIDATES {
+      return;
+    }
+
+    let end = if self.len == SNIFF_CANDIDATES {
+      SNIFF_CANDIDATES - 1
+    } else {
+      self.len += 1;
+      self.len - 1
+    };
+    self.candidates.copy_within(position..end, position + 1);
+    self.candidates[position] = (name, closeness);
+  }
+
+  /// Returns the closest candidates with the number of elements their probe accepted,
+  /// the closest first
+  pub fn candidates(&self) -> &[(&'static str, usize)] {
+    &self.candidates[..self.len]
+  }
+}
+
+impl crate::lib::std::fmt::Display for UnrecognizedFormat {
+  fn fmt(&self, f: &mut crate::lib::std::fmt::Formatter<'_>) -> crate::lib::std::fmt::Result {
+    write!(f, "unrecognized format")?;
+    for (index, (name, closeness)) in self.candidates().iter().enumerate() {
+      let separator = if index == 0 {
+        ", closest candidates: "
+      } else {
+        ", "
+      };
+      write!(f, "{}{} ({} matching)", separator, name, closeness)?;
+    }
+    Ok(())
+  }
+}
+
+/// Tests whether a prefix of the input is recognized as a format, for [sniff()].
+///
+/// It is implemented for magic byte strings (`&[u8]`, `&[u8; N]` or `&str`) that
+/// must start the input, for [MagicAt] values built with [magic_at()], and for
+/// closures taking the prefix and returning a `bool`, to test its structure.
+pub trait Probe<I> {
+  /// Returns `Ok(())` if the format is recognized, otherwise `Err` with the number
+  /// of leading elements of the prefix that matched, used to report the closest
+  /// candidates
+  fn probe(&mut self, prefix: &I) -> Result<(), usize>;
+}
+
+/// Counts the leading elements of `prefix` equal to those of `magic`, and checks that
+/// all of `magic` matched
+fn probe_magic<I, T, M>(prefix: &I, magic: M) -> Result<(), usize>
+where
+  I: Input<Item = T>,
+  T: PartialEq,
+  M: ExactSizeIterator<Item = T>,
+{
+  let len = magic.len();
+  let matched = prefix
+    .iter_elements()
+    .zip(magic)
+    .take_while(|(a, b)| a == b)
+    .count();
+
+  if matched == len {
+    Ok(())
+  } else {
+    Err(matched)
+  }
+}
+
+impl<I: Input<Item = u8>> Probe<I> for &[u8] {
+  fn probe(&mut self, prefix: &I) -> Result<(), usize> {
+    probe_magic(prefix, self.iter().copied())
+  }
+}
+
+impl<const N: usize, I: Input<Item = u8>> Probe<I> for &[u8; N] {
+  fn probe(&mut self, prefix: &I) -> Result<(), usize> {
+    probe_magic(prefix, self.iter().copied())
+  }
+}
+
+impl<I: Input<Item = char>> Probe<I> for &str {
+  fn probe(&mut self, prefix: &I) -> Result<(), usize> {
+    let len = self.chars().count();
+    let matched = prefix
+      .iter_elements()
+      .zip(self.chars())
+      .take_while(|(a, b)| a == b)
+      .count();
+
+    if matched == len {
+      Ok(())
+    } else {
+      Err(matched)
+    }
+  }
+}
+
+impl<I, F: FnMut(&I) -> bool> Probe<I> for F {
+  fn probe(&mut self, prefix: &I) -> Result<(), usize> {
+    if self(prefix) {
+      Ok(())
+    } else {
+      Err(0)
+    }
+  }
+}
+
+/// Probe for magic bytes found at an offset in the input, built with [magic_at()]
+#[derive(Clone, Copy, Debug)]
+pub struct MagicAt<'b> {
+  offset: usize,
+  magic: &'b [u8],
+}
+
+/// Recognizes a format from the `magic` bytes found at `offset` in the input, like
+/// `ftyp` at offset 4 in MP4 files or `ustar` at offset 257 in tar archives.
+///
+/// The closeness reported for the [UnrecognizedFormat] error counts the bytes of
+/// `magic` that matched.
+pub fn magic_at(offset: usize, magic: &[u8]) -> MagicAt<'_> {
+  MagicAt { offset, magic }
+}
+
+impl<'b, I: Input<Item = u8>> Probe<I> for MagicAt<'b> {
+  fn probe(&mut self, prefix: &I) -> Result<(), usize> {
+    if prefix.input_len() < self.offset {
+      return Err(0);
+    }
+    probe_magic(&prefix.take_from(self.offset), self.magic.iter().copied())
+  }
+}
+
+/// Peeks at a bounded prefix of the input to recognize its format, then applies the
+/// parser of that format to the whole input.
+///
+/// `table` lists the candidate formats as `(name, probe, parser)` triples, either in a
+/// tuple (up to 64 of them, the probes and parsers can be of different types), an
+/// array or a mutable slice. The probes, usually magic byte strings or closures (see
+/// [Probe]), are tried in order on the first `len` elements of the input, and the parser
+/// of the first one that recognizes it is applied. Nothing is consumed by the probes.
+///
+/// If no probe recognizes the prefix, it returns an error with [ErrorKind::Switch],
+/// built with [FromExternalError] from an [UnrecognizedFormat] value listing the
+/// closest candidates. In streaming mode, if the input is shorter than `len`, it
+/// returns [Err::Incomplete] instead, as more data could be recognized.
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+/// use nom::branch::{magic_at, sniff, UnrecognizedFormat};
+/// use nom::bytes::complete::{tag, take};
+/// use nom::combinator::value;
+/// use nom::error::FromExternalError;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct FormatError(Option<UnrecognizedFormat>);
+///
+/// impl<I> nom::error::ParseError<I> for FormatError {
+///   fn from_error_kind(_: I, _: ErrorKind) -> Self { FormatError(None) }
+///   fn append(_: I, _: ErrorKind, other: Self) -> Self { other }
+/// }
+///
+/// impl<I> FromExternalError<I, UnrecognizedFormat> for FormatError {
+///   fn from_external_error(_: I, _: ErrorKind, e: UnrecognizedFormat) -> Self {
+///     FormatError(Some(e))
+///   }
+/// }
+///
+/// fn format(input: &[u8]) -> IResult<&[u8], &str, FormatError> {
+///   sniff(8, (
+///     ("png", b"\x89PNG\r\n\x1a\n", value("png", take(8u8))),
+///     ("gif", b"GIF8", value("gif", tag("GIF8"))),
+///     ("mp4", magic_at(4, b"ftyp"), value("mp4", take(8u8))),
+///   ))
+///   .parse_complete(input)
+/// }
+///
+/// assert_eq!(format(&b"GIF89a"[..]), Ok((&b"9a"[..], "gif")));
+/// assert_eq!(format(&b"\0\0\0\x18ftyp"[..]), Ok((&b""[..], "mp4")));
+///
+/// let error = match format(&b"GIF7\x89PN"[..]) {
+///   Err(Err::Error(FormatError(Some(e)))) => e,
+///   _ => unreachable!(),
+/// };
+/// assert_eq!(error.candidates(), [("gif", 3)]);
+/// assert_eq!(error.to_string(), "unrecognized format, closest candidates: gif (3 matching)");
+/// ```
+pub fn sniff<List>(len: usize, table: List) -> Sniff<List> {
+  Sniff { len, table }
+}
+
+/// Parser implementation for [sniff()]
+pub struct Sniff<List> {
+  len: usize,
+  table: List,
+}
+
+/// Returns the error of [sniff()] when no probe recognized the input.
+fn sniff_unrecognized<OM, I, O, E>(
+  len: usize,
+  unrecognized: UnrecognizedFormat,
+  input: I,
+) -> crate::PResult<OM, I, O, E>
+where
+  OM: crate::OutputMode,
+  I: Input,
+  E: ParseError<I> + FromExternalError<I, UnrecognizedFormat>,
+{
+  let available = input.input_len();
+  if available < len && input.is_streaming::<OM>() {
+    return Err(Err::Incomplete(Needed::new(len - available)));
+  }
+
+  Err(Err::Error(OM::Error::bind(|| {
+    E::from_external_error(input, ErrorKind::Switch, unrecognized)
+  })))
+}
+
+macro_rules! sniff_trait(
+  ($($it:tt $id:ident $probe:ident)+) => (
+    sniff_trait!(__impl []; $($it $id $probe)+);
+  );
+  (__impl [$($current_it:tt $current_id:ident $current_probe:ident)*]; $it:tt $id:ident $probe:ident $($rest_it:tt $rest_id:ident $rest_probe:ident)*) => (
+    sniff_trait_impl!($($current_it $current_id $current_probe)* $it $id $probe);
+    sniff_trait!(__impl [$($current_it $current_id $current_probe)* $it $id $probe]; $($rest_it $rest_id $rest_probe)*);
+  );
+  (__impl [$($current_it:tt $current_id:ident $current_probe:ident)*];) => ();
+);
+
+macro_rules! sniff_trait_impl(
+  ($($it:tt $id:ident $probe:ident)+) => (
+    impl<
+      Input: crate::traits::Input, Output,
+      Error: ParseError<Input> + FromExternalError<Input, UnrecognizedFormat>,
+      $($probe: Probe<Input>, $id: Parser<Input, Output = Output, Error = Error>),+
+    > Parser<Input> for Sniff<( $((&'static str, $probe, $id),)+ )> {
+      type Output = Output;
+      type Error = Error;
+
+      #[inline]
+      fn process<OM: crate::OutputMode>(
+        &mut self,
+        input: Input,
+      ) -> crate::PResult<OM, Input, Self::Output, Self::Error> {
+        let prefix = input.take(self.len.min(input.input_len()));
+        let mut unrecognized = UnrecognizedFormat::new();
+        $(
+          match self.table.$it.1.probe(&prefix) {
+            Ok(()) => return self.table.$it.2.process::<OM>(input),
+            Err(closeness) => unrecognized.push(self.table.$it.0, closeness),
+          }
+        )+
+
+        sniff_unrecognized::<OM, _, _, _>(self.len, unrecognized, input)
+      }
+    }
+  );
+);
+
+sniff_trait!(
+  0 A PA 1 B PB 2 C PC 3 D PD 4 E PE 5 F PF 6 G PG 7 H PH
+  8 I PI 9 J PJ 10 K PK 11 L PL 12 M PM 13 N PN 14 O PO 15 P PP
+  16 Q PQ 17 R PR 18 S PS 19 T PT 20 U PU 21 V PV 22 W PW 23 X PX
+  24 Y PY 25 Z PZ 26 A2 PA2 27 B2 PB2 28 C2 PC2 29 D2 PD2 30 E2 PE2 31 F2 PF2
+  32 G2 PG2 33 H2 PH2 34 I2 PI2 35 J2 PJ2 36 K2 PK2 37 L2 PL2 38 M2 PM2 39 N2 PN2
+  40 O2 PO2 41 P2 PP2 42 Q2 PQ2 43 R2 PR2 44 S2 PS2 45 T2 PT2 46 U2 PU2 47 V2 PV2
+  48 W2 PW2 49 X2 PX2 50 Y2 PY2 51 Z2 PZ2 52 A3 PA3 53 B3 PB3 54 C3 PC3 55 D3 PD3
+  56 E3 PE3 57 F3 PF3 58 G3 PG3 59 H3 PH3 60 I3 PI3 61 J3 PJ3 62 K3 PK3 63 L3 PL3
+);
+
+/// Applies the parser of the first entry yielded by `table` whose probe recognizes the
+/// prefix of the input.
+///
+/// Shared by the [sniff()] implementations over arrays and slices.
+fn sniff_iter<'a, OM, I, O, E, P, A, It>(
+  len: usize,
+  table: It,
+  input: I,
+) -> crate::PResult<OM, I, O, E>
+where
+  OM: crate::OutputMode,
+  I: Input,
+  E: ParseError<I> + FromExternalError<I, UnrecognizedFormat>,
+  P: Probe<I> + 'a,
+  A: Parser<I, Output = O, Error = E> + 'a,
+  It: Iterator<Item = &'a mut (&'static str, P, A)>,
+{
+  let prefix = input.take(len.min(input.input_len()));
+  let mut unrecognized = UnrecognizedFormat::new();
+  for (name, probe, parser) in table {
+    match probe.probe(&prefix) {
+      Ok(()) => return parser.process::<OM>(input),
+      Err(closeness) => unrecognized.push(name, closeness),
+    }
+  }
+
+  sniff_unrecognized::<OM, _, _, _>(len, unrecognized, input)
+}
+
+impl<
+    const N: usize,
+    Input: crate::traits::Input,
+    Output,
+    Error: ParseError<Input> + FromExternalError<Input, UnrecognizedFormat>,
+    P: Probe<Input>,
+    A: Parser<Input, Output = Output, Error = Error>,
+  > Parser<Input> for Sniff<[(&'static str, P, A); N]>
+{
+  type Output = Output;
+  type Error = Error;
+
+  #[inline]
+  fn process<OM: crate::OutputMode>(
+    &mut self,
+    input: Input,
+  ) -> crate::PResult<OM, Input, Self::Output, Self::Error> {
+    sniff_iter::<OM, _, _, _, _, _, _>(self.len, self.table.iter_mut(), input)
+  }
+}
+
+impl<
+    Input: crate::traits::Input,
+    Output,
+    Error: ParseError<Input> + FromExternalError<Input, UnrecognizedFormat>,
+    P: Probe<Input>,
+    A: Parser<Input, Output = Output, Error = Error>,
+  > Parser<Input> for Sniff<&mut [(&'static str, P, A)]>
+{
+  type Output = Output;
+  type Error = Error;
+
+  #[inline]
+  fn process<OM: crate::OutputMode>(
+    &mut self,
+    input: Input,
+  ) -> crate::PResult<OM, Input, Self::Output, Self::Error> {
+    sniff_iter::<OM, _, _, _, _, _, _>(self.len, self.table.iter_mut(), input)
+  }
+}
@@ -1,4 +1,4 @@
-use crate::branch::{alt, permutation};
+use crate::branch::{alt, dispatch, permutation, select, switch_byte};
 use crate::bytes::streaming::tag;
 use crate::error::ErrorKind;
 use crate::internal::{Err, IResult, Needed};
@@ -147,6 +147,227 @@ fn alt_dynamic_array() {
   assert_eq!(alt1(defg), Ok((&b"g"[..], (&b"def"[..]))));
 }
 
+#[cfg(feature = "alloc")]
+#[test]
+fn alt_vec() {
+  use crate::lib::std::boxed::Box;
+  use crate::lib::std::vec::Vec;
+
+  let mut keywords = alt(
+    ["a", "bc", "def"]
+      .iter()
+      .map(|k| tag::<_, _, crate::error::Error<_>>(*k))
+      .collect::<Vec<_>>(),
+  );
+  assert_eq!(keywords.parse(&b"bcd"[..]), Ok((&b"d"[..], &b"bc"[..])));
+  assert_eq!(
+    keywords.parse(&b"de"[..]),
+    Err(Err::Incomplete(Needed::new(1)))
+  );
+  assert_eq!(
+    keywords.parse(&b"xyz"[..]),
+    Err(Err::Error(error_position!(&b"xyz"[..], ErrorKind::Tag)))
+  );
+
+  type BoxedParser<'a> = Box<dyn FnMut(&'a [u8]) -> IResult<&'a [u8], &'a [u8], ErrorStr>>;
+  fn work(input: &[u8]) -> IResult<&[u8], &[u8], ErrorStr> {
+    Ok((&b""[..], input))
+  }
+  fn dont_work(_input: &[u8]) -> IResult<&[u8], &[u8], ErrorStr> {
+    Err(Err::Error(ErrorStr("abcd".to_string())))
+  }
+
+  let mut boxed: Vec<BoxedParser<'_>> = vec![Box::new(dont_work), Box::new(dont_work)];
+  assert_eq!(
+    alt(&mut boxed[..]).parse(&b"abcd"[..]),
+    Err(Err::Error(ErrorStr(format!(
+      "custom error message: ({:?}, {:?}) - {:?}",
+      &b"abcd"[..],
+      ErrorKind::Alt,
+      ErrorStr("abcd".to_string())
+    ))))
+  );
+
+  boxed.push(Box::new(work));
+  assert_eq!(alt(boxed).parse(&b"abcd"[..]), Ok((&b""[..], &b"abcd"[..])));
+
+  let mut empty: Vec<BoxedParser<'_>> = Vec::new();
+  assert_eq!(
+    alt(&mut empty[..]).parse(&b"abcd"[..]),
+    Err(Err::Error(ErrorStr(format!(
+      "custom error message: ({:?}, {:?})",
+      &b"abcd"[..],
+      ErrorKind::Alt
+    ))))
+  );
+}
+
+#[test]
+fn dispatch_test() {
+  use crate::bytes::streaming::take;
+  use crate::number::streaming::be_u8;
+
+  fn message(i: &[u8]) -> IResult<&[u8], &[u8]> {
+    dispatch(be_u8, |opcode| match opcode {
+      1 => Some(take(2u8)),
+      2 => Some(take(3u8)),
+      _ => None,
+    })
+    .parse(i)
+  }
+
+  assert_eq!(message(&b"\x01abc"[..]), Ok((&b"c"[..], &b"ab"[..])));
+  assert_eq!(message(&b"\x02abc"[..]), Ok((&b""[..], &b"abc"[..])));
+  assert_eq!(message(&b"\x02a"[..]), Err(Err::Incomplete(Needed::new(2))));
+  assert_eq!(message(&b""[..]), Err(Err::Incomplete(Needed::new(1))));
+  assert_eq!(
+    message(&b"\x03abc"[..]),
+    Err(Err::Error(error_position!(
+      &b"\x03abc"[..],
+      ErrorKind::Switch
+    )))
+  );
+  assert_eq!(
+    crate::combinator::opt(message).parse(&b"\x03abc"[..]),
+    Ok((&b"\x03abc"[..], None))
+  );
+}
+
+#[test]
+fn dispatch_macro_test() {
+  use crate::bytes::streaming::take;
+  use crate::combinator::value;
+  use crate::number::streaming::be_u8;
+
+  fn message(i: &[u8]) -> IResult<&[u8], u32> {
+    crate::dispatch!(be_u8;
+      1 => value(1, tag("abc")),
+      2 | 3 => take(2u8).map(|s: &[u8]| s.len() as u32),
+      n if n >= 0x80 => value(u32::from(n), take(1u8)),
+    )
+    .parse(i)
+  }
+
+  assert_eq!(message(&b"\x01abcd"[..]), Ok((&b"d"[..], 1)));
+  assert_eq!(message(&b"\x03abcd"[..]), Ok((&b"cd"[..], 2)));
+  assert_eq!(message(&b"\x81abcd"[..]), Ok((&b"bcd"[..], 0x81)));
+  assert_eq!(
+    message(&b"\x01ab"[..]),
+    Err(Err::Incomplete(Needed::new(1)))
+  );
+  assert_eq!(
+    message(&b"\x01abd"[..]),
+    Err(Err::Error(error_position!(&b"abd"[..], ErrorKind::Tag)))
+  );
+  assert_eq!(
+    message(&b"\x04abcd"[..]),
+    Err(Err::Error(error_position!(
+      &b"\x04abcd"[..],
+      ErrorKind::Switch
+    )))
+  );
+}
+
+#[test]
+fn switch_byte_test() {
+  use crate::bytes::streaming::take;
+  use crate::combinator::fail;
+
+  let table: [Option<_>; 256] = core::array::from_fn(|b| match b {
+    0x01 => Some(take(1u8)),
+    0x02 => Some(take(2u8)),
+    _ => None,
+  });
+  let mut parser = switch_byte(table, fail::<_, &[u8], crate::error::Error<_>>());
+
+  assert_eq!(parser.unhandled().count(), 254);
+  assert!(!parser.is_exhaustive());
+  assert_eq!(parser.unhandled().nth(1), Some(3));
+
+  assert_eq!(parser.parse(&b"\x01abc"[..]), Ok((&b"bc"[..], &b"a"[..])));
+  assert_eq!(parser.parse(&b"\x02abc"[..]), Ok((&b"c"[..], &b"ab"[..])));
+  assert_eq!(
+    parser.parse(&b"\x02a"[..]),
+    Err(Err::Incomplete(Needed::new(1)))
+  );
+  assert_eq!(parser.parse(&b""[..]), Err(Err::Incomplete(Needed::new(1))));
+  assert_eq!(
+    parser.parse(&b"\x03abc"[..]),
+    Err(Err::Error(error_position!(
+      &b"\x03abc"[..],
+      ErrorKind::Fail
+    )))
+  );
+  assert_eq!(
+    parser.parse_complete(&b""[..]),
+    Err(Err::Error(error_position!(&b""[..], ErrorKind::Eof)))
+  );
+
+  let table: [Option<_>; 256] = core::array::from_fn(|_| Some(take(1u8)));
+  let parser = switch_byte(table, fail::<_, &[u8], crate::error::Error<_>>());
+  assert!(parser.is_exhaustive());
+  assert_eq!(parser.unhandled().next(), None);
+}
+
+#[test]
+fn select_test() {
+  use crate::bytes::streaming::take;
+  use crate::combinator::value;
+
+  #[derive(Clone, Copy, Debug, PartialEq)]
+  enum Mode {
+    Short,
+    Long,
+    Tagged,
+  }
+
+  fn field(mode: Mode, i: &[u8]) -> IResult<&[u8], &[u8]> {
+    select(
+      |_: &&[u8]| mode,
+      (
+        (Mode::Short, take(1u8)),
+        (Mode::Long, take(3u8)),
+        (Mode::Tagged, value(&b"t"[..], tag("t:"))),
+      ),
+    )
+    .parse(i)
+  }
+
+  assert_eq!(
+    field(Mode::Short, &b"abcd"[..]),
+    Ok((&b"bcd"[..], &b"a"[..]))
+  );
+  assert_eq!(
+    field(Mode::Long, &b"abcd"[..]),
+    Ok((&b"d"[..], &b"abc"[..]))
+  );
+  assert_eq!(
+    field(Mode::Long, &b"ab"[..]),
+    Err(Err::Incomplete(Needed::new(1)))
+  );
+  assert_eq!(field(Mode::Tagged, &b"t:a"[..]), Ok((&b"a"[..], &b"t"[..])));
+
+  fn sized(i: &[u8]) -> IResult<&[u8], &[u8]> {
+    // the selector can inspect the input
+    select(
+      |i: &&[u8]| i.first().copied(),
+      [(Some(b'a'), take(2u8)), (Some(b'b'), take(3u8))],
+    )
+    .parse(i)
+  }
+
+  assert_eq!(sized(&b"abcd"[..]), Ok((&b"cd"[..], &b"ab"[..])));
+  assert_eq!(sized(&b"bcde"[..]), Ok((&b"e"[..], &b"bcd"[..])));
+  assert_eq!(
+    sized(&b"cdef"[..]),
+    Err(Err::Error(error_position!(&b"cdef"[..], ErrorKind::Switch)))
+  );
+  assert_eq!(
+    sized(&b""[..]),
+    Err(Err::Error(error_position!(&b""[..], ErrorKind::Switch)))
+  );
+}
+
 #[test]
 fn permutation_test() {
   #[allow(clippy::type_complexity)]
@@ -176,3 +397,193 @@ fn permutation_test() {
   let e = &b"efgabc"[..];
   assert_eq!(perm(e), Err(Err::Incomplete(Needed::new(1))));
 }
+
+#[test]
+#[cfg(feature = "alloc")]
+fn high_arity_tuples() {
+  use crate::character::complete::char;
+
+  macro_rules! chars (
+    ($($c:literal)*) => (($(char::<_, crate::error::Error<&str>>($c),)*));
+  );
+
+  let mut choice = alt(chars!(
+    '0' '1' '2' '3' '4' '5' '6' '7' '8' '9' 'a' 'b' 'c' 'd' 'e' 'f' 'g' 'h' 'i' 'j' 'k'
+    'l' 'm' 'n' 'o' 'p' 'q' 'r' 's' 't' 'u' 'v' 'w' 'x' 'y' 'z' 'A' 'B' 'C' 'D' 'E' 'F'
+    'G' 'H' 'I' 'J' 'K' 'L' 'M' 'N' 'O' 'P' 'Q' 'R' 'S' 'T' 'U' 'V' 'W' 'X' 'Y' 'Z' '-'
+    '_'
+  ));
+  assert_eq!(choice.parse("0abc"), Ok(("abc", '0')));
+  assert_eq!(choice.parse("_abc"), Ok(("abc", '_')));
+  assert_eq!(
+    choice.parse("!abc"),
+    Err(Err::Error(error_position!("!abc", ErrorKind::Char)))
+  );
+
+  let mut reversed: String = "_-".into();
+  reversed.extend(('A'..='Z').rev());
+  reversed.extend(('a'..='z').rev());
+  reversed.extend(('0'..='9').rev());
+  let mut perm = permutation(chars!(
+    '0' '1' '2' '3' '4' '5' '6' '7' '8' '9' 'a' 'b' 'c' 'd' 'e' 'f' 'g' 'h' 'i' 'j' 'k'
+    'l' 'm' 'n' 'o' 'p' 'q' 'r' 's' 't' 'u' 'v' 'w' 'x' 'y' 'z' 'A' 'B' 'C' 'D' 'E' 'F'
+    'G' 'H' 'I' 'J' 'K' 'L' 'M' 'N' 'O' 'P' 'Q' 'R' 'S' 'T' 'U' 'V' 'W' 'X' 'Y' 'Z' '-'
+    '_'
+  ));
+  let (rest, res) = perm.parse(&reversed).unwrap();
+  assert_eq!((rest, res.0, res.36, res.63), ("", '0', 'A', '_'));
+}
+
+#[test]
+fn permutation_opt_test() {
+  use crate::branch::{optional, permutation_opt, required, separated_permutation_opt};
+  use crate::combinator::recognize;
+
+  #[allow(clippy::type_complexity)]
+  fn perm(i: &[u8]) -> IResult<&[u8], (&[u8], Option<&[u8]>, Option<&[u8]>)> {
+    permutation_opt((
+      required(tag("abcd")),
+      optional(tag("efg")),
+      optional(tag("hi")),
+    ))
+    .parse(i)
+  }
+
+  let a = &b"hiabcd;"[..];
+  assert_eq!(
+    perm(a),
+    Ok((&b";"[..], (&b"abcd"[..], None, Some(&b"hi"[..]))))
+  );
+  let b = &b"efghiabcd;"[..];
+  assert_eq!(
+    perm(b),
+    Ok((
+      &b";"[..],
+      (&b"abcd"[..], Some(&b"efg"[..]), Some(&b"hi"[..]))
+    ))
+  );
+  // the required member is missing
+  assert_eq!(
+    perm(&b"hi;"[..]),
+    Err(Err::Error(error_node_position!(
+      &b";"[..],
+      ErrorKind::Permutation,
+      error_position!(&b";"[..], ErrorKind::Tag)
+    )))
+  );
+  assert_eq!(
+    perm(&b"hiabcdhi;"[..]),
+    Err(Err::Error(error_position!(&b"hi;"[..], ErrorKind::Unique)))
+  );
+  assert_eq!(perm(&b"hiab"[..]), Err(Err::Incomplete(Needed::new(2))));
+
+  // without output
+  let res: IResult<&[u8], &[u8]> = recognize(perm).parse(&b"abcdhi;"[..]);
+  assert_eq!(res, Ok((&b";"[..], &b"abcdhi"[..])));
+
+  #[allow(clippy::type_complexity)]
+  fn sep(i: &[u8]) -> IResult<&[u8], (&[u8], Option<&[u8]>)> {
+    separated_permutation_opt(tag(","), (required(tag("abcd")), optional(tag("efg")))).parse(i)
+  }
+
+  assert_eq!(
+    sep(&b"efg,abcd;"[..]),
+    Ok((&b";"[..], (&b"abcd"[..], Some(&b"efg"[..]))))
+  );
+  assert_eq!(sep(&b"abcd,;"[..]), Ok((&b",;"[..], (&b"abcd"[..], None))));
+  assert_eq!(
+    sep(&b"abcd,abcd"[..]),
+    Err(Err::Error(error_position!(&b"abcd"[..], ErrorKind::Unique)))
+  );
+  // the separator does not precede the first member
+  assert_eq!(
+    sep(&b",abcd"[..]),
+    Err(Err::Error(error_node_position!(
+      &b",abcd"[..],
+      ErrorKind::Permutation,
+      error_position!(&b",abcd"[..], ErrorKind::Tag)
+    )))
+  );
+  assert_eq!(
+    sep(&b"efg;abcd"[..]),
+    Err(Err::Error(error_position!(
+      &b";abcd"[..],
+      ErrorKind::Permutation
+    )))
+  );
+}
+
+#[test]
+fn sniff_test() {
+  use crate::branch::{sniff, Probe, UnrecognizedFormat};
+  use crate::combinator::value;
+  use crate::error::{Error, FromExternalError, ParseError};
+
+  #[derive(Debug, PartialEq)]
+  struct SniffError(Option<UnrecognizedFormat>);
+
+  impl<I> ParseError<I> for SniffError {
+    fn from_error_kind(_: I, _: ErrorKind) -> Self {
+      SniffError(None)
+    }
+
+    fn append(_: I, _: ErrorKind, other: Self) -> Self {
+      other
+    }
+  }
+
+  impl<I> FromExternalError<I, UnrecognizedFormat> for SniffError {
+    fn from_external_error(_: I, _: ErrorKind, e: UnrecognizedFormat) -> Self {
+      SniffError(Some(e))
+    }
+  }
+
+  fn format(i: &[u8]) -> IResult<&[u8], u8, SniffError> {
+    sniff(
+      4,
+      (
+        ("abcd", b"abcd", value(1, tag("ab"))),
+        ("abce", b"abce", value(2, tag("ab"))),
+        ("abxx", b"abxx", value(3, tag("ab"))),
+        ("axxx", b"axxx", value(4, tag("ab"))),
+        (
+          "even",
+          |p: &&[u8]| p.len() == 4 && p[0] % 2 == 0,
+          value(5, tag("b")),
+        ),
+      ),
+    )
+    .parse(i)
+  }
+
+  assert_eq!(format(&b"abcd;"[..]), Ok((&b"cd;"[..], 1)));
+  assert_eq!(format(&b"abxxab"[..]), Ok((&b"xxab"[..], 3)));
+  assert_eq!(format(&b"bbbb"[..]), Ok((&b"bbb"[..], 5)));
+  assert_eq!(format(&b"abc"[..]), Err(Err::Incomplete(Needed::new(1))));
+
+  let error = match format(&b"abcfgh"[..]) {
+    Err(Err::Error(SniffError(Some(e)))) => e,
+    res => panic!("unexpected result: {:?}", res),
+  };
+  assert_eq!(error.candidates(), [("abcd", 3), ("abce", 3), ("abxx", 2)]);
+  #[cfg(feature = "alloc")]
+  assert_eq!(
+    error.to_string(),
+    "unrecognized format, closest candidates: abcd (3 matching), abce (3 matching), abxx (2 matching)"
+  );
+
+  let mut table = [
+    ("crlf", &b"\r\n"[..], tag::<_, _, Error<_>>("\r")),
+    ("lf", &b"\n"[..], tag("\n")),
+  ];
+  let mut newline = sniff(2, &mut table[..]);
+  assert_eq!(newline.parse(&b"\nx"[..]), Ok((&b"x"[..], &b"\n"[..])));
+  assert_eq!(
+    newline.parse_complete(&b"x"[..]),
+    Err(Err::Error(error_position!(&b"x"[..], ErrorKind::Switch)))
+  );
+  assert_eq!(
+    Probe::<&[u8]>::probe(&mut &b"\r\n"[..], &&b"\rx"[..]),
+    Err(1)
+  );
+}
@@ -11,17 +11,21 @@ use crate::error::ErrorKind;
 use crate::error::ParseError;
 use crate::internal::{Err, Needed, Parser};
 use crate::lib::std::result::Result::*;
-use crate::traits::{Compare, CompareResult};
+use crate::traits::{Comparator, Compare, CompareResult};
+use crate::AsBytes;
 use crate::AsChar;
 use crate::Check;
+use crate::Emit;
 use crate::ExtendInto;
 use crate::FindSubstring;
+use crate::FindSubstringSet;
 use crate::FindToken;
 use crate::Input;
-use crate::IsStreaming;
 use crate::Mode;
 use crate::OutputM;
 use crate::OutputMode;
+use crate::TagLength;
+use crate::ToArray;
 use crate::ToUsize;
 
 /// Recognizes a pattern.
@@ -42,10 +46,13 @@ use crate::ToUsize;
 /// assert_eq!(parser("S"), Err(Err::Error(Error::new("S", ErrorKind::Tag))));
 /// assert_eq!(parser("H"), Err(Err::Incomplete(Needed::new(4))));
 /// ```
+///
+/// On `&[u8]` inputs, the tag can be a byte array: its bytes are then compared in a
+/// single comparison of `N` bytes.
 pub fn tag<T, I, Error: ParseError<I>>(tag: T) -> impl Parser<I, Output = I, Error = Error>
 where
   I: Input + Compare<T>,
-  T: Input + Clone,
+  T: TagLength + Clone,
 {
   Tag {
     tag,
@@ -54,28 +61,65 @@ where
 }
 
 /// Tag implementation
+///
+/// It can be built in `const` contexts with [Tag::new], so tables of tags can be
+/// constants or statics, and copied to be applied.
+///
+/// ```rust
+/// # use nom::{Err, Parser};
+/// use nom::bytes::Tag;
+///
+/// // the `()` error type does not borrow the input, so the table can be static
+/// static MAGICS: [Tag<&[u8; 4], ()>; 2] = [Tag::new(b"\x89PNG"), Tag::new(b"GIF8")];
+///
+/// fn format(i: &[u8]) -> Option<usize> {
+///   MAGICS.iter().position(|magic| { let mut magic = *magic; magic.parse(i).is_ok() })
+/// }
+///
+/// assert_eq!(format(b"GIF89a"), Some(1));
+/// assert_eq!(format(b"\xff\xd8\xff"), None);
+/// ```
 pub struct Tag<T, E> {
   tag: T,
   e: PhantomData<E>,
 }
 
+impl<T, E> Tag<T, E> {
+  /// Creates a parser recognizing `tag`, like [tag]
+  pub const fn new(tag: T) -> Self {
+    Tag {
+      tag,
+      e: PhantomData,
+    }
+  }
+}
+
+impl<T: Clone, E> Clone for Tag<T, E> {
+  fn clone(&self) -> Self {
+    Tag::new(self.tag.clone())
+  }
+}
+
+impl<T: Copy, E> Copy for Tag<T, E> {}
+
 impl<I, Error: ParseError<I>, T> Parser<I> for Tag<T, Error>
 where
   I: Input + Compare<T>,
-  T: Input + Clone,
+  T: TagLength + Clone,
 {
   type Output = I;
 
   type Error = Error;
 
   fn process<OM: OutputMode>(&mut self, i: I) -> crate::PResult<OM, I, Self::Output, Self::Error> {
-    let tag_len = self.tag.input_len();
     let t = self.tag.clone();
+    let (res, len) = i.compare_len(t);
+    let tag_len = len.unwrap_or_else(|| self.tag.tag_len());
 
-    match i.compare(t) {
+    match res {
       CompareResult::Ok => Ok((i.take_from(tag_len), OM::Output::bind(|| i.take(tag_len)))),
       CompareResult::Incomplete => {
-        if OM::Incomplete::is_streaming() {
+        if i.is_streaming::<OM>() {
           Err(Err::Incomplete(Needed::new(tag_len - i.input_len())))
         } else {
           Err(Err::Error(OM::Error::bind(|| {
@@ -141,11 +185,17 @@ where
     let tag_len = self.tag.input_len();
     let t = self.tag.clone();
 
-    match i.compare_no_case(t) {
-      CompareResult::Ok => Ok((i.take_from(tag_len), OM::Output::bind(|| i.take(tag_len)))),
-      CompareResult::Incomplete => {
-        if OM::Incomplete::is_streaming() {
-          Err(Err::Incomplete(Needed::new(tag_len - i.input_len())))
+    // with Unicode case folding, the matched input can be longer or shorter than the tag
+    match i.compare_no_case_len(t) {
+      (CompareResult::Ok, len) => {
+        let len = len.unwrap_or(tag_len);
+        Ok((i.take_from(len), OM::Output::bind(|| i.take(len))))
+      }
+      (CompareResult::Incomplete, _) => {
+        if i.is_streaming::<OM>() {
+          Err(Err::Incomplete(Needed::new(
+            tag_len.saturating_sub(i.input_len()),
+          )))
         } else {
           Err(Err::Error(OM::Error::bind(|| {
             let e: ErrorKind = ErrorKind::Tag;
@@ -153,7 +203,7 @@ where
           })))
         }
       }
-      CompareResult::Error => Err(Err::Error(OM::Error::bind(|| {
+      (CompareResult::Error, _) => Err(Err::Error(OM::Error::bind(|| {
         let e: ErrorKind = ErrorKind::Tag;
         Error::from_error_kind(i, e)
       }))),
@@ -161,6 +211,254 @@ where
   }
 }
 
+/// Recognizes a pattern, compared to the input with the equivalence rule of `cmp`.
+///
+/// The comparators are [Exact](crate::Exact), [AsciiCaseInsensitive](crate::AsciiCaseInsensitive),
+/// [UnicodeCaseFold](crate::UnicodeCaseFold) and, with the `normalization` feature,
+/// [Nfc](crate::Nfc) and [Nfkc](crate::Nfkc). The part of the input that matched is returned,
+/// and it can be longer or shorter than the tag.
+///
+/// It will return `Err(Err::Error((_, ErrorKind::Tag)))` if the input doesn't match the pattern,
+/// and `Err::Incomplete` in streaming mode if the input is a prefix of a match.
+/// # Example
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Needed, IResult, Parser};
+/// use nom::bytes::tag_with;
+/// use nom::AsciiCaseInsensitive;
+///
+/// fn command(s: &str) -> IResult<&str, &str> {
+///   tag_with(AsciiCaseInsensitive, "LOGIN").parse_complete(s)
+/// }
+///
+/// assert_eq!(command("login bob"), Ok((" bob", "login")));
+/// assert_eq!(command("LoGiN bob"), Ok((" bob", "LoGiN")));
+/// assert_eq!(command("logout"), Err(Err::Error(Error::new("logout", ErrorKind::Tag))));
+/// ```
+pub fn tag_with<C, T, I, Error: ParseError<I>>(
+  cmp: C,
+  tag: T,
+) -> impl Parser<I, Output = I, Error = Error>
+where
+  I: Input,
+  C: Comparator<I, T>,
+  T: TagLength + Clone,
+{
+  TagWith {
+    cmp,
+    tag,
+    e: PhantomData,
+  }
+}
+
+/// Parser implementation for [tag_with]
+pub struct TagWith<C, T, E> {
+  cmp: C,
+  tag: T,
+  e: PhantomData<E>,
+}
+
+impl<I, Error: ParseError<I>, C, T> Parser<I> for TagWith<C, T, Error>
+where
+  I: Input,
+  C: Comparator<I, T>,
+  T: TagLength + Clone,
+{
+  type Output = I;
+
+  type Error = Error;
+
+  fn process<OM: OutputMode>(&mut self, i: I) -> crate::PResult<OM, I, Self::Output, Self::Error> {
+    let tag_len = self.tag.tag_len();
+
+    match self.cmp.compare(&i, self.tag.clone()) {
+      (CompareResult::Ok, len) => {
+        let len = len.unwrap_or(tag_len);
+        Ok((i.take_from(len), OM::Output::bind(|| i.take(len))))
+      }
+      (CompareResult::Incomplete, _) if i.is_streaming::<OM>() => Err(Err::Incomplete(
+        Needed::new(tag_len.saturating_sub(i.input_len())),
+      )),
+      _ => Err(Err::Error(OM::Error::bind(|| {
+        Error::from_error_kind(i, ErrorKind::Tag)
+      }))),
+    }
+  }
+}
+
+/// Recognizes a case insensitive keyword, returning the matched input and its normalized form.
+///
+/// The input data is compared to the keyword with no regard to case, like [tag_no_case], and the
+/// output contains both the part of the input that matched, with its original case, and the
+/// keyword as it was passed to this function. This is useful for grammars that compare keywords
+/// independently of the case, but must echo them as the user wrote them.
+/// # Example
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Needed, IResult};
+/// use nom::bytes::streaming::keyword_no_case;
+///
+/// fn select(s: &str) -> IResult<&str, (&str, &str)> {
+///   keyword_no_case("SELECT")(s)
+/// }
+///
+/// assert_eq!(select("select *"), Ok((" *", ("select", "SELECT"))));
+/// assert_eq!(select("SeLeCt *"), Ok((" *", ("SeLeCt", "SELECT"))));
+/// assert_eq!(select("insert"), Err(Err::Error(Error::new("insert", ErrorKind::Tag))));
+/// assert_eq!(select("sel"), Err(Err::Incomplete(Needed::new(3))));
+/// ```
+pub fn keyword_no_case<T, I, Error: ParseError<I>>(
+  keyword: T,
+) -> impl Parser<I, Output = (I, T), Error = Error>
+where
+  I: Input + Compare<T>,
+  T: Input + Clone,
+{
+  let normalized = keyword.clone();
+  tag_no_case(keyword).map(move |original| (original, normalized.clone()))
+}
+
+/// Text encoding detected from a byte order mark, see [bom()]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Encoding {
+  /// UTF-8, with the `EF BB BF` mark
+  Utf8,
+  /// little endian UTF-16, with the `FF FE` mark
+  Utf16Le,
+  /// big endian UTF-16, with the `FE FF` mark
+  Utf16Be,
+  /// little endian UTF-32, with the `FF FE 00 00` mark
+  Utf32Le,
+  /// big endian UTF-32, with the `00 00 FE FF` mark
+  Utf32Be,
+}
+
+impl Encoding {
+  /// Returns the byte order mark of this encoding
+  pub fn bom(self) -> &'static [u8] {
+    match self {
+      Encoding::Utf8 => b"\xEF\xBB\xBF",
+      Encoding::Utf16Le => b"\xFF\xFE",
+      Encoding::Utf16Be => b"\xFE\xFF",
+      Encoding::Utf32Le => b"\xFF\xFE\x00\x00",
+      Encoding::Utf32Be => b"\x00\x00\xFE\xFF",
+    }
+  }
+}
+
+/// Recognizes a byte order mark, returning the encoding it indicates.
+///
+/// The UTF-32 marks are tested before the UTF-16 ones, since the little endian UTF-32
+/// mark starts with the little endian UTF-16 one. It returns an error with [ErrorKind::Tag]
+/// if the input does not start with a byte order mark. In streaming mode, it returns
+/// `Incomplete` if the input could be the start of a longer mark.
+/// # Example
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Needed, IResult};
+/// use nom::bytes::Encoding;
+/// use nom::bytes::complete::bom;
+///
+/// fn parser(s: &[u8]) -> IResult<&[u8], Encoding> {
+///   bom(s)
+/// }
+///
+/// assert_eq!(parser(b"\xEF\xBB\xBFabc"), Ok((&b"abc"[..], Encoding::Utf8)));
+/// assert_eq!(parser(b"\xFF\xFEa\x00"), Ok((&b"a\x00"[..], Encoding::Utf16Le)));
+/// assert_eq!(parser(b"\xFF\xFE\x00\x00"), Ok((&b""[..], Encoding::Utf32Le)));
+/// assert_eq!(parser(b"abc"), Err(Err::Error(Error::new(&b"abc"[..], ErrorKind::Tag))));
+/// ```
+pub fn bom<I, Error: ParseError<I>>() -> impl Parser<I, Output = Encoding, Error = Error>
+where
+  I: Input + Compare<&'static [u8]>,
+{
+  Bom { e: PhantomData }
+}
+
+/// Parser implementation for [bom()]
+pub struct Bom<E> {
+  e: PhantomData<E>,
+}
+
+impl<I, Error: ParseError<I>> Parser<I> for Bom<Error>
+where
+  I: Input + Compare<&'static [u8]>,
+{
+  type Output = Encoding;
+  type Error = Error;
+
+  fn process<OM: OutputMode>(&mut self, i: I) -> crate::PResult<OM, I, Self::Output, Self::Error> {
+    // longest marks first
+    for encoding in [
+      Encoding::Utf32Le,
+      Encoding::Utf32Be,
+      Encoding::Utf8,
+      Encoding::Utf16Le,
+      Encoding::Utf16Be,
+    ] {
+      let mark = encoding.bom();
+      match i.compare(mark) {
+        CompareResult::Ok => return Ok((i.take_from(mark.len()), OM::Output::bind(|| encoding))),
+        CompareResult::Incomplete if i.is_streaming::<OM>() => {
+          return Err(Err::Incomplete(Needed::new(mark.len() - i.input_len())))
+        }
+        _ => {}
+      }
+    }
+
+    Err(Err::Error(OM::Error::bind(|| {
+      Error::from_error_kind(i, ErrorKind::Tag)
+    })))
+  }
+}
+
+/// Skips the UTF-8 byte order mark if the input starts with it.
+///
+/// Text files exported from some Windows tools start with the `EF BB BF` mark, which
+/// appears as the U+FEFF character in a `&str`. This parser can be applied before the
+/// rest of the grammar to ignore it. It never fails in complete mode.
+/// # Example
+/// ```rust
+/// # use nom::{IResult, Parser};
+/// use nom::bytes::complete::strip_bom;
+/// use nom::character::complete::alpha1;
+/// use nom::sequence::preceded;
+///
+/// fn parser(s: &str) -> IResult<&str, &str> {
+///   preceded(strip_bom, alpha1).parse(s)
+/// }
+///
+/// assert_eq!(parser("\u{feff}abc"), Ok(("", "abc")));
+/// assert_eq!(parser("abc"), Ok(("", "abc")));
+/// ```
+pub fn strip_bom<I, Error: ParseError<I>>() -> impl Parser<I, Output = (), Error = Error>
+where
+  I: Input + Compare<&'static [u8]>,
+{
+  StripBom { e: PhantomData }
+}
+
+/// Parser implementation for [strip_bom()]
+pub struct StripBom<E> {
+  e: PhantomData<E>,
+}
+
+impl<I, Error: ParseError<I>> Parser<I> for StripBom<Error>
+where
+  I: Input + Compare<&'static [u8]>,
+{
+  type Output = ();
+  type Error = Error;
+
+  fn process<OM: OutputMode>(&mut self, i: I) -> crate::PResult<OM, I, Self::Output, Self::Error> {
+    let mark = Encoding::Utf8.bom();
+    match i.compare(mark) {
+      CompareResult::Ok => Ok((i.take_from(mark.len()), OM::Output::bind(|| ()))),
+      CompareResult::Incomplete if i.is_streaming::<OM>() => {
+        Err(Err::Incomplete(Needed::new(mark.len() - i.input_len())))
+      }
+      _ => Ok((i, OM::Output::bind(|| ()))),
+    }
+  }
+}
+
 /// Parser wrapper for `split_at_position`
 pub struct SplitPosition<F, E> {
   predicate: F,
@@ -423,7 +721,7 @@ where
     }
 
     let input_len = input.input_len();
-    if OM::Incomplete::is_streaming() {
+    if input.is_streaming::<OM>() {
       let needed = if self.m > input_len {
         self.m - input_len
       } else {
@@ -508,6 +806,229 @@ where
   }
 }
 
+/// Set of bytes, stored as a 256-bit table
+///
+/// Testing a byte is a lookup in the table, instead of a call to a predicate or a search
+/// in a list of tokens. The sets are built with `const fn`s, so they can be constants.
+///
+/// ```rust
+/// use nom::bytes::ByteSet;
+///
+/// // the `tchar` rule of HTTP
+/// const TCHAR: ByteSet = ByteSet::from_range(b'0', b'9')
+///   .with_range(b'a', b'z')
+///   .with_range(b'A', b'Z')
+///   .with_bytes(b"!#$%&'*+-.^_`|~");
+///
+/// assert!(TCHAR.contains(b'G'));
+/// assert!(!TCHAR.contains(b':'));
+/// assert!(TCHAR.complement().contains(b':'));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ByteSet {
+  bits: [u64; 4],
+}
+
+impl ByteSet {
+  /// Creates an empty set
+  pub const fn new() -> Self {
+    ByteSet { bits: [0; 4] }
+  }
+
+  /// Creates the set of the bytes of `bytes`
+  pub const fn from_bytes(bytes: &[u8]) -> Self {
+    ByteSet::new().with_bytes(bytes)
+  }
+
+  /// Creates the set of the bytes from `start` to `end`, inclusive
+  pub const fn from_range(start: u8, end: u8) -> Self {
+    ByteSet::new().with_range(start, end)
+  }
+
+  /// Returns the set with the bytes of `bytes` added
+  pub const fn with_bytes(mut self, bytes: &[u8]) -> Self {
+    let mut i = 0;
+    while i < bytes.len() {
+      self = self.with(bytes[i]);
+      i += 1;
+    }
+    self
+  }
+
+  /// Returns the set with the bytes from `start` to `end`, inclusive, added
+  pub const fn with_range(mut self, start: u8, end: u8) -> Self {
+    let mut b = start as usize;
+    while b <= end as usize {
+      self = self.with(b as u8);
+      b += 1;
+    }
+    self
+  }
+
+  /// Returns the set with `byte` added
+  pub const fn with(mut self, byte: u8) -> Self {
+    self.bits[(byte >> 6) as usize] |= 1 << (byte & 63);
+    self
+  }
+
+  /// Returns the bytes that are in `self` or in `other`
+  pub const fn union(self, other: ByteSet) -> Self {
+    ByteSet {
+      bits: [
+        self.bits[0] | other.bits[0],
+        self.bits[1] | other.bits[1],
+        self.bits[2] | other.bits[2],
+        self.bits[3] | other.bits[3],
+      ],
+    }
+  }
+
+  /// Returns the bytes that are not in `self`
+  pub const fn complement(self) -> Self {
+    ByteSet {
+      bits: [!self.bits[0], !self.bits[1], !self.bits[2], !self.bits[3]],
+    }
+  }
+
+  /// Returns true if `byte` is in the set
+  #[inline(always)]
+  pub const fn contains(&self, byte: u8) -> bool {
+    (self.bits[(byte >> 6) as usize] >> (byte & 63)) & 1 != 0
+  }
+
+  /// Returns the length of the longest prefix of `bytes` made of bytes of the set
+  ///
+  /// The bytes are tested 8 at a time, without branching on each of them.
+  #[inline]
+  pub fn prefix_len(&self, bytes: &[u8]) -> usize {
+    let mut len = 0;
+    for chunk in bytes.chunks_exact(8) {
+      if !chunk.iter().fold(true, |all, &b| all & self.contains(b)) {
+        break;
+      }
+      len += 8;
+    }
+    len
+      + bytes[len..]
+        .iter()
+        .position(|&b| !self.contains(b))
+        .unwrap_or(bytes.len() - len)
+  }
+}
+
+impl FindToken<u8> for ByteSet {
+  fn find_token(&self, token: u8) -> bool {
+    self.contains(token)
+  }
+}
+
+/// Returns the longest input slice (if any) made of the bytes of `set`.
+///
+/// This is [take_while] for a set of bytes: each byte is tested with a lookup in the table
+/// of the set, so it is faster than a predicate that compares the byte to several values.
+///
+/// # Streaming Specific
+/// *Streaming version* will return a `Err::Incomplete(Needed::new(1))` if the match reaches
+/// the end of input.
+/// # Example
+/// ```rust
+/// # use nom::{Err, error::ErrorKind, Needed, IResult, Parser};
+/// use nom::bytes::{take_while_set, ByteSet};
+///
+/// const DIGITS: ByteSet = ByteSet::from_range(b'0', b'9');
+///
+/// fn digits(s: &[u8]) -> IResult<&[u8], &[u8]> {
+///   take_while_set(DIGITS).parse_complete(s)
+/// }
+///
+/// assert_eq!(digits(b"123abc"), Ok((&b"abc"[..], &b"123"[..])));
+/// assert_eq!(digits(b"abc"), Ok((&b"abc"[..], &b""[..])));
+/// assert_eq!(digits(b"123"), Ok((&b""[..], &b"123"[..])));
+/// assert_eq!(
+///   take_while_set::<_, nom::error::Error<_>>(DIGITS).parse(&b"123"[..]),
+///   Err(Err::Incomplete(Needed::new(1)))
+/// );
+/// ```
+pub fn take_while_set<I, Error: ParseError<I>>(
+  set: ByteSet,
+) -> impl Parser<I, Output = I, Error = Error>
+where
+  I: Input<Item = u8> + AsBytes,
+{
+  TakeWhileSet {
+    set,
+    e: None,
+    error: PhantomData,
+  }
+}
+
+/// Returns the longest input slice (at least 1) made of the bytes of `set`.
+///
+/// This is [is_a] for a set of bytes, tested with lookups in the table of the set.
+///
+/// It will return a `Err(Err::Error((_, ErrorKind::IsA)))` if the input does not start with
+/// a byte of the set.
+///
+/// # Streaming Specific
+/// *Streaming version* will return a `Err::Incomplete(Needed::new(1))` if the match reaches
+/// the end of input.
+/// # Example
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+/// use nom::bytes::{is_a_set, ByteSet};
+///
+/// const HEX: ByteSet = ByteSet::from_range(b'0', b'9').with_range(b'A', b'F');
+///
+/// fn hex(s: &[u8]) -> IResult<&[u8], &[u8]> {
+///   is_a_set(HEX).parse_complete(s)
+/// }
+///
+/// assert_eq!(hex(b"DEADBEEF and others"), Ok((&b" and others"[..], &b"DEADBEEF"[..])));
+/// assert_eq!(hex(b"D15EA5E"), Ok((&b""[..], &b"D15EA5E"[..])));
+/// assert_eq!(hex(b"xyz"), Err(Err::Error(Error::new(&b"xyz"[..], ErrorKind::IsA))));
+/// assert_eq!(hex(b""), Err(Err::Error(Error::new(&b""[..], ErrorKind::IsA))));
+/// ```
+pub fn is_a_set<I, Error: ParseError<I>>(set: ByteSet) -> impl Parser<I, Output = I, Error = Error>
+where
+  I: Input<Item = u8> + AsBytes,
+{
+  TakeWhileSet {
+    set,
+    e: Some(ErrorKind::IsA),
+    error: PhantomData,
+  }
+}
+
+/// Parser implementation for [take_while_set] and [is_a_set]
+pub struct TakeWhileSet<E> {
+  set: ByteSet,
+  /// Error returned if nothing matched, when at least one byte is required
+  e: Option<ErrorKind>,
+  error: PhantomData<E>,
+}
+
+impl<I, Error: ParseError<I>> Parser<I> for TakeWhileSet<Error>
+where
+  I: Input<Item = u8> + AsBytes,
+{
+  type Output = I;
+
+  type Error = Error;
+
+  #[inline(always)]
+  fn process<OM: OutputMode>(&mut self, i: I) -> crate::PResult<OM, I, Self::Output, Self::Error> {
+    let len = self.set.prefix_len(i.as_bytes());
+
+    if len == i.input_len() && i.is_streaming::<OM>() {
+      return Err(Err::Incomplete(Needed::new(1)));
+    }
+    match self.e {
+      Some(e) if len == 0 => Err(Err::Error(OM::Error::bind(|| Error::from_error_kind(i, e)))),
+      _ => Ok((i.take_from(len), OM::Output::bind(|| i.take(len)))),
+    }
+  }
+}
+
 /// Returns an input slice containing the first N input elements (Input[..N]).
 ///
 /// # Streaming Specific
@@ -548,6 +1069,26 @@ pub struct Take<E> {
   e: PhantomData<E>,
 }
 
+impl<E> Take<E> {
+  /// Creates a parser returning the first `length` elements of the input, like [take]
+  ///
+  /// Unlike [take], it can be called in `const` contexts.
+  pub const fn new(length: usize) -> Self {
+    Take {
+      length,
+      e: PhantomData,
+    }
+  }
+}
+
+impl<E> Clone for Take<E> {
+  fn clone(&self) -> Self {
+    *self
+  }
+}
+
+impl<E> Copy for Take<E> {}
+
 impl<I, Error: ParseError<I>> Parser<I> for Take<Error>
 where
   I: Input,
@@ -558,7 +1099,7 @@ where
   fn process<OM: OutputMode>(&mut self, i: I) -> crate::PResult<OM, I, Self::Output, Self::Error> {
     match i.slice_index(self.length) {
       Err(needed) => {
-        if OM::Incomplete::is_streaming() {
+        if i.is_streaming::<OM>() {
           Err(Err::Incomplete(needed))
         } else {
           Err(Err::Error(OM::Error::bind(|| {
@@ -572,40 +1113,346 @@ where
   }
 }
 
-/// Returns the input slice up to the first occurrence of the pattern.
+/// Returns the first `N` bytes of the input as an array, without allocating.
 ///
-/// It doesn't consume the pattern.
+/// For a `&[u8]`, the output is a `&[u8; N]` borrowing the input. For inputs that are
+/// not contiguous in memory, it is a `[u8; N]` copy, see [ToArray].
 ///
-/// # Streaming Specific
-/// *Streaming version* will return a `Err::Incomplete(Needed::new(N))` if the input doesn't
-/// contain the pattern or if the input is smaller than the pattern.
+/// *Streaming version*: returns `Err::Incomplete(Needed::new(M))`, where M is the number of
+/// missing bytes, if the input is shorter than `N`.
+///
+/// *Complete version*: returns an error with [ErrorKind::Eof] if the input is shorter than `N`.
 /// # Example
 /// ```rust
-/// # use nom::{Err, error::ErrorKind, Needed, IResult};
-/// use nom::bytes::streaming::take_until;
+/// # use nom::{Err, error::{Error, ErrorKind}, Needed, Parser};
+/// use nom::bytes::take_array;
+/// use nom::multi::array;
+/// use nom::number::be_u16;
 ///
-/// fn until_eof(s: &str) -> IResult<&str, &str> {
-///   take_until("eof")(s)
-/// }
+/// let input = &b"\x89PNG\x00\x01\x00\x02rest"[..];
+/// let (rest, (magic, sizes)) = (take_array::<4, _, Error<_>>(), array::<2, _, _>(be_u16()))
+///   .parse(input)
+///   .unwrap();
+/// assert_eq!(magic, b"\x89PNG");
+/// assert_eq!(sizes, [1, 2]);
+/// assert_eq!(rest, b"rest");
 ///
-/// assert_eq!(until_eof("hello, worldeof"), Ok(("eof", "hello, world")));
-/// assert_eq!(until_eof("hello, world"), Err(Err::Incomplete(Needed::Unknown)));
-/// assert_eq!(until_eof("hello, worldeo"), Err(Err::Incomplete(Needed::Unknown)));
-/// assert_eq!(until_eof("1eof2eof"), Ok(("eof2eof", "1")));
+/// assert_eq!(take_array::<4, _, Error<_>>().parse(&b"\x89P"[..]), Err(Err::Incomplete(Needed::new(2))));
+/// assert_eq!(
+///   take_array::<4, _, Error<_>>().parse_complete(&b"\x89P"[..]),
+///   Err(Err::Error(Error::new(&b"\x89P"[..], ErrorKind::Eof)))
+/// );
 /// ```
-pub fn take_until<T, I, Error: ParseError<I>>(tag: T) -> impl Parser<I, Output = I, Error = Error>
+pub fn take_array<const N: usize, I, Error: ParseError<I>>(
+) -> impl Parser<I, Output = <I as ToArray<N>>::Array, Error = Error>
 where
-  I: Input + FindSubstring<T>,
-  T: Clone,
+  I: ToArray<N>,
 {
-  TakeUntil {
-    tag,
-    e: PhantomData,
-  }
+  TakeArray { e: PhantomData }
 }
 
-/// Parser implementation for [take_until]
-pub struct TakeUntil<T, E> {
+/// Parser implementation for [take_array]
+pub struct TakeArray<E, const N: usize> {
+  e: PhantomData<E>,
+}
+
+impl<I, Error: ParseError<I>, const N: usize> Parser<I> for TakeArray<Error, N>
+where
+  I: ToArray<N>,
+{
+  type Output = <I as ToArray<N>>::Array;
+  type Error = Error;
+
+  fn process<OM: OutputMode>(&mut self, i: I) -> crate::PResult<OM, I, Self::Output, Self::Error> {
+    let (i, bytes) = take(N).process::<OutputM<Emit, OM::Error, OM::Incomplete>>(i)?;
+    Ok((i, OM::Output::bind(|| bytes.to_array())))
+  }
+}
+
+/// Returns the first `N` bytes of the input as an array, if they all match the predicate.
+///
+/// This is [take_while_m_n] with `m` and `n` equal to `N`, for fixed width fields like
+/// the groups of a UUID or a hexadecimal checksum. As for [take_array], the output is a
+/// `&[u8; N]` for a `&[u8]` input, so no length check or conversion is needed afterwards.
+///
+/// It will return an `Err::Error((_, ErrorKind::TakeWhileMN))` if one of the `N` first
+/// bytes does not match the predicate.
+///
+/// *Streaming version*: returns `Err::Incomplete(Needed::new(M))`, where M is the number of
+/// missing bytes, if the input is shorter than `N` and all its bytes match.
+///
+/// *Complete version*: returns an error with [ErrorKind::TakeWhileMN] if the input is
+/// shorter than `N`.
+/// # Example
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Needed, Parser};
+/// use nom::bytes::take_while_array;
+/// use nom::AsChar;
+///
+/// let mut crc = take_while_array::<8, _, _, Error<_>>(AsChar::is_hex_digit);
+/// assert_eq!(crc.parse(&b"cbf43926 rest"[..]), Ok((&b" rest"[..], b"cbf43926")));
+/// assert_eq!(
+///   crc.parse(&b"cbf4392 rest"[..]),
+///   Err(Err::Error(Error::new(&b"cbf4392 rest"[..], ErrorKind::TakeWhileMN)))
+/// );
+/// assert_eq!(crc.parse(&b"cbf4"[..]), Err(Err::Incomplete(Needed::new(4))));
+/// ```
+pub fn take_while_array<const N: usize, F, I, Error: ParseError<I>>(
+  predicate: F,
+) -> impl Parser<I, Output = <I as ToArray<N>>::Array, Error = Error>
+where
+  I: ToArray<N>,
+  F: Fn(u8) -> bool,
+{
+  TakeWhileArray {
+    predicate,
+    e: PhantomData,
+  }
+}
+
+/// Parser implementation for [take_while_array]
+pub struct TakeWhileArray<F, E, const N: usize> {
+  predicate: F,
+  e: PhantomData<E>,
+}
+
+impl<I, Error: ParseError<I>, F, const N: usize> Parser<I> for TakeWhileArray<F, Error, N>
+where
+  I: ToArray<N>,
+  F: Fn(u8) -> bool,
+{
+  type Output = <I as ToArray<N>>::Array;
+  type Error = Error;
+
+  fn process<OM: OutputMode>(&mut self, i: I) -> crate::PResult<OM, I, Self::Output, Self::Error> {
+    let (i, bytes) =
+      take_while_m_n(N, N, &self.predicate)
+        .process::<OutputM<Emit, OM::Error, OM::Incomplete>>(i)?;
+    Ok((i, OM::Output::bind(|| bytes.to_array())))
+  }
+}
+
+/// Returns a fixed width text field of `count` bytes, without its trailing padding.
+///
+/// Legacy record formats like tar headers, ISO 9660 descriptors or fixed width tables
+/// store text in fields of a fixed size, padded at the end with NUL bytes or spaces.
+/// This takes exactly `count` bytes, removes the `pad` bytes at the end of the field,
+/// and checks that the rest is valid UTF-8.
+///
+/// It will return an error with [ErrorKind::Utf8], pointing at the first invalid byte,
+/// if the field is not valid UTF-8.
+///
+/// *Streaming version*: returns `Err::Incomplete(Needed::new(M))`, where M is the number of
+/// missing bytes, if the input is shorter than `count`.
+///
+/// *Complete version*: returns an error with [ErrorKind::Eof] if the input is shorter than
+/// `count`.
+/// # Example
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Needed, Parser};
+/// use nom::bytes::padded_fixed_str;
+///
+/// let mut header = (
+///   padded_fixed_str::<Error<_>>(8, b'\0'),
+///   padded_fixed_str(6, b' '),
+/// );
+/// assert_eq!(
+///   header.parse(&b"doc.txt\0ustar rest"[..]),
+///   Ok((&b"rest"[..], ("doc.txt", "ustar")))
+/// );
+/// assert_eq!(
+///   header.parse(&b"doc.txt\0us"[..]),
+///   Err(Err::Incomplete(Needed::new(4)))
+/// );
+/// assert_eq!(
+///   header.parse(&b"doc\xff\0\0\0\0ustar "[..]),
+///   Err(Err::Error(Error::new(&b"\xff\0\0\0\0ustar "[..], ErrorKind::Utf8)))
+/// );
+/// ```
+pub fn padded_fixed_str<'a, Error: ParseError<&'a [u8]>>(
+  count: usize,
+  pad: u8,
+) -> impl Parser<&'a [u8], Output = &'a str, Error = Error> {
+  PaddedFixedStr {
+    length: count,
+    pad,
+    e: PhantomData,
+  }
+}
+
+/// Parser implementation for [padded_fixed_str]
+pub struct PaddedFixedStr<E> {
+  length: usize,
+  pad: u8,
+  e: PhantomData<E>,
+}
+
+impl<'a, Error: ParseError<&'a [u8]>> Parser<&'a [u8]> for PaddedFixedStr<Error> {
+  type Output = &'a str;
+  type Error = Error;
+
+  fn process<OM: OutputMode>(
+    &mut self,
+    i: &'a [u8],
+  ) -> crate::PResult<OM, &'a [u8], Self::Output, Self::Error> {
+    let (rest, field) = take(self.length).process::<OutputM<Emit, OM::Error, OM::Incomplete>>(i)?;
+    let end = field
+      .iter()
+      .rposition(|b| *b != self.pad)
+      .map_or(0, |index| index + 1);
+
+    match crate::lib::std::str::from_utf8(&field[..end]) {
+      Ok(s) => Ok((rest, OM::Output::bind(|| s))),
+      Err(e) => Err(Err::Error(OM::Error::bind(|| {
+        Error::from_error_kind(&i[e.valid_up_to()..], ErrorKind::Utf8)
+      }))),
+    }
+  }
+}
+
+/// Parses a block of bytes protected by a checksum, like the chunks of many binary formats.
+///
+/// The first parser returns the length of the block, which is taken from the input, then
+/// the checksum parser is applied after the block. `check` receives the raw bytes of the
+/// block and the checksum, and the body parser is applied on the block only if it returns
+/// `true`. The body parser runs in complete mode, and does not have to consume the whole
+/// block.
+///
+/// It will return an error with [ErrorKind::Checksum], pointing at the start of the block,
+/// if the checksum does not match.
+///
+/// *Streaming version*: returns `Err::Incomplete` if the block or the checksum are not
+/// complete.
+///
+/// *Complete version*: returns an error with [ErrorKind::Eof] if the input is shorter than
+/// the block.
+/// # Example
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Needed, Parser};
+/// use nom::bytes::{tag, verified_block};
+/// use nom::number::u8;
+///
+/// // the checksum is the sum of the bytes of the block
+/// let sum = |block: &&[u8], checksum: &u8| {
+///   block.iter().fold(0u8, |sum, b| sum.wrapping_add(*b)) == *checksum
+/// };
+/// let mut chunk = verified_block(u8(), tag::<_, _, Error<_>>("ping"), u8(), sum);
+///
+/// assert_eq!(chunk.parse(&b"\x04ping\xaerest"[..]), Ok((&b"rest"[..], &b"ping"[..])));
+/// assert_eq!(
+///   chunk.parse(&b"\x04pong\xae"[..]),
+///   Err(Err::Error(Error::new(&b"pong"[..], ErrorKind::Checksum)))
+/// );
+/// assert_eq!(chunk.parse(&b"\x04pin"[..]), Err(Err::Incomplete(Needed::new(1))));
+/// ```
+pub fn verified_block<I, E, L, B, C, F>(
+  length: L,
+  body: B,
+  checksum: C,
+  check: F,
+) -> impl Parser<I, Output = <B as Parser<I>>::Output, Error = E>
+where
+  I: Input,
+  E: ParseError<I>,
+  L: Parser<I, Error = E>,
+  <L as Parser<I>>::Output: ToUsize,
+  B: Parser<I, Error = E>,
+  C: Parser<I, Error = E>,
+  F: FnMut(&I, &<C as Parser<I>>::Output) -> bool,
+{
+  VerifiedBlock {
+    length,
+    body,
+    checksum,
+    check,
+  }
+}
+
+/// Parser implementation for [verified_block]
+pub struct VerifiedBlock<L, B, C, F> {
+  length: L,
+  body: B,
+  checksum: C,
+  check: F,
+}
+
+impl<I, E, L, B, C, F> Parser<I> for VerifiedBlock<L, B, C, F>
+where
+  I: Input,
+  E: ParseError<I>,
+  L: Parser<I, Error = E>,
+  <L as Parser<I>>::Output: ToUsize,
+  B: Parser<I, Error = E>,
+  C: Parser<I, Error = E>,
+  F: FnMut(&I, &<C as Parser<I>>::Output) -> bool,
+{
+  type Output = <B as Parser<I>>::Output;
+  type Error = E;
+
+  fn process<OM: OutputMode>(&mut self, i: I) -> crate::PResult<OM, I, Self::Output, Self::Error> {
+    let (i, length) = self
+      .length
+      .process::<OutputM<Emit, OM::Error, OM::Incomplete>>(i)?;
+    let (i, block) =
+      take::<_, _, E>(length.to_usize()).process::<OutputM<Emit, OM::Error, OM::Incomplete>>(i)?;
+    let (rest, checksum) = self
+      .checksum
+      .process::<OutputM<Emit, OM::Error, OM::Incomplete>>(i)?;
+
+    if !(self.check)(&block, &checksum) {
+      return Err(Err::Error(OM::Error::bind(|| {
+        E::from_error_kind(block, ErrorKind::Checksum)
+      })));
+    }
+
+    match self
+      .body
+      .process::<OutputM<OM::Output, OM::Error, crate::Complete>>(block.clone())
+    {
+      Ok((_, o)) => Ok((rest, o)),
+      Err(Err::Incomplete(_)) => Err(Err::Error(OM::Error::bind(|| {
+        E::from_error_kind(block, ErrorKind::Complete)
+      }))),
+      Err(Err::Error(e)) => Err(Err::Error(e)),
+      Err(Err::Failure(e)) => Err(Err::Failure(e)),
+    }
+  }
+}
+
+/// Returns the input slice up to the first occurrence of the pattern.
+///
+/// It doesn't consume the pattern.
+///
+/// # Streaming Specific
+/// *Streaming version* will return a `Err::Incomplete(Needed::new(N))` if the input doesn't
+/// contain the pattern or if the input is smaller than the pattern.
+/// # Example
+/// ```rust
+/// # use nom::{Err, error::ErrorKind, Needed, IResult};
+/// use nom::bytes::streaming::take_until;
+///
+/// fn until_eof(s: &str) -> IResult<&str, &str> {
+///   take_until("eof")(s)
+/// }
+///
+/// assert_eq!(until_eof("hello, worldeof"), Ok(("eof", "hello, world")));
+/// assert_eq!(until_eof("hello, world"), Err(Err::Incomplete(Needed::Unknown)));
+/// assert_eq!(until_eof("hello, worldeo"), Err(Err::Incomplete(Needed::Unknown)));
+/// assert_eq!(until_eof("1eof2eof"), Ok(("eof2eof", "1")));
+/// ```
+pub fn take_until<T, I, Error: ParseError<I>>(tag: T) -> impl Parser<I, Output = I, Error = Error>
+where
+  I: Input + FindSubstring<T>,
+  T: Clone,
+{
+  TakeUntil {
+    tag,
+    e: PhantomData,
+  }
+}
+
+/// Parser implementation for [take_until]
+pub struct TakeUntil<T, E> {
   tag: T,
   e: PhantomData<E>,
 }
@@ -621,7 +1468,7 @@ where
   fn process<OM: OutputMode>(&mut self, i: I) -> crate::PResult<OM, I, Self::Output, Self::Error> {
     match i.find_substring(self.tag.clone()) {
       None => {
-        if OM::Incomplete::is_streaming() {
+        if i.is_streaming::<OM>() {
           Err(Err::Incomplete(Needed::Unknown))
         } else {
           Err(Err::Error(OM::Error::bind(|| {
@@ -685,7 +1532,7 @@ where
   fn process<OM: OutputMode>(&mut self, i: I) -> crate::PResult<OM, I, Self::Output, Self::Error> {
     match i.find_substring(self.tag.clone()) {
       None => {
-        if OM::Incomplete::is_streaming() {
+        if i.is_streaming::<OM>() {
           Err(Err::Incomplete(Needed::Unknown))
         } else {
           Err(Err::Error(OM::Error::bind(|| {
@@ -703,6 +1550,149 @@ where
   }
 }
 
+/// Returns the input slice up to the first occurrence of any pattern from a set.
+///
+/// It doesn't consume the pattern. The set can be an array or a slice of patterns, or
+/// any other type implementing [FindSubstringSet], like a prebuilt automaton with the
+/// `aho-corasick` feature.
+///
+/// # Streaming Specific
+/// *Streaming version* will return a `Err::Incomplete(Needed::Unknown)` if the input doesn't
+/// contain any of the patterns. If a pattern is found, the result does not account for a
+/// longer pattern starting earlier that could be completed by more data.
+/// # Example
+/// ```rust
+/// # use nom::{Err, error::ErrorKind, Needed, IResult};
+/// use nom::bytes::streaming::take_until_any;
+///
+/// fn comment(s: &str) -> IResult<&str, &str> {
+///   take_until_any(["-->", "\n"])(s)
+/// }
+///
+/// assert_eq!(comment("hello -->"), Ok(("-->", "hello ")));
+/// assert_eq!(comment("hello\nworld -->"), Ok(("\nworld -->", "hello")));
+/// assert_eq!(comment("hello"), Err(Err::Incomplete(Needed::Unknown)));
+/// ```
+pub fn take_until_any<T, I, Error: ParseError<I>>(
+  set: T,
+) -> impl Parser<I, Output = I, Error = Error>
+where
+  I: Input + FindSubstringSet<T>,
+  T: Clone,
+{
+  TakeUntilAny {
+    set,
+    e: PhantomData,
+  }
+}
+
+/// Parser implementation for [take_until_any]
+pub struct TakeUntilAny<T, E> {
+  set: T,
+  e: PhantomData<E>,
+}
+
+impl<I, T, Error: ParseError<I>> Parser<I> for TakeUntilAny<T, Error>
+where
+  I: Input + FindSubstringSet<T>,
+  T: Clone,
+{
+  type Output = I;
+  type Error = Error;
+
+  fn process<OM: OutputMode>(&mut self, i: I) -> crate::PResult<OM, I, Self::Output, Self::Error> {
+    match i.find_substring_set(self.set.clone()) {
+      None => {
+        if i.is_streaming::<OM>() {
+          Err(Err::Incomplete(Needed::Unknown))
+        } else {
+          Err(Err::Error(OM::Error::bind(|| {
+            Error::from_error_kind(i, ErrorKind::TakeUntil)
+          })))
+        }
+      }
+      Some(index) => Ok((i.take_from(index), OM::Output::bind(|| i.take(index)))),
+    }
+  }
+}
+
+/// Returns the input slice up to the first position where the embedded parser succeeds.
+///
+/// The embedded parser is applied at each position of the input without producing its
+/// output, and its match is not consumed. This is useful to skip data until the next
+/// valid element, like a record header. It returns an error with [ErrorKind::TakeUntil]
+/// if the parser never succeeds, and failures from the parser are returned directly.
+///
+/// # Streaming Specific
+/// *Streaming version* will return a `Err::Incomplete(Needed::Unknown)` if the parser does
+/// not succeed on the available data, or the parser's `Err::Incomplete` if it needs more data
+/// at one of the positions.
+/// # Example
+/// ```rust
+/// # use nom::{Err, error::ErrorKind, Needed, IResult};
+/// use nom::bytes::streaming::take_until_parser;
+/// use nom::character::streaming::{char, digit1};
+/// use nom::sequence::pair;
+///
+/// fn skip_to_record(s: &str) -> IResult<&str, &str> {
+///   take_until_parser(pair(char('#'), digit1))(s)
+/// }
+///
+/// assert_eq!(skip_to_record("garbage #x #12;"), Ok(("#12;", "garbage #x ")));
+/// assert_eq!(skip_to_record("garbage #1"), Err(Err::Incomplete(Needed::new(1))));
+/// ```
+pub fn take_until_parser<I, Error: ParseError<I>, F>(
+  parser: F,
+) -> impl Parser<I, Output = I, Error = Error>
+where
+  I: Input,
+  F: Parser<I, Error = Error>,
+{
+  TakeUntilParser { parser }
+}
+
+/// Parser implementation for [take_until_parser]
+pub struct TakeUntilParser<F> {
+  parser: F,
+}
+
+impl<I, F> Parser<I> for TakeUntilParser<F>
+where
+  I: Input,
+  F: Parser<I>,
+{
+  type Output = I;
+  type Error = <F as Parser<I>>::Error;
+
+  fn process<OM: OutputMode>(&mut self, i: I) -> crate::PResult<OM, I, Self::Output, Self::Error> {
+    let positions = i
+      .iter_indices()
+      .map(|(index, _)| index)
+      .chain(core::iter::once(i.input_len()));
+
+    for index in positions {
+      let tail = i.take_from(index);
+      match self
+        .parser
+        .process::<OutputM<Check, Check, OM::Incomplete>>(tail.clone())
+      {
+        Ok(_) => return Ok((tail, OM::Output::bind(|| i.take(index)))),
+        Err(Err::Error(_)) => {}
+        Err(Err::Failure(e)) => return Err(Err::Failure(e)),
+        Err(Err::Incomplete(n)) => return Err(Err::Incomplete(n)),
+      }
+    }
+
+    if i.is_streaming::<OM>() {
+      Err(Err::Incomplete(Needed::Unknown))
+    } else {
+      Err(Err::Error(OM::Error::bind(|| {
+        <F as Parser<I>>::Error::from_error_kind(i, ErrorKind::TakeUntil)
+      })))
+    }
+  }
+}
+
 /// Matches a byte string with escaped characters.
 ///
 /// * The first argument matches the normal characters (it must not accept the control character)
@@ -777,7 +1767,7 @@ where
       {
         Ok((i2, _)) => {
           if i2.input_len() == 0 {
-            if OM::Incomplete::is_streaming() {
+            if input.is_streaming::<OM>() {
               return Err(Err::Incomplete(Needed::Unknown));
             } else {
               let index = input.input_len();
@@ -801,7 +1791,7 @@ where
           if i.iter_elements().next().unwrap().as_char() == self.control_char {
             let next = self.control_char.len_utf8();
             if next >= i.input_len() {
-              if OM::Incomplete::is_streaming() {
+              if input.is_streaming::<OM>() {
                 return Err(Err::Incomplete(Needed::new(1)));
               } else {
                 return Err(Err::Error(OM::Error::bind(|| {
@@ -815,7 +1805,7 @@ where
               {
                 Ok((i2, _)) => {
                   if i2.input_len() == 0 {
-                    if OM::Incomplete::is_streaming() {
+                    if input.is_streaming::<OM>() {
                       return Err(Err::Incomplete(Needed::Unknown));
                     } else {
                       let index = input.input_len();
@@ -854,7 +1844,7 @@ where
       }
     }
 
-    if OM::Incomplete::is_streaming() {
+    if input.is_streaming::<OM>() {
       Err(Err::Incomplete(Needed::Unknown))
     } else {
       let index = input.input_len();
@@ -965,7 +1955,7 @@ where
             res
           });
           if i2.input_len() == 0 {
-            if OM::Incomplete::is_streaming() {
+            if input.is_streaming::<OM>() {
               return Err(Err::Incomplete(Needed::Unknown));
             } else {
               let index = input.input_len();
@@ -984,7 +1974,7 @@ where
             let input_len = input.input_len();
 
             if next >= input_len {
-              if OM::Incomplete::is_streaming() {
+              if input.is_streaming::<OM>() {
                 return Err(Err::Incomplete(Needed::Unknown));
               } else {
                 return Err(Err::Error(OM::Error::bind(|| {
@@ -999,7 +1989,7 @@ where
                     res
                   });
                   if i2.input_len() == 0 {
-                    if OM::Incomplete::is_streaming() {
+                    if input.is_streaming::<OM>() {
                       return Err(Err::Incomplete(Needed::Unknown));
                     } else {
                       return Ok((input.take_from(input.input_len()), res));
@@ -1035,10 +2025,182 @@ where
       }
     }
 
-    if OM::Incomplete::is_streaming() {
+    if input.is_streaming::<OM>() {
       Err(Err::Incomplete(Needed::Unknown))
     } else {
       Ok((input.take_from(index), res))
     }
   }
 }
+
+/// Matches a string with escaped characters, borrowing the input if there is no
+/// escape sequence.
+///
+/// This works like [escaped_transform], but returns a [Cow](crate::lib::std::borrow::Cow):
+/// when only the `normal` parser matched, the output borrows the recognized input
+/// without allocating. The output of `normal` is not used: the input it consumed is
+/// borrowed or copied as is, while the output of `transform` replaces the escape
+/// sequences.
+///
+/// ```
+/// # use nom::{Err, error::ErrorKind, Needed, IResult, Parser};
+/// use nom::bytes::escaped_transform_cow;
+/// use nom::bytes::complete::tag;
+/// use nom::character::complete::alpha1;
+/// use nom::branch::alt;
+/// use nom::combinator::value;
+/// use std::borrow::Cow;
+///
+/// fn parser(input: &str) -> IResult<&str, Cow<'_, str>> {
+///   escaped_transform_cow(
+///     alpha1,
+///     '\\',
+///     alt((
+///       value("\\", tag("\\")),
+///       value("\"", tag("\"")),
+///       value("\n", tag("n")),
+///     ))
+///   ).parse_complete(input)
+/// }
+///
+/// assert_eq!(parser("abcd\""), Ok(("\"", Cow::Borrowed("abcd"))));
+/// assert_eq!(parser("ab\\\"cd\""), Ok(("\"", Cow::Owned(String::from("ab\"cd")))));
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "alloc")))]
+pub fn escaped_transform_cow<'a, I, Error, F, G>(
+  normal: F,
+  control_char: char,
+  transform: G,
+) -> EscapedTransformCow<'a, F, G, Error>
+where
+  I: Clone + crate::traits::Offset + Input + crate::traits::AsCow<'a>,
+  <G as Parser<I>>::Output:
+    ExtendInto<Item = <I as ExtendInto>::Item, Extender = <I as ExtendInto>::Extender>,
+  <I as Input>::Item: AsChar,
+  F: Parser<I, Error = Error>,
+  G: Parser<I, Error = Error>,
+  Error: ParseError<I>,
+{
+  EscapedTransformCow {
+    normal,
+    control_char,
+    transform,
+    e: PhantomData,
+    o: PhantomData,
+  }
+}
+
+/// Parser implementation for [escaped_transform_cow]
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "alloc")))]
+pub struct EscapedTransformCow<'a, F, G, E> {
+  normal: F,
+  transform: G,
+  control_char: char,
+  e: PhantomData<E>,
+  o: PhantomData<&'a ()>,
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, I, Error, F, G> Parser<I> for EscapedTransformCow<'a, F, G, Error>
+where
+  I: Clone + crate::traits::Offset + Input + crate::traits::AsCow<'a>,
+  <G as Parser<I>>::Output:
+    ExtendInto<Item = <I as ExtendInto>::Item, Extender = <I as ExtendInto>::Extender>,
+  <I as Input>::Item: AsChar,
+  F: Parser<I, Error = Error>,
+  G: Parser<I, Error = Error>,
+  Error: ParseError<I>,
+{
+  type Output = crate::lib::std::borrow::Cow<'a, <I as crate::traits::AsCow<'a>>::Target>;
+  type Error = Error;
+
+  fn process<OM: OutputMode>(
+    &mut self,
+    input: I,
+  ) -> crate::PResult<OM, I, Self::Output, Self::Error> {
+    // decoded value, only built once an escape sequence is found
+    let mut owned = None;
+    // start of the input that was not copied yet to `owned`
+    let mut start = 0;
+    let mut index = 0;
+
+    let end = loop {
+      if index >= input.input_len() {
+        if input.is_streaming::<OM>() {
+          return Err(Err::Incomplete(Needed::Unknown));
+        }
+        break index;
+      }
+
+      let remainder = input.take_from(index);
+      match self
+        .normal
+        .process::<OutputM<Check, OM::Error, OM::Incomplete>>(remainder.clone())
+      {
+        Ok((i2, _)) => {
+          if i2.input_len() == remainder.input_len() {
+            break index;
+          }
+          index = input.offset(&i2);
+        }
+        Err(Err::Error(_)) => {
+          // unwrap() should be safe here since index < $i.input_len()
+          if remainder.iter_elements().next().unwrap().as_char() != self.control_char {
+            if index == 0 {
+              return Err(Err::Error(OM::Error::bind(|| {
+                Error::from_error_kind(remainder, ErrorKind::EscapedTransform)
+              })));
+            }
+            break index;
+          }
+
+          let next = index + self.control_char.len_utf8();
+          if next >= input.input_len() {
+            if input.is_streaming::<OM>() {
+              return Err(Err::Incomplete(Needed::Unknown));
+            } else {
+              return Err(Err::Error(OM::Error::bind(|| {
+                Error::from_error_kind(remainder, ErrorKind::EscapedTransform)
+              })));
+            }
+          }
+
+          let (i2, o) = self.transform.process::<OM>(input.take_from(next))?;
+          let prefix = input.take_from(start).take(index - start);
+          let acc = match owned.take() {
+            Some(acc) => acc,
+            None => OM::Output::bind(|| input.new_builder()),
+          };
+          owned = Some(OM::Output::combine(o, acc, |o, mut acc| {
+            prefix.extend_into(&mut acc);
+            o.extend_into(&mut acc);
+            acc
+          }));
+          index = input.offset(&i2);
+          start = index;
+        }
+        Err(Err::Failure(e)) => {
+          return Err(Err::Failure(e));
+        }
+        Err(Err::Incomplete(i)) => {
+          return Err(Err::Incomplete(i));
+        }
+      }
+    };
+
+    let output = match owned {
+      None => OM::Output::bind(|| input.take(end).as_cow()),
+      Some(acc) => {
+        let rest = input.take_from(start).take(end - start);
+        OM::Output::map(acc, move |mut acc| {
+          rest.extend_into(&mut acc);
+          crate::lib::std::borrow::Cow::Owned(acc)
+        })
+      }
+    };
+
+    Ok((input.take_from(end), output))
+  }
+}
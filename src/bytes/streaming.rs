@@ -4,7 +4,13 @@ use core::marker::PhantomData;
 
 use crate::error::ParseError;
 use crate::internal::{IResult, Parser};
-use crate::traits::{Compare, FindSubstring, FindToken, ToUsize};
+#[cfg(feature = "alloc")]
+use crate::lib::std::borrow::Cow;
+#[cfg(feature = "alloc")]
+use crate::traits::AsCow;
+use crate::traits::{
+  Compare, FindSubstring, FindSubstringSet, FindToken, TagLength, ToArray, ToUsize,
+};
 use crate::Emit;
 use crate::Input;
 use crate::OutputM;
@@ -31,7 +37,7 @@ use crate::Streaming;
 pub fn tag<T, I, Error: ParseError<I>>(tag: T) -> impl Fn(I) -> IResult<I, I, Error>
 where
   I: Input + Compare<T>,
-  T: Input + Clone,
+  T: TagLength + Clone,
 {
   move |i: I| {
     let mut parser = super::Tag {
@@ -77,6 +83,82 @@ where
   }
 }
 
+/// Recognizes a case insensitive keyword, returning the matched input and its normalized form.
+///
+/// The output contains the part of the input that matched, with its original case, and the
+/// keyword as it was passed to this function.
+/// # Example
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Needed, IResult};
+/// use nom::bytes::streaming::keyword_no_case;
+///
+/// fn select(s: &str) -> IResult<&str, (&str, &str)> {
+///   keyword_no_case("select")(s)
+/// }
+///
+/// assert_eq!(select("SELECT *"), Ok((" *", ("SELECT", "select"))));
+/// assert_eq!(select("sel"), Err(Err::Incomplete(Needed::new(3))));
+/// ```
+pub fn keyword_no_case<T, I, Error: ParseError<I>>(
+  keyword: T,
+) -> impl FnMut(I) -> IResult<I, (I, T), Error>
+where
+  I: Input + Compare<T>,
+  T: Input + Clone,
+{
+  let mut parser = super::keyword_no_case(keyword);
+
+  move |i: I| parser.process::<OutputM<Emit, Emit, Streaming>>(i)
+}
+
+/// Recognizes a byte order mark, returning the encoding it indicates.
+///
+/// *Streaming version*: returns `Incomplete` if the input could be the start of a byte order mark.
+/// # Example
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Needed, IResult};
+/// use nom::bytes::Encoding;
+/// use nom::bytes::streaming::bom;
+///
+/// fn parser(s: &[u8]) -> IResult<&[u8], Encoding> {
+///   bom(s)
+/// }
+///
+/// assert_eq!(parser(b"\xFE\xFFabc"), Ok((&b"abc"[..], Encoding::Utf16Be)));
+/// // it could be the start of the UTF-32 mark
+/// assert_eq!(parser(b"\xFF\xFE"), Err(Err::Incomplete(Needed::new(2))));
+/// assert_eq!(parser(b"abc"), Err(Err::Error(Error::new(&b"abc"[..], ErrorKind::Tag))));
+/// ```
+pub fn bom<I, Error: ParseError<I>>(input: I) -> IResult<I, super::Encoding, Error>
+where
+  I: Input + Compare<&'static [u8]>,
+{
+  super::bom().process::<OutputM<Emit, Emit, Streaming>>(input)
+}
+
+/// Skips the UTF-8 byte order mark if the input starts with it.
+///
+/// *Streaming version*: returns `Incomplete` if the input could be the start of the mark.
+/// # Example
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Needed, IResult};
+/// use nom::bytes::streaming::strip_bom;
+///
+/// fn parser(s: &str) -> IResult<&str, ()> {
+///   strip_bom(s)
+/// }
+///
+/// assert_eq!(parser("\u{feff}abc"), Ok(("abc", ())));
+/// assert_eq!(parser("abc"), Ok(("abc", ())));
+/// assert_eq!(parser(""), Err(Err::Incomplete(Needed::new(3))));
+/// ```
+pub fn strip_bom<I, Error: ParseError<I>>(input: I) -> IResult<I, (), Error>
+where
+  I: Input + Compare<&'static [u8]>,
+{
+  super::strip_bom().process::<OutputM<Emit, Emit, Streaming>>(input)
+}
+
 /// Parse till certain characters are met.
 ///
 /// The parser will return the longest slice till one of the characters of the combinator's argument are met.
@@ -346,6 +428,142 @@ where
   move |i: I| parser.process::<OutputM<Emit, Emit, Streaming>>(i)
 }
 
+/// Returns the first `N` bytes of the input as an array.
+///
+/// # Streaming Specific
+/// *Streaming version* if the input is shorter than `N`, `take_array` will return a
+/// `Err::Incomplete(Needed::new(M))` where M is the number of additional bytes the parser
+/// would need to succeed.
+/// # Example
+/// ```rust
+/// # use nom::{Err, error::ErrorKind, Needed, IResult};
+/// use nom::bytes::streaming::take_array;
+///
+/// fn magic(s: &[u8]) -> IResult<&[u8], &[u8; 4]> {
+///   take_array::<4, _, _>()(s)
+/// }
+///
+/// assert_eq!(magic(b"\x7FELF\x02"), Ok((&b"\x02"[..], b"\x7FELF")));
+/// assert_eq!(magic(b"\x7FE"), Err(Err::Incomplete(Needed::new(2))));
+/// ```
+pub fn take_array<const N: usize, I, Error: ParseError<I>>(
+) -> impl FnMut(I) -> IResult<I, <I as ToArray<N>>::Array, Error>
+where
+  I: ToArray<N>,
+{
+  let mut parser = super::take_array();
+
+  move |i: I| parser.process::<OutputM<Emit, Emit, Streaming>>(i)
+}
+
+/// Returns the first `N` bytes of the input as an array, if they all match the predicate.
+///
+/// It will return an `Err::Error((_, ErrorKind::TakeWhileMN))` if one of them does not
+/// match.
+///
+/// # Streaming Specific
+/// *Streaming version* if the input is shorter than `N` and all its bytes match,
+/// `take_while_array` will return a `Err::Incomplete(Needed::new(M))` where M is the number
+/// of additional bytes the parser would need to succeed.
+/// # Example
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Needed, IResult};
+/// use nom::bytes::streaming::take_while_array;
+/// use nom::AsChar;
+///
+/// fn uuid_group(s: &[u8]) -> IResult<&[u8], &[u8; 4]> {
+///   take_while_array::<4, _, _, _>(AsChar::is_hex_digit)(s)
+/// }
+///
+/// assert_eq!(uuid_group(b"a0b1-c2d3"), Ok((&b"-c2d3"[..], b"a0b1")));
+/// assert_eq!(uuid_group(b"a0b"), Err(Err::Incomplete(Needed::new(1))));
+/// ```
+pub fn take_while_array<const N: usize, F, I, Error: ParseError<I>>(
+  predicate: F,
+) -> impl FnMut(I) -> IResult<I, <I as ToArray<N>>::Array, Error>
+where
+  I: ToArray<N>,
+  F: Fn(u8) -> bool,
+{
+  let mut parser = super::take_while_array(predicate);
+
+  move |i: I| parser.process::<OutputM<Emit, Emit, Streaming>>(i)
+}
+
+/// Returns a fixed width text field of `count` bytes, without its trailing padding.
+///
+/// It will return `Err(Err::Error((_, ErrorKind::Utf8)))` if the field is not valid UTF-8.
+///
+/// # Streaming Specific
+/// *Streaming version* if the input is shorter than `count`, `padded_fixed_str` will return
+/// a `Err::Incomplete(Needed::new(M))` where M is the number of additional bytes the parser
+/// would need to succeed.
+/// # Example
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Needed, IResult};
+/// use nom::bytes::streaming::padded_fixed_str;
+///
+/// fn name(s: &[u8]) -> IResult<&[u8], &str> {
+///   padded_fixed_str(8, b' ')(s)
+/// }
+///
+/// assert_eq!(name(b"README  .md"), Ok((&b".md"[..], "README")));
+/// assert_eq!(name(b"README"), Err(Err::Incomplete(Needed::new(2))));
+/// ```
+pub fn padded_fixed_str<'a, Error: ParseError<&'a [u8]>>(
+  count: usize,
+  pad: u8,
+) -> impl FnMut(&'a [u8]) -> IResult<&'a [u8], &'a str, Error> {
+  let mut parser = super::padded_fixed_str(count, pad);
+
+  move |i: &'a [u8]| parser.process::<OutputM<Emit, Emit, Streaming>>(i)
+}
+
+/// Parses a block of bytes of the length given by the first parser, followed by a checksum
+/// verified by `check` before applying the body parser on the block.
+///
+/// It will return `Err(Err::Error((_, ErrorKind::Checksum)))` if the checksum does not match.
+///
+/// # Streaming Specific
+/// *Streaming version* will return a `Err::Incomplete(Needed::new(N))` if the block or the
+/// checksum are not complete.
+/// # Example
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Needed, IResult};
+/// use nom::bytes::streaming::{take, verified_block};
+/// use nom::number::streaming::u8;
+///
+/// fn chunk(s: &[u8]) -> IResult<&[u8], &[u8]> {
+///   let sum = |block: &&[u8], checksum: &u8| {
+///     block.iter().fold(0u8, |sum, b| sum.wrapping_add(*b)) == *checksum
+///   };
+///   verified_block(u8, take(2u8), u8, sum)(s)
+/// }
+///
+/// assert_eq!(chunk(b"\x02ab\xc3;"), Ok((&b";"[..], &b"ab"[..])));
+/// assert_eq!(chunk(b"\x02ab\x00"), Err(Err::Error(Error::new(&b"ab"[..], ErrorKind::Checksum))));
+/// assert_eq!(chunk(b"\x02a"), Err(Err::Incomplete(Needed::new(1))));
+/// ```
+pub fn verified_block<I, E, L, B, C, F>(
+  length: L,
+  body: B,
+  checksum: C,
+  check: F,
+) -> impl FnMut(I) -> IResult<I, <B as Parser<I>>::Output, E>
+where
+  I: Input,
+  E: ParseError<I>,
+  L: Parser<I, Error = E>,
+  <L as Parser<I>>::Output: ToUsize,
+  B: Parser<I, Error = E>,
+  C: Parser<I, Error = E>,
+  F: FnMut(&I, &<C as Parser<I>>::Output) -> bool,
+{
+  let mut parser = super::verified_block(length, body, checksum, check);
+
+  move |i: I| parser.process::<OutputM<Emit, Emit, Streaming>>(i)
+}
+
 /// Returns the input slice up to the first occurrence of the pattern.
 ///
 /// It doesn't consume the pattern.
@@ -409,6 +627,70 @@ where
   move |i: I| parser.process::<OutputM<Emit, Emit, Streaming>>(i)
 }
 
+/// Returns the input slice up to the first occurrence of any pattern from a set.
+///
+/// It doesn't consume the pattern. The set can be an array or a slice of patterns, or any
+/// other type implementing [FindSubstringSet].
+///
+/// # Streaming Specific
+/// *Streaming version* will return a `Err::Incomplete(Needed::Unknown)` if the input doesn't
+/// contain any of the patterns.
+/// # Example
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Needed, IResult};
+/// use nom::bytes::streaming::take_until_any;
+///
+/// fn until_end(s: &str) -> IResult<&str, &str> {
+///   take_until_any(["eof", "end", ";"])(s)
+/// }
+///
+/// assert_eq!(until_end("hello;end"), Ok((";end", "hello")));
+/// assert_eq!(until_end("hello, worldeof"), Ok(("eof", "hello, world")));
+/// assert_eq!(until_end("hello, world"), Err(Err::Incomplete(Needed::Unknown)));
+/// ```
+pub fn take_until_any<T, I, Error: ParseError<I>>(set: T) -> impl FnMut(I) -> IResult<I, I, Error>
+where
+  I: Input + FindSubstringSet<T>,
+  T: Clone,
+{
+  let mut parser = super::take_until_any(set);
+
+  move |i: I| parser.process::<OutputM<Emit, Emit, Streaming>>(i)
+}
+
+/// Returns the input slice up to the first position where the embedded parser succeeds.
+///
+/// The parser's match is not consumed.
+///
+/// # Streaming Specific
+/// *Streaming version* will return a `Err::Incomplete(Needed::Unknown)` if the parser does
+/// not succeed on the available data, or the parser's `Err::Incomplete` if it needs more data
+/// at one of the positions.
+/// # Example
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Needed, IResult};
+/// use nom::bytes::streaming::take_until_parser;
+/// use nom::bytes::streaming::tag;
+///
+/// fn skip_to_header(s: &str) -> IResult<&str, &str> {
+///   take_until_parser(tag("HDR"))(s)
+/// }
+///
+/// assert_eq!(skip_to_header("garbageHDR"), Ok(("HDR", "garbage")));
+/// assert_eq!(skip_to_header("garbageHD"), Err(Err::Incomplete(Needed::new(1))));
+/// ```
+pub fn take_until_parser<I, Error: ParseError<I>, F>(
+  parser: F,
+) -> impl FnMut(I) -> IResult<I, I, Error>
+where
+  I: Input,
+  F: Parser<I, Error = Error>,
+{
+  let mut parser = super::take_until_parser(parser);
+
+  move |i: I| parser.process::<OutputM<Emit, Emit, Streaming>>(i)
+}
+
 /// Matches a byte string with escaped characters.
 ///
 /// * The first argument matches the normal characters (it must not accept the control character)
@@ -497,3 +779,54 @@ where
 
   move |i: I| parser.process::<OutputM<Emit, Emit, Streaming>>(i)
 }
+
+/// Matches a string with escaped characters, borrowing the input if there is no
+/// escape sequence.
+///
+/// See [escaped_transform_cow](super::escaped_transform_cow).
+///
+/// ```
+/// # use nom::{Err, error::ErrorKind, Needed, IResult};
+/// use nom::bytes::streaming::{escaped_transform_cow, tag};
+/// use nom::character::streaming::alpha1;
+/// use nom::branch::alt;
+/// use nom::combinator::value;
+/// use std::borrow::Cow;
+///
+/// fn parser(input: &str) -> IResult<&str, Cow<'_, str>> {
+///   escaped_transform_cow(
+///     alpha1,
+///     '\\',
+///     alt((
+///       value("\\", tag("\\")),
+///       value("\n", tag("n")),
+///     ))
+///   )(input)
+/// }
+///
+/// assert_eq!(parser("abcd\""), Ok(("\"", Cow::Borrowed("abcd"))));
+/// assert_eq!(parser("ab\\ncd\""), Ok(("\"", Cow::Owned(String::from("ab\ncd")))));
+/// assert_eq!(parser("abcd"), Err(Err::Incomplete(Needed::new(1))));
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "alloc")))]
+pub fn escaped_transform_cow<'a, I, Error, F, G>(
+  normal: F,
+  control_char: char,
+  transform: G,
+) -> impl FnMut(I) -> IResult<I, Cow<'a, <I as AsCow<'a>>::Target>, Error>
+where
+  I: Clone + crate::traits::Offset + Input + AsCow<'a>,
+  <G as Parser<I>>::Output: crate::traits::ExtendInto<
+    Item = <I as crate::traits::ExtendInto>::Item,
+    Extender = <I as crate::traits::ExtendInto>::Extender,
+  >,
+  <I as Input>::Item: crate::traits::AsChar,
+  F: Parser<I, Error = Error>,
+  G: Parser<I, Error = Error>,
+  Error: ParseError<I>,
+{
+  let mut parser = super::escaped_transform_cow(normal, control_char, transform);
+
+  move |i: I| parser.process::<OutputM<Emit, Emit, Streaming>>(i)
+}
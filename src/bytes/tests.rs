@@ -278,6 +278,64 @@ fn escape_transform_str() {
   assert_eq!(esc3("a␛0bc␛n"), Ok(("", String::from("a\0bc\n"))));
 }
 
+#[cfg(feature = "alloc")]
+#[test]
+fn escape_transform_cow() {
+  use crate::bytes::complete::escaped_transform_cow;
+  use crate::character::complete::alpha1;
+  use crate::lib::std::borrow::Cow;
+
+  fn esc(i: &[u8]) -> IResult<&[u8], Cow<'_, [u8]>> {
+    escaped_transform_cow(
+      alpha1,
+      '\\',
+      alt((value(&b"\\"[..], tag("\\")), value(&b"\n"[..], tag("n")))),
+    )(i)
+  }
+
+  assert_eq!(
+    esc(&b"abcd;"[..]),
+    Ok((&b";"[..], Cow::Borrowed(&b"abcd"[..])))
+  );
+  assert!(matches!(esc(&b"abcd"[..]), Ok((_, Cow::Borrowed(_)))));
+  assert_eq!(
+    esc(&b"ab\\ncd\\\\;"[..]),
+    Ok((&b";"[..], Cow::Owned(b"ab\ncd\\".to_vec())))
+  );
+  assert_eq!(
+    esc(&b"\\nab;"[..]),
+    Ok((&b";"[..], Cow::Owned(b"\nab".to_vec())))
+  );
+  assert_eq!(
+    esc(&b"AB\\"[..]),
+    Err(Err::Error(error_position!(
+      &b"\\"[..],
+      ErrorKind::EscapedTransform
+    )))
+  );
+  assert_eq!(
+    esc(&b";"[..]),
+    Err(Err::Error(error_position!(
+      &b";"[..],
+      ErrorKind::EscapedTransform
+    )))
+  );
+
+  fn esc_str(i: &str) -> IResult<&str, Cow<'_, str>> {
+    crate::bytes::streaming::escaped_transform_cow(
+      alpha,
+      '&',
+      alt((value("è", tag("egrave;")), value("à", tag("agrave;")))),
+    )(i)
+  }
+  assert_eq!(esc_str("abc;"), Ok((";", Cow::Borrowed("abc"))));
+  assert_eq!(
+    esc_str("ab&egrave;D&agrave;EF;"),
+    Ok((";", Cow::Owned(String::from("abèDàEF"))))
+  );
+  assert_eq!(esc_str("ab&egrave;"), Err(Err::Incomplete(Needed::Unknown)));
+}
+
 #[test]
 fn take_until_incomplete() {
   use crate::bytes::streaming::take_until;
@@ -298,6 +356,109 @@ fn take_until_incomplete_s() {
   assert_eq!(ys("123en"), Err(Err::Incomplete(Needed::Unknown)));
 }
 
+#[test]
+fn take_until_any_test() {
+  use crate::bytes::complete::take_until_any as complete_take_until_any;
+  use crate::bytes::streaming::take_until_any;
+
+  fn y(i: &[u8]) -> IResult<&[u8], &[u8]> {
+    take_until_any([&b"-->"[..], &b"\n"[..]])(i)
+  }
+  assert_eq!(y(&b"ab\ncd-->"[..]), Ok((&b"\ncd-->"[..], &b"ab"[..])));
+  assert_eq!(y(&b"ab-->\n"[..]), Ok((&b"-->\n"[..], &b"ab"[..])));
+  assert_eq!(y(&b"-->"[..]), Ok((&b"-->"[..], &b""[..])));
+  assert_eq!(y(&b"ab--"[..]), Err(Err::Incomplete(Needed::Unknown)));
+
+  let set: &[&str] = &["end", "stop"];
+  fn z<'a>(i: &'a str, set: &[&str]) -> IResult<&'a str, &'a str> {
+    complete_take_until_any(set)(i)
+  }
+  assert_eq!(z("abc stop end", set), Ok(("stop end", "abc ")));
+  assert_eq!(
+    z("abc", set),
+    Err(Err::Error(error_position!("abc", ErrorKind::TakeUntil)))
+  );
+  assert_eq!(
+    z("abc", &[]),
+    Err(Err::Error(error_position!("abc", ErrorKind::TakeUntil)))
+  );
+}
+
+#[test]
+fn take_until_parser_test() {
+  use crate::bytes::complete::take_until_parser as complete_take_until_parser;
+  use crate::bytes::streaming::take_until_parser;
+  use crate::combinator::{cut, eof, verify};
+  use crate::number::streaming::be_u8;
+  use crate::sequence::preceded;
+
+  // a header is a 0xff byte followed by a length lower than 4
+  fn skip(i: &[u8]) -> IResult<&[u8], &[u8]> {
+    take_until_parser(preceded(
+      crate::bytes::streaming::tag(&[0xff][..]),
+      verify(be_u8, |len| *len < 4),
+    ))(i)
+  }
+  assert_eq!(
+    skip(&[1, 0xff, 5, 0xff, 2, 3][..]),
+    Ok((&[0xff, 2, 3][..], &[1, 0xff, 5][..]))
+  );
+  assert_eq!(
+    skip(&[1, 2, 0xff][..]),
+    Err(Err::Incomplete(Needed::new(1)))
+  );
+  assert_eq!(skip(&[1, 2][..]), Err(Err::Incomplete(Needed::new(1))));
+
+  fn skip_complete(i: &str) -> IResult<&str, &str> {
+    complete_take_until_parser(crate::bytes::complete::tag("é"))(i)
+  }
+  assert_eq!(skip_complete("aébé"), Ok(("ébé", "a")));
+  assert_eq!(
+    skip_complete("ab"),
+    Err(Err::Error(error_position!("ab", ErrorKind::TakeUntil)))
+  );
+
+  // the parser can match at the end of the input
+  assert_eq!(
+    complete_take_until_parser::<_, (_, ErrorKind), _>(eof)("abc"),
+    Ok(("", "abc"))
+  );
+
+  // failures are not skipped
+  assert_eq!(
+    complete_take_until_parser(preceded(
+      crate::bytes::complete::tag::<_, _, (_, ErrorKind)>("#"),
+      cut(crate::bytes::complete::tag("1"))
+    ))("ab#2#1"),
+    Err(Err::Failure(error_position!("2#1", ErrorKind::Tag)))
+  );
+}
+
+#[test]
+#[cfg(feature = "aho-corasick")]
+fn take_until_any_aho_corasick() {
+  use crate::bytes::complete::take_until_any;
+  use aho_corasick::{AhoCorasick, MatchKind};
+
+  let set = AhoCorasick::builder()
+    .match_kind(MatchKind::LeftmostFirst)
+    .build(["abcd", "c"])
+    .unwrap();
+
+  assert_eq!(
+    take_until_any::<_, _, (_, ErrorKind)>(&set)("xxabcd"),
+    Ok(("abcd", "xx"))
+  );
+  assert_eq!(
+    take_until_any::<_, _, (_, ErrorKind)>(&set)(&b"xxcd"[..]),
+    Ok((&b"cd"[..], &b"xx"[..]))
+  );
+  assert_eq!(
+    take_until_any::<_, _, (_, ErrorKind)>(&set)("xx"),
+    Err(Err::Error(("xx", ErrorKind::TakeUntil)))
+  );
+}
+
 #[test]
 fn recognize() {
   use crate::bytes::streaming::{tag, take};
@@ -630,6 +791,75 @@ fn case_insensitive() {
   );
 }
 
+#[test]
+fn keyword_case_insensitive() {
+  use crate::bytes::complete::keyword_no_case as complete_keyword_no_case;
+  use crate::bytes::streaming::keyword_no_case;
+
+  fn test(i: &[u8]) -> IResult<&[u8], (&[u8], &str)> {
+    keyword_no_case("from")(i)
+  }
+  assert_eq!(
+    test(&b"FROM t"[..]),
+    Ok((&b" t"[..], (&b"FROM"[..], "from")))
+  );
+  assert_eq!(test(&b"Fr"[..]), Err(Err::Incomplete(Needed::new(2))));
+  assert_eq!(
+    test(&b"into"[..]),
+    Err(Err::Error(error_position!(&b"into"[..], ErrorKind::Tag)))
+  );
+
+  fn test2(i: &str) -> IResult<&str, (&str, &str)> {
+    complete_keyword_no_case("Where")(i)
+  }
+  assert_eq!(test2("wHeRe x"), Ok((" x", ("wHeRe", "Where"))));
+  assert_eq!(
+    test2("whe"),
+    Err(Err::Error(error_position!("whe", ErrorKind::Tag)))
+  );
+}
+
+#[test]
+#[cfg(feature = "unicode")]
+fn case_insensitive_unicode() {
+  use crate::bytes::complete::tag_no_case as complete_tag_no_case;
+  use crate::bytes::streaming::tag_no_case;
+
+  fn test(i: &str) -> IResult<&str, &str> {
+    tag_no_case("stra\u{DF}e")(i)
+  }
+  assert_eq!(test("STRASSE 1"), Ok((" 1", "STRASSE")));
+  assert_eq!(test("Stra\u{DF}e 1"), Ok((" 1", "Stra\u{DF}e")));
+  assert_eq!(test("STRA\u{1E9E}E 1"), Ok((" 1", "STRA\u{1E9E}E")));
+  assert_eq!(test("STRAS"), Err(Err::Incomplete(Needed::new(2))));
+  assert_eq!(
+    test("STRASE"),
+    Err(Err::Error(error_position!("STRASE", ErrorKind::Tag)))
+  );
+
+  // the tag must not end in the middle of the folding of an input character
+  fn test2(i: &str) -> IResult<&str, &str> {
+    complete_tag_no_case("stras")(i)
+  }
+  assert_eq!(
+    test2("stra\u{DF}e"),
+    Err(Err::Error(error_position!("stra\u{DF}e", ErrorKind::Tag)))
+  );
+  assert_eq!(test2("STRASSE"), Ok(("SE", "STRAS")));
+
+  fn test3(i: &str) -> IResult<&str, &str> {
+    complete_tag_no_case("\u{3A3}\u{3BF}\u{3C6}\u{3CC}\u{3C2}")(i)
+  }
+  assert_eq!(
+    test3("\u{3C3}\u{3BF}\u{3C6}\u{3CC}\u{3C3}!"),
+    Ok(("!", "\u{3C3}\u{3BF}\u{3C6}\u{3CC}\u{3C3}"))
+  );
+  assert_eq!(
+    complete_tag_no_case::<_, _, (_, ErrorKind)>("file")("\u{FB01}le"),
+    Ok(("", "\u{FB01}le"))
+  );
+}
+
 #[test]
 fn tag_fixed_size_array() {
   use crate::bytes::streaming::tag;
@@ -644,3 +874,362 @@ fn tag_fixed_size_array() {
   assert_eq!(test(&input), Ok((&b"\x00"[..], &b"\x42"[..])));
   assert_eq!(test2(&input), Ok((&b"\x00"[..], &b"\x42"[..])));
 }
+
+#[test]
+fn bom_test() {
+  use crate::bytes::{complete, streaming, Encoding};
+
+  type Res<'a, O> = IResult<&'a [u8], O, (&'a [u8], ErrorKind)>;
+
+  for encoding in [
+    Encoding::Utf8,
+    Encoding::Utf16Le,
+    Encoding::Utf16Be,
+    Encoding::Utf32Le,
+    Encoding::Utf32Be,
+  ] {
+    let mark = encoding.bom();
+    let mut buffer = [1, 2, 3, 4, 0, 0, 0, 0];
+    buffer.copy_within(0..4, mark.len());
+    buffer[..mark.len()].copy_from_slice(mark);
+    let input = &buffer[..mark.len() + 4];
+    let res: Res<'_, _> = complete::bom(input);
+    assert_eq!(res, Ok((&b"\x01\x02\x03\x04"[..], encoding)));
+    let res: Res<'_, _> = streaming::bom(input);
+    assert_eq!(res, Ok((&b"\x01\x02\x03\x04"[..], encoding)));
+  }
+
+  let res: Res<'_, _> = complete::bom(&b"\xFF\xFE\x00a"[..]);
+  assert_eq!(res, Ok((&b"\x00a"[..], Encoding::Utf16Le)));
+  let res: Res<'_, _> = streaming::bom(&b"\xFF\xFE\x00"[..]);
+  assert_eq!(res, Err(Err::Incomplete(Needed::new(1))));
+  let res: Res<'_, _> = streaming::bom(&b"\xEF\xBB"[..]);
+  assert_eq!(res, Err(Err::Incomplete(Needed::new(1))));
+  let res: Res<'_, _> = complete::bom(&b"\xEF\xBB"[..]);
+  assert_eq!(res, Err(Err::Error((&b"\xEF\xBB"[..], ErrorKind::Tag))));
+  let res: Res<'_, _> = complete::bom(&b""[..]);
+  assert_eq!(res, Err(Err::Error((&b""[..], ErrorKind::Tag))));
+
+  let res: Res<'_, _> = complete::strip_bom(&b"\xEF\xBB\xBFa"[..]);
+  assert_eq!(res, Ok((&b"a"[..], ())));
+  let res: Res<'_, _> = complete::strip_bom(&b"\xEF\xBB"[..]);
+  assert_eq!(res, Ok((&b"\xEF\xBB"[..], ())));
+  let res: Res<'_, _> = streaming::strip_bom(&b"\xEF\xBB"[..]);
+  assert_eq!(res, Err(Err::Incomplete(Needed::new(1))));
+  let res: Res<'_, _> = streaming::strip_bom(&b"\xFF\xFE"[..]);
+  assert_eq!(res, Ok((&b"\xFF\xFE"[..], ())));
+}
+
+#[test]
+fn take_array_test() {
+  use crate::bytes::take_array;
+  use crate::Segmented;
+
+  let segments: [&[u8]; 3] = [b"\x7FE", b"L", b"F\x02"];
+  let input = Segmented::new(&segments);
+  let (rest, magic) = take_array::<4, _, (_, ErrorKind)>().parse(input).unwrap();
+  assert_eq!(magic, *b"\x7FELF");
+  assert_eq!(rest.as_contiguous(), Some(&b"\x02"[..]));
+
+  assert_eq!(
+    take_array::<6, _, (_, ErrorKind)>().parse(input),
+    Err(Err::Incomplete(Needed::new(1)))
+  );
+  assert_eq!(
+    take_array::<6, _, (_, ErrorKind)>().parse_complete(input),
+    Err(Err::Error((input, ErrorKind::Eof)))
+  );
+}
+
+#[test]
+fn take_while_array_test() {
+  use crate::bytes::take_while_array;
+  use crate::Segmented;
+
+  let valid: [&[u8]; 3] = [b"a0", b"b", b"1-c2"];
+  let invalid: [&[u8]; 2] = [b"a0", b"-b1"];
+  let short: [&[u8]; 2] = [b"a", b"0"];
+  let mut hex = take_while_array::<4, _, _, (_, ErrorKind)>(AsChar::is_hex_digit);
+
+  let (rest, group) = hex.parse(Segmented::new(&valid)).unwrap();
+  assert_eq!(group, *b"a0b1");
+  assert_eq!(rest.as_contiguous(), Some(&b"-c2"[..]));
+
+  let input = Segmented::new(&invalid);
+  assert_eq!(
+    hex.parse(input),
+    Err(Err::Error((input, ErrorKind::TakeWhileMN)))
+  );
+
+  let input = Segmented::new(&short);
+  assert_eq!(hex.parse(input), Err(Err::Incomplete(Needed::new(2))));
+  assert_eq!(
+    hex.parse_complete(input),
+    Err(Err::Error((input, ErrorKind::TakeWhileMN)))
+  );
+}
+
+#[test]
+fn padded_fixed_str_test() {
+  use crate::bytes::padded_fixed_str;
+
+  let mut field = padded_fixed_str::<(_, ErrorKind)>(6, b'\0');
+  assert_eq!(field.parse(&b"abc\0\0\0def"[..]), Ok((&b"def"[..], "abc")));
+  assert_eq!(field.parse(&b"abc\0d\0"[..]), Ok((&b""[..], "abc\0d")));
+  assert_eq!(field.parse(&b"\0\0\0\0\0\0"[..]), Ok((&b""[..], "")));
+  assert_eq!(
+    field.parse(&b"\xc3\xa9t\xc3\xa9!"[..]),
+    Ok((&b""[..], "été!"))
+  );
+  assert_eq!(
+    field.parse(&b"ab\xc3\0\0\0"[..]),
+    Err(Err::Error((&b"\xc3\0\0\0"[..], ErrorKind::Utf8)))
+  );
+  assert_eq!(
+    field.parse(&b"abc"[..]),
+    Err(Err::Incomplete(Needed::new(3)))
+  );
+  assert_eq!(
+    field.parse_complete(&b"abc"[..]),
+    Err(Err::Error((&b"abc"[..], ErrorKind::Eof)))
+  );
+}
+
+#[test]
+fn verified_block_test() {
+  use crate::bytes::{take, verified_block};
+  use crate::number::{be_u16, u8};
+
+  let xor = |block: &&[u8], checksum: &u8| block.iter().fold(0, |x, b| x ^ b) == *checksum;
+
+  // the body does not have to consume the whole block
+  let mut block = verified_block(be_u16(), take::<_, _, (_, ErrorKind)>(1u8), u8(), xor);
+  assert_eq!(
+    block.parse(&b"\x00\x03\x01\x02\x04\x07;"[..]),
+    Ok((&b";"[..], &b"\x01"[..]))
+  );
+  assert_eq!(
+    block.parse(&b"\x00\x03\x01\x02\x04\x06"[..]),
+    Err(Err::Error((&b"\x01\x02\x04"[..], ErrorKind::Checksum)))
+  );
+  assert_eq!(
+    block.parse(&b"\x00\x03\x01\x02\x04"[..]),
+    Err(Err::Incomplete(Needed::new(1)))
+  );
+  assert_eq!(
+    block.parse_complete(&b"\x00\x03\x01\x02\x04"[..]),
+    Err(Err::Error((&b""[..], ErrorKind::Eof)))
+  );
+  assert_eq!(
+    block.parse(&b"\x00\x00\x00"[..]),
+    Err(Err::Error((&b""[..], ErrorKind::Eof)))
+  );
+
+  // the body is applied on a complete block
+  let mut block = verified_block(
+    u8(),
+    crate::bytes::streaming::take::<_, _, (_, ErrorKind)>(3u8),
+    u8(),
+    xor,
+  );
+  assert_eq!(
+    block.parse(&b"\x02\x01\x02\x03"[..]),
+    Err(Err::Error((&b"\x01\x02"[..], ErrorKind::Complete)))
+  );
+}
+
+#[test]
+fn byte_set() {
+  use crate::bytes::{is_a, is_a_set, take_while_set, ByteSet};
+  use crate::Partial;
+
+  const TOKEN: ByteSet = ByteSet::from_range(b'a', b'z')
+    .with_range(b'0', b'9')
+    .with_bytes(b"-_");
+
+  assert_eq!(ByteSet::new(), ByteSet::default());
+  assert_eq!(ByteSet::from_bytes(b"ab"), ByteSet::from_range(b'a', b'b'));
+  assert_eq!(ByteSet::from_range(b'b', b'a'), ByteSet::new());
+  assert_eq!(
+    ByteSet::from_range(0, 127).union(ByteSet::from_range(128, 255)),
+    ByteSet::new().complement()
+  );
+  for b in 0..=255u8 {
+    let expected = b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-' || b == b'_';
+    assert_eq!(TOKEN.contains(b), expected);
+    assert_eq!(TOKEN.complement().contains(b), !expected);
+  }
+
+  // the prefix ends at any position of the chunks
+  for len in 0..40 {
+    for end in 0..=len {
+      let mut input = [b'a'; 40];
+      if end < len {
+        input[end] = b' ';
+      }
+      assert_eq!(TOKEN.prefix_len(&input[..len]), end);
+    }
+  }
+
+  let mut token = take_while_set::<_, (_, ErrorKind)>(TOKEN);
+  assert_eq!(
+    token.parse_complete(&b"max-age=3"[..]),
+    Ok((&b"=3"[..], &b"max-age"[..]))
+  );
+  assert_eq!(token.parse_complete(&b""[..]), Ok((&b""[..], &b""[..])));
+  assert_eq!(
+    token.parse(&b"abc"[..]),
+    Err(Err::Incomplete(Needed::new(1)))
+  );
+  assert_eq!(
+    take_while_set::<_, (_, ErrorKind)>(TOKEN)
+      .parse(Partial::new(&b"abc=1"[..]))
+      .map(|(i, o)| (i.into_inner(), o.into_inner())),
+    Ok((&b"=1"[..], &b"abc"[..]))
+  );
+
+  let mut token1 = is_a_set::<_, (_, ErrorKind)>(TOKEN);
+  assert_eq!(
+    token1.parse_complete(&b"abc def"[..]),
+    Ok((&b" def"[..], &b"abc"[..]))
+  );
+  assert_eq!(
+    token1.parse_complete(&b"=1"[..]),
+    Err(Err::Error((&b"=1"[..], ErrorKind::IsA)))
+  );
+  assert_eq!(
+    token1.parse_complete(&b""[..]),
+    Err(Err::Error((&b""[..], ErrorKind::IsA)))
+  );
+  assert_eq!(token1.parse(&b""[..]), Err(Err::Incomplete(Needed::new(1))));
+
+  // the sets are also tokens for the other parsers
+  assert_eq!(
+    is_a::<_, _, (_, ErrorKind)>(TOKEN).parse(&b"ab cd"[..]),
+    Ok((&b" cd"[..], &b"ab"[..]))
+  );
+}
+
+#[test]
+fn array_tags() {
+  use crate::bytes::{tag, Tag, Take};
+  use crate::traits::{Compare, CompareResult};
+
+  let input = &b"GIF89a"[..];
+  assert_eq!(input.compare(b"GIF8"), CompareResult::Ok);
+  assert_eq!(input.compare(*b"GIF8"), CompareResult::Ok);
+  assert_eq!(input.compare(b"GIF9"), CompareResult::Error);
+  assert_eq!(input.compare(b"GIF89a!"), CompareResult::Incomplete);
+  assert_eq!(input.compare(b"GIF99a!"), CompareResult::Error);
+  assert_eq!(input.compare(b""), CompareResult::Ok);
+  assert_eq!((&b""[..]).compare(b"a"), CompareResult::Incomplete);
+
+  assert_eq!(
+    tag::<_, _, (_, ErrorKind)>(b"GIF8").parse(input),
+    Ok((&b"9a"[..], &b"GIF8"[..]))
+  );
+  assert_eq!(
+    tag::<_, _, (_, ErrorKind)>(*b"GIF89a!").parse(input),
+    Err(Err::Incomplete(Needed::new(1)))
+  );
+  assert_eq!(
+    tag::<_, _, (_, ErrorKind)>(b"GIF89a!").parse_complete(input),
+    Err(Err::Error((input, ErrorKind::Tag)))
+  );
+
+  // the parsers can be built at compile time
+  const MAGIC: Tag<&[u8; 3], (&[u8], ErrorKind)> = Tag::new(b"GIF");
+  const VERSION: Take<(&[u8], ErrorKind)> = Take::new(3);
+  let mut header = (MAGIC, VERSION);
+  assert_eq!(
+    header.parse(input),
+    Ok((&b""[..], (&b"GIF"[..], &b"89a"[..])))
+  );
+  assert_eq!(
+    header.parse(&b"GIF8"[..]),
+    Err(Err::Incomplete(Needed::new(2)))
+  );
+}
+
+#[test]
+fn tag_with_comparators() {
+  use crate::bytes::tag_with;
+  use crate::traits::{AsciiCaseInsensitive, Comparator, Exact, Partial, UnicodeCaseFold};
+
+  fn parse<C: Comparator<&'static str, &'static str>>(
+    cmp: C,
+    tag: &'static str,
+    input: &'static str,
+  ) -> IResult<&'static str, &'static str> {
+    tag_with(cmp, tag).parse_complete(input)
+  }
+
+  assert_eq!(parse(Exact, "abc", "abcd"), Ok(("d", "abc")));
+  assert_eq!(
+    parse(Exact, "abc", "ABCd"),
+    Err(Err::Error(error_position!("ABCd", ErrorKind::Tag)))
+  );
+  assert_eq!(parse(AsciiCaseInsensitive, "abc", "ABCd"), Ok(("d", "ABC")));
+  assert_eq!(
+    parse(AsciiCaseInsensitive, "é", "É"),
+    Err(Err::Error(error_position!("É", ErrorKind::Tag)))
+  );
+  assert_eq!(parse(UnicodeCaseFold, "é", "É;"), Ok((";", "É")));
+
+  // byte inputs and array tags
+  let input = Partial::new(&b"LOG"[..]);
+  assert_eq!(
+    tag_with::<_, _, _, (_, ErrorKind)>(AsciiCaseInsensitive, b"login").parse(input),
+    Err(Err::Incomplete(Needed::new(2)))
+  );
+  assert_eq!(
+    tag_with::<_, _, _, (_, ErrorKind)>(AsciiCaseInsensitive, b"log").parse(input),
+    Ok((Partial::new(&b""[..]), Partial::new(&b"LOG"[..])))
+  );
+}
+
+#[cfg(feature = "normalization")]
+#[test]
+fn tag_with_normalization() {
+  use crate::bytes::tag_with;
+  use crate::traits::{Nfc, Nfkc, Partial};
+
+  fn nfc(tag: &'static str, input: &'static str) -> IResult<&'static str, &'static str> {
+    tag_with(Nfc, tag).parse(input)
+  }
+
+  // composed and decomposed forms match each other
+  assert_eq!(nfc("caf\u{e9}", "cafe\u{301};"), Ok((";", "cafe\u{301}")));
+  assert_eq!(nfc("cafe\u{301}", "caf\u{e9};"), Ok((";", "caf\u{e9}")));
+  // combining marks are reordered
+  assert_eq!(
+    nfc("a\u{323}\u{301}", "a\u{301}\u{323}b"),
+    Ok(("b", "a\u{301}\u{323}"))
+  );
+  // the tag cannot end before the combining marks of a character
+  assert_eq!(
+    nfc("cafe", "cafe\u{301}"),
+    Err(Err::Error(error_position!("cafe\u{301}", ErrorKind::Tag)))
+  );
+  assert_eq!(
+    nfc("caf\u{e9}", "caf"),
+    Err(Err::Incomplete(Needed::new(2)))
+  );
+  assert_eq!(
+    nfc("caf\u{e9}", "cafe"),
+    Err(Err::Incomplete(Needed::new(1)))
+  );
+  assert_eq!(
+    nfc("\u{fb01}le", "file"),
+    Err(Err::Error(error_position!("file", ErrorKind::Tag)))
+  );
+
+  assert_eq!(
+    tag_with::<_, _, _, (_, ErrorKind)>(Nfkc, "file").parse_complete("\u{fb01}le;"),
+    Ok((";", "\u{fb01}le"))
+  );
+  assert_eq!(
+    tag_with::<_, _, _, (_, ErrorKind)>(Nfkc, "x2").parse(Partial::new("x\u{b2}")),
+    Ok((Partial::new(""), Partial::new("x\u{b2}")))
+  );
+}
@@ -7,6 +7,10 @@ use crate::combinator::opt;
 use crate::error::ErrorKind;
 use crate::error::ParseError;
 use crate::internal::{Err, IResult};
+#[cfg(feature = "alloc")]
+use crate::lib::std::vec::Vec;
+#[cfg(feature = "alloc")]
+use crate::multi::{many0, many1};
 use crate::traits::{AsChar, FindToken, Input};
 use crate::traits::{Compare, CompareResult};
 use crate::Complete;
@@ -39,6 +43,34 @@ where
   move |i: I| parser.process::<OutputM<Emit, Emit, Complete>>(i)
 }
 
+/// Recognizes one character, independently of the case.
+///
+/// The characters are compared by lowercasing them, or with their case folding if the
+/// `unicode` feature is activated.
+///
+/// *Complete version*: Will return an error if there's not enough input data.
+/// # Example
+///
+/// ```
+/// # use nom::{Err, error::{ErrorKind, Error}, IResult};
+/// # use nom::character::complete::char_no_case;
+/// fn parser(i: &str) -> IResult<&str, char> {
+///     char_no_case('é')(i)
+/// }
+/// assert_eq!(parser("éa"), Ok(("a", 'é')));
+/// assert_eq!(parser("Éa"), Ok(("a", 'É')));
+/// assert_eq!(parser("ea"), Err(Err::Error(Error::new("ea", ErrorKind::Char))));
+/// assert_eq!(parser(""), Err(Err::Error(Error::new("", ErrorKind::Char))));
+/// ```
+pub fn char_no_case<I, Error: ParseError<I>>(c: char) -> impl FnMut(I) -> IResult<I, char, Error>
+where
+  I: Input,
+  <I as Input>::Item: AsChar,
+{
+  let mut parser = super::char_no_case(c);
+  move |i: I| parser.process::<OutputM<Emit, Emit, Complete>>(i)
+}
+
 /// Recognizes one character and checks that it satisfies a predicate
 ///
 /// *Complete version*: Will return an error if there's not enough input data.
@@ -221,6 +253,135 @@ where
   }
 }
 
+/// Recognizes a line, and returns its content without the '\n' or '\r\n' ending it.
+///
+/// The last line of the input does not need a line ending. Lines containing a '\r' not
+/// followed by '\n' are rejected like in [not_line_ending].
+///
+/// *Complete version*: Will return an error if the input is empty.
+/// # Example
+///
+/// ```
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult, Needed};
+/// # use nom::character::complete::line;
+/// fn parser(input: &str) -> IResult<&str, &str> {
+///     line(input)
+/// }
+///
+/// assert_eq!(parser("ab\r\nc"), Ok(("c", "ab")));
+/// assert_eq!(parser("\nc"), Ok(("c", "")));
+/// assert_eq!(parser("abc"), Ok(("", "abc")));
+/// assert_eq!(parser(""), Err(Err::Error(Error::new("", ErrorKind::Eof))));
+/// assert_eq!(parser("a\rbc"), Err(Err::Error(Error::new("a\rbc", ErrorKind::Tag))));
+/// ```
+pub fn line<T, E: ParseError<T>>(input: T) -> IResult<T, T, E>
+where
+  T: Input,
+  T: Compare<&'static str>,
+  <T as Input>::Item: AsChar,
+{
+  if input.input_len() == 0 {
+    return Err(Err::Error(E::from_error_kind(input, ErrorKind::Eof)));
+  }
+
+  let (rest, content) = not_line_ending(input)?;
+  if rest.input_len() == 0 {
+    Ok((rest, content))
+  } else {
+    let (rest, _) = line_ending(rest)?;
+    Ok((rest, content))
+  }
+}
+
+/// Recognizes a line with at least one character, and returns its content without the
+/// '\n' or '\r\n' ending it.
+///
+/// *Complete version*: Will return an error if the input is empty.
+/// # Example
+///
+/// ```
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult, Needed};
+/// # use nom::character::complete::non_empty_line;
+/// fn parser(input: &str) -> IResult<&str, &str> {
+///     non_empty_line(input)
+/// }
+///
+/// assert_eq!(parser("ab\r\nc"), Ok(("c", "ab")));
+/// assert_eq!(parser("abc"), Ok(("", "abc")));
+/// assert_eq!(parser("\nc"), Err(Err::Error(Error::new("\nc", ErrorKind::NonEmpty))));
+/// assert_eq!(parser(""), Err(Err::Error(Error::new("", ErrorKind::Eof))));
+/// ```
+pub fn non_empty_line<T, E: ParseError<T>>(input: T) -> IResult<T, T, E>
+where
+  T: Input,
+  T: Compare<&'static str>,
+  <T as Input>::Item: AsChar,
+{
+  let (rest, content) = line(input.clone())?;
+  if content.input_len() == 0 {
+    Err(Err::Error(E::from_error_kind(input, ErrorKind::NonEmpty)))
+  } else {
+    Ok((rest, content))
+  }
+}
+
+/// Recognizes zero or more lines with [line], and returns their content.
+///
+/// To go through the lines without gathering them, use
+/// [iterator][crate::combinator::iterator] with [line].
+///
+/// *Complete version*: The last line does not need a line ending.
+/// # Example
+///
+/// ```
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult, Needed};
+/// # use nom::character::complete::lines0;
+/// fn parser(input: &str) -> IResult<&str, Vec<&str>> {
+///     lines0(input)
+/// }
+///
+/// assert_eq!(parser("ab\r\n\ncd\n"), Ok(("", vec!["ab", "", "cd"])));
+/// assert_eq!(parser("ab\ncd"), Ok(("", vec!["ab", "cd"])));
+/// assert_eq!(parser(""), Ok(("", vec![])));
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "alloc")))]
+pub fn lines0<T, E: ParseError<T>>(input: T) -> IResult<T, Vec<T>, E>
+where
+  T: Input,
+  T: Compare<&'static str>,
+  <T as Input>::Item: AsChar,
+{
+  many0(line).parse(input)
+}
+
+/// Recognizes one or more lines with [line], and returns their content.
+///
+/// *Complete version*: Will return an error if the input is empty.
+/// # Example
+///
+/// ```
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult, Needed};
+/// # use nom::character::complete::lines1;
+/// fn parser(input: &str) -> IResult<&str, Vec<&str>> {
+///     lines1(input)
+/// }
+///
+/// assert_eq!(parser("ab\r\n\ncd\n"), Ok(("", vec!["ab", "", "cd"])));
+/// assert_eq!(parser("ab"), Ok(("", vec!["ab"])));
+/// assert_eq!(parser(""), Err(Err::Error(Error::new("", ErrorKind::Eof))));
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "alloc")))]
+pub fn lines1<T, E: ParseError<T>>(input: T) -> IResult<T, Vec<T>, E>
+where
+  T: Input,
+  T: Compare<&'static str>,
+  <T as Input>::Item: AsChar,
+{
+  many1(line).parse(input)
+}
+
 /// Matches a newline character '\n'.
 ///
 /// *Complete version*: Will return an error if there's not enough input data.
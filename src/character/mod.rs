@@ -6,8 +6,8 @@ use core::marker::PhantomData;
 
 use crate::error::ErrorKind;
 use crate::FindToken;
-use crate::IsStreaming;
 use crate::Mode;
+use crate::Strictness;
 use crate::{error::ParseError, AsChar, Err, IResult, Input, Needed, Parser};
 
 #[cfg(test)]
@@ -15,6 +15,9 @@ mod tests;
 
 pub mod complete;
 pub mod streaming;
+#[cfg(feature = "unicode")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "unicode")))]
+pub mod unicode;
 
 #[inline]
 #[doc(hidden)]
@@ -123,17 +126,90 @@ where
   ) -> crate::PResult<OM, I, Self::Output, Self::Error> {
     match (i).iter_elements().next().map(|t| {
       let b = t.as_char() == self.c;
-      (&self.c, b)
+      (t.len(), b)
     }) {
       None => {
-        if OM::Incomplete::is_streaming() {
+        if i.is_streaming::<OM>() {
           Err(Err::Incomplete(Needed::new(self.c.len() - i.input_len())))
         } else {
           Err(Err::Error(OM::Error::bind(|| Error::from_char(i, self.c))))
         }
       }
       Some((_, false)) => Err(Err::Error(OM::Error::bind(|| Error::from_char(i, self.c)))),
-      Some((c, true)) => Ok((i.take_from(c.len()), OM::Output::bind(|| c.as_char()))),
+      Some((len, true)) => Ok((i.take_from(len), OM::Output::bind(|| self.c))),
+    }
+  }
+}
+
+/// Recognizes one character, independently of the case.
+///
+/// The characters are compared by lowercasing them, or with their case folding if the
+/// `unicode` feature is activated. The output is the character from the input, with its
+/// original case.
+///
+/// # Example
+///
+/// ```
+/// # use nom::{Err, error::{ErrorKind, Error}, Needed, IResult};
+/// # use nom::character::streaming::char_no_case;
+/// fn parser(i: &str) -> IResult<&str, char> {
+///     char_no_case('a')(i)
+/// }
+/// assert_eq!(parser("abc"), Ok(("bc", 'a')));
+/// assert_eq!(parser("ABC"), Ok(("BC", 'A')));
+/// assert_eq!(parser("bc"), Err(Err::Error(Error::new("bc", ErrorKind::Char))));
+/// assert_eq!(parser(""), Err(Err::Incomplete(Needed::new(1))));
+/// ```
+pub fn char_no_case<I, Error: ParseError<I>>(
+  c: char,
+) -> impl Parser<I, Output = char, Error = Error>
+where
+  I: Input,
+  <I as Input>::Item: AsChar,
+{
+  CharNoCase { c, e: PhantomData }
+}
+
+/// Parser implementation for [char_no_case()]
+pub struct CharNoCase<E> {
+  c: char,
+  e: PhantomData<E>,
+}
+
+#[cfg(feature = "unicode")]
+fn eq_no_case(a: char, b: char) -> bool {
+  a == b || crate::traits::CaseFold::new(a).eq(crate::traits::CaseFold::new(b))
+}
+
+#[cfg(not(feature = "unicode"))]
+fn eq_no_case(a: char, b: char) -> bool {
+  a == b || a.to_lowercase().eq(b.to_lowercase())
+}
+
+impl<I, Error: ParseError<I>> Parser<I> for CharNoCase<Error>
+where
+  I: Input,
+  <I as Input>::Item: AsChar,
+{
+  type Output = char;
+  type Error = Error;
+
+  fn process<OM: crate::OutputMode>(
+    &mut self,
+    i: I,
+  ) -> crate::PResult<OM, I, Self::Output, Self::Error> {
+    match i.iter_elements().next() {
+      None => {
+        if i.is_streaming::<OM>() {
+          Err(Err::Incomplete(Needed::new(1)))
+        } else {
+          Err(Err::Error(OM::Error::bind(|| Error::from_char(i, self.c))))
+        }
+      }
+      Some(t) if eq_no_case(t.as_char(), self.c) => {
+        Ok((i.take_from(t.len()), OM::Output::bind(|| t.as_char())))
+      }
+      Some(_) => Err(Err::Error(OM::Error::bind(|| Error::from_char(i, self.c)))),
     }
   }
 }
@@ -190,17 +266,17 @@ where
     match (i).iter_elements().next().map(|t| {
       let c = t.as_char();
       let b = (self.predicate)(c);
-      (c, b)
+      (t.len(), c, b)
     }) {
       None => {
-        if OM::Incomplete::is_streaming() {
+        if i.is_streaming::<OM>() {
           Err(Err::Incomplete(Needed::Unknown))
         } else {
           Err(Err::Error(OM::Error::bind(|| (self.make_error)(i))))
         }
       }
-      Some((_, false)) => Err(Err::Error(OM::Error::bind(|| (self.make_error)(i)))),
-      Some((c, true)) => Ok((i.take_from(c.len()), OM::Output::bind(|| c.as_char()))),
+      Some((_, _, false)) => Err(Err::Error(OM::Error::bind(|| (self.make_error)(i)))),
+      Some((len, c, true)) => Ok((i.take_from(len), OM::Output::bind(|| c))),
     }
   }
 }
@@ -296,7 +372,7 @@ where
   ) -> crate::PResult<OM, I, Self::Output, Self::Error> {
     match (i).iter_elements().next() {
       None => {
-        if OM::Incomplete::is_streaming() {
+        if i.is_streaming::<OM>() {
           Err(Err::Incomplete(Needed::new(1)))
         } else {
           Err(Err::Error(OM::Error::bind(|| {
@@ -400,3 +476,314 @@ where
     })
   }
 }
+
+/// Integer types that can be parsed by [integer]
+pub trait Integer: Copy {
+  /// Whether the type can hold negative numbers
+  const SIGNED: bool;
+  /// The value before the first digit
+  const ZERO: Self;
+
+  /// Appends a decimal digit to the number, subtracting it if the number is negative.
+  /// Returns `None` on overflow
+  fn push_digit(self, digit: u32, negative: bool) -> Option<Self>;
+}
+
+macro_rules! integers {
+  ($($t:ty, $signed:expr)*) => {
+    $(
+      impl Integer for $t {
+        const SIGNED: bool = $signed;
+        const ZERO: Self = 0;
+
+        #[inline]
+        fn push_digit(self, digit: u32, negative: bool) -> Option<Self> {
+          let value = self.checked_mul(10)?;
+          if negative {
+            value.checked_sub(digit as $t)
+          } else {
+            value.checked_add(digit as $t)
+          }
+        }
+      }
+    )*
+  };
+}
+
+integers! {
+  i8, true i16, true i32, true i64, true i128, true isize, true
+  u8, false u16, false u32, false u64, false u128, false usize, false
+}
+
+/// Recognizes a decimal integer, with the accepted format depending on `strictness`
+///
+/// In strict mode, the number is a `-` for signed types, followed by digits without
+/// leading zeros. In lenient mode, it can also start with spaces or tabs, a `+` sign, and
+/// leading zeros. The spaces following the number are not consumed.
+///
+/// It returns an error with [ErrorKind::Digit] if there is no digit, if the number
+/// overflows, or if it has a leading zero in strict mode.
+///
+/// *Streaming version*: returns `Err::Incomplete` if the input ends before a character
+/// ending the number.
+/// # Example
+///
+/// ```
+/// # use nom::{Err, error::{Error, ErrorKind}, Needed, Parser, Strictness};
+/// use nom::character::integer;
+///
+/// let mut strict = integer::<i32, _, Error<_>>(Strictness::Strict);
+/// assert_eq!(strict.parse_complete("-120;"), Ok((";", -120)));
+/// assert_eq!(strict.parse_complete("+120"), Err(Err::Error(Error::new("+120", ErrorKind::Digit))));
+/// assert_eq!(strict.parse_complete("0120"), Err(Err::Error(Error::new("0120", ErrorKind::Digit))));
+/// assert_eq!(strict.parse("120"), Err(Err::Incomplete(Needed::new(1))));
+///
+/// let mut lenient = integer::<u8, _, Error<_>>(Strictness::Lenient);
+/// assert_eq!(lenient.parse_complete("\t+0120 ms"), Ok((" ms", 120)));
+/// assert_eq!(lenient.parse_complete("-1"), Err(Err::Error(Error::new("-1", ErrorKind::Digit))));
+/// assert_eq!(lenient.parse_complete("256"), Err(Err::Error(Error::new("256", ErrorKind::Digit))));
+/// ```
+pub fn integer<N, I, E: ParseError<I>>(
+  strictness: Strictness,
+) -> impl Parser<I, Output = N, Error = E>
+where
+  N: Integer,
+  I: Input,
+  <I as Input>::Item: AsChar,
+{
+  IntegerParser {
+    strictness,
+    n: PhantomData,
+    e: PhantomData,
+  }
+}
+
+/// Parser implementation for [integer]
+pub struct IntegerParser<N, E> {
+  strictness: Strictness,
+  n: PhantomData<N>,
+  e: PhantomData<E>,
+}
+
+impl<N, I, E: ParseError<I>> Parser<I> for IntegerParser<N, E>
+where
+  N: Integer,
+  I: Input,
+  <I as Input>::Item: AsChar,
+{
+  type Output = N;
+  type Error = E;
+
+  fn process<OM: crate::OutputMode>(
+    &mut self,
+    input: I,
+  ) -> crate::PResult<OM, I, Self::Output, Self::Error> {
+    let lenient = self.strictness == Strictness::Lenient;
+    let mut chars = input.iter_elements().map(|c| (c.as_char(), c.len()));
+    let mut next = chars.next();
+    let mut pos = 0;
+
+    if lenient {
+      while let Some((' ' | '\t', len)) = next {
+        pos += len;
+        next = chars.next();
+      }
+    }
+
+    let mut negative = false;
+    if let Some((sign @ ('-' | '+'), len)) = next {
+      if (sign == '-' && N::SIGNED) || (sign == '+' && lenient) {
+        negative = sign == '-';
+        pos += len;
+        next = chars.next();
+      }
+    }
+
+    let mut value = N::ZERO;
+    let mut digits = 0;
+    let mut leading_zero = false;
+    loop {
+      match next {
+        None if input.is_streaming::<OM>() => return Err(Err::Incomplete(Needed::new(1))),
+        Some((c, len)) if c.is_ascii_digit() => {
+          let digit = c as u32 - '0' as u32;
+          if digits == 0 {
+            leading_zero = digit == 0;
+          }
+          match value.push_digit(digit, negative) {
+            Some(v) => value = v,
+            None => {
+              return Err(Err::Error(OM::Error::bind(|| {
+                E::from_error_kind(input, ErrorKind::Digit)
+              })))
+            }
+          }
+          digits += 1;
+          pos += len;
+          next = chars.next();
+        }
+        _ => break,
+      }
+    }
+
+    if digits == 0 || (!lenient && leading_zero && digits > 1) {
+      return Err(Err::Error(OM::Error::bind(|| {
+        E::from_error_kind(input, ErrorKind::Digit)
+      })));
+    }
+
+    Ok((input.take_from(pos), OM::Output::bind(|| value)))
+  }
+}
+
+/// Error of [separated_digits]
+pub(crate) enum SeparatedDigitsError {
+  /// The input ended in the digits, on streaming data
+  Incomplete,
+  /// A separator is not between two digits, or `push` rejected a digit
+  Invalid,
+}
+
+/// Scans the digits in `radix` at the start of `input`, with `separator` allowed between
+/// two digits, and passes each digit to `push`, which returns `false` to reject it
+///
+/// Returns the length of the digits with their separators, and the number of digits.
+pub(crate) fn separated_digits<I, F>(
+  input: &I,
+  radix: u32,
+  separator: char,
+  streaming: bool,
+  mut push: F,
+) -> Result<(usize, usize), SeparatedDigitsError>
+where
+  I: Input,
+  <I as Input>::Item: AsChar,
+  F: FnMut(u32) -> bool,
+{
+  let mut pos = 0;
+  let mut digits = 0;
+  let mut after_separator = false;
+
+  for c in input.iter_elements() {
+    let (c, len) = (c.as_char(), c.len());
+    if let Some(digit) = c.to_digit(radix) {
+      if !push(digit) {
+        return Err(SeparatedDigitsError::Invalid);
+      }
+      digits += 1;
+      after_separator = false;
+    } else if c == separator && digits > 0 && !after_separator {
+      after_separator = true;
+    } else if after_separator || c == separator {
+      return Err(SeparatedDigitsError::Invalid);
+    } else {
+      return Ok((pos, digits));
+    }
+    pos += len;
+  }
+
+  if streaming {
+    Err(SeparatedDigitsError::Incomplete)
+  } else if after_separator {
+    Err(SeparatedDigitsError::Invalid)
+  } else {
+    Ok((pos, digits))
+  }
+}
+
+/// Recognizes a decimal integer with digit separators, like `1_000_000`
+///
+/// The number is a `-` for signed types, followed by digits. `separator` is allowed
+/// between two digits only: the number cannot start or end with it, or contain two
+/// separators in a row.
+///
+/// It returns an error with [ErrorKind::Digit] if there is no digit, if a separator is
+/// misplaced, or if the number overflows.
+///
+/// *Streaming version*: returns `Err::Incomplete` if the input ends before a character
+/// ending the number.
+/// # Example
+///
+/// ```
+/// # use nom::{Err, error::{Error, ErrorKind}, Needed, Parser};
+/// use nom::character::integer_sep;
+///
+/// let mut parser = integer_sep::<i64, _, Error<_>>('_');
+/// assert_eq!(parser.parse_complete("-1_000_000;"), Ok((";", -1_000_000)));
+/// assert_eq!(parser.parse_complete("1000"), Ok(("", 1000)));
+/// assert_eq!(parser.parse_complete("1__000"), Err(Err::Error(Error::new("1__000", ErrorKind::Digit))));
+/// assert_eq!(parser.parse_complete("1_"), Err(Err::Error(Error::new("1_", ErrorKind::Digit))));
+/// assert_eq!(parser.parse_complete("_1"), Err(Err::Error(Error::new("_1", ErrorKind::Digit))));
+/// assert_eq!(parser.parse("1_0"), Err(Err::Incomplete(Needed::new(1))));
+///
+/// let mut locale = integer_sep::<u32, _, Error<_>>('\'');
+/// assert_eq!(locale.parse_complete("1'234 CHF"), Ok((" CHF", 1234)));
+/// ```
+pub fn integer_sep<N, I, E: ParseError<I>>(separator: char) -> impl Parser<I, Output = N, Error = E>
+where
+  N: Integer,
+  I: Input,
+  <I as Input>::Item: AsChar,
+{
+  IntegerSep {
+    separator,
+    n: PhantomData,
+    e: PhantomData,
+  }
+}
+
+/// Parser implementation for [integer_sep]
+pub struct IntegerSep<N, E> {
+  separator: char,
+  n: PhantomData<N>,
+  e: PhantomData<E>,
+}
+
+impl<N, I, E: ParseError<I>> Parser<I> for IntegerSep<N, E>
+where
+  N: Integer,
+  I: Input,
+  <I as Input>::Item: AsChar,
+{
+  type Output = N;
+  type Error = E;
+
+  fn process<OM: crate::OutputMode>(
+    &mut self,
+    input: I,
+  ) -> crate::PResult<OM, I, Self::Output, Self::Error> {
+    let mut pos = 0;
+    let mut negative = false;
+    if let Some(c) = input.iter_elements().next() {
+      if c.as_char() == '-' && N::SIGNED {
+        negative = true;
+        pos = c.len();
+      }
+    }
+
+    let mut value = N::ZERO;
+    let res = separated_digits(
+      &input.take_from(pos),
+      10,
+      self.separator,
+      input.is_streaming::<OM>(),
+      |digit| match value.push_digit(digit, negative) {
+        Some(v) => {
+          value = v;
+          true
+        }
+        None => false,
+      },
+    );
+
+    match res {
+      Err(SeparatedDigitsError::Incomplete) => Err(Err::Incomplete(Needed::new(1))),
+      Ok((len, digits)) if digits > 0 => {
+        Ok((input.take_from(pos + len), OM::Output::bind(|| value)))
+      }
+      _ => Err(Err::Error(OM::Error::bind(|| {
+        E::from_error_kind(input, ErrorKind::Digit)
+      }))),
+    }
+  }
+}
@@ -38,6 +38,33 @@ where
   move |i: I| parser.process::<OutputM<Emit, Emit, Streaming>>(i)
 }
 
+/// Recognizes one character, independently of the case.
+///
+/// The characters are compared by lowercasing them, or with their case folding if the
+/// `unicode` feature is activated.
+///
+/// *Streaming version*: Will return `Err(nom::Err::Incomplete(_))` if there's not enough input data.
+/// # Example
+///
+/// ```
+/// # use nom::{Err, error::{ErrorKind, Error}, Needed, IResult};
+/// # use nom::character::streaming::char_no_case;
+/// fn parser(i: &str) -> IResult<&str, char> {
+///     char_no_case('é')(i)
+/// }
+/// assert_eq!(parser("Éa"), Ok(("a", 'É')));
+/// assert_eq!(parser("ea"), Err(Err::Error(Error::new("ea", ErrorKind::Char))));
+/// assert_eq!(parser(""), Err(Err::Incomplete(Needed::new(1))));
+/// ```
+pub fn char_no_case<I, Error: ParseError<I>>(c: char) -> impl FnMut(I) -> IResult<I, char, Error>
+where
+  I: Input,
+  <I as Input>::Item: AsChar,
+{
+  let mut parser = super::char_no_case(c);
+  move |i: I| parser.process::<OutputM<Emit, Emit, Streaming>>(i)
+}
+
 /// Recognizes one character and checks that it satisfies a predicate
 ///
 /// *Streaming version*: Will return `Err(nom::Err::Incomplete(_))` if there's not enough input data.
@@ -213,6 +240,59 @@ where
   }
 }
 
+/// Recognizes a line, and returns its content without the '\n' or '\r\n' ending it.
+///
+/// *Streaming version*: Will return `Err(nom::Err::Incomplete(_))` if the line ending has
+/// not been received yet.
+/// # Example
+///
+/// ```
+/// # use nom::{Err, error::ErrorKind, IResult, Needed};
+/// # use nom::character::streaming::line;
+/// assert_eq!(line::<_, (_, ErrorKind)>("ab\r\nc"), Ok(("c", "ab")));
+/// assert_eq!(line::<_, (_, ErrorKind)>("\nc"), Ok(("c", "")));
+/// assert_eq!(line::<_, (_, ErrorKind)>("abc"), Err(Err::Incomplete(Needed::Unknown)));
+/// assert_eq!(line::<_, (_, ErrorKind)>("a\rbc"), Err(Err::Error(("a\rbc", ErrorKind::Tag))));
+/// ```
+pub fn line<T, E: ParseError<T>>(input: T) -> IResult<T, T, E>
+where
+  T: Input,
+  T: Compare<&'static str>,
+  <T as Input>::Item: AsChar,
+{
+  let (rest, content) = not_line_ending(input)?;
+  let (rest, _) = line_ending(rest)?;
+  Ok((rest, content))
+}
+
+/// Recognizes a line with at least one character, and returns its content without the
+/// '\n' or '\r\n' ending it.
+///
+/// *Streaming version*: Will return `Err(nom::Err::Incomplete(_))` if the line ending has
+/// not been received yet.
+/// # Example
+///
+/// ```
+/// # use nom::{Err, error::ErrorKind, IResult, Needed};
+/// # use nom::character::streaming::non_empty_line;
+/// assert_eq!(non_empty_line::<_, (_, ErrorKind)>("ab\r\nc"), Ok(("c", "ab")));
+/// assert_eq!(non_empty_line::<_, (_, ErrorKind)>("\nc"), Err(Err::Error(("\nc", ErrorKind::NonEmpty))));
+/// assert_eq!(non_empty_line::<_, (_, ErrorKind)>("abc"), Err(Err::Incomplete(Needed::Unknown)));
+/// ```
+pub fn non_empty_line<T, E: ParseError<T>>(input: T) -> IResult<T, T, E>
+where
+  T: Input,
+  T: Compare<&'static str>,
+  <T as Input>::Item: AsChar,
+{
+  let (rest, content) = line(input.clone())?;
+  if content.input_len() == 0 {
+    Err(Err::Error(E::from_error_kind(input, ErrorKind::NonEmpty)))
+  } else {
+    Ok((rest, content))
+  }
+}
+
 /// Matches a newline character '\\n'.
 ///
 /// *Streaming version*: Will return `Err(nom::Err::Incomplete(_))` if there's not enough input data.
@@ -22,6 +22,64 @@ fn one_of_test() {
   assert!(utf8("\u{FF0B}").is_ok());
 }
 
+#[test]
+fn non_ascii_byte() {
+  fn f(i: &[u8]) -> IResult<&[u8], char> {
+    satisfy(|c| c == '\u{e9}')(i)
+  }
+  fn g(i: &[u8]) -> IResult<&[u8], char> {
+    char('\u{e9}')(i)
+  }
+
+  // each byte is one element, even if the character is encoded on 2 bytes in UTF-8
+  assert_eq!(f(&b"\xe9ab"[..]), Ok((&b"ab"[..], '\u{e9}')));
+  assert_eq!(g(&b"\xe9ab"[..]), Ok((&b"ab"[..], '\u{e9}')));
+}
+
+#[test]
+fn char_no_case_test() {
+  fn f(i: &[u8]) -> IResult<&[u8], char> {
+    char_no_case('a')(i)
+  }
+
+  assert_eq!(f(&b"Ab"[..]), Ok((&b"b"[..], 'A')));
+  assert_eq!(f(&b"ab"[..]), Ok((&b"b"[..], 'a')));
+  assert_eq!(
+    f(&b"b"[..]),
+    Err(Err::Error(error_position!(&b"b"[..], ErrorKind::Char)))
+  );
+
+  fn utf8(i: &str) -> IResult<&str, char> {
+    char_no_case('\u{3A3}')(i)
+  }
+
+  assert_eq!(utf8("\u{3C3}a"), Ok(("a", '\u{3C3}')));
+  assert_eq!(utf8("\u{3A3}a"), Ok(("a", '\u{3A3}')));
+  assert_eq!(
+    crate::character::complete::char_no_case::<_, (_, ErrorKind)>('x')(""),
+    Err(Err::Error(("", ErrorKind::Char)))
+  );
+}
+
+#[test]
+#[cfg(feature = "unicode")]
+fn char_no_case_unicode() {
+  fn f(i: &str) -> IResult<&str, char> {
+    char_no_case('\u{3C3}')(i)
+  }
+
+  // final sigma, and the German sharp s with its capital form
+  assert_eq!(f("\u{3C2}a"), Ok(("a", '\u{3C2}')));
+  assert_eq!(
+    char_no_case::<_, (_, ErrorKind)>('\u{DF}')("\u{1E9E}a"),
+    Ok(("a", '\u{1E9E}'))
+  );
+  assert_eq!(
+    char_no_case::<_, (_, ErrorKind)>('\u{DF}')("SS"),
+    Err(Err::Error(("SS", ErrorKind::Char)))
+  );
+}
+
 #[test]
 fn none_of_test() {
   fn f(i: &[u8]) -> IResult<&[u8], char> {
@@ -60,3 +118,113 @@ fn char_str() {
   let b = "cde";
   assert_eq!(f(b), Ok(("de", 'c')));
 }
+
+#[test]
+fn integer_strictness() {
+  use super::integer;
+  use crate::{Needed, Parser, Strictness};
+
+  let mut strict = integer::<i16, _, (_, ErrorKind)>(Strictness::Strict);
+  assert_eq!(strict.parse_complete("0;"), Ok((";", 0)));
+  assert_eq!(strict.parse_complete("-32768"), Ok(("", i16::MIN)));
+  assert_eq!(
+    strict.parse_complete("32768"),
+    Err(Err::Error(("32768", ErrorKind::Digit)))
+  );
+  assert_eq!(
+    strict.parse_complete(" 1"),
+    Err(Err::Error((" 1", ErrorKind::Digit)))
+  );
+  assert_eq!(
+    strict.parse_complete("-"),
+    Err(Err::Error(("-", ErrorKind::Digit)))
+  );
+  assert_eq!(
+    strict.parse_complete("-01"),
+    Err(Err::Error(("-01", ErrorKind::Digit)))
+  );
+  assert_eq!(strict.parse("-"), Err(Err::Incomplete(Needed::new(1))));
+  assert_eq!(
+    integer::<i16, _, (_, ErrorKind)>(Strictness::Strict).parse(&b"12\r\n"[..]),
+    Ok((&b"\r\n"[..], 12))
+  );
+
+  let mut lenient = integer::<i16, _, (_, ErrorKind)>(Strictness::Lenient);
+  assert_eq!(lenient.parse_complete("  -007 "), Ok((" ", -7)));
+  assert_eq!(lenient.parse_complete("+0"), Ok(("", 0)));
+  assert_eq!(
+    lenient.parse_complete("+-1"),
+    Err(Err::Error(("+-1", ErrorKind::Digit)))
+  );
+  assert_eq!(lenient.parse(" "), Err(Err::Incomplete(Needed::new(1))));
+
+  let mut unsigned = integer::<u64, _, (_, ErrorKind)>(Strictness::Strict);
+  assert_eq!(
+    unsigned.parse_complete("-0"),
+    Err(Err::Error(("-0", ErrorKind::Digit)))
+  );
+  assert_eq!(
+    unsigned.parse_complete("18446744073709551615"),
+    Ok(("", u64::MAX))
+  );
+}
+
+#[test]
+fn line_test() {
+  use crate::Needed;
+
+  fn complete_line(i: &str) -> IResult<&str, &str> {
+    super::complete::line(i)
+  }
+
+  let mut input = "ab\n\r\ncd";
+  let mut lines = Vec::new();
+  while let Ok((rest, l)) = complete_line(input) {
+    lines.push(l);
+    input = rest;
+  }
+  assert_eq!(lines, ["ab", "", "cd"]);
+
+  assert_eq!(
+    line::<_, (_, ErrorKind)>(&b"ab\r"[..]),
+    Err(Err::Incomplete(Needed::Unknown))
+  );
+  assert_eq!(
+    line::<_, (_, ErrorKind)>(&b"ab\r\n"[..]),
+    Ok((&b""[..], &b"ab"[..]))
+  );
+  assert_eq!(
+    non_empty_line::<_, (_, ErrorKind)>(&b"\r\nab"[..]),
+    Err(Err::Error((&b"\r\nab"[..], ErrorKind::NonEmpty)))
+  );
+}
+
+#[test]
+fn integer_separators() {
+  use super::integer_sep;
+  use crate::{Needed, Parser};
+
+  let mut signed = integer_sep::<i8, _, (_, ErrorKind)>('_');
+  assert_eq!(signed.parse_complete("-1_2_8"), Ok(("", i8::MIN)));
+  assert_eq!(
+    signed.parse_complete("1_28"),
+    Err(Err::Error(("1_28", ErrorKind::Digit)))
+  );
+  assert_eq!(
+    signed.parse_complete("-_1"),
+    Err(Err::Error(("-_1", ErrorKind::Digit)))
+  );
+  assert_eq!(
+    signed.parse_complete("1_a"),
+    Err(Err::Error(("1_a", ErrorKind::Digit)))
+  );
+  assert_eq!(signed.parse("-"), Err(Err::Incomplete(Needed::new(1))));
+  assert_eq!(signed.parse("1_"), Err(Err::Incomplete(Needed::new(1))));
+
+  let mut unsigned = integer_sep::<u16, _, (_, ErrorKind)>(',');
+  assert_eq!(unsigned.parse_complete("65,535 "), Ok((" ", u16::MAX)));
+  assert_eq!(
+    unsigned.parse_complete("-1"),
+    Err(Err::Error(("-1", ErrorKind::Digit)))
+  );
+}
@@ -0,0 +1,375 @@
+//! Grapheme cluster input wrapper
+
+use unicode_segmentation::{GraphemeCursor, UnicodeSegmentation};
+
+use crate::error::{ErrorKind, ParseError};
+use crate::internal::{Err, IResult, Needed};
+use crate::traits::{AsChar, Compare, CompareResult, FindSubstring, Input, Offset, Rewind};
+
+/// Input wrapper making parsers work on grapheme clusters instead of `char`s
+///
+/// A grapheme cluster is what a user sees as a single character: a base character with
+/// its combining marks, an emoji with its modifiers and joined emojis, a flag made of two
+/// regional indicators, or a `\r\n` pair. With this wrapper, [Input::iter_elements] yields
+/// [Grapheme] elements, so [take](crate::bytes::complete::take) counts clusters, and parsers
+/// like [char](crate::character::complete::char), [satisfy](crate::character::complete::satisfy)
+/// or [one_of](crate::character::complete::one_of) consume whole clusters, testing their first
+/// character. [Compare] and [FindSubstring] only match if the tag ends on a cluster boundary.
+///
+/// Positions and lengths are still counted in bytes, like for `&str`.
+///
+/// The segmentation follows the extended grapheme cluster rules from
+/// [UAX #29](https://www.unicode.org/reports/tr29/), as implemented by the
+/// [unicode-segmentation](https://docs.rs/unicode-segmentation) crate, with the
+/// `unicode-segmentation` feature.
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+/// use nom::bytes::complete::{tag, take};
+/// use nom::character::unicode::Graphemes;
+///
+/// // "e" followed by a combining acute accent, then a thumbs up with a skin tone modifier
+/// let input = Graphemes::new("e\u{301}\u{1F44D}\u{1F3FD}!");
+///
+/// let (rest, first) = take::<_, _, Error<_>>(2usize).parse(input).unwrap();
+/// assert_eq!(first.as_str(), "e\u{301}\u{1F44D}\u{1F3FD}");
+/// assert_eq!(rest.as_str(), "!");
+///
+/// // "e" is not a whole cluster here
+/// assert_eq!(
+///   tag::<_, _, Error<_>>("e").parse(input),
+///   Err(Err::Error(Error::new(input, ErrorKind::Tag)))
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Graphemes<T>(T);
+
+impl<'a> Graphemes<&'a str> {
+  /// Wraps a string
+  pub fn new(input: &'a str) -> Self {
+    Graphemes(input)
+  }
+
+  /// Returns the wrapped string
+  pub fn as_str(&self) -> &'a str {
+    self.0
+  }
+}
+
+impl<T> Graphemes<T> {
+  /// Returns the wrapped input
+  pub fn into_inner(self) -> T {
+    self.0
+  }
+}
+
+/// A grapheme cluster, the element of [Graphemes]
+///
+/// Through [AsChar], the cluster is seen as its first character, usually the base character
+/// the other ones modify, while [AsChar::len] is the length of the whole cluster. The
+/// classification methods like [AsChar::is_alpha] only accept clusters made of a single
+/// character, except for [AsChar::is_newline] which also accepts `\r\n`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Grapheme<'a>(&'a str);
+
+impl<'a> Grapheme<'a> {
+  /// Returns the characters of the cluster
+  pub fn as_str(&self) -> &'a str {
+    self.0
+  }
+
+  fn single(self) -> Option<char> {
+    let mut chars = self.0.chars();
+    match (chars.next(), chars.next()) {
+      (Some(c), None) => Some(c),
+      _ => None,
+    }
+  }
+}
+
+impl<'a> AsChar for Grapheme<'a> {
+  #[inline]
+  fn as_char(self) -> char {
+    // clusters are never empty
+    self.0.chars().next().unwrap_or('\u{FFFD}')
+  }
+  fn is_alpha(self) -> bool {
+    self.single().map_or(false, AsChar::is_alpha)
+  }
+  fn is_alphanum(self) -> bool {
+    self.single().map_or(false, AsChar::is_alphanum)
+  }
+  fn is_dec_digit(self) -> bool {
+    self.single().map_or(false, AsChar::is_dec_digit)
+  }
+  fn is_hex_digit(self) -> bool {
+    self.single().map_or(false, AsChar::is_hex_digit)
+  }
+  fn is_oct_digit(self) -> bool {
+    self.single().map_or(false, AsChar::is_oct_digit)
+  }
+  fn is_bin_digit(self) -> bool {
+    self.single().map_or(false, AsChar::is_bin_digit)
+  }
+  #[inline]
+  fn len(self) -> usize {
+    self.0.len()
+  }
+  fn is_space(self) -> bool {
+    self.single().map_or(false, AsChar::is_space)
+  }
+  fn is_newline(self) -> bool {
+    self.0 == "\n" || self.0 == "\r\n"
+  }
+}
+
+/// Iterator over the clusters of [Graphemes]
+pub struct GraphemeIter<'a> {
+  iter: unicode_segmentation::Graphemes<'a>,
+}
+
+impl<'a> Iterator for GraphemeIter<'a> {
+  type Item = Grapheme<'a>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.iter.next().map(Grapheme)
+  }
+}
+
+/// Iterator over the clusters of [Graphemes] and their byte positions
+pub struct GraphemeIndices<'a> {
+  iter: unicode_segmentation::GraphemeIndices<'a>,
+}
+
+impl<'a> Iterator for GraphemeIndices<'a> {
+  type Item = (usize, Grapheme<'a>);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self
+      .iter
+      .next()
+      .map(|(index, cluster)| (index, Grapheme(cluster)))
+  }
+}
+
+impl<'a> Input for Graphemes<&'a str> {
+  type Item = Grapheme<'a>;
+  type Iter = GraphemeIter<'a>;
+  type IterIndices = GraphemeIndices<'a>;
+
+  fn input_len(&self) -> usize {
+    self.0.len()
+  }
+
+  #[inline]
+  fn take(&self, index: usize) -> Self {
+    Graphemes(&self.0[..index])
+  }
+
+  #[inline]
+  fn take_from(&self, index: usize) -> Self {
+    Graphemes(&self.0[index..])
+  }
+
+  #[inline]
+  fn take_split(&self, index: usize) -> (Self, Self) {
+    let (prefix, suffix) = self.0.split_at(index);
+    (Graphemes(suffix), Graphemes(prefix))
+  }
+
+  fn position<P>(&self, predicate: P) -> Option<usize>
+  where
+    P: Fn(Self::Item) -> bool,
+  {
+    self
+      .iter_indices()
+      .find(|(_, cluster)| predicate(*cluster))
+      .map(|(index, _)| index)
+  }
+
+  #[inline]
+  fn iter_elements(&self) -> Self::Iter {
+    GraphemeIter {
+      iter: self.0.graphemes(true),
+    }
+  }
+
+  #[inline]
+  fn iter_indices(&self) -> Self::IterIndices {
+    GraphemeIndices {
+      iter: self.0.grapheme_indices(true),
+    }
+  }
+
+  fn slice_index(&self, count: usize) -> Result<usize, Needed> {
+    let mut cnt = 0;
+    for (index, _) in self.iter_indices() {
+      if cnt == count {
+        return Ok(index);
+      }
+      cnt += 1;
+    }
+    if cnt == count {
+      return Ok(self.0.len());
+    }
+    Err(Needed::Unknown)
+  }
+
+  fn split_at_position<P, E: ParseError<Self>>(&self, predicate: P) -> IResult<Self, Self, E>
+  where
+    P: Fn(Self::Item) -> bool,
+  {
+    match self.position(predicate) {
+      Some(n) => Ok(self.take_split(n)),
+      None => Err(Err::Incomplete(Needed::new(1))),
+    }
+  }
+
+  fn split_at_position1<P, E: ParseError<Self>>(
+    &self,
+    predicate: P,
+    e: ErrorKind,
+  ) -> IResult<Self, Self, E>
+  where
+    P: Fn(Self::Item) -> bool,
+  {
+    match self.position(predicate) {
+      Some(0) => Err(Err::Error(E::from_error_kind(*self, e))),
+      Some(n) => Ok(self.take_split(n)),
+      None => Err(Err::Incomplete(Needed::new(1))),
+    }
+  }
+}
+
+impl Offset for Graphemes<&str> {
+  fn offset(&self, second: &Self) -> usize {
+    self.0.offset(second.0)
+  }
+}
+
+impl Rewind for Graphemes<&str> {
+  type Checkpoint = ();
+
+  #[inline]
+  fn checkpoint(&self) {}
+
+  #[inline]
+  fn rewind(&self, _checkpoint: ()) {}
+}
+
+impl<'b> Compare<&'b str> for Graphemes<&str> {
+  fn compare(&self, t: &'b str) -> CompareResult {
+    match self.0.compare(t) {
+      CompareResult::Ok if !is_boundary(self.0, t.len()) => CompareResult::Error,
+      res => res,
+    }
+  }
+
+  fn compare_no_case(&self, t: &'b str) -> CompareResult {
+    self.compare_no_case_len(t).0
+  }
+
+  fn compare_no_case_len(&self, t: &'b str) -> (CompareResult, Option<usize>) {
+    match self.0.compare_no_case_len(t) {
+      (CompareResult::Ok, len) if !is_boundary(self.0, len.unwrap_or(t.len())) => {
+        (CompareResult::Error, None)
+      }
+      res => res,
+    }
+  }
+}
+
+impl<'b> FindSubstring<&'b str> for Graphemes<&str> {
+  fn find_substring(&self, substr: &'b str) -> Option<usize> {
+    self
+      .iter_indices()
+      .map(|(index, _)| index)
+      .find(|index| Graphemes(&self.0[*index..]).compare(substr) == CompareResult::Ok)
+  }
+}
+
+/// Returns `true` if a cluster ends at the byte position `index`
+fn is_boundary(s: &str, index: usize) -> bool {
+  GraphemeCursor::new(index, s.len(), true)
+    .is_boundary(s, 0)
+    .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::bytes::complete::{tag, take, take_until};
+  use crate::character::complete::{digit1, one_of, satisfy};
+  use crate::error::Error;
+  use crate::Parser;
+
+  fn clusters(s: &str) -> impl Iterator<Item = &str> {
+    Graphemes::new(s).iter_elements().map(|g| g.as_str())
+  }
+
+  #[test]
+  fn segmentation() {
+    assert!(clusters("").eq(None::<&str>));
+    assert!(clusters("ab\r\n\n").eq(["a", "b", "\r\n", "\n"]));
+    assert!(clusters("e\u{301}\u{302}x").eq(["e\u{301}\u{302}", "x"]));
+    // a family emoji, joined with ZWJ, and a flag
+    assert!(
+      clusters("\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}\u{1F1EB}\u{1F1F7}").eq([
+        "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}",
+        "\u{1F1EB}\u{1F1F7}"
+      ])
+    );
+    // three regional indicators make one flag and a lone indicator
+    assert!(clusters("\u{1F1EB}\u{1F1F7}\u{1F1EB}").eq(["\u{1F1EB}\u{1F1F7}", "\u{1F1EB}"]));
+    // a letter followed by ZWJ does not join the next character
+    assert!(clusters("a\u{200D}\u{1F600}").eq(["a\u{200D}", "\u{1F600}"]));
+    // hangul syllable from conjoining jamos, then a precomposed one
+    assert!(
+      clusters("\u{1100}\u{1161}\u{11A8}\u{AC00}").eq(["\u{1100}\u{1161}\u{11A8}", "\u{AC00}"])
+    );
+    // devanagari spacing mark
+    assert!(clusters("\u{915}\u{93F}").eq(["\u{915}\u{93F}"]));
+    // prepended concatenation mark
+    assert!(clusters("\u{600}12").eq(["\u{600}1", "2"]));
+    // a symbol that is not extended pictographic is not joined by ZWJ
+    assert!(clusters("\u{B0}\u{200D}\u{B0}").eq(["\u{B0}\u{200D}", "\u{B0}"]));
+  }
+
+  #[test]
+  fn parsers() {
+    let input = Graphemes::new("n\u{303}a\u{1F44D}\u{1F3FD}123");
+
+    let (rest, first) = take::<_, _, Error<_>>(3usize).parse(input).unwrap();
+    assert_eq!(first.as_str(), "n\u{303}a\u{1F44D}\u{1F3FD}");
+    assert_eq!(
+      digit1::<_, Error<_>>(rest),
+      Ok((Graphemes::new(""), Graphemes::new("123")))
+    );
+
+    // satisfy sees the base character, and consumes the whole cluster
+    let (rest, c) = satisfy::<_, _, Error<_>>(|c| c == 'n')
+      .parse(input)
+      .unwrap();
+    assert_eq!(c, 'n');
+    assert_eq!(rest.as_str(), "a\u{1F44D}\u{1F3FD}123");
+    assert!(one_of::<_, _, Error<_>>("ab").parse(rest).is_ok());
+
+    assert!(tag::<_, _, Error<_>>("n").parse(input).is_err());
+    assert!(tag::<_, _, Error<_>>("n\u{303}a").parse(input).is_ok());
+
+    let input = Graphemes::new("e\u{301}e.");
+    let (rest, before) = take_until::<_, _, Error<_>>("e").parse(input).unwrap();
+    assert_eq!((rest.as_str(), before.as_str()), ("e.", "e\u{301}"));
+    assert_eq!(input.offset(&rest), 3);
+  }
+
+  #[test]
+  fn grapheme_as_char() {
+    let mut iter = Graphemes::new("1\u{20E3}\r\n7").iter_elements();
+    let keycap = iter.next().unwrap();
+    assert_eq!(keycap.as_char(), '1');
+    assert!(!keycap.is_dec_digit());
+    assert!(iter.next().unwrap().is_newline());
+    assert!(iter.next().unwrap().is_dec_digit());
+  }
+}
@@ -0,0 +1,535 @@
+//! Unicode character classes
+//!
+//! Parsers for identifiers following the default syntax of
+//! [UAX #31](https://www.unicode.org/reports/tr31/), Unicode whitespace, and characters
+//! of a given [general category](GeneralCategory), for language implementations that
+//! accept more than ASCII. The `XID_Start` and `XID_Continue` properties come from the
+//! [unicode-ident](https://docs.rs/unicode-ident) crate.
+//!
+//! Like the parsers from [character](crate::character), they accept any input whose
+//! elements implement [AsChar], and they follow the streaming or complete semantics
+//! depending on how they are applied.
+//!
+//! ```rust
+//! # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+//! use nom::character::complete::char;
+//! use nom::character::unicode::{identifier, unicode_whitespace0};
+//! use nom::sequence::{delimited, separated_pair};
+//!
+//! fn binding(input: &str) -> IResult<&str, (&str, &str)> {
+//!   separated_pair(
+//!     identifier(),
+//!     delimited(unicode_whitespace0(), char('='), unicode_whitespace0()),
+//!     identifier(),
+//!   )
+//!   .parse_complete(input)
+//! }
+//!
+//! // U+3000 is an ideographic space
+//! assert_eq!(binding("größe\u{3000}= δ_2"), Ok(("", ("größe", "δ_2"))));
+//! assert_eq!(binding("2x = y"), Err(Err::Error(Error::new("2x = y", ErrorKind::Satisfy))));
+//! ```
+
+#[cfg(feature = "unicode-segmentation")]
+mod graphemes;
+mod tables;
+
+#[cfg(feature = "unicode-segmentation")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "unicode-segmentation")))]
+pub use self::graphemes::{Grapheme, GraphemeIndices, GraphemeIter, Graphemes};
+
+use core::marker::PhantomData;
+
+use crate::character::satisfy;
+use crate::combinator::recognize;
+use crate::error::{ErrorKind, ParseError};
+use crate::traits::{AsChar, FindToken, Input, Offset};
+use crate::Parser;
+
+/// Returns `true` if the character has the `XID_Start` property
+///
+/// Those are the characters that can start an identifier.
+#[inline]
+pub fn is_xid_start(c: char) -> bool {
+  unicode_ident::is_xid_start(c)
+}
+
+/// Returns `true` if the character has the `XID_Continue` property
+///
+/// Those are the characters that can follow the first one in an identifier.
+#[inline]
+pub fn is_xid_continue(c: char) -> bool {
+  unicode_ident::is_xid_continue(c)
+}
+
+/// Recognizes an identifier: a `XID_Start` character followed by any number of
+/// `XID_Continue` characters.
+///
+/// Returns an error with [ErrorKind::Satisfy] if the first character cannot start an
+/// identifier. Languages accepting other characters, like an initial `_`, can combine
+/// [is_xid_start] and [xid_continue0] themselves.
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Needed, Parser};
+/// use nom::character::unicode::identifier;
+///
+/// let mut parser = identifier::<_, Error<_>>();
+/// assert_eq!(parser.parse_complete("naïve_1 = 2"), Ok((" = 2", "naïve_1")));
+/// assert_eq!(parser.parse_complete("_a"), Err(Err::Error(Error::new("_a", ErrorKind::Satisfy))));
+/// assert_eq!(parser.parse("ab"), Err(Err::Incomplete(Needed::new(1))));
+/// ```
+pub fn identifier<I, E: ParseError<I>>() -> impl Parser<I, Output = I, Error = E>
+where
+  I: Input + Offset,
+  <I as Input>::Item: AsChar,
+{
+  recognize((satisfy(is_xid_start), xid_continue0()))
+}
+
+/// Recognizes one or more `XID_Start` characters.
+///
+/// Returns an error with [ErrorKind::Alpha] if the input does not start with one.
+pub fn xid_start1<I, E: ParseError<I>>() -> impl Parser<I, Output = I, Error = E>
+where
+  I: Input,
+  <I as Input>::Item: AsChar,
+{
+  Class {
+    predicate: is_xid_start,
+    kind: Some(ErrorKind::Alpha),
+    e: PhantomData,
+  }
+}
+
+/// Recognizes zero or more `XID_Continue` characters.
+pub fn xid_continue0<I, E: ParseError<I>>() -> impl Parser<I, Output = I, Error = E>
+where
+  I: Input,
+  <I as Input>::Item: AsChar,
+{
+  Class {
+    predicate: is_xid_continue,
+    kind: None,
+    e: PhantomData,
+  }
+}
+
+/// Recognizes one or more `XID_Continue` characters.
+///
+/// Returns an error with [ErrorKind::AlphaNumeric] if the input does not start with one.
+pub fn xid_continue1<I, E: ParseError<I>>() -> impl Parser<I, Output = I, Error = E>
+where
+  I: Input,
+  <I as Input>::Item: AsChar,
+{
+  Class {
+    predicate: is_xid_continue,
+    kind: Some(ErrorKind::AlphaNumeric),
+    e: PhantomData,
+  }
+}
+
+/// Recognizes zero or more characters with the Unicode `White_Space` property.
+///
+/// Unlike [multispace0](crate::character::complete::multispace0), this accepts all the
+/// Unicode spaces and line separators, like U+00A0 (no-break space) or U+2028 (line
+/// separator).
+///
+/// ```rust
+/// # use nom::{error::Error, Parser};
+/// use nom::character::unicode::unicode_whitespace0;
+///
+/// let mut parser = unicode_whitespace0::<_, Error<_>>();
+/// assert_eq!(parser.parse_complete("\u{a0}\t\u{2028}x"), Ok(("x", "\u{a0}\t\u{2028}")));
+/// assert_eq!(parser.parse_complete("x"), Ok(("x", "")));
+/// ```
+pub fn unicode_whitespace0<I, E: ParseError<I>>() -> impl Parser<I, Output = I, Error = E>
+where
+  I: Input,
+  <I as Input>::Item: AsChar,
+{
+  Class {
+    predicate: char::is_whitespace,
+    kind: None,
+    e: PhantomData,
+  }
+}
+
+/// Recognizes one or more characters with the Unicode `White_Space` property.
+///
+/// Returns an error with [ErrorKind::MultiSpace] if the input does not start with one.
+pub fn unicode_whitespace1<I, E: ParseError<I>>() -> impl Parser<I, Output = I, Error = E>
+where
+  I: Input,
+  <I as Input>::Item: AsChar,
+{
+  Class {
+    predicate: char::is_whitespace,
+    kind: Some(ErrorKind::MultiSpace),
+    e: PhantomData,
+  }
+}
+
+/// Recognizes a character belonging to one of the general categories in `categories`.
+///
+/// `categories` can be a single [GeneralCategory], or an array or slice of them. The
+/// [GeneralCategory] methods like [GeneralCategory::is_letter] can be used to build
+/// the list of a group of categories.
+///
+/// Returns an error with [ErrorKind::Satisfy] if the character does not match.
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Parser};
+/// use nom::character::unicode::{char_class, GeneralCategory};
+///
+/// let mut upper = char_class::<_, _, Error<_>>(GeneralCategory::UppercaseLetter);
+/// assert_eq!(upper.parse_complete("Éa"), Ok(("a", 'É')));
+/// assert_eq!(upper.parse_complete("éa"), Err(Err::Error(Error::new("éa", ErrorKind::Satisfy))));
+///
+/// let mut dash = char_class::<_, _, Error<_>>([
+///   GeneralCategory::DashPunctuation,
+///   GeneralCategory::MathSymbol,
+/// ]);
+/// assert_eq!(dash.parse_complete("—"), Ok(("", '—')));
+/// assert_eq!(dash.parse_complete("+"), Ok(("", '+')));
+/// ```
+pub fn char_class<I, C, E: ParseError<I>>(categories: C) -> impl Parser<I, Output = char, Error = E>
+where
+  I: Input,
+  <I as Input>::Item: AsChar,
+  C: FindToken<GeneralCategory>,
+{
+  satisfy(move |c| categories.find_token(GeneralCategory::of(c)))
+}
+
+/// Parser implementation for the character class parsers
+struct Class<E> {
+  predicate: fn(char) -> bool,
+  /// error returned if no character matches, or `None` to accept empty results
+  kind: Option<ErrorKind>,
+  e: PhantomData<E>,
+}
+
+impl<I, E: ParseError<I>> Parser<I> for Class<E>
+where
+  I: Input,
+  <I as Input>::Item: AsChar,
+{
+  type Output = I;
+  type Error = E;
+
+  #[inline]
+  fn process<OM: crate::OutputMode>(
+    &mut self,
+    i: I,
+  ) -> crate::PResult<OM, I, Self::Output, Self::Error> {
+    let predicate = self.predicate;
+    match self.kind {
+      Some(kind) => i.split_at_position_mode1::<OM, _, _>(|item| !predicate(item.as_char()), kind),
+      None => i.split_at_position_mode::<OM, _, _>(|item| !predicate(item.as_char())),
+    }
+  }
+}
+
+/// Unicode general category of a character
+///
+/// The categories come from the Unicode Character Database, and unassigned code
+/// points are [GeneralCategory::Unassigned].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GeneralCategory {
+  /// Lu
+  UppercaseLetter,
+  /// Ll
+  LowercaseLetter,
+  /// Lt, digraphs like `ǅ`
+  TitlecaseLetter,
+  /// Lm
+  ModifierLetter,
+  /// Lo, letters without case like ideographs
+  OtherLetter,
+  /// Mn
+  NonspacingMark,
+  /// Mc
+  SpacingMark,
+  /// Me
+  EnclosingMark,
+  /// Nd
+  DecimalNumber,
+  /// Nl, like roman numerals
+  LetterNumber,
+  /// No, like superscripts and fractions
+  OtherNumber,
+  /// Pc, like `_`
+  ConnectorPunctuation,
+  /// Pd
+  DashPunctuation,
+  /// Ps
+  OpenPunctuation,
+  /// Pe
+  ClosePunctuation,
+  /// Pi
+  InitialPunctuation,
+  /// Pf
+  FinalPunctuation,
+  /// Po
+  OtherPunctuation,
+  /// Sm
+  MathSymbol,
+  /// Sc
+  CurrencySymbol,
+  /// Sk
+  ModifierSymbol,
+  /// So
+  OtherSymbol,
+  /// Zs
+  SpaceSeparator,
+  /// Zl
+  LineSeparator,
+  /// Zp
+  ParagraphSeparator,
+  /// Cc
+  Control,
+  /// Cf
+  Format,
+  /// Cs
+  Surrogate,
+  /// Co
+  PrivateUse,
+  /// Cn
+  Unassigned,
+}
+
+impl GeneralCategory {
+  /// Every category, in the order of the generated table
+  const ALL: [GeneralCategory; 30] = [
+    GeneralCategory::UppercaseLetter,
+    GeneralCategory::LowercaseLetter,
+    GeneralCategory::TitlecaseLetter,
+    GeneralCategory::ModifierLetter,
+    GeneralCategory::OtherLetter,
+    GeneralCategory::NonspacingMark,
+    GeneralCategory::SpacingMark,
+    GeneralCategory::EnclosingMark,
+    GeneralCategory::DecimalNumber,
+    GeneralCategory::LetterNumber,
+    GeneralCategory::OtherNumber,
+    GeneralCategory::ConnectorPunctuation,
+    GeneralCategory::DashPunctuation,
+    GeneralCategory::OpenPunctuation,
+    GeneralCategory::ClosePunctuation,
+    GeneralCategory::InitialPunctuation,
+    GeneralCategory::FinalPunctuation,
+    GeneralCategory::OtherPunctuation,
+    GeneralCategory::MathSymbol,
+    GeneralCategory::CurrencySymbol,
+    GeneralCategory::ModifierSymbol,
+    GeneralCategory::OtherSymbol,
+    GeneralCategory::SpaceSeparator,
+    GeneralCategory::LineSeparator,
+    GeneralCategory::ParagraphSeparator,
+    GeneralCategory::Control,
+    GeneralCategory::Format,
+    GeneralCategory::Surrogate,
+    GeneralCategory::PrivateUse,
+    GeneralCategory::Unassigned,
+  ];
+
+  /// Returns the general category of a character
+  pub fn of(c: char) -> GeneralCategory {
+    let index = match tables::STARTS.binary_search(&(c as u32)) {
+      Ok(index) => index,
+      // the table starts at 0, so the character is after the first entry
+      Err(index) => index - 1,
+    };
+    GeneralCategory::ALL[tables::CATEGORIES[index] as usize]
+  }
+
+  /// Returns `true` for the letter categories (L)
+  pub fn is_letter(self) -> bool {
+    matches!(
+      self,
+      GeneralCategory::UppercaseLetter
+        | GeneralCategory::LowercaseLetter
+        | GeneralCategory::TitlecaseLetter
+        | GeneralCategory::ModifierLetter
+        | GeneralCategory::OtherLetter
+    )
+  }
+
+  /// Returns `true` for the mark categories (M)
+  pub fn is_mark(self) -> bool {
+    matches!(
+      self,
+      GeneralCategory::NonspacingMark
+        | GeneralCategory::SpacingMark
+        | GeneralCategory::EnclosingMark
+    )
+  }
+
+  /// Returns `true` for the number categories (N)
+  pub fn is_number(self) -> bool {
+    matches!(
+      self,
+      GeneralCategory::DecimalNumber | GeneralCategory::LetterNumber | GeneralCategory::OtherNumber
+    )
+  }
+
+  /// Returns `true` for the punctuation categories (P)
+  pub fn is_punctuation(self) -> bool {
+    matches!(
+      self,
+      GeneralCategory::ConnectorPunctuation
+        | GeneralCategory::DashPunctuation
+        | GeneralCategory::OpenPunctuation
+        | GeneralCategory::ClosePunctuation
+        | GeneralCategory::InitialPunctuation
+        | GeneralCategory::FinalPunctuation
+        | GeneralCategory::OtherPunctuation
+    )
+  }
+
+  /// Returns `true` for the symbol categories (S)
+  pub fn is_symbol(self) -> bool {
+    matches!(
+      self,
+      GeneralCategory::MathSymbol
+        | GeneralCategory::CurrencySymbol
+        | GeneralCategory::ModifierSymbol
+        | GeneralCategory::OtherSymbol
+    )
+  }
+
+  /// Returns `true` for the separator categories (Z)
+  pub fn is_separator(self) -> bool {
+    matches!(
+      self,
+      GeneralCategory::SpaceSeparator
+        | GeneralCategory::LineSeparator
+        | GeneralCategory::ParagraphSeparator
+    )
+  }
+
+  /// Returns `true` for the other categories (C): control, format, surrogate, private
+  /// use and unassigned code points
+  pub fn is_other(self) -> bool {
+    matches!(
+      self,
+      GeneralCategory::Control
+        | GeneralCategory::Format
+        | GeneralCategory::Surrogate
+        | GeneralCategory::PrivateUse
+        | GeneralCategory::Unassigned
+    )
+  }
+}
+
+impl FindToken<GeneralCategory> for GeneralCategory {
+  fn find_token(&self, token: GeneralCategory) -> bool {
+    *self == token
+  }
+}
+
+impl<const N: usize> FindToken<GeneralCategory> for [GeneralCategory; N] {
+  fn find_token(&self, token: GeneralCategory) -> bool {
+    self.contains(&token)
+  }
+}
+
+impl FindToken<GeneralCategory> for &[GeneralCategory] {
+  fn find_token(&self, token: GeneralCategory) -> bool {
+    self.contains(&token)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::error::Error;
+  use crate::{Err, Needed};
+
+  #[test]
+  fn general_category() {
+    assert_eq!(tables::STARTS.len(), tables::CATEGORIES.len());
+    assert!(tables::STARTS.windows(2).all(|w| w[0] < w[1]));
+
+    assert_eq!(GeneralCategory::of('\0'), GeneralCategory::Control);
+    assert_eq!(GeneralCategory::of('A'), GeneralCategory::UppercaseLetter);
+    assert_eq!(GeneralCategory::of('ǅ'), GeneralCategory::TitlecaseLetter);
+    assert_eq!(GeneralCategory::of('字'), GeneralCategory::OtherLetter);
+    assert_eq!(
+      GeneralCategory::of('\u{301}'),
+      GeneralCategory::NonspacingMark
+    );
+    assert_eq!(GeneralCategory::of('Ⅻ'), GeneralCategory::LetterNumber);
+    assert_eq!(
+      GeneralCategory::of('_'),
+      GeneralCategory::ConnectorPunctuation
+    );
+    assert_eq!(
+      GeneralCategory::of('«'),
+      GeneralCategory::InitialPunctuation
+    );
+    assert_eq!(GeneralCategory::of('€'), GeneralCategory::CurrencySymbol);
+    assert_eq!(
+      GeneralCategory::of('\u{2029}'),
+      GeneralCategory::ParagraphSeparator
+    );
+    assert_eq!(GeneralCategory::of('\u{e000}'), GeneralCategory::PrivateUse);
+    assert_eq!(
+      GeneralCategory::of('\u{10ffff}'),
+      GeneralCategory::Unassigned
+    );
+
+    assert!(GeneralCategory::of('ß').is_letter());
+    assert!(GeneralCategory::of('½').is_number());
+    assert!(GeneralCategory::of('∑').is_symbol());
+    assert!(!GeneralCategory::of('∑').is_punctuation());
+  }
+
+  #[test]
+  fn xid() {
+    let mut start = xid_start1::<_, Error<_>>();
+    assert_eq!(start.parse_complete("αβ1"), Ok(("1", "αβ")));
+    assert_eq!(
+      start.parse_complete("1α"),
+      Err(Err::Error(Error::new("1α", ErrorKind::Alpha)))
+    );
+
+    let mut cont = xid_continue1::<_, Error<_>>();
+    assert_eq!(cont.parse_complete("a_1\u{301}-"), Ok(("-", "a_1\u{301}")));
+    assert_eq!(
+      cont.parse_complete("-"),
+      Err(Err::Error(Error::new("-", ErrorKind::AlphaNumeric)))
+    );
+    assert_eq!(cont.parse("a1"), Err(Err::Incomplete(Needed::new(1))));
+
+    let mut ident = identifier::<_, Error<_>>();
+    assert_eq!(ident.parse_complete("变量1+"), Ok(("+", "变量1")));
+    assert_eq!(ident.parse_complete("x"), Ok(("", "x")));
+  }
+
+  #[test]
+  fn whitespace() {
+    let mut ws = unicode_whitespace1::<_, Error<_>>();
+    assert_eq!(
+      ws.parse_complete("\u{3000} \u{85}a"),
+      Ok(("a", "\u{3000} \u{85}"))
+    );
+    assert_eq!(
+      ws.parse_complete("a"),
+      Err(Err::Error(Error::new("a", ErrorKind::MultiSpace)))
+    );
+  }
+
+  #[test]
+  fn char_class_slice() {
+    let letters = &GeneralCategory::ALL[..5];
+    assert!(letters.iter().all(|c| c.is_letter()));
+    let mut letter = char_class::<_, _, Error<_>>(letters);
+    assert_eq!(letter.parse_complete("ǅ1"), Ok(("1", 'ǅ')));
+    assert_eq!(
+      letter.parse_complete("1"),
+      Err(Err::Error(Error::new("1", ErrorKind::Satisfy)))
+    );
+  }
+}
@@ -2,21 +2,27 @@
 
 #![allow(unused_imports)]
 
+use core::cell::Cell;
 use core::marker::PhantomData;
+use core::ops::ControlFlow;
+use core::sync::atomic::{AtomicBool, Ordering};
 
 #[cfg(feature = "alloc")]
 use crate::lib::std::boxed::Box;
 
-use crate::error::{ErrorKind, FromExternalError, ParseError};
+use crate::error::{ContextError, ErrorKind, FromExternalError, MapInput, ParseError};
 use crate::internal::*;
 use crate::lib::std::borrow::Borrow;
 use crate::lib::std::convert::Into;
+use crate::lib::std::fmt;
 #[cfg(feature = "std")]
 use crate::lib::std::fmt::Debug;
 use crate::lib::std::mem::transmute;
 use crate::lib::std::ops::{Range, RangeFrom, RangeTo};
-use crate::traits::{AsChar, Input, ParseTo};
+use crate::traits::{AsChar, ExtendInto, Fueled, Input, ParseTo, Rewind, Stateful};
 use crate::traits::{Compare, CompareResult, Offset};
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
 
 #[cfg(test)]
 mod tests;
@@ -110,6 +116,95 @@ where
   parser.map_res(f)
 }
 
+/// Applies a function returning a `Result` over the result of a parser, building the
+/// error from the value that was rejected.
+///
+/// [map_res] passes the error of the function to [FromExternalError], but the parsed
+/// value is gone by then. Here the function borrows the value, and if it fails,
+/// `describe` receives the value and its error to build the external error, so the
+/// message can say which value was invalid. The resulting error has the kind
+/// [ErrorKind::MapRes] and is positioned at the start of the child parser's input.
+///
+/// ```rust
+/// # use nom::{Err, error::{ErrorKind, FromExternalError, ParseError}, IResult, Parser};
+/// use nom::character::complete::digit1;
+/// use nom::combinator::map_res_ctx;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Message(String);
+///
+/// impl<I> ParseError<I> for Message {
+///   fn from_error_kind(_: I, kind: ErrorKind) -> Self { Message(kind.description().to_string()) }
+///   fn append(_: I, _: ErrorKind, other: Self) -> Self { other }
+/// }
+///
+/// impl<I> FromExternalError<I, String> for Message {
+///   fn from_external_error(_: I, _: ErrorKind, e: String) -> Self { Message(e) }
+/// }
+///
+/// let mut port = map_res_ctx(
+///   digit1,
+///   |s: &&str| s.parse::<u16>(),
+///   |s: &&str, e| format!("port {} out of range: {}", s, e),
+/// );
+///
+/// assert_eq!(port.parse("8080"), Ok(("", 8080)));
+/// assert_eq!(
+///   port.parse("70000"),
+///   Err(Err::Error(Message("port 70000 out of range: number too large to fit in target type".to_string())))
+/// );
+/// ```
+pub fn map_res_ctx<I: Clone, O, E, E2, E3, F, G, H>(
+  parser: F,
+  f: G,
+  describe: H,
+) -> impl Parser<I, Output = O, Error = E>
+where
+  F: Parser<I, Error = E>,
+  G: FnMut(&<F as Parser<I>>::Output) -> Result<O, E2>,
+  H: FnMut(&<F as Parser<I>>::Output, E2) -> E3,
+  E: ParseError<I> + FromExternalError<I, E3>,
+{
+  MapResCtx {
+    parser,
+    f,
+    describe,
+  }
+}
+
+/// Parser implementation for [map_res_ctx]
+pub struct MapResCtx<F, G, H> {
+  parser: F,
+  f: G,
+  describe: H,
+}
+
+impl<I, O, E2, E3, F, G, H> Parser<I> for MapResCtx<F, G, H>
+where
+  I: Clone,
+  F: Parser<I>,
+  G: FnMut(&<F as Parser<I>>::Output) -> Result<O, E2>,
+  H: FnMut(&<F as Parser<I>>::Output, E2) -> E3,
+  <F as Parser<I>>::Error: FromExternalError<I, E3>,
+{
+  type Output = O;
+  type Error = <F as Parser<I>>::Error;
+
+  fn process<OM: OutputMode>(&mut self, input: I) -> PResult<OM, I, Self::Output, Self::Error> {
+    let (i, o1) = self
+      .parser
+      .process::<OutputM<Emit, OM::Error, OM::Incomplete>>(input.clone())?;
+
+    match (self.f)(&o1) {
+      Ok(o2) => Ok((i, OM::Output::bind(|| o2))),
+      Err(e) => Err(Err::Error(OM::Error::bind(|| {
+        let e = (self.describe)(&o1, e);
+        <F as Parser<I>>::Error::from_external_error(input, ErrorKind::MapRes, e)
+      }))),
+    }
+  }
+}
+
 /// Applies a function returning an `Option` over the result of a parser.
 ///
 /// ```rust
@@ -474,6 +569,91 @@ where
   }
 }
 
+/// Like [all_consuming], describing the leftover input in the error
+///
+/// The error at the leftover input is created with [ErrorKind::Eof], then receives a context
+/// with the number of elements left unconsumed, bytes for `&[u8]` and `&str`, and the offset
+/// where parsing stopped, through [ContextError::add_lazy_context]. Error types that do not
+/// keep those contexts return the same error as [all_consuming].
+///
+/// ```rust
+/// # use nom::{Err, IResult, Parser};
+/// use nom::combinator::all_consuming_verbose;
+/// use nom::number::complete::be_u16;
+/// use nom_language::error::{VerboseError, VerboseErrorKind};
+///
+/// fn header(i: &[u8]) -> IResult<&[u8], u16, VerboseError<&[u8]>> {
+///   all_consuming_verbose(be_u16).parse(i)
+/// }
+///
+/// assert_eq!(header(b"\x00\x01"), Ok((&b""[..], 1)));
+/// let Err(Err::Error(e)) = header(b"\x00\x01\xff\xff\xff") else { panic!() };
+/// assert_eq!(
+///   e.errors[1].1,
+///   VerboseErrorKind::Label("3 bytes left unconsumed at offset 2".to_string())
+/// );
+/// ```
+pub fn all_consuming_verbose<I, E, F>(
+  parser: F,
+) -> impl Parser<I, Output = <F as Parser<I>>::Output, Error = E>
+where
+  I: Input,
+  E: ParseError<I> + ContextError<I>,
+  F: Parser<I, Error = E>,
+{
+  AllConsumingVerbose { parser }
+}
+
+/// Parser implementation for [all_consuming_verbose]
+pub struct AllConsumingVerbose<F> {
+  parser: F,
+}
+
+/// Leftover input described by [all_consuming_verbose]
+struct Leftover {
+  remaining: usize,
+  offset: usize,
+}
+
+impl fmt::Display for Leftover {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let unit = if self.remaining == 1 { "byte" } else { "bytes" };
+    write!(
+      f,
+      "{} {} left unconsumed at offset {}",
+      self.remaining, unit, self.offset
+    )
+  }
+}
+
+impl<I, F> Parser<I> for AllConsumingVerbose<F>
+where
+  I: Input,
+  F: Parser<I>,
+  <F as Parser<I>>::Error: ContextError<I>,
+{
+  type Output = <F as Parser<I>>::Output;
+  type Error = <F as Parser<I>>::Error;
+
+  fn process<OM: OutputMode>(&mut self, input: I) -> PResult<OM, I, Self::Output, Self::Error> {
+    let len = input.input_len();
+    let (input, res) = self.parser.process::<OM>(input)?;
+    let remaining = input.input_len();
+    if remaining == 0 {
+      Ok((input, res))
+    } else {
+      Err(Err::Error(OM::Error::bind(|| {
+        let leftover = Leftover {
+          remaining,
+          offset: len - remaining,
+        };
+        let e = <F as Parser<I>>::Error::from_error_kind(input.clone(), ErrorKind::Eof);
+        <F as Parser<I>>::Error::add_lazy_context(input, &leftover, e)
+      })))
+    }
+  }
+}
+
 /// Returns the result of the child parser if it satisfies a verification function.
 ///
 /// The verification function takes as argument a reference to the output of the
@@ -543,6 +723,177 @@ where
   }
 }
 
+/// Returns the result of the child parser if it satisfies a verification function,
+/// building the error from the value that was rejected.
+///
+/// This is [verify], except that when the verification fails, `describe` receives the
+/// rejected value and returns an external error, converted with [FromExternalError]
+/// into an error with [ErrorKind::Verify], positioned at the start of the child
+/// parser's input.
+///
+/// ```rust
+/// # use nom::{Err, error::{ErrorKind, FromExternalError, ParseError}, IResult, Parser};
+/// use nom::character::complete::u32;
+/// use nom::combinator::verify_with;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Message(String);
+///
+/// impl<I> ParseError<I> for Message {
+///   fn from_error_kind(_: I, kind: ErrorKind) -> Self { Message(kind.description().to_string()) }
+///   fn append(_: I, _: ErrorKind, other: Self) -> Self { other }
+/// }
+///
+/// impl<I> FromExternalError<I, String> for Message {
+///   fn from_external_error(_: I, _: ErrorKind, e: String) -> Self { Message(e) }
+/// }
+///
+/// let mut port = verify_with(
+///   u32,
+///   |port: &u32| *port <= 65535,
+///   |port: &u32| format!("port {} out of range", port),
+/// );
+///
+/// assert_eq!(port.parse("8080"), Ok(("", 8080)));
+/// assert_eq!(port.parse("70000"), Err(Err::Error(Message("port 70000 out of range".to_string()))));
+/// ```
+pub fn verify_with<I: Clone, O2, E, E2, F, G, H>(
+  parser: F,
+  predicate: G,
+  describe: H,
+) -> impl Parser<I, Output = <F as Parser<I>>::Output, Error = E>
+where
+  F: Parser<I, Error = E>,
+  G: Fn(&O2) -> bool,
+  H: FnMut(&O2) -> E2,
+  <F as Parser<I>>::Output: Borrow<O2>,
+  O2: ?Sized,
+  E: ParseError<I> + FromExternalError<I, E2>,
+{
+  VerifyWith {
+    parser,
+    predicate,
+    describe,
+    o2: PhantomData,
+  }
+}
+
+/// Parser implementation for [verify_with]
+pub struct VerifyWith<F, G, H, O2: ?Sized> {
+  parser: F,
+  predicate: G,
+  describe: H,
+  o2: PhantomData<O2>,
+}
+
+impl<I, F, G, H, O2, E2> Parser<I> for VerifyWith<F, G, H, O2>
+where
+  I: Clone,
+  F: Parser<I>,
+  G: Fn(&O2) -> bool,
+  H: FnMut(&O2) -> E2,
+  <F as Parser<I>>::Output: Borrow<O2>,
+  <F as Parser<I>>::Error: FromExternalError<I, E2>,
+  O2: ?Sized,
+{
+  type Output = <F as Parser<I>>::Output;
+  type Error = <F as Parser<I>>::Error;
+
+  fn process<OM: OutputMode>(&mut self, input: I) -> PResult<OM, I, Self::Output, Self::Error> {
+    let (i, o) = self
+      .parser
+      .process::<OutputM<Emit, OM::Error, OM::Incomplete>>(input.clone())?;
+
+    if (self.predicate)(o.borrow()) {
+      Ok((i, OM::Output::bind(|| o)))
+    } else {
+      Err(Err::Error(OM::Error::bind(|| {
+        let e = (self.describe)(o.borrow());
+        <F as Parser<I>>::Error::from_external_error(input, ErrorKind::Verify, e)
+      })))
+    }
+  }
+}
+
+/// Checks the result of the child parser with a function that can also look at the
+/// input it was applied on.
+///
+/// This is meant for semantic checks spanning several fields, like a declared length
+/// that must match the length of a payload. The validation function takes as arguments
+/// a reference to the output of the parser and the input before it was applied. If it
+/// returns an error, it is converted with [FromExternalError] into an error with
+/// [ErrorKind::Verify], positioned at the start of the child parser's input.
+///
+/// ```rust
+/// # use nom::{Err,error::ErrorKind, IResult, Parser};
+/// use nom::combinator::validate_with;
+/// use nom::bytes::complete::take;
+/// use nom::number::complete::u8;
+/// use nom::sequence::pair;
+/// # fn main() {
+///
+/// // a declared length, then a fixed size payload
+/// let mut parser = validate_with(
+///   pair(u8, take(3usize)),
+///   |(len, payload): &(u8, &[u8]), _input: &&[u8]| {
+///     if usize::from(*len) <= payload.len() {
+///       Ok(())
+///     } else {
+///       Err("declared length is too large")
+///     }
+///   },
+/// );
+///
+/// assert_eq!(parser.parse(&[2, 1, 2, 3, 4][..]), Ok((&[4][..], (2, &[1, 2, 3][..]))));
+/// assert_eq!(
+///   parser.parse(&[5, 1, 2, 3][..]),
+///   Err(Err::Error((&[5, 1, 2, 3][..], ErrorKind::Verify)))
+/// );
+/// # }
+/// ```
+pub fn validate_with<I: Clone, E, E2, F, G>(
+  parser: F,
+  f: G,
+) -> impl Parser<I, Output = <F as Parser<I>>::Output, Error = E>
+where
+  F: Parser<I, Error = E>,
+  G: FnMut(&<F as Parser<I>>::Output, &I) -> Result<(), E2>,
+  E: ParseError<I> + FromExternalError<I, E2>,
+{
+  ValidateWith { parser, f }
+}
+
+/// Parser implementation for [validate_with]
+pub struct ValidateWith<F, G> {
+  parser: F,
+  f: G,
+}
+
+impl<I, F, G, E2> Parser<I> for ValidateWith<F, G>
+where
+  I: Clone,
+  F: Parser<I>,
+  G: FnMut(&<F as Parser<I>>::Output, &I) -> Result<(), E2>,
+  <F as Parser<I>>::Error: FromExternalError<I, E2>,
+{
+  type Output = <F as Parser<I>>::Output;
+
+  type Error = <F as Parser<I>>::Error;
+
+  fn process<OM: OutputMode>(&mut self, input: I) -> PResult<OM, I, Self::Output, Self::Error> {
+    let (i, o) = self
+      .parser
+      .process::<OutputM<Emit, OM::Error, OM::Incomplete>>(input.clone())?;
+
+    match (self.f)(&o, &input) {
+      Ok(()) => Ok((i, OM::Output::bind(|| o))),
+      Err(e) => Err(Err::Error(OM::Error::bind(move || {
+        <F as Parser<I>>::Error::from_external_error(input, ErrorKind::Verify, e)
+      }))),
+    }
+  }
+}
+
 /// Returns the provided value if the child parser succeeds.
 ///
 /// ```rust
@@ -716,7 +1067,7 @@ where
 
 /// Parser implementation for [consumed]
 pub struct Consumed<F> {
-  parser: F,
+  pub(crate) parser: F,
 }
 
 impl<I, F> Parser<I> for Consumed<F>
@@ -747,298 +1098,2686 @@ where
   }
 }
 
-/// Transforms an [`Err::Error`] (recoverable) to [`Err::Failure`] (unrecoverable)
-///
-/// This commits the parse result, preventing alternative branch paths like with
-/// [`nom::branch::alt`][crate::branch::alt].
+/// Returns the offset of the current position in the original input, without consuming
+/// anything.
 ///
-/// # Example
+/// `original` is the whole input given to the top level parser, and the offset is computed
+/// with [Offset], so for slices it is a number of bytes.
 ///
-/// Without `cut`:
 /// ```rust
-/// # use nom::{Err,error::ErrorKind, IResult, Parser};
-/// # use nom::character::complete::{one_of, digit1};
-/// # use nom::combinator::rest;
-/// # use nom::branch::alt;
-/// # use nom::sequence::preceded;
-/// # fn main() {
-///
-/// fn parser(input: &str) -> IResult<&str, &str> {
-///   alt((
-///     preceded(one_of("+-"), digit1),
-///     rest
-///   )).parse(input)
-/// }
+/// # use nom::{Err, error::ErrorKind, IResult, Parser};
+/// use nom::combinator::position;
+/// use nom::character::complete::{alpha1, space1};
+/// use nom::sequence::preceded;
 ///
-/// assert_eq!(parser("+10 ab"), Ok((" ab", "10")));
-/// assert_eq!(parser("ab"), Ok(("", "ab")));
-/// assert_eq!(parser("+"), Ok(("", "+")));
-/// # }
+/// let input = "let  x";
+/// let mut parser = preceded((alpha1, space1), position::<_, (_, ErrorKind)>(input));
+/// assert_eq!(parser.parse(input), Ok(("x", 5)));
 /// ```
+pub fn position<I, E>(original: I) -> impl Parser<I, Output = usize, Error = E>
+where
+  I: Offset,
+  E: ParseError<I>,
+{
+  Position {
+    original,
+    e: PhantomData,
+  }
+}
+
+/// Parser implementation for [position]
+pub struct Position<I, E> {
+  original: I,
+  e: PhantomData<E>,
+}
+
+impl<I, E> Parser<I> for Position<I, E>
+where
+  I: Offset,
+  E: ParseError<I>,
+{
+  type Output = usize;
+  type Error = E;
+
+  #[inline(always)]
+  fn process<OM: OutputMode>(&mut self, input: I) -> PResult<OM, I, Self::Output, Self::Error> {
+    let offset = self.original.offset(&input);
+    Ok((input, OM::Output::bind(|| offset)))
+  }
+}
+
+/// Returns the range of the original input consumed by the parser along with its output.
 ///
-/// With `cut`:
-/// ```rust
-/// # use nom::{Err,error::ErrorKind, IResult, Parser, error::Error};
-/// # use nom::character::complete::{one_of, digit1};
-/// # use nom::combinator::rest;
-/// # use nom::branch::alt;
-/// # use nom::sequence::preceded;
-/// use nom::combinator::cut;
-/// # fn main() {
+/// `original` is the whole input given to the top level parser, and the range is computed
+/// with [Offset], so for slices it is a range of bytes. AST nodes can keep it to point
+/// back to the source, and [line_col](crate::error::line_col) converts its bounds to
+/// lines and columns.
 ///
-/// fn parser(input: &str) -> IResult<&str, &str> {
-///   alt((
-///     preceded(one_of("+-"), cut(digit1)),
-///     rest
-///   )).parse(input)
-/// }
+/// ```rust
+/// # use nom::{Err, error::ErrorKind, IResult, Parser};
+/// use nom::combinator::with_span;
+/// use nom::character::complete::{alpha1, space1};
+/// use nom::sequence::preceded;
 ///
-/// assert_eq!(parser("+10 ab"), Ok((" ab", "10")));
-/// assert_eq!(parser("ab"), Ok(("", "ab")));
-/// assert_eq!(parser("+"), Err(Err::Failure(Error { input: "", code: ErrorKind::Digit })));
-/// # }
+/// let input = "let  x = 1";
+/// let mut parser = preceded((alpha1, space1), with_span(input, alpha1::<_, (_, ErrorKind)>));
+/// assert_eq!(parser.parse(input), Ok((" = 1", (5..6, "x"))));
 /// ```
-pub fn cut<I, E: ParseError<I>, F>(
+pub fn with_span<I, F>(
+  original: I,
   parser: F,
-) -> impl Parser<I, Output = <F as Parser<I>>::Output, Error = E>
+) -> impl Parser<I, Output = (Range<usize>, <F as Parser<I>>::Output), Error = <F as Parser<I>>::Error>
 where
-  F: Parser<I, Error = E>,
+  I: Clone + Offset,
+  F: Parser<I>,
 {
-  Cut { parser }
+  WithSpan { original, parser }
 }
 
-/// Parser implementation for [cut]
-pub struct Cut<F> {
+/// Parser implementation for [with_span]
+pub struct WithSpan<I, F> {
+  original: I,
   parser: F,
 }
 
-impl<I, F> Parser<I> for Cut<F>
+impl<I, F> Parser<I> for WithSpan<I, F>
 where
+  I: Clone + Offset,
   F: Parser<I>,
 {
-  type Output = <F as Parser<I>>::Output;
-
+  type Output = (Range<usize>, <F as Parser<I>>::Output);
   type Error = <F as Parser<I>>::Error;
 
   #[inline(always)]
   fn process<OM: OutputMode>(&mut self, input: I) -> PResult<OM, I, Self::Output, Self::Error> {
-    match self
-      .parser
-      .process::<OutputM<OM::Output, Emit, OM::Incomplete>>(input)
-    {
-      Err(Err::Error(e)) => Err(Err::Failure(e)),
-      Err(Err::Failure(e)) => Err(Err::Failure(e)),
-      Err(Err::Incomplete(i)) => Err(Err::Incomplete(i)),
-      Ok((i, o)) => Ok((i, o)),
+    let start = self.original.offset(&input);
+    let (remaining, result) = self.parser.process::<OM>(input)?;
+    let end = self.original.offset(&remaining);
+
+    Ok((remaining, OM::Output::map(result, |res| (start..end, res))))
+  }
+}
+
+/// Returns an owned copy of the input consumed by the parser along with its output.
+///
+/// This is like [consumed], but the consumed part is copied to a buffer that does not
+/// borrow the input: a `Vec<u8>` for byte inputs, including the ones that are not
+/// contiguous in memory like [Segmented](crate::Segmented), and a `String` for `&str`.
+/// It can be kept after the input buffer is reused, to log the exact bytes of a message
+/// or to verify its signature.
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+/// use nom::Segmented;
+/// use nom::bytes::complete::{tag, take_until};
+/// use nom::combinator::tee_raw;
+///
+/// let segments: [&[u8]; 2] = [b"From: al", b"ice\r\nbody"];
+/// let input = Segmented::new(&segments);
+///
+/// let mut header = tee_raw((tag::<_, _, Error<_>>("From: "), take_until("\r\n"), tag("\r\n")));
+/// let (_, (raw, (_, name, _))) = header.parse(input).unwrap();
+/// assert_eq!(raw, b"From: alice\r\n");
+/// assert_eq!(name.segments().collect::<Vec<_>>(), [&b"al"[..], b"ice"]);
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "alloc")))]
+pub fn tee_raw<I, F, E>(
+  parser: F,
+) -> impl Parser<I, Output = (<I as ExtendInto>::Extender, <F as Parser<I>>::Output), Error = E>
+where
+  I: Clone + Offset + Input + ExtendInto,
+  E: ParseError<I>,
+  F: Parser<I, Error = E>,
+{
+  TeeRaw { parser }
+}
+
+/// Parser implementation for [tee_raw]
+#[cfg(feature = "alloc")]
+pub struct TeeRaw<F> {
+  parser: F,
+}
+
+#[cfg(feature = "alloc")]
+impl<I, F> Parser<I> for TeeRaw<F>
+where
+  I: Clone + Offset + Input + ExtendInto,
+  F: Parser<I>,
+{
+  type Output = (<I as ExtendInto>::Extender, <F as Parser<I>>::Output);
+  type Error = <F as Parser<I>>::Error;
+
+  #[inline(always)]
+  fn process<OM: OutputMode>(&mut self, input: I) -> PResult<OM, I, Self::Output, Self::Error> {
+    let (remaining, result) = self.parser.process::<OM>(input.clone())?;
+    let index = input.offset(&remaining);
+
+    Ok((
+      remaining,
+      OM::Output::map(result, |res| {
+        let consumed = input.take(index);
+        let mut raw = consumed.new_builder();
+        consumed.extend_into(&mut raw);
+        (raw, res)
+      }),
+    ))
+  }
+}
+
+/// Applies a parser as a transaction on a stateful input: if it fails, the state of the
+/// input is restored to what it was before the parser was applied.
+///
+/// The input is checkpointed with [Rewind] before applying the parser. On success, the
+/// parser's result is returned as is. On any error, including `Err::Incomplete`, the input
+/// is rewound to the checkpoint, then the error is returned, so an enclosing
+/// [alt](crate::branch::alt) or [opt] can try another branch from a consistent state.
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser, Rewind};
+/// use std::cell::Cell;
+/// use nom::branch::alt;
+/// use nom::combinator::atomic;
+///
+/// // input counting the opened brackets
+/// #[derive(Clone, Debug, PartialEq)]
+/// struct Nested<'a> {
+///   input: &'a str,
+///   depth: &'a Cell<usize>,
+/// }
+///
+/// impl Rewind for Nested<'_> {
+///   type Checkpoint = usize;
+///   fn checkpoint(&self) -> usize {
+///     self.depth.get()
+///   }
+///   fn rewind(&self, depth: usize) {
+///     self.depth.set(depth)
+///   }
+/// }
+///
+/// fn symbol<'a>(c: char) -> impl Fn(Nested<'a>) -> IResult<Nested<'a>, char, Error<Nested<'a>>> {
+///   move |i: Nested<'a>| match i.input.strip_prefix(c) {
+///     Some(rest) => {
+///       if c == '[' {
+///         i.depth.set(i.depth.get() + 1);
+///       }
+///       Ok((Nested { input: rest, ..i }, c))
+///     }
+///     None => Err(Err::Error(Error::new(i, ErrorKind::Char))),
+///   }
+/// }
+///
+/// let depth = Cell::new(0);
+/// let input = Nested { input: "[b", depth: &depth };
+///
+/// // the first branch opens a bracket, then fails
+/// let mut parser = alt((atomic((symbol('['), symbol('a'))), (symbol('['), symbol('b'))));
+/// assert!(parser.parse(input).is_ok());
+/// assert_eq!(depth.get(), 1);
+/// ```
+pub fn atomic<I, E: ParseError<I>, F>(
+  parser: F,
+) -> impl Parser<I, Output = <F as Parser<I>>::Output, Error = E>
+where
+  I: Clone + Rewind,
+  F: Parser<I, Error = E>,
+{
+  Atomic { parser }
+}
+
+/// Parser implementation for [atomic]
+pub struct Atomic<F> {
+  parser: F,
+}
+
+impl<I, F> Parser<I> for Atomic<F>
+where
+  I: Clone + Rewind,
+  F: Parser<I>,
+{
+  type Output = <F as Parser<I>>::Output;
+  type Error = <F as Parser<I>>::Error;
+
+  #[inline(always)]
+  fn process<OM: OutputMode>(&mut self, input: I) -> PResult<OM, I, Self::Output, Self::Error> {
+    let checkpoint = input.checkpoint();
+    match self.parser.process::<OM>(input.clone()) {
+      Ok(res) => Ok(res),
+      Err(e) => {
+        input.rewind(checkpoint);
+        Err(e)
+      }
+    }
+  }
+}
+
+/// Applies `f` to the state of a [Stateful] input, returning its result without consuming
+/// input
+///
+/// `f` is called in every mode, even when the output is discarded, since it can change the
+/// state. The state is mutably borrowed while `f` runs.
+///
+/// ```rust
+/// # use nom::{IResult, Parser};
+/// use nom::Stateful;
+/// use nom::character::complete::char;
+/// use nom::combinator::with_state;
+/// use nom::multi::many0;
+/// use std::cell::RefCell;
+///
+/// // counts the opening brackets, returning the depth reached by each one
+/// fn open<'a, 's>(i: Stateful<'s, &'a str, usize>) -> IResult<Stateful<'s, &'a str, usize>, usize> {
+///   let depth = with_state(|depth: &mut usize| {
+///     *depth += 1;
+///     *depth
+///   });
+///   (char('['), depth).map(|(_, depth)| depth).parse(i)
+/// }
+///
+/// let depth = RefCell::new(0);
+/// let (rest, depths) = many0(open).parse(Stateful::new("[[[]", &depth)).unwrap();
+/// assert_eq!(depths, [1, 2, 3]);
+/// assert_eq!(rest.into_inner(), "]");
+/// ```
+pub fn with_state<'s, I, S, O, E, F>(f: F) -> impl Parser<Stateful<'s, I, S>, Output = O, Error = E>
+where
+  S: 's,
+  E: ParseError<Stateful<'s, I, S>>,
+  F: FnMut(&mut S) -> O,
+{
+  WithState { f, e: PhantomData }
+}
+
+/// Parser implementation for [with_state]
+pub struct WithState<F, E> {
+  f: F,
+  e: PhantomData<E>,
+}
+
+impl<'s, I, S, O, E, F> Parser<Stateful<'s, I, S>> for WithState<F, E>
+where
+  E: ParseError<Stateful<'s, I, S>>,
+  F: FnMut(&mut S) -> O,
+{
+  type Output = O;
+  type Error = E;
+
+  fn process<OM: OutputMode>(
+    &mut self,
+    input: Stateful<'s, I, S>,
+  ) -> PResult<OM, Stateful<'s, I, S>, Self::Output, Self::Error> {
+    let o = (self.f)(&mut input.state().borrow_mut());
+    Ok((input, OM::Output::bind(|| o)))
+  }
+}
+
+/// Applies a parser on a [Stateful] input, then maps its output with `f`, which also receives
+/// the state
+///
+/// The parser always produces its output for `f`, even when the output of `map_with_state` is
+/// discarded. The state is mutably borrowed while `f` runs, but not while the parser runs.
+/// See [Stateful] for an example.
+pub fn map_with_state<'s, I, S, O, E, F, G>(
+  parser: F,
+  f: G,
+) -> impl Parser<Stateful<'s, I, S>, Output = O, Error = E>
+where
+  S: 's,
+  E: ParseError<Stateful<'s, I, S>>,
+  F: Parser<Stateful<'s, I, S>, Error = E>,
+  G: FnMut(<F as Parser<Stateful<'s, I, S>>>::Output, &mut S) -> O,
+{
+  MapWithState { parser, f }
+}
+
+/// Parser implementation for [map_with_state]
+pub struct MapWithState<F, G> {
+  parser: F,
+  f: G,
+}
+
+impl<'s, I, S, O, F, G> Parser<Stateful<'s, I, S>> for MapWithState<F, G>
+where
+  F: Parser<Stateful<'s, I, S>>,
+  G: FnMut(<F as Parser<Stateful<'s, I, S>>>::Output, &mut S) -> O,
+{
+  type Output = O;
+  type Error = <F as Parser<Stateful<'s, I, S>>>::Error;
+
+  fn process<OM: OutputMode>(
+    &mut self,
+    input: Stateful<'s, I, S>,
+  ) -> PResult<OM, Stateful<'s, I, S>, Self::Output, Self::Error> {
+    let (input, o) = self
+      .parser
+      .process::<OutputM<Emit, OM::Error, OM::Incomplete>>(input)?;
+    let o = (self.f)(o, &mut input.state().borrow_mut());
+    Ok((input, OM::Output::bind(|| o)))
+  }
+}
+
+/// Transforms an [`Err::Error`] (recoverable) to [`Err::Failure`] (unrecoverable)
+///
+/// This commits the parse result, preventing alternative branch paths like with
+/// [`nom::branch::alt`][crate::branch::alt].
+///
+/// # Example
+///
+/// Without `cut`:
+/// ```rust
+/// # use nom::{Err,error::ErrorKind, IResult, Parser};
+/// # use nom::character::complete::{one_of, digit1};
+/// # use nom::combinator::rest;
+/// # use nom::branch::alt;
+/// # use nom::sequence::preceded;
+/// # fn main() {
+///
+/// fn parser(input: &str) -> IResult<&str, &str> {
+///   alt((
+///     preceded(one_of("+-"), digit1),
+///     rest
+///   )).parse(input)
+/// }
+///
+/// assert_eq!(parser("+10 ab"), Ok((" ab", "10")));
+/// assert_eq!(parser("ab"), Ok(("", "ab")));
+/// assert_eq!(parser("+"), Ok(("", "+")));
+/// # }
+/// ```
+///
+/// With `cut`:
+/// ```rust
+/// # use nom::{Err,error::ErrorKind, IResult, Parser, error::Error};
+/// # use nom::character::complete::{one_of, digit1};
+/// # use nom::combinator::rest;
+/// # use nom::branch::alt;
+/// # use nom::sequence::preceded;
+/// use nom::combinator::cut;
+/// # fn main() {
+///
+/// fn parser(input: &str) -> IResult<&str, &str> {
+///   alt((
+///     preceded(one_of("+-"), cut(digit1)),
+///     rest
+///   )).parse(input)
+/// }
+///
+/// assert_eq!(parser("+10 ab"), Ok((" ab", "10")));
+/// assert_eq!(parser("ab"), Ok(("", "ab")));
+/// assert_eq!(parser("+"), Err(Err::Failure(Error { input: "", code: ErrorKind::Digit })));
+/// # }
+/// ```
+pub fn cut<I, E: ParseError<I>, F>(
+  parser: F,
+) -> impl Parser<I, Output = <F as Parser<I>>::Output, Error = E>
+where
+  F: Parser<I, Error = E>,
+{
+  Cut { parser }
+}
+
+/// Parser implementation for [cut]
+pub struct Cut<F> {
+  parser: F,
+}
+
+impl<I, F> Parser<I> for Cut<F>
+where
+  F: Parser<I>,
+{
+  type Output = <F as Parser<I>>::Output;
+
+  type Error = <F as Parser<I>>::Error;
+
+  #[inline(always)]
+  fn process<OM: OutputMode>(&mut self, input: I) -> PResult<OM, I, Self::Output, Self::Error> {
+    match self
+      .parser
+      .process::<OutputM<OM::Output, Emit, OM::Incomplete>>(input)
+    {
+      Err(Err::Error(e)) => Err(Err::Failure(e)),
+      Err(Err::Failure(e)) => Err(Err::Failure(e)),
+      Err(Err::Incomplete(i)) => Err(Err::Incomplete(i)),
+      Ok((i, o)) => Ok((i, o)),
+    }
+  }
+}
+
+/// Transforms an [`Err::Failure`] (unrecoverable) to [`Err::Error`] (recoverable),
+/// the reverse of [cut].
+///
+/// A failure stops every enclosing `alt` and `many`, up to the caller. `backtrack`
+/// limits the effect of the `cut` calls inside its parser to that parser: if it fails,
+/// an enclosing `alt` can try its next branch again.
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+/// use nom::branch::alt;
+/// use nom::bytes::complete::tag;
+/// use nom::character::complete::{alpha1, digit1};
+/// use nom::combinator::{backtrack, cut};
+/// use nom::sequence::preceded;
+///
+/// // inside a number, a sign must be followed by digits
+/// fn number(input: &str) -> IResult<&str, &str> {
+///   preceded(tag("-"), cut(digit1)).parse(input)
+/// }
+///
+/// // but a value can also be a dash followed by a name
+/// fn value(input: &str) -> IResult<&str, &str> {
+///   alt((backtrack(number), preceded(tag("-"), alpha1))).parse(input)
+/// }
+///
+/// assert_eq!(number("-ab"), Err(Err::Failure(Error::new("ab", ErrorKind::Digit))));
+/// assert_eq!(value("-12"), Ok(("", "12")));
+/// assert_eq!(value("-ab"), Ok(("", "ab")));
+/// ```
+pub fn backtrack<I, E: ParseError<I>, F>(
+  parser: F,
+) -> impl Parser<I, Output = <F as Parser<I>>::Output, Error = E>
+where
+  F: Parser<I, Error = E>,
+{
+  Backtrack { parser }
+}
+
+/// Parser implementation for [backtrack]
+pub struct Backtrack<F> {
+  parser: F,
+}
+
+impl<I, F> Parser<I> for Backtrack<F>
+where
+  F: Parser<I>,
+{
+  type Output = <F as Parser<I>>::Output;
+
+  type Error = <F as Parser<I>>::Error;
+
+  #[inline(always)]
+  fn process<OM: OutputMode>(&mut self, input: I) -> PResult<OM, I, Self::Output, Self::Error> {
+    match self.parser.process::<OM>(input) {
+      Err(Err::Failure(e)) => Err(Err::Error(OM::Error::bind(|| e))),
+      res => res,
+    }
+  }
+}
+
+/// Applies `prefix`, then `rest` with its errors transformed to failures, returning the
+/// output of `rest`.
+///
+/// This is `preceded(prefix, cut(rest))`: if `prefix` fails, the error is recoverable
+/// and an enclosing `alt` tries its next branch, but once `prefix` matched, like the
+/// keyword starting a statement, an error in `rest` is final and reported at its
+/// position, instead of the position of the last branch of the `alt`.
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+/// use nom::branch::alt;
+/// use nom::bytes::complete::tag;
+/// use nom::character::complete::{alpha1, digit1, space1};
+/// use nom::combinator::commit_after;
+///
+/// fn statement(input: &str) -> IResult<&str, &str> {
+///   alt((
+///     commit_after(tag("let"), (space1, alpha1)).map(|(_, name)| name),
+///     commit_after(tag("print"), (space1, digit1)).map(|(_, value)| value),
+///   ))
+///   .parse(input)
+/// }
+///
+/// assert_eq!(statement("let x"), Ok(("", "x")));
+/// assert_eq!(statement("print 1"), Ok(("", "1")));
+/// assert_eq!(statement("let 1"), Err(Err::Failure(Error::new("1", ErrorKind::Alpha))));
+/// assert_eq!(statement("exit"), Err(Err::Error(Error::new("exit", ErrorKind::Tag))));
+/// ```
+pub fn commit_after<I, E: ParseError<I>, F, G>(
+  prefix: F,
+  rest: G,
+) -> impl Parser<I, Output = <G as Parser<I>>::Output, Error = E>
+where
+  F: Parser<I, Error = E>,
+  G: Parser<I, Error = E>,
+{
+  CommitAfter { prefix, rest }
+}
+
+/// Parser implementation for [commit_after]
+pub struct CommitAfter<F, G> {
+  prefix: F,
+  rest: G,
+}
+
+impl<I, E: ParseError<I>, F, G> Parser<I> for CommitAfter<F, G>
+where
+  F: Parser<I, Error = E>,
+  G: Parser<I, Error = E>,
+{
+  type Output = <G as Parser<I>>::Output;
+
+  type Error = E;
+
+  #[inline(always)]
+  fn process<OM: OutputMode>(&mut self, input: I) -> PResult<OM, I, Self::Output, Self::Error> {
+    let (input, _) = self
+      .prefix
+      .process::<OutputM<Check, OM::Error, OM::Incomplete>>(input)?;
+    match self
+      .rest
+      .process::<OutputM<OM::Output, Emit, OM::Incomplete>>(input)
+    {
+      Err(Err::Error(e)) => Err(Err::Failure(e)),
+      Err(Err::Failure(e)) => Err(Err::Failure(e)),
+      Err(Err::Incomplete(i)) => Err(Err::Incomplete(i)),
+      Ok((i, o)) => Ok((i, o)),
+    }
+  }
+}
+
+/// automatically converts the child parser's result to another type
+///
+/// it will be able to convert the output value and the error value
+/// as long as the `Into` implementations are available
+///
+/// ```rust
+/// # use nom::{IResult, Parser};
+/// use nom::combinator::into;
+/// use nom::character::complete::alpha1;
+/// # fn main() {
+///
+/// fn parser1(i: &str) -> IResult<&str, &str> {
+///   alpha1(i)
+/// }
+///
+/// let mut parser2 = into(parser1);
+///
+/// // the parser converts the &str output of the child parser into a Vec<u8>
+/// let bytes: IResult<&str, Vec<u8>> = parser2.parse("abcd");
+/// assert_eq!(bytes, Ok(("", vec![97, 98, 99, 100])));
+/// # }
+/// ```
+pub fn into<I, O1, O2, E1, E2, F>(parser: F) -> impl Parser<I, Output = O2, Error = E2>
+where
+  O2: From<O1>,
+  E2: From<E1>,
+  E1: ParseError<I>,
+  E2: ParseError<I>,
+  F: Parser<I, Output = O1, Error = E1>,
+{
+  parser.into::<O2, E2>()
+}
+
+/// Creates an iterator from input data and a parser.
+///
+/// Call the iterator's [ParseStream::finish] method to get the remaining input if successful,
+/// or the error value if we encountered an error.
+///
+/// On [`Err::Error`], iteration will stop. To instead chain an error up, see [`cut`].
+///
+/// ```rust
+/// use nom::{combinator::iterator, IResult, bytes::complete::tag, character::complete::alpha1, sequence::terminated};
+/// use std::collections::HashMap;
+///
+/// let data = "abc|defg|hijkl|mnopqr|123";
+/// let mut it = iterator(data, terminated(alpha1, tag("|")));
+///
+/// let parsed = it.by_ref().map(|v| (v, v.len())).collect::<HashMap<_,_>>();
+/// let res: IResult<_,_> = it.finish();
+///
+/// assert_eq!(parsed, [("abc", 3usize), ("defg", 4), ("hijkl", 5), ("mnopqr", 6)].iter().cloned().collect());
+/// assert_eq!(res, Ok(("123", ())));
+/// ```
+pub fn iterator<Input, Error, F>(input: Input, f: F) -> ParseStream<Input, Error, F>
+where
+  F: Parser<Input>,
+  Error: ParseError<Input>,
+{
+  ParseStream {
+    parser: f,
+    input,
+    end: None,
+    refill: None,
+    exhausted: false,
+  }
+}
+
+/// Former name of [ParseStream]
+#[deprecated(since = "8.1.0", note = "Replaced with `ParseStream`")]
+pub type ParserIterator<I, E, F> = ParseStream<I, E, F>;
+
+/// Iterator over the outputs of a parser applied repeatedly, returned by [iterator]
+///
+/// The iteration ends at the first error, which stays available with [ParseStream::error],
+/// along with the input it stopped at, with [ParseStream::remaining]. Once it ended, the
+/// iterator only returns `None`.
+///
+/// The parser is applied in streaming mode. When it returns [Err::Incomplete], a callback set
+/// with [ParseStream::refill] can provide more data, and the parser is applied again on it.
+/// Once the callback has no more data, the parser is applied in complete mode.
+pub struct ParseStream<I, E, F, R = fn(I, Needed) -> Option<I>> {
+  parser: F,
+  input: I,
+  /// Error which ended the iteration
+  end: Option<Err<E>>,
+  refill: Option<R>,
+  /// Set once the refill callback has no more data
+  exhausted: bool,
+}
+
+impl<I: Clone, E, F, R> ParseStream<I, E, F, R> {
+  /// Returns the remaining input if parsing was successful, or the error if we encountered an error.
+  pub fn finish(self) -> IResult<I, (), E> {
+    match self.end {
+      None | Some(Err::Error(_)) => Ok((self.input, ())),
+      Some(Err::Failure(e)) => Err(Err::Failure(e)),
+      Some(Err::Incomplete(i)) => Err(Err::Incomplete(i)),
+    }
+  }
+
+  /// Returns the remaining input, and the error which ended the iteration, including the
+  /// [Err::Error] that [ParseStream::finish] discards
+  pub fn into_parts(self) -> (I, Option<Err<E>>) {
+    (self.input, self.end)
+  }
+
+  /// Returns the input which was not consumed by the items parsed so far
+  pub fn remaining(&self) -> &I {
+    &self.input
+  }
+
+  /// Returns the error which ended the iteration, or `None` if it can continue
+  ///
+  /// ```rust
+  /// # use nom::{Err, error::{Error, ErrorKind}};
+  /// use nom::{combinator::iterator, character::complete::{alpha1, char}, sequence::terminated};
+  ///
+  /// let mut it = iterator("abc|123|", terminated(alpha1::<_, Error<_>>, char('|')));
+  /// assert_eq!(it.next(), Some("abc"));
+  /// assert_eq!(it.error(), None);
+  ///
+  /// assert_eq!(it.next(), None);
+  /// assert_eq!(it.error(), Some(&Err::Error(Error::new("123|", ErrorKind::Alpha))));
+  /// assert_eq!(*it.remaining(), "123|");
+  /// ```
+  pub fn error(&self) -> Option<&Err<E>> {
+    self.end.as_ref()
+  }
+
+  /// Sets a callback called with the remaining input when the parser returns
+  /// [Err::Incomplete], and returning the same input followed by more data, or `None` once
+  /// there is no more data
+  ///
+  /// ```rust
+  /// # use nom::{Err, error::Error, IResult, Needed};
+  /// use nom::{combinator::iterator, bytes::take_while1, character::char, sequence::terminated};
+  ///
+  /// // the data is received 4 bytes at a time
+  /// let data = "abc|def|ghi|";
+  /// let mut received = 4;
+  /// let refill = |rest: &'static str, _: Needed| {
+  ///   if received == data.len() {
+  ///     return None;
+  ///   }
+  ///   let start = received - rest.len();
+  ///   received = (received + 4).min(data.len());
+  ///   Some(&data[start..received])
+  /// };
+  ///
+  /// let word = terminated(take_while1(|c: char| c.is_alphabetic()), char::<_, Error<_>>('|'));
+  /// let mut it = iterator(&data[..4], word).refill(refill);
+  /// assert_eq!(it.by_ref().collect::<Vec<_>>(), vec!["abc", "def", "ghi"]);
+  /// let res: IResult<_, _> = it.finish();
+  /// assert_eq!(res, Ok(("", ())));
+  /// ```
+  pub fn refill<R2>(self, refill: R2) -> ParseStream<I, E, F, R2>
+  where
+    R2: FnMut(I, Needed) -> Option<I>,
+  {
+    ParseStream {
+      parser: self.parser,
+      input: self.input,
+      end: self.end,
+      refill: Some(refill),
+      exhausted: false,
+    }
+  }
+
+  /// Stops the iteration once `token` is set, checking it before parsing each item.
+  ///
+  /// Another thread can set the token to abort the parsing of a slow or malicious
+  /// input. The iteration then stops, and [ParseStream::finish] returns a failure
+  /// with [ErrorKind::Cancelled] at the position of the next item.
+  ///
+  /// ```rust
+  /// # use nom::{Err, error::{Error, ErrorKind}, IResult};
+  /// use core::sync::atomic::{AtomicBool, Ordering};
+  /// use nom::{combinator::iterator, character::complete::{alpha1, char}, sequence::terminated};
+  ///
+  /// let cancel = AtomicBool::new(false);
+  /// let mut it = iterator("abc|def|ghi|", terminated(alpha1, char('|'))).cancel_on(&cancel);
+  ///
+  /// assert_eq!(it.next(), Some("abc"));
+  /// cancel.store(true, Ordering::Relaxed);
+  /// assert_eq!(it.next(), None);
+  ///
+  /// let res: IResult<_, _> = it.finish();
+  /// assert_eq!(res, Err(Err::Failure(Error::new("def|ghi|", ErrorKind::Cancelled))));
+  /// ```
+  pub fn cancel_on(self, token: &AtomicBool) -> CancellableIterator<'_, I, E, F, R> {
+    CancellableIterator {
+      iterator: self,
+      token,
+    }
+  }
+}
+
+impl<Input, Output, Error, F, R> core::iter::Iterator for ParseStream<Input, Error, F, R>
+where
+  F: Parser<Input, Output = Output, Error = Error>,
+  R: FnMut(Input, Needed) -> Option<Input>,
+  Input: Clone,
+{
+  type Item = Output;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.end.is_some() {
+      return None;
+    }
+
+    loop {
+      let input = self.input.clone();
+      let res = if self.exhausted {
+        self.parser.parse_complete(input)
+      } else {
+        self.parser.parse(input)
+      };
+
+      match res {
+        Ok((i, o)) => {
+          self.input = i;
+          return Some(o);
+        }
+        Err(Err::Incomplete(needed)) if !self.exhausted => match self.refill.as_mut() {
+          Some(refill) => match refill(self.input.clone(), needed) {
+            Some(input) => self.input = input,
+            None => self.exhausted = true,
+          },
+          None => {
+            self.end = Some(Err::Incomplete(needed));
+            return None;
+          }
+        },
+        Err(e) => {
+          self.end = Some(e);
+          return None;
+        }
+      }
+    }
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    if self.end.is_some() {
+      (0, Some(0))
+    } else {
+      (0, None)
+    }
+  }
+}
+
+impl<Input, Output, Error, F, R> core::iter::FusedIterator for ParseStream<Input, Error, F, R>
+where
+  F: Parser<Input, Output = Output, Error = Error>,
+  R: FnMut(Input, Needed) -> Option<Input>,
+  Input: Clone,
+{
+}
+
+/// Iterator returned by [ParseStream::cancel_on]
+pub struct CancellableIterator<'t, I, E, F, R = fn(I, Needed) -> Option<I>> {
+  iterator: ParseStream<I, E, F, R>,
+  token: &'t AtomicBool,
+}
+
+impl<'t, I: Clone, E, F, R> CancellableIterator<'t, I, E, F, R> {
+  /// Returns the remaining input if parsing was successful, or the error if we encountered an error.
+  pub fn finish(self) -> IResult<I, (), E> {
+    self.iterator.finish()
+  }
+}
+
+impl<'t, Input, Output, Error, F, R> core::iter::Iterator
+  for CancellableIterator<'t, Input, Error, F, R>
+where
+  F: Parser<Input, Output = Output, Error = Error>,
+  R: FnMut(Input, Needed) -> Option<Input>,
+  Input: Clone,
+  Error: ParseError<Input>,
+{
+  type Item = Output;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.iterator.end.is_none() && self.token.load(Ordering::Relaxed) {
+      let e = Error::from_error_kind(self.iterator.input.clone(), ErrorKind::Cancelled);
+      self.iterator.end = Some(Err::Failure(e));
+      return None;
+    }
+
+    self.iterator.next()
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    self.iterator.size_hint()
+  }
+}
+
+impl<'t, Input, Output, Error, F, R> core::iter::FusedIterator
+  for CancellableIterator<'t, Input, Error, F, R>
+where
+  F: Parser<Input, Output = Output, Error = Error>,
+  R: FnMut(Input, Needed) -> Option<Input>,
+  Input: Clone,
+  Error: ParseError<Input>,
+{
+}
+
+/// Checks `token` before applying the parser, returning a failure with
+/// [ErrorKind::Cancelled] once it is set.
+///
+/// Wrapping the item parser of a repetition lets another thread abort a long parse
+/// between items.
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+/// use core::sync::atomic::AtomicBool;
+/// use nom::{bytes::complete::tag, combinator::cancellable, multi::many0};
+///
+/// let cancel = AtomicBool::new(false);
+/// let mut parser = many0(cancellable(&cancel, tag::<_, _, Error<&str>>("ab")));
+/// assert_eq!(parser.parse("abab"), Ok(("", vec!["ab", "ab"])));
+///
+/// let cancel = AtomicBool::new(true);
+/// let mut parser = many0(cancellable(&cancel, tag::<_, _, Error<&str>>("ab")));
+/// assert_eq!(parser.parse("abab"), Err(Err::Failure(Error::new("abab", ErrorKind::Cancelled))));
+/// ```
+pub fn cancellable<I, F>(token: &AtomicBool, parser: F) -> Cancellable<'_, F>
+where
+  F: Parser<I>,
+{
+  Cancellable { token, parser }
+}
+
+/// Parser implementation for [cancellable]
+pub struct Cancellable<'t, F> {
+  token: &'t AtomicBool,
+  parser: F,
+}
+
+impl<'t, I, F: Parser<I>> Parser<I> for Cancellable<'t, F> {
+  type Output = <F as Parser<I>>::Output;
+  type Error = <F as Parser<I>>::Error;
+
+  fn process<OM: OutputMode>(&mut self, input: I) -> PResult<OM, I, Self::Output, Self::Error> {
+    if self.token.load(Ordering::Relaxed) {
+      return Err(Err::Failure(<F as Parser<I>>::Error::from_error_kind(
+        input,
+        ErrorKind::Cancelled,
+      )));
+    }
+
+    self.parser.process::<OM>(input)
+  }
+}
+
+/// a parser which always succeeds with given value without consuming any input.
+///
+/// It can be used for example as the last alternative in `alt` to
+/// specify the default case.
+///
+/// ```rust
+/// # use nom::{Err,error::ErrorKind, IResult, Parser};
+/// use nom::branch::alt;
+/// use nom::combinator::{success, value};
+/// use nom::character::complete::char;
+/// # fn main() {
+///
+/// let mut parser = success::<_,_,(_,ErrorKind)>(10);
+/// assert_eq!(parser.parse("xyz"), Ok(("xyz", 10)));
+///
+/// let mut sign = alt((value(-1, char('-')), value(1, char('+')), success::<_,_,(_,ErrorKind)>(1)));
+/// assert_eq!(sign.parse("+10"), Ok(("10", 1)));
+/// assert_eq!(sign.parse("-10"), Ok(("10", -1)));
+/// assert_eq!(sign.parse("10"), Ok(("10", 1)));
+/// # }
+/// ```
+pub fn success<I, O: Clone, E: ParseError<I>>(val: O) -> Success<O, E> {
+  Success {
+    val,
+    e: PhantomData,
+  }
+}
+
+/// Parser implementation for [success]
+pub struct Success<O: Clone, E> {
+  val: O,
+  e: PhantomData<E>,
+}
+
+impl<I, O, E> Parser<I> for Success<O, E>
+where
+  O: Clone,
+  E: ParseError<I>,
+{
+  type Output = O;
+  type Error = E;
+
+  fn process<OM: OutputMode>(&mut self, input: I) -> PResult<OM, I, Self::Output, Self::Error> {
+    Ok((input, OM::Output::bind(|| self.val.clone())))
+  }
+}
+
+/// A parser which always fails.
+///
+/// ```rust
+/// # use nom::{Err, error::ErrorKind, IResult, Parser};
+/// use nom::combinator::fail;
+///
+/// let s = "string";
+/// assert_eq!(fail::<_, &str, _>().parse(s), Err(Err::Error((s, ErrorKind::Fail))));
+/// ```
+pub fn fail<I, O, E: ParseError<I>>() -> impl Parser<I, Output = O, Error = E> {
+  Fail {
+    o: PhantomData,
+    e: PhantomData,
+  }
+}
+
+/// Parser implementation for [fail]
+pub struct Fail<O, E> {
+  o: PhantomData<O>,
+  e: PhantomData<E>,
+}
+
+impl<I, O, E> Parser<I> for Fail<O, E>
+where
+  E: ParseError<I>,
+{
+  type Output = O;
+  type Error = E;
+
+  fn process<OM: OutputMode>(&mut self, input: I) -> PResult<OM, I, Self::Output, Self::Error> {
+    Err(Err::Error(OM::Error::bind(|| {
+      E::from_error_kind(input, ErrorKind::Fail)
+    })))
+  }
+}
+
+/// Skips the spaces, tabs, carriage returns and line feeds around the embedded parser.
+///
+/// This is equivalent to `delimited(multispace0, parser, multispace0)`. Use a [Skipper]
+/// to skip other kinds of trivia, like comments.
+///
+/// ```rust
+/// # use nom::{Err, error::ErrorKind, IResult, Parser};
+/// use nom::combinator::padded;
+/// use nom::character::complete::alpha1;
+///
+/// let mut parser = padded(alpha1::<_, (_, ErrorKind)>);
+///
+/// assert_eq!(parser.parse_complete(" \tabc\n 123"), Ok(("123", "abc")));
+/// assert_eq!(parser.parse_complete("abc"), Ok(("", "abc")));
+/// assert_eq!(parser.parse_complete(" 123"), Err(Err::Error(("123", ErrorKind::Alpha))));
+/// ```
+pub fn padded<I, E: ParseError<I>, F>(
+  parser: F,
+) -> impl Parser<I, Output = <F as Parser<I>>::Output, Error = E>
+where
+  I: Input,
+  <I as Input>::Item: AsChar,
+  F: Parser<I, Error = E>,
+{
+  crate::sequence::delimited(
+    crate::character::multispace0(),
+    parser,
+    crate::character::multispace0(),
+  )
+}
+
+/// Creates a [Skipper] from a parser recognizing trivia: whitespace, comments, etc.
+///
+/// The skipper then wraps the token parsers of a grammar with [Skipper::lexeme], which
+/// skips the trivia following each token, and the entry point of the grammar with
+/// [Skipper::padded], which also skips the leading trivia. The trivia parser is applied
+/// repeatedly, until it fails or stops consuming input.
+///
+/// The trivia parser is cloned in every wrapped parser, so it is usually a function
+/// or a closure without captures.
+///
+/// ```rust
+/// # use nom::{Err, error::ErrorKind, IResult, Parser};
+/// use nom::branch::alt;
+/// use nom::bytes::complete::{is_not, tag, take_until};
+/// use nom::character::complete::{alpha1, char, multispace1, u32};
+/// use nom::combinator::skipper;
+/// use nom::sequence::{delimited, separated_pair};
+///
+/// // whitespace, `// line` and `/* block */` comments
+/// fn trivia(i: &str) -> IResult<&str, &str> {
+///   alt((
+///     multispace1,
+///     delimited(tag("//"), is_not("\n"), char('\n')).map(|_| ""),
+///     delimited(tag("/*"), take_until("*/"), tag("*/")),
+///   ))
+///   .parse(i)
+/// }
+///
+/// fn assignment(i: &str) -> IResult<&str, (&str, u32)> {
+///   let ws = skipper(trivia);
+///   ws.padded(separated_pair(
+///     ws.lexeme(alpha1),
+///     ws.lexeme(char('=')),
+///     ws.lexeme(u32),
+///   ))
+///   .parse(i)
+/// }
+///
+/// assert_eq!(assignment("a = 1"), Ok(("", ("a", 1))));
+/// assert_eq!(
+///   assignment("/* answer */ a // comment\n =\t42 ;"),
+///   Ok((";", ("a", 42)))
+/// );
+/// ```
+pub fn skipper<S>(trivia: S) -> Skipper<S> {
+  Skipper { trivia }
+}
+
+/// Trivia skipping wrapper, created by [skipper]
+pub struct Skipper<S> {
+  trivia: S,
+}
+
+impl<S: Clone> Skipper<S> {
+  /// Applies the embedded parser, then skips the trivia following it.
+  pub fn lexeme<F>(&self, parser: F) -> Lexeme<F, S> {
+    Lexeme {
+      parser,
+      trivia: self.trivia.clone(),
+      leading: false,
+    }
+  }
+
+  /// Skips the trivia before and after the embedded parser.
+  pub fn padded<F>(&self, parser: F) -> Lexeme<F, S> {
+    Lexeme {
+      parser,
+      trivia: self.trivia.clone(),
+      leading: true,
+    }
+  }
+}
+
+/// Parser implementation for [Skipper::lexeme] and [Skipper::padded]
+pub struct Lexeme<F, S> {
+  parser: F,
+  trivia: S,
+  leading: bool,
+}
+
+impl<F, S> Lexeme<F, S> {
+  fn skip<I, OM>(&mut self, mut input: I) -> PResult<OM, I, (), <F as Parser<I>>::Error>
+  where
+    I: Clone + Input,
+    F: Parser<I>,
+    S: Parser<I, Error = <F as Parser<I>>::Error>,
+    OM: OutputMode,
+  {
+    loop {
+      let len = input.input_len();
+      match self
+        .trivia
+        .process::<OutputM<Check, Check, OM::Incomplete>>(input.clone())
+      {
+        Ok((tail, _)) => {
+          // stop if the trivia parser does not consume anymore
+          if tail.input_len() == len {
+            return Ok((input, OM::Output::bind(|| ())));
+          }
+          input = tail;
+        }
+        Err(Err::Error(_)) => return Ok((input, OM::Output::bind(|| ()))),
+        Err(Err::Failure(e)) => return Err(Err::Failure(e)),
+        Err(Err::Incomplete(n)) => return Err(Err::Incomplete(n)),
+      }
+    }
+  }
+}
+
+impl<I, F, S> Parser<I> for Lexeme<F, S>
+where
+  I: Clone + Input,
+  F: Parser<I>,
+  S: Parser<I, Error = <F as Parser<I>>::Error>,
+{
+  type Output = <F as Parser<I>>::Output;
+  type Error = <F as Parser<I>>::Error;
+
+  fn process<OM: OutputMode>(&mut self, input: I) -> PResult<OM, I, Self::Output, Self::Error> {
+    let input = if self.leading {
+      self.skip::<I, OM>(input)?.0
+    } else {
+      input
+    };
+
+    let (input, o) = self.parser.process::<OM>(input)?;
+    let (input, _) = self.skip::<I, OM>(input)?;
+
+    Ok((input, o))
+  }
+}
+
+/// Limits of a [Budget]
+///
+/// By default, there is no limit.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Limits {
+  steps: Option<usize>,
+  #[cfg(feature = "std")]
+  time: Option<Duration>,
+}
+
+impl Limits {
+  /// Creates limits that are never exceeded
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Sets the maximum number of steps, counted as calls to the parsers wrapped by
+  /// [with_budget] and [Budget::meter]
+  pub fn steps(mut self, steps: usize) -> Self {
+    self.steps = Some(steps);
+    self
+  }
+
+  /// Sets the maximum duration of a parse, checked at every step
+  #[cfg(feature = "std")]
+  #[cfg_attr(feature = "docsrs", doc(cfg(feature = "std")))]
+  pub fn time(mut self, time: Duration) -> Self {
+    self.time = Some(time);
+    self
+  }
+}
+
+/// Amount of work allowed for a parse, shared by the parsers of a grammar
+///
+/// A parse starts with [with_budget], and every parser wrapped by [Budget::meter]
+/// consumes one step each time it is applied. Once the [Limits] are exceeded,
+/// those parsers return a failure with [ErrorKind::Budget], aborting the parse.
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+/// use nom::branch::alt;
+/// use nom::character::complete::char;
+/// use nom::combinator::{with_budget, Budget, Limits};
+/// use nom::multi::many0;
+///
+/// let budget = Budget::new(Limits::new().steps(4));
+/// let mut parser = with_budget(&budget, many0(budget.meter(alt((char('a'), char('b'))))));
+///
+/// // one step for `with_budget`, then one for each call to the metered parser
+/// assert_eq!(parser.parse("ab"), Ok(("", vec!['a', 'b'])));
+/// assert_eq!(budget.used(), 4);
+/// assert_eq!(parser.parse("abab"), Err(Err::Failure(Error::new("b", ErrorKind::Budget))));
+/// ```
+#[derive(Debug)]
+pub struct Budget {
+  limits: Limits,
+  steps: Cell<usize>,
+  #[cfg(feature = "std")]
+  start: Cell<Option<Instant>>,
+}
+
+impl Budget {
+  /// Creates a budget with the given limits
+  pub fn new(limits: Limits) -> Self {
+    Budget {
+      limits,
+      steps: Cell::new(0),
+      #[cfg(feature = "std")]
+      start: Cell::new(None),
+    }
+  }
+
+  /// Number of steps used by the current or last parse
+  pub fn used(&self) -> usize {
+    self.steps.get()
+  }
+
+  /// Wraps a parser, consuming one step of the budget each time it is applied
+  pub fn meter<F>(&self, parser: F) -> Metered<'_, F> {
+    Metered {
+      budget: self,
+      parser,
+    }
+  }
+
+  fn reset(&self) {
+    self.steps.set(0);
+    #[cfg(feature = "std")]
+    self.start.set(self.limits.time.map(|_| Instant::now()));
+  }
+
+  /// Consumes one step, returns `false` if the limits are exceeded
+  fn charge(&self) -> bool {
+    let steps = self.steps.get() + 1;
+    self.steps.set(steps);
+
+    if let Some(max) = self.limits.steps {
+      if steps > max {
+        return false;
+      }
+    }
+
+    #[cfg(feature = "std")]
+    if let (Some(start), Some(time)) = (self.start.get(), self.limits.time) {
+      if start.elapsed() > time {
+        return false;
+      }
+    }
+
+    true
+  }
+
+  fn apply<I, F: Parser<I>, OM: OutputMode>(
+    &self,
+    parser: &mut F,
+    input: I,
+  ) -> PResult<OM, I, <F as Parser<I>>::Output, <F as Parser<I>>::Error> {
+    if self.charge() {
+      parser.process::<OM>(input)
+    } else {
+      Err(Err::Failure(<F as Parser<I>>::Error::from_error_kind(
+        input,
+        ErrorKind::Budget,
+      )))
+    }
+  }
+}
+
+/// Applies a parser within the limits of a [Budget].
+///
+/// The budget is reset each time this parser is applied, then it consumes one step. If
+/// the limits are exceeded during the parse, this returns a failure with
+/// [ErrorKind::Budget]. See [Budget] for an example.
+pub fn with_budget<'b, I, E: ParseError<I>, F>(
+  budget: &'b Budget,
+  parser: F,
+) -> impl Parser<I, Output = <F as Parser<I>>::Output, Error = E> + 'b
+where
+  F: Parser<I, Error = E> + 'b,
+{
+  WithBudget { budget, parser }
+}
+
+/// Parser implementation for [with_budget]
+pub struct WithBudget<'b, F> {
+  budget: &'b Budget,
+  parser: F,
+}
+
+impl<'b, I, F: Parser<I>> Parser<I> for WithBudget<'b, F> {
+  type Output = <F as Parser<I>>::Output;
+  type Error = <F as Parser<I>>::Error;
+
+  fn process<OM: OutputMode>(&mut self, input: I) -> PResult<OM, I, Self::Output, Self::Error> {
+    self.budget.reset();
+    self.budget.apply::<I, F, OM>(&mut self.parser, input)
+  }
+}
+
+/// Parser implementation for [Budget::meter]
+pub struct Metered<'b, F> {
+  budget: &'b Budget,
+  parser: F,
+}
+
+impl<'b, I, F: Parser<I>> Parser<I> for Metered<'b, F> {
+  type Output = <F as Parser<I>>::Output;
+  type Error = <F as Parser<I>>::Error;
+
+  fn process<OM: OutputMode>(&mut self, input: I) -> PResult<OM, I, Self::Output, Self::Error> {
+    self.budget.apply::<I, F, OM>(&mut self.parser, input)
+  }
+}
+
+/// Applies a parser on a [Fueled] input, with `budget` units of fuel
+///
+/// The fuel of the input is refilled to `budget` each time this parser is applied. Every
+/// access of the parsers to the input burns one unit, so unlike [with_budget], the
+/// parsers of the grammar do not need to be metered one by one. If the fuel runs out,
+/// the input looks empty to the parsers, which stop quickly, and this returns a failure
+/// with [ErrorKind::Budget], whatever the result of the parser.
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+/// use nom::{Fuel, Fueled};
+/// use nom::branch::alt;
+/// use nom::bytes::complete::tag;
+/// use nom::character::complete::char;
+/// use nom::combinator::{value, with_fuel};
+/// use nom::sequence::delimited;
+///
+/// // backtracking grammar: `(((x)))` is parsed as a pair then as a group at each level
+/// fn expr<'a, 'f>(i: Fueled<'f, &'a str>) -> IResult<Fueled<'f, &'a str>, ()> {
+///   alt((
+///     value((), (char('('), expr, char(','), expr, char(')'))),
+///     value((), delimited(char('('), expr, char(')'))),
+///     value((), tag("x")),
+///   ))
+///   .parse(i)
+/// }
+///
+/// let nested = format!("{}x{}", "(".repeat(30), ")".repeat(30));
+/// let fuel = Fuel::new(0);
+/// let mut parser = with_fuel(1000, expr);
+///
+/// let (rest, _) = parser.parse(Fueled::new("((x))", &fuel)).unwrap();
+/// assert_eq!(rest.into_inner(), "");
+///
+/// match parser.parse(Fueled::new(&nested, &fuel)) {
+///   Err(Err::Failure(e)) => assert_eq!(e.code, ErrorKind::Budget),
+///   res => panic!("unexpected result: {:?}", res),
+/// }
+/// assert!(fuel.is_exhausted());
+/// ```
+pub fn with_fuel<'f, I, E, F>(
+  budget: usize,
+  parser: F,
+) -> impl Parser<Fueled<'f, I>, Output = <F as Parser<Fueled<'f, I>>>::Output, Error = E>
+where
+  I: Clone,
+  E: ParseError<Fueled<'f, I>>,
+  F: Parser<Fueled<'f, I>, Error = E>,
+{
+  WithFuel { budget, parser }
+}
+
+/// Parser implementation for [with_fuel]
+pub struct WithFuel<F> {
+  budget: usize,
+  parser: F,
+}
+
+impl<'f, I, F> Parser<Fueled<'f, I>> for WithFuel<F>
+where
+  I: Clone,
+  F: Parser<Fueled<'f, I>>,
+{
+  type Output = <F as Parser<Fueled<'f, I>>>::Output;
+  type Error = <F as Parser<Fueled<'f, I>>>::Error;
+
+  fn process<OM: OutputMode>(
+    &mut self,
+    input: Fueled<'f, I>,
+  ) -> PResult<OM, Fueled<'f, I>, Self::Output, Self::Error> {
+    let fuel = input.fuel();
+    fuel.refill(self.budget);
+
+    match self.parser.process::<OM>(input.clone()) {
+      _ if fuel.is_exhausted() => Err(Err::Failure(Self::Error::from_error_kind(
+        input,
+        ErrorKind::Budget,
+      ))),
+      res => res,
+    }
+  }
+}
+
+/// Maximum nesting depth of recursive parsers, shared by the parsers of a grammar
+///
+/// A recursive descent parser uses the call stack for each level of nesting, so deeply
+/// nested input like `((((…))))` can overflow the stack. Every parser wrapped by
+/// [DepthLimit::nest] adds one level while it is applied. Once the maximum depth is
+/// exceeded, it returns a failure with [ErrorKind::Depth] instead of recursing
+/// further.
+///
+/// The level is entered before the wrapped parser runs, so the failure happens even if
+/// that parser would not have matched.
+/// [delimited_with_depth](crate::sequence::delimited_with_depth) only enters it once the
+/// opening delimiter is found.
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+/// use nom::branch::alt;
+/// use nom::character::complete::{char, digit1};
+/// use nom::combinator::DepthLimit;
+/// use nom::sequence::delimited;
+///
+/// fn value<'a>(limit: &DepthLimit, i: &'a str) -> IResult<&'a str, &'a str> {
+///   alt((
+///     digit1,
+///     limit.nest(delimited(char('['), |i| value(limit, i), char(']'))),
+///   ))
+///   .parse(i)
+/// }
+///
+/// let limit = DepthLimit::new(2);
+/// assert_eq!(value(&limit, "[[1]]"), Ok(("", "1")));
+/// assert_eq!(value(&limit, "[[[1]]]"), Err(Err::Failure(Error::new("[1]]]", ErrorKind::Depth))));
+/// assert_eq!(limit.depth(), 0);
+/// ```
+#[derive(Debug)]
+pub struct DepthLimit {
+  max: usize,
+  depth: Cell<usize>,
+}
+
+impl DepthLimit {
+  /// Creates a limit allowing `max` nested levels
+  pub fn new(max: usize) -> Self {
+    DepthLimit {
+      max,
+      depth: Cell::new(0),
+    }
+  }
+
+  /// Maximum number of nested levels
+  pub fn max(&self) -> usize {
+    self.max
+  }
+
+  /// Number of levels entered by the parsers currently running
+  pub fn depth(&self) -> usize {
+    self.depth.get()
+  }
+
+  /// Wraps a parser, adding one level of nesting while it is applied
+  pub fn nest<F>(&self, parser: F) -> Nest<'_, F> {
+    Nest {
+      limit: self,
+      parser,
+    }
+  }
+
+  fn apply<I, F: Parser<I>, OM: OutputMode>(
+    &self,
+    parser: &mut F,
+    input: I,
+  ) -> PResult<OM, I, <F as Parser<I>>::Output, <F as Parser<I>>::Error> {
+    let depth = self.depth.get();
+    if depth >= self.max {
+      return Err(Err::Failure(<F as Parser<I>>::Error::from_error_kind(
+        input,
+        ErrorKind::Depth,
+      )));
+    }
+
+    self.depth.set(depth + 1);
+    let res = parser.process::<OM>(input);
+    self.depth.set(depth);
+    res
+  }
+}
+
+/// Parser implementation for [DepthLimit::nest]
+pub struct Nest<'d, F> {
+  limit: &'d DepthLimit,
+  parser: F,
+}
+
+impl<'d, I, F: Parser<I>> Parser<I> for Nest<'d, F> {
+  type Output = <F as Parser<I>>::Output;
+  type Error = <F as Parser<I>>::Error;
+
+  fn process<OM: OutputMode>(&mut self, input: I) -> PResult<OM, I, Self::Output, Self::Error> {
+    self.limit.apply::<I, F, OM>(&mut self.parser, input)
+  }
+}
+
+#[cfg(feature = "std")]
+std::thread_local! {
+  static DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Limits the nesting depth of a recursive parser, using a counter local to the current thread
+///
+/// Recursive parsers written as functions build a new parser at each level, so they
+/// cannot easily share a [DepthLimit]. `depth_limited` instead counts the levels
+/// entered by all the `depth_limited` parsers running on the current thread. Once
+/// more than `max` levels are nested, it returns a failure with [ErrorKind::Depth]
+/// instead of recursing further.
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+/// use nom::branch::alt;
+/// use nom::character::complete::{char, digit1};
+/// use nom::combinator::depth_limited;
+/// use nom::sequence::delimited;
+///
+/// fn value(i: &str) -> IResult<&str, &str> {
+///   alt((
+///     digit1,
+///     depth_limited(2, delimited(char('('), value, char(')'))),
+///   ))
+///   .parse(i)
+/// }
+///
+/// assert_eq!(value("((1))"), Ok(("", "1")));
+/// assert_eq!(value("(((1)))"), Err(Err::Failure(Error::new("(1)))", ErrorKind::Depth))));
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "std")))]
+pub fn depth_limited<I, F>(max: usize, parser: F) -> DepthLimited<F>
+where
+  F: Parser<I>,
+{
+  DepthLimited { max, parser }
+}
+
+/// Parser implementation for [depth_limited]
+#[cfg(feature = "std")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "std")))]
+pub struct DepthLimited<F> {
+  max: usize,
+  parser: F,
+}
+
+#[cfg(feature = "std")]
+impl<I, F: Parser<I>> Parser<I> for DepthLimited<F> {
+  type Output = <F as Parser<I>>::Output;
+  type Error = <F as Parser<I>>::Error;
+
+  fn process<OM: OutputMode>(&mut self, input: I) -> PResult<OM, I, Self::Output, Self::Error> {
+    let depth = DEPTH.with(|d| d.get());
+    if depth >= self.max {
+      return Err(Err::Failure(<F as Parser<I>>::Error::from_error_kind(
+        input,
+        ErrorKind::Depth,
+      )));
+    }
+
+    DEPTH.with(|d| d.set(depth + 1));
+    let res = self.parser.process::<OM>(input);
+    DEPTH.with(|d| d.set(depth));
+    res
+  }
+}
+
+/// Progress of a parse, reported by [with_progress]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+  /// Number of input elements consumed so far
+  pub offset: usize,
+  /// Total length of the input, if known
+  pub total: Option<usize>,
+}
+
+impl Progress {
+  /// Percentage of the input consumed, if the total length is known
+  pub fn percentage(&self) -> Option<f64> {
+    self.total.map(|total| {
+      if total == 0 {
+        100.0
+      } else {
+        self.offset as f64 * 100.0 / total as f64
+      }
+    })
+  }
+}
+
+/// Calls `callback` every time the wrapped parser has consumed `every` more input elements
+///
+/// The wrapped parser is usually the item parser of a repetition like [many0](crate::multi::many0),
+/// so that the callback is called regularly while parsing a large input. The offset counts
+/// the elements consumed by all the successful applications of the wrapped parser,
+/// and `total` is passed as is to the callback to compute a percentage.
+///
+/// If the callback returns [ControlFlow::Break], the parse is cancelled with a failure
+/// with [ErrorKind::Cancelled].
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+/// use core::ops::ControlFlow;
+/// use nom::bytes::complete::tag;
+/// use nom::combinator::{with_progress, Progress};
+/// use nom::multi::many0;
+///
+/// let input = "abababab";
+/// let mut reports = Vec::new();
+/// let mut parser = many0(with_progress(4, Some(input.len()), |p: Progress| {
+///   reports.push(p.percentage());
+///   ControlFlow::Continue(())
+/// }, tag::<_, _, Error<&str>>("ab")));
+/// assert_eq!(parser.parse(input), Ok(("", vec!["ab"; 4])));
+/// drop(parser);
+/// assert_eq!(reports, [Some(50.0), Some(100.0)]);
+///
+/// let mut parser = many0(with_progress(2, None, |p: Progress| {
+///   if p.offset >= 4 { ControlFlow::Break(()) } else { ControlFlow::Continue(()) }
+/// }, tag::<_, _, Error<&str>>("ab")));
+/// assert_eq!(parser.parse(input), Err(Err::Failure(Error::new("abab", ErrorKind::Cancelled))));
+/// ```
+pub fn with_progress<I, F, C>(
+  every: usize,
+  total: Option<usize>,
+  callback: C,
+  parser: F,
+) -> WithProgress<F, C>
+where
+  I: Input,
+  F: Parser<I>,
+  C: FnMut(Progress) -> ControlFlow<()>,
+{
+  WithProgress {
+    every: every.max(1),
+    total,
+    callback,
+    parser,
+    offset: 0,
+    next: every.max(1),
+  }
+}
+
+/// Parser implementation for [with_progress]
+pub struct WithProgress<F, C> {
+  every: usize,
+  total: Option<usize>,
+  callback: C,
+  parser: F,
+  offset: usize,
+  next: usize,
+}
+
+impl<I, F, C> Parser<I> for WithProgress<F, C>
+where
+  I: Input,
+  F: Parser<I>,
+  C: FnMut(Progress) -> ControlFlow<()>,
+{
+  type Output = <F as Parser<I>>::Output;
+  type Error = <F as Parser<I>>::Error;
+
+  fn process<OM: OutputMode>(&mut self, input: I) -> PResult<OM, I, Self::Output, Self::Error> {
+    let len = input.input_len();
+    let (i, o) = self.parser.process::<OM>(input)?;
+    self.offset += len.saturating_sub(i.input_len());
+
+    if self.offset >= self.next {
+      self.next = (self.offset / self.every + 1) * self.every;
+      let progress = Progress {
+        offset: self.offset,
+        total: self.total,
+      };
+      if (self.callback)(progress).is_break() {
+        return Err(Err::Failure(<F as Parser<I>>::Error::from_error_kind(
+          i,
+          ErrorKind::Cancelled,
+        )));
+      }
+    }
+
+    Ok((i, o))
+  }
+}
+
+/// Reports when the parser is entered and exited, to debug a grammar.
+///
+/// With the `trace` feature, every application of the parser prints an enter event
+/// with the remaining input, then an exit event with the number of consumed elements,
+/// or the kind of error. The events are indented by the number of `trace` parsers
+/// running. With the `tracing` feature, they are emitted as `tracing` events at the
+/// trace level with the `nom` target instead.
+///
+/// Without those features, `trace` only applies the parser, so it can be left in the
+/// grammar.
+///
+/// ```rust
+/// # use nom::{IResult, Parser};
+/// use nom::combinator::trace;
+/// use nom::character::complete::{alpha1, digit1};
+///
+/// fn word(i: &str) -> IResult<&str, (&str, &str)> {
+///   trace("word", (trace("alpha", alpha1), trace("digit", digit1))).parse(i)
+/// }
+///
+/// // With the `trace` feature, prints:
+/// // > word "abc123;"
+/// //   > alpha "abc123;"
+/// //   < alpha ok, consumed 3
+/// //   > digit "123;"
+/// //   < digit ok, consumed 3
+/// // < word ok, consumed 6
+/// assert_eq!(word("abc123;"), Ok((";", ("abc", "123"))));
+/// ```
+pub fn trace<I, F>(name: &'static str, parser: F) -> Trace<F>
+where
+  I: Input + core::fmt::Debug,
+  F: Parser<I>,
+{
+  Trace { name, parser }
+}
+
+/// Parser implementation for [trace]
+pub struct Trace<F> {
+  #[cfg_attr(not(feature = "trace"), allow(dead_code))]
+  name: &'static str,
+  parser: F,
+}
+
+impl<I, F> Parser<I> for Trace<F>
+where
+  I: Input + core::fmt::Debug,
+  F: Parser<I>,
+{
+  type Output = <F as Parser<I>>::Output;
+  type Error = <F as Parser<I>>::Error;
+
+  #[cfg(not(feature = "trace"))]
+  fn process<OM: OutputMode>(&mut self, input: I) -> PResult<OM, I, Self::Output, Self::Error> {
+    self.parser.process::<OM>(input)
+  }
+
+  #[cfg(feature = "trace")]
+  fn process<OM: OutputMode>(&mut self, input: I) -> PResult<OM, I, Self::Output, Self::Error> {
+    let len = input.input_len();
+    let preview = input.take(len.min(TRACE_PREVIEW));
+    trace_event(self.name, format_args!("> {} {:?}", self.name, preview), 0);
+
+    let res = self.parser.process::<OM>(input);
+    match &res {
+      Ok((i, _)) => trace_event(
+        self.name,
+        format_args!("< {} ok, consumed {}", self.name, len - i.input_len()),
+        -1,
+      ),
+      Err(Err::Error(_)) => trace_event(self.name, format_args!("< {} error", self.name), -1),
+      Err(Err::Failure(_)) => trace_event(self.name, format_args!("< {} failure", self.name), -1),
+      Err(Err::Incomplete(n)) => trace_event(
+        self.name,
+        format_args!("< {} incomplete, {:?}", self.name, n),
+        -1,
+      ),
+    }
+    res
+  }
+}
+
+/// Number of input elements printed by [trace]
+#[cfg(feature = "trace")]
+const TRACE_PREVIEW: usize = 32;
+
+#[cfg(all(feature = "trace", not(feature = "tracing")))]
+std::thread_local! {
+  static TRACE_DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Prints a [trace] event, entering a level if `level` is 0 and leaving it if it is -1
+#[cfg(all(feature = "trace", not(feature = "tracing")))]
+fn trace_event(_name: &str, event: core::fmt::Arguments<'_>, level: isize) {
+  let depth = TRACE_DEPTH.with(|d| d.get());
+  let depth = if level < 0 {
+    depth.saturating_sub(1)
+  } else {
+    depth
+  };
+  eprint_event(event, depth);
+  TRACE_DEPTH.with(|d| d.set(if level < 0 { depth } else { depth + 1 }));
+}
+
+/// Emits a [trace] event with the `tracing` crate
+#[cfg(feature = "tracing")]
+fn trace_event(name: &str, event: core::fmt::Arguments<'_>, _level: isize) {
+  tracing_event(name, event, tracing::Level::TRACE);
+}
+
+/// Prints an event of [trace] or [Parser::dbg] to stderr, indented by `depth` levels
+#[cfg(any(feature = "debug", all(feature = "trace", not(feature = "tracing"))))]
+fn eprint_event(event: core::fmt::Arguments<'_>, depth: usize) {
+  std::eprintln!("{:indent$}{}", "", event, indent = depth * 2);
+}
+
+/// Emits an event of [trace] or [Parser::dbg] with the `tracing` crate, with the `nom`
+/// target, at the debug level for `Level::DEBUG` and at the trace level otherwise
+#[cfg(feature = "tracing")]
+fn tracing_event(name: &str, event: core::fmt::Arguments<'_>, level: tracing::Level) {
+  if level == tracing::Level::DEBUG {
+    tracing::debug!(target: "nom", parser = name, "{}", event);
+  } else {
+    tracing::trace!(target: "nom", parser = name, "{}", event);
+  }
+}
+
+/// Calls hooks with the input and the result of a parser, to debug a grammar.
+///
+/// With the `debug` feature, `on_ok` receives the input given to the parser and its
+/// output, and `on_err` receives that input and the error. The parser then always
+/// builds its output and errors, even in the places where they would be discarded.
+///
+/// Without that feature, `inspect` only applies the parser and the hooks are never
+/// called, so it can be left in the grammar.
+///
+/// ```rust
+/// # use nom::{Err, IResult, Parser};
+/// use nom::combinator::inspect;
+/// use nom::character::complete::digit1;
+/// use std::cell::RefCell;
+///
+/// let seen = RefCell::new(Vec::new());
+/// let mut parser = inspect(
+///   digit1::<_, nom::error::Error<_>>,
+///   |i: &&str, o: &&str| seen.borrow_mut().push(format!("{:?} -> {:?}", i, o)),
+///   |i: &&str, e: &Err<_>| seen.borrow_mut().push(format!("{:?} -> {:?}", i, e)),
+/// );
+///
+/// assert_eq!(parser.parse("12;"), Ok((";", "12")));
+/// assert!(parser.parse(";").is_err());
+/// # #[cfg(feature = "debug")]
+/// assert_eq!(
+///   *seen.borrow(),
+///   [
+///     r#""12;" -> "12""#,
+///     r#"";" -> Error(Error { input: ";", code: Digit })"#
+///   ]
+/// );
+/// ```
+pub fn inspect<I, F, G, H>(parser: F, on_ok: G, on_err: H) -> Inspect<F, G, H>
+where
+  I: Clone,
+  F: Parser<I>,
+  G: FnMut(&I, &<F as Parser<I>>::Output),
+  H: FnMut(&I, &Err<<F as Parser<I>>::Error>),
+{
+  Inspect {
+    parser,
+    on_ok,
+    on_err,
+  }
+}
+
+/// Parser implementation for [inspect]
+pub struct Inspect<F, G, H> {
+  parser: F,
+  #[cfg_attr(not(feature = "debug"), allow(dead_code))]
+  on_ok: G,
+  #[cfg_attr(not(feature = "debug"), allow(dead_code))]
+  on_err: H,
+}
+
+impl<I, F, G, H> Parser<I> for Inspect<F, G, H>
+where
+  I: Clone,
+  F: Parser<I>,
+  G: FnMut(&I, &<F as Parser<I>>::Output),
+  H: FnMut(&I, &Err<<F as Parser<I>>::Error>),
+{
+  type Output = <F as Parser<I>>::Output;
+  type Error = <F as Parser<I>>::Error;
+
+  #[cfg(not(feature = "debug"))]
+  fn process<OM: OutputMode>(&mut self, input: I) -> PResult<OM, I, Self::Output, Self::Error> {
+    self.parser.process::<OM>(input)
+  }
+
+  #[cfg(feature = "debug")]
+  fn process<OM: OutputMode>(&mut self, input: I) -> PResult<OM, I, Self::Output, Self::Error> {
+    match self
+      .parser
+      .process::<OutputM<Emit, Emit, OM::Incomplete>>(input.clone())
+    {
+      Ok((i, o)) => {
+        (self.on_ok)(&input, &o);
+        Ok((i, OM::Output::bind(|| o)))
+      }
+      Err(e) => {
+        (self.on_err)(&input, &e);
+        Err(match e {
+          Err::Error(e) => Err::Error(OM::Error::bind(|| e)),
+          Err::Failure(e) => Err::Failure(e),
+          Err::Incomplete(n) => Err::Incomplete(n),
+        })
+      }
+    }
+  }
+}
+
+/// Parser implementation for [Parser::dbg]
+pub struct Dbg<F> {
+  pub(crate) parser: F,
+  #[cfg_attr(not(feature = "debug"), allow(dead_code))]
+  pub(crate) label: &'static str,
+}
+
+impl<I, F> Parser<I> for Dbg<F>
+where
+  I: Clone + fmt::Debug,
+  F: Parser<I>,
+  <F as Parser<I>>::Error: fmt::Debug,
+{
+  type Output = <F as Parser<I>>::Output;
+  type Error = <F as Parser<I>>::Error;
+
+  #[cfg(not(feature = "debug"))]
+  fn process<OM: OutputMode>(&mut self, input: I) -> PResult<OM, I, Self::Output, Self::Error> {
+    self.parser.process::<OM>(input)
+  }
+
+  #[cfg(feature = "debug")]
+  fn process<OM: OutputMode>(&mut self, input: I) -> PResult<OM, I, Self::Output, Self::Error> {
+    let label = self.label;
+    let res = self
+      .parser
+      .process::<OutputM<OM::Output, Emit, OM::Incomplete>>(input.clone());
+
+    let input = DbgPreview(&input);
+    let sink = *DBG_SINK.read().unwrap_or_else(|e| e.into_inner());
+    match &res {
+      Ok((i, _)) => sink(
+        label,
+        format_args!("{}: {} -> ok, rest {}", label, input, DbgPreview(i)),
+      ),
+      Err(Err::Error(e)) => sink(
+        label,
+        format_args!("{}: {} -> error {}", label, input, DbgPreview(e)),
+      ),
+      Err(Err::Failure(e)) => sink(
+        label,
+        format_args!("{}: {} -> failure {}", label, input, DbgPreview(e)),
+      ),
+      Err(Err::Incomplete(n)) => sink(
+        label,
+        format_args!("{}: {} -> incomplete, {:?}", label, input, n),
+      ),
     }
+
+    res.map_err(|e| match e {
+      Err::Error(e) => Err::Error(OM::Error::bind(|| e)),
+      Err::Failure(e) => Err::Failure(e),
+      Err::Incomplete(n) => Err::Incomplete(n),
+    })
   }
 }
 
-/// automatically converts the child parser's result to another type
+/// Receives the events of [Parser::dbg]: the label of the parser, and the message
+pub type DbgSink = fn(&'static str, fmt::Arguments<'_>);
+
+#[cfg(feature = "debug")]
+static DBG_SINK: std::sync::RwLock<DbgSink> = std::sync::RwLock::new(dbg_stderr);
+
+/// Sends the events of [Parser::dbg] to `sink`, returning the previous sink
 ///
-/// it will be able to convert the output value and the error value
-/// as long as the `Into` implementations are available
+/// The sink is shared by all threads, and starts as [dbg_stderr].
 ///
 /// ```rust
 /// # use nom::{IResult, Parser};
-/// use nom::combinator::into;
+/// use nom::combinator::{dbg_stderr, set_dbg_sink};
 /// use nom::character::complete::alpha1;
-/// # fn main() {
 ///
-/// fn parser1(i: &str) -> IResult<&str, &str> {
-///   alpha1(i)
-/// }
-///
-/// let mut parser2 = into(parser1);
+/// fn ignore(_label: &'static str, _message: std::fmt::Arguments<'_>) {}
 ///
-/// // the parser converts the &str output of the child parser into a Vec<u8>
-/// let bytes: IResult<&str, Vec<u8>> = parser2.parse("abcd");
-/// assert_eq!(bytes, Ok(("", vec![97, 98, 99, 100])));
-/// # }
+/// let previous = set_dbg_sink(ignore);
+/// let res: IResult<&str, &str> = alpha1.dbg("name").parse("abc1");
+/// assert_eq!(res, Ok(("1", "abc")));
+/// set_dbg_sink(previous);
 /// ```
-pub fn into<I, O1, O2, E1, E2, F>(parser: F) -> impl Parser<I, Output = O2, Error = E2>
-where
-  O2: From<O1>,
-  E2: From<E1>,
-  E1: ParseError<I>,
-  E2: ParseError<I>,
-  F: Parser<I, Output = O1, Error = E1>,
-{
-  parser.into::<O2, E2>()
+#[cfg(feature = "debug")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "debug")))]
+pub fn set_dbg_sink(sink: DbgSink) -> DbgSink {
+  let mut current = DBG_SINK.write().unwrap_or_else(|e| e.into_inner());
+  core::mem::replace(&mut *current, sink)
 }
 
-/// Creates an iterator from input data and a parser.
-///
-/// Call the iterator's [ParserIterator::finish] method to get the remaining input if successful,
-/// or the error value if we encountered an error.
+/// Prints the events of [Parser::dbg] to stderr
+#[cfg(feature = "debug")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "debug")))]
+pub fn dbg_stderr(_label: &'static str, message: fmt::Arguments<'_>) {
+  eprint_event(message, 0);
+}
+
+/// Emits the events of [Parser::dbg] as `log` records at the debug level, with the
+/// `nom` target
+#[cfg(all(feature = "debug", feature = "log"))]
+#[cfg_attr(feature = "docsrs", doc(cfg(all(feature = "debug", feature = "log"))))]
+pub fn dbg_log(_label: &'static str, message: fmt::Arguments<'_>) {
+  log::debug!(target: "nom", "{}", message);
+}
+
+/// Emits the events of [Parser::dbg] as `tracing` events at the debug level, with the
+/// `nom` target
+#[cfg(all(feature = "debug", feature = "tracing"))]
+#[cfg_attr(
+  feature = "docsrs",
+  doc(cfg(all(feature = "debug", feature = "tracing")))
+)]
+pub fn dbg_tracing(label: &'static str, message: fmt::Arguments<'_>) {
+  tracing_event(label, message, tracing::Level::DEBUG);
+}
+
+/// Number of characters of the input and errors printed by [Parser::dbg]
+#[cfg(feature = "debug")]
+const DBG_PREVIEW: usize = 64;
+
+/// Shows the `Debug` representation of a value, cut after [DBG_PREVIEW] characters
+#[cfg(feature = "debug")]
+struct DbgPreview<'a, T>(&'a T);
+
+#[cfg(feature = "debug")]
+impl<'a, T: fmt::Debug> fmt::Display for DbgPreview<'a, T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    struct Truncate<'a, 'b> {
+      f: &'a mut fmt::Formatter<'b>,
+      left: usize,
+    }
+
+    impl<'a, 'b> fmt::Write for Truncate<'a, 'b> {
+      fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+          if self.left == 0 {
+            return Err(fmt::Error);
+          }
+          self.left -= 1;
+          self.f.write_char(c)?;
+        }
+        Ok(())
+      }
+    }
+
+    let mut w = Truncate {
+      f,
+      left: DBG_PREVIEW,
+    };
+    // the representation was cut if the writer stopped it
+    if fmt::write(&mut w, format_args!("{:?}", self.0)).is_err() && w.left == 0 {
+      w.f.write_str("...")?;
+    }
+    Ok(())
+  }
+}
+
+/// Statistics collected by a [Profiler] for one label
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "alloc")))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProfileStats {
+  /// Number of times the parser was applied
+  pub calls: usize,
+  /// Number of `Err::Error` results
+  pub errors: usize,
+  /// Number of `Err::Failure` results
+  pub failures: usize,
+  /// Number of `Err::Incomplete` results
+  pub incomplete: usize,
+  /// Number of input elements consumed by the successful applications
+  pub consumed: usize,
+  /// Number of applications at a position where the parser was already applied
+  pub repeated_calls: usize,
+  /// Number of input elements consumed again by the repeated applications
+  pub reexamined: usize,
+  /// Maximum number of nested applications of the parser
+  pub max_depth: usize,
+}
+
+#[cfg(feature = "alloc")]
+#[derive(Default)]
+struct ProfileEntry {
+  stats: ProfileStats,
+  depth: usize,
+  positions: crate::lib::std::collections::BTreeSet<usize>,
+}
+
+/// Collects statistics on the parsers of a grammar, to find where it spends its time
 ///
-/// On [`Err::Error`], iteration will stop. To instead chain an error up, see [`cut`].
+/// Every parser wrapped by [Profiler::profile] counts its applications and results
+/// under a label. Positions are identified by the length of the remaining input, so
+/// applying a parser again at the same position is recorded as a repeated call, and
+/// the input it consumes again as reexamined. A high count points to an `alt` or
+/// `peek` that parses the same input several times.
 ///
 /// ```rust
-/// use nom::{combinator::iterator, IResult, bytes::complete::tag, character::complete::alpha1, sequence::terminated};
-/// use std::collections::HashMap;
+/// # use nom::{IResult, Parser};
+/// use nom::branch::alt;
+/// use nom::bytes::complete::tag;
+/// use nom::character::complete::alpha1;
+/// use nom::combinator::Profiler;
+/// use nom::sequence::terminated;
+///
+/// let profiler = Profiler::new();
+/// let mut parser = alt((
+///   terminated(profiler.profile("word", alpha1), tag(";")),
+///   terminated(profiler.profile("word", alpha1), tag(".")),
+/// ));
+/// let res: IResult<_, _> = parser.parse("abc.");
+/// assert_eq!(res, Ok(("", "abc")));
+///
+/// let stats = profiler.stats("word").unwrap();
+/// assert_eq!(stats.calls, 2);
+/// assert_eq!(stats.repeated_calls, 1);
+/// assert_eq!(stats.reexamined, 3);
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "alloc")))]
+#[derive(Default)]
+pub struct Profiler {
+  entries: core::cell::RefCell<crate::lib::std::collections::BTreeMap<&'static str, ProfileEntry>>,
+}
+
+#[cfg(feature = "alloc")]
+impl Profiler {
+  /// Creates a profiler without statistics
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Wraps a parser, recording its applications under `label`
+  pub fn profile<F>(&self, label: &'static str, parser: F) -> Profiled<'_, F> {
+    Profiled {
+      profiler: self,
+      label,
+      parser,
+    }
+  }
+
+  /// Returns the statistics recorded under `label`
+  pub fn stats(&self, label: &str) -> Option<ProfileStats> {
+    self.entries.borrow().get(label).map(|e| e.stats)
+  }
+
+  /// Returns the statistics of all labels, sorted by label
+  pub fn report(&self) -> crate::lib::std::vec::Vec<(&'static str, ProfileStats)> {
+    self
+      .entries
+      .borrow()
+      .iter()
+      .map(|(label, e)| (*label, e.stats))
+      .collect()
+  }
+
+  /// Removes all the statistics
+  pub fn reset(&self) {
+    self.entries.borrow_mut().clear();
+  }
+
+  fn enter(&self, label: &'static str, position: usize) -> bool {
+    let mut entries = self.entries.borrow_mut();
+    let entry = entries.entry(label).or_default();
+    entry.stats.calls += 1;
+    entry.depth += 1;
+    entry.stats.max_depth = entry.stats.max_depth.max(entry.depth);
+
+    let repeated = !entry.positions.insert(position);
+    if repeated {
+      entry.stats.repeated_calls += 1;
+    }
+    repeated
+  }
+
+  fn exit<I: Input, O, E, F>(
+    &self,
+    label: &'static str,
+    position: usize,
+    repeated: bool,
+    res: &Result<(I, O), Err<E, F>>,
+  ) {
+    let mut entries = self.entries.borrow_mut();
+    let entry = entries.entry(label).or_default();
+    entry.depth -= 1;
+    match res {
+      Ok((i, _)) => {
+        let consumed = position - i.input_len();
+        entry.stats.consumed += consumed;
+        if repeated {
+          entry.stats.reexamined += consumed;
+        }
+      }
+      Err(Err::Error(_)) => entry.stats.errors += 1,
+      Err(Err::Failure(_)) => entry.stats.failures += 1,
+      Err(Err::Incomplete(_)) => entry.stats.incomplete += 1,
+    }
+  }
+}
+
+/// Parser implementation for [Profiler::profile]
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "alloc")))]
+pub struct Profiled<'p, F> {
+  profiler: &'p Profiler,
+  label: &'static str,
+  parser: F,
+}
+
+#[cfg(feature = "alloc")]
+impl<'p, I: Input, F: Parser<I>> Parser<I> for Profiled<'p, F> {
+  type Output = <F as Parser<I>>::Output;
+  type Error = <F as Parser<I>>::Error;
+
+  fn process<OM: OutputMode>(&mut self, input: I) -> PResult<OM, I, Self::Output, Self::Error> {
+    let position = input.input_len();
+    let repeated = self.profiler.enter(self.label, position);
+    let res = self.parser.process::<OM>(input);
+    self.profiler.exit(self.label, position, repeated, &res);
+    res
+  }
+}
+
+/// Applies a text parser to the longest valid UTF-8 prefix of a byte slice, then
+/// continues on the remaining bytes.
 ///
-/// let data = "abc|defg|hijkl|mnopqr|123";
-/// let mut it = iterator(data, terminated(alpha1, tag("|")));
+/// This parses formats that start with a text header followed by a binary body, without
+/// converting the input back and forth by hand. The input positions of the errors are
+/// converted with [MapInput](crate::error::MapInput) to the same positions in the byte
+/// slice. In streaming mode, the text parser only returns `Incomplete` if the input ends
+/// with a truncated character: if the prefix ends at an invalid byte, more data cannot
+/// extend it, so the text parser is applied in complete mode.
 ///
-/// let parsed = it.by_ref().map(|v| (v, v.len())).collect::<HashMap<_,_>>();
-/// let res: IResult<_,_> = it.finish();
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+/// use nom::bytes::complete::{tag, take};
+/// use nom::character::complete::{alpha1, digit1};
+/// use nom::combinator::{map_res, text};
+/// use nom::sequence::terminated;
+///
+/// fn message(i: &[u8]) -> IResult<&[u8], (&str, &[u8])> {
+///   let (i, (name, len)) = text((
+///     terminated(alpha1::<_, Error<_>>, tag(" ")),
+///     terminated(map_res(digit1, str::parse::<usize>), tag("\n")),
+///   ))
+///   .parse(i)?;
+///   let (i, body) = take(len)(i)?;
+///   Ok((i, (name, body)))
+/// }
 ///
-/// assert_eq!(parsed, [("abc", 3usize), ("defg", 4), ("hijkl", 5), ("mnopqr", 6)].iter().cloned().collect());
-/// assert_eq!(res, Ok(("123", ())));
+/// assert_eq!(message(b"data 3\n\xff\x00\xfe!"), Ok((&b"!"[..], ("data", &b"\xff\x00\xfe"[..]))));
+/// assert_eq!(message(b"data x\n"), Err(Err::Error(Error::new(&b"x\n"[..], ErrorKind::Digit))));
 /// ```
-pub fn iterator<Input, Error, F>(input: Input, f: F) -> ParserIterator<Input, Error, F>
+pub fn text<'a, F>(parser: F) -> Text<F>
 where
-  F: Parser<Input>,
-  Error: ParseError<Input>,
+  F: Parser<&'a str>,
+  <F as Parser<&'a str>>::Error: MapInput<&'a str>,
+  <<F as Parser<&'a str>>::Error as MapInput<&'a str>>::Mapped<&'a [u8]>: ParseError<&'a [u8]>,
 {
-  ParserIterator {
-    iterator: f,
-    input,
-    state: Some(State::Running),
-  }
+  Text { parser }
 }
 
-/// Main structure associated to the [iterator] function.
-pub struct ParserIterator<I, E, F> {
-  iterator: F,
-  input: I,
-  state: Option<State<E>>,
+/// Parser implementation for [text]
+pub struct Text<F> {
+  parser: F,
 }
 
-impl<I: Clone, E, F> ParserIterator<I, E, F> {
-  /// Returns the remaining input if parsing was successful, or the error if we encountered an error.
-  pub fn finish(mut self) -> IResult<I, (), E> {
-    match self.state.take().unwrap() {
-      State::Running | State::Done => Ok((self.input, ())),
-      State::Failure(e) => Err(Err::Failure(e)),
-      State::Incomplete(i) => Err(Err::Incomplete(i)),
+impl<'a, F> Parser<&'a [u8]> for Text<F>
+where
+  F: Parser<&'a str>,
+  <F as Parser<&'a str>>::Error: MapInput<&'a str>,
+  <<F as Parser<&'a str>>::Error as MapInput<&'a str>>::Mapped<&'a [u8]>: ParseError<&'a [u8]>,
+{
+  type Output = <F as Parser<&'a str>>::Output;
+  type Error = <<F as Parser<&'a str>>::Error as MapInput<&'a str>>::Mapped<&'a [u8]>;
+
+  fn process<OM: OutputMode>(
+    &mut self,
+    input: &'a [u8],
+  ) -> PResult<OM, &'a [u8], Self::Output, Self::Error> {
+    // more data can complete a truncated character at the end of the input, but not
+    // an invalid byte
+    let (s, invalid) = match core::str::from_utf8(input) {
+      Ok(s) => (s, false),
+      Err(e) => (
+        core::str::from_utf8(&input[..e.valid_up_to()]).unwrap_or_default(),
+        e.error_len().is_some(),
+      ),
+    };
+
+    let res = if invalid {
+      self
+        .parser
+        .process::<OutputM<OM::Output, OM::Error, Complete>>(s)
+    } else {
+      self
+        .parser
+        .process::<OutputM<OM::Output, OM::Error, OM::Incomplete>>(s)
+    };
+
+    let position = |e: &'a str| &input[s.offset(e)..];
+    match res {
+      Ok((rest, o)) => Ok((position(rest), o)),
+      Err(Err::Error(e)) => Err(Err::Error(OM::Error::map(e, |e| e.map_input(position)))),
+      Err(Err::Failure(e)) => Err(Err::Failure(e.map_input(position))),
+      Err(Err::Incomplete(n)) => Err(Err::Incomplete(n)),
     }
   }
 }
 
-impl<Input, Output, Error, F> core::iter::Iterator for ParserIterator<Input, Error, F>
+/// Applies a byte parser to a string, then continues on the remaining text.
+///
+/// This is the opposite of [text], for a binary part inside a text format. If the byte
+/// parser stops inside a UTF-8 character, this returns an error with [ErrorKind::Utf8].
+/// The input positions of the errors of the byte parser are converted to strings with
+/// [MapInput](crate::error::MapInput), moving them back to the start of the character.
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+/// use nom::bytes::complete::take;
+/// use nom::character::complete::alpha1;
+/// use nom::combinator::binary;
+///
+/// let mut parser = (binary(take::<_, _, Error<_>>(2u8)), alpha1);
+/// assert_eq!(parser.parse("éabc"), Ok(("", (&b"\xc3\xa9"[..], "abc"))));
+/// assert_eq!(parser.parse("aé"), Err(Err::Error(Error::new("é", ErrorKind::Utf8))));
+/// assert_eq!(parser.parse("a"), Err(Err::Error(Error::new("a", ErrorKind::Eof))));
+/// ```
+pub fn binary<'a, F>(parser: F) -> Binary<F>
 where
-  F: Parser<Input, Output = Output, Error = Error>,
-  Input: Clone,
+  F: Parser<&'a [u8]>,
+  <F as Parser<&'a [u8]>>::Error: MapInput<&'a [u8]>,
+  <<F as Parser<&'a [u8]>>::Error as MapInput<&'a [u8]>>::Mapped<&'a str>: ParseError<&'a str>,
 {
-  type Item = Output;
+  Binary { parser }
+}
 
-  fn next(&mut self) -> Option<Self::Item> {
-    if let State::Running = self.state.take().unwrap() {
-      let input = self.input.clone();
+/// Parser implementation for [binary]
+pub struct Binary<F> {
+  parser: F,
+}
 
-      match (self.iterator).parse(input) {
-        Ok((i, o)) => {
-          self.input = i;
-          self.state = Some(State::Running);
-          Some(o)
-        }
-        Err(Err::Error(_)) => {
-          self.state = Some(State::Done);
-          None
-        }
-        Err(Err::Failure(e)) => {
-          self.state = Some(State::Failure(e));
-          None
-        }
-        Err(Err::Incomplete(i)) => {
-          self.state = Some(State::Incomplete(i));
-          None
+impl<'a, F> Parser<&'a str> for Binary<F>
+where
+  F: Parser<&'a [u8]>,
+  <F as Parser<&'a [u8]>>::Error: MapInput<&'a [u8]>,
+  <<F as Parser<&'a [u8]>>::Error as MapInput<&'a [u8]>>::Mapped<&'a str>: ParseError<&'a str>,
+{
+  type Output = <F as Parser<&'a [u8]>>::Output;
+  type Error = <<F as Parser<&'a [u8]>>::Error as MapInput<&'a [u8]>>::Mapped<&'a str>;
+
+  fn process<OM: OutputMode>(
+    &mut self,
+    input: &'a str,
+  ) -> PResult<OM, &'a str, Self::Output, Self::Error> {
+    let bytes = input.as_bytes();
+    // moves a position back to the start of its character
+    let to_str = |b: &'a [u8]| {
+      let mut offset = bytes.offset(b).min(input.len());
+      while !input.is_char_boundary(offset) {
+        offset -= 1;
+      }
+      &input[offset..]
+    };
+
+    match self.parser.process::<OM>(bytes) {
+      Ok((rest, o)) => {
+        let offset = bytes.offset(rest);
+        if input.is_char_boundary(offset) {
+          Ok((&input[offset..], o))
+        } else {
+          Err(Err::Error(OM::Error::bind(|| {
+            Self::Error::from_error_kind(to_str(rest), ErrorKind::Utf8)
+          })))
         }
       }
+      Err(Err::Error(e)) => Err(Err::Error(OM::Error::map(e, |e| e.map_input(to_str)))),
+      Err(Err::Failure(e)) => Err(Err::Failure(e.map_input(to_str))),
+      Err(Err::Incomplete(n)) => Err(Err::Incomplete(n)),
+    }
+  }
+}
+
+/// Applies a parser written for the input type wrapped by [Partial](crate::Partial),
+/// in streaming mode if the input may be followed by more data, and in complete mode
+/// otherwise.
+///
+/// This is a migration path for grammars written over plain slices: they can be
+/// embedded in a grammar over `Partial` inputs without being rewritten. The mode only
+/// reaches parsers that take it from the caller, like the ones of the parent modules:
+/// [character::complete](crate::character::complete) parsers stay complete. The input
+/// positions of the errors are wrapped with [MapInput](crate::error::MapInput).
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Needed, Parser};
+/// use nom::Partial;
+/// use nom::bytes::tag;
+/// use nom::character::digit1;
+/// use nom::combinator::on_partial;
+///
+/// let mut parser = (tag::<_, _, Error<_>>("n="), on_partial(digit1::<&str, Error<_>>()));
+/// assert_eq!(parser.parse(Partial::new("n=12")), Err(Err::Incomplete(Needed::new(1))));
+///
+/// let (_, (_, n)) = parser.parse(Partial::new("n=12").complete()).unwrap();
+/// assert_eq!(n, "12");
+/// ```
+pub fn on_partial<I, F>(parser: F) -> OnPartial<F>
+where
+  F: Parser<I>,
+  <F as Parser<I>>::Error: MapInput<I>,
+  <<F as Parser<I>>::Error as MapInput<I>>::Mapped<crate::Partial<I>>:
+    ParseError<crate::Partial<I>>,
+{
+  OnPartial { parser }
+}
+
+/// Parser implementation for [on_partial]
+pub struct OnPartial<F> {
+  parser: F,
+}
+
+impl<I, F> Parser<crate::Partial<I>> for OnPartial<F>
+where
+  F: Parser<I>,
+  <F as Parser<I>>::Error: MapInput<I>,
+  <<F as Parser<I>>::Error as MapInput<I>>::Mapped<crate::Partial<I>>:
+    ParseError<crate::Partial<I>>,
+{
+  type Output = <F as Parser<I>>::Output;
+  type Error = <<F as Parser<I>>::Error as MapInput<I>>::Mapped<crate::Partial<I>>;
+
+  fn process<OM: OutputMode>(
+    &mut self,
+    input: crate::Partial<I>,
+  ) -> PResult<OM, crate::Partial<I>, Self::Output, Self::Error> {
+    let partial = input.is_partial();
+    let wrap = move |i: I| {
+      let i = crate::Partial::new(i);
+      if partial {
+        i
+      } else {
+        i.complete()
+      }
+    };
+
+    let res = if partial {
+      self
+        .parser
+        .process::<OutputM<OM::Output, OM::Error, Streaming>>(input.into_inner())
     } else {
-      None
+      self
+        .parser
+        .process::<OutputM<OM::Output, OM::Error, Complete>>(input.into_inner())
+    };
+
+    match res {
+      Ok((rest, o)) => Ok((wrap(rest), o)),
+      Err(Err::Error(e)) => Err(Err::Error(OM::Error::map(e, |e| e.map_input(wrap)))),
+      Err(Err::Failure(e)) => Err(Err::Failure(e.map_input(wrap))),
+      Err(Err::Incomplete(n)) => Err(Err::Incomplete(n)),
     }
   }
 }
 
-enum State<E> {
-  Running,
-  Done,
-  Failure(E),
-  Incomplete(Needed),
+/// Information about an index outside of its table, reported by [resolve] and
+/// [resolve_str] through [FromExternalError].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IndexOutOfRange {
+  /// The index that was parsed
+  pub index: usize,
+  /// Length of the table
+  pub len: usize,
 }
 
-/// a parser which always succeeds with given value without consuming any input.
+impl crate::lib::std::fmt::Display for IndexOutOfRange {
+  fn fmt(&self, f: &mut crate::lib::std::fmt::Formatter<'_>) -> crate::lib::std::fmt::Result {
+    write!(
+      f,
+      "index {} out of range for a table of length {}",
+      self.index, self.len
+    )
+  }
+}
+
+/// Parses an index with the `index` parser and returns the entry of `table` it refers to.
 ///
-/// It can be used for example as the last alternative in `alt` to
-/// specify the default case.
+/// Formats like Java class files or DWARF store their constants in a table, referred to
+/// by index in the rest of the file. The table is parsed first, then the parsers of the
+/// following structures resolve indices into it. `base` is the index of the first entry,
+/// as tables are sometimes indexed from 1.
+///
+/// An index outside of the table returns an error with [ErrorKind::OutOfRange] at the
+/// position of the index, built with [FromExternalError] from an [IndexOutOfRange] value.
 ///
 /// ```rust
-/// # use nom::{Err,error::ErrorKind, IResult, Parser};
-/// use nom::branch::alt;
-/// use nom::combinator::{success, value};
-/// use nom::character::complete::char;
-/// # fn main() {
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+/// use nom::combinator::resolve;
+/// use nom::multi::{length_count, many0};
+/// use nom::number::complete::u8;
+/// use nom::bytes::complete::take;
 ///
-/// let mut parser = success::<_,_,(_,ErrorKind)>(10);
-/// assert_eq!(parser.parse("xyz"), Ok(("xyz", 10)));
+/// let data = b"\x02\x02ab\x03cde\x01\x02\x01\x03";
 ///
-/// let mut sign = alt((value(-1, char('-')), value(1, char('+')), success::<_,_,(_,ErrorKind)>(1)));
-/// assert_eq!(sign.parse("+10"), Ok(("10", 1)));
-/// assert_eq!(sign.parse("-10"), Ok(("10", -1)));
-/// assert_eq!(sign.parse("10"), Ok(("10", 1)));
-/// # }
+/// // first phase: the constant table, with entries indexed from 1
+/// let res: IResult<_, _> = length_count(u8, u8.flat_map(take)).parse(&data[..]);
+/// let (rest, table) = res.unwrap();
+///
+/// // second phase: references into the table
+/// let res: IResult<_, _> = many0(resolve(&table, 1, u8).map(|name| *name)).parse(rest);
+/// let (_, names) = res.unwrap();
+/// assert_eq!(names, [&b"ab"[..], &b"cde"[..], &b"ab"[..]]);
+///
+/// assert_eq!(
+///   resolve::<_, _, Error<_>, _>(&table, 1, u8).parse(&b"\x03"[..]),
+///   Err(Err::Error(Error::new(&b"\x03"[..], ErrorKind::OutOfRange)))
+/// );
 /// ```
-pub fn success<I, O: Clone, E: ParseError<I>>(val: O) -> impl Parser<I, Output = O, Error = E> {
-  Success {
-    val,
-    e: PhantomData,
-  }
+pub fn resolve<'a, I, T, E, F>(table: &'a [T], base: usize, index: F) -> Resolve<'a, T, F>
+where
+  I: Clone,
+  F: Parser<I, Error = E>,
+  <F as Parser<I>>::Output: crate::traits::ToUsize,
+  E: ParseError<I> + FromExternalError<I, IndexOutOfRange>,
+{
+  Resolve { table, base, index }
 }
 
-/// Parser implementation for [success]
-pub struct Success<O: Clone, E> {
-  val: O,
-  e: PhantomData<E>,
+/// Parser implementation for [resolve]
+pub struct Resolve<'a, T, F> {
+  table: &'a [T],
+  base: usize,
+  index: F,
 }
 
-impl<I, O, E> Parser<I> for Success<O, E>
+impl<'a, I, T, E, F> Parser<I> for Resolve<'a, T, F>
 where
-  O: Clone,
-  E: ParseError<I>,
+  I: Clone,
+  F: Parser<I, Error = E>,
+  <F as Parser<I>>::Output: crate::traits::ToUsize,
+  E: ParseError<I> + FromExternalError<I, IndexOutOfRange>,
 {
-  type Output = O;
+  type Output = &'a T;
   type Error = E;
 
   fn process<OM: OutputMode>(&mut self, input: I) -> PResult<OM, I, Self::Output, Self::Error> {
-    Ok((input, OM::Output::bind(|| self.val.clone())))
+    use crate::traits::ToUsize;
+
+    let (i, index) = self
+      .index
+      .process::<OutputM<Emit, OM::Error, OM::Incomplete>>(input.clone())?;
+    let index = index.to_usize();
+    match index
+      .checked_sub(self.base)
+      .and_then(|position| self.table.get(position))
+    {
+      Some(entry) => Ok((i, OM::Output::bind(|| entry))),
+      None => Err(Err::Error(OM::Error::bind(|| {
+        E::from_external_error(
+          input,
+          ErrorKind::OutOfRange,
+          IndexOutOfRange {
+            index,
+            len: self.table.len(),
+          },
+        )
+      }))),
+    }
   }
 }
 
-/// A parser which always fails.
+/// Parses an offset with the `offset` parser and returns the NUL terminated string at
+/// that offset in `table`, without the terminator.
 ///
-/// ```rust
-/// # use nom::{Err, error::ErrorKind, IResult, Parser};
-/// use nom::combinator::fail;
+/// This resolves references into string tables like the ones of ELF files or the
+/// `.debug_str` section of DWARF, see [resolve]. An offset past the end of the table
+/// returns an error with [ErrorKind::OutOfRange] built from an [IndexOutOfRange] value,
+/// and a string without terminator returns an error with [ErrorKind::Eof], both at the
+/// position of the offset.
 ///
-/// let s = "string";
-/// assert_eq!(fail::<_, &str, _>().parse(s), Err(Err::Error((s, ErrorKind::Fail))));
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+/// use nom::combinator::resolve_str;
+/// use nom::number::complete::le_u32;
+///
+/// let strtab = &b"\0.text\0.data\0"[..];
+///
+/// let mut section_name = resolve_str::<_, Error<_>, _>(strtab, le_u32);
+/// assert_eq!(section_name.parse(&b"\x07\x00\x00\x00"[..]), Ok((&b""[..], &b".data"[..])));
+/// assert_eq!(section_name.parse(&b"\x00\x00\x00\x00"[..]), Ok((&b""[..], &b""[..])));
+/// assert_eq!(
+///   section_name.parse(&b"\x20\x00\x00\x00"[..]),
+///   Err(Err::Error(Error::new(&b"\x20\x00\x00\x00"[..], ErrorKind::OutOfRange)))
+/// );
 /// ```
-pub fn fail<I, O, E: ParseError<I>>() -> impl Parser<I, Output = O, Error = E> {
-  Fail {
-    o: PhantomData,
-    e: PhantomData,
-  }
+pub fn resolve_str<I, E, F>(table: I, offset: F) -> ResolveStr<I, F>
+where
+  I: Input<Item = u8>,
+  F: Parser<I, Error = E>,
+  <F as Parser<I>>::Output: crate::traits::ToUsize,
+  E: ParseError<I> + FromExternalError<I, IndexOutOfRange>,
+{
+  ResolveStr { table, offset }
 }
 
-/// Parser implementation for [fail]
-pub struct Fail<O, E> {
-  o: PhantomData<O>,
-  e: PhantomData<E>,
+/// Parser implementation for [resolve_str]
+pub struct ResolveStr<I, F> {
+  table: I,
+  offset: F,
 }
 
-impl<I, O, E> Parser<I> for Fail<O, E>
+impl<I, E, F> Parser<I> for ResolveStr<I, F>
 where
-  E: ParseError<I>,
+  I: Input<Item = u8>,
+  F: Parser<I, Error = E>,
+  <F as Parser<I>>::Output: crate::traits::ToUsize,
+  E: ParseError<I> + FromExternalError<I, IndexOutOfRange>,
 {
-  type Output = O;
+  type Output = I;
   type Error = E;
 
   fn process<OM: OutputMode>(&mut self, input: I) -> PResult<OM, I, Self::Output, Self::Error> {
-    Err(Err::Error(OM::Error::bind(|| {
-      E::from_error_kind(input, ErrorKind::Fail)
-    })))
+    use crate::traits::ToUsize;
+
+    let (i, offset) = self
+      .offset
+      .process::<OutputM<Emit, OM::Error, OM::Incomplete>>(input.clone())?;
+    let offset = offset.to_usize();
+    let len = self.table.input_len();
+    if offset >= len {
+      return Err(Err::Error(OM::Error::bind(|| {
+        E::from_external_error(
+          input,
+          ErrorKind::OutOfRange,
+          IndexOutOfRange { index: offset, len },
+        )
+      })));
+    }
+
+    let string = self.table.take_from(offset);
+    match string.position(|b| b == 0) {
+      Some(end) => Ok((i, OM::Output::bind(|| string.take(end)))),
+      None => Err(Err::Error(OM::Error::bind(|| {
+        E::from_error_kind(input, ErrorKind::Eof)
+      }))),
+    }
   }
 }
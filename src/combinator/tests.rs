@@ -282,3 +282,1096 @@ fn fail_test() {
     Err(Err::Error((b, ErrorKind::Fail)))
   );
 }
+
+#[test]
+#[cfg(feature = "alloc")]
+fn all_consuming_verbose_test() {
+  use crate::character::complete::alpha1;
+  use crate::error::{ContextError, Error};
+  use crate::lib::std::string::{String, ToString};
+  use crate::lib::std::vec::Vec;
+
+  #[derive(Debug, PartialEq)]
+  struct Contexts<'a> {
+    input: &'a str,
+    kind: ErrorKind,
+    contexts: Vec<String>,
+  }
+
+  impl<'a> ParseError<&'a str> for Contexts<'a> {
+    fn from_error_kind(input: &'a str, kind: ErrorKind) -> Self {
+      Contexts {
+        input,
+        kind,
+        contexts: Vec::new(),
+      }
+    }
+
+    fn append(_: &'a str, _: ErrorKind, other: Self) -> Self {
+      other
+    }
+  }
+
+  impl<'a> ContextError<&'a str> for Contexts<'a> {
+    fn add_lazy_context(_: &'a str, ctx: &dyn fmt::Display, mut other: Self) -> Self {
+      other.contexts.push(ctx.to_string());
+      other
+    }
+  }
+
+  let mut parser = all_consuming_verbose(alpha1::<_, Contexts<'_>>);
+  assert_eq!(parser.parse("abc"), Ok(("", "abc")));
+  assert_eq!(
+    parser.parse("abc;"),
+    Err(Err::Error(Contexts {
+      input: ";",
+      kind: ErrorKind::Eof,
+      contexts: vec!["1 byte left unconsumed at offset 3".to_string()],
+    }))
+  );
+  // errors of the child parser are not changed
+  assert_eq!(
+    parser.parse("1").map_err(|e| e.map(|e| e.contexts.len())),
+    Err(Err::Error(0))
+  );
+
+  // without contexts, this is `all_consuming`
+  assert_eq!(
+    all_consuming_verbose(alpha1::<_, Error<_>>).parse("ab; cd"),
+    Err(Err::Error(Error::new("; cd", ErrorKind::Eof)))
+  );
+}
+
+#[test]
+fn stateful_test() {
+  use crate::branch::alt;
+  use crate::character::complete::{alpha1, char};
+  use crate::error::Error;
+  use crate::traits::Stateful;
+  use core::cell::RefCell;
+
+  type Input<'a, 's> = Stateful<'s, &'a str, (usize, usize)>;
+
+  // counts the words and the characters in them
+  fn word<'a, 's>(i: Input<'a, 's>) -> IResult<Input<'a, 's>, usize> {
+    map_with_state(
+      alpha1,
+      |w: Input<'a, 's>, (words, chars): &mut (usize, usize)| {
+        *words += 1;
+        *chars += w.len();
+        *words
+      },
+    )
+    .parse(i)
+  }
+
+  let state = RefCell::new((0, 0));
+  let input = Stateful::new("ab cde", &state);
+  assert_eq!(
+    (word, char(' '), word)
+      .parse(input)
+      .map(|(rest, (a, _, b))| (rest.into_inner(), a, b)),
+    Ok(("", 1, 2))
+  );
+  assert_eq!(*state.borrow(), (2, 5));
+
+  // the state is changed in check mode too
+  assert_eq!(
+    recognize(word).parse(input).map(|(_, w)| w.into_inner()),
+    Ok("ab")
+  );
+  assert_eq!(*state.borrow(), (3, 7));
+
+  // a failed branch keeps its changes, unless it is atomic
+  let state = RefCell::new((0, 0));
+  let input = Stateful::new("ab;", &state);
+  let mut pair = alt(((word, char('.')), (word, char(';'))));
+  assert!(pair.parse(input).is_ok());
+  assert_eq!(*state.borrow(), (2, 4));
+  *state.borrow_mut() = (0, 0);
+  let mut pair = alt((atomic((word, char('.'))), (word, char(';'))));
+  assert!(pair.parse(input).is_ok());
+  assert_eq!(*state.borrow(), (1, 2));
+
+  let mut reset = with_state::<_, _, _, Error<_>, _>(|state: &mut (usize, usize)| {
+    crate::lib::std::mem::take(state)
+  });
+  assert_eq!(reset.parse(input).map(|(_, s)| s), Ok((1, 2)));
+  assert_eq!(*state.borrow(), (0, 0));
+}
+
+#[test]
+fn padded_test() {
+  use crate::character::complete::digit1;
+
+  assert_parse!(
+    padded(digit1).parse_complete(" \r\n12 ab"),
+    Ok(("ab", "12"))
+  );
+  assert_parse!(padded(digit1).parse_complete("12"), Ok(("", "12")));
+  assert_parse!(
+    padded(digit1).parse_complete(" ab"),
+    Err(Err::Error(("ab", ErrorKind::Digit)))
+  );
+  // streaming: trailing whitespace may continue
+  assert_parse!(
+    padded(digit1).parse(" 12 "),
+    Err(Err::Incomplete(Needed::new(1)))
+  );
+}
+
+#[test]
+fn skipper_test() {
+  use crate::bytes::complete::tag;
+  use crate::character::complete::{alpha1, space1};
+  use crate::sequence::pair;
+
+  fn trivia(i: &str) -> IResult<&str, &str> {
+    crate::branch::alt((space1, tag("#"))).parse(i)
+  }
+
+  fn failing(i: &str) -> IResult<&str, &str> {
+    cut(tag("!")).parse(i)
+  }
+
+  let ws = skipper(trivia);
+  let mut words = ws.padded(pair(ws.lexeme(alpha1), ws.lexeme(alpha1)));
+
+  assert_eq!(words.parse(" # ab # cd #ef"), Ok(("ef", ("ab", "cd"))));
+  assert_eq!(words.parse("ab cd"), Ok(("", ("ab", "cd"))));
+  assert_eq!(
+    words.parse("ab 12"),
+    Err(Err::Error(error_position!("12", ErrorKind::Alpha)))
+  );
+
+  // trivia parsers that do not consume do not loop forever
+  let ws = skipper(|i| Ok::<_, Err<(&str, ErrorKind)>>((i, ())));
+  assert_parse!(ws.padded(alpha1).parse("ab cd"), Ok((" cd", "ab")));
+
+  // failures from the trivia parser are returned
+  let ws = skipper(failing);
+  assert_eq!(
+    ws.lexeme(alpha1).parse("ab cd"),
+    Err(Err::Failure(error_position!(" cd", ErrorKind::Tag)))
+  );
+}
+
+#[test]
+fn validate_with_test() {
+  use crate::bytes::complete::take;
+  use crate::sequence::pair;
+
+  // the declared length must match the payload, and a trailer byte must follow
+  fn record(i: &[u8]) -> IResult<&[u8], (u8, &[u8])> {
+    validate_with(pair(u8, take(2usize)), |(len, payload), input: &&[u8]| {
+      if usize::from(*len) != payload.len() {
+        Err("length mismatch")
+      } else if input.len() < 4 {
+        Err("missing trailer")
+      } else {
+        Ok(())
+      }
+    })
+    .parse(i)
+  }
+
+  assert_eq!(record(&[2, 1, 2, 0][..]), Ok((&[0][..], (2, &[1, 2][..]))));
+  assert_eq!(
+    record(&[3, 1, 2, 0][..]),
+    Err(Err::Error(error_position!(
+      &[3, 1, 2, 0][..],
+      ErrorKind::Verify
+    )))
+  );
+  assert_eq!(
+    record(&[2, 1, 2][..]),
+    Err(Err::Error(error_position!(
+      &[2, 1, 2][..],
+      ErrorKind::Verify
+    )))
+  );
+  assert_eq!(
+    record(&[2, 1][..]),
+    Err(Err::Error(error_position!(&[1][..], ErrorKind::Eof)))
+  );
+}
+
+#[test]
+fn value_errors_test() {
+  use crate::bytes::complete::take;
+
+  #[derive(Debug, PartialEq)]
+  struct Rejected<'a> {
+    input: &'a [u8],
+    kind: ErrorKind,
+    value: Option<u32>,
+  }
+
+  impl<'a> ParseError<&'a [u8]> for Rejected<'a> {
+    fn from_error_kind(input: &'a [u8], kind: ErrorKind) -> Self {
+      Rejected {
+        input,
+        kind,
+        value: None,
+      }
+    }
+
+    fn append(_: &'a [u8], _: ErrorKind, other: Self) -> Self {
+      other
+    }
+  }
+
+  impl<'a> FromExternalError<&'a [u8], u32> for Rejected<'a> {
+    fn from_external_error(input: &'a [u8], kind: ErrorKind, value: u32) -> Self {
+      Rejected {
+        input,
+        kind,
+        value: Some(value),
+      }
+    }
+  }
+
+  let mut even = verify_with(u8, |n: &u8| n % 2 == 0, |n: &u8| u32::from(*n));
+  assert_eq!(even.parse(&[4, 1][..]), Ok((&[1][..], 4)));
+  assert_eq!(
+    even.parse(&[3, 1][..]),
+    Err(Err::Error(Rejected {
+      input: &[3, 1][..],
+      kind: ErrorKind::Verify,
+      value: Some(3)
+    }))
+  );
+
+  let mut ascii = map_res_ctx(
+    take(2usize),
+    |b: &&[u8]| crate::lib::std::str::from_utf8(b),
+    |b: &&[u8], e| u32::from(b[e.valid_up_to()]),
+  );
+  assert_eq!(ascii.parse(&b"ab!"[..]), Ok((&b"!"[..], "ab")));
+  assert_eq!(
+    ascii.parse(&b"a\xff!"[..]),
+    Err(Err::Error(Rejected {
+      input: &b"a\xff!"[..],
+      kind: ErrorKind::MapRes,
+      value: Some(0xff)
+    }))
+  );
+  assert_eq!(
+    ascii.parse(&b"a"[..]),
+    Err(Err::Error(Rejected {
+      input: &b"a"[..],
+      kind: ErrorKind::Eof,
+      value: None
+    }))
+  );
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn backtrack_test() {
+  use crate::branch::alt;
+  use crate::character::complete::{alpha1, digit1};
+  use crate::multi::many0;
+
+  fn item(i: &str) -> IResult<&str, &str> {
+    alt((
+      backtrack(commit_after(tag("#"), digit1)),
+      commit_after(tag("#"), alpha1),
+    ))
+    .parse(i)
+  }
+
+  assert_eq!(item("#12"), Ok(("", "12")));
+  assert_eq!(item("#ab"), Ok(("", "ab")));
+  assert_eq!(
+    item("#;"),
+    Err(Err::Failure(error_position!(";", ErrorKind::Alpha)))
+  );
+  assert_eq!(
+    item("ab"),
+    Err(Err::Error(error_position!("ab", ErrorKind::Tag)))
+  );
+  assert_eq!(
+    many0(item).parse("#1#a#;"),
+    Err(Err::Failure(error_position!(";", ErrorKind::Alpha)))
+  );
+  assert_eq!(
+    many0(backtrack(item)).parse("#1#a#;"),
+    Ok(("#;", vec!["1", "a"]))
+  );
+  assert_eq!(
+    recognize(backtrack(item)).parse("#;"),
+    Err(Err::Error(error_position!(";", ErrorKind::Alpha)))
+  );
+
+  let mut streaming = commit_after(tag::<_, _, (_, ErrorKind)>("let"), tag(" "));
+  assert_eq!(streaming.parse("le"), Err(Err::Incomplete(Needed::new(1))));
+  assert_eq!(streaming.parse("let"), Err(Err::Incomplete(Needed::new(1))));
+}
+
+#[test]
+fn budget_test() {
+  use crate::branch::alt;
+  use crate::character::complete::char;
+  use crate::sequence::delimited;
+
+  // nested parentheses, each level consuming one step
+  fn nested<'b>(budget: &'b Budget) -> impl FnMut(&str) -> IResult<&str, usize> + 'b {
+    move |i| {
+      budget
+        .meter(alt((
+          delimited(char('('), nested(budget), char(')')).map(|depth| depth + 1),
+          success(0),
+        )))
+        .parse(i)
+    }
+  }
+
+  let budget = Budget::new(Limits::new().steps(5));
+  let mut parser = with_budget(&budget, nested(&budget));
+  assert_eq!(parser.parse("((()))"), Ok(("", 3)));
+  assert_eq!(budget.used(), 5);
+
+  // the budget is reset for each parse
+  assert_eq!(parser.parse("(())"), Ok(("", 2)));
+  assert_eq!(budget.used(), 4);
+
+  // the failure is not caught by `alt`
+  assert_eq!(
+    parser.parse("(((())))"),
+    Err(Err::Failure(error_position!("))))", ErrorKind::Budget)))
+  );
+
+  let budget = Budget::new(Limits::new());
+  assert_eq!(
+    with_budget(&budget, nested(&budget)).parse("(((())))"),
+    Ok(("", 4))
+  );
+
+  let budget = Budget::new(Limits::new().steps(0));
+  assert_eq!(
+    with_budget(&budget, nested(&budget)).parse("()"),
+    Err(Err::Failure(error_position!("()", ErrorKind::Budget)))
+  );
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn fuel_test() {
+  use crate::branch::alt;
+  use crate::bytes::take_while1;
+  use crate::character::char;
+  use crate::lib::std::vec::Vec;
+  use crate::multi::many0;
+  use crate::traits::{Fuel, Fueled};
+
+  type Input<'a, 'f> = Fueled<'f, &'a str>;
+
+  fn words<'a, 'f>(i: Input<'a, 'f>) -> IResult<Input<'a, 'f>, Vec<&'a str>> {
+    many0(alt((
+      take_while1(|c: char| c.is_ascii_alphabetic()).map(Fueled::into_inner),
+      char(' ').map(|_| " "),
+    )))
+    .parse_complete(i)
+  }
+
+  let fuel = Fuel::new(0);
+  let mut parser = with_fuel(100, words);
+  let (rest, res) = parser.parse(Fueled::new("ab cd", &fuel)).unwrap();
+  assert_eq!((rest.into_inner(), res), ("", vec!["ab", " ", "cd"]));
+  assert!(!fuel.is_exhausted());
+  let used = 100 - fuel.remaining();
+
+  // the fuel is refilled for each parse
+  assert_eq!(
+    parser
+      .parse(Fueled::new("ab cd", &fuel))
+      .map(|(_, res)| res),
+    Ok(vec!["ab", " ", "cd"])
+  );
+  assert_eq!(100 - fuel.remaining(), used);
+
+  // the parse stops as soon as the fuel runs out, even in complete mode
+  let mut parser = with_fuel(used - 1, words);
+  let input = Fueled::new("ab cd", &fuel);
+  assert_eq!(
+    parser.parse(input),
+    Err(Err::Failure(error_position!(input, ErrorKind::Budget)))
+  );
+  assert!(fuel.is_exhausted());
+  assert_eq!(fuel.remaining(), 0);
+
+  // without `with_fuel`, the input looks empty once the fuel is exhausted
+  let fuel = Fuel::new(0);
+  let input = Fueled::new("abc", &fuel);
+  assert!(
+    take_while1::<_, _, (_, ErrorKind)>(|c: char| c.is_ascii_alphabetic())
+      .parse_complete(input)
+      .is_err()
+  );
+  assert!(fuel.is_exhausted());
+  assert!(input.is_streaming::<OutputM<Emit, Emit, Complete>>());
+}
+
+#[test]
+fn depth_limit_test() {
+  use crate::branch::alt;
+  use crate::character::complete::char;
+  use crate::sequence::{delimited, delimited_with_depth};
+
+  fn nested<'a>(limit: &DepthLimit, i: &'a str) -> IResult<&'a str, usize> {
+    alt((
+      delimited_with_depth(limit, char('('), |i| nested(limit, i), char(')')).map(|d| d + 1),
+      success(0),
+    ))
+    .parse(i)
+  }
+
+  let limit = DepthLimit::new(3);
+  assert_eq!(limit.max(), 3);
+  assert_eq!(nested(&limit, "((()))"), Ok(("", 3)));
+  assert_eq!(nested(&limit, "()()"), Ok(("()", 1)));
+  assert_eq!(
+    nested(&limit, "(((())))"),
+    Err(Err::Failure(error_position!("))))", ErrorKind::Depth)))
+  );
+  // the depth is restored after errors and failures
+  assert_eq!(limit.depth(), 0);
+  assert_eq!(nested(&limit, "((("), Ok(("(((", 0)));
+  assert_eq!(limit.depth(), 0);
+
+  // `nest` enters the level before the wrapped parser runs
+  let limit = DepthLimit::new(0);
+  assert_eq!(
+    limit
+      .nest(delimited(
+        char::<_, (&str, ErrorKind)>('('),
+        char('a'),
+        char(')')
+      ))
+      .parse("b"),
+    Err(Err::Failure(error_position!("b", ErrorKind::Depth)))
+  );
+  assert_eq!(nested(&limit, "b"), Ok(("b", 0)));
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn depth_limited_test() {
+  use crate::branch::alt;
+  use crate::character::complete::char;
+  use crate::sequence::delimited;
+
+  // the level is entered before the opening parenthesis is checked
+  fn nested(i: &str) -> IResult<&str, usize> {
+    alt((
+      depth_limited(4, delimited(char('('), nested, char(')'))).map(|d| d + 1),
+      success(0),
+    ))
+    .parse(i)
+  }
+
+  assert_eq!(nested("((()))"), Ok(("", 3)));
+  assert_eq!(
+    nested("(((())))"),
+    Err(Err::Failure(error_position!("))))", ErrorKind::Depth)))
+  );
+  // the thread local depth is restored after failures
+  assert_eq!(nested("((()))"), Ok(("", 3)));
+  assert_eq!(nested("((("), Ok(("(((", 0)));
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn budget_time_test() {
+  use std::time::Duration;
+
+  let budget = Budget::new(Limits::new().time(Duration::from_millis(1)));
+  let mut slow = budget.meter(|i| {
+    std::thread::sleep(Duration::from_millis(2));
+    Ok::<_, Err<(&str, ErrorKind)>>((i, ()))
+  });
+  let mut parser = with_budget(&budget, |i| {
+    let (i, _) = slow.parse(i)?;
+    slow.parse(i)
+  });
+  assert_eq!(
+    parser.parse("abc"),
+    Err(Err::Failure(("abc", ErrorKind::Budget)))
+  );
+}
+
+#[test]
+fn atomic_test() {
+  use crate::branch::alt;
+
+  // input counting the tokens read, in a state shared by its clones
+  #[derive(Clone, Debug, PartialEq)]
+  struct Counted<'a> {
+    input: &'a [u8],
+    tokens: &'a Cell<usize>,
+  }
+
+  impl Rewind for Counted<'_> {
+    type Checkpoint = usize;
+
+    fn checkpoint(&self) -> usize {
+      self.tokens.get()
+    }
+
+    fn rewind(&self, tokens: usize) {
+      self.tokens.set(tokens)
+    }
+  }
+
+  fn token<'a>(
+    t: &'static [u8],
+  ) -> impl FnMut(Counted<'a>) -> IResult<Counted<'a>, (), (Counted<'a>, ErrorKind)> {
+    move |i: Counted<'a>| {
+      if i.input.starts_with(t) {
+        i.tokens.set(i.tokens.get() + 1);
+        Ok((
+          Counted {
+            input: &i.input[t.len()..],
+            ..i
+          },
+          (),
+        ))
+      } else if t.starts_with(i.input) {
+        Err(Err::Incomplete(Needed::new(t.len() - i.input.len())))
+      } else {
+        Err(Err::Error((i, ErrorKind::Tag)))
+      }
+    }
+  }
+
+  let tokens = Cell::new(0);
+  let input = |s| Counted {
+    input: s,
+    tokens: &tokens,
+  };
+
+  // without atomic, the failed branch leaves a token counted
+  let mut parser = alt(((token(b"a"), token(b"b")), (token(b"a"), token(b"c"))));
+  assert!(parser.parse(input(b"ac")).is_ok());
+  assert_eq!(tokens.replace(0), 3);
+
+  let mut parser = alt((
+    atomic((token(b"a"), token(b"b"))),
+    (token(b"a"), token(b"c")),
+  ));
+  assert!(parser.parse(input(b"ac")).is_ok());
+  assert_eq!(tokens.replace(0), 2);
+
+  let mut parser = atomic((token(b"a"), cut(token(b"b"))));
+  assert!(matches!(parser.parse(input(b"ac")), Err(Err::Failure(_))));
+  assert_eq!(tokens.get(), 0);
+  assert_eq!(
+    parser.parse(input(b"a")),
+    Err(Err::Incomplete(Needed::new(1)))
+  );
+  assert_eq!(tokens.get(), 0);
+  assert!(parser.parse(input(b"ab")).is_ok());
+  assert_eq!(tokens.get(), 2);
+
+  // inputs without external state are left as they are
+  assert_parse!(
+    atomic(tag("ab")).parse(&b"abc"[..]),
+    Ok((&b"c"[..], &b"ab"[..]))
+  );
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn tee_raw_test() {
+  use crate::bytes::complete::take_while1;
+  use crate::lib::std::string::String;
+  use crate::lib::std::vec::Vec;
+
+  let mut bytes = tee_raw((tag("ab"), u8));
+  assert_parse!(
+    bytes.parse(&b"ab\x01cd"[..]),
+    Ok((&b"cd"[..], (Vec::from(&b"ab\x01"[..]), (&b"ab"[..], 1))))
+  );
+  assert_parse!(bytes.parse(&b"a"[..]), Err(Err::Incomplete(Needed::new(1))));
+
+  let mut text = tee_raw(take_while1(|c: char| c.is_alphabetic()));
+  let res: IResult<&str, (String, &str)> = text.parse("été!");
+  assert_eq!(res, Ok(("!", (String::from("été"), "été"))));
+
+  // nothing is copied when the output is not used
+  assert_parse!(
+    recognize(tee_raw(tag("ab"))).parse(&b"abc"[..]),
+    Ok((&b"c"[..], &b"ab"[..]))
+  );
+}
+
+#[test]
+fn with_progress_test() {
+  use crate::multi::fold;
+
+  let mut calls = 0;
+  let mut parser = fold(
+    0..,
+    with_progress(
+      3,
+      Some(10),
+      |p: Progress| {
+        calls += 1;
+        assert_eq!(p.total, Some(10));
+        ControlFlow::Continue(())
+      },
+      take::<_, _, (&[u8], ErrorKind)>(2u8),
+    ),
+    || 0,
+    |acc, _| acc + 1,
+  );
+  // reports at offsets 4, 6 and 10
+  assert_eq!(parser.parse(&b"abcdefghij"[..]), Ok((&b""[..], 5)));
+  drop(parser);
+  assert_eq!(calls, 3);
+
+  let mut offsets = [0; 2];
+  let mut parser = with_progress(
+    1,
+    None,
+    |p: Progress| {
+      offsets[0] = p.offset;
+      offsets[1] += 1;
+      ControlFlow::Break(())
+    },
+    take::<_, _, (&[u8], ErrorKind)>(2u8),
+  );
+  assert_eq!(
+    parser.parse(&b"abc"[..]),
+    Err(Err::Failure(error_position!(
+      &b"c"[..],
+      ErrorKind::Cancelled
+    )))
+  );
+  // errors of the wrapped parser are returned as is without calling back
+  assert_eq!(
+    parser.parse(&b"a"[..]),
+    Err(Err::Error(error_position!(&b"a"[..], ErrorKind::Eof)))
+  );
+  drop(parser);
+  assert_eq!(offsets, [2, 1]);
+
+  assert_eq!(
+    Progress {
+      offset: 5,
+      total: Some(20)
+    }
+    .percentage(),
+    Some(25.0)
+  );
+  assert_eq!(
+    Progress {
+      offset: 5,
+      total: None
+    }
+    .percentage(),
+    None
+  );
+}
+
+#[test]
+fn cancellation_test() {
+  use crate::character::complete::{alpha1, char};
+  use crate::sequence::terminated;
+  use core::sync::atomic::{AtomicBool, Ordering};
+
+  let token = AtomicBool::new(false);
+  let mut it = iterator(
+    "abc|def|",
+    terminated(alpha1::<_, (&str, ErrorKind)>, char('|')),
+  )
+  .cancel_on(&token);
+  assert_eq!(it.next(), Some("abc"));
+  assert_eq!(it.next(), Some("def"));
+  assert_eq!(it.next(), None);
+  // setting the token after the iteration ended does not change the result
+  token.store(true, Ordering::Relaxed);
+  assert_eq!(it.finish(), Ok(("", ())));
+
+  let mut it = iterator(
+    "abc|def|",
+    terminated(alpha1::<_, (&str, ErrorKind)>, char('|')),
+  )
+  .cancel_on(&token);
+  assert_eq!(it.next(), None);
+  assert_eq!(
+    it.finish(),
+    Err(Err::Failure(error_position!(
+      "abc|def|",
+      ErrorKind::Cancelled
+    )))
+  );
+
+  let mut parser = cancellable(&token, take::<_, _, (&[u8], ErrorKind)>(2u8));
+  assert_eq!(
+    parser.parse(&b"abc"[..]),
+    Err(Err::Failure(error_position!(
+      &b"abc"[..],
+      ErrorKind::Cancelled
+    )))
+  );
+  token.store(false, Ordering::Relaxed);
+  assert_eq!(parser.parse(&b"abc"[..]), Ok((&b"c"[..], &b"ab"[..])));
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn parse_stream_test() {
+  use crate::number::be_u16;
+
+  let data = &b"\x00\x01\x00\x02\x00"[..];
+  let mut it = iterator(data, be_u16::<_, (&[u8], ErrorKind)>());
+  assert_eq!(it.size_hint(), (0, None));
+  assert_eq!(it.next(), Some(1));
+  assert_eq!(*it.remaining(), &data[2..]);
+  assert_eq!(it.next(), Some(2));
+  assert_eq!(it.next(), None);
+  assert_eq!(it.error(), Some(&Err::Incomplete(Needed::new(1))));
+  assert_eq!(it.size_hint(), (0, Some(0)));
+  // the iteration stays over
+  assert_eq!(it.next(), None);
+  assert_eq!(
+    it.into_parts(),
+    (&data[4..], Some(Err::Incomplete(Needed::new(1))))
+  );
+
+  // the data is received one byte at a time, then the parser runs in complete mode
+  let mut end = 1;
+  let mut it =
+    iterator(&data[..1], be_u16::<_, (&[u8], ErrorKind)>()).refill(|rest: &'static [u8], _| {
+      if end == data.len() {
+        return None;
+      }
+      let start = end - rest.len();
+      end += 1;
+      Some(&data[start..end])
+    });
+  assert_eq!(it.by_ref().collect::<Vec<_>>(), [1, 2]);
+  assert_eq!(
+    it.into_parts(),
+    (&data[4..], Some(Err::Error((&data[4..], ErrorKind::Eof))))
+  );
+}
+
+#[test]
+fn trace_test() {
+  use crate::character::complete::{alpha1, digit1};
+
+  let mut parser = trace(
+    "word",
+    (
+      trace("alpha", alpha1::<_, (&str, ErrorKind)>),
+      trace("digit", digit1),
+    ),
+  );
+  assert_eq!(parser.parse("abc123;"), Ok((";", ("abc", "123"))));
+  assert_eq!(
+    parser.parse("abc;"),
+    Err(Err::Error(error_position!(";", ErrorKind::Digit)))
+  );
+
+  let mut parser = trace("take", take::<_, _, (&[u8], ErrorKind)>(2u8));
+  assert_eq!(parser.parse(&b"abc"[..]), Ok((&b"c"[..], &b"ab"[..])));
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn profiler_test() {
+  use crate::branch::alt;
+  use crate::character::complete::{char, digit1};
+  use crate::sequence::delimited;
+
+  fn value<'a>(profiler: &Profiler, i: &'a str) -> IResult<&'a str, &'a str> {
+    profiler
+      .profile(
+        "value",
+        alt((
+          digit1,
+          delimited(char('('), |i| value(profiler, i), char(')')),
+        )),
+      )
+      .parse(i)
+  }
+
+  let profiler = Profiler::new();
+  assert_eq!(value(&profiler, "((1))"), Ok(("", "1")));
+  assert_eq!(
+    profiler.stats("value"),
+    Some(ProfileStats {
+      calls: 3,
+      consumed: 5 + 3 + 1,
+      max_depth: 3,
+      ..ProfileStats::default()
+    })
+  );
+
+  assert_eq!(
+    value(&profiler, "(("),
+    Err(Err::Error(error_position!("", ErrorKind::Char)))
+  );
+  let stats = profiler.stats("value").unwrap();
+  assert_eq!(stats.calls, 6);
+  assert_eq!(stats.errors, 3);
+  assert_eq!(stats.repeated_calls, 0);
+
+  // positions are identified by the remaining length, so parsing "(1)" after "((1))"
+  // and "((" repeats the applications at lengths 3 and 2
+  assert_eq!(value(&profiler, "(1)"), Ok(("", "1")));
+  let stats = profiler.stats("value").unwrap();
+  assert_eq!(stats.repeated_calls, 2);
+  assert_eq!(stats.reexamined, 3 + 1);
+
+  assert_eq!(profiler.report(), [("value", stats)]);
+  profiler.reset();
+  assert_eq!(profiler.stats("value"), None);
+}
+
+#[test]
+fn span_test() {
+  use crate::character::complete::{alpha1, space0};
+  use crate::multi::many0;
+  use crate::sequence::terminated;
+
+  let input = &b"ab cd  ef"[..];
+  let mut parser = many0(terminated(
+    with_span(input, alpha1::<_, (&[u8], ErrorKind)>),
+    space0,
+  ));
+  assert_parse!(
+    parser.parse(input),
+    Ok((
+      &b""[..],
+      vec![(0..2, &b"ab"[..]), (3..5, &b"cd"[..]), (7..9, &b"ef"[..])]
+    ))
+  );
+
+  let mut parser = (alpha1::<_, (&str, ErrorKind)>, position("ab cd"));
+  assert_eq!(parser.parse("ab cd"), Ok((" cd", ("ab", 2))));
+  assert_eq!(
+    with_span("ab cd", alpha1::<_, (&str, ErrorKind)>).parse(" cd"),
+    Err(Err::Error(error_position!(" cd", ErrorKind::Alpha)))
+  );
+}
+
+#[test]
+fn text_binary_test() {
+  use crate::bytes::complete::take;
+  use crate::character::complete::alpha1;
+  use crate::character::streaming::alpha1 as streaming_alpha1;
+
+  // the text parser only sees the valid UTF-8 prefix
+  let mut parser = text(alpha1::<_, (&str, ErrorKind)>);
+  assert_eq!(parser.parse(&b"ab\xffcd"[..]), Ok((&b"\xffcd"[..], "ab")));
+  assert_eq!(
+    parser.parse(&b"\xc3\xa9a"[..]),
+    Err(Err::Error(error_position!(
+      &b"\xc3\xa9a"[..],
+      ErrorKind::Alpha
+    )))
+  );
+  assert_eq!(
+    text(streaming_alpha1::<_, (&str, ErrorKind)>).parse(&b"ab\xc3"[..]),
+    Err(Err::Incomplete(Needed::new(1)))
+  );
+  // more data cannot extend a prefix ending at an invalid byte
+  assert_eq!(
+    text(crate::character::digit1::<_, (&str, ErrorKind)>()).parse(&b"12\xff3"[..]),
+    Ok((&b"\xff3"[..], "12"))
+  );
+  assert_eq!(
+    text(crate::bytes::tag::<_, _, (&str, ErrorKind)>("abc")).parse(&b"ab\xff\0"[..]),
+    Err(Err::Error(error_position!(
+      &b"ab\xff\0"[..],
+      ErrorKind::Tag
+    )))
+  );
+  // the errors point into the byte input, past the invalid byte
+  assert_eq!(
+    text((alpha1::<_, (&str, ErrorKind)>, alpha1)).parse(&b"ab\xffcd"[..]),
+    Err(Err::Error(error_position!(
+      &b"\xffcd"[..],
+      ErrorKind::Alpha
+    )))
+  );
+
+  let mut parser = binary(cut(take::<_, _, (&[u8], ErrorKind)>(4u8)));
+  assert_eq!(parser.parse("aébc"), Ok(("c", &b"a\xc3\xa9b"[..])));
+  // the error position is moved back to the start of the character
+  assert_eq!(
+    binary(take::<_, _, (&[u8], ErrorKind)>(2u8)).parse("aé"),
+    Err(Err::Error(error_position!("é", ErrorKind::Utf8)))
+  );
+  assert_eq!(
+    parser.parse("aé"),
+    Err(Err::Failure(error_position!("aé", ErrorKind::Eof)))
+  );
+}
+
+#[test]
+fn on_partial_test() {
+  use crate::bytes::{tag, take_until};
+  use crate::Partial;
+
+  let mut parser = (
+    tag::<_, _, (_, ErrorKind)>("<"),
+    on_partial(take_until::<_, &[u8], (&[u8], ErrorKind)>(">")),
+  );
+  assert_eq!(
+    parser.parse(Partial::new(&b"<ab"[..])),
+    Err(Err::Incomplete(Needed::Unknown))
+  );
+  let input = Partial::new(&b"<ab"[..]).complete();
+  assert_eq!(
+    parser.parse(input),
+    Err(Err::Error(error_position!(
+      input.take_from(1),
+      ErrorKind::TakeUntil
+    )))
+  );
+  let (rest, (_, inner)) = parser.parse(Partial::new(&b"<ab>"[..])).unwrap();
+  assert_eq!((rest.into_inner(), inner), (&b">"[..], &b"ab"[..]));
+  assert!(rest.is_partial());
+}
+
+#[test]
+fn resolve_test() {
+  use crate::error::FromExternalError;
+  use crate::number::streaming::be_u16;
+
+  #[derive(Debug, PartialEq)]
+  enum TableError<'a> {
+    OutOfRange(&'a [u8], IndexOutOfRange),
+    Other(&'a [u8], ErrorKind),
+  }
+
+  impl<'a> ParseError<&'a [u8]> for TableError<'a> {
+    fn from_error_kind(input: &'a [u8], kind: ErrorKind) -> Self {
+      TableError::Other(input, kind)
+    }
+
+    fn append(_: &'a [u8], _: ErrorKind, other: Self) -> Self {
+      other
+    }
+  }
+
+  impl<'a> FromExternalError<&'a [u8], IndexOutOfRange> for TableError<'a> {
+    fn from_external_error(input: &'a [u8], _: ErrorKind, e: IndexOutOfRange) -> Self {
+      TableError::OutOfRange(input, e)
+    }
+  }
+
+  let table = ["one", "two", "three"];
+  let mut parser = resolve::<_, _, TableError<'_>, _>(&table, 1, u8);
+  assert_eq!(parser.parse(&b"\x02;"[..]), Ok((&b";"[..], &"two")));
+  // below the base index
+  assert_eq!(
+    parser.parse(&b"\x00"[..]),
+    Err(Err::Error(TableError::OutOfRange(
+      &b"\x00"[..],
+      IndexOutOfRange { index: 0, len: 3 }
+    )))
+  );
+  assert_eq!(
+    parser.parse(&b"\x04"[..]),
+    Err(Err::Error(TableError::OutOfRange(
+      &b"\x04"[..],
+      IndexOutOfRange { index: 4, len: 3 }
+    )))
+  );
+  assert_eq!(
+    resolve::<_, _, TableError<'_>, _>(&table, 0, be_u16).parse(&b"\x00"[..]),
+    Err(Err::Incomplete(Needed::new(1)))
+  );
+  assert_eq!(
+    IndexOutOfRange { index: 4, len: 3 }.to_string(),
+    "index 4 out of range for a table of length 3"
+  );
+
+  let strtab = &b"\0abc\0def"[..];
+  let mut parser = resolve_str::<_, TableError<'_>, _>(strtab, u8);
+  assert_eq!(parser.parse(&b"\x02"[..]), Ok((&b""[..], &b"bc"[..])));
+  // the last string is not terminated
+  assert_eq!(
+    parser.parse(&b"\x05"[..]),
+    Err(Err::Error(TableError::Other(&b"\x05"[..], ErrorKind::Eof)))
+  );
+  assert_eq!(
+    parser.parse(&b"\x08"[..]),
+    Err(Err::Error(TableError::OutOfRange(
+      &b"\x08"[..],
+      IndexOutOfRange { index: 8, len: 8 }
+    )))
+  );
+}
+
+#[test]
+#[cfg(feature = "debug")]
+fn dbg_test() {
+  use crate::character::complete::{alpha1, digit1};
+  use crate::combinator::recognize;
+  use crate::error::Error;
+  use crate::lib::std::string::{String, ToString};
+  use crate::lib::std::vec::Vec;
+  use std::sync::Mutex;
+
+  static EVENTS: Mutex<Vec<(&'static str, String)>> = Mutex::new(Vec::new());
+  fn sink(label: &'static str, message: fmt::Arguments<'_>) {
+    EVENTS.lock().unwrap().push((label, message.to_string()));
+  }
+
+  let previous = set_dbg_sink(sink);
+  let mut parser = (alpha1.dbg("alpha"), digit1.dbg("digit"));
+  let res: IResult<_, _, Error<_>> = parser.parse_complete("abc123;");
+  assert_eq!(res, Ok((";", ("abc", "123"))));
+  // errors are shown even in check mode
+  let res: IResult<_, _, Error<_>> = recognize(digit1.dbg("digit")).parse_complete("x");
+  assert!(res.is_err());
+  // the input is cut
+  let long = "a".repeat(100);
+  let _: IResult<_, _, Error<_>> = digit1.dbg("digit").parse_complete(&long[..]);
+  // any input implementing Debug
+  let _: IResult<_, _, Error<_>> = u8.dbg("byte").parse(&[1u8, 2][..]);
+  set_dbg_sink(previous);
+
+  assert_eq!(
+    *EVENTS.lock().unwrap(),
+    [
+      (
+        "alpha",
+        r#"alpha: "abc123;" -> ok, rest "123;""#.to_string()
+      ),
+      ("digit", r#"digit: "123;" -> ok, rest ";""#.to_string()),
+      (
+        "digit",
+        r#"digit: "x" -> error Error { input: "x", code: Digit }"#.to_string()
+      ),
+      (
+        "digit",
+        format!(
+          r#"digit: "{}... -> error Error {{ input: "{}..."#,
+          "a".repeat(63),
+          "a".repeat(48)
+        )
+      ),
+      ("byte", "byte: [1, 2] -> ok, rest [2]".to_string()),
+    ]
+  );
+
+  let mut seen = Vec::new();
+  let mut errors = 0;
+  let res: IResult<_, _, Error<_>> = recognize(inspect(
+    digit1,
+    |i: &&str, o: &&str| seen.push((*i, *o)),
+    |_: &&str, _: &Err<_>| errors += 1,
+  ))
+  .parse_complete("12;");
+  assert_eq!(res, Ok((";", "12")));
+  assert_eq!(seen, [("12;", "12")]);
+  assert_eq!(errors, 0);
+}
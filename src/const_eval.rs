@@ -0,0 +1,275 @@
+//! Parsers that can be evaluated at compile time
+//!
+//! Trait methods cannot be called from a `const fn` on stable Rust, so the generic
+//! combinators from the rest of the crate cannot run at compile time. This module
+//! provides a small set of byte slice parsers written as `const fn`: they can be
+//! used to validate magic numbers, embedded resource headers or configuration
+//! strings while the crate is being built.
+//!
+//! Those parsers work on `&[u8]` with the default [Error] type, and they follow the
+//! *complete* semantics: the data is entirely known when they are evaluated, so
+//! they never return `Incomplete`.
+//!
+//! Since closures and function pointers cannot be called from a `const fn` either,
+//! sequences are written by hand, with the [const_try!](crate::const_try) macro
+//! playing the role of the `?` operator:
+//!
+//! ```rust
+//! use nom::{const_try, IResult};
+//! use nom::const_eval::{be_u32, eof, tag};
+//!
+//! const HEADER: &[u8] = b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR";
+//!
+//! const fn png_header(input: &[u8]) -> IResult<&[u8], u32> {
+//!   let (input, _) = const_try!(tag(input, b"\x89PNG\r\n\x1a\n"));
+//!   let (input, length) = const_try!(be_u32(input));
+//!   let (input, _) = const_try!(tag(input, b"IHDR"));
+//!   let (input, _) = const_try!(eof(input));
+//!   Ok((input, length))
+//! }
+//!
+//! // this fails to compile if the header is invalid
+//! const _: () = assert!(matches!(png_header(HEADER), Ok((_, 13))));
+//! ```
+
+use crate::error::{Error, ErrorKind};
+use crate::internal::{Err, IResult};
+
+/// Evaluates an expression returning an [IResult] in a `const fn`, returns early on error.
+///
+/// This plays the same role as the `?` operator, which is not available in a `const fn`.
+///
+/// ```rust
+/// use nom::{const_try, IResult};
+/// use nom::const_eval::{tag, u8};
+///
+/// const fn version(input: &[u8]) -> IResult<&[u8], u8> {
+///   let (input, _) = const_try!(tag(input, b"v"));
+///   u8(input)
+/// }
+///
+/// const VERSION: u8 = match version(b"v\x02") {
+///   Ok((_, v)) => v,
+///   Err(_) => panic!("invalid version"),
+/// };
+/// assert_eq!(VERSION, 2);
+/// ```
+#[macro_export]
+macro_rules! const_try (
+  ($e:expr) => (
+    match $e {
+      $crate::lib::std::result::Result::Ok(res) => res,
+      $crate::lib::std::result::Result::Err(e) => return $crate::lib::std::result::Result::Err(e),
+    }
+  );
+);
+
+#[inline]
+const fn error(input: &[u8], code: ErrorKind) -> Err<Error<&[u8]>> {
+  Err::Error(Error { input, code })
+}
+
+/// Splits the input at `index`, returning `(remaining, taken)` like [Input::take_split].
+///
+/// `<[u8]>::split_at` is not a `const fn` in our minimal supported Rust version.
+///
+/// [Input::take_split]: crate::Input::take_split
+#[inline]
+const fn take_split(input: &[u8], index: usize) -> (&[u8], &[u8]) {
+  assert!(index <= input.len());
+  // SAFETY: `index` is in bounds, so both slices are contained in `input`
+  unsafe {
+    (
+      core::slice::from_raw_parts(input.as_ptr().add(index), input.len() - index),
+      core::slice::from_raw_parts(input.as_ptr(), index),
+    )
+  }
+}
+
+/// Recognizes a fixed byte pattern.
+///
+/// Returns an error with [ErrorKind::Tag] if the input does not start with `tag`.
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}};
+/// use nom::const_eval::tag;
+///
+/// const RES: bool = tag(b"GIF89a...", b"GIF89a").is_ok();
+/// assert!(RES);
+/// assert_eq!(tag(b"GIF89a...", b"GIF"), Ok((&b"89a..."[..], &b"GIF"[..])));
+/// assert_eq!(tag(b"PNG", b"GIF"), Err(Err::Error(Error::new(&b"PNG"[..], ErrorKind::Tag))));
+/// assert_eq!(tag(b"GI", b"GIF"), Err(Err::Error(Error::new(&b"GI"[..], ErrorKind::Tag))));
+/// ```
+pub const fn tag<'a>(input: &'a [u8], tag: &[u8]) -> IResult<&'a [u8], &'a [u8]> {
+  if input.len() < tag.len() {
+    return Err(error(input, ErrorKind::Tag));
+  }
+
+  let mut i = 0;
+  while i < tag.len() {
+    if input[i] != tag[i] {
+      return Err(error(input, ErrorKind::Tag));
+    }
+    i += 1;
+  }
+
+  Ok(take_split(input, tag.len()))
+}
+
+/// Returns a slice of `count` bytes.
+///
+/// Returns an error with [ErrorKind::Eof] if the input is shorter than `count`.
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}};
+/// use nom::const_eval::take;
+///
+/// assert_eq!(take(b"abcdef", 4), Ok((&b"ef"[..], &b"abcd"[..])));
+/// assert_eq!(take(b"ab", 4), Err(Err::Error(Error::new(&b"ab"[..], ErrorKind::Eof))));
+/// ```
+pub const fn take(input: &[u8], count: usize) -> IResult<&[u8], &[u8]> {
+  if input.len() < count {
+    Err(error(input, ErrorKind::Eof))
+  } else {
+    Ok(take_split(input, count))
+  }
+}
+
+/// Succeeds if the whole input has been consumed.
+///
+/// Returns an error with [ErrorKind::Eof] if there is remaining data.
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}};
+/// use nom::const_eval::eof;
+///
+/// assert_eq!(eof(b""), Ok((&b""[..], &b""[..])));
+/// assert_eq!(eof(b"abc"), Err(Err::Error(Error::new(&b"abc"[..], ErrorKind::Eof))));
+/// ```
+pub const fn eof(input: &[u8]) -> IResult<&[u8], &[u8]> {
+  if input.is_empty() {
+    Ok((input, input))
+  } else {
+    Err(error(input, ErrorKind::Eof))
+  }
+}
+
+macro_rules! const_numbers (
+  ($($(#[$attr:meta])* $name:ident: $t:ty = $from:ident;)+) => (
+    $(
+      $(#[$attr])*
+      ///
+      /// Returns an error with [ErrorKind::Eof] if there is not enough input data.
+      #[inline]
+      pub const fn $name(input: &[u8]) -> IResult<&[u8], $t> {
+        const SIZE: usize = core::mem::size_of::<$t>();
+        if input.len() < SIZE {
+          return Err(error(input, ErrorKind::Eof));
+        }
+
+        let mut bytes = [0u8; SIZE];
+        let mut i = 0;
+        while i < SIZE {
+          bytes[i] = input[i];
+          i += 1;
+        }
+
+        let (input, _) = take_split(input, SIZE);
+        Ok((input, <$t>::$from(bytes)))
+      }
+    )+
+  );
+);
+
+const_numbers! {
+  /// Recognizes an unsigned 1 byte integer.
+  u8: u8 = from_be_bytes;
+  /// Recognizes a signed 1 byte integer.
+  i8: i8 = from_be_bytes;
+  /// Recognizes a big endian unsigned 2 bytes integer.
+  be_u16: u16 = from_be_bytes;
+  /// Recognizes a big endian unsigned 4 bytes integer.
+  be_u32: u32 = from_be_bytes;
+  /// Recognizes a big endian unsigned 8 bytes integer.
+  be_u64: u64 = from_be_bytes;
+  /// Recognizes a big endian signed 2 bytes integer.
+  be_i16: i16 = from_be_bytes;
+  /// Recognizes a big endian signed 4 bytes integer.
+  be_i32: i32 = from_be_bytes;
+  /// Recognizes a big endian signed 8 bytes integer.
+  be_i64: i64 = from_be_bytes;
+  /// Recognizes a little endian unsigned 2 bytes integer.
+  le_u16: u16 = from_le_bytes;
+  /// Recognizes a little endian unsigned 4 bytes integer.
+  le_u32: u32 = from_le_bytes;
+  /// Recognizes a little endian unsigned 8 bytes integer.
+  le_u64: u64 = from_le_bytes;
+  /// Recognizes a little endian signed 2 bytes integer.
+  le_i16: i16 = from_le_bytes;
+  /// Recognizes a little endian signed 4 bytes integer.
+  le_i32: i32 = from_le_bytes;
+  /// Recognizes a little endian signed 8 bytes integer.
+  le_i64: i64 = from_le_bytes;
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const fn record(input: &[u8]) -> IResult<&[u8], (u16, u32)> {
+    let (input, _) = const_try!(tag(input, b"REC"));
+    let (input, a) = const_try!(le_u16(input));
+    let (input, b) = const_try!(be_u32(input));
+    let (input, _) = const_try!(eof(input));
+    Ok((input, (a, b)))
+  }
+
+  const RECORD: (u16, u32) = match record(b"REC\x01\x02\x00\x00\x01\x00") {
+    Ok((_, r)) => r,
+    Err(_) => panic!("invalid record"),
+  };
+
+  #[test]
+  fn const_sequence() {
+    assert_eq!(RECORD, (0x0201, 0x100));
+    assert_eq!(
+      record(b"REC\x01\x02\x00\x00"),
+      Err(Err::Error(Error::new(&b"\x00\x00"[..], ErrorKind::Eof)))
+    );
+    assert_eq!(
+      record(b"REC\x01\x02\x00\x00\x01\x00\x00"),
+      Err(Err::Error(Error::new(&b"\x00"[..], ErrorKind::Eof)))
+    );
+    assert_eq!(
+      record(b"RAC"),
+      Err(Err::Error(Error::new(&b"RAC"[..], ErrorKind::Tag)))
+    );
+  }
+
+  #[test]
+  fn const_numbers() {
+    assert_eq!(u8(b"\xff"), Ok((&b""[..], 0xff)));
+    assert_eq!(i8(b"\xff"), Ok((&b""[..], -1)));
+    assert_eq!(be_i16(b"\xff\xfe"), Ok((&b""[..], -2)));
+    assert_eq!(le_i16(b"\xfe\xff"), Ok((&b""[..], -2)));
+    assert_eq!(
+      be_u64(b"\x00\x00\x00\x00\x00\x00\x00\x01a"),
+      Ok((&b"a"[..], 1))
+    );
+    assert_eq!(
+      le_u64(b"\x01\x00\x00\x00\x00\x00\x00\x00"),
+      Ok((&b""[..], 1))
+    );
+    assert_eq!(
+      le_u32(b"\x01\x00\x00"),
+      Err(Err::Error(Error::new(&b"\x01\x00\x00"[..], ErrorKind::Eof)))
+    );
+  }
+
+  #[test]
+  fn const_tag() {
+    assert_eq!(tag(b"abc", b""), Ok((&b"abc"[..], &b""[..])));
+    assert_eq!(tag(b"abc", b"abc"), Ok((&b""[..], &b"abc"[..])));
+    assert_eq!(take(b"abc", 0), Ok((&b"abc"[..], &b""[..])));
+  }
+}
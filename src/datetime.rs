@@ -0,0 +1,813 @@
+//! Parsers for the timestamps of internet protocols
+//!
+//! This module recognizes the date formats of [RFC 3339](https://www.rfc-editor.org/rfc/rfc3339)
+//! (`1985-04-12T23:20:50.52Z`), of the email headers from
+//! [RFC 2822](https://www.rfc-editor.org/rfc/rfc2822) (`Fri, 21 Nov 1997 09:55:06 -0600`),
+//! and of HTTP headers from [RFC 9110](https://www.rfc-editor.org/rfc/rfc9110#name-date-time-formats)
+//! (`Sun, 06 Nov 1994 08:49:37 GMT`). They are built from the byte and character parsers,
+//! so they accept `&str` and `&[u8]` inputs, and return `Err::Incomplete` on streaming
+//! data that ends in a timestamp.
+//!
+//! The parsers return a [DateTime], made of the fields written in the timestamp. They
+//! reject the dates and times that do not exist, like February 30th, or a day of the week
+//! that does not match the date. With the `chrono` and `time` features, a [DateTime] can be
+//! converted to the types of those crates.
+//!
+//! ```rust
+//! # use nom::{error::Error, Parser};
+//! use nom::datetime::{rfc3339, Date, DateTime, Time};
+//!
+//! let (_, dt) = rfc3339::<_, Error<_>>().parse_complete("1985-04-12T23:20:50.52-04:00").unwrap();
+//! assert_eq!(
+//!   dt,
+//!   DateTime {
+//!     date: Date { year: 1985, month: 4, day: 12 },
+//!     time: Time { hour: 23, minute: 20, second: 50, nanosecond: 520_000_000 },
+//!     offset: -240,
+//!   }
+//! );
+//! assert_eq!(dt.unix_timestamp(), 482210450);
+//! ```
+
+use crate::branch::alt;
+use crate::bytes::{tag, take_while, take_while1, take_while_m_n};
+use crate::character::{char, one_of};
+use crate::combinator::{map_opt, opt, verify};
+use crate::error::ParseError;
+use crate::sequence::{preceded, terminated};
+use crate::{AsChar, Compare, CompareResult, Input, Parser};
+
+/// Calendar date
+///
+/// The fields are ordered so that comparing dates compares them chronologically.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+  /// year, from 0 to 9999
+  pub year: u16,
+  /// month, from 1 to 12
+  pub month: u8,
+  /// day of the month, from 1 to 31
+  pub day: u8,
+}
+
+/// Time of the day
+///
+/// The fields are ordered so that comparing times compares them chronologically.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Time {
+  /// from 0 to 23
+  pub hour: u8,
+  /// from 0 to 59
+  pub minute: u8,
+  /// from 0 to 60, for leap seconds
+  pub second: u8,
+  /// fraction of the second, from 0 to 999 999 999
+  pub nanosecond: u32,
+}
+
+/// Date and time, with the offset from UTC they are written in
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DateTime {
+  /// local date
+  pub date: Date,
+  /// local time
+  pub time: Time,
+  /// offset from UTC in minutes, positive east of Greenwich. The unknown offsets `-00:00`
+  /// and `-0000` are 0.
+  pub offset: i16,
+}
+
+impl Date {
+  /// Returns the number of days since 1970-01-01
+  pub fn days_since_epoch(&self) -> i64 {
+    // algorithm from http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+    let month = self.month as i64;
+    let year = self.year as i64 - (month <= 2) as i64;
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + self.day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+  }
+
+  /// Returns the day of the week, from 0 for Monday to 6 for Sunday
+  pub fn weekday(&self) -> u8 {
+    // 1970-01-01 was a Thursday
+    (self.days_since_epoch() + 3).rem_euclid(7) as u8
+  }
+
+  fn is_valid(&self) -> bool {
+    (1..=12).contains(&self.month) && (1..=days_in_month(self.year, self.month)).contains(&self.day)
+  }
+}
+
+impl Time {
+  fn is_valid(&self) -> bool {
+    self.hour <= 23 && self.minute <= 59 && self.second <= 60
+  }
+}
+
+impl DateTime {
+  /// Returns the number of seconds since 1970-01-01T00:00:00Z, with leap seconds
+  /// counted as the following second
+  pub fn unix_timestamp(&self) -> i64 {
+    self.date.days_since_epoch() * 86_400
+      + self.time.hour as i64 * 3_600
+      + self.time.minute as i64 * 60
+      + self.time.second as i64
+      - self.offset as i64 * 60
+  }
+
+  /// Converts the date and time to a `chrono` type, or returns `None` if it cannot
+  /// represent them
+  ///
+  /// Leap seconds are represented like in `chrono`, as the second 59 with more than
+  /// 1 000 000 000 nanoseconds.
+  #[cfg(feature = "chrono")]
+  #[cfg_attr(feature = "docsrs", doc(cfg(feature = "chrono")))]
+  pub fn to_chrono(&self) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+    use chrono::TimeZone;
+
+    let (second, nanosecond) = match self.time.second {
+      60 => (59, self.time.nanosecond + 1_000_000_000),
+      second => (second, self.time.nanosecond),
+    };
+    let date = chrono::NaiveDate::from_ymd_opt(
+      self.date.year as i32,
+      self.date.month as u32,
+      self.date.day as u32,
+    )?;
+    let datetime = date.and_hms_nano_opt(
+      self.time.hour as u32,
+      self.time.minute as u32,
+      second as u32,
+      nanosecond,
+    )?;
+
+    chrono::FixedOffset::east_opt(self.offset as i32 * 60)?
+      .from_local_datetime(&datetime)
+      .single()
+  }
+
+  /// Converts the date and time to a `time` type, or returns `None` if it cannot
+  /// represent them, like for leap seconds
+  #[cfg(feature = "time")]
+  #[cfg_attr(feature = "docsrs", doc(cfg(feature = "time")))]
+  pub fn to_time(&self) -> Option<time::OffsetDateTime> {
+    let month = time::Month::try_from(self.date.month).ok()?;
+    let date = time::Date::from_calendar_date(self.date.year as i32, month, self.date.day).ok()?;
+    let t = time::Time::from_hms_nano(
+      self.time.hour,
+      self.time.minute,
+      self.time.second,
+      self.time.nanosecond,
+    )
+    .ok()?;
+    let offset = time::UtcOffset::from_whole_seconds(self.offset as i32 * 60).ok()?;
+
+    Some(time::PrimitiveDateTime::new(date, t).assume_offset(offset))
+  }
+}
+
+const MONTHS: [&str; 12] = [
+  "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+const LONG_WEEKDAYS: [&str; 7] = [
+  "Monday",
+  "Tuesday",
+  "Wednesday",
+  "Thursday",
+  "Friday",
+  "Saturday",
+  "Sunday",
+];
+
+fn is_leap_year(year: u16) -> bool {
+  year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+fn days_in_month(year: u16, month: u8) -> u8 {
+  match month {
+    2 if is_leap_year(year) => 29,
+    2 => 28,
+    4 | 6 | 9 | 11 => 30,
+    _ => 31,
+  }
+}
+
+/// Recognizes a full date, like `1985-04-12`
+///
+/// It returns an error with [ErrorKind::Verify](crate::error::ErrorKind::Verify) if the
+/// date does not exist.
+pub fn rfc3339_date<I, E: ParseError<I>>() -> impl Parser<I, Output = Date, Error = E>
+where
+  I: Input + Compare<&'static str>,
+  <I as Input>::Item: AsChar,
+{
+  let date = (
+    number(4, 4),
+    char('-'),
+    number(2, 2),
+    char('-'),
+    number(2, 2),
+  )
+    .map(|(year, _, month, _, day)| Date {
+      year: year as u16,
+      month: month as u8,
+      day: day as u8,
+    });
+
+  verify(date, Date::is_valid)
+}
+
+/// Recognizes a time without offset, like `23:20:50.52`
+///
+/// The fraction of the second can have any number of digits, and the digits after the
+/// 9th are ignored. It returns an error with
+/// [ErrorKind::Verify](crate::error::ErrorKind::Verify) if the time does not exist.
+pub fn rfc3339_time<I, E: ParseError<I>>() -> impl Parser<I, Output = Time, Error = E>
+where
+  I: Input + Compare<&'static str>,
+  <I as Input>::Item: AsChar,
+{
+  let time = (
+    number(2, 2),
+    char(':'),
+    number(2, 2),
+    char(':'),
+    number(2, 2),
+    opt(preceded(char('.'), fraction())),
+  )
+    .map(|(hour, _, minute, _, second, nanosecond)| Time {
+      hour: hour as u8,
+      minute: minute as u8,
+      second: second as u8,
+      nanosecond: nanosecond.unwrap_or(0),
+    });
+
+  verify(time, Time::is_valid)
+}
+
+/// Recognizes an offset from UTC, like `Z` or `-04:00`, and returns it in minutes
+pub fn rfc3339_offset<I, E: ParseError<I>>() -> impl Parser<I, Output = i16, Error = E>
+where
+  I: Input + Compare<&'static str>,
+  <I as Input>::Item: AsChar,
+{
+  let numeric = verify(
+    (one_of("+-"), number(2, 2), char(':'), number(2, 2)),
+    |(_, hour, _, minute)| *hour <= 23 && *minute <= 59,
+  )
+  .map(|(sign, hour, _, minute)| signed_offset(sign, hour * 60 + minute));
+
+  alt((one_of("Zz").map(|_| 0), numeric))
+}
+
+/// Recognizes a RFC 3339 timestamp, like `1985-04-12T23:20:50.52Z`
+///
+/// The date and time can be separated by `T`, `t` or a space, as RFC 3339 allows.
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Needed, Parser};
+/// use nom::datetime::rfc3339;
+///
+/// let mut parser = rfc3339::<_, Error<_>>();
+/// assert_eq!(
+///   parser.parse_complete("1990-12-31 23:59:60z").map(|(_, dt)| dt.unix_timestamp()),
+///   Ok(662688000)
+/// );
+/// assert_eq!(
+///   parser.parse_complete("2023-02-29T00:00:00Z"),
+///   Err(Err::Error(Error::new("2023-02-29T00:00:00Z", ErrorKind::Verify)))
+/// );
+/// assert_eq!(parser.parse("2023-02-28T00:00:00.12"), Err(Err::Incomplete(Needed::new(1))));
+/// ```
+pub fn rfc3339<I, E: ParseError<I>>() -> impl Parser<I, Output = DateTime, Error = E>
+where
+  I: Input + Compare<&'static str>,
+  <I as Input>::Item: AsChar,
+{
+  (
+    rfc3339_date(),
+    one_of("Tt "),
+    rfc3339_time(),
+    rfc3339_offset(),
+  )
+    .map(|(date, _, time, offset)| DateTime { date, time, offset })
+}
+
+/// Recognizes the date of an email header, following RFC 2822, like
+/// `Fri, 21 Nov 1997 09:55:06 -0600`
+///
+/// It takes the unfolded header value, and accepts:
+/// * an optional day of the week, which must match the date
+/// * the day of the month with 1 or 2 digits
+/// * a year with 2 or 3 digits from the obsolete syntax: 2 digit years below 50 are in
+///   the 21st century, the other ones are in the 20th century
+/// * optional seconds
+/// * a numeric zone like `+0200`, or the obsolete `UT`, `GMT`, or north American zones
+///   like `EST`. Military zones are unknown offsets, represented as 0
+///
+/// Names are case insensitive, and the parts can be separated by any number of spaces or
+/// tabs. Comments are not supported.
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Needed, Parser};
+/// use nom::datetime::rfc2822;
+///
+/// let mut parser = rfc2822::<_, Error<_>>();
+/// let (_, dt) = parser.parse_complete("Fri, 21 Nov 1997 09:55:06 -0600").unwrap();
+/// assert_eq!((dt.date.day, dt.time.hour, dt.offset), (21, 9, -360));
+///
+/// let (_, dt) = parser.parse_complete("21 nov 97 09:55 EST").unwrap();
+/// assert_eq!((dt.date.year, dt.time.second, dt.offset), (1997, 0, -300));
+///
+/// assert_eq!(
+///   parser.parse_complete("Sat, 21 Nov 1997 09:55:06 -0600"),
+///   Err(Err::Error(Error::new("Sat, 21 Nov 1997 09:55:06 -0600", ErrorKind::Verify)))
+/// );
+/// ```
+pub fn rfc2822<I, E: ParseError<I>>() -> impl Parser<I, Output = DateTime, Error = E>
+where
+  I: Input + Compare<&'static str>,
+  <I as Input>::Item: AsChar,
+{
+  let weekday = terminated(
+    name(3, &WEEKDAYS, true),
+    (whitespace0(), char(','), whitespace0()),
+  );
+  let year = take_while_m_n(2, 4, is_digit::<I>).map(|year: I| {
+    let value = digits_value(&year) as u16;
+    match year.input_len() {
+      2 if value < 50 => 2000 + value,
+      2 | 3 => 1900 + value,
+      _ => value,
+    }
+  });
+  let date = (
+    number(1, 2),
+    whitespace1(),
+    name(3, &MONTHS, true),
+    whitespace1(),
+    year,
+  )
+    .map(|(day, _, month, _, year)| Date {
+      year,
+      month: month + 1,
+      day: day as u8,
+    });
+  let time = (
+    number(2, 2),
+    char(':'),
+    number(2, 2),
+    opt(preceded(char(':'), number(2, 2))),
+  )
+    .map(|(hour, _, minute, second)| Time {
+      hour: hour as u8,
+      minute: minute as u8,
+      second: second.unwrap_or(0) as u8,
+      nanosecond: 0,
+    });
+
+  let datetime = (
+    opt(weekday),
+    verify(date, Date::is_valid),
+    whitespace1(),
+    verify(time, Time::is_valid),
+    whitespace1(),
+    rfc2822_zone(),
+  )
+    .map(|(weekday, date, _, time, _, offset)| (weekday, DateTime { date, time, offset }));
+
+  verify(datetime, |(weekday, dt)| {
+    weekday.map_or(true, |w| w == dt.date.weekday())
+  })
+  .map(|(_, dt)| dt)
+}
+
+/// Recognizes the zone of a RFC 2822 date, and returns it in minutes
+fn rfc2822_zone<I, E: ParseError<I>>() -> impl Parser<I, Output = i16, Error = E>
+where
+  I: Input + Compare<&'static str>,
+  <I as Input>::Item: AsChar,
+{
+  let numeric = verify((one_of("+-"), number(4, 4)), |(_, zone)| zone % 100 <= 59)
+    .map(|(sign, zone)| signed_offset(sign, zone / 100 * 60 + zone % 100));
+  let named = map_opt(
+    take_while_m_n(1, 3, |c: <I as Input>::Item| {
+      c.as_char().is_ascii_alphabetic()
+    }),
+    |zone: I| {
+      const ZONES: [(&str, i16); 10] = [
+        ("UT", 0),
+        ("GMT", 0),
+        ("EST", -5),
+        ("EDT", -4),
+        ("CST", -6),
+        ("CDT", -5),
+        ("MST", -7),
+        ("MDT", -6),
+        ("PST", -8),
+        ("PDT", -7),
+      ];
+      if zone.input_len() == 1 {
+        let letter = zone.iter_elements().next()?.as_char();
+        return (!letter.eq_ignore_ascii_case(&'j')).then_some(0);
+      }
+      ZONES
+        .iter()
+        .find(|(name, _)| matches_name(&zone, name, true))
+        .map(|(_, hours)| hours * 60)
+    },
+  );
+
+  alt((numeric, named))
+}
+
+/// Recognizes the date of a HTTP header, like `Sun, 06 Nov 1994 08:49:37 GMT`
+///
+/// It accepts the 3 formats of RFC 9110 section 5.6.7: the preferred one, and the
+/// obsolete RFC 850 and asctime formats. The 2 digit years of the RFC 850 format below 50
+/// are in the 21st century, the other ones are in the 20th century. Names are case
+/// sensitive, and the day of the week must match the date.
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Needed, Parser};
+/// use nom::datetime::http_date;
+///
+/// let mut parser = http_date::<_, Error<_>>();
+/// let expected = parser.parse_complete("Sun, 06 Nov 1994 08:49:37 GMT").unwrap().1;
+/// assert_eq!(expected.unix_timestamp(), 784111777);
+/// assert_eq!(parser.parse_complete("Sunday, 06-Nov-94 08:49:37 GMT"), Ok(("", expected)));
+/// assert_eq!(parser.parse_complete("Sun Nov  6 08:49:37 1994"), Ok(("", expected)));
+/// assert!(parser.parse_complete("Sun, 06 Nov 1994 08:49:37 UTC").is_err());
+/// assert_eq!(
+///   http_date::<_, Error<_>>().parse(&b"Sun, 06 Nov 1994 08:49"[..]),
+///   Err(Err::Incomplete(Needed::new(1)))
+/// );
+/// ```
+pub fn http_date<I, E: ParseError<I>>() -> impl Parser<I, Output = DateTime, Error = E>
+where
+  I: Input + Compare<&'static str>,
+  <I as Input>::Item: AsChar,
+{
+  let time = || {
+    verify(
+      (
+        number(2, 2),
+        char(':'),
+        number(2, 2),
+        char(':'),
+        number(2, 2),
+      ),
+      |(hour, _, minute, _, second)| *hour <= 23 && *minute <= 59 && *second <= 60,
+    )
+    .map(|(hour, _, minute, _, second)| Time {
+      hour: hour as u8,
+      minute: minute as u8,
+      second: second as u8,
+      nanosecond: 0,
+    })
+  };
+  let date = |day: u32, month: u8, year: u32| Date {
+    year: year as u16,
+    month: month + 1,
+    day: day as u8,
+  };
+
+  let imf_fixdate = (
+    name(3, &WEEKDAYS, false),
+    tag(", "),
+    number(2, 2),
+    char(' '),
+    name(3, &MONTHS, false),
+    char(' '),
+    number(4, 4),
+    char(' '),
+    time(),
+    tag(" GMT"),
+  )
+    .map(move |(weekday, _, day, _, month, _, year, _, time, _)| {
+      (weekday, date(day, month, year), time)
+    });
+  let rfc850_date = (
+    name(9, &LONG_WEEKDAYS, false),
+    tag(", "),
+    number(2, 2),
+    char('-'),
+    name(3, &MONTHS, false),
+    char('-'),
+    number(2, 2),
+    char(' '),
+    time(),
+    tag(" GMT"),
+  )
+    .map(move |(weekday, _, day, _, month, _, year, _, time, _)| {
+      let year = if year < 50 { 2000 + year } else { 1900 + year };
+      (weekday, date(day, month, year), time)
+    });
+  let asctime_date = (
+    name(3, &WEEKDAYS, false),
+    char(' '),
+    name(3, &MONTHS, false),
+    char(' '),
+    alt((number(2, 2), preceded(char(' '), number(1, 1)))),
+    char(' '),
+    time(),
+    char(' '),
+    number(4, 4),
+  )
+    .map(move |(weekday, _, month, _, day, _, time, _, year)| {
+      (weekday, date(day, month, year), time)
+    });
+
+  verify(
+    alt((imf_fixdate, rfc850_date, asctime_date)),
+    |(weekday, date, _)| date.is_valid() && *weekday == date.weekday(),
+  )
+  .map(|(_, date, time)| DateTime {
+    date,
+    time,
+    offset: 0,
+  })
+}
+
+fn is_digit<I: Input>(c: <I as Input>::Item) -> bool
+where
+  <I as Input>::Item: AsChar,
+{
+  c.as_char().is_ascii_digit()
+}
+
+fn digits_value<I: Input>(digits: &I) -> u32
+where
+  <I as Input>::Item: AsChar,
+{
+  digits
+    .iter_elements()
+    .fold(0, |n, c| n * 10 + c.as_char().to_digit(10).unwrap_or(0))
+}
+
+/// Recognizes a decimal number written with `min` to `max` digits
+fn number<I, E: ParseError<I>>(min: usize, max: usize) -> impl Parser<I, Output = u32, Error = E>
+where
+  I: Input,
+  <I as Input>::Item: AsChar,
+{
+  take_while_m_n(min, max, is_digit::<I>).map(|digits: I| digits_value(&digits))
+}
+
+/// Recognizes the fraction of a second, and returns it in nanoseconds
+fn fraction<I, E: ParseError<I>>() -> impl Parser<I, Output = u32, Error = E>
+where
+  I: Input,
+  <I as Input>::Item: AsChar,
+{
+  take_while1(is_digit::<I>).map(|digits: I| {
+    let mut nanosecond = 0;
+    let mut count = 0;
+    for c in digits.iter_elements().take(9) {
+      nanosecond = nanosecond * 10 + c.as_char().to_digit(10).unwrap_or(0);
+      count += 1;
+    }
+    nanosecond * 10u32.pow(9 - count)
+  })
+}
+
+fn whitespace0<I, E: ParseError<I>>() -> impl Parser<I, Output = I, Error = E>
+where
+  I: Input,
+  <I as Input>::Item: AsChar,
+{
+  take_while(|c: <I as Input>::Item| matches!(c.as_char(), ' ' | '\t'))
+}
+
+fn whitespace1<I, E: ParseError<I>>() -> impl Parser<I, Output = I, Error = E>
+where
+  I: Input,
+  <I as Input>::Item: AsChar,
+{
+  take_while1(|c: <I as Input>::Item| matches!(c.as_char(), ' ' | '\t'))
+}
+
+/// Checks that `input` is exactly `name`
+fn matches_name<I>(input: &I, name: &'static str, no_case: bool) -> bool
+where
+  I: Input + Compare<&'static str>,
+{
+  let res = if no_case {
+    input.compare_no_case(name)
+  } else {
+    input.compare(name)
+  };
+  input.input_len() == name.len() && matches!(res, CompareResult::Ok)
+}
+
+/// Recognizes one of `names`, which have at most `max` letters, and returns its index
+fn name<I, E: ParseError<I>>(
+  max: usize,
+  names: &'static [&'static str],
+  no_case: bool,
+) -> impl Parser<I, Output = u8, Error = E>
+where
+  I: Input + Compare<&'static str>,
+  <I as Input>::Item: AsChar,
+{
+  map_opt(
+    take_while_m_n(1, max, |c: <I as Input>::Item| {
+      c.as_char().is_ascii_alphabetic()
+    }),
+    move |word: I| {
+      names
+        .iter()
+        .position(|name| matches_name(&word, name, no_case))
+        .map(|index| index as u8)
+    },
+  )
+}
+
+fn signed_offset(sign: char, minutes: u32) -> i16 {
+  if sign == '-' {
+    -(minutes as i16)
+  } else {
+    minutes as i16
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::error::{Error, ErrorKind};
+  use crate::{Err, Needed};
+
+  #[test]
+  fn dates() {
+    assert_eq!(
+      Date {
+        year: 1970,
+        month: 1,
+        day: 1
+      }
+      .days_since_epoch(),
+      0
+    );
+    assert_eq!(
+      Date {
+        year: 2000,
+        month: 3,
+        day: 1
+      }
+      .days_since_epoch(),
+      11_017
+    );
+    assert_eq!(
+      Date {
+        year: 1969,
+        month: 12,
+        day: 31
+      }
+      .weekday(),
+      2
+    );
+    assert!(Date {
+      year: 2000,
+      month: 2,
+      day: 29
+    }
+    .is_valid());
+    assert!(!Date {
+      year: 1900,
+      month: 2,
+      day: 29
+    }
+    .is_valid());
+    assert!(!Date {
+      year: 2001,
+      month: 13,
+      day: 1
+    }
+    .is_valid());
+  }
+
+  #[test]
+  fn rfc3339_timestamps() {
+    let mut parser = rfc3339::<_, Error<_>>();
+
+    let (rest, dt) = parser
+      .parse_complete("1937-01-01T12:00:27.87654321987+00:20;")
+      .unwrap();
+    assert_eq!(rest, ";");
+    assert_eq!(dt.time.nanosecond, 876_543_219);
+    assert_eq!(dt.offset, 20);
+    assert_eq!(
+      parser.parse_complete("1937-01-01T12:00:27.+00:20"),
+      Err(Err::Error(Error::new(".+00:20", ErrorKind::OneOf)))
+    );
+    assert_eq!(
+      parser.parse_complete("1937-01-01T24:00:00Z"),
+      Err(Err::Error(Error::new("24:00:00Z", ErrorKind::Verify)))
+    );
+    assert_eq!(
+      parser.parse_complete("1937-01-01T12:00:00+24:00"),
+      Err(Err::Error(Error::new("+24:00", ErrorKind::Verify)))
+    );
+
+    let mut streaming = rfc3339::<_, Error<_>>();
+    assert_eq!(
+      streaming.parse(&b"1937-01-01T12:00:00+01:0"[..]),
+      Err(Err::Incomplete(Needed::new(1)))
+    );
+    assert_eq!(
+      streaming
+        .parse(&b"1937-01-01T12:00:00Z"[..])
+        .map(|(_, dt)| dt.unix_timestamp()),
+      Ok(-1041336000)
+    );
+  }
+
+  #[test]
+  fn rfc2822_dates() {
+    let mut parser = rfc2822::<_, Error<_>>();
+
+    assert_eq!(
+      parser
+        .parse_complete("Thu,\t13 Feb 1969 23:32:54 -0330")
+        .map(|(_, dt)| (dt.offset, dt.unix_timestamp())),
+      Ok((-210, -27_723_426))
+    );
+    assert_eq!(
+      parser
+        .parse_complete("1 Jan 049 00:00 z")
+        .map(|(_, dt)| (dt.date.year, dt.offset)),
+      Ok((1949, 0))
+    );
+    assert_eq!(
+      parser
+        .parse_complete("1 Jan 49 00:00 GMT")
+        .map(|(_, dt)| dt.date.year),
+      Ok(2049)
+    );
+    assert!(parser.parse_complete("1 Jan 2000 00:00 J").is_err());
+    assert!(parser.parse_complete("1 Jan 2000 00:00 +0060").is_err());
+    assert!(parser.parse_complete("31 Apr 2000 00:00 +0000").is_err());
+  }
+
+  #[test]
+  fn http_dates() {
+    let mut parser = http_date::<_, Error<_>>();
+
+    assert!(parser
+      .parse_complete("Mon, 06 Nov 1994 08:49:37 GMT")
+      .is_err());
+    assert!(parser
+      .parse_complete("sun, 06 Nov 1994 08:49:37 GMT")
+      .is_err());
+    assert_eq!(
+      parser
+        .parse_complete("Thursday, 01-Jan-70 00:00:00 GMT")
+        .map(|(_, dt)| dt.unix_timestamp()),
+      Ok(0)
+    );
+    assert_eq!(
+      parser
+        .parse_complete("Sat Jan  1 00:00:00 2000\r\n")
+        .map(|(rest, dt)| (rest, dt.date)),
+      Ok((
+        "\r\n",
+        Date {
+          year: 2000,
+          month: 1,
+          day: 1
+        }
+      ))
+    );
+  }
+
+  #[test]
+  #[cfg(feature = "chrono")]
+  fn chrono_conversion() {
+    let (_, dt) = rfc3339::<_, Error<_>>()
+      .parse_complete("2016-12-31T23:59:60.5+01:00")
+      .unwrap();
+    let converted = dt.to_chrono().unwrap();
+    // chrono counts the leap second in the nanoseconds of the previous one
+    assert_eq!(converted.timestamp(), dt.unix_timestamp() - 1);
+    assert_eq!(converted.timestamp_subsec_nanos(), 1_500_000_000);
+    assert_eq!(converted.offset().local_minus_utc(), 3600);
+  }
+
+  #[test]
+  #[cfg(feature = "time")]
+  fn time_conversion() {
+    let (_, dt) = rfc3339::<_, Error<_>>()
+      .parse_complete("2016-12-31T23:59:59.5+01:00")
+      .unwrap();
+    let converted = dt.to_time().unwrap();
+    assert_eq!(converted.unix_timestamp(), dt.unix_timestamp());
+    assert_eq!(converted.nanosecond(), 500_000_000);
+    assert_eq!(converted.offset().whole_minutes(), 60);
+  }
+}
@@ -4,13 +4,20 @@
 //! the `error::ParseError<Input>` trait.
 
 use crate::internal::{Err, Mode, OutputMode, PResult, Parser};
+use crate::lib::std::cmp::Ordering;
 use crate::lib::std::fmt;
+use crate::traits::{AsChar, Input, Offset};
+use core::hash::{Hash, Hasher};
 
 #[cfg(feature = "alloc")]
 use crate::alloc::borrow::ToOwned;
 
 #[cfg(feature = "std")]
 use crate::internal::IResult;
+#[cfg(feature = "incomplete-context")]
+use crate::internal::Needed;
+#[cfg(any(feature = "std", feature = "alloc"))]
+use crate::traits::AsBytes;
 
 /// This trait must be implemented by the error type of a nom parser.
 ///
@@ -26,6 +33,9 @@ pub trait ParseError<I>: Sized {
   /// Combines an existing error with a new one created from the input
   /// position and an [ErrorKind]. This is useful when backtracking
   /// through a parse tree, accumulating error context on the way
+  ///
+  /// The combinators of nom call it from the innermost parser to the outermost one, so
+  /// an error accumulating these calls always records them in the same order.
   fn append(input: I, kind: ErrorKind, other: Self) -> Self;
 
   /// Creates an error from an input position and an expected character
@@ -35,6 +45,10 @@ pub trait ParseError<I>: Sized {
 
   /// Combines two existing errors. This function is used to compare errors
   /// generated in various branches of `alt`.
+  ///
+  /// The branches are combined in their order: `self` comes from the earlier
+  /// branches, and `other` from the next one. The error of the whole `alt` then
+  /// only depends on the errors of the branches, not on how they were reached.
   fn or(self, other: Self) -> Self {
     other
   }
@@ -49,6 +63,14 @@ pub trait ContextError<I>: Sized {
   fn add_context(_input: I, _ctx: &'static str, other: Self) -> Self {
     other
   }
+
+  /// Creates a new error from an input position, a context formatted on demand and an
+  /// existing error. This is used by combinators with contexts computed while parsing,
+  /// like the index of an element in [many_labeled](crate::multi::many_labeled): the
+  /// context is only formatted by the errors keeping it, and only when a parser fails
+  fn add_lazy_context(_input: I, _ctx: &dyn fmt::Display, other: Self) -> Self {
+    other
+  }
 }
 
 /// This trait is required by the `map_res` combinator to integrate
@@ -60,7 +82,8 @@ pub trait FromExternalError<I, E> {
 }
 
 /// default error type, only contains the error's location and code
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Error<I> {
   /// position of the error in the input data
   pub input: I,
@@ -73,6 +96,28 @@ impl<I> Error<I> {
   pub fn new(input: I, code: ErrorKind) -> Error<I> {
     Error { input, code }
   }
+
+  /// Replaces the input position with its offset in `original`, the whole input given to
+  /// the parser, so the error does not borrow the input anymore.
+  ///
+  /// ```rust
+  /// # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+  /// use nom::character::complete::{alpha1, digit1};
+  ///
+  /// let input = "abc;";
+  /// let res: IResult<_, _> = (alpha1, digit1).parse(input);
+  /// let e = res.map_err(|e| e.map(|e| e.offset_in(&input)));
+  /// assert_eq!(e, Err(Err::Error(Error::new(3, ErrorKind::Digit))));
+  /// ```
+  pub fn offset_in(self, original: &I) -> Error<usize>
+  where
+    I: Offset,
+  {
+    Error {
+      input: original.offset(&self.input),
+      code: self.code,
+    }
+  }
 }
 
 impl<I> ParseError<I> for Error<I> {
@@ -146,6 +191,217 @@ impl<I: Copy> Error<&mut I> {
 #[cfg(feature = "std")]
 impl<I: fmt::Debug + fmt::Display> std::error::Error for Error<I> {}
 
+/// Error types whose input positions can be converted to another type
+///
+/// Errors usually borrow the input, which prevents returning them from the function
+/// that owns it. [MapInput::into_owned] copies the input positions, so the error can be
+/// propagated with `?`. [MapInput::map_input] can convert them to offsets or line
+/// numbers instead. [Err::map_error_input] and [Err::into_owned] apply them to the error
+/// wrapped in an [Err].
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+/// use nom::character::complete::{alpha1, digit1};
+///
+/// fn parse_config(path: &str) -> Result<(), Box<dyn std::error::Error>> {
+///   // the input is dropped at the end of the function
+///   let content = String::from("abc;");
+///   let (_, _) = (alpha1::<_, Error<_>>, digit1)
+///     .parse(content.as_str())
+///     .map_err(|e| e.into_owned())?;
+///   Ok(())
+/// }
+///
+/// assert_eq!(parse_config("config").unwrap_err().to_string(), r#"Parsing Error: Error { input: ";", code: Digit }"#);
+/// ```
+pub trait MapInput<I>: Sized {
+  /// The error type with input positions of type `U`
+  type Mapped<U>;
+
+  /// Converts every input position of the error with `f`
+  fn map_input<U, F: FnMut(I) -> U>(self, f: F) -> Self::Mapped<U>;
+
+  /// Converts the borrowed input positions to their owned version, like `String` for
+  /// `&str` or `Vec<u8>` for `&[u8]`
+  #[cfg(feature = "alloc")]
+  #[cfg_attr(feature = "docsrs", doc(cfg(feature = "alloc")))]
+  fn into_owned(self) -> Self::Mapped<<I::Target as ToOwned>::Owned>
+  where
+    I: crate::lib::std::ops::Deref,
+    I::Target: ToOwned,
+  {
+    self.map_input(|i| i.to_owned())
+  }
+}
+
+impl<I> MapInput<I> for Error<I> {
+  type Mapped<U> = Error<U>;
+
+  fn map_input<U, F: FnMut(I) -> U>(self, mut f: F) -> Error<U> {
+    Error {
+      input: f(self.input),
+      code: self.code,
+    }
+  }
+}
+
+impl<I> MapInput<I> for (I, ErrorKind) {
+  type Mapped<U> = (U, ErrorKind);
+
+  fn map_input<U, F: FnMut(I) -> U>(self, mut f: F) -> (U, ErrorKind) {
+    (f(self.0), self.1)
+  }
+}
+
+impl<I> MapInput<I> for () {
+  type Mapped<U> = ();
+
+  fn map_input<U, F: FnMut(I) -> U>(self, _f: F) {}
+}
+
+impl<I, E: MapInput<I>> MapInput<I> for FurthestError<I, E> {
+  type Mapped<U> = FurthestError<U, E::Mapped<U>>;
+
+  fn map_input<U, F: FnMut(I) -> U>(self, mut f: F) -> Self::Mapped<U> {
+    FurthestError {
+      input: f(self.input),
+      error: self.error.map_input(f),
+    }
+  }
+}
+
+/// Correspondence between the positions of a transformed input, like an unescaped or
+/// decompressed buffer, and the positions of the input it was built from
+///
+/// The map is a list of segments, each one starting at an offset in the transformed input
+/// and ending at the start of the next one. The bytes of a segment are either copied from
+/// the original input, each one mapping to its own position, or replace a part of it, like
+/// an escape sequence or a compressed block, and all map to the start of that part.
+/// Positions before the first segment map to themselves.
+///
+/// [SourceMap::map_error] uses it to convert the input positions of the errors of a parser
+/// applied to the transformed input back to the original input, with [MapInput], so that
+/// nested formats get their diagnostics at the right place.
+///
+/// ```rust
+/// use nom::error::SourceMap;
+///
+/// // the escape sequence in "a\tb" is replaced with a tab, shifting "b" back by one byte
+/// let mut map = SourceMap::new();
+/// map.copied(0, 0);
+/// map.replaced(1, 1);
+/// map.copied(2, 3);
+/// assert_eq!(map.original(0), 0);
+/// assert_eq!(map.original(1), 1);
+/// assert_eq!(map.original(2), 3);
+/// assert_eq!(map.original(3), 4);
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "alloc")))]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SourceMap {
+  /// Segments sorted by offset, with the original position of their start, and whether
+  /// their bytes are copied
+  segments: crate::lib::std::vec::Vec<(usize, usize, bool)>,
+}
+
+#[cfg(feature = "alloc")]
+impl SourceMap {
+  /// Creates an empty map, where every position maps to itself
+  pub fn new() -> Self {
+    SourceMap::default()
+  }
+
+  /// Starts a segment at `offset`, whose bytes are copied from the original input
+  /// starting at `original`
+  pub fn copied(&mut self, offset: usize, original: usize) {
+    self.push(offset, original, true)
+  }
+
+  /// Starts a segment at `offset`, whose bytes replace the part of the original input
+  /// starting at `original`
+  pub fn replaced(&mut self, offset: usize, original: usize) {
+    self.push(offset, original, false)
+  }
+
+  fn push(&mut self, offset: usize, original: usize, copied: bool) {
+    // segments must be added in order, and a later one at the same offset replaces the
+    // previous one
+    let index = self.segments.partition_point(|s| s.0 < offset);
+    self.segments.truncate(index);
+    self.segments.push((offset, original, copied));
+  }
+
+  /// Returns the position in the original input of the `offset` of the transformed input
+  pub fn original(&self, offset: usize) -> usize {
+    let index = self.segments.partition_point(|s| s.0 <= offset);
+    match index.checked_sub(1).map(|i| self.segments[i]) {
+      None => offset,
+      Some((start, original, true)) => original + (offset - start),
+      Some((_, original, false)) => original,
+    }
+  }
+
+  /// Converts the input positions of an error returned by a parser applied to `buffer`,
+  /// the transformed version of `input`, to positions in `input`
+  ///
+  /// ```rust
+  /// # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+  /// use nom::bytes::take_till;
+  /// use nom::character::complete::{alpha1, char};
+  /// use nom::combinator::eof;
+  /// use nom::error::SourceMap;
+  /// use nom::multi::separated_list1;
+  /// use nom::sequence::{delimited, terminated};
+  ///
+  /// // a quoted string where the "\\n" escape sequence stands for a line ending
+  /// fn unescaped(input: &str) -> IResult<&str, (String, SourceMap)> {
+  ///   let (rest, content) = delimited(char('"'), take_till(|c| c == '"'), char('"')).parse(input)?;
+  ///   let mut text = String::new();
+  ///   let mut map = SourceMap::new();
+  ///   // the content starts after the opening quote
+  ///   let mut start = 1;
+  ///   for (index, part) in content.split("\\n").enumerate() {
+  ///     if index > 0 {
+  ///       map.replaced(text.len(), start - 2);
+  ///       text.push('\n');
+  ///     }
+  ///     map.copied(text.len(), start);
+  ///     text.push_str(part);
+  ///     start += part.len() + 2;
+  ///   }
+  ///   Ok((rest, (text, map)))
+  /// }
+  ///
+  /// fn lines(text: &str) -> IResult<&str, Vec<String>> {
+  ///   let line = alpha1.map(|l: &str| l.to_string());
+  ///   terminated(separated_list1(char('\n'), line), eof).parse(text)
+  /// }
+  ///
+  /// // a list of words, one per line, in a quoted string
+  /// fn words(input: &str) -> IResult<&str, Vec<String>> {
+  ///   let (rest, (text, map)) = unescaped(input)?;
+  ///   let (_, words) = lines(&text).map_err(|e| map.map_error(&input, text.as_str(), e))?;
+  ///   Ok((rest, words))
+  /// }
+  ///
+  /// assert_eq!(words(r#""ab\ncd";"#), Ok((";", vec!["ab".to_string(), "cd".to_string()])));
+  /// assert_eq!(
+  ///   words(r#""ab\ncd\n12";"#),
+  ///   Err(Err::Error(Error::new(r#"\n12";"#, ErrorKind::Eof)))
+  /// );
+  /// ```
+  pub fn map_error<'b, I, B, E>(&self, input: &I, buffer: &'b B, error: Err<E>) -> Err<E::Mapped<I>>
+  where
+    I: Input,
+    B: ?Sized,
+    &'b B: Offset,
+    E: MapInput<&'b B>,
+  {
+    error.map_error_input(|i| input.take_from(self.original(Offset::offset(&buffer, &i))))
+  }
+}
+
 #[cfg(feature = "alloc")]
 #[cfg_attr(feature = "docsrs", doc(cfg(feature = "alloc")))]
 impl From<Error<&[u8]>> for Error<crate::lib::std::vec::Vec<u8>> {
@@ -200,6 +456,365 @@ impl<I, E> FromExternalError<I, E> for () {
   fn from_external_error(_input: I, _kind: ErrorKind, _e: E) -> Self {}
 }
 
+/// Compact error type, holding the position of the error in 4 bytes and its kind in 1
+///
+/// [Error] stores the input position, which is two words for slices and strings, and
+/// moving it through every combinator can show up in hot loops, or take too much room
+/// on embedded targets. `TinyError` is 8 bytes and `Copy`: it records the length of the
+/// input remaining at the error, which [TinyError::offset_in] converts to an offset
+/// from the start of the whole input. Lengths over `u32::MAX` are saturated.
+///
+/// It implements [ParseError], [ContextError] and [FromExternalError], so it can be
+/// used with every combinator, but it drops the contexts and the external errors.
+///
+/// ```rust
+/// # use nom::{Err, error::ErrorKind, IResult, Parser};
+/// use nom::character::complete::{alpha1, digit1};
+/// use nom::error::TinyError;
+///
+/// let input = "abc;";
+/// let res: IResult<_, _, TinyError> = (alpha1, digit1).parse(input);
+/// let Err(Err::Error(e)) = res else { unreachable!() };
+/// assert_eq!(e.kind, ErrorKind::Digit);
+/// assert_eq!(e.offset_in(&input), 3);
+/// assert_eq!(e.input_in(input), ";");
+/// assert_eq!(std::mem::size_of::<TinyError>(), 8);
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TinyError {
+  /// Length of the input remaining at the error
+  pub remaining: u32,
+  /// nom error code
+  pub kind: ErrorKind,
+}
+
+impl TinyError {
+  /// Creates an error for the input `input`
+  pub fn new<I: Input>(input: &I, kind: ErrorKind) -> Self {
+    TinyError {
+      remaining: u32::try_from(input.input_len()).unwrap_or(u32::MAX),
+      kind,
+    }
+  }
+
+  /// Returns the offset of the error in `original`, the whole input given to the parser
+  pub fn offset_in<I: Input>(&self, original: &I) -> usize {
+    original.input_len().saturating_sub(self.remaining as usize)
+  }
+
+  /// Returns the input position of the error in `original`, the whole input given to
+  /// the parser
+  pub fn input_in<I: Input>(&self, original: I) -> I {
+    let offset = self.offset_in(&original);
+    original.take_from(offset)
+  }
+}
+
+impl<I: Input> ParseError<I> for TinyError {
+  fn from_error_kind(input: I, kind: ErrorKind) -> Self {
+    TinyError::new(&input, kind)
+  }
+
+  fn append(_: I, _: ErrorKind, other: Self) -> Self {
+    other
+  }
+}
+
+impl<I: Input> ContextError<I> for TinyError {}
+
+impl<I: Input, E> FromExternalError<I, E> for TinyError {
+  fn from_external_error(input: I, kind: ErrorKind, _e: E) -> Self {
+    TinyError::new(&input, kind)
+  }
+}
+
+impl fmt::Display for TinyError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "error {:?} with {} input elements remaining",
+      self.kind, self.remaining
+    )
+  }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TinyError {}
+
+/// Error keeping the last `N` errors and contexts added while backtracking, without
+/// allocating
+///
+/// Without the `alloc` feature, the error types of nom record a single position. This one
+/// stores up to `N` frames inline, each one with its input position and a [StackFrame]: the
+/// error kinds added by [ParseError], and the contexts added by [ContextError]. When it is
+/// full, the oldest frame, the innermost one, is dropped to make room, and counted in
+/// [StackError::dropped]. The contexts added with [ContextError::add_lazy_context] are not
+/// kept, since formatting them would allocate.
+///
+/// ```rust
+/// # use nom::{Err, error::{context, ErrorKind}, IResult, Parser};
+/// use nom::character::complete::{char, digit1};
+/// use nom::error::{StackError, StackFrame};
+/// use nom::sequence::delimited;
+///
+/// fn list(i: &str) -> IResult<&str, &str, StackError<&str, 2>> {
+///   context("list", delimited(char('['), context("item", digit1), char(']'))).parse(i)
+/// }
+///
+/// let Err(Err::Error(e)) = list("[a]") else { unreachable!() };
+/// let frames: Vec<_> = e.frames().collect();
+/// assert_eq!(
+///   frames,
+///   [
+///     &("a]", StackFrame::Context("item")),
+///     &("[a]", StackFrame::Context("list"))
+///   ]
+/// );
+/// // the frame of digit1 was dropped
+/// assert_eq!(e.dropped(), 1);
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct StackError<I, const N: usize> {
+  /// Circular buffer of the frames
+  frames: [Option<(I, StackFrame)>; N],
+  /// Index of the oldest frame
+  start: usize,
+  /// Number of frames
+  len: usize,
+  /// Number of frames dropped
+  dropped: usize,
+}
+
+/// Frame of a [StackError]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum StackFrame {
+  /// Error kind given by a nom parser
+  Kind(ErrorKind),
+  /// Character expected by the `char` parser
+  Char(char),
+  /// Static string added by the [context] combinator
+  Context(&'static str),
+}
+
+impl<I, const N: usize> StackError<I, N> {
+  /// Creates an error with a single frame
+  pub fn new(input: I, frame: StackFrame) -> Self {
+    let mut error = StackError {
+      frames: [(); N].map(|_| None),
+      start: 0,
+      len: 0,
+      dropped: 0,
+    };
+    error.push(input, frame);
+    error
+  }
+
+  /// Adds a frame, dropping the oldest one if the error is full
+  pub fn push(&mut self, input: I, frame: StackFrame) {
+    if N == 0 {
+      self.dropped += 1;
+    } else if self.len < N {
+      self.frames[(self.start + self.len) % N] = Some((input, frame));
+      self.len += 1;
+    } else {
+      self.frames[self.start] = Some((input, frame));
+      self.start = (self.start + 1) % N;
+      self.dropped += 1;
+    }
+  }
+
+  /// Returns the frames, from the oldest, innermost one, to the newest
+  pub fn frames(&self) -> impl Iterator<Item = &(I, StackFrame)> {
+    (0..self.len).filter_map(move |i| self.frames[(self.start + i) % N].as_ref())
+  }
+
+  /// Returns the number of frames dropped because the error was full
+  pub fn dropped(&self) -> usize {
+    self.dropped
+  }
+}
+
+impl<I, const N: usize> ParseError<I> for StackError<I, N> {
+  fn from_error_kind(input: I, kind: ErrorKind) -> Self {
+    StackError::new(input, StackFrame::Kind(kind))
+  }
+
+  fn append(input: I, kind: ErrorKind, mut other: Self) -> Self {
+    other.push(input, StackFrame::Kind(kind));
+    other
+  }
+
+  fn from_char(input: I, c: char) -> Self {
+    StackError::new(input, StackFrame::Char(c))
+  }
+}
+
+impl<I, const N: usize> ContextError<I> for StackError<I, N> {
+  fn add_context(input: I, ctx: &'static str, mut other: Self) -> Self {
+    other.push(input, StackFrame::Context(ctx));
+    other
+  }
+}
+
+impl<I, E, const N: usize> FromExternalError<I, E> for StackError<I, N> {
+  fn from_external_error(input: I, kind: ErrorKind, _e: E) -> Self {
+    StackError::new(input, StackFrame::Kind(kind))
+  }
+}
+
+impl<I, const N: usize> MapInput<I> for StackError<I, N> {
+  type Mapped<U> = StackError<U, N>;
+
+  fn map_input<U, F: FnMut(I) -> U>(self, mut f: F) -> StackError<U, N> {
+    StackError {
+      frames: self
+        .frames
+        .map(|frame| frame.map(|(i, frame)| (f(i), frame))),
+      start: self.start,
+      len: self.len,
+      dropped: self.dropped,
+    }
+  }
+}
+
+impl<I: fmt::Display, const N: usize> fmt::Display for StackError<I, N> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    writeln!(f, "Parse error:")?;
+    if self.dropped > 0 {
+      writeln!(f, "({} frames dropped)", self.dropped)?;
+    }
+    for (input, frame) in self.frames() {
+      match frame {
+        StackFrame::Kind(kind) => writeln!(f, "{:?} at: {}", kind, input)?,
+        StackFrame::Char(c) => writeln!(f, "expected '{}' at: {}", c, input)?,
+        StackFrame::Context(s) => writeln!(f, "in section '{}', at: {}", s, input)?,
+      }
+    }
+    Ok(())
+  }
+}
+
+#[cfg(feature = "std")]
+impl<I: fmt::Debug + fmt::Display, const N: usize> std::error::Error for StackError<I, N> {}
+
+/// Error wrapper keeping the error that went the furthest in the input
+///
+/// By default, [ParseError::or] keeps the error from the last branch of `alt`,
+/// which usually points at the wrong location when several branches share a
+/// common prefix. This wrapper records the position of the wrapped error and,
+/// when combining errors from `alt` branches, keeps the one with the least
+/// remaining input, i.e. the branch that consumed the most before failing.
+/// If both errors are at the same position, the wrapped type's `or` decides.
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind, FurthestError}, IResult, Parser};
+/// use nom::branch::alt;
+/// use nom::bytes::complete::tag;
+/// use nom::character::complete::digit1;
+/// use nom::sequence::preceded;
+///
+/// fn value(i: &str) -> IResult<&str, &str, FurthestError<&str, Error<&str>>> {
+///   alt((
+///     preceded(tag("int:"), digit1),
+///     preceded(tag("hex:0x"), digit1),
+///     tag("null"),
+///   )).parse(i)
+/// }
+///
+/// // the first branch went further than the other ones
+/// assert_eq!(
+///   value("int:abc").map_err(|e| e.map(FurthestError::into_inner)),
+///   Err(Err::Error(Error::new("abc", ErrorKind::Digit)))
+/// );
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FurthestError<I, E> {
+  /// position of the wrapped error in the input data
+  pub input: I,
+  /// wrapped error
+  pub error: E,
+}
+
+impl<I, E> FurthestError<I, E> {
+  /// Returns the wrapped error
+  pub fn into_inner(self) -> E {
+    self.error
+  }
+}
+
+impl<I: Input, E: ParseError<I>> ParseError<I> for FurthestError<I, E> {
+  fn from_error_kind(input: I, kind: ErrorKind) -> Self {
+    FurthestError {
+      input: input.clone(),
+      error: E::from_error_kind(input, kind),
+    }
+  }
+
+  fn append(input: I, kind: ErrorKind, other: Self) -> Self {
+    FurthestError {
+      input: other.input,
+      error: E::append(input, kind, other.error),
+    }
+  }
+
+  fn from_char(input: I, c: char) -> Self {
+    FurthestError {
+      input: input.clone(),
+      error: E::from_char(input, c),
+    }
+  }
+
+  fn or(self, other: Self) -> Self {
+    match self.input.input_len().cmp(&other.input.input_len()) {
+      Ordering::Less => self,
+      Ordering::Greater => other,
+      Ordering::Equal => FurthestError {
+        input: other.input,
+        error: self.error.or(other.error),
+      },
+    }
+  }
+}
+
+impl<I: Input, E: ContextError<I>> ContextError<I> for FurthestError<I, E> {
+  fn add_context(input: I, ctx: &'static str, other: Self) -> Self {
+    FurthestError {
+      input: other.input,
+      error: E::add_context(input, ctx, other.error),
+    }
+  }
+
+  fn add_lazy_context(input: I, ctx: &dyn fmt::Display, other: Self) -> Self {
+    FurthestError {
+      input: other.input,
+      error: E::add_lazy_context(input, ctx, other.error),
+    }
+  }
+}
+
+impl<I: Input, E, EXT> FromExternalError<I, EXT> for FurthestError<I, E>
+where
+  E: FromExternalError<I, EXT>,
+{
+  fn from_external_error(input: I, kind: ErrorKind, e: EXT) -> Self {
+    FurthestError {
+      input: input.clone(),
+      error: E::from_external_error(input, kind, e),
+    }
+  }
+}
+
+impl<I, E: fmt::Display> fmt::Display for FurthestError<I, E> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    self.error.fmt(f)
+  }
+}
+
+#[cfg(feature = "std")]
+impl<I: fmt::Debug, E: std::error::Error> std::error::Error for FurthestError<I, E> {}
+
 /// Creates an error from the input position and an [ErrorKind]
 pub fn make_error<I, E: ParseError<I>>(input: I, kind: ErrorKind) -> E {
   E::from_error_kind(input, kind)
@@ -212,6 +827,281 @@ pub fn append_error<I, E: ParseError<I>>(input: I, kind: ErrorKind, other: E) ->
   E::append(input, kind, other)
 }
 
+/// Converts a byte offset in `original` to a line and a column, both starting at 1.
+///
+/// Lines are separated by `\n`, and the column counts the characters before `offset`
+/// in its line. An offset past the end of `original` is clamped to its end, and an
+/// offset inside a character is rounded down to the start of that character.
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult, Offset, Parser};
+/// use nom::error::line_col;
+/// use nom::character::complete::{alpha1, multispace0};
+/// use nom::sequence::preceded;
+///
+/// let input = "abc\n  def 12";
+/// let res: IResult<_, _> = preceded((alpha1, multispace0, alpha1, multispace0), alpha1).parse(input);
+/// let Err(Err::Error(e)) = res else { panic!() };
+/// assert_eq!(line_col(input, input.offset(&e.input)), (2, 7));
+/// ```
+pub fn line_col(original: &str, offset: usize) -> (usize, usize) {
+  let mut offset = offset.min(original.len());
+  while !original.is_char_boundary(offset) {
+    offset -= 1;
+  }
+
+  let before = &original[..offset];
+  let line = before.matches('\n').count() + 1;
+  let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+  let column = before[line_start..].chars().count() + 1;
+  (line, column)
+}
+
+/// Returns a hash of `error`, with its input positions replaced by their offsets in `original`,
+/// to deduplicate the errors found by a fuzzer or compare them in snapshot tests
+///
+/// The hash covers everything the error records: for the error types of nom, the offsets,
+/// the error kinds and the contexts, so the same error at the same offset of another copy of
+/// the input has the same fingerprint. It is computed with FNV-1a, with integers written as
+/// little endian, so unlike the `DefaultHasher` of the standard library, it does not depend
+/// on the process or the platform and can be stored next to a corpus.
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+/// use nom::character::complete::{alpha1, digit1};
+/// use nom::error::fingerprint;
+///
+/// fn parser(i: &str) -> IResult<&str, (&str, &str)> {
+///   (alpha1, digit1).parse(i)
+/// }
+///
+/// let first = String::from("abc;");
+/// let second = first.clone();
+/// let Err(Err::Error(e1)) = parser(&first) else { unreachable!() };
+/// let Err(Err::Error(e2)) = parser(&second) else { unreachable!() };
+/// assert_eq!(fingerprint(&first.as_str(), e1), fingerprint(&second.as_str(), e2));
+///
+/// let Err(Err::Error(e3)) = parser(&second[1..]) else { unreachable!() };
+/// assert_ne!(fingerprint(&second.as_str(), e3.clone()), fingerprint(&&second[1..], e3));
+/// ```
+pub fn fingerprint<I, E>(original: &I, error: E) -> u64
+where
+  I: Offset,
+  E: MapInput<I>,
+  E::Mapped<usize>: Hash,
+{
+  let mut hasher = Fnv(0xcbf2_9ce4_8422_2325);
+  error.map_input(|i| original.offset(&i)).hash(&mut hasher);
+  hasher.finish()
+}
+
+/// FNV-1a hasher writing integers as little endian, for [fingerprint]
+struct Fnv(u64);
+
+impl Hasher for Fnv {
+  fn finish(&self) -> u64 {
+    self.0
+  }
+
+  fn write(&mut self, bytes: &[u8]) {
+    for b in bytes {
+      self.0 = (self.0 ^ u64::from(*b)).wrapping_mul(0x0100_0000_01b3);
+    }
+  }
+
+  fn write_u16(&mut self, i: u16) {
+    self.write(&i.to_le_bytes())
+  }
+
+  fn write_u32(&mut self, i: u32) {
+    self.write(&i.to_le_bytes())
+  }
+
+  fn write_u64(&mut self, i: u64) {
+    self.write(&i.to_le_bytes())
+  }
+
+  fn write_u128(&mut self, i: u128) {
+    self.write(&i.to_le_bytes())
+  }
+
+  fn write_usize(&mut self, i: usize) {
+    self.write_u64(i as u64)
+  }
+}
+
+/// Error of a parser bundled with the whole input, returned by
+/// [Finish::into_report](crate::Finish::into_report)
+///
+/// For `&str` inputs and errors implementing [MapInput], its `Display` implementation
+/// shows the line and column of the error, followed by the line and a caret under
+/// the error position.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Report<I, E> {
+  /// Whole input given to the parser
+  pub input: I,
+  /// Error returned by the parser
+  pub error: E,
+}
+
+impl<I: Clone + Offset, E: MapInput<I> + Clone> Report<I, E> {
+  /// Returns the offset in the input of the first position recorded in the error,
+  /// which is the innermost error for the error types of nom
+  pub fn offset(&self) -> Option<usize> {
+    let mut offset = None;
+    self.error.clone().map_input(|i| {
+      offset.get_or_insert_with(|| self.input.offset(&i));
+    });
+    offset
+  }
+}
+
+impl<'a, E: MapInput<&'a str> + Clone> Report<&'a str, E> {
+  /// Returns the line and column of the error, see [line_col]
+  pub fn line_col(&self) -> Option<(usize, usize)> {
+    self.offset().map(|offset| line_col(self.input, offset))
+  }
+}
+
+impl<'a, E: MapInput<&'a str> + Clone + fmt::Display> fmt::Display for Report<&'a str, E> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let offset = match self.offset() {
+      None => return write!(f, "{}", self.error),
+      Some(offset) => offset,
+    };
+    let (line, column) = line_col(self.input, offset);
+    let line_start = self.input[..offset].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let line_end = self.input[line_start..]
+      .find('\n')
+      .map(|i| line_start + i)
+      .unwrap_or(self.input.len());
+    write_caret(
+      f,
+      &self.error,
+      (line, column),
+      &self.input[line_start..line_end],
+    )
+  }
+}
+
+/// Writes the position and the message of an error, then its line with a caret under the error
+fn write_caret(
+  f: &mut fmt::Formatter<'_>,
+  error: &dyn fmt::Display,
+  (line, column): (usize, usize),
+  text: &str,
+) -> fmt::Result {
+  writeln!(f, "{}:{}: {}", line, column, error)?;
+  write!(f, "{}\n{:width$}^", text, "", width = column - 1)
+}
+
+/// Result of [Parser::parse_all]: the output of a parser that consumed the whole input,
+/// or a [SourceError]
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "alloc")))]
+pub type ParseReport<O, E> = Result<O, SourceError<E>>;
+
+/// Error returned by [Parser::parse_all], which owns everything needed to show it
+///
+/// The input positions of the error are replaced by their offsets in the input, and the
+/// line of the input where it happened is copied, so it can outlive the input. Like
+/// [Report], its `Display` implementation shows the line and column of the error, followed
+/// by the line and a caret under the error position. Byte inputs are shown as lossy UTF-8.
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "alloc")))]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SourceError<E> {
+  error: E,
+  location: Option<(usize, usize, usize)>,
+  line: crate::lib::std::string::String,
+}
+
+#[cfg(feature = "alloc")]
+impl<E> SourceError<E> {
+  /// Replaces the positions of `error` with their offsets in `original`, and copies the line
+  /// of its first position
+  pub(crate) fn new<I>(original: &I, error: impl MapInput<I, Mapped<usize> = E>) -> Self
+  where
+    I: AsBytes + Offset,
+  {
+    let mut offset = None;
+    let error = error.map_input(|i| {
+      let o = original.offset(&i);
+      offset.get_or_insert(o);
+      o
+    });
+
+    let bytes = original.as_bytes();
+    let Some(offset) = offset.map(|o| o.min(bytes.len())) else {
+      return SourceError {
+        error,
+        location: None,
+        line: Default::default(),
+      };
+    };
+    let line_start = bytes[..offset]
+      .iter()
+      .rposition(|b| *b == b'\n')
+      .map(|i| i + 1)
+      .unwrap_or(0);
+    let line_end = bytes[line_start..]
+      .iter()
+      .position(|b| *b == b'\n')
+      .map(|i| line_start + i)
+      .unwrap_or(bytes.len());
+    let line = bytes[..line_start].iter().filter(|b| **b == b'\n').count() + 1;
+    let column = crate::lib::std::string::String::from_utf8_lossy(&bytes[line_start..offset])
+      .chars()
+      .count()
+      + 1;
+
+    SourceError {
+      error,
+      location: Some((offset, line, column)),
+      line: crate::lib::std::string::String::from_utf8_lossy(&bytes[line_start..line_end])
+        .into_owned(),
+    }
+  }
+
+  /// Returns the error of the parser, with offsets as input positions
+  pub fn error(&self) -> &E {
+    &self.error
+  }
+
+  /// Returns the error of the parser, with offsets as input positions
+  pub fn into_error(self) -> E {
+    self.error
+  }
+
+  /// Returns the offset in the input of the first position recorded in the error
+  pub fn offset(&self) -> Option<usize> {
+    self.location.map(|(offset, _, _)| offset)
+  }
+
+  /// Returns the line and column of the error, see [line_col]
+  pub fn line_col(&self) -> Option<(usize, usize)> {
+    self.location.map(|(_, line, column)| (line, column))
+  }
+
+  /// Returns the line of the input where the error happened, without its line ending
+  pub fn line(&self) -> Option<&str> {
+    self.location.map(|_| self.line.as_str())
+  }
+}
+
+#[cfg(feature = "alloc")]
+impl<E: fmt::Display> fmt::Display for SourceError<E> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self.location {
+      None => write!(f, "{}", self.error),
+      Some((_, line, column)) => write_caret(f, &self.error, (line, column), &self.line),
+    }
+  }
+}
+
+#[cfg(feature = "std")]
+impl<E: fmt::Debug + fmt::Display> std::error::Error for SourceError<E> {}
+
 /// Create a new error from an input position, a static string and an existing error.
 /// This is used mainly in the [context] combinator, to add user friendly information
 /// to errors when backtracking through a parse tree
@@ -219,39 +1109,160 @@ pub fn context<F>(context: &'static str, parser: F) -> Context<F> {
   Context { context, parser }
 }
 
-/// Parser implementation for [context]
-pub struct Context<F> {
-  context: &'static str,
-  parser: F,
+/// Parser implementation for [context]
+pub struct Context<F> {
+  context: &'static str,
+  parser: F,
+}
+
+impl<F> Context<F> {
+  fn apply<I, OM>(&mut self, input: I) -> PResult<OM, I, F::Output, F::Error>
+  where
+    I: Clone,
+    F: Parser<I>,
+    <F as Parser<I>>::Error: ContextError<I>,
+    OM: OutputMode,
+  {
+    match self.parser.process::<OM>(input.clone()) {
+      Err(Err::Error(e)) => Err(Err::Error(OM::Error::map(e, |e| {
+        <F as Parser<I>>::Error::add_context(input, self.context, e)
+      }))),
+      Err(Err::Failure(e)) => Err(Err::Failure(<F as Parser<I>>::Error::add_context(
+        input,
+        self.context,
+        e,
+      ))),
+      x => x,
+    }
+  }
+}
+
+/// With the `incomplete-context` feature, the innermost [context] returning `Incomplete`
+/// is recorded, and can be retrieved with [missing]
+impl<I, F> Parser<I> for Context<F>
+where
+  I: Clone,
+  F: Parser<I>,
+  <F as Parser<I>>::Error: ContextError<I>,
+{
+  type Output = <F as Parser<I>>::Output;
+  type Error = <F as Parser<I>>::Error;
+
+  #[cfg(not(feature = "incomplete-context"))]
+  fn process<OM: OutputMode>(&mut self, input: I) -> PResult<OM, I, Self::Output, Self::Error> {
+    self.apply::<I, OM>(input)
+  }
+
+  #[cfg(feature = "incomplete-context")]
+  fn process<OM: OutputMode>(&mut self, input: I) -> PResult<OM, I, Self::Output, Self::Error> {
+    incomplete::clear();
+    let res = self.apply::<I, OM>(input);
+    match &res {
+      Err(Err::Incomplete(needed)) => incomplete::record(Missing {
+        context: self.context,
+        needed: *needed,
+      }),
+      _ => incomplete::clear(),
+    }
+    res
+  }
+}
+
+/// Innermost [context] of an `Incomplete` result, with the `incomplete-context` feature
+///
+/// `Needed` only tells how much data is missing, this tells what it is missing for: in
+/// streaming mode, the caller can log it before waiting for more data.
+///
+/// ```rust
+/// # use nom::{Err, IResult, Needed, Parser};
+/// use nom::bytes::streaming::take;
+/// use nom::error::{context, missing, Error, Missing};
+/// use nom::number::streaming::be_u32;
+///
+/// fn frame(i: &[u8]) -> IResult<&[u8], &[u8]> {
+///   let (i, _) = context("frame type", take(1usize)).parse(i)?;
+///   let (i, len) = context("frame length", be_u32).parse(i)?;
+///   context("frame payload", take(len)).parse(i)
+/// }
+///
+/// let buffer = &b"\x01\x00\x00"[..];
+/// assert_eq!(frame(buffer), Err(Err::Incomplete(Needed::new(2))));
+///
+/// let missing = missing().unwrap();
+/// assert_eq!(
+///   missing,
+///   Missing { context: "frame length", needed: Needed::new(2) }
+/// );
+/// assert_eq!(missing.to_string(), "need 2 more bytes for frame length");
+///
+/// // the record is taken
+/// assert_eq!(nom::error::missing(), None);
+/// ```
+#[cfg(feature = "incomplete-context")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "incomplete-context")))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Missing {
+  /// Label of the innermost context that returned `Incomplete`
+  pub context: &'static str,
+  /// Amount of data needed
+  pub needed: Needed,
+}
+
+#[cfg(feature = "incomplete-context")]
+impl fmt::Display for Missing {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self.needed {
+      Needed::Size(n) => write!(f, "need {} more bytes for {}", n, self.context),
+      Needed::Unknown => write!(f, "need more data for {}", self.context),
+    }
+  }
+}
+
+/// Takes the [Missing] record of the last `Incomplete` result returned by a [context]
+/// on the current thread, with the `incomplete-context` feature
+///
+/// It is cleared when a context starts, and when a context does not return the `Incomplete`
+/// result of an inner one, as when it is wrapped in `complete`. An `Incomplete` result returned
+/// outside of any context leaves the record of the previous one, so it should be taken after
+/// each `Incomplete` result.
+#[cfg(feature = "incomplete-context")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "incomplete-context")))]
+pub fn missing() -> Option<Missing> {
+  incomplete::take()
 }
 
-impl<I, F> Parser<I> for Context<F>
-where
-  I: Clone,
-  F: Parser<I>,
-  <F as Parser<I>>::Error: ContextError<I>,
-{
-  type Output = <F as Parser<I>>::Output;
-  type Error = <F as Parser<I>>::Error;
+#[cfg(feature = "incomplete-context")]
+mod incomplete {
+  use super::Missing;
+  use std::cell::Cell;
 
-  fn process<OM: OutputMode>(&mut self, input: I) -> PResult<OM, I, Self::Output, Self::Error> {
-    match self.parser.process::<OM>(input.clone()) {
-      Err(Err::Error(e)) => Err(Err::Error(OM::Error::map(e, |e| {
-        <F as Parser<I>>::Error::add_context(input, self.context, e)
-      }))),
-      Err(Err::Failure(e)) => Err(Err::Failure(<F as Parser<I>>::Error::add_context(
-        input,
-        self.context,
-        e,
-      ))),
-      x => x,
-    }
+  std::thread_local! {
+    static MISSING: Cell<Option<Missing>> = const { Cell::new(None) };
+  }
+
+  pub(super) fn take() -> Option<Missing> {
+    MISSING.with(|m| m.take())
+  }
+
+  /// Removes the record of an `Incomplete` result that was not returned
+  pub(super) fn clear() {
+    MISSING.with(|m| m.set(None));
+  }
+
+  /// Keeps the record of an inner context, or records this one
+  pub(super) fn record(missing: Missing) {
+    MISSING.with(|m| {
+      if m.get().is_none() {
+        m.set(Some(missing));
+      }
+    });
   }
 }
 
 /// Indicates which parser returned an error
 #[rustfmt::skip]
-#[derive(Debug,PartialEq,Eq,Hash,Clone,Copy)]
+#[derive(Debug,PartialEq,Eq,Clone,Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(deprecated,missing_docs)]
 pub enum ErrorKind {
   Tag,
@@ -311,11 +1322,37 @@ pub enum ErrorKind {
   Many,
   Fold,
   Precedence,
+  Unique,
+  Sorted,
+  Budget,
+  Depth,
+  InfiniteLoop,
+  Cancelled,
+  Utf8,
+  OutOfRange,
+  Indent,
+  Checksum,
+  Dedent,
+  Suffix,
+  Unclosed,
+  Unopened,
+}
+
+/// Hashes the stable code of [error_to_u32], so the hash does not depend on the order of
+/// the variants
+impl Hash for ErrorKind {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    error_to_u32(self).hash(state);
+  }
 }
 
 #[rustfmt::skip]
 #[allow(deprecated)]
 /// Converts an ErrorKind to a number
+///
+/// The codes are stable, so they can be stored, like in fuzzing corpora or telemetry: the
+/// code of a kind never changes, and the code of a removed kind is not reused. New kinds
+/// get the next unused code. [ErrorKind::from_u32] converts a code back.
 pub fn error_to_u32(e: &ErrorKind) -> u32 {
   match *e {
     ErrorKind::Tag                       => 1,
@@ -375,10 +1412,112 @@ pub fn error_to_u32(e: &ErrorKind) -> u32 {
     ErrorKind::Fold                      => 77,
     ErrorKind::BinDigit                  => 78,
     ErrorKind::Precedence                => 79,
+    ErrorKind::Unique                    => 80,
+    ErrorKind::Sorted                    => 81,
+    ErrorKind::Budget                    => 82,
+    ErrorKind::Depth                     => 83,
+    ErrorKind::InfiniteLoop              => 84,
+    ErrorKind::Cancelled                 => 85,
+    ErrorKind::Utf8                      => 86,
+    ErrorKind::OutOfRange                => 87,
+    ErrorKind::Indent                    => 88,
+    ErrorKind::Checksum                  => 89,
+    ErrorKind::Dedent                    => 90,
+    ErrorKind::Suffix                    => 91,
+    ErrorKind::Unclosed                  => 92,
+    ErrorKind::Unopened                  => 93,
   }
 }
 
 impl ErrorKind {
+  #[rustfmt::skip]
+  #[allow(deprecated)]
+  /// Converts a code returned by [error_to_u32] back to an ErrorKind, returns `None` for
+  /// unknown codes
+  ///
+  /// ```rust
+  /// use nom::error::{error_to_u32, ErrorKind};
+  ///
+  /// assert_eq!(ErrorKind::from_u32(error_to_u32(&ErrorKind::Tag)), Some(ErrorKind::Tag));
+  /// assert_eq!(ErrorKind::from_u32(11), None);
+  /// ```
+  pub fn from_u32(code: u32) -> Option<ErrorKind> {
+    Some(match code {
+      1    => ErrorKind::Tag,
+      2    => ErrorKind::MapRes,
+      3    => ErrorKind::MapOpt,
+      4    => ErrorKind::Alt,
+      5    => ErrorKind::IsNot,
+      6    => ErrorKind::IsA,
+      7    => ErrorKind::SeparatedList,
+      8    => ErrorKind::SeparatedNonEmptyList,
+      9    => ErrorKind::Many1,
+      10   => ErrorKind::Count,
+      12   => ErrorKind::TakeUntil,
+      15   => ErrorKind::LengthValue,
+      16   => ErrorKind::TagClosure,
+      17   => ErrorKind::Alpha,
+      18   => ErrorKind::Digit,
+      19   => ErrorKind::AlphaNumeric,
+      20   => ErrorKind::Space,
+      21   => ErrorKind::MultiSpace,
+      22   => ErrorKind::LengthValueFn,
+      23   => ErrorKind::Eof,
+      27   => ErrorKind::Switch,
+      28   => ErrorKind::TagBits,
+      29   => ErrorKind::OneOf,
+      30   => ErrorKind::NoneOf,
+      40   => ErrorKind::Char,
+      41   => ErrorKind::CrLf,
+      42   => ErrorKind::RegexpMatch,
+      43   => ErrorKind::RegexpMatches,
+      44   => ErrorKind::RegexpFind,
+      45   => ErrorKind::RegexpCapture,
+      46   => ErrorKind::RegexpCaptures,
+      47   => ErrorKind::TakeWhile1,
+      48   => ErrorKind::Complete,
+      49   => ErrorKind::Fix,
+      50   => ErrorKind::Escaped,
+      51   => ErrorKind::EscapedTransform,
+      56   => ErrorKind::NonEmpty,
+      57   => ErrorKind::ManyMN,
+      59   => ErrorKind::HexDigit,
+      61   => ErrorKind::OctDigit,
+      62   => ErrorKind::Many0,
+      63   => ErrorKind::Not,
+      64   => ErrorKind::Permutation,
+      65   => ErrorKind::ManyTill,
+      66   => ErrorKind::Verify,
+      67   => ErrorKind::TakeTill1,
+      69   => ErrorKind::TakeWhileMN,
+      70   => ErrorKind::TooLarge,
+      71   => ErrorKind::Many0Count,
+      72   => ErrorKind::Many1Count,
+      73   => ErrorKind::Float,
+      74   => ErrorKind::Satisfy,
+      75   => ErrorKind::Fail,
+      76   => ErrorKind::Many,
+      77   => ErrorKind::Fold,
+      78   => ErrorKind::BinDigit,
+      79   => ErrorKind::Precedence,
+      80   => ErrorKind::Unique,
+      81   => ErrorKind::Sorted,
+      82   => ErrorKind::Budget,
+      83   => ErrorKind::Depth,
+      84   => ErrorKind::InfiniteLoop,
+      85   => ErrorKind::Cancelled,
+      86   => ErrorKind::Utf8,
+      87   => ErrorKind::OutOfRange,
+      88   => ErrorKind::Indent,
+      89   => ErrorKind::Checksum,
+      90   => ErrorKind::Dedent,
+      91   => ErrorKind::Suffix,
+      92   => ErrorKind::Unclosed,
+      93   => ErrorKind::Unopened,
+      _    => return None,
+    })
+  }
+
   #[rustfmt::skip]
   #[allow(deprecated)]
   /// Converts an ErrorKind to a text description
@@ -441,10 +1580,266 @@ impl ErrorKind {
       ErrorKind::Many                      => "Many",
       ErrorKind::Fold                      => "Fold",
       ErrorKind::Precedence                => "Precedence",
+      ErrorKind::Unique                    => "Unique",
+      ErrorKind::Sorted                    => "Sorted",
+      ErrorKind::Budget                    => "Budget",
+      ErrorKind::Depth                     => "Depth",
+      ErrorKind::InfiniteLoop              => "Infinite loop",
+      ErrorKind::Cancelled                 => "Cancelled",
+      ErrorKind::Utf8                      => "UTF-8",
+      ErrorKind::OutOfRange                => "Index out of range",
+      ErrorKind::Indent                    => "Indentation",
+      ErrorKind::Checksum                  => "Checksum",
+      ErrorKind::Dedent                    => "Dedent",
+      ErrorKind::Suffix                    => "Suffix",
+      ErrorKind::Unclosed                  => "Unclosed delimiter",
+      ErrorKind::Unopened                  => "Unopened delimiter",
+    }
+  }
+}
+
+impl ErrorKind {
+  #[rustfmt::skip]
+  #[allow(deprecated)]
+  /// Converts an ErrorKind to a message for the users of an application, describing
+  /// what was expected instead of the parser that failed
+  ///
+  /// The messages are stable, unlike [ErrorKind::description]. See [Expectation] to
+  /// replace them or to take the failing input into account.
+  pub fn expectation(&self) -> &'static str {
+    match *self {
+      ErrorKind::Tag
+      | ErrorKind::TagClosure
+      | ErrorKind::TagBits
+      | ErrorKind::Char                    => "expected a specific token",
+      ErrorKind::OneOf
+      | ErrorKind::Satisfy                 => "expected a specific character",
+      ErrorKind::NoneOf                    => "unexpected character",
+      ErrorKind::Alpha                     => "expected a letter",
+      ErrorKind::Digit                     => "expected a number",
+      ErrorKind::HexDigit                  => "expected a hexadecimal number",
+      ErrorKind::OctDigit                  => "expected an octal number",
+      ErrorKind::BinDigit                  => "expected a binary number",
+      ErrorKind::Float                     => "expected a decimal number",
+      ErrorKind::AlphaNumeric              => "expected a letter or a digit",
+      ErrorKind::Space
+      | ErrorKind::MultiSpace              => "expected whitespace",
+      ErrorKind::CrLf                      => "expected a line ending",
+      ErrorKind::Eof                       => "unexpected input length",
+      ErrorKind::TakeUntil                 => "missing closing delimiter",
+      ErrorKind::Escaped
+      | ErrorKind::EscapedTransform        => "invalid escape sequence",
+      ErrorKind::MapRes
+      | ErrorKind::MapOpt
+      | ErrorKind::Verify                  => "invalid value",
+      ErrorKind::Many1
+      | ErrorKind::Many1Count
+      | ErrorKind::SeparatedNonEmptyList
+      | ErrorKind::NonEmpty
+      | ErrorKind::TakeWhile1
+      | ErrorKind::TakeTill1               => "expected at least one element",
+      ErrorKind::Count
+      | ErrorKind::ManyMN
+      | ErrorKind::Many
+      | ErrorKind::TakeWhileMN             => "wrong number of elements",
+      ErrorKind::LengthValue
+      | ErrorKind::LengthValueFn           => "invalid length",
+      ErrorKind::TooLarge                  => "input too large",
+      ErrorKind::Unique                    => "duplicate entry",
+      ErrorKind::Sorted                    => "entries out of order",
+      ErrorKind::Precedence                => "invalid expression",
+      ErrorKind::Budget
+      | ErrorKind::Depth                   => "input nested too deeply",
+      ErrorKind::Cancelled                 => "parsing cancelled",
+      ErrorKind::Utf8                      => "invalid UTF-8",
+      ErrorKind::OutOfRange                => "reference out of range",
+      ErrorKind::Indent                    => "unexpected indentation",
+      ErrorKind::Checksum                  => "checksum mismatch",
+      ErrorKind::Dedent                    => "unindent does not match any outer indentation level",
+      ErrorKind::Suffix                    => "invalid literal suffix",
+      ErrorKind::Unclosed                  => "unclosed delimiter",
+      ErrorKind::Unopened                  => "closing delimiter without an opening one",
+      ErrorKind::Alt
+      | ErrorKind::Switch
+      | ErrorKind::Permutation
+      | ErrorKind::IsNot
+      | ErrorKind::IsA
+      | ErrorKind::SeparatedList
+      | ErrorKind::Many0
+      | ErrorKind::ManyTill
+      | ErrorKind::RegexpMatch
+      | ErrorKind::RegexpMatches
+      | ErrorKind::RegexpFind
+      | ErrorKind::RegexpCapture
+      | ErrorKind::RegexpCaptures
+      | ErrorKind::Complete
+      | ErrorKind::Fix
+      | ErrorKind::Not
+      | ErrorKind::Many0Count
+      | ErrorKind::Fail
+      | ErrorKind::Fold
+      | ErrorKind::InfiniteLoop            => "unexpected input",
+    }
+  }
+}
+
+/// Converts a code returned by [error_to_u32], the error is the unknown code
+impl core::convert::TryFrom<u32> for ErrorKind {
+  type Error = u32;
+
+  fn try_from(code: u32) -> Result<Self, u32> {
+    ErrorKind::from_u32(code).ok_or(code)
+  }
+}
+
+/// Translation of errors to messages for the users of an application
+///
+/// It is implemented by closures taking the error kind and the failing input, and
+/// returning `None` to keep the default message of [ErrorKind::expectation].
+pub trait Expectations<I> {
+  /// Returns the message of an error of kind `kind` at `input`
+  fn message(&self, kind: ErrorKind, input: &I) -> Option<&'static str>;
+}
+
+impl<I, F> Expectations<I> for F
+where
+  F: Fn(ErrorKind, &I) -> Option<&'static str>,
+{
+  fn message(&self, kind: ErrorKind, input: &I) -> Option<&'static str> {
+    self(kind, input)
+  }
+}
+
+/// Error converted to a message for the users of an application
+///
+/// Parsers report errors with an [ErrorKind] naming the combinator that failed, which
+/// is meaningless to the users of a tool built on them. An `Expectation` replaces it
+/// with a message describing what was expected, and its `Display` implementation shows
+/// that message followed by the start of the failing input. The messages come from
+/// [ErrorKind::expectation], or from the [Expectations] of the application, which can
+/// use the input to refine them.
+///
+/// It implements [MapInput], so it can be shown with its line and column in a [Report].
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+/// use nom::bytes::complete::{tag, take_until};
+/// use nom::character::complete::digit1;
+/// use nom::error::{Expectation, Report};
+/// use nom::sequence::delimited;
+///
+/// let messages = |kind, input: &&str| match kind {
+///   ErrorKind::TakeUntil => Some("unterminated string"),
+///   ErrorKind::Tag if input.starts_with('\'') => Some("strings use double quotes"),
+///   _ => None,
+/// };
+///
+/// let mut string = delimited(tag::<_, _, Error<_>>("\""), take_until("\""), tag("\""));
+/// let Err(Err::Error(e)) = string.parse("\"abc") else { unreachable!() };
+/// assert_eq!(e.expectation(&messages).to_string(), "unterminated string, found `abc`");
+/// let Err(Err::Error(e)) = string.parse("'abc'") else { unreachable!() };
+/// assert_eq!(e.expectation(&messages).to_string(), "strings use double quotes, found `'abc'`");
+/// let Err(Err::Error(e)) = string.parse("") else { unreachable!() };
+/// assert_eq!(e.expectation(&messages).to_string(), "expected a specific token at the end of the input");
+///
+/// let input = "x = 12;\ny = abc;";
+/// let Err(Err::Error(e)) = digit1::<_, Error<_>>.parse(&input[12..]) else { unreachable!() };
+/// let report = Report { input, error: Expectation::from(e) };
+/// assert_eq!(report.to_string(), "2:5: expected a number, found `abc;`\ny = abc;\n    ^");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Expectation<I> {
+  /// Position of the error
+  pub input: I,
+  /// Kind of the error
+  pub kind: ErrorKind,
+  /// Message for the users
+  pub message: &'static str,
+}
+
+impl<I> Expectation<I> {
+  /// Creates an expectation with the default message of the error kind
+  pub fn new(input: I, kind: ErrorKind) -> Self {
+    Expectation {
+      input,
+      kind,
+      message: kind.expectation(),
+    }
+  }
+
+  /// Creates an expectation with the message of `messages`, or the default message
+  /// of the error kind
+  pub fn with<X: Expectations<I>>(input: I, kind: ErrorKind, messages: &X) -> Self {
+    let message = messages
+      .message(kind, &input)
+      .unwrap_or_else(|| kind.expectation());
+    Expectation {
+      input,
+      kind,
+      message,
+    }
+  }
+}
+
+impl<I> Error<I> {
+  /// Converts the error to an [Expectation], with the messages of the application
+  pub fn expectation<X: Expectations<I>>(self, messages: &X) -> Expectation<I> {
+    Expectation::with(self.input, self.code, messages)
+  }
+}
+
+impl<I> From<Error<I>> for Expectation<I> {
+  fn from(e: Error<I>) -> Self {
+    Expectation::new(e.input, e.code)
+  }
+}
+
+impl<I> MapInput<I> for Expectation<I> {
+  type Mapped<U> = Expectation<U>;
+
+  fn map_input<U, F: FnMut(I) -> U>(self, mut f: F) -> Expectation<U> {
+    Expectation {
+      input: f(self.input),
+      kind: self.kind,
+      message: self.message,
+    }
+  }
+}
+
+/// Shows the message, then the failing input up to the next whitespace, or at most 16
+/// characters of it
+impl<I> fmt::Display for Expectation<I>
+where
+  I: Input,
+  <I as Input>::Item: AsChar,
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if self.input.input_len() == 0 {
+      return write!(f, "{} at the end of the input", self.message);
+    }
+
+    write!(f, "{}, found `", self.message)?;
+    for c in self
+      .input
+      .iter_elements()
+      .map(AsChar::as_char)
+      .take_while(|c| !c.is_whitespace())
+      .take(16)
+    {
+      write!(f, "{}", c)?;
     }
+    write!(f, "`")
   }
 }
 
+#[cfg(feature = "std")]
+impl<I> std::error::Error for Expectation<I>
+where
+  I: Input + fmt::Debug,
+  <I as Input>::Item: AsChar,
+{
+}
+
 /// Creates a parse error from a `nom::ErrorKind`
 /// and the position in the input
 #[allow(unused_variables)]
@@ -471,7 +1866,8 @@ macro_rules! error_node_position(
 /// The message prints the `Error` or `Incomplete`
 /// and the parser's calling code.
 ///
-/// It also displays the input in hexdump format
+/// It also displays the input in hexdump format. Use [dbg_dmp_max] to limit the length
+/// of the dump.
 ///
 /// ```rust
 /// use nom::{IResult, error::dbg_dmp, bytes::complete::tag};
@@ -483,7 +1879,7 @@ macro_rules! error_node_position(
 ///   let a = &b"efghijkl"[..];
 ///
 /// // Will print the following message:
-/// // Error(Position(0, [101, 102, 103, 104, 105, 106, 107, 108])) at l.5 by ' tag ! ( "abcd" ) '
+/// // tag: Error(Error(Error { input: [101, 102, 103, 104, 105, 106, 107, 108], code: Tag })) at:
 /// // 00000000        65 66 67 68 69 6a 6b 6c         efghijkl
 /// f(a);
 /// ```
@@ -506,6 +1902,49 @@ where
   }
 }
 
+/// Like [dbg_dmp], but dumps at most `max_len` bytes of the input
+///
+/// The dump of a long input ends with the number of bytes left out.
+///
+/// ```rust
+/// use nom::{IResult, error::dbg_dmp_max, character::complete::digit1};
+///
+/// fn f(i: &str) -> IResult<&str, &str> {
+///   dbg_dmp_max(digit1, "digits", 8)(i)
+/// }
+///
+/// // Will print the following message:
+/// // digits: Error(Error(Error { input: "a very long line", code: Digit })) at:
+/// // 00000000        61 20 76 65 72 79 20 6c         a very l
+/// // ... 8 more bytes
+/// f("a very long line");
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "std")))]
+pub fn dbg_dmp_max<I, F, O, E: std::fmt::Debug>(
+  mut f: F,
+  context: &'static str,
+  max_len: usize,
+) -> impl FnMut(I) -> IResult<I, O, E>
+where
+  I: AsBytes + Clone,
+  F: FnMut(I) -> IResult<I, O, E>,
+{
+  use crate::HexDisplay;
+  move |i: I| match f(i.clone()) {
+    Err(e) => {
+      let bytes = i.as_bytes();
+      let shown = &bytes[..bytes.len().min(max_len)];
+      println!("{}: Error({:?}) at:\n{}", context, e, shown.to_hex(8));
+      if shown.len() < bytes.len() {
+        println!("... {} more bytes", bytes.len() - shown.len());
+      }
+      Err(e)
+    }
+    a => a,
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -566,6 +2005,54 @@ mod tests {
     );
   }
 
+  #[test]
+  fn furthest_error() {
+    use crate::branch::alt;
+    use crate::bytes::complete::tag;
+    use crate::sequence::{pair, preceded};
+
+    type E<'a> = FurthestError<&'a str, Error<&'a str>>;
+
+    fn kv(i: &str) -> crate::IResult<&str, &str, E<'_>> {
+      alt((
+        preceded(pair(tag("key"), tag("=")), tag("value")),
+        context("long", preceded(tag("key:"), tag("value"))),
+        tag("null"),
+      ))
+      .parse(i)
+    }
+
+    assert_eq!(kv("key=value"), Ok(("", "value")));
+    assert_eq!(
+      kv("key=other"),
+      Err(Err::Error(FurthestError {
+        input: "other",
+        error: Error::new("other", ErrorKind::Tag),
+      }))
+    );
+    assert_eq!(
+      kv("key:other"),
+      Err(Err::Error(FurthestError {
+        input: "other",
+        error: Error::new("other", ErrorKind::Tag),
+      }))
+    );
+    // same position: the last error is kept, like the wrapped type does
+    assert_eq!(
+      kv("abc"),
+      Err(Err::Error(FurthestError {
+        input: "abc",
+        error: Error::new("abc", ErrorKind::Tag),
+      }))
+    );
+
+    let a = E::from_error_kind("bc", ErrorKind::Char);
+    let b = E::from_error_kind("abc", ErrorKind::Tag);
+    assert_eq!(a.clone().or(b.clone()), a);
+    assert_eq!(b.or(a.clone()), a);
+    assert_eq!(E::append("abc", ErrorKind::Alt, a.clone()).input, "bc");
+  }
+
   #[cfg(feature = "alloc")]
   #[test]
   fn clone_error() {
@@ -587,6 +2074,417 @@ mod tests {
 
     let _err: Error<u8> = err.copied();
   }
+
+  #[cfg(feature = "serde")]
+  #[test]
+  fn serde_error() {
+    use crate::internal::Needed;
+    use crate::Err;
+
+    let e: Err<Error<&str>> = Err::Error(Error::new("abc", ErrorKind::Tag));
+    let json = serde_json::to_string(&e).unwrap();
+    assert_eq!(json, r#"{"Error":{"input":"abc","code":"Tag"}}"#);
+    assert_eq!(serde_json::from_str::<Err<Error<&str>>>(&json).unwrap(), e);
+
+    let e = Error::new(&"abc;"[3..], ErrorKind::Digit).offset_in(&"abc;");
+    assert_eq!(
+      serde_json::to_string(&e).unwrap(),
+      r#"{"input":3,"code":"Digit"}"#
+    );
+
+    let n: Err<Error<usize>> = Err::Incomplete(Needed::new(2));
+    let json = serde_json::to_string(&n).unwrap();
+    assert_eq!(json, r#"{"Incomplete":{"Size":2}}"#);
+    assert_eq!(serde_json::from_str::<Err<Error<usize>>>(&json).unwrap(), n);
+  }
+
+  #[cfg(feature = "alloc")]
+  #[test]
+  fn map_input_test() {
+    use crate::lib::std::string::String;
+
+    let e = FurthestError {
+      input: "abc",
+      error: Error::new("bc", ErrorKind::Tag),
+    };
+    assert_eq!(
+      e.clone().into_owned(),
+      FurthestError {
+        input: String::from("abc"),
+        error: Error::new(String::from("bc"), ErrorKind::Tag)
+      }
+    );
+    assert_eq!(
+      e.map_input(|i| i.len()),
+      FurthestError {
+        input: 3,
+        error: Error::new(2, ErrorKind::Tag)
+      }
+    );
+    assert_eq!(
+      ("abc", ErrorKind::Eof).map_input(str::len),
+      (3, ErrorKind::Eof)
+    );
+
+    let e: Err<(&[u8], ErrorKind)> = Err::Failure((&b"abc"[..], ErrorKind::Eof));
+    assert_eq!(
+      e.into_owned(),
+      Err::Failure((b"abc".to_vec(), ErrorKind::Eof))
+    );
+  }
+
+  #[cfg(feature = "alloc")]
+  #[test]
+  fn source_map_test() {
+    use crate::lib::std::vec;
+
+    // "a" x3, "b" x2, then "cd" copied from a run length encoded input
+    let input = &b"\x03a\x02b\x00cd"[..];
+    let buffer = b"aaabbcd".to_vec();
+    let mut map = SourceMap::new();
+    map.replaced(0, 0);
+    map.replaced(3, 2);
+    map.copied(5, 5);
+    assert_eq!(
+      (0..8).map(|i| map.original(i)).collect::<vec::Vec<_>>(),
+      [0, 0, 0, 2, 2, 5, 6, 7]
+    );
+
+    let e: Err<(&[u8], ErrorKind)> = Err::Error((&buffer[4..], ErrorKind::Tag));
+    assert_eq!(
+      map.map_error(&input, &buffer[..], e),
+      Err::Error((&input[2..], ErrorKind::Tag))
+    );
+    let e: Err<(&[u8], ErrorKind)> = Err::Failure((&buffer[6..], ErrorKind::Eof));
+    assert_eq!(
+      map.map_error(&input, &buffer[..], e),
+      Err::Failure((&input[6..], ErrorKind::Eof))
+    );
+
+    // a later segment at the same offset replaces the previous one
+    map.copied(5, 6);
+    assert_eq!(map.original(6), 7);
+    assert_eq!(SourceMap::new().original(4), 4);
+  }
+
+  #[test]
+  fn stack_error() {
+    let mut e = StackError::<&str, 3>::from_error_kind("a", ErrorKind::Tag);
+    e = StackError::append("ba", ErrorKind::Many0, e);
+    e = StackError::add_context("cba", "list", e);
+    assert_eq!(e.dropped(), 0);
+    e = StackError::add_context("dcba", "outer", e);
+    assert_eq!(e.dropped(), 1);
+
+    let frames = [
+      ("ba", StackFrame::Kind(ErrorKind::Many0)),
+      ("cba", StackFrame::Context("list")),
+      ("dcba", StackFrame::Context("outer")),
+    ];
+    assert!(e.frames().eq(frames.iter()));
+
+    let e = e.map_input(str::len);
+    assert!(e.frames().map(|f| f.0).eq([2, 3, 4]));
+
+    let e = StackError::<&str, 0>::from_char("a", 'b');
+    assert_eq!(e.frames().next(), None);
+    assert_eq!(e.dropped(), 1);
+
+    #[cfg(feature = "alloc")]
+    {
+      use crate::lib::std::string::ToString;
+
+      let e = StackError::<&str, 2>::add_context("ab", "word", StackError::from_char("b", 'c'));
+      assert_eq!(
+        e.to_string(),
+        "Parse error:\nexpected 'c' at: b\nin section 'word', at: ab\n"
+      );
+    }
+  }
+
+  #[test]
+  fn fingerprint_test() {
+    let e1 = StackError::<&str, 2>::add_context("bc", "word", StackError::from_char("c", 'd'));
+    let e2 = StackError::<&str, 2>::add_context("bc", "word", StackError::from_char("c", 'd'));
+    assert_eq!(e1, e2);
+
+    let input = "abc";
+    assert_eq!(fingerprint(&input, e1.clone()), fingerprint(&input, e2));
+    assert_ne!(
+      fingerprint(&input, e1.clone()),
+      fingerprint(&&input[1..], e1)
+    );
+    assert_ne!(
+      fingerprint(&input, Error::new(&input[1..], ErrorKind::Tag)),
+      fingerprint(&input, Error::new(&input[1..], ErrorKind::Digit))
+    );
+    // the fingerprint does not depend on the platform nor on the order of the variants of
+    // `ErrorKind`: FNV-1a of the offset, as a 64 bits little endian integer, and of the
+    // stable code of the kind, as a 32 bits one
+    let mut hasher = Fnv(0xcbf2_9ce4_8422_2325);
+    1usize.hash(&mut hasher);
+    error_to_u32(&ErrorKind::Tag).hash(&mut hasher);
+    let fp = fingerprint(&input, Error::new(&input[1..], ErrorKind::Tag));
+    assert_eq!(fp, hasher.finish());
+    assert_eq!(fp, 0xff1e_d942_1877_e875);
+  }
+
+  #[cfg(feature = "alloc")]
+  #[test]
+  fn alt_error_order() {
+    use crate::branch::alt;
+    use crate::bytes::complete::tag;
+    use crate::character::complete::{alpha1, digit1};
+    use crate::lib::std::vec::Vec;
+
+    #[derive(Debug, PartialEq)]
+    struct Kinds(Vec<ErrorKind>);
+
+    impl<I> ParseError<I> for Kinds {
+      fn from_error_kind(_: I, kind: ErrorKind) -> Self {
+        Kinds(vec![kind])
+      }
+
+      fn append(_: I, kind: ErrorKind, mut other: Self) -> Self {
+        other.0.push(kind);
+        other
+      }
+
+      fn or(mut self, other: Self) -> Self {
+        self.0.extend(other.0);
+        self
+      }
+    }
+
+    // the errors of the branches are combined in the order of the branches
+    let res: IResult<&str, &str, Kinds> = alt((tag("a"), digit1, alpha1)).parse(";");
+    assert_eq!(
+      res,
+      Err(Err::Error(Kinds(vec![
+        ErrorKind::Tag,
+        ErrorKind::Digit,
+        ErrorKind::Alpha,
+        ErrorKind::Alt
+      ])))
+    );
+    let mut branches = [tag("a"), tag("b"), tag("c")];
+    let res: IResult<&str, &str, Kinds> = alt(&mut branches[..]).parse(";");
+    assert_eq!(
+      res,
+      Err(Err::Error(Kinds(vec![
+        ErrorKind::Tag,
+        ErrorKind::Tag,
+        ErrorKind::Tag,
+        ErrorKind::Alt
+      ])))
+    );
+  }
+
+  #[test]
+  fn line_col_test() {
+    let input = "ab\ncdé\n\nf";
+    assert_eq!(line_col(input, 0), (1, 1));
+    assert_eq!(line_col(input, 2), (1, 3));
+    assert_eq!(line_col(input, 3), (2, 1));
+    // inside `é`, and after it
+    assert_eq!(line_col(input, 6), (2, 3));
+    assert_eq!(line_col(input, 7), (2, 4));
+    assert_eq!(line_col(input, 8), (3, 1));
+    assert_eq!(line_col(input, 9), (4, 1));
+    assert_eq!(line_col(input, 10), (4, 2));
+    assert_eq!(line_col(input, 100), (4, 2));
+    assert_eq!(line_col("", 0), (1, 1));
+  }
+
+  #[cfg(feature = "alloc")]
+  #[test]
+  fn report_test() {
+    use crate::internal::{Err, Finish, IResult};
+    use crate::lib::std::string::{String, ToString};
+
+    let input = "ab\ncdé x\nf";
+    let res: IResult<&str, (), Error<&str>> =
+      Err(Err::Error(Error::new(&input[8..], ErrorKind::Tag)));
+    let report = res.into_report(input).unwrap_err();
+    assert_eq!(report.offset(), Some(8));
+    assert_eq!(report.line_col(), Some((2, 5)));
+    assert_eq!(report.to_string(), "2:5: error Tag at: x\nf\ncdé x\n    ^");
+
+    let res: IResult<&str, (), ()> = Err(Err::Error(()));
+    assert_eq!(res.into_report(input).unwrap_err().offset(), None);
+
+    let e: Err<Error<String>> = Err::Error(Error::new(&input[3..], ErrorKind::Tag)).into();
+    assert_eq!(
+      e,
+      Err::Error(Error::new(String::from("cdé x\nf"), ErrorKind::Tag))
+    );
+  }
+
+  #[cfg(feature = "alloc")]
+  #[test]
+  fn parse_all_test() {
+    use crate::bytes::complete::tag;
+    use crate::character::complete::{alpha1, digit1};
+    use crate::lib::std::string::ToString;
+
+    let mut parser = (alpha1::<_, Error<&str>>, tag(" "), digit1);
+    assert_eq!(parser.parse_all("abc 12"), Ok(("abc", " ", "12")));
+
+    let input = "ab é";
+    let e = parser.parse_all(input).unwrap_err();
+    assert_eq!(e.error(), &Error::new(3, ErrorKind::Digit));
+    assert_eq!(
+      (e.offset(), e.line_col(), e.line()),
+      (Some(3), Some((1, 4)), Some(input))
+    );
+    assert_eq!(e.to_string(), "1:4: error Digit at: 3\nab é\n   ^");
+
+    // byte inputs are shown as lossy UTF-8
+    let input = &b"a\n\xff12\nb"[..];
+    let e = tag::<_, _, (&[u8], ErrorKind)>(&b"a\n"[..])
+      .parse_all(input)
+      .unwrap_err();
+    assert_eq!(e.clone().into_error(), (2, ErrorKind::Eof));
+    assert_eq!(e.line_col(), Some((2, 1)));
+    assert_eq!(e.line(), Some("\u{fffd}12"));
+
+    // errors without positions
+    let e = tag::<_, _, ()>("a").parse_all("b").unwrap_err();
+    assert_eq!((e.offset(), e.line()), (None, None));
+  }
+
+  #[test]
+  fn tiny_error() {
+    use crate::bytes::complete::tag;
+    use crate::character::complete::digit1;
+    use crate::combinator::map_res;
+    use crate::internal::{Err, IResult};
+    use crate::lib::std::str::FromStr;
+
+    fn port(i: &str) -> IResult<&str, u16, TinyError> {
+      context(
+        "port",
+        crate::sequence::preceded(tag(":"), map_res(digit1, u16::from_str)),
+      )
+      .parse(i)
+    }
+
+    let input = "host:70000";
+    let Err(Err::Error(e)) = port(&input[4..]) else {
+      panic!()
+    };
+    assert_eq!(e.kind, ErrorKind::MapRes);
+    assert_eq!(e.offset_in(&input), 5);
+    assert_eq!(e.input_in(input), "70000");
+
+    let input = &b"host"[..];
+    let e = TinyError::from_error_kind(&input[4..], ErrorKind::Eof);
+    assert_eq!(
+      e,
+      TinyError {
+        remaining: 0,
+        kind: ErrorKind::Eof
+      }
+    );
+    assert_eq!(e.offset_in(&input), 4);
+  }
+
+  #[test]
+  fn error_code_test() {
+    use core::convert::TryFrom;
+
+    // every code decodes to the kind encoded with it
+    let mut kinds = 0;
+    for code in 0..256 {
+      if let Some(kind) = ErrorKind::from_u32(code) {
+        assert_eq!(error_to_u32(&kind), code);
+        kinds += 1;
+      }
+    }
+    // one code for each kind
+    assert_eq!(kinds, 71);
+
+    // the codes are stable
+    assert_eq!(error_to_u32(&ErrorKind::Tag), 1);
+    assert_eq!(error_to_u32(&ErrorKind::Eof), 23);
+    assert_eq!(error_to_u32(&ErrorKind::Float), 73);
+    assert_eq!(error_to_u32(&ErrorKind::Suffix), 91);
+
+    assert_eq!(ErrorKind::try_from(62), Ok(ErrorKind::Many0));
+    assert_eq!(ErrorKind::try_from(0), Err(0));
+    assert_eq!(ErrorKind::try_from(u32::MAX), Err(u32::MAX));
+  }
+
+  #[test]
+  fn expectation_test() {
+    use crate::lib::std::string::ToString;
+
+    let e = Expectation::from(Error::new(
+      &b"12345678901234567890 rest"[..],
+      ErrorKind::Alpha,
+    ));
+    assert_eq!(e.message, "expected a letter");
+    assert_eq!(e.to_string(), "expected a letter, found `1234567890123456`");
+    assert_eq!(
+      e.map_input(|i| i.len()),
+      Expectation {
+        input: 25,
+        kind: ErrorKind::Alpha,
+        message: "expected a letter"
+      }
+    );
+
+    let messages = |kind, input: &&[u8]| match (kind, input.first()) {
+      (ErrorKind::Eof, None) => Some("truncated record"),
+      (ErrorKind::Eof, Some(_)) => Some("trailing data"),
+      _ => None,
+    };
+    let e = Error::new(&b""[..], ErrorKind::Eof).expectation(&messages);
+    assert_eq!(e.to_string(), "truncated record at the end of the input");
+    let e = Error::new(&b"\x00\x01"[..], ErrorKind::Eof).expectation(&messages);
+    assert_eq!(e.to_string(), "trailing data, found `\x00\x01`");
+    let e = Error::new(&b"a b"[..], ErrorKind::Digit).expectation(&messages);
+    assert_eq!(e.to_string(), "expected a number, found `a`");
+  }
+
+  #[test]
+  #[cfg(feature = "incomplete-context")]
+  fn missing_test() {
+    use crate::bytes::streaming::tag;
+    use crate::combinator::{complete, opt};
+    use crate::internal::Needed;
+
+    let mut header = (
+      context("magic", tag::<_, _, Error<_>>("GIF")),
+      context("version", tag("89a")),
+    );
+    assert_eq!(header.parse("GIF8"), Err(Err::Incomplete(Needed::new(2))));
+    assert_eq!(
+      missing(),
+      Some(Missing {
+        context: "version",
+        needed: Needed::new(2)
+      })
+    );
+
+    // the outer context keeps the innermost label
+    let mut outer = context("header", context("magic", tag::<_, _, Error<_>>("GIF")));
+    assert_eq!(outer.parse("GI"), Err(Err::Incomplete(Needed::new(1))));
+    assert_eq!(missing().map(|m| m.context), Some("magic"));
+
+    // an `Incomplete` result converted by `complete` is not reported
+    let mut optional = context(
+      "record",
+      (
+        opt(complete(context("comment", tag::<_, _, Error<_>>("ab!")))),
+        context("body", tag("ab")),
+      ),
+    );
+    assert_eq!(optional.parse("ab"), Ok(("", (None, "ab"))));
+    assert_eq!(missing(), None);
+    assert_eq!(optional.parse(""), Err(Err::Incomplete(Needed::new(2))));
+    assert_eq!(missing().map(|m| m.context), Some("body"));
+  }
 }
 
 /*
@@ -0,0 +1,727 @@
+//! Parsers for the `Cookie` and `Set-Cookie` HTTP headers
+//!
+//! Those parsers follow [RFC 6265](https://www.rfc-editor.org/rfc/rfc6265), and work in
+//! one of two [Strictness] modes:
+//! * [Strictness::Strict] follows the grammar of section 4, which servers must produce.
+//!   It is useful to validate headers, or to detect malformed ones in a security scanner.
+//! * [Strictness::Lenient] follows the algorithms of section 5, used by browsers to
+//!   interpret the headers they receive: invalid attributes are ignored, and dates can
+//!   be written in any of the formats found in practice.
+//!
+//! The parsers take the header value, without the header name, and stop at the end of
+//! the line. The names, values and attributes borrow the input.
+//!
+//! ```rust
+//! # use nom::{error::Error, Parser};
+//! use nom::formats::cookie::{cookie, set_cookie, Attribute, CookieDate};
+//! use nom::Strictness;
+//!
+//! let (_, pairs) = cookie::<Error<_>>(Strictness::Strict)
+//!   .parse_complete("SID=31d4d96e407aad42; lang=en-US")
+//!   .unwrap();
+//! assert_eq!(pairs, [("SID", "31d4d96e407aad42"), ("lang", "en-US")]);
+//!
+//! let (_, c) = set_cookie::<Error<_>>(Strictness::Lenient)
+//!   .parse_complete("lang=en-US; expires=Wed, 09-Jun-2021 10:18:14 GMT; Secure; Max-Age=x")
+//!   .unwrap();
+//! assert_eq!((c.name, c.value), ("lang", "en-US"));
+//! assert_eq!(
+//!   c.attributes,
+//!   [
+//!     Attribute::Expires(CookieDate { year: 2021, month: 6, day: 9, hour: 10, minute: 18, second: 14 }),
+//!     Attribute::Secure,
+//!   ]
+//! );
+//! ```
+
+use core::marker::PhantomData;
+
+use crate::branch::alt;
+use crate::bytes::{tag, take, take_till, take_while, take_while1, take_while_m_n};
+use crate::character::char;
+use crate::combinator::{all_consuming, map_opt, recognize, verify};
+use crate::error::{ErrorKind, ParseError};
+use crate::internal::{Emit, Err, Mode, OutputM, OutputMode, PResult, Parser};
+use crate::lib::std::vec::Vec;
+use crate::multi::{many0, separated_list1};
+use crate::sequence::{delimited, preceded, separated_pair};
+use crate::Strictness;
+
+/// Date of the `Expires` attribute, in UTC
+///
+/// The fields are ordered so that comparing dates compares them chronologically.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CookieDate {
+  /// year, from 1601
+  pub year: u16,
+  /// month, from 1 to 12
+  pub month: u8,
+  /// day of the month, from 1 to 31
+  pub day: u8,
+  /// from 0 to 23
+  pub hour: u8,
+  /// from 0 to 59
+  pub minute: u8,
+  /// from 0 to 59
+  pub second: u8,
+}
+
+/// Attribute of a `Set-Cookie` header
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Attribute<'a> {
+  /// `Expires=<date>`
+  Expires(CookieDate),
+  /// `Max-Age=<seconds>`, can be 0 or negative in lenient mode, which expires the cookie
+  MaxAge(i64),
+  /// `Domain=<domain>`, without the leading `.` in lenient mode
+  Domain(&'a str),
+  /// `Path=<path>`
+  Path(&'a str),
+  /// `Secure`
+  Secure,
+  /// `HttpOnly`
+  HttpOnly,
+  /// Any other attribute, like `SameSite=Lax`, as written in the header
+  Extension(&'a str),
+}
+
+/// Content of a `Set-Cookie` header
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SetCookie<'a> {
+  /// cookie name
+  pub name: &'a str,
+  /// cookie value, with its double quotes if there are some
+  pub value: &'a str,
+  /// attributes, in order
+  pub attributes: Vec<Attribute<'a>>,
+}
+
+const MONTHS: [&str; 12] = [
+  "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// Recognizes a cookie name: a token, as defined by RFC 2616.
+///
+/// Returns an error with [ErrorKind::TakeWhile1] if the name is empty.
+pub fn cookie_name<'a, E: ParseError<&'a str>>() -> impl Parser<&'a str, Output = &'a str, Error = E>
+{
+  take_while1(is_token)
+}
+
+/// Recognizes a cookie value, optionally between double quotes, which are included in
+/// the output.
+///
+/// The value can be empty, and it cannot contain spaces, `"`, `,`, `;` or `\`.
+pub fn cookie_value<'a, E: ParseError<&'a str>>(
+) -> impl Parser<&'a str, Output = &'a str, Error = E> {
+  alt((
+    recognize(delimited(char('"'), take_while(is_cookie_octet), char('"'))),
+    take_while(is_cookie_octet),
+  ))
+}
+
+/// Recognizes a strict `name=value` pair, returning the name and the value.
+pub fn cookie_pair<'a, E: ParseError<&'a str>>(
+) -> impl Parser<&'a str, Output = (&'a str, &'a str), Error = E> {
+  separated_pair(cookie_name(), char('='), cookie_value())
+}
+
+/// Recognizes a date from an `Expires` attribute.
+///
+/// In strict mode, the date must use the RFC 1123 format, as in
+/// `Sun, 06 Nov 1994 08:49:37 GMT`, and there is an error if it is invalid.
+///
+/// In lenient mode, the date extends to the next `;` or to the end of the line. Its
+/// parts can be written in any order and separated by any delimiter, the year can have 2
+/// digits, and the month name is only checked on its first 3 letters. If the date is
+/// invalid, it returns an error with [ErrorKind::Verify].
+///
+/// ```rust
+/// # use nom::{error::Error, Parser};
+/// use nom::formats::cookie::{cookie_date, CookieDate};
+/// use nom::Strictness;
+///
+/// let date = CookieDate { year: 1994, month: 11, day: 6, hour: 8, minute: 49, second: 37 };
+///
+/// let mut strict = cookie_date::<Error<_>>(Strictness::Strict);
+/// assert_eq!(strict.parse_complete("Sun, 06 Nov 1994 08:49:37 GMT"), Ok(("", date)));
+/// assert!(strict.parse_complete("Sunday, 06-Nov-94 08:49:37 GMT").is_err());
+///
+/// let mut lenient = cookie_date::<Error<_>>(Strictness::Lenient);
+/// assert_eq!(lenient.parse_complete("Sunday, 06-Nov-94 08:49:37 GMT"), Ok(("", date)));
+/// assert_eq!(lenient.parse_complete("Sun Nov  6 08:49:37 1994; Path=/"), Ok(("; Path=/", date)));
+/// ```
+pub fn cookie_date<'a, E: ParseError<&'a str>>(
+  strictness: Strictness,
+) -> impl Parser<&'a str, Output = CookieDate, Error = E> {
+  CookieDateParser {
+    strictness,
+    e: PhantomData,
+  }
+}
+
+/// Parser implementation for [cookie_date]
+pub struct CookieDateParser<E> {
+  strictness: Strictness,
+  e: PhantomData<E>,
+}
+
+impl<'a, E: ParseError<&'a str>> Parser<&'a str> for CookieDateParser<E> {
+  type Output = CookieDate;
+  type Error = E;
+
+  fn process<OM: OutputMode>(&mut self, i: &'a str) -> PResult<OM, &'a str, CookieDate, E> {
+    match self.strictness {
+      Strictness::Strict => rfc1123_date().process::<OM>(i),
+      Strictness::Lenient => {
+        let (rest, value) =
+          take_till(is_attribute_end).process::<OutputM<Emit, OM::Error, OM::Incomplete>>(i)?;
+        match lenient_date(value) {
+          Some(date) => Ok((rest, OM::Output::bind(|| date))),
+          None => Err(Err::Error(OM::Error::bind(|| {
+            E::from_error_kind(i, ErrorKind::Verify)
+          }))),
+        }
+      }
+    }
+  }
+}
+
+/// Recognizes the value of a `Cookie` header, returning the `(name, value)` pairs.
+///
+/// In strict mode, the pairs must be separated by `"; "`, and the parser stops before
+/// the first invalid pair.
+///
+/// In lenient mode, the pairs are separated by `;` with optional spaces around them,
+/// names and values can contain any character except `;` and `=`, and pairs without a
+/// `=` are ignored.
+///
+/// ```rust
+/// # use nom::{error::Error, Parser};
+/// use nom::formats::cookie::cookie;
+/// use nom::Strictness;
+///
+/// let input = "a=1;b = \"2\" ;flag; c=";
+///
+/// let mut strict = cookie::<Error<_>>(Strictness::Strict);
+/// assert_eq!(strict.parse_complete(input), Ok((";b = \"2\" ;flag; c=", vec![("a", "1")])));
+///
+/// let mut lenient = cookie::<Error<_>>(Strictness::Lenient);
+/// assert_eq!(lenient.parse_complete(input), Ok(("", vec![("a", "1"), ("b", "\"2\""), ("c", "")])));
+/// ```
+pub fn cookie<'a, E: ParseError<&'a str>>(
+  strictness: Strictness,
+) -> impl Parser<&'a str, Output = Vec<(&'a str, &'a str)>, Error = E> {
+  Cookie {
+    strictness,
+    e: PhantomData,
+  }
+}
+
+/// Parser implementation for [cookie]
+pub struct Cookie<E> {
+  strictness: Strictness,
+  e: PhantomData<E>,
+}
+
+impl<'a, E: ParseError<&'a str>> Parser<&'a str> for Cookie<E> {
+  type Output = Vec<(&'a str, &'a str)>;
+  type Error = E;
+
+  fn process<OM: OutputMode>(&mut self, i: &'a str) -> PResult<OM, &'a str, Self::Output, E> {
+    match self.strictness {
+      Strictness::Strict => separated_list1(tag("; "), cookie_pair()).process::<OM>(i),
+      Strictness::Lenient => {
+        let (rest, line) =
+          take_till(is_line_end).process::<OutputM<Emit, OM::Error, OM::Incomplete>>(i)?;
+        Ok((
+          rest,
+          OM::Output::bind(|| line.split(';').filter_map(lenient_pair).collect()),
+        ))
+      }
+    }
+  }
+}
+
+/// Recognizes the value of a `Set-Cookie` header.
+///
+/// In strict mode, the attributes must be separated by `"; "`. The known attributes
+/// (`Expires`, `Max-Age`, `Domain`, `Path`, `Secure` and `HttpOnly`) must be valid, and
+/// the parser stops before the first invalid attribute. The attribute names are case
+/// insensitive.
+///
+/// In lenient mode, the spaces around the name, the value and the attributes are
+/// ignored, as well as the invalid attributes. It returns an error with
+/// [ErrorKind::Verify] if the cookie has no `=` or an empty name.
+///
+/// ```rust
+/// # use nom::{error::Error, Parser};
+/// use nom::formats::cookie::{set_cookie, Attribute};
+/// use nom::Strictness;
+///
+/// let input = "id=a3fWa; Max-Age=2592000; Domain=.example.com; SameSite=Lax";
+///
+/// let (rest, c) = set_cookie::<Error<_>>(Strictness::Strict).parse_complete(input).unwrap();
+/// assert_eq!(rest, "; Domain=.example.com; SameSite=Lax");
+/// assert_eq!(c.attributes, [Attribute::MaxAge(2592000)]);
+///
+/// let (rest, c) = set_cookie::<Error<_>>(Strictness::Lenient).parse_complete(input).unwrap();
+/// assert_eq!(rest, "");
+/// assert_eq!(
+///   c.attributes,
+///   [Attribute::MaxAge(2592000), Attribute::Domain("example.com"), Attribute::Extension("SameSite=Lax")]
+/// );
+/// ```
+pub fn set_cookie<'a, E: ParseError<&'a str>>(
+  strictness: Strictness,
+) -> impl Parser<&'a str, Output = SetCookie<'a>, Error = E> {
+  SetCookieParser {
+    strictness,
+    e: PhantomData,
+  }
+}
+
+/// Parser implementation for [set_cookie]
+pub struct SetCookieParser<E> {
+  strictness: Strictness,
+  e: PhantomData<E>,
+}
+
+impl<'a, E: ParseError<&'a str>> Parser<&'a str> for SetCookieParser<E> {
+  type Output = SetCookie<'a>;
+  type Error = E;
+
+  fn process<OM: OutputMode>(&mut self, i: &'a str) -> PResult<OM, &'a str, Self::Output, E> {
+    match self.strictness {
+      Strictness::Strict => (
+        cookie_pair(),
+        many0(preceded(
+          tag("; "),
+          map_opt(take_while1(is_av_char), strict_attribute),
+        )),
+      )
+        .map(|((name, value), attributes)| SetCookie {
+          name,
+          value,
+          attributes,
+        })
+        .process::<OM>(i),
+      Strictness::Lenient => {
+        let (rest, line) =
+          take_till(is_line_end).process::<OutputM<Emit, OM::Error, OM::Incomplete>>(i)?;
+
+        let mut parts = line.split(';');
+        let (name, value) = match parts.next().and_then(lenient_pair) {
+          Some((name, value)) if !name.is_empty() => (name, value),
+          _ => {
+            return Err(Err::Error(OM::Error::bind(|| {
+              E::from_error_kind(i, ErrorKind::Verify)
+            })))
+          }
+        };
+
+        Ok((
+          rest,
+          OM::Output::bind(|| SetCookie {
+            name,
+            value,
+            attributes: parts.filter_map(lenient_attribute).collect(),
+          }),
+        ))
+      }
+    }
+  }
+}
+
+fn is_line_end(c: char) -> bool {
+  c == '\r' || c == '\n'
+}
+
+fn is_attribute_end(c: char) -> bool {
+  c == ';' || is_line_end(c)
+}
+
+fn is_token(c: char) -> bool {
+  c.is_ascii_graphic() && !"()<>@,;:\\\"/[]?={}".contains(c)
+}
+
+fn is_cookie_octet(c: char) -> bool {
+  c.is_ascii_graphic() && !"\",;\\".contains(c)
+}
+
+fn is_av_char(c: char) -> bool {
+  (c.is_ascii_graphic() || c == ' ') && c != ';'
+}
+
+fn trim(s: &str) -> &str {
+  s.trim_matches(|c| c == ' ' || c == '\t')
+}
+
+fn lenient_pair(pair: &str) -> Option<(&str, &str)> {
+  let index = pair.find('=')?;
+  Some((trim(&pair[..index]), trim(&pair[index + 1..])))
+}
+
+fn two_digits<'a, E: ParseError<&'a str>>() -> impl Parser<&'a str, Output = u8, Error = E> {
+  take_while_m_n(2, 2, |c: char| c.is_ascii_digit()).map(|s: &str| s.parse().unwrap_or(0))
+}
+
+fn rfc1123_date<'a, E: ParseError<&'a str>>() -> impl Parser<&'a str, Output = CookieDate, Error = E>
+{
+  let weekday = alt((
+    tag("Mon"),
+    tag("Tue"),
+    tag("Wed"),
+    tag("Thu"),
+    tag("Fri"),
+    tag("Sat"),
+    tag("Sun"),
+  ));
+  let month = map_opt(take(3usize), |m: &str| {
+    MONTHS
+      .iter()
+      .position(|name| *name == m)
+      .map(|n| n as u8 + 1)
+  });
+  let year =
+    take_while_m_n(4, 4, |c: char| c.is_ascii_digit()).map(|s: &str| s.parse().unwrap_or(0));
+
+  let date = (
+    weekday,
+    tag(", "),
+    two_digits(),
+    char(' '),
+    month,
+    char(' '),
+    year,
+    char(' '),
+    two_digits(),
+    char(':'),
+    two_digits(),
+    char(':'),
+    two_digits(),
+    tag(" GMT"),
+  )
+    .map(
+      |(_, _, day, _, month, _, year, _, hour, _, minute, _, second, _)| CookieDate {
+        year,
+        month,
+        day,
+        hour,
+        minute,
+        second,
+      },
+    );
+
+  verify(date, is_valid_date)
+}
+
+fn is_valid_date(date: &CookieDate) -> bool {
+  (1..=31).contains(&date.day)
+    && date.year >= 1601
+    && date.hour <= 23
+    && date.minute <= 59
+    && date.second <= 59
+}
+
+/// Cookie date parsing algorithm from RFC 6265 section 5.1.1
+fn lenient_date(input: &str) -> Option<CookieDate> {
+  fn is_delimiter(c: char) -> bool {
+    matches!(c, '\t' | ' '..='/' | ';'..='@' | '['..='`' | '{'..='~')
+  }
+
+  // splits a token in a prefix of `min` to `max` digits, followed by nothing or a
+  // non digit character
+  fn digits(token: &str, min: usize, max: usize) -> Option<(u16, &str)> {
+    let len = token
+      .find(|c: char| !c.is_ascii_digit())
+      .unwrap_or(token.len());
+    if len < min || len > max {
+      return None;
+    }
+    Some((token[..len].parse().ok()?, &token[len..]))
+  }
+
+  fn time(token: &str) -> Option<(u8, u8, u8)> {
+    let (hour, rest) = digits(token, 1, 2)?;
+    let (minute, rest) = digits(rest.strip_prefix(':')?, 1, 2)?;
+    let (second, _) = digits(rest.strip_prefix(':')?, 1, 2)?;
+    Some((hour as u8, minute as u8, second as u8))
+  }
+
+  let (mut found_time, mut day, mut month, mut year) = (None, None, None, None);
+
+  for token in input.split(is_delimiter).filter(|t| !t.is_empty()) {
+    if found_time.is_none() {
+      if let Some(t) = time(token) {
+        found_time = Some(t);
+        continue;
+      }
+    }
+    if day.is_none() {
+      if let Some((d, _)) = digits(token, 1, 2) {
+        day = Some(d as u8);
+        continue;
+      }
+    }
+    if month.is_none() {
+      let prefix = token.get(..3).unwrap_or("");
+      if let Some(m) = MONTHS.iter().position(|m| m.eq_ignore_ascii_case(prefix)) {
+        month = Some(m as u8 + 1);
+        continue;
+      }
+    }
+    if year.is_none() {
+      if let Some((y, _)) = digits(token, 2, 4) {
+        year = Some(y);
+        continue;
+      }
+    }
+  }
+
+  let (hour, minute, second) = found_time?;
+  let year = match year? {
+    y @ 70..=99 => y + 1900,
+    y @ 0..=69 => y + 2000,
+    y => y,
+  };
+  let date = CookieDate {
+    year,
+    month: month?,
+    day: day?,
+    hour,
+    minute,
+    second,
+  };
+  is_valid_date(&date).then_some(date)
+}
+
+fn strict_attribute(av: &str) -> Option<Attribute<'_>> {
+  let (name, value) = match av.find('=') {
+    Some(index) => (&av[..index], Some(&av[index + 1..])),
+    None => (av, None),
+  };
+
+  let attribute = match (name.to_ascii_lowercase().as_str(), value) {
+    ("expires", Some(value)) => Attribute::Expires(
+      all_consuming(rfc1123_date::<()>())
+        .parse_complete(value)
+        .ok()?
+        .1,
+    ),
+    ("max-age", Some(value)) => {
+      if !value.starts_with(|c: char| ('1'..='9').contains(&c))
+        || !value.bytes().all(|b| b.is_ascii_digit())
+      {
+        return None;
+      }
+      Attribute::MaxAge(value.parse().unwrap_or(i64::MAX))
+    }
+    ("domain", Some(value)) => {
+      let valid_label = |label: &str| {
+        !label.is_empty()
+          && label
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-')
+          && !label.starts_with('-')
+          && !label.ends_with('-')
+      };
+      if !value.split('.').all(valid_label) {
+        return None;
+      }
+      Attribute::Domain(value)
+    }
+    ("path", Some(value)) => Attribute::Path(value),
+    ("secure", None) => Attribute::Secure,
+    ("httponly", None) => Attribute::HttpOnly,
+    ("expires" | "max-age" | "domain" | "path" | "secure" | "httponly", _) => return None,
+    _ => Attribute::Extension(av),
+  };
+  Some(attribute)
+}
+
+/// Attribute parsing from RFC 6265 section 5.2
+fn lenient_attribute(av: &str) -> Option<Attribute<'_>> {
+  let av = trim(av);
+  if av.is_empty() {
+    return None;
+  }
+  let (name, value) = match av.find('=') {
+    Some(index) => (trim(&av[..index]), trim(&av[index + 1..])),
+    None => (av, ""),
+  };
+
+  let attribute = if name.eq_ignore_ascii_case("expires") {
+    Attribute::Expires(lenient_date(value)?)
+  } else if name.eq_ignore_ascii_case("max-age") {
+    let digits = value.strip_prefix('-').unwrap_or(value);
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+      return None;
+    }
+    let max_age = digits.parse::<i64>().unwrap_or(i64::MAX);
+    Attribute::MaxAge(if digits.len() < value.len() {
+      -max_age
+    } else {
+      max_age
+    })
+  } else if name.eq_ignore_ascii_case("domain") {
+    let domain = value.strip_prefix('.').unwrap_or(value);
+    if domain.is_empty() {
+      return None;
+    }
+    Attribute::Domain(domain)
+  } else if name.eq_ignore_ascii_case("path") {
+    Attribute::Path(value)
+  } else if name.eq_ignore_ascii_case("secure") {
+    Attribute::Secure
+  } else if name.eq_ignore_ascii_case("httponly") {
+    Attribute::HttpOnly
+  } else {
+    Attribute::Extension(av)
+  };
+  Some(attribute)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::error::Error;
+  use crate::Needed;
+
+  fn date(year: u16, month: u8, day: u8, hour: u8, minute: u8, second: u8) -> CookieDate {
+    CookieDate {
+      year,
+      month,
+      day,
+      hour,
+      minute,
+      second,
+    }
+  }
+
+  #[test]
+  fn dates() {
+    let mut strict = cookie_date::<Error<_>>(Strictness::Strict);
+    assert_eq!(
+      strict.parse_complete("Thu, 01 Jan 1970 00:00:00 GMT; Path=/"),
+      Ok(("; Path=/", date(1970, 1, 1, 0, 0, 0)))
+    );
+    assert!(strict
+      .parse_complete("Thu, 32 Jan 1970 00:00:00 GMT")
+      .is_err());
+    assert!(strict
+      .parse_complete("Thu, 01 jan 1970 00:00:00 GMT")
+      .is_err());
+    assert_eq!(
+      strict.parse("Thu, 01 Jan 1970 00:00"),
+      Err(Err::Incomplete(Needed::new(1)))
+    );
+
+    assert_eq!(
+      lenient_date("1 jan 70 1:2:3"),
+      Some(date(1970, 1, 1, 1, 2, 3))
+    );
+    assert_eq!(lenient_date("19 JANUARY 2038 03:14"), None);
+    assert_eq!(
+      lenient_date("2038-JANUARY-19 03:14:07 UTC"),
+      Some(date(2038, 1, 19, 3, 14, 7))
+    );
+    assert_eq!(lenient_date("Tue, 10 Nov 1600 00:00:00"), None);
+    assert_eq!(lenient_date("Tue, 10 Nov 2009 24:00:00"), None);
+    assert_eq!(lenient_date("Tue, 10 2009 00:00:00"), None);
+  }
+
+  #[test]
+  fn cookie_header() {
+    let mut strict = cookie::<Error<_>>(Strictness::Strict);
+    assert_eq!(
+      strict.parse_complete("a=\"x\"; b=; c=3\r\n"),
+      Ok(("\r\n", vec![("a", "\"x\""), ("b", ""), ("c", "3")]))
+    );
+    assert_eq!(
+      strict.parse_complete("=1"),
+      Err(Err::Error(Error::new("=1", ErrorKind::TakeWhile1)))
+    );
+    assert_eq!(strict.parse_complete("a=x y"), Ok((" y", vec![("a", "x")])));
+
+    let mut lenient = cookie::<Error<_>>(Strictness::Lenient);
+    assert_eq!(
+      lenient.parse_complete("  a = x y ;; b=1=2\r\nnext"),
+      Ok(("\r\nnext", vec![("a", "x y"), ("b", "1=2")]))
+    );
+    assert_eq!(lenient.parse("a=1"), Err(Err::Incomplete(Needed::new(1))));
+  }
+
+  #[test]
+  fn set_cookie_header() {
+    let input =
+      "SID=31d4d96e407aad42; Path=/; Secure; HttpOnly; Expires=Wed, 09 Jun 2021 10:18:14 GMT";
+    let expected = SetCookie {
+      name: "SID",
+      value: "31d4d96e407aad42",
+      attributes: vec![
+        Attribute::Path("/"),
+        Attribute::Secure,
+        Attribute::HttpOnly,
+        Attribute::Expires(date(2021, 6, 9, 10, 18, 14)),
+      ],
+    };
+    assert_eq!(
+      set_cookie::<Error<_>>(Strictness::Strict).parse_complete(input),
+      Ok(("", expected.clone()))
+    );
+    assert_eq!(
+      set_cookie::<Error<_>>(Strictness::Lenient).parse_complete(input),
+      Ok(("", expected))
+    );
+
+    for invalid in [
+      "Max-Age=0",
+      "Max-Age=-1",
+      "Domain=.example.com",
+      "Domain=-a.com",
+      "Secure=1",
+      "Expires=Wed, 09-Jun-2021 10:18:14 GMT",
+    ] {
+      let input = format!("a=b; {}", invalid);
+      let mut strict = set_cookie::<Error<_>>(Strictness::Strict);
+      let (rest, c) = strict.parse_complete(&input).unwrap();
+      assert_eq!(rest, &input[3..]);
+      assert!(c.attributes.is_empty());
+    }
+    let mut strict = set_cookie::<Error<_>>(Strictness::Strict);
+    assert_eq!(
+      strict
+        .parse_complete("a=b; domain=sub.example.com; SAMESITE=None")
+        .map(|(_, c)| c.attributes),
+      Ok(vec![
+        Attribute::Domain("sub.example.com"),
+        Attribute::Extension("SAMESITE=None")
+      ])
+    );
+
+    let mut lenient = set_cookie::<Error<_>>(Strictness::Lenient);
+    assert_eq!(
+      lenient
+        .parse_complete(" a = b ;max-age=-5; max-age=1x; expires=never; domain=.; path; secure=yes")
+        .map(|(_, c)| (c.name, c.value, c.attributes)),
+      Ok((
+        "a",
+        "b",
+        vec![
+          Attribute::MaxAge(-5),
+          Attribute::Path(""),
+          Attribute::Secure
+        ]
+      ))
+    );
+    assert_eq!(
+      lenient.parse_complete("novalue; Secure"),
+      Err(Err::Error(Error::new("novalue; Secure", ErrorKind::Verify)))
+    );
+    assert_eq!(
+      lenient.parse_complete("=b"),
+      Err(Err::Error(Error::new("=b", ErrorKind::Verify)))
+    );
+  }
+}
@@ -0,0 +1,7 @@
+//! Parsers for common data formats
+//!
+//! Those formats are small, but their details are easy to get wrong, so they are
+//! written once here, as parsers that can be composed with the rest of a grammar.
+
+pub mod cookie;
+pub mod urlencoded;
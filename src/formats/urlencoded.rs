@@ -0,0 +1,284 @@
+//! Parser for `application/x-www-form-urlencoded` data
+//!
+//! This is the format of HTML form submissions and URL query strings: `name=value`
+//! pairs separated by `&`, where spaces are encoded as `+` and other bytes can be
+//! written as `%` followed by 2 hexadecimal digits. The parsing follows the
+//! [URL standard](https://url.spec.whatwg.org/#urlencoded-parsing):
+//! * empty pairs, as in `a=1&&b=2`, are ignored
+//! * a pair without `=` has an empty value, and the value extends to the next `&`,
+//!   so it can contain `=`
+//! * invalid percent escapes, like `%zz` or a lone `%`, are kept as they are
+//! * decoded bytes that are not valid UTF-8 are replaced with U+FFFD
+//!
+//! The form stops at the first ASCII whitespace or `#` character, or at the end of the
+//! input, so it can be used inside a larger grammar, like a HTTP request line.
+
+use core::marker::PhantomData;
+
+use crate::bytes::take_till;
+use crate::error::{ErrorKind, FromExternalError, ParseError};
+use crate::internal::{Emit, Err, Mode, OutputM, OutputMode, PResult, Parser};
+use crate::lib::std::borrow::Cow;
+use crate::lib::std::collections::BTreeMap;
+use crate::lib::std::string::String;
+use crate::lib::std::vec::Vec;
+use crate::multi::DuplicateKey;
+
+/// What to do when a form contains the same name more than once, see [urlencoded]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Duplicates {
+  /// Keeps all the pairs
+  Keep,
+  /// Keeps the first value, and ignores the following pairs with the same name
+  First,
+  /// Keeps the last value, at the position of the first pair with that name
+  Last,
+  /// Returns an error with [ErrorKind::Unique] at the position of the second pair,
+  /// built with [FromExternalError] from a [DuplicateKey]
+  Reject,
+}
+
+/// Recognizes `application/x-www-form-urlencoded` data, returning the decoded
+/// `(name, value)` pairs in order.
+///
+/// The names and values borrow the input when they contain no `+` or percent escape.
+/// `duplicates` selects how repeated names are handled.
+///
+/// *Streaming version*: returns `Err::Incomplete` if the input ends before a character
+/// ending the form (ASCII whitespace or `#`).
+///
+/// *Complete version*: the form extends to the end of the input if there is no such
+/// character.
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+/// use nom::formats::urlencoded::{urlencoded, Duplicates};
+///
+/// let mut parser = urlencoded::<Error<_>>(Duplicates::Keep);
+/// let (rest, pairs) = parser.parse_complete("name=J%C3%BCrgen+M&tag&tag=a=b HTTP/1.1").unwrap();
+/// assert_eq!(rest, " HTTP/1.1");
+/// assert_eq!(pairs, [("name".into(), "Jürgen M".into()), ("tag".into(), "".into()), ("tag".into(), "a=b".into())]);
+///
+/// let mut parser = urlencoded::<Error<_>>(Duplicates::Last);
+/// assert_eq!(parser.parse_complete("a=1&b=2&a=3").unwrap().1, [("a".into(), "3".into()), ("b".into(), "2".into())]);
+///
+/// let mut parser = urlencoded::<Error<_>>(Duplicates::Reject);
+/// assert_eq!(parser.parse_complete("a=1&b=2&a=3"), Err(Err::Error(Error::new("a=3", ErrorKind::Unique))));
+/// ```
+pub fn urlencoded<'a, E>(
+  duplicates: Duplicates,
+) -> impl Parser<&'a str, Output = Vec<(Cow<'a, str>, Cow<'a, str>)>, Error = E>
+where
+  E: ParseError<&'a str> + FromExternalError<&'a str, DuplicateKey<&'a str, Cow<'a, str>>>,
+{
+  UrlEncoded {
+    duplicates,
+    e: PhantomData,
+  }
+}
+
+/// Parser implementation for [urlencoded]
+pub struct UrlEncoded<E> {
+  duplicates: Duplicates,
+  e: PhantomData<E>,
+}
+
+impl<'a, E> Parser<&'a str> for UrlEncoded<E>
+where
+  E: ParseError<&'a str> + FromExternalError<&'a str, DuplicateKey<&'a str, Cow<'a, str>>>,
+{
+  type Output = Vec<(Cow<'a, str>, Cow<'a, str>)>;
+  type Error = E;
+
+  fn process<OM: OutputMode>(
+    &mut self,
+    input: &'a str,
+  ) -> PResult<OM, &'a str, Self::Output, Self::Error> {
+    let mut form_end = take_till(|c: char| c.is_ascii_whitespace() || c == '#');
+    let (rest, form) = form_end.process::<OutputM<Emit, OM::Error, OM::Incomplete>>(input)?;
+
+    let mut pairs: Vec<(Cow<'a, str>, Cow<'a, str>)> = Vec::new();
+    // decoded name -> (index in `pairs`, position of the pair)
+    let mut names: BTreeMap<Cow<'a, str>, (usize, &'a str)> = BTreeMap::new();
+
+    let mut position = 0;
+    for segment in form.split('&') {
+      let start = position;
+      position += segment.len() + 1;
+      if segment.is_empty() {
+        continue;
+      }
+
+      let (name, value) = match segment.find('=') {
+        Some(index) => (&segment[..index], &segment[index + 1..]),
+        None => (segment, ""),
+      };
+      let name = decode_component(name);
+      let value = decode_component(value);
+
+      if self.duplicates == Duplicates::Keep {
+        pairs.push((name, value));
+        continue;
+      }
+
+      match names.get(&name) {
+        None => {
+          names.insert(name.clone(), (pairs.len(), &form[start..]));
+          pairs.push((name, value));
+        }
+        Some(_) if self.duplicates == Duplicates::First => {}
+        Some((index, _)) if self.duplicates == Duplicates::Last => pairs[*index].1 = value,
+        Some((_, first)) => {
+          let second = &form[start..];
+          let first = *first;
+          return Err(Err::Error(OM::Error::bind(|| {
+            E::from_external_error(
+              second,
+              ErrorKind::Unique,
+              DuplicateKey {
+                key: name,
+                first,
+                second,
+              },
+            )
+          })));
+        }
+      }
+    }
+
+    Ok((rest, OM::Output::bind(|| pairs)))
+  }
+}
+
+/// Decodes a name or a value from `application/x-www-form-urlencoded` data
+///
+/// `+` is replaced with a space, and percent escapes with the byte they encode. Invalid
+/// escapes are kept as they are, and decoded bytes that are not valid UTF-8 are replaced
+/// with U+FFFD. The result borrows the input if there is nothing to decode.
+///
+/// ```rust
+/// use nom::formats::urlencoded::decode_component;
+///
+/// assert_eq!(decode_component("a+b%2B%C3%A9"), "a b+é");
+/// assert_eq!(decode_component("100%"), "100%");
+/// assert_eq!(decode_component("%zz%ff"), "%zz\u{fffd}");
+/// ```
+pub fn decode_component(input: &str) -> Cow<'_, str> {
+  if !input.contains(&['+', '%'][..]) {
+    return Cow::Borrowed(input);
+  }
+
+  let bytes = input.as_bytes();
+  let mut decoded = Vec::with_capacity(bytes.len());
+  let mut i = 0;
+  while i < bytes.len() {
+    match bytes[i] {
+      b'+' => decoded.push(b' '),
+      b'%' => match (
+        bytes.get(i + 1).and_then(hex),
+        bytes.get(i + 2).and_then(hex),
+      ) {
+        (Some(high), Some(low)) => {
+          decoded.push(high << 4 | low);
+          i += 2;
+        }
+        _ => decoded.push(b'%'),
+      },
+      b => decoded.push(b),
+    }
+    i += 1;
+  }
+
+  match String::from_utf8(decoded) {
+    Ok(s) => Cow::Owned(s),
+    Err(e) => Cow::Owned(String::from_utf8_lossy(e.as_bytes()).into_owned()),
+  }
+}
+
+fn hex(b: &u8) -> Option<u8> {
+  (*b as char).to_digit(16).map(|d| d as u8)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::error::Error;
+  use crate::Needed;
+
+  type Pairs<'a> = Vec<(Cow<'a, str>, Cow<'a, str>)>;
+
+  fn pairs<'a>(p: &[(&'a str, &'a str)]) -> Pairs<'a> {
+    p.iter()
+      .map(|(n, v)| (Cow::Borrowed(*n), Cow::Borrowed(*v)))
+      .collect()
+  }
+
+  #[test]
+  fn form() {
+    let mut parser = urlencoded::<Error<_>>(Duplicates::Keep);
+    assert_eq!(parser.parse_complete(""), Ok(("", Vec::new())));
+    assert_eq!(
+      parser.parse_complete("&a=1&&=2&b=#frag"),
+      Ok(("#frag", pairs(&[("a", "1"), ("", "2"), ("b", "")])))
+    );
+    assert!(matches!(
+      parser.parse_complete("a=1").unwrap().1[0],
+      (Cow::Borrowed("a"), Cow::Borrowed("1"))
+    ));
+    assert_eq!(
+      parser.parse_complete("a%20b=c%2"),
+      Ok(("", pairs(&[("a b", "c%2")])))
+    );
+
+    assert_eq!(parser.parse("a=1&b"), Err(Err::Incomplete(Needed::new(1))));
+    assert_eq!(
+      parser.parse("a=1&b\r\n"),
+      Ok(("\r\n", pairs(&[("a", "1"), ("b", "")])))
+    );
+  }
+
+  #[test]
+  fn duplicates() {
+    let input = "a=1&b=2&a=3&%61=4";
+
+    let mut parser = urlencoded::<Error<_>>(Duplicates::First);
+    assert_eq!(
+      parser.parse_complete(input),
+      Ok(("", pairs(&[("a", "1"), ("b", "2")])))
+    );
+
+    let mut parser = urlencoded::<Error<_>>(Duplicates::Last);
+    assert_eq!(
+      parser.parse_complete(input),
+      Ok(("", pairs(&[("a", "4"), ("b", "2")])))
+    );
+
+    // the error reports both positions
+    struct Positions<'a>(Option<(&'a str, &'a str)>);
+    impl<'a> ParseError<&'a str> for Positions<'a> {
+      fn from_error_kind(_: &'a str, _: ErrorKind) -> Self {
+        Positions(None)
+      }
+      fn append(_: &'a str, _: ErrorKind, other: Self) -> Self {
+        other
+      }
+    }
+    impl<'a> FromExternalError<&'a str, DuplicateKey<&'a str, Cow<'a, str>>> for Positions<'a> {
+      fn from_external_error(
+        _: &'a str,
+        _: ErrorKind,
+        e: DuplicateKey<&'a str, Cow<'a, str>>,
+      ) -> Self {
+        Positions(Some((e.first, e.second)))
+      }
+    }
+
+    let mut parser = urlencoded::<Positions<'_>>(Duplicates::Reject);
+    match parser.parse_complete("b=2&%61=1&a=3") {
+      Err(Err::Error(Positions(Some(positions)))) => {
+        assert_eq!(positions, ("%61=1&a=3", "a=3"))
+      }
+      _ => panic!("expected a duplicate key error"),
+    }
+  }
+}
@@ -0,0 +1,482 @@
+//! Serializers mirroring the parsers
+//!
+//! A protocol implementation usually needs to emit the messages it parses. A
+//! [Generator] is the dual of a [Parser](crate::Parser): instead of consuming an input
+//! and producing a value, it consumes a value and writes its encoding to a [Sink]. The
+//! combinators of this module have the names and arguments of the parsers they invert,
+//! and tuples of generators write tuples of values, so a message can be described
+//! twice with the same shape and round trip through both descriptions.
+//!
+//! ```rust
+//! use nom::bytes::tag;
+//! use nom::error::Error;
+//! use nom::generate;
+//! use nom::multi::{length_data, separated_list0};
+//! use nom::number::{be_u16, u8};
+//! use nom::Parser;
+//!
+//! // a header followed by a list of names
+//! let mut generator = (
+//!   generate::tag(b"NAMES"),
+//!   generate::be_u16(),
+//!   generate::separated_list(generate::tag(b","), generate::length_data(generate::u8())),
+//! );
+//! let names: Vec<&[u8]> = vec![b"alice", b"bob"];
+//! let message = generate::to_vec(&mut generator, &((), 7, names.clone())).unwrap();
+//! assert_eq!(message, b"NAMES\x00\x07\x05alice,\x03bob");
+//!
+//! let mut parser = (
+//!   tag::<_, _, Error<_>>(&b"NAMES"[..]),
+//!   be_u16(),
+//!   separated_list0(tag(&b","[..]), length_data(u8())),
+//! );
+//! let (_, (_, id, parsed)) = parser.parse_complete(&message[..]).unwrap();
+//! assert_eq!((id, parsed), (7, names));
+//! ```
+
+use core::marker::PhantomData;
+
+use crate::internal::Needed;
+use crate::lib::std::fmt;
+#[cfg(feature = "alloc")]
+use crate::lib::std::vec::Vec;
+
+/// Error returned by a [Generator]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GenError {
+  /// The sink had no room for the output, and needed at least this many more bytes
+  BufferFull(Needed),
+  /// A length did not fit in the number type of its prefix
+  TooLong {
+    /// Length that was written
+    length: usize,
+  },
+  /// The value cannot be encoded
+  Invalid(&'static str),
+}
+
+impl fmt::Display for GenError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      GenError::BufferFull(Needed::Size(n)) => {
+        write!(f, "output buffer full, {} more bytes needed", n)
+      }
+      GenError::BufferFull(Needed::Unknown) => write!(f, "output buffer full"),
+      GenError::TooLong { length } => {
+        write!(f, "length {} does not fit in its prefix", length)
+      }
+      GenError::Invalid(message) => write!(f, "invalid value: {}", message),
+    }
+  }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for GenError {}
+
+/// Destination of the bytes written by a [Generator]
+pub trait Sink {
+  /// Appends bytes at the end of the output
+  fn write(&mut self, bytes: &[u8]) -> Result<(), GenError>;
+}
+
+#[cfg(feature = "alloc")]
+impl Sink for Vec<u8> {
+  fn write(&mut self, bytes: &[u8]) -> Result<(), GenError> {
+    self.extend_from_slice(bytes);
+    Ok(())
+  }
+}
+
+/// Fills the slice from its start, leaving the unwritten part in `self`, like
+/// `std::io::Write` for `&mut [u8]`
+impl Sink for &mut [u8] {
+  fn write(&mut self, bytes: &[u8]) -> Result<(), GenError> {
+    if bytes.len() > self.len() {
+      return Err(GenError::BufferFull(Needed::new(bytes.len() - self.len())));
+    }
+    let (head, tail) = core::mem::take(self).split_at_mut(bytes.len());
+    head.copy_from_slice(bytes);
+    *self = tail;
+    Ok(())
+  }
+}
+
+impl<S: Sink + ?Sized> Sink for &mut S {
+  fn write(&mut self, bytes: &[u8]) -> Result<(), GenError> {
+    (**self).write(bytes)
+  }
+}
+
+/// Serializer of values of type `T`, the dual of a [Parser](crate::Parser)
+///
+/// It is implemented by the combinators of the [generate](self) module, by tuples of
+/// generators, and by closures taking the value and a `&mut dyn Sink`.
+pub trait Generator<T: ?Sized> {
+  /// Writes the encoding of `value` to the sink
+  fn generate<S: Sink>(&mut self, value: &T, sink: &mut S) -> Result<(), GenError>;
+
+  /// Writes values of type `U`, after converting them with `f`
+  ///
+  /// This inverts [Parser::map](crate::Parser::map): for a parser mapping its output
+  /// with a function, the generator maps its input with the reverse function.
+  fn map<U: ?Sized, F>(self, f: F) -> Map<Self, F, T>
+  where
+    F: FnMut(&U) -> T,
+    T: Sized,
+    Self: Sized,
+  {
+    Map {
+      g: self,
+      f,
+      t: PhantomData,
+    }
+  }
+}
+
+impl<T: ?Sized, F> Generator<T> for F
+where
+  F: FnMut(&T, &mut dyn Sink) -> Result<(), GenError>,
+{
+  fn generate<S: Sink>(&mut self, value: &T, sink: &mut S) -> Result<(), GenError> {
+    self(value, sink)
+  }
+}
+
+/// Writes a value to a new vector
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "alloc")))]
+pub fn to_vec<T: ?Sized, G: Generator<T>>(
+  generator: &mut G,
+  value: &T,
+) -> Result<Vec<u8>, GenError> {
+  let mut out = Vec::new();
+  generator.generate(value, &mut out)?;
+  Ok(out)
+}
+
+/// Writes a value at the start of a buffer, returning the number of bytes written
+///
+/// ```rust
+/// use nom::generate::{self, GenError};
+/// use nom::Needed;
+///
+/// let mut buffer = [0; 4];
+/// assert_eq!(generate::to_slice(&mut (generate::u8(), generate::be_u16()), &(1, 2), &mut buffer), Ok(3));
+/// assert_eq!(&buffer[..3], b"\x01\x00\x02");
+///
+/// assert_eq!(
+///   generate::to_slice(&mut generate::be_u64(), &1, &mut buffer),
+///   Err(GenError::BufferFull(Needed::new(4)))
+/// );
+/// ```
+pub fn to_slice<T: ?Sized, G: Generator<T>>(
+  generator: &mut G,
+  value: &T,
+  buffer: &mut [u8],
+) -> Result<usize, GenError> {
+  let len = buffer.len();
+  let mut rest = &mut buffer[..];
+  generator.generate(value, &mut rest)?;
+  Ok(len - rest.len())
+}
+
+/// Implementation of [Generator::map]
+pub struct Map<G, F, T> {
+  g: G,
+  f: F,
+  t: PhantomData<fn() -> T>,
+}
+
+impl<U: ?Sized, T, G, F> Generator<U> for Map<G, F, T>
+where
+  G: Generator<T>,
+  F: FnMut(&U) -> T,
+{
+  fn generate<S: Sink>(&mut self, value: &U, sink: &mut S) -> Result<(), GenError> {
+    let value = (self.f)(value);
+    self.g.generate(&value, sink)
+  }
+}
+
+/// Writes a fixed sequence of bytes, the dual of [bytes::tag](crate::bytes::tag)
+///
+/// The value is ignored, so it can be the output of the tag parser or `()`.
+pub fn tag<T: AsRef<[u8]>>(tag: T) -> Tag<T> {
+  Tag { tag }
+}
+
+/// Implementation of [tag()]
+pub struct Tag<T> {
+  tag: T,
+}
+
+impl<V: ?Sized, T: AsRef<[u8]>> Generator<V> for Tag<T> {
+  fn generate<S: Sink>(&mut self, _value: &V, sink: &mut S) -> Result<(), GenError> {
+    sink.write(self.tag.as_ref())
+  }
+}
+
+/// Writes the bytes of the value, the dual of [bytes::take](crate::bytes::take) and of
+/// the other parsers returning a part of the input
+pub fn bytes() -> Bytes {
+  Bytes
+}
+
+/// Implementation of [bytes()]
+pub struct Bytes;
+
+impl<V: AsRef<[u8]> + ?Sized> Generator<V> for Bytes {
+  fn generate<S: Sink>(&mut self, value: &V, sink: &mut S) -> Result<(), GenError> {
+    sink.write(value.as_ref())
+  }
+}
+
+macro_rules! number_generator {
+  ($($name:ident $endian:ident $ty:ident $to_bytes:ident $doc:literal;)+) => {
+    $(
+      #[doc = $doc]
+      pub fn $name() -> $endian<$ty> {
+        $endian(PhantomData)
+      }
+
+      impl Generator<$ty> for $endian<$ty> {
+        #[inline]
+        fn generate<S: Sink>(&mut self, value: &$ty, sink: &mut S) -> Result<(), GenError> {
+          sink.write(&value.$to_bytes())
+        }
+      }
+    )+
+  };
+}
+
+/// Implementation of the big endian number generators
+pub struct Be<T>(PhantomData<T>);
+
+/// Implementation of the little endian number generators
+pub struct Le<T>(PhantomData<T>);
+
+number_generator! {
+  u8 Be u8 to_be_bytes "Writes a `u8`, the dual of [number::u8](crate::number::u8)";
+  i8 Be i8 to_be_bytes "Writes an `i8`, the dual of [number::i8](crate::number::i8)";
+  be_u16 Be u16 to_be_bytes "Writes a big endian `u16`, the dual of [number::be_u16](crate::number::be_u16)";
+  be_u32 Be u32 to_be_bytes "Writes a big endian `u32`, the dual of [number::be_u32](crate::number::be_u32)";
+  be_u64 Be u64 to_be_bytes "Writes a big endian `u64`, the dual of [number::be_u64](crate::number::be_u64)";
+  be_i16 Be i16 to_be_bytes "Writes a big endian `i16`, the dual of [number::be_i16](crate::number::be_i16)";
+  be_i32 Be i32 to_be_bytes "Writes a big endian `i32`, the dual of [number::be_i32](crate::number::be_i32)";
+  be_i64 Be i64 to_be_bytes "Writes a big endian `i64`, the dual of [number::be_i64](crate::number::be_i64)";
+  be_f32 Be f32 to_be_bytes "Writes a big endian `f32`, the dual of [number::be_f32](crate::number::be_f32)";
+  be_f64 Be f64 to_be_bytes "Writes a big endian `f64`, the dual of [number::be_f64](crate::number::be_f64)";
+  le_u16 Le u16 to_le_bytes "Writes a little endian `u16`, the dual of [number::le_u16](crate::number::le_u16)";
+  le_u32 Le u32 to_le_bytes "Writes a little endian `u32`, the dual of [number::le_u32](crate::number::le_u32)";
+  le_u64 Le u64 to_le_bytes "Writes a little endian `u64`, the dual of [number::le_u64](crate::number::le_u64)";
+  le_i16 Le i16 to_le_bytes "Writes a little endian `i16`, the dual of [number::le_i16](crate::number::le_i16)";
+  le_i32 Le i32 to_le_bytes "Writes a little endian `i32`, the dual of [number::le_i32](crate::number::le_i32)";
+  le_i64 Le i64 to_le_bytes "Writes a little endian `i64`, the dual of [number::le_i64](crate::number::le_i64)";
+  le_f32 Le f32 to_le_bytes "Writes a little endian `f32`, the dual of [number::le_f32](crate::number::le_f32)";
+  le_f64 Le f64 to_le_bytes "Writes a little endian `f64`, the dual of [number::le_f64](crate::number::le_f64)";
+}
+
+/// Writes the length of the value with `length`, then its bytes, the dual of
+/// [multi::length_data](crate::multi::length_data)
+///
+/// It returns [GenError::TooLong] if the length does not fit in the number type
+/// written by `length`.
+///
+/// ```rust
+/// use nom::generate::{self, GenError};
+///
+/// assert_eq!(generate::to_vec(&mut generate::length_data(generate::be_u16()), b"abc"), Ok(b"\x00\x03abc".to_vec()));
+/// assert_eq!(
+///   generate::to_vec(&mut generate::length_data(generate::u8()), &[0; 300][..]),
+///   Err(GenError::TooLong { length: 300 })
+/// );
+/// ```
+pub fn length_data<L, G: Generator<L>>(length: G) -> LengthData<G, L> {
+  LengthData {
+    length,
+    l: PhantomData,
+  }
+}
+
+/// Implementation of [length_data]
+pub struct LengthData<G, L> {
+  length: G,
+  l: PhantomData<fn() -> L>,
+}
+
+impl<V, L, G> Generator<V> for LengthData<G, L>
+where
+  V: AsRef<[u8]> + ?Sized,
+  L: TryFrom<usize>,
+  G: Generator<L>,
+{
+  fn generate<S: Sink>(&mut self, value: &V, sink: &mut S) -> Result<(), GenError> {
+    let bytes = value.as_ref();
+    let length = L::try_from(bytes.len()).map_err(|_| GenError::TooLong {
+      length: bytes.len(),
+    })?;
+    self.length.generate(&length, sink)?;
+    sink.write(bytes)
+  }
+}
+
+/// Writes the elements of a list with `element`, with `separator` between them, the
+/// dual of [multi::separated_list0](crate::multi::separated_list0) and
+/// [multi::separated_list1](crate::multi::separated_list1)
+///
+/// The separator generator receives `()` as value, which [tag()] accepts.
+///
+/// ```rust
+/// use nom::generate;
+///
+/// let mut list = generate::separated_list(generate::tag(", "), generate::bytes());
+/// assert_eq!(generate::to_vec(&mut list, &["a", "b", "c"][..]), Ok(b"a, b, c".to_vec()));
+/// assert_eq!(generate::to_vec(&mut list, &vec!["a"]), Ok(b"a".to_vec()));
+/// ```
+pub fn separated_list<G, H>(separator: G, element: H) -> SeparatedList<G, H>
+where
+  G: Generator<()>,
+{
+  SeparatedList { separator, element }
+}
+
+/// Implementation of [separated_list]
+pub struct SeparatedList<G, H> {
+  separator: G,
+  element: H,
+}
+
+impl<G, H> SeparatedList<G, H> {
+  fn generate_slice<T, S: Sink>(&mut self, values: &[T], sink: &mut S) -> Result<(), GenError>
+  where
+    G: Generator<()>,
+    H: Generator<T>,
+  {
+    for (index, value) in values.iter().enumerate() {
+      if index > 0 {
+        self.separator.generate(&(), sink)?;
+      }
+      self.element.generate(value, sink)?;
+    }
+    Ok(())
+  }
+}
+
+impl<T, G, H> Generator<[T]> for SeparatedList<G, H>
+where
+  G: Generator<()>,
+  H: Generator<T>,
+{
+  fn generate<S: Sink>(&mut self, value: &[T], sink: &mut S) -> Result<(), GenError> {
+    self.generate_slice(value, sink)
+  }
+}
+
+#[cfg(feature = "alloc")]
+impl<T, G, H> Generator<Vec<T>> for SeparatedList<G, H>
+where
+  G: Generator<()>,
+  H: Generator<T>,
+{
+  fn generate<S: Sink>(&mut self, value: &Vec<T>, sink: &mut S) -> Result<(), GenError> {
+    self.generate_slice(value, sink)
+  }
+}
+
+macro_rules! impl_generator_for_tuple {
+  ($($generator:ident $value:ident),+) => (
+    #[allow(non_snake_case)]
+    impl<$($value),+, $($generator),+> Generator<($($value),+,)> for ($($generator),+,)
+    where
+      $($generator: Generator<$value>),+
+    {
+      #[inline]
+      fn generate<S: Sink>(&mut self, value: &($($value),+,), sink: &mut S) -> Result<(), GenError> {
+        let ($(ref mut $generator),+,) = *self;
+        let ($(ref $value),+,) = *value;
+
+        $($generator.generate($value, sink)?;)+
+        Ok(())
+      }
+    }
+  )
+}
+
+macro_rules! impl_generator_for_tuples {
+    ($generator1:ident $value1:ident, $($generator:ident $value:ident),+) => {
+        impl_generator_for_tuples!(__impl $generator1 $value1; $($generator $value),+);
+    };
+    (__impl $($generator:ident $value:ident),+; $generator1:ident $value1:ident $(,$generator2:ident $value2:ident)*) => {
+        impl_generator_for_tuple!($($generator $value),+);
+        impl_generator_for_tuples!(__impl $($generator $value),+, $generator1 $value1; $($generator2 $value2),*);
+    };
+    (__impl $($generator:ident $value:ident),+;) => {
+        impl_generator_for_tuple!($($generator $value),+);
+    }
+}
+
+impl_generator_for_tuples!(
+  G1 V1, G2 V2, G3 V3, G4 V4, G5 V5, G6 V6, G7 V7, G8 V8,
+  G9 V9, G10 V10, G11 V11, G12 V12, G13 V13, G14 V14, G15 V15, G16 V16,
+  G17 V17, G18 V18, G19 V19, G20 V20, G21 V21, G22 V22, G23 V23, G24 V24,
+  G25 V25, G26 V26, G27 V27, G28 V28, G29 V29, G30 V30, G31 V31, G32 V32,
+  G33 V33, G34 V34, G35 V35, G36 V36, G37 V37, G38 V38, G39 V39, G40 V40,
+  G41 V41, G42 V42, G43 V43, G44 V44, G45 V45, G46 V46, G47 V47, G48 V48,
+  G49 V49, G50 V50, G51 V51, G52 V52, G53 V53, G54 V54, G55 V55, G56 V56,
+  G57 V57, G58 V58, G59 V59, G60 V60, G61 V61, G62 V62, G63 V63, G64 V64
+);
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::error::Error;
+  use crate::number;
+  use crate::Parser;
+
+  #[derive(Debug, PartialEq)]
+  struct Point {
+    x: i32,
+    y: i32,
+  }
+
+  #[test]
+  fn numbers() {
+    let mut generator = (le_i32(), le_i32()).map(|p: &Point| (p.x, p.y));
+    let bytes = to_vec(&mut generator, &Point { x: -1, y: 2 }).unwrap();
+    assert_eq!(bytes, b"\xff\xff\xff\xff\x02\x00\x00\x00");
+    let mut point = (number::le_i32(), number::le_i32()).map(|(x, y)| Point { x, y });
+    assert_eq!(
+      point.parse(&bytes[..]),
+      Ok::<_, crate::Err<Error<_>>>((&b""[..], Point { x: -1, y: 2 }))
+    );
+
+    assert_eq!(
+      to_vec(&mut (u8(), le_u16(), be_f32()), &(1, 2, 1.0)),
+      Ok(b"\x01\x02\x00\x3f\x80\x00\x00".to_vec())
+    );
+  }
+
+  #[test]
+  fn closures() {
+    let mut text = |value: &&str, mut sink: &mut dyn Sink| {
+      if value.contains('\0') {
+        return Err(GenError::Invalid("NUL in text"));
+      }
+      (bytes(), tag(b"\0")).generate(&(value, ()), &mut sink)
+    };
+    assert_eq!(to_vec(&mut text, &"abc"), Ok(b"abc\0".to_vec()));
+    assert_eq!(
+      to_vec(&mut text, &"a\0c"),
+      Err(GenError::Invalid("NUL in text"))
+    );
+
+    let mut buffer = [0; 6];
+    let mut list = separated_list(tag(b" "), &mut text);
+    assert_eq!(to_slice(&mut list, &["ab", "c"][..], &mut buffer), Ok(6));
+    assert_eq!(&buffer, b"ab\0 c\0");
+    assert_eq!(
+      to_slice(&mut list, &["abc", "d"][..], &mut buffer),
+      Err(GenError::BufferFull(Needed::new(1)))
+    );
+  }
+}
@@ -0,0 +1,293 @@
+//! Experimental support for incremental parsing
+//!
+//! Editors and language servers parse the same file again after every small edit.
+//! This module lets a grammar remember the results of some of its rules, keyed by
+//! their position in the input, so that the next parse can skip the parts of the
+//! input that were not touched by the edit.
+//!
+//! Each memoized rule gets a [Memo], which outlives the input buffer. The rule is
+//! wrapped with [memoize], along with the complete input of the current parse,
+//! used to compute positions with [Offset]. After an edit, [Memo::edit] drops the
+//! results overlapping the modified range and moves the ones following it, then
+//! the grammar is applied on the new input.
+//!
+//! A result is reused if the edit does not touch the range the rule consumed, or
+//! the element right after it. Rules looking further ahead than that, or depending
+//! on what precedes them, must not be memoized. Only successful results are stored,
+//! so their outputs are cloned when reused, and cannot borrow the input.
+//!
+//! ```rust
+//! # use nom::{IResult, Parser};
+//! use nom::character::complete::{alpha1, multispace0};
+//! use nom::incremental::{memoize, Memo};
+//! use nom::multi::many0;
+//! use nom::sequence::terminated;
+//!
+//! fn words<'a>(memo: &'a Memo<String>, input: &'a str) -> IResult<&'a str, Vec<String>> {
+//!   many0(memoize(
+//!     memo,
+//!     input,
+//!     terminated(alpha1, multispace0).map(|word: &str| word.to_uppercase()),
+//!   ))
+//!   .parse(input)
+//! }
+//!
+//! let memo = Memo::new();
+//!
+//! let text = String::from("a bb ccc");
+//! assert_eq!(words(&memo, &text).unwrap().1, ["A", "BB", "CCC"]);
+//! assert_eq!(memo.len(), 3);
+//!
+//! // replace "bb" with "dddd": only the result for "ccc" is kept, since the rule
+//! // recognizing "a " stopped right before the edit
+//! let text = String::from("a dddd ccc");
+//! memo.edit(2..4, 4);
+//! assert_eq!(memo.len(), 1);
+//! assert_eq!(words(&memo, &text).unwrap().1, ["A", "DDDD", "CCC"]);
+//! ```
+
+use core::cell::RefCell;
+
+use crate::error::ParseError;
+use crate::internal::{Emit, Mode, OutputM, OutputMode, PResult, Parser};
+use crate::lib::std::collections::BTreeMap;
+use crate::lib::std::ops::Range;
+use crate::traits::{Input, Offset};
+
+/// Results of a rule from previous parses, indexed by position
+///
+/// Positions are offsets from the start of the complete input, as computed by [Offset].
+pub struct Memo<O> {
+  /// start position -> (consumed length, output)
+  entries: RefCell<BTreeMap<usize, (usize, O)>>,
+}
+
+impl<O> Memo<O> {
+  /// Creates an empty memo
+  pub fn new() -> Self {
+    Memo {
+      entries: RefCell::new(BTreeMap::new()),
+    }
+  }
+
+  /// Number of stored results
+  pub fn len(&self) -> usize {
+    self.entries.borrow().len()
+  }
+
+  /// Returns `true` if there are no stored results
+  pub fn is_empty(&self) -> bool {
+    self.entries.borrow().is_empty()
+  }
+
+  /// Removes all the stored results
+  pub fn clear(&self) {
+    self.entries.borrow_mut().clear()
+  }
+
+  /// Ranges of the input recognized by the stored results, in order
+  pub fn spans(&self) -> crate::lib::std::vec::Vec<Range<usize>> {
+    self
+      .entries
+      .borrow()
+      .iter()
+      .map(|(start, (len, _))| *start..*start + *len)
+      .collect()
+  }
+
+  /// Updates the stored results after an edit of the input
+  ///
+  /// `range` is the replaced part of the previous input, and `replacement_len` the length
+  /// of the new content. Results overlapping the range, or ending right before it, are
+  /// dropped, and results after the range are moved by the difference in length.
+  pub fn edit(&self, range: Range<usize>, replacement_len: usize) {
+    let mut entries = self.entries.borrow_mut();
+    let previous = crate::lib::std::mem::take(&mut *entries);
+
+    for (start, entry) in previous {
+      if start >= range.end {
+        entries.insert(start - range.end + range.start + replacement_len, entry);
+      } else if start + entry.0 < range.start {
+        entries.insert(start, entry);
+      }
+    }
+  }
+}
+
+impl<O> Default for Memo<O> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+/// Stores the results of the embedded parser in `memo`, and reuses them on later parses.
+///
+/// `input` is the complete input of the current parse: the positions of the results are
+/// computed as offsets from it. See the [module documentation](self) for an example.
+pub fn memoize<'m, I, O, E, F>(
+  memo: &'m Memo<O>,
+  input: I,
+  parser: F,
+) -> impl Parser<I, Output = O, Error = E> + 'm
+where
+  I: Clone + Input + Offset + 'm,
+  O: Clone,
+  E: ParseError<I>,
+  F: Parser<I, Output = O, Error = E> + 'm,
+{
+  Memoize {
+    memo,
+    origin: input,
+    parser,
+  }
+}
+
+/// Parser implementation for [memoize]
+pub struct Memoize<'m, I, O, F> {
+  memo: &'m Memo<O>,
+  origin: I,
+  parser: F,
+}
+
+impl<'m, I, O, F> Parser<I> for Memoize<'m, I, O, F>
+where
+  I: Clone + Input + Offset,
+  O: Clone,
+  F: Parser<I, Output = O>,
+{
+  type Output = O;
+  type Error = <F as Parser<I>>::Error;
+
+  fn process<OM: OutputMode>(&mut self, input: I) -> PResult<OM, I, Self::Output, Self::Error> {
+    let start = self.origin.offset(&input);
+
+    // the borrow is released before applying the parser, which can be recursive
+    let cached = match self.memo.entries.borrow().get(&start) {
+      Some((len, o)) if *len <= input.input_len() => Some((*len, o.clone())),
+      _ => None,
+    };
+
+    if let Some((len, o)) = cached {
+      return Ok((input.take_from(len), OM::Output::bind(|| o)));
+    }
+
+    let (i, o) = self
+      .parser
+      .process::<OutputM<Emit, OM::Error, OM::Incomplete>>(input.clone())?;
+
+    let len = input.input_len() - i.input_len();
+    self
+      .memo
+      .entries
+      .borrow_mut()
+      .insert(start, (len, o.clone()));
+
+    Ok((i, OM::Output::bind(|| o)))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::character::complete::{char, digit1};
+  use crate::combinator::opt;
+  use crate::lib::std::string::String;
+  use crate::lib::std::vec::Vec;
+  use crate::multi::separated_list0;
+  use crate::sequence::delimited;
+  use crate::IResult;
+
+  #[derive(Clone, Debug, PartialEq)]
+  enum Tree {
+    Leaf(u32),
+    Node(Vec<Tree>),
+  }
+
+  // counts the calls to the leaf parser, to check which results are reused
+  fn tree<'a>(
+    memo: &'a Memo<Tree>,
+    origin: &'a str,
+    calls: &'a RefCell<usize>,
+  ) -> impl FnMut(&'a str) -> IResult<&'a str, Tree> + 'a {
+    move |i| {
+      let leaf = digit1.map(|d: &str| {
+        *calls.borrow_mut() += 1;
+        Tree::Leaf(d.parse().unwrap())
+      });
+      let node = delimited(
+        char('('),
+        separated_list0(char(' '), tree(memo, origin, calls)),
+        char(')'),
+      )
+      .map(Tree::Node);
+      memoize(memo, origin, crate::branch::alt((leaf, node))).parse(i)
+    }
+  }
+
+  #[test]
+  fn reuse() {
+    let memo = Memo::new();
+    let calls = RefCell::new(0);
+
+    let text = String::from("(1 (2 3) 4)");
+    let (_, first) = tree(&memo, &text, &calls)(&text).unwrap();
+    assert_eq!(*calls.borrow(), 4);
+    assert_eq!(memo.spans(), [0..11, 1..2, 3..8, 4..5, 6..7, 9..10]);
+
+    // applying it again on the same input reuses the root
+    let (_, again) = tree(&memo, &text, &calls)(&text).unwrap();
+    assert_eq!(again, first);
+    assert_eq!(*calls.borrow(), 4);
+
+    // "3" becomes "35": the leaf, its parent and the root are parsed again
+    let text = String::from("(1 (2 35) 4)");
+    memo.edit(7..7, 1);
+    assert_eq!(memo.spans(), [1..2, 4..5, 10..11]);
+    let (_, edited) = tree(&memo, &text, &calls)(&text).unwrap();
+    assert_eq!(
+      edited,
+      Tree::Node(vec![
+        Tree::Leaf(1),
+        Tree::Node(vec![Tree::Leaf(2), Tree::Leaf(35)]),
+        Tree::Leaf(4)
+      ])
+    );
+    assert_eq!(*calls.borrow(), 5);
+  }
+
+  #[test]
+  fn edit() {
+    let memo = Memo::new();
+    {
+      let mut entries = memo.entries.borrow_mut();
+      entries.insert(0, (2, ()));
+      entries.insert(2, (3, ()));
+      entries.insert(5, (1, ()));
+      entries.insert(6, (2, ()));
+      entries.insert(9, (1, ()));
+    }
+
+    // removes 6..8, replaced by 1 element
+    memo.edit(6..8, 1);
+    assert_eq!(memo.spans(), [0..2, 2..5, 8..9]);
+
+    memo.edit(0..0, 2);
+    assert_eq!(memo.spans(), [2..4, 4..7, 10..11]);
+
+    memo.clear();
+    assert!(memo.is_empty());
+  }
+
+  #[test]
+  fn shorter_input() {
+    let memo = Memo::new();
+    let text = "123";
+    let mut parser = memoize(&memo, text, opt(digit1::<_, crate::error::Error<_>>));
+    assert_eq!(parser.parse(text), Ok(("", Some("123"))));
+
+    // the stored result is longer than the remaining input
+    let text2 = &text[..2];
+    let mut parser = memoize(&memo, text2, opt(digit1::<_, crate::error::Error<_>>));
+    assert_eq!(parser.parse(text2), Ok(("", Some("12"))));
+  }
+}
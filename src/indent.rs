@@ -0,0 +1,1110 @@
+//! Parsing of layouts where the block structure is given by indentation
+//!
+//! Formats like Python or YAML have no delimiters around blocks: a block starts with a
+//! line indented deeper than the line before, and ends at the first line indented less.
+//! The [Indented] wrapper carries the indentation of the current block along with the
+//! input, and the parsers of this module read and change it:
+//!
+//! - [indented_block] applies a parser to a block indented deeper than the current one
+//! - [same_indent] consumes the indentation of a line belonging to the current block
+//! - [dedent] checks that the current block ends before the next line, and that the next
+//!   line returns to the indentation of an enclosing block
+//! - [indented_more] consumes the indentation of a line continuing the current one, indented
+//!   deeper without starting a block
+//! - [current_indent] returns the indentation of the current block
+//!
+//! Those formats usually end statements at line breaks too, except inside brackets or after
+//! a continuation token, like a trailing `\` or operator. The wrapper also tracks those, for
+//! the parsers handling the spacing between tokens:
+//!
+//! - [bracketed] applies a parser between brackets, where line breaks are spacing
+//! - [continuation] marks the end of a parser as continuing on the next line
+//! - [spacing] consumes the spaces between tokens, and the line breaks that do not end a
+//!   statement
+//! - [separator] consumes a line break ending a statement
+//!
+//! The indentation is counted in spaces and tabs, a tab counting as a single column, so the
+//! lines of a block must be indented with the same characters. Blank lines, made of spaces,
+//! tabs and `\r` only, are not part of the structure and are skipped by those parsers.
+//!
+//! ```rust
+//! # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+//! use nom::bytes::tag;
+//! use nom::character::complete::{alpha1, line_ending};
+//! use nom::combinator::cut;
+//! use nom::indent::{dedent, indented_block, same_indent, Indented};
+//! use nom::multi::many1;
+//! use nom::sequence::{delimited, preceded, terminated};
+//!
+//! #[derive(Debug, PartialEq)]
+//! enum Statement<'a> {
+//!   Call(&'a str),
+//!   If(&'a str, Vec<Statement<'a>>),
+//! }
+//!
+//! fn statement(input: Indented<&str>) -> IResult<Indented<&str>, Statement<'_>> {
+//!   let header = delimited(tag("if "), alpha1, (tag(":"), line_ending));
+//!   let condition = (header, cut(indented_block(block)))
+//!     .map(|(condition, body)| Statement::If(condition.into_inner(), body));
+//!   let call = terminated(alpha1, line_ending)
+//!     .map(|name: Indented<&str>| Statement::Call(name.into_inner()));
+//!
+//!   condition.or(call).parse_complete(input)
+//! }
+//!
+//! // statements at the indentation of the block, and nothing indented deeper after them
+//! fn block(input: Indented<&str>) -> IResult<Indented<&str>, Vec<Statement<'_>>> {
+//!   terminated(many1(preceded(same_indent(), statement)), dedent()).parse_complete(input)
+//! }
+//!
+//! let source = "if ready:\n  start\n\n  if fast:\n    run\n  stop\nexit\n";
+//! let (_, statements) = block.parse_complete(Indented::new(source)).unwrap();
+//! assert_eq!(
+//!   statements,
+//!   vec![
+//!     Statement::If(
+//!       "ready",
+//!       vec![
+//!         Statement::Call("start"),
+//!         Statement::If("fast", vec![Statement::Call("run")]),
+//!         Statement::Call("stop"),
+//!       ]
+//!     ),
+//!     Statement::Call("exit"),
+//!   ]
+//! );
+//!
+//! // "run" is indented deeper than the rest of the block
+//! let source = "if ready:\n  start\n    run\n";
+//! assert_eq!(
+//!   block
+//!     .parse_complete(Indented::new(source))
+//!     .map_err(|e| e.map_input(Indented::into_inner)),
+//!   Err(Err::Failure(Error::new("    run\n", ErrorKind::Indent)))
+//! );
+//! ```
+
+use core::fmt;
+use core::marker::PhantomData;
+use core::ops::Deref;
+
+use crate::error::{ErrorKind, ParseError};
+use crate::internal::{Check, Err, Mode, Needed, OutputM, OutputMode, PResult, Parser};
+use crate::traits::{
+  AsBytes, AsChar, Compare, CompareResult, FindSubstring, FindToken, Input, Offset, ParseTo, Rewind,
+};
+
+/// Input wrapper holding the indentation of the block being parsed
+///
+/// The indentation starts at 0 with [Indented::new], and is changed for the duration of a
+/// block by [indented_block]. The wrapper also tracks the brackets opened by [bracketed]
+/// and the tokens marked by [continuation], after which line breaks do not end statements.
+/// The other input traits are implemented by the wrapped input, so the parsers of the crate
+/// can be applied to an `Indented` input, and return `Indented` slices.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Indented<I> {
+  input: I,
+  level: usize,
+  /// Indentation of the block enclosing the current one
+  outer: usize,
+  /// Number of brackets opened with [bracketed]
+  nesting: usize,
+  /// Set by [continuation] until the next [spacing]
+  continued: bool,
+}
+
+impl<I> Indented<I> {
+  /// Wraps an input, starting at the top level block, which is not indented
+  pub fn new(input: I) -> Self {
+    Indented {
+      input,
+      level: 0,
+      outer: 0,
+      nesting: 0,
+      continued: false,
+    }
+  }
+
+  /// Returns the indentation of the current block
+  pub fn level(&self) -> usize {
+    self.level
+  }
+
+  /// Returns the number of brackets the input is in
+  pub fn nesting(&self) -> usize {
+    self.nesting
+  }
+
+  /// Returns `true` if line breaks are spacing at this position, inside brackets or after
+  /// a continuation token
+  pub fn joins_lines(&self) -> bool {
+    self.nesting > 0 || self.continued
+  }
+
+  /// Returns the wrapped input
+  pub fn into_inner(self) -> I {
+    self.input
+  }
+
+  fn with_input(&self, input: I) -> Self {
+    Indented {
+      input,
+      level: self.level,
+      outer: self.outer,
+      nesting: self.nesting,
+      continued: self.continued,
+    }
+  }
+}
+
+impl<I> Deref for Indented<I> {
+  type Target = I;
+
+  fn deref(&self) -> &I {
+    &self.input
+  }
+}
+
+impl<I: fmt::Display> fmt::Display for Indented<I> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    self.input.fmt(f)
+  }
+}
+
+impl<I: Input> Input for Indented<I> {
+  type Item = I::Item;
+  type Iter = I::Iter;
+  type IterIndices = I::IterIndices;
+
+  #[inline]
+  fn input_len(&self) -> usize {
+    self.input.input_len()
+  }
+
+  #[inline]
+  fn take(&self, index: usize) -> Self {
+    self.with_input(self.input.take(index))
+  }
+
+  #[inline]
+  fn take_from(&self, index: usize) -> Self {
+    self.with_input(self.input.take_from(index))
+  }
+
+  #[inline]
+  fn take_split(&self, index: usize) -> (Self, Self) {
+    let (suffix, prefix) = self.input.take_split(index);
+    (self.with_input(suffix), self.with_input(prefix))
+  }
+
+  #[inline]
+  fn position<P>(&self, predicate: P) -> Option<usize>
+  where
+    P: Fn(Self::Item) -> bool,
+  {
+    self.input.position(predicate)
+  }
+
+  #[inline]
+  fn iter_elements(&self) -> Self::Iter {
+    self.input.iter_elements()
+  }
+
+  #[inline]
+  fn iter_indices(&self) -> Self::IterIndices {
+    self.input.iter_indices()
+  }
+
+  #[inline]
+  fn slice_index(&self, count: usize) -> Result<usize, Needed> {
+    self.input.slice_index(count)
+  }
+
+  #[inline]
+  fn is_streaming<OM: OutputMode>(&self) -> bool {
+    self.input.is_streaming::<OM>()
+  }
+}
+
+impl<I: Rewind> Rewind for Indented<I> {
+  type Checkpoint = I::Checkpoint;
+
+  #[inline]
+  fn checkpoint(&self) -> Self::Checkpoint {
+    self.input.checkpoint()
+  }
+
+  #[inline]
+  fn rewind(&self, checkpoint: Self::Checkpoint) {
+    self.input.rewind(checkpoint)
+  }
+}
+
+impl<I: Offset> Offset for Indented<I> {
+  fn offset(&self, second: &Self) -> usize {
+    self.input.offset(&second.input)
+  }
+}
+
+impl<I: AsBytes> AsBytes for Indented<I> {
+  #[inline(always)]
+  fn as_bytes(&self) -> &[u8] {
+    self.input.as_bytes()
+  }
+}
+
+impl<I: Compare<T>, T> Compare<T> for Indented<I> {
+  #[inline]
+  fn compare(&self, t: T) -> CompareResult {
+    self.input.compare(t)
+  }
+
+  #[inline]
+  fn compare_no_case(&self, t: T) -> CompareResult {
+    self.input.compare_no_case(t)
+  }
+
+  #[inline]
+  fn compare_len(&self, t: T) -> (CompareResult, Option<usize>) {
+    self.input.compare_len(t)
+  }
+}
+
+impl<I: FindToken<T>, T> FindToken<T> for Indented<I> {
+  fn find_token(&self, token: T) -> bool {
+    self.input.find_token(token)
+  }
+}
+
+impl<I: FindSubstring<T>, T> FindSubstring<T> for Indented<I> {
+  fn find_substring(&self, substr: T) -> Option<usize> {
+    self.input.find_substring(substr)
+  }
+}
+
+impl<I: ParseTo<R>, R> ParseTo<R> for Indented<I> {
+  fn parse_to(&self) -> Option<R> {
+    self.input.parse_to()
+  }
+}
+
+/// Finds the next line that is not blank, and returns the offset of its start, the offset
+/// of its first character after the indentation, and the width of the indentation
+fn next_line<I>(input: &I) -> Option<(usize, usize, usize)>
+where
+  I: Input,
+  I::Item: AsChar,
+{
+  let mut start = 0;
+  let mut width = 0;
+  for (index, item) in input.iter_indices() {
+    match item.as_char() {
+      ' ' | '\t' => width += 1,
+      '\r' => {}
+      '\n' => {
+        start = index + item.len();
+        width = 0;
+      }
+      _ => return Some((start, index, width)),
+    }
+  }
+  None
+}
+
+fn indent_error<OM: OutputMode, I: Input, O, E: ParseError<Indented<I>>>(
+  input: Indented<I>,
+  start: usize,
+) -> PResult<OM, Indented<I>, O, E> {
+  Err(Err::Error(OM::Error::bind(|| {
+    E::from_error_kind(input.take_from(start), ErrorKind::Indent)
+  })))
+}
+
+/// Consumes the indentation of the next line that is not blank, if it is at the level of
+/// the current block
+///
+/// The blank lines before it are consumed too. This returns an error with
+/// [ErrorKind::Indent] if the line is indented differently, and with [ErrorKind::Eof] if no
+/// line is left, or [Err::Incomplete] in streaming mode. It is meant to be applied at the
+/// start of a line, usually before each statement of a block.
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+/// use nom::character::complete::alpha1;
+/// use nom::indent::{same_indent, Indented};
+///
+/// fn word(input: Indented<&str>) -> IResult<Indented<&str>, Indented<&str>> {
+///   (same_indent(), alpha1).map(|(_, word)| word).parse_complete(input)
+/// }
+///
+/// let (_, w) = word.parse_complete(Indented::new("\n  \nabc")).unwrap();
+/// assert_eq!(w.into_inner(), "abc");
+/// assert_eq!(
+///   word.parse_complete(Indented::new("  abc")),
+///   Err(Err::Error(Error::new(Indented::new("  abc"), ErrorKind::Indent)))
+/// );
+/// ```
+pub fn same_indent<I, E>() -> impl Parser<Indented<I>, Output = (), Error = E>
+where
+  I: Input,
+  I::Item: AsChar,
+  E: ParseError<Indented<I>>,
+{
+  SameIndent { e: PhantomData }
+}
+
+/// Parser implementation for [same_indent]
+pub struct SameIndent<E> {
+  e: PhantomData<E>,
+}
+
+impl<I, E> Parser<Indented<I>> for SameIndent<E>
+where
+  I: Input,
+  I::Item: AsChar,
+  E: ParseError<Indented<I>>,
+{
+  type Output = ();
+  type Error = E;
+
+  fn process<OM: OutputMode>(
+    &mut self,
+    input: Indented<I>,
+  ) -> PResult<OM, Indented<I>, Self::Output, Self::Error> {
+    match next_line(&input.input) {
+      Some((_, content, width)) if width == input.level => {
+        Ok((input.take_from(content), OM::Output::bind(|| ())))
+      }
+      Some((start, _, _)) => indent_error::<OM, _, _, _>(input, start),
+      None if input.is_streaming::<OM>() => Err(Err::Incomplete(Needed::new(1))),
+      None => Err(Err::Error(OM::Error::bind(|| {
+        E::from_error_kind(input, ErrorKind::Eof)
+      }))),
+    }
+  }
+}
+
+/// Applies a parser to a block indented deeper than the current one
+///
+/// The indentation of the block is the one of its first line that is not blank, and stays
+/// the current one until the parser returns. The parser receives the input from the start
+/// of that line, so it usually starts with [same_indent]. This returns an error with
+/// [ErrorKind::Indent] if that line is not indented deeper, and with [ErrorKind::Eof] if no
+/// line is left, or [Err::Incomplete] in streaming mode.
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+/// use nom::character::complete::{alpha1, line_ending};
+/// use nom::indent::{indented_block, same_indent, Indented};
+/// use nom::multi::many1;
+/// use nom::sequence::{delimited, terminated};
+///
+/// fn item(input: Indented<&str>) -> IResult<Indented<&str>, &str> {
+///   delimited(same_indent(), alpha1, line_ending)
+///     .map(|w: Indented<&str>| w.into_inner())
+///     .parse_complete(input)
+/// }
+///
+/// fn list(input: Indented<&str>) -> IResult<Indented<&str>, (&str, Vec<&str>)> {
+///   (terminated(alpha1, line_ending), indented_block(many1(item)))
+///     .map(|(name, items)| (name.into_inner(), items))
+///     .parse_complete(input)
+/// }
+///
+/// let (rest, output) = list.parse_complete(Indented::new("list\n  a\n  b\nend\n")).unwrap();
+/// assert_eq!(output, ("list", vec!["a", "b"]));
+/// assert_eq!((rest.into_inner(), rest.level()), ("end\n", 0));
+///
+/// assert_eq!(
+///   list.parse_complete(Indented::new("list\na\n")),
+///   Err(Err::Error(Error::new(Indented::new("a\n"), ErrorKind::Indent)))
+/// );
+/// ```
+pub fn indented_block<I, E, F>(
+  parser: F,
+) -> impl Parser<Indented<I>, Output = <F as Parser<Indented<I>>>::Output, Error = E>
+where
+  I: Input,
+  I::Item: AsChar,
+  E: ParseError<Indented<I>>,
+  F: Parser<Indented<I>, Error = E>,
+{
+  IndentedBlock { parser }
+}
+
+/// Parser implementation for [indented_block]
+pub struct IndentedBlock<F> {
+  parser: F,
+}
+
+impl<I, E, F> Parser<Indented<I>> for IndentedBlock<F>
+where
+  I: Input,
+  I::Item: AsChar,
+  E: ParseError<Indented<I>>,
+  F: Parser<Indented<I>, Error = E>,
+{
+  type Output = <F as Parser<Indented<I>>>::Output;
+  type Error = E;
+
+  fn process<OM: OutputMode>(
+    &mut self,
+    input: Indented<I>,
+  ) -> PResult<OM, Indented<I>, Self::Output, Self::Error> {
+    let (start, width) = match next_line(&input.input) {
+      Some((start, _, width)) if width > input.level => (start, width),
+      Some((start, _, _)) => return indent_error::<OM, _, _, _>(input, start),
+      None if input.is_streaming::<OM>() => return Err(Err::Incomplete(Needed::new(1))),
+      None => {
+        return Err(Err::Error(OM::Error::bind(|| {
+          E::from_error_kind(input, ErrorKind::Eof)
+        })))
+      }
+    };
+
+    let (level, outer) = (input.level, input.outer);
+    let block = Indented {
+      level: width,
+      outer: level,
+      ..input.take_from(start)
+    };
+    let (rest, output) = self.parser.process::<OM>(block)?;
+    Ok((
+      Indented {
+        level,
+        outer,
+        ..rest
+      },
+      output,
+    ))
+  }
+}
+
+/// Checks that the next line that is not blank is indented less than the current block,
+/// without consuming anything
+///
+/// This succeeds at the end of the input, or returns [Err::Incomplete] in streaming mode,
+/// and returns an error with [ErrorKind::Indent] if the line is at the level of the current
+/// block or deeper. Applied after the statements of a block, it reports the lines that were
+/// not parsed as part of the block.
+///
+/// A line indented less than the current block, but deeper than the block enclosing it,
+/// does not belong to any block: this returns [Err::Failure] with [ErrorKind::Dedent] at the
+/// start of that line, so that the error is not hidden by the alternatives of the
+/// enclosing blocks.
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+/// use nom::character::complete::{alpha1, line_ending};
+/// use nom::indent::{dedent, indented_block, same_indent, Indented};
+///
+/// let mut end = dedent::<_, Error<_>>();
+/// assert!(end.parse_complete(Indented::new("\n \n")).is_ok());
+/// assert_eq!(end.parse(Indented::new("\n \n")), Err(Err::Incomplete(nom::Needed::new(1))));
+/// assert_eq!(
+///   end.parse_complete(Indented::new("\nabc")),
+///   Err(Err::Error(Error::new(Indented::new("abc"), ErrorKind::Indent)))
+/// );
+///
+/// // "b" is between the block of "a" and the top level
+/// let mut block = indented_block((same_indent(), alpha1, line_ending, dedent::<_, Error<_>>()));
+/// assert_eq!(
+///   block
+///     .parse_complete(Indented::new("    a\n  b\n"))
+///     .map_err(|e| e.map_input(Indented::into_inner)),
+///   Err(Err::Failure(Error::new("  b\n", ErrorKind::Dedent)))
+/// );
+/// ```
+pub fn dedent<I, E>() -> impl Parser<Indented<I>, Output = (), Error = E>
+where
+  I: Input,
+  I::Item: AsChar,
+  E: ParseError<Indented<I>>,
+{
+  Dedent { e: PhantomData }
+}
+
+/// Parser implementation for [dedent]
+pub struct Dedent<E> {
+  e: PhantomData<E>,
+}
+
+impl<I, E> Parser<Indented<I>> for Dedent<E>
+where
+  I: Input,
+  I::Item: AsChar,
+  E: ParseError<Indented<I>>,
+{
+  type Output = ();
+  type Error = E;
+
+  fn process<OM: OutputMode>(
+    &mut self,
+    input: Indented<I>,
+  ) -> PResult<OM, Indented<I>, Self::Output, Self::Error> {
+    match next_line(&input.input) {
+      Some((start, _, width)) if width < input.level && width > input.outer => Err(Err::Failure(
+        E::from_error_kind(input.take_from(start), ErrorKind::Dedent),
+      )),
+      Some((_, _, width)) if width < input.level => Ok((input, OM::Output::bind(|| ()))),
+      Some((start, _, _)) => indent_error::<OM, _, _, _>(input, start),
+      None if input.is_streaming::<OM>() => Err(Err::Incomplete(Needed::new(1))),
+      None => Ok((input, OM::Output::bind(|| ()))),
+    }
+  }
+}
+
+/// Returns the indentation of the current block, without consuming anything
+///
+/// ```rust
+/// # use nom::{error::Error, IResult, Parser};
+/// use nom::character::complete::{alpha1, line_ending};
+/// use nom::indent::{current_indent, indented_block, same_indent, Indented};
+///
+/// fn levels(input: Indented<&str>) -> IResult<Indented<&str>, (usize, usize)> {
+///   let inner = (same_indent(), current_indent()).map(|(_, level)| level);
+///   (current_indent(), alpha1, line_ending, indented_block(inner))
+///     .map(|(outer, _, _, inner)| (outer, inner))
+///     .parse_complete(input)
+/// }
+///
+/// assert_eq!(levels(Indented::new("a\n   b")).map(|(_, l)| l), Ok((0, 3)));
+/// ```
+pub fn current_indent<I, E>() -> impl Parser<Indented<I>, Output = usize, Error = E>
+where
+  E: ParseError<Indented<I>>,
+{
+  CurrentIndent { e: PhantomData }
+}
+
+/// Parser implementation for [current_indent]
+pub struct CurrentIndent<E> {
+  e: PhantomData<E>,
+}
+
+impl<I, E> Parser<Indented<I>> for CurrentIndent<E>
+where
+  E: ParseError<Indented<I>>,
+{
+  type Output = usize;
+  type Error = E;
+
+  fn process<OM: OutputMode>(
+    &mut self,
+    input: Indented<I>,
+  ) -> PResult<OM, Indented<I>, Self::Output, Self::Error> {
+    let level = input.level;
+    Ok((input, OM::Output::bind(|| level)))
+  }
+}
+
+/// Consumes the indentation of the next line that is not blank, if it is deeper than the
+/// current block, returning its width
+///
+/// Unlike [indented_block], the current block does not change: this is meant for the lines
+/// continuing a statement, like the folded lines of a YAML scalar. The blank lines before it
+/// are consumed too. This returns an error with [ErrorKind::Indent] if the line is not
+/// indented deeper, and with [ErrorKind::Eof] if no line is left, or [Err::Incomplete] in
+/// streaming mode.
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+/// use nom::character::complete::{line_ending, not_line_ending};
+/// use nom::indent::{indented_more, Indented};
+/// use nom::multi::many0;
+/// use nom::sequence::{preceded, terminated};
+///
+/// // a value, and the lines indented deeper after it
+/// fn folded(input: Indented<&str>) -> IResult<Indented<&str>, Vec<&str>> {
+///   let line = |i| terminated(not_line_ending, line_ending).parse_complete(i);
+///   (line, many0(preceded(indented_more(), line)))
+///     .map(|(first, rest): (Indented<&str>, Vec<Indented<&str>>)| {
+///       let mut lines = vec![first.into_inner()];
+///       lines.extend(rest.into_iter().map(Indented::into_inner));
+///       lines
+///     })
+///     .parse_complete(input)
+/// }
+///
+/// let (rest, lines) = folded(Indented::new("a\n  b\n\n    c\nd\n")).unwrap();
+/// assert_eq!(lines, ["a", "b", "c"]);
+/// assert_eq!(rest.into_inner(), "d\n");
+/// ```
+pub fn indented_more<I, E>() -> impl Parser<Indented<I>, Output = usize, Error = E>
+where
+  I: Input,
+  I::Item: AsChar,
+  E: ParseError<Indented<I>>,
+{
+  IndentedMore { e: PhantomData }
+}
+
+/// Parser implementation for [indented_more]
+pub struct IndentedMore<E> {
+  e: PhantomData<E>,
+}
+
+impl<I, E> Parser<Indented<I>> for IndentedMore<E>
+where
+  I: Input,
+  I::Item: AsChar,
+  E: ParseError<Indented<I>>,
+{
+  type Output = usize;
+  type Error = E;
+
+  fn process<OM: OutputMode>(
+    &mut self,
+    input: Indented<I>,
+  ) -> PResult<OM, Indented<I>, Self::Output, Self::Error> {
+    match next_line(&input.input) {
+      Some((_, content, width)) if width > input.level => {
+        Ok((input.take_from(content), OM::Output::bind(|| width)))
+      }
+      Some((start, _, _)) => indent_error::<OM, _, _, _>(input, start),
+      None if input.is_streaming::<OM>() => Err(Err::Incomplete(Needed::new(1))),
+      None => Err(Err::Error(OM::Error::bind(|| {
+        E::from_error_kind(input, ErrorKind::Eof)
+      }))),
+    }
+  }
+}
+
+/// Applies a parser between brackets, where line breaks are spacing instead of separators
+///
+/// The `open` and `close` parsers recognize the brackets, and their output is discarded.
+/// The parser and the `close` parser are applied inside the brackets, so [spacing] skips
+/// the line breaks before the closing bracket too.
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+/// use nom::bytes::tag;
+/// use nom::character::complete::digit1;
+/// use nom::indent::{bracketed, separator, spacing, Indented};
+/// use nom::multi::separated_list1;
+/// use nom::sequence::{preceded, terminated};
+///
+/// fn token<'a>(
+///   t: &'static str,
+/// ) -> impl Parser<Indented<&'a str>, Output = Indented<&'a str>, Error = Error<Indented<&'a str>>> {
+///   preceded(spacing(), tag(t))
+/// }
+///
+/// fn list(input: Indented<&str>) -> IResult<Indented<&str>, Vec<&str>> {
+///   let number = preceded(spacing(), digit1).map(|n: Indented<&str>| n.into_inner());
+///   bracketed(token("["), separated_list1(token(","), number), token("]"))
+///     .parse_complete(input)
+/// }
+///
+/// let mut lists = separated_list1(separator(), list);
+/// let (rest, output) = lists.parse_complete(Indented::new("[1, 2]\n[\n  3,\n  4\n]\n")).unwrap();
+/// assert_eq!(output, vec![vec!["1", "2"], vec!["3", "4"]]);
+/// assert_eq!(rest.into_inner(), "\n");
+/// ```
+pub fn bracketed<I, E, O, F, C>(
+  open: O,
+  parser: F,
+  close: C,
+) -> impl Parser<Indented<I>, Output = <F as Parser<Indented<I>>>::Output, Error = E>
+where
+  E: ParseError<Indented<I>>,
+  O: Parser<Indented<I>, Error = E>,
+  F: Parser<Indented<I>, Error = E>,
+  C: Parser<Indented<I>, Error = E>,
+{
+  Bracketed {
+    open,
+    parser,
+    close,
+  }
+}
+
+/// Parser implementation for [bracketed]
+pub struct Bracketed<O, F, C> {
+  open: O,
+  parser: F,
+  close: C,
+}
+
+impl<I, E, O, F, C> Parser<Indented<I>> for Bracketed<O, F, C>
+where
+  E: ParseError<Indented<I>>,
+  O: Parser<Indented<I>, Error = E>,
+  F: Parser<Indented<I>, Error = E>,
+  C: Parser<Indented<I>, Error = E>,
+{
+  type Output = <F as Parser<Indented<I>>>::Output;
+  type Error = E;
+
+  fn process<OM: OutputMode>(
+    &mut self,
+    input: Indented<I>,
+  ) -> PResult<OM, Indented<I>, Self::Output, Self::Error> {
+    let nesting = input.nesting;
+    let (i, _) = self
+      .open
+      .process::<OutputM<Check, OM::Error, OM::Incomplete>>(input)?;
+    let inner = Indented {
+      nesting: nesting + 1,
+      ..i
+    };
+    let (i, output) = self.parser.process::<OM>(inner)?;
+    let (i, _) = self
+      .close
+      .process::<OutputM<Check, OM::Error, OM::Incomplete>>(i)?;
+    Ok((Indented { nesting, ..i }, output))
+  }
+}
+
+/// Applies a parser recognizing a continuation token, after which the next line break is
+/// spacing instead of a separator
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+/// use nom::bytes::tag;
+/// use nom::character::complete::alpha1;
+/// use nom::indent::{continuation, separator, spacing, Indented};
+/// use nom::multi::separated_list1;
+/// use nom::sequence::preceded;
+///
+/// // a sum of names, which can continue on the next line after a `+`
+/// fn sum(input: Indented<&str>) -> IResult<Indented<&str>, Vec<Indented<&str>>> {
+///   separated_list1(
+///     continuation(preceded(spacing(), tag("+"))),
+///     preceded(spacing(), alpha1),
+///   )
+///   .parse_complete(input)
+/// }
+///
+/// let (_, sums) = separated_list1(separator(), sum)
+///   .parse_complete(Indented::new("a + b +\n  c\nd"))
+///   .unwrap();
+/// assert_eq!(sums.iter().map(Vec::len).collect::<Vec<_>>(), [3, 1]);
+/// ```
+pub fn continuation<I, E, F>(
+  parser: F,
+) -> impl Parser<Indented<I>, Output = <F as Parser<Indented<I>>>::Output, Error = E>
+where
+  E: ParseError<Indented<I>>,
+  F: Parser<Indented<I>, Error = E>,
+{
+  Continuation { parser }
+}
+
+/// Parser implementation for [continuation]
+pub struct Continuation<F> {
+  parser: F,
+}
+
+impl<I, E, F> Parser<Indented<I>> for Continuation<F>
+where
+  E: ParseError<Indented<I>>,
+  F: Parser<Indented<I>, Error = E>,
+{
+  type Output = <F as Parser<Indented<I>>>::Output;
+  type Error = E;
+
+  fn process<OM: OutputMode>(
+    &mut self,
+    input: Indented<I>,
+  ) -> PResult<OM, Indented<I>, Self::Output, Self::Error> {
+    let (i, output) = self.parser.process::<OM>(input)?;
+    Ok((
+      Indented {
+        continued: true,
+        ..i
+      },
+      output,
+    ))
+  }
+}
+
+/// Consumes the spaces and tabs between tokens, and the line breaks inside brackets or
+/// after a continuation token
+///
+/// After a continuation token, only the first line break of the spacing that directly
+/// follows it is consumed. This never returns an error, but returns [Err::Incomplete] in
+/// streaming mode if the input ends with spacing.
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Parser};
+/// use nom::indent::{spacing, Indented};
+///
+/// let (rest, _) = spacing::<_, Error<_>>().parse_complete(Indented::new("  \n a")).unwrap();
+/// assert_eq!(rest.into_inner(), "\n a");
+/// ```
+pub fn spacing<I, E>() -> impl Parser<Indented<I>, Output = (), Error = E>
+where
+  I: Input,
+  I::Item: AsChar,
+  E: ParseError<Indented<I>>,
+{
+  Spacing { e: PhantomData }
+}
+
+/// Parser implementation for [spacing]
+pub struct Spacing<E> {
+  e: PhantomData<E>,
+}
+
+impl<I, E> Parser<Indented<I>> for Spacing<E>
+where
+  I: Input,
+  I::Item: AsChar,
+  E: ParseError<Indented<I>>,
+{
+  type Output = ();
+  type Error = E;
+
+  fn process<OM: OutputMode>(
+    &mut self,
+    input: Indented<I>,
+  ) -> PResult<OM, Indented<I>, Self::Output, Self::Error> {
+    let mut continued = input.continued;
+    let mut end = None;
+    for (index, item) in input.iter_indices() {
+      match item.as_char() {
+        ' ' | '\t' => {}
+        '\r' if input.nesting > 0 || continued => {}
+        '\n' if input.nesting > 0 || continued => continued = false,
+        _ => {
+          end = Some(index);
+          break;
+        }
+      }
+    }
+
+    let end = match end {
+      Some(end) => end,
+      None if input.is_streaming::<OM>() => return Err(Err::Incomplete(Needed::new(1))),
+      None => input.input_len(),
+    };
+    Ok((
+      Indented {
+        continued: false,
+        ..input.take_from(end)
+      },
+      OM::Output::bind(|| ()),
+    ))
+  }
+}
+
+/// Consumes a line break ending a statement, with the spaces before it and the blank lines
+/// after it
+///
+/// The indentation of the next line is not consumed, so [same_indent] can check it. This
+/// returns an error with [ErrorKind::CrLf] if the next character is not a line break, or if
+/// the line break is inside brackets or after a continuation token.
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Parser};
+/// use nom::indent::{separator, Indented};
+///
+/// let (rest, _) = separator::<_, Error<_>>().parse_complete(Indented::new(" \n\n  a")).unwrap();
+/// assert_eq!(rest.into_inner(), "  a");
+/// assert_eq!(
+///   separator().parse_complete(Indented::new(" a")),
+///   Err(Err::Error(Error::new(Indented::new("a"), ErrorKind::CrLf)))
+/// );
+/// ```
+pub fn separator<I, E>() -> impl Parser<Indented<I>, Output = (), Error = E>
+where
+  I: Input,
+  I::Item: AsChar,
+  E: ParseError<Indented<I>>,
+{
+  Separator { e: PhantomData }
+}
+
+/// Parser implementation for [separator]
+pub struct Separator<E> {
+  e: PhantomData<E>,
+}
+
+impl<I, E> Parser<Indented<I>> for Separator<E>
+where
+  I: Input,
+  I::Item: AsChar,
+  E: ParseError<Indented<I>>,
+{
+  type Output = ();
+  type Error = E;
+
+  fn process<OM: OutputMode>(
+    &mut self,
+    input: Indented<I>,
+  ) -> PResult<OM, Indented<I>, Self::Output, Self::Error> {
+    let position = input.position(|item| !matches!(item.as_char(), ' ' | '\t' | '\r'));
+    let index = match position {
+      Some(index) => index,
+      None if input.is_streaming::<OM>() => return Err(Err::Incomplete(Needed::new(1))),
+      None => input.input_len(),
+    };
+
+    let line_break = input
+      .take_from(index)
+      .iter_elements()
+      .next()
+      .filter(|item| item.as_char() == '\n');
+    let after = match line_break {
+      Some(item) if !input.joins_lines() => input.take_from(index + item.len()),
+      _ => {
+        return Err(Err::Error(OM::Error::bind(|| {
+          E::from_error_kind(input.take_from(index), ErrorKind::CrLf)
+        })))
+      }
+    };
+
+    let next = match next_line(&after.input) {
+      Some((start, _, _)) => start,
+      None if input.is_streaming::<OM>() => return Err(Err::Incomplete(Needed::new(1))),
+      None => after.input_len(),
+    };
+    Ok((
+      Indented {
+        continued: false,
+        ..after.take_from(next)
+      },
+      OM::Output::bind(|| ()),
+    ))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::bytes::tag;
+  use crate::character::complete::{alpha1, line_ending};
+  use crate::error::Error;
+  use crate::multi::many0;
+  use crate::sequence::{preceded, terminated};
+  use crate::IResult;
+
+  #[derive(Debug, PartialEq)]
+  struct Node<'a> {
+    name: &'a [u8],
+    children: Vec<Node<'a>>,
+  }
+
+  fn node(input: Indented<&[u8]>) -> IResult<Indented<&[u8]>, Node<'_>> {
+    let children = preceded(tag(":"), indented_block(nodes));
+    (
+      preceded(same_indent(), alpha1),
+      terminated(crate::combinator::opt(children), many0(line_ending)),
+    )
+      .map(|(name, children)| Node {
+        name: name.into_inner(),
+        children: children.unwrap_or_default(),
+      })
+      .parse_complete(input)
+  }
+
+  fn nodes(input: Indented<&[u8]>) -> IResult<Indented<&[u8]>, Vec<Node<'_>>> {
+    terminated(many0(node), dedent()).parse_complete(input)
+  }
+
+  #[test]
+  fn blocks() {
+    let leaf = |name| Node {
+      name,
+      children: Vec::new(),
+    };
+
+    let input = &b"a:\n\tb:\r\n\t\tc\n\n\t\td\n\te\nf"[..];
+    let (rest, output) = nodes.parse_complete(Indented::new(input)).unwrap();
+    assert_eq!(rest.into_inner(), &b""[..]);
+    assert_eq!(
+      output,
+      vec![
+        Node {
+          name: b"a",
+          children: vec![
+            Node {
+              name: b"b",
+              children: vec![leaf(&b"c"[..]), leaf(&b"d"[..])],
+            },
+            leaf(&b"e"[..]),
+          ],
+        },
+        leaf(&b"f"[..]),
+      ]
+    );
+
+    // a line between the levels of two blocks
+    let input = &b"a:\n    b\n  c\n"[..];
+    assert_eq!(
+      nodes
+        .parse_complete(Indented::new(input))
+        .map_err(|e| e.map_input(Indented::into_inner)),
+      Err(Err::Failure(Error::new(&b"  c\n"[..], ErrorKind::Dedent)))
+    );
+    // and three levels deep, where "d" matches the level of "b" and "e" the top level
+    let input = &b"a:\n  b:\n      c\n  d\ne\n"[..];
+    let (_, output) = nodes.parse_complete(Indented::new(input)).unwrap();
+    assert_eq!(output.len(), 2);
+    let input = &b"a:\n  b:\n      c\n    d\n"[..];
+    assert_eq!(
+      nodes
+        .parse_complete(Indented::new(input))
+        .map_err(|e| e.map_input(Indented::into_inner)),
+      Err(Err::Failure(Error::new(&b"    d\n"[..], ErrorKind::Dedent)))
+    );
+    let input = &b"a:\n  b:\n      c\n d\n"[..];
+    assert_eq!(
+      nodes
+        .parse_complete(Indented::new(input))
+        .map_err(|e| e.map_input(Indented::into_inner)),
+      Err(Err::Failure(Error::new(&b" d\n"[..], ErrorKind::Dedent)))
+    );
+
+    // more lines of the block can follow
+    let mut lines = terminated(
+      many0(preceded(same_indent(), terminated(alpha1, line_ending))),
+      dedent::<_, Error<_>>(),
+    );
+    assert_eq!(
+      lines.parse(Indented::new(&b"a\nb\n"[..])),
+      Err(Err::Incomplete(Needed::new(1)))
+    );
+  }
+
+  #[test]
+  fn separators() {
+    use crate::bytes::tag;
+    use crate::multi::separated_list1;
+
+    fn expression(input: Indented<&str>) -> IResult<Indented<&str>, usize> {
+      let word = preceded(spacing(), alpha1).map(|_| 1);
+      let group = bracketed(
+        preceded(spacing(), tag("(")),
+        expression,
+        preceded(spacing(), tag(")")),
+      );
+      separated_list1(continuation(preceded(spacing(), tag("+"))), word.or(group))
+        .map(|terms| terms.into_iter().sum())
+        .parse_complete(input)
+    }
+
+    let mut statements = separated_list1(separator(), expression);
+    let input = "a + (b\r\n+\n\n c) +\r\n d\r\n\r\n  e\nf";
+    let (rest, output) = statements.parse_complete(Indented::new(input)).unwrap();
+    assert_eq!(output, [4, 1, 1]);
+    assert_eq!(rest.into_inner(), "");
+    assert_eq!((rest.nesting(), rest.joins_lines()), (0, false));
+
+    // the line break after "b" is not preceded by a continuation token
+    let input = Indented::new("a + b\nc");
+    let (rest, output) = statements.parse_complete(input).unwrap();
+    assert_eq!(output, [2, 1]);
+    assert_eq!(rest.into_inner(), "");
+
+    assert_eq!(
+      separator::<_, Error<_>>().parse(Indented::new("\n  ")),
+      Err(Err::Incomplete(Needed::new(1)))
+    );
+    assert_eq!(
+      spacing::<_, Error<_>>().parse(Indented::new(" ")),
+      Err(Err::Incomplete(Needed::new(1)))
+    );
+  }
+}
@@ -1,8 +1,11 @@
 //! Basic types to build the parsers
 
 use self::Needed::*;
+use crate::combinator::{Consumed, Dbg};
 use crate::error::{self, ErrorKind, FromExternalError, ParseError};
 use crate::lib::std::fmt;
+use crate::sequence::{Preceded, Terminated};
+use crate::traits::ToUsize;
 use core::marker::PhantomData;
 use core::num::NonZeroUsize;
 
@@ -31,6 +34,29 @@ pub trait Finish<I, O, E> {
   ///   Once the parser returns either `Ok(_)`, `Err(Err::Error(_))` or `Err(Err::Failure(_))`,
   ///   you can get out of the parsing loop and call `finish()` on the parser's result
   fn finish(self) -> Result<(I, O), E>;
+
+  /// Like [Finish::finish], but bundles the error with `input`, the whole input given to
+  /// the parser, in a [Report](error::Report) that can show where the error happened
+  ///
+  /// ```rust
+  /// # use nom::{Finish, IResult, Parser};
+  /// use nom::character::complete::{alpha1, digit1, newline};
+  ///
+  /// let input = "abc\ndef";
+  /// let res: IResult<_, _> = (alpha1, newline, digit1).parse(input);
+  /// let report = res.into_report(input).unwrap_err();
+  ///
+  /// assert_eq!(report.line_col(), Some((2, 1)));
+  /// assert_eq!(report.to_string(), "2:1: error Digit at: def\ndef\n^");
+  /// ```
+  fn into_report(self, input: I) -> Result<(I, O), error::Report<I, E>>
+  where
+    Self: Sized,
+  {
+    self
+      .finish()
+      .map_err(|error| error::Report { input, error })
+  }
 }
 
 impl<I, O, E> Finish<I, O, E> for IResult<I, O, E> {
@@ -46,7 +72,8 @@ impl<I, O, E> Finish<I, O, E> for IResult<I, O, E> {
 }
 
 /// Contains information on needed data if a parser returned `Incomplete`
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Needed {
   /// Needs more data, but we do not know how much
   Unknown,
@@ -97,7 +124,8 @@ impl Needed {
 ///
 /// See also: [`Finish`].
 ///
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Err<Failure, Error = Failure> {
   /// There was not enough data
   Incomplete(Needed),
@@ -128,11 +156,18 @@ impl<E> Err<E> {
   }
 
   /// Automatically converts between errors if the underlying type supports it
-  pub fn convert<F>(e: Err<F>) -> Self
+  ///
+  /// ```rust
+  /// # use nom::{Err, error::{Error, ErrorKind}};
+  /// let e: Err<Error<&str>> = Err::Error(Error::new("abc", ErrorKind::Tag));
+  /// let e: Err<Error<String>> = e.convert();
+  /// assert_eq!(e, Err::Error(Error::new(String::from("abc"), ErrorKind::Tag)));
+  /// ```
+  pub fn convert<E2>(self) -> Err<E2>
   where
-    E: From<F>,
+    E2: From<E>,
   {
-    e.map(crate::lib::std::convert::Into::into)
+    self.map(crate::lib::std::convert::Into::into)
   }
 }
 
@@ -170,6 +205,31 @@ impl<T> Err<error::Error<T>> {
   }
 }
 
+impl<E> Err<E> {
+  /// Converts the input positions of the inner error with `f`, for any error type
+  /// implementing [MapInput](error::MapInput)
+  pub fn map_error_input<I, U, F>(self, f: F) -> Err<E::Mapped<U>>
+  where
+    E: error::MapInput<I>,
+    F: FnMut(I) -> U,
+  {
+    self.map(|e| e.map_input(f))
+  }
+
+  /// Converts the borrowed input positions of the inner error to their owned version,
+  /// see [MapInput](error::MapInput)
+  #[cfg(feature = "alloc")]
+  #[cfg_attr(feature = "docsrs", doc(cfg(feature = "alloc")))]
+  pub fn into_owned<I>(self) -> Err<E::Mapped<<I::Target as ToOwned>::Owned>>
+  where
+    E: error::MapInput<I>,
+    I: crate::lib::std::ops::Deref,
+    I::Target: ToOwned,
+  {
+    self.map(error::MapInput::into_owned)
+  }
+}
+
 #[cfg(feature = "alloc")]
 use crate::lib::std::{borrow::ToOwned, string::String, vec::Vec};
 #[cfg(feature = "alloc")]
@@ -208,6 +268,38 @@ impl Err<error::Error<&str>> {
   }
 }
 
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "alloc")))]
+impl From<Err<error::Error<&[u8]>>> for Err<error::Error<Vec<u8>>> {
+  fn from(e: Err<error::Error<&[u8]>>) -> Self {
+    e.convert()
+  }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "alloc")))]
+impl From<Err<error::Error<&str>>> for Err<error::Error<String>> {
+  fn from(e: Err<error::Error<&str>>) -> Self {
+    e.convert()
+  }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "alloc")))]
+impl From<Err<(&[u8], ErrorKind)>> for Err<(Vec<u8>, ErrorKind)> {
+  fn from(e: Err<(&[u8], ErrorKind)>) -> Self {
+    e.to_owned()
+  }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "alloc")))]
+impl From<Err<(&str, ErrorKind)>> for Err<(String, ErrorKind)> {
+  fn from(e: Err<(&str, ErrorKind)>) -> Self {
+    e.to_owned()
+  }
+}
+
 impl<E: Eq> Eq for Err<E> {}
 
 impl<E> fmt::Display for Err<E>
@@ -329,6 +421,20 @@ pub type PResult<OM, I, O, E> = Result<
 ///
 /// The same parser implementation can vary in behaviour according to the chosen
 /// output mode
+///
+/// # Errors and failures
+///
+/// The error mode only applies to [Err::Error], the recoverable errors that combinators
+/// like `alt` or `many0` discard to try something else, so they do not need to be built
+/// when the caller only checks that a parser failed. [Err::Failure] always carries its
+/// error, since it stops every enclosing combinator and reaches the caller.
+///
+/// [cut](crate::combinator::cut) applies its parser with the [Emit] error mode, because
+/// its errors become failures. [commit_after](crate::combinator::commit_after) applies its
+/// prefix in the caller's error mode, and the rest like `cut`, so grammars can express
+/// that errors are final once a keyword matched. [backtrack](crate::combinator::backtrack)
+/// converts failures back into errors, dropping them if the caller's error mode is
+/// [Check], so the `cut` calls inside a parser do not escape it.
 pub trait OutputMode {
   /// Defines the [Mode] for the output type. [Emit] will generate the value, [Check] will
   /// apply the parser but will only generate `()` if successful. This can be used when
@@ -399,6 +505,20 @@ impl<M: Mode, EM: Mode, S: IsStreaming> OutputMode for OutputM<M, EM, S> {
   type Error = EM;
   type Incomplete = S;
 }
+
+/// How closely parsers configured with it follow the format they recognize
+///
+/// With the same grammar, strict mode is used to validate data, and lenient mode to
+/// ingest data as it is found in practice. See [integer](crate::character::integer) and
+/// [decimal](crate::number::decimal) for the deviations accepted by the numeric parsers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Strictness {
+  /// Rejects anything outside of the canonical format
+  Strict,
+  /// Accepts common deviations from the format
+  Lenient,
+}
+
 /// All nom parsers implement this trait
 pub trait Parser<Input> {
   /// Type of the produced value
@@ -420,6 +540,45 @@ pub trait Parser<Input> {
     self.process::<OutputM<Emit, Emit, Complete>>(input)
   }
 
+  /// Applies the parser in complete mode on the whole `input`, returning its output, or an
+  /// error that does not borrow the input and can be displayed
+  ///
+  /// Input left by the parser is an error with [ErrorKind::Eof], like in
+  /// [all_consuming](crate::combinator::all_consuming). The [SourceError](error::SourceError)
+  /// keeps the offsets of the error positions and the line where it happened, so it can be
+  /// returned with `?` once the input is dropped.
+  ///
+  /// ```rust
+  /// # use nom::{error::{Error, ErrorKind}, Parser};
+  /// use nom::character::complete::{alpha1, digit1, newline};
+  ///
+  /// let mut parser = (alpha1::<_, Error<_>>, newline, digit1);
+  /// assert_eq!(parser.parse_all("abc\n12").unwrap(), ("abc", '\n', "12"));
+  ///
+  /// let e = parser.parse_all("abc\n12;").unwrap_err();
+  /// assert_eq!(e.error(), &Error::new(6, ErrorKind::Eof));
+  /// assert_eq!(e.line_col(), Some((2, 3)));
+  /// assert_eq!(e.to_string(), "2:3: error Eof at: 6\n12;\n  ^");
+  /// ```
+  #[cfg(feature = "alloc")]
+  #[cfg_attr(feature = "docsrs", doc(cfg(feature = "alloc")))]
+  fn parse_all(
+    &mut self,
+    input: Input,
+  ) -> error::ParseReport<Self::Output, <Self::Error as error::MapInput<Input>>::Mapped<usize>>
+  where
+    Input: crate::traits::Input + crate::traits::Offset + crate::traits::AsBytes,
+    Self::Error: error::MapInput<Input>,
+  {
+    let e = match self.parse_complete(input.clone()) {
+      Ok((rest, output)) if rest.input_len() == 0 => return Ok(output),
+      Ok((rest, _)) => Self::Error::from_error_kind(rest, ErrorKind::Eof),
+      Err(Err::Error(e)) | Err(Err::Failure(e)) => e,
+      Err(Err::Incomplete(_)) => Self::Error::from_error_kind(input.clone(), ErrorKind::Complete),
+    };
+    Err(error::SourceError::new(&input, e))
+  }
+
   /// A parser takes in input type, and returns a `Result` containing
   /// either the remaining input and the output value, or an error
   fn process<OM: OutputMode>(
@@ -483,6 +642,26 @@ pub trait Parser<Input> {
     And { f: self, g }
   }
 
+  /// Applies a second parser after the first one and discards its result, like
+  /// [terminated](crate::sequence::terminated)
+  fn then_skip<G>(self, g: G) -> Terminated<Self, G>
+  where
+    G: Parser<Input, Error = Self::Error>,
+    Self: core::marker::Sized,
+  {
+    Terminated { f: self, g }
+  }
+
+  /// Discards the result of the first parser and applies a second parser after it, like
+  /// [preceded](crate::sequence::preceded)
+  fn skip_then<G>(self, g: G) -> Preceded<Self, G>
+  where
+    G: Parser<Input, Error = Self::Error>,
+    Self: core::marker::Sized,
+  {
+    Preceded { f: self, g }
+  }
+
   /// Applies a second parser over the input if the first one failed
   fn or<G>(self, g: G) -> Or<Self, G>
   where
@@ -492,6 +671,155 @@ pub trait Parser<Input> {
     Or { f: self, g }
   }
 
+  /// Applies the parser again on a normalized version of the input if it failed, returning
+  /// `true` with the output if it had to be normalized
+  ///
+  /// `normalize` receives the input given to the parser, and returns its normalized version,
+  /// or `None` if it cannot be fixed. The parser is retried once, and if it fails again, the
+  /// error of the first attempt is returned. The rest of the input then comes from the
+  /// normalized version. `Failure` and `Incomplete` results are returned without retrying.
+  ///
+  /// ```rust
+  /// # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+  /// use nom::character::complete::digit1;
+  ///
+  /// fn number(i: &str) -> IResult<&str, (&str, bool)> {
+  ///   digit1
+  ///     .retry_with(|i: &&str| {
+  ///       let trimmed = i.trim_start_matches(|c: char| c.is_control());
+  ///       (trimmed.len() != i.len()).then_some(trimmed)
+  ///     })
+  ///     .parse(i)
+  /// }
+  ///
+  /// assert_eq!(number("12;"), Ok((";", ("12", false))));
+  /// assert_eq!(number("\u{7}\u{0}12;"), Ok((";", ("12", true))));
+  /// assert_eq!(number("\u{7}a"), Err(Err::Error(Error::new("\u{7}a", ErrorKind::Digit))));
+  /// ```
+  fn retry_with<N>(self, normalize: N) -> RetryWith<Self, N>
+  where
+    N: FnMut(&Input) -> Option<Input>,
+    Self: core::marker::Sized,
+  {
+    RetryWith { f: self, normalize }
+  }
+
+  /// Rejects lengths and counts larger than `max`, with [ErrorKind::TooLarge]
+  ///
+  /// This is meant for parsers reading a length or count prefix from untrusted data, before
+  /// it is used to size a buffer or repeat a parser, as in
+  /// [length_count](crate::multi::length_count), [length_data](crate::multi::length_data), or
+  /// [count](crate::multi::count) through [flat_map](Parser::flat_map). A prefix like
+  /// `0xFFFFFFFF` then returns an error at its position, instead of requesting gigabytes of
+  /// data or memory.
+  ///
+  /// ```rust
+  /// # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+  /// use nom::multi::count;
+  /// use nom::number::complete::{be_u16, be_u32};
+  ///
+  /// fn parser(s: &[u8]) -> IResult<&[u8], Vec<u16>> {
+  ///   be_u32.with_allocation_limit(1024).flat_map(|n| count(be_u16, n as usize)).parse(s)
+  /// }
+  ///
+  /// assert_eq!(parser(b"\x00\x00\x00\x02\x00\x01\x00\x02"), Ok((&b""[..], vec![1, 2])));
+  /// assert_eq!(
+  ///   parser(b"\xff\xff\xff\xff\x00\x01"),
+  ///   Err(Err::Error(Error::new(&b"\xff\xff\xff\xff\x00\x01"[..], ErrorKind::TooLarge)))
+  /// );
+  /// ```
+  fn with_allocation_limit(self, max: usize) -> AllocationLimit<Self>
+  where
+    Self::Output: ToUsize,
+    Self: core::marker::Sized,
+  {
+    AllocationLimit { f: self, max }
+  }
+
+  /// Applies the parser and discards its output, keeping its errors
+  ///
+  /// The parser runs without producing its output, like in
+  /// [recognize](crate::combinator::recognize), so `void` can make the output types of
+  /// several parsers match cheaply.
+  ///
+  /// ```rust
+  /// # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+  /// use nom::branch::alt;
+  /// use nom::bytes::complete::tag;
+  /// use nom::character::complete::{char, digit1};
+  ///
+  /// fn separator(i: &str) -> IResult<&str, ()> {
+  ///   alt((char(',').void(), tag("=>").void(), digit1.void())).parse(i)
+  /// }
+  ///
+  /// assert_eq!(separator("=>1"), Ok(("1", ())));
+  /// assert_eq!(separator(";"), Err(Err::Error(Error::new(";", ErrorKind::Digit))));
+  /// ```
+  fn void(self) -> Void<Self>
+  where
+    Self: core::marker::Sized,
+  {
+    Void { f: self }
+  }
+
+  /// Returns the input consumed by the parser with its output, like
+  /// [consumed](crate::combinator::consumed)
+  ///
+  /// This keeps the original text of a value in one pass, for formatters or editors that
+  /// must preserve how it was written.
+  ///
+  /// ```rust
+  /// # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+  /// use nom::character::complete::i32;
+  ///
+  /// fn number(i: &str) -> IResult<&str, (&str, i32)> {
+  ///   i32.with_recognized().parse(i)
+  /// }
+  ///
+  /// assert_eq!(number("+0012;"), Ok((";", ("+0012", 12))));
+  /// assert_eq!(number(";"), Err(Err::Error(Error::new(";", ErrorKind::Digit))));
+  /// ```
+  fn with_recognized(self) -> Consumed<Self>
+  where
+    Input: Clone + crate::traits::Offset + crate::traits::Input,
+    Self: core::marker::Sized,
+  {
+    Consumed { parser: self }
+  }
+
+  /// Sends the input and the result of each application of the parser to the sink set
+  /// with [set_dbg_sink](crate::combinator::set_dbg_sink), prefixed with `label`
+  ///
+  /// This works for any input implementing `Debug`, and the input and errors are cut to
+  /// their first 64 characters. Without the `debug` feature, the parser is only applied,
+  /// so `dbg` can be left in the grammar. See [inspect](crate::combinator::inspect) to
+  /// call hooks instead.
+  ///
+  /// ```rust
+  /// # use nom::{IResult, Parser};
+  /// use nom::character::complete::{alpha1, digit1};
+  ///
+  /// fn word(i: &str) -> IResult<&str, (&str, &str)> {
+  ///   (alpha1.dbg("alpha"), digit1.dbg("digit")).parse(i)
+  /// }
+  ///
+  /// // With the `debug` feature, prints to stderr:
+  /// // alpha: "abc123;" -> ok, rest "123;"
+  /// // digit: "123;" -> ok, rest ";"
+  /// assert_eq!(word("abc123;"), Ok((";", ("abc", "123"))));
+  /// ```
+  fn dbg(self, label: &'static str) -> Dbg<Self>
+  where
+    Input: Clone + fmt::Debug,
+    Self::Error: fmt::Debug,
+    Self: core::marker::Sized,
+  {
+    Dbg {
+      parser: self,
+      label,
+    }
+  }
+
   /// automatically converts the parser's output and error values to another type, as long as they
   /// implement the `From` trait
   fn into<O2: From<Self::Output>, E2: From<Self::Error>>(self) -> Into<Self, O2, E2>
@@ -560,7 +888,16 @@ macro_rules! impl_parser_for_tuples {
     }
 }
 
-impl_parser_for_tuples!(P1 O1, P2 O2, P3 O3, P4 O4, P5 O5, P6 O6, P7 O7, P8 O8, P9 O9, P10 O10, P11 O11, P12 O12, P13 O13, P14 O14, P15 O15, P16 O16, P17 O17, P18 O18, P19 O19, P20 O20, P21 O21);
+impl_parser_for_tuples!(
+  P1 O1, P2 O2, P3 O3, P4 O4, P5 O5, P6 O6, P7 O7, P8 O8,
+  P9 O9, P10 O10, P11 O11, P12 O12, P13 O13, P14 O14, P15 O15, P16 O16,
+  P17 O17, P18 O18, P19 O19, P20 O20, P21 O21, P22 O22, P23 O23, P24 O24,
+  P25 O25, P26 O26, P27 O27, P28 O28, P29 O29, P30 O30, P31 O31, P32 O32,
+  P33 O33, P34 O34, P35 O35, P36 O36, P37 O37, P38 O38, P39 O39, P40 O40,
+  P41 O41, P42 O42, P43 O43, P44 O44, P45 O45, P46 O46, P47 O47, P48 O48,
+  P49 O49, P50 O50, P51 O51, P52 O52, P53 O53, P54 O54, P55 O55, P56 O56,
+  P57 O57, P58 O58, P59 O59, P60 O60, P61 O61, P62 O62, P63 O63, P64 O64
+);
 
 /*
 #[cfg(feature = "alloc")]
@@ -753,6 +1090,79 @@ impl<
   }
 }
 
+/// Implementation of `Parser::retry_with`
+pub struct RetryWith<F, N> {
+  f: F,
+  normalize: N,
+}
+
+impl<I: Clone, F: Parser<I>, N: FnMut(&I) -> Option<I>> Parser<I> for RetryWith<F, N> {
+  type Output = (<F as Parser<I>>::Output, bool);
+  type Error = <F as Parser<I>>::Error;
+
+  fn process<OM: OutputMode>(&mut self, i: I) -> PResult<OM, I, Self::Output, Self::Error> {
+    match self.f.process::<OM>(i.clone()) {
+      Ok((i, o)) => Ok((i, OM::Output::map(o, |o| (o, false)))),
+      Err(Err::Error(e)) => match (self.normalize)(&i) {
+        None => Err(Err::Error(e)),
+        Some(normalized) => match self.f.process::<OM>(normalized) {
+          Ok((i, o)) => Ok((i, OM::Output::map(o, |o| (o, true)))),
+          Err(Err::Error(_)) => Err(Err::Error(e)),
+          Err(e) => Err(e),
+        },
+      },
+      Err(Err::Failure(e)) => Err(Err::Failure(e)),
+      Err(Err::Incomplete(n)) => Err(Err::Incomplete(n)),
+    }
+  }
+}
+
+/// Implementation of `Parser::with_allocation_limit`
+pub struct AllocationLimit<F> {
+  f: F,
+  max: usize,
+}
+
+impl<I: Clone, F: Parser<I>> Parser<I> for AllocationLimit<F>
+where
+  <F as Parser<I>>::Output: ToUsize,
+{
+  type Output = <F as Parser<I>>::Output;
+  type Error = <F as Parser<I>>::Error;
+
+  fn process<OM: OutputMode>(&mut self, i: I) -> PResult<OM, I, Self::Output, Self::Error> {
+    let (input, o) = self
+      .f
+      .process::<OutputM<Emit, OM::Error, OM::Incomplete>>(i.clone())?;
+
+    if o.to_usize() > self.max {
+      Err(Err::Error(OM::Error::bind(|| {
+        <F as Parser<I>>::Error::from_error_kind(i, ErrorKind::TooLarge)
+      })))
+    } else {
+      Ok((input, OM::Output::bind(|| o)))
+    }
+  }
+}
+
+/// Implementation of `Parser::void`
+pub struct Void<F> {
+  f: F,
+}
+
+impl<I, F: Parser<I>> Parser<I> for Void<F> {
+  type Output = ();
+  type Error = <F as Parser<I>>::Error;
+
+  #[inline(always)]
+  fn process<OM: OutputMode>(&mut self, i: I) -> PResult<OM, I, Self::Output, Self::Error> {
+    let (i, _) = self
+      .f
+      .process::<OutputM<Check, OM::Error, OM::Incomplete>>(i)?;
+    Ok((i, OM::Output::bind(|| ())))
+  }
+}
+
 /// Implementation of `Parser::into`
 pub struct Into<F, O2, E2> {
   f: F,
@@ -856,4 +1266,98 @@ mod tests {
       Err(Err::Error(error_position!(&b"jk"[..], ErrorKind::Tag)))
     );
   }
+
+  #[test]
+  fn skip_methods_test() {
+    fn key_value(i: &[u8]) -> IResult<&[u8], (&[u8], u16)> {
+      take(3u8)
+        .then_skip(tag("="))
+        .and(tag("0x").skip_then(be_u16))
+        .parse(i)
+    }
+
+    assert_eq!(
+      key_value(&b"abc=0xdefg"[..]),
+      Ok((&b"fg"[..], (&b"abc"[..], 0x6465u16)))
+    );
+    assert_eq!(
+      key_value(&b"abc:0xdefg"[..]),
+      Err(Err::Error(error_position!(&b":0xdefg"[..], ErrorKind::Tag)))
+    );
+    assert_eq!(
+      key_value(&b"abc=0"[..]),
+      Err(Err::Incomplete(Needed::new(1)))
+    );
+  }
+
+  #[test]
+  fn void_test() {
+    let mut parser = take::<_, _, (&[u8], ErrorKind)>(3u8).void().and(tag("de"));
+    assert_eq!(
+      parser.parse(&b"abcdefg"[..]),
+      Ok((&b"fg"[..], ((), &b"de"[..])))
+    );
+    assert_eq!(
+      parser.parse(&b"abcxx"[..]),
+      Err(Err::Error(error_position!(&b"xx"[..], ErrorKind::Tag)))
+    );
+    assert_eq!(
+      tag::<_, _, (&[u8], ErrorKind)>("ab")
+        .void()
+        .parse(&b"a"[..]),
+      Err(Err::Incomplete(Needed::new(1)))
+    );
+  }
+
+  #[test]
+  fn retry_with_test() {
+    let mut calls = 0;
+    let mut parser = terminated(be_u16::<_, (&[u8], ErrorKind)>, tag(";")).retry_with(|i| {
+      calls += 1;
+      i.strip_prefix(&b"\x00"[..])
+    });
+    assert_eq!(
+      parser.parse(&b"\x01\x02;"[..]),
+      Ok((&b""[..], (0x0102, false)))
+    );
+    assert_eq!(
+      parser.parse(&b"\x00\x01\x02;"[..]),
+      Ok((&b""[..], (0x0102, true)))
+    );
+    // the error of the first attempt is returned
+    assert_eq!(
+      parser.parse(&b"\x00\x01\x02:"[..]),
+      Err(Err::Error(error_position!(&b"\x02:"[..], ErrorKind::Tag)))
+    );
+    assert_eq!(
+      parser.parse(&b"\x01\x02:"[..]),
+      Err(Err::Error(error_position!(&b":"[..], ErrorKind::Tag)))
+    );
+    // Incomplete is not retried
+    assert_eq!(
+      parser.parse(&b"\x00\x01"[..]),
+      Err(Err::Incomplete(Needed::new(1)))
+    );
+    drop(parser);
+    assert_eq!(calls, 3);
+  }
+
+  #[test]
+  fn with_recognized_test() {
+    use crate::number::complete::double_with_str;
+
+    let mut parser = double_with_str::<_, (&str, ErrorKind)>
+      .map(|(s, f)| (s, f * 2.0))
+      .with_recognized();
+    assert_eq!(parser.parse("1E3;"), Ok((";", ("1E3", ("1E3", 2000.0)))));
+
+    // the recognized input is not built in check mode
+    let mut parser =
+      crate::combinator::recognize(tag::<_, _, (&[u8], ErrorKind)>("ab").with_recognized());
+    assert_eq!(parser.parse(&b"abc"[..]), Ok((&b"c"[..], &b"ab"[..])));
+    assert_eq!(
+      parser.parse(&b"a"[..]),
+      Err(Err::Incomplete(Needed::new(1)))
+    );
+  }
 }
@@ -379,6 +379,8 @@
 #![cfg_attr(feature = "docsrs", feature(doc_cfg))]
 #![allow(clippy::doc_markdown)]
 #![deny(missing_docs)]
+// the tuple implementations of `alt`, `permutation` and sequences recurse once per element
+#![recursion_limit = "256"]
 #[cfg(feature = "alloc")]
 #[macro_use]
 extern crate alloc;
@@ -402,7 +404,7 @@ pub mod lib {
 
     #[cfg(feature = "alloc")]
     #[doc(hidden)]
-    pub use alloc::{borrow, boxed, string, vec};
+    pub use alloc::{borrow, boxed, collections, string, vec};
 
     #[doc(hidden)]
     pub use core::{cmp, convert, fmt, iter, mem, num, ops, option, result, slice, str};
@@ -455,6 +457,58 @@ mod str;
 
 pub mod number;
 
+pub mod record;
+
+pub mod generate;
+
+pub mod indent;
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "alloc")))]
+pub mod string;
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "alloc")))]
+pub mod incremental;
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "alloc")))]
+pub mod formats;
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "alloc")))]
+pub mod rfc;
+
+pub mod const_eval;
+
+#[cfg(feature = "std")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "std")))]
+pub mod test;
+
+#[cfg(feature = "std")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "std")))]
+pub mod stream;
+
+#[cfg(feature = "std")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "std")))]
+pub mod parallel;
+
+#[cfg(feature = "regex")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "regex")))]
+pub mod regexp;
+
+#[cfg(feature = "accounting")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "accounting")))]
+pub mod accounting;
+
+#[cfg(feature = "datetime")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "datetime")))]
+pub mod datetime;
+
+#[cfg(feature = "json")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "json")))]
+pub mod batteries;
+
 #[cfg(all(feature = "std", any(doc, doctest, feature = "docsrs")))]
 #[cfg_attr(any(doc, doctest, feature = "docsrs"), doc = include_str!("../doc/nom_recipes.md"))]
 pub mod recipes {}
@@ -20,4 +20,47 @@ macro_rules! succ (
   (18, $submac:ident ! ($($rest:tt)*)) => ($submac!(19, $($rest)*));
   (19, $submac:ident ! ($($rest:tt)*)) => ($submac!(20, $($rest)*));
   (20, $submac:ident ! ($($rest:tt)*)) => ($submac!(21, $($rest)*));
+  (21, $submac:ident ! ($($rest:tt)*)) => ($submac!(22, $($rest)*));
+  (22, $submac:ident ! ($($rest:tt)*)) => ($submac!(23, $($rest)*));
+  (23, $submac:ident ! ($($rest:tt)*)) => ($submac!(24, $($rest)*));
+  (24, $submac:ident ! ($($rest:tt)*)) => ($submac!(25, $($rest)*));
+  (25, $submac:ident ! ($($rest:tt)*)) => ($submac!(26, $($rest)*));
+  (26, $submac:ident ! ($($rest:tt)*)) => ($submac!(27, $($rest)*));
+  (27, $submac:ident ! ($($rest:tt)*)) => ($submac!(28, $($rest)*));
+  (28, $submac:ident ! ($($rest:tt)*)) => ($submac!(29, $($rest)*));
+  (29, $submac:ident ! ($($rest:tt)*)) => ($submac!(30, $($rest)*));
+  (30, $submac:ident ! ($($rest:tt)*)) => ($submac!(31, $($rest)*));
+  (31, $submac:ident ! ($($rest:tt)*)) => ($submac!(32, $($rest)*));
+  (32, $submac:ident ! ($($rest:tt)*)) => ($submac!(33, $($rest)*));
+  (33, $submac:ident ! ($($rest:tt)*)) => ($submac!(34, $($rest)*));
+  (34, $submac:ident ! ($($rest:tt)*)) => ($submac!(35, $($rest)*));
+  (35, $submac:ident ! ($($rest:tt)*)) => ($submac!(36, $($rest)*));
+  (36, $submac:ident ! ($($rest:tt)*)) => ($submac!(37, $($rest)*));
+  (37, $submac:ident ! ($($rest:tt)*)) => ($submac!(38, $($rest)*));
+  (38, $submac:ident ! ($($rest:tt)*)) => ($submac!(39, $($rest)*));
+  (39, $submac:ident ! ($($rest:tt)*)) => ($submac!(40, $($rest)*));
+  (40, $submac:ident ! ($($rest:tt)*)) => ($submac!(41, $($rest)*));
+  (41, $submac:ident ! ($($rest:tt)*)) => ($submac!(42, $($rest)*));
+  (42, $submac:ident ! ($($rest:tt)*)) => ($submac!(43, $($rest)*));
+  (43, $submac:ident ! ($($rest:tt)*)) => ($submac!(44, $($rest)*));
+  (44, $submac:ident ! ($($rest:tt)*)) => ($submac!(45, $($rest)*));
+  (45, $submac:ident ! ($($rest:tt)*)) => ($submac!(46, $($rest)*));
+  (46, $submac:ident ! ($($rest:tt)*)) => ($submac!(47, $($rest)*));
+  (47, $submac:ident ! ($($rest:tt)*)) => ($submac!(48, $($rest)*));
+  (48, $submac:ident ! ($($rest:tt)*)) => ($submac!(49, $($rest)*));
+  (49, $submac:ident ! ($($rest:tt)*)) => ($submac!(50, $($rest)*));
+  (50, $submac:ident ! ($($rest:tt)*)) => ($submac!(51, $($rest)*));
+  (51, $submac:ident ! ($($rest:tt)*)) => ($submac!(52, $($rest)*));
+  (52, $submac:ident ! ($($rest:tt)*)) => ($submac!(53, $($rest)*));
+  (53, $submac:ident ! ($($rest:tt)*)) => ($submac!(54, $($rest)*));
+  (54, $submac:ident ! ($($rest:tt)*)) => ($submac!(55, $($rest)*));
+  (55, $submac:ident ! ($($rest:tt)*)) => ($submac!(56, $($rest)*));
+  (56, $submac:ident ! ($($rest:tt)*)) => ($submac!(57, $($rest)*));
+  (57, $submac:ident ! ($($rest:tt)*)) => ($submac!(58, $($rest)*));
+  (58, $submac:ident ! ($($rest:tt)*)) => ($submac!(59, $($rest)*));
+  (59, $submac:ident ! ($($rest:tt)*)) => ($submac!(60, $($rest)*));
+  (60, $submac:ident ! ($($rest:tt)*)) => ($submac!(61, $($rest)*));
+  (61, $submac:ident ! ($($rest:tt)*)) => ($submac!(62, $($rest)*));
+  (62, $submac:ident ! ($($rest:tt)*)) => ($submac!(63, $($rest)*));
+  (63, $submac:ident ! ($($rest:tt)*)) => ($submac!(64, $($rest)*));
 );
@@ -6,12 +6,20 @@ mod tests;
 use core::marker::PhantomData;
 
 use crate::bytes::take;
+use crate::error::ContextError;
 use crate::error::ErrorKind;
+#[cfg(feature = "alloc")]
+use crate::error::FromExternalError;
 use crate::error::ParseError;
 use crate::internal::{Err, Needed, Parser};
+#[cfg(feature = "alloc")]
+use crate::lib::std::collections::BTreeMap;
+use crate::lib::std::fmt;
 use crate::lib::std::num::NonZeroUsize;
 #[cfg(feature = "alloc")]
 use crate::lib::std::vec::Vec;
+#[cfg(feature = "alloc")]
+use crate::traits::Delta;
 use crate::traits::ToUsize;
 use crate::Check;
 use crate::Emit;
@@ -33,6 +41,60 @@ use crate::OutputMode;
 #[cfg(feature = "alloc")]
 const MAX_INITIAL_CAPACITY_BYTES: usize = 65536;
 
+/// Creates the vector built by `combinator`, reporting the reserved memory with the
+/// `accounting` feature
+#[cfg(feature = "alloc")]
+#[cfg_attr(not(feature = "accounting"), allow(unused_variables))]
+fn reserve<T>(combinator: &'static str, capacity: usize) -> Vec<T> {
+  let res = Vec::with_capacity(capacity);
+  #[cfg(feature = "accounting")]
+  crate::accounting::record(
+    combinator,
+    res.capacity() * crate::lib::std::mem::size_of::<T>(),
+  );
+  res
+}
+
+/// Adds an element to the vector built by `combinator`, reporting its growth with the
+/// `accounting` feature
+#[cfg(feature = "alloc")]
+fn push<T>(combinator: &'static str, res: &mut Vec<T>, value: T) {
+  extend(combinator, res, Some(value));
+}
+
+/// Adds elements to the vector built by `combinator`, reporting its growth with the
+/// `accounting` feature
+#[cfg(feature = "alloc")]
+#[cfg_attr(not(feature = "accounting"), allow(unused_variables))]
+fn extend<T, It: IntoIterator<Item = T>>(combinator: &'static str, res: &mut Vec<T>, values: It) {
+  #[cfg(feature = "accounting")]
+  let capacity = res.capacity();
+  res.extend(values);
+  #[cfg(feature = "accounting")]
+  crate::accounting::record(
+    combinator,
+    (res.capacity() - capacity) * crate::lib::std::mem::size_of::<T>(),
+  );
+}
+
+/// Builds the error returned when a repeated parser does not consume input, which would
+/// otherwise loop forever
+///
+/// By default, this is a recoverable error with the kind of the combinator. With the
+/// `checked` feature, it is a failure with [ErrorKind::InfiniteLoop] at the position where
+/// the parser stopped consuming, so that an `alt` or `opt` around the repetition cannot
+/// hide the bug.
+fn infinite_loop<OM: OutputMode, I, E: ParseError<I>>(
+  input: I,
+  kind: ErrorKind,
+) -> Err<E, <<OM as OutputMode>::Error as Mode>::Output<E>> {
+  if cfg!(feature = "checked") {
+    Err::Failure(E::from_error_kind(input, ErrorKind::InfiniteLoop))
+  } else {
+    Err::Error(OM::Error::bind(|| E::from_error_kind(input, kind)))
+  }
+}
+
 /// Repeats the embedded parser, gathering the results in a `Vec`.
 ///
 /// This stops on [`Err::Error`] and returns the results that were accumulated. To instead chain an error up, see
@@ -89,7 +151,7 @@ where
     &mut self,
     mut i: I,
   ) -> crate::PResult<OM, I, Self::Output, Self::Error> {
-    let mut acc = OM::Output::bind(|| crate::lib::std::vec::Vec::with_capacity(4));
+    let mut acc = OM::Output::bind(|| reserve("many0", 4));
     loop {
       let len = i.input_len();
       match self
@@ -102,15 +164,13 @@ where
         Ok((i1, o)) => {
           // infinite loop check: the parser must always consume
           if i1.input_len() == len {
-            return Err(Err::Error(OM::Error::bind(|| {
-              <F as Parser<I>>::Error::from_error_kind(i, ErrorKind::Many0)
-            })));
+            return Err(infinite_loop::<OM, _, _>(i, ErrorKind::Many0));
           }
 
           i = i1;
 
           acc = OM::Output::combine(acc, o, |mut acc, o| {
-            acc.push(o);
+            push("many0", &mut acc, o);
             acc
           })
         }
@@ -187,8 +247,8 @@ where
       Err(Err::Incomplete(i)) => Err(Err::Incomplete(i)),
       Ok((i1, o)) => {
         let mut acc = OM::Output::map(o, |o| {
-          let mut acc = crate::lib::std::vec::Vec::with_capacity(4);
-          acc.push(o);
+          let mut acc = reserve("many1", 4);
+          push("many1", &mut acc, o);
           acc
         });
 
@@ -206,15 +266,13 @@ where
             Ok((i1, o)) => {
               // infinite loop check: the parser must always consume
               if i1.input_len() == len {
-                return Err(Err::Error(OM::Error::bind(|| {
-                  <F as Parser<I>>::Error::from_error_kind(i, ErrorKind::Many0)
-                })));
+                return Err(infinite_loop::<OM, _, _>(i, ErrorKind::Many0));
               }
 
               i = i1;
 
               acc = OM::Output::combine(acc, o, |mut acc, o| {
-                acc.push(o);
+                push("many1", &mut acc, o);
                 acc
               })
             }
@@ -288,7 +346,7 @@ where
     &mut self,
     mut i: I,
   ) -> crate::PResult<OM, I, Self::Output, Self::Error> {
-    let mut res = OM::Output::bind(crate::lib::std::vec::Vec::new);
+    let mut res = OM::Output::bind(|| reserve("many_till", 0));
     loop {
       let len = i.input_len();
       match self
@@ -310,15 +368,13 @@ where
             Ok((i1, o)) => {
               // infinite loop check: the parser must always consume
               if i1.input_len() == len {
-                return Err(Err::Error(OM::Error::bind(|| {
-                  E::from_error_kind(i, ErrorKind::Many0)
-                })));
+                return Err(infinite_loop::<OM, _, _>(i, ErrorKind::Many0));
               }
 
               i = i1;
 
               res = OM::Output::combine(res, o, |mut acc, o| {
-                acc.push(o);
+                push("many_till", &mut acc, o);
                 acc
               })
             }
@@ -329,6 +385,200 @@ where
   }
 }
 
+/// Applies the parser `f` between `min` and `max` times until the parser `g` produces a
+/// result.
+///
+/// Returns a tuple of the results of `f` in a `Vec` and the result of `g`. This returns
+/// an error with [ErrorKind::ManyTill] if `g` succeeds before `min` results of `f`, or
+/// if it still fails after `max` results, so a stream without the terminator cannot
+/// grow the `Vec` indefinitely.
+///
+/// # Arguments
+/// * `min` The minimum number of results of `f`.
+/// * `max` The maximum number of results of `f`.
+/// * `f` The parser to apply repeatedly.
+/// * `g` The terminator.
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Needed, IResult, Parser};
+/// use nom::multi::many_till_m_n;
+/// use nom::bytes::complete::tag;
+///
+/// fn parser(s: &str) -> IResult<&str, (Vec<&str>, &str)> {
+///   many_till_m_n(1, 2, tag("abc"), tag("end")).parse(s)
+/// };
+///
+/// assert_eq!(parser("abcabcend"), Ok(("", (vec!["abc", "abc"], "end"))));
+/// assert_eq!(parser("end"), Err(Err::Error(Error::new("end", ErrorKind::ManyTill))));
+/// assert_eq!(
+///   parser("abcabcabcend"),
+///   Err(Err::Error(Error::new("abcend", ErrorKind::ManyTill)))
+/// );
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "alloc")))]
+pub fn many_till_m_n<I, E, F, G>(
+  min: usize,
+  max: usize,
+  f: F,
+  g: G,
+) -> impl Parser<I, Output = (Vec<<F as Parser<I>>::Output>, <G as Parser<I>>::Output), Error = E>
+where
+  I: Clone + Input,
+  F: Parser<I, Error = E>,
+  G: Parser<I, Error = E>,
+  E: ParseError<I>,
+{
+  FoldManyTill {
+    f,
+    g,
+    init: Vec::new,
+    fold: |mut acc: Vec<_>, o| {
+      acc.push(o);
+      acc
+    },
+    min,
+    max,
+    r: PhantomData,
+  }
+}
+
+/// Applies the parser `f` until the parser `g` produces a result, calling `fold` to
+/// gather the results of `f`.
+///
+/// Returns a tuple of the accumulated value and the result of `g`. This is [many_till]
+/// without the `Vec`, for data that can be processed as it is parsed.
+///
+/// `f` keeps going so long as `g` produces [`Err::Error`]. To instead chain an error up,
+/// see [`cut`][crate::combinator::cut].
+///
+/// # Arguments
+/// * `f` The parser to apply repeatedly.
+/// * `g` The terminator.
+/// * `init` A function returning the initial value.
+/// * `fold` The function that combines a result of `f` with the current accumulator.
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Needed, IResult, Parser};
+/// use nom::multi::fold_many_till;
+/// use nom::bytes::complete::tag;
+/// use nom::character::complete::{digit1, char};
+/// use nom::combinator::map_res;
+/// use nom::sequence::terminated;
+///
+/// fn sum(s: &str) -> IResult<&str, (u32, &str)> {
+///   fold_many_till(
+///     terminated(map_res(digit1, str::parse::<u32>), char(',')),
+///     tag("end"),
+///     || 0,
+///     |acc, n| acc + n,
+///   ).parse(s)
+/// };
+///
+/// assert_eq!(sum("1,2,3,end!"), Ok(("!", (6, "end"))));
+/// assert_eq!(sum("end"), Ok(("", (0, "end"))));
+/// assert_eq!(sum("1,2"), Err(Err::Error(Error::new("", ErrorKind::Char))));
+/// ```
+pub fn fold_many_till<I, E, F, G, H, J, R>(
+  f: F,
+  g: G,
+  init: H,
+  fold: J,
+) -> FoldManyTill<F, G, H, J, R>
+where
+  I: Clone + Input,
+  F: Parser<I, Error = E>,
+  G: Parser<I, Error = E>,
+  H: FnMut() -> R,
+  J: FnMut(R, <F as Parser<I>>::Output) -> R,
+  E: ParseError<I>,
+{
+  FoldManyTill {
+    f,
+    g,
+    init,
+    fold,
+    min: 0,
+    max: usize::MAX,
+    r: PhantomData,
+  }
+}
+
+/// Parser implementation for the [fold_many_till] and [many_till_m_n] combinators
+pub struct FoldManyTill<F, G, H, J, R> {
+  f: F,
+  g: G,
+  init: H,
+  fold: J,
+  min: usize,
+  max: usize,
+  r: PhantomData<R>,
+}
+
+impl<I, E, F, G, H, J, R> Parser<I> for FoldManyTill<F, G, H, J, R>
+where
+  I: Clone + Input,
+  F: Parser<I, Error = E>,
+  G: Parser<I, Error = E>,
+  H: FnMut() -> R,
+  J: FnMut(R, <F as Parser<I>>::Output) -> R,
+  E: ParseError<I>,
+{
+  type Output = (R, <G as Parser<I>>::Output);
+  type Error = E;
+
+  fn process<OM: OutputMode>(
+    &mut self,
+    mut i: I,
+  ) -> crate::PResult<OM, I, Self::Output, Self::Error> {
+    if self.min > self.max {
+      return Err(Err::Error(OM::Error::bind(|| {
+        E::from_error_kind(i, ErrorKind::ManyTill)
+      })));
+    }
+
+    let mut res = OM::Output::bind(|| (self.init)());
+    let mut count = 0;
+    loop {
+      let len = i.input_len();
+      match self
+        .g
+        .process::<OutputM<OM::Output, Check, OM::Incomplete>>(i.clone())
+      {
+        Ok(_) if count < self.min => {
+          return Err(Err::Error(OM::Error::bind(|| {
+            E::from_error_kind(i, ErrorKind::ManyTill)
+          })))
+        }
+        Ok((i1, o)) => return Ok((i1, OM::Output::combine(res, o, |res, o| (res, o)))),
+        Err(Err::Failure(e)) => return Err(Err::Failure(e)),
+        Err(Err::Incomplete(i)) => return Err(Err::Incomplete(i)),
+        Err(Err::Error(_)) if count == self.max => {
+          return Err(Err::Error(OM::Error::bind(|| {
+            E::from_error_kind(i, ErrorKind::ManyTill)
+          })))
+        }
+        Err(Err::Error(_)) => match self.f.process::<OM>(i.clone()) {
+          Err(Err::Error(err)) => {
+            return Err(Err::Error(OM::Error::map(err, |err| {
+              E::append(i, ErrorKind::ManyTill, err)
+            })))
+          }
+          Err(e) => return Err(e),
+          Ok((i1, o)) => {
+            // infinite loop check: the parser must always consume
+            if i1.input_len() == len {
+              return Err(infinite_loop::<OM, _, _>(i, ErrorKind::ManyTill));
+            }
+
+            i = i1;
+            count += 1;
+            res = OM::Output::combine(res, o, |acc, o| (self.fold)(acc, o));
+          }
+        },
+      }
+    }
+  }
+}
+
 /// Alternates between two parsers to produce a list of elements.
 ///
 /// This stops when either parser returns [`Err::Error`]  and returns the results that were accumulated. To instead chain an error up, see
@@ -392,7 +642,7 @@ where
     &mut self,
     mut i: I,
   ) -> crate::PResult<OM, I, Self::Output, Self::Error> {
-    let mut res = OM::Output::bind(crate::lib::std::vec::Vec::new);
+    let mut res = OM::Output::bind(|| reserve("separated_list0", 0));
 
     match self
       .parser
@@ -403,7 +653,7 @@ where
       Err(Err::Incomplete(e)) => return Err(Err::Incomplete(e)),
       Ok((i1, o)) => {
         res = OM::Output::combine(res, o, |mut res, o| {
-          res.push(o);
+          push("separated_list0", &mut res, o);
           res
         });
         i = i1;
@@ -430,13 +680,11 @@ where
             Ok((i2, o)) => {
               // infinite loop check: the parser must always consume
               if i2.input_len() == len {
-                return Err(Err::Error(OM::Error::bind(|| {
-                  <F as Parser<I>>::Error::from_error_kind(i, ErrorKind::SeparatedList)
-                })));
+                return Err(infinite_loop::<OM, _, _>(i, ErrorKind::SeparatedList));
               }
 
               res = OM::Output::combine(res, o, |mut res, o| {
-                res.push(o);
+                push("separated_list0", &mut res, o);
                 res
               });
 
@@ -510,13 +758,13 @@ where
     &mut self,
     mut i: I,
   ) -> crate::PResult<OM, I, Self::Output, Self::Error> {
-    let mut res = OM::Output::bind(crate::lib::std::vec::Vec::new);
+    let mut res = OM::Output::bind(|| reserve("separated_list1", 0));
 
     match self.parser.process::<OM>(i.clone()) {
       Err(e) => return Err(e),
       Ok((i1, o)) => {
         res = OM::Output::combine(res, o, |mut res, o| {
-          res.push(o);
+          push("separated_list1", &mut res, o);
           res
         });
         i = i1;
@@ -543,13 +791,11 @@ where
             Ok((i2, o)) => {
               // infinite loop check: the parser must always consume
               if i2.input_len() == len {
-                return Err(Err::Error(OM::Error::bind(|| {
-                  <F as Parser<I>>::Error::from_error_kind(i, ErrorKind::SeparatedList)
-                })));
+                return Err(infinite_loop::<OM, _, _>(i, ErrorKind::SeparatedList));
               }
 
               res = OM::Output::combine(res, o, |mut res, o| {
-                res.push(o);
+                push("separated_list1", &mut res, o);
                 res
               });
               i = i2;
@@ -561,440 +807,830 @@ where
   }
 }
 
-/// Repeats the embedded parser `m..=n` times
+/// Repeats a record parser, each record being followed by a terminator, like the lines of
+/// a log or a CSV file.
 ///
-/// This stops before `n` when the parser returns [`Err::Error`]  and returns the results that were accumulated. To instead chain an error up, see
-/// [`cut`][crate::combinator::cut].
+/// The terminator of the last record is optional at the end of a complete input, and an
+/// input ending with a terminator does not produce an additional empty record, even if
+/// `record` accepts an empty input. On streaming data, the end of the input is never the
+/// end of the records, so use [Parser::parse_complete] when the whole input is available.
+///
+/// This stops when either parser returns [`Err::Error`] and returns the results that were
+/// accumulated. To instead chain an error up, see [`cut`][crate::combinator::cut].
 ///
 /// # Arguments
-/// * `m` The minimum number of iterations.
-/// * `n` The maximum number of iterations.
-/// * `f` The parser to apply.
+/// * `record` Parses a record.
+/// * `terminator` Parses the end of a record, like [line_ending][crate::character::complete::line_ending].
 ///
-/// *Note*: If the parser passed to `many1` accepts empty inputs
-/// (like `alpha0` or `digit0`), `many1` will return an error,
-/// to prevent going into an infinite loop.
+/// *Note*: if the record and terminator parsers together accept empty inputs,
+/// `terminated_records` will return an error with [ErrorKind::Many0], to prevent going
+/// into an infinite loop.
 ///
 /// ```rust
 /// # use nom::{Err, error::ErrorKind, Needed, IResult, Parser};
-/// use nom::multi::many_m_n;
-/// use nom::bytes::complete::tag;
+/// use nom::multi::{separated_list0, terminated_records};
+/// use nom::character::complete::{alphanumeric0, char, line_ending};
 ///
-/// fn parser(s: &str) -> IResult<&str, Vec<&str>> {
-///   many_m_n(0, 2, tag("abc")).parse(s)
+/// fn csv(s: &str) -> IResult<&str, Vec<Vec<&str>>> {
+///   terminated_records(separated_list0(char(','), alphanumeric0), line_ending).parse_complete(s)
 /// }
 ///
-/// assert_eq!(parser("abcabc"), Ok(("", vec!["abc", "abc"])));
-/// assert_eq!(parser("abc123"), Ok(("123", vec!["abc"])));
-/// assert_eq!(parser("123123"), Ok(("123123", vec![])));
-/// assert_eq!(parser(""), Ok(("", vec![])));
-/// assert_eq!(parser("abcabcabc"), Ok(("abc", vec!["abc", "abc"])));
+/// assert_eq!(csv("a,b\nc,d\n"), Ok(("", vec![vec!["a", "b"], vec!["c", "d"]])));
+/// assert_eq!(csv("a,b\nc,d"), Ok(("", vec![vec!["a", "b"], vec!["c", "d"]])));
+/// assert_eq!(csv("a,b\n\nc"), Ok(("", vec![vec!["a", "b"], vec![""], vec!["c"]])));
+/// assert_eq!(csv("a,b;c"), Ok((";c", vec![vec!["a", "b"]])));
+/// assert_eq!(csv(""), Ok(("", vec![])));
 /// ```
 #[cfg(feature = "alloc")]
 #[cfg_attr(feature = "docsrs", doc(cfg(feature = "alloc")))]
-pub fn many_m_n<I, E, F>(
-  min: usize,
-  max: usize,
-  parser: F,
+pub fn terminated_records<I, E, F, G>(
+  record: F,
+  terminator: G,
 ) -> impl Parser<I, Output = Vec<<F as Parser<I>>::Output>, Error = E>
 where
   I: Clone + Input,
   F: Parser<I, Error = E>,
+  G: Parser<I, Error = E>,
   E: ParseError<I>,
 {
-  ManyMN { parser, min, max }
+  TerminatedRecords { record, terminator }
 }
 
 #[cfg(feature = "alloc")]
-/// Parser implementation for the [many_m_n] combinator
-pub struct ManyMN<F> {
-  parser: F,
-  min: usize,
-  max: usize,
+/// Parser implementation for the [terminated_records] combinator
+pub struct TerminatedRecords<F, G> {
+  record: F,
+  terminator: G,
 }
 
 #[cfg(feature = "alloc")]
-impl<I, F> Parser<I> for ManyMN<F>
+impl<I, E: ParseError<I>, F, G> Parser<I> for TerminatedRecords<F, G>
 where
   I: Clone + Input,
-  F: Parser<I>,
+  F: Parser<I, Error = E>,
+  G: Parser<I, Error = E>,
 {
   type Output = Vec<<F as Parser<I>>::Output>;
-  type Error = <F as Parser<I>>::Error;
+  type Error = E;
 
   fn process<OM: OutputMode>(
     &mut self,
-    mut input: I,
+    mut i: I,
   ) -> crate::PResult<OM, I, Self::Output, Self::Error> {
-    if self.min > self.max {
-      return Err(Err::Failure(<F as Parser<I>>::Error::from_error_kind(
-        input,
-        ErrorKind::ManyMN,
-      )));
-    }
+    let mut res = OM::Output::bind(|| reserve("terminated_records", 4));
 
-    let max_initial_capacity = MAX_INITIAL_CAPACITY_BYTES
-      / crate::lib::std::mem::size_of::<<F as Parser<I>>::Output>().max(1);
-    let mut res = OM::Output::bind(|| {
-      crate::lib::std::vec::Vec::with_capacity(self.min.min(max_initial_capacity))
-    });
-    for count in 0..self.max {
-      let len = input.input_len();
-      match self.parser.process::<OM>(input.clone()) {
-        Ok((tail, value)) => {
-          // infinite loop check: the parser must always consume
-          if tail.input_len() == len {
-            return Err(Err::Error(OM::Error::bind(|| {
-              <F as Parser<I>>::Error::from_error_kind(input, ErrorKind::ManyMN)
-            })));
-          }
+    loop {
+      // a terminator at the end of a complete input does not start a new record
+      if i.input_len() == 0 && !i.is_streaming::<OM>() {
+        return Ok((i, res));
+      }
 
-          res = OM::Output::combine(res, value, |mut res, value| {
-            res.push(value);
-            res
-          });
-          input = tail;
-        }
-        Err(Err::Error(e)) => {
-          if count < self.min {
-            return Err(Err::Error(OM::Error::map(e, |e| {
-              <F as Parser<I>>::Error::append(input, ErrorKind::ManyMN, e)
-            })));
-          } else {
-            return Ok((input, res));
+      let len = i.input_len();
+      let (i1, o) = match self
+        .record
+        .process::<OutputM<OM::Output, Check, OM::Incomplete>>(i.clone())
+      {
+        Err(Err::Error(_)) => return Ok((i, res)),
+        Err(Err::Failure(e)) => return Err(Err::Failure(e)),
+        Err(Err::Incomplete(e)) => return Err(Err::Incomplete(e)),
+        Ok(r) => r,
+      };
+
+      res = OM::Output::combine(res, o, |mut res, o| {
+        push("terminated_records", &mut res, o);
+        res
+      });
+
+      match self
+        .terminator
+        .process::<OutputM<Check, Check, OM::Incomplete>>(i1.clone())
+      {
+        // the last record may not be terminated
+        Err(Err::Error(_)) => return Ok((i1, res)),
+        Err(Err::Failure(e)) => return Err(Err::Failure(e)),
+        Err(Err::Incomplete(e)) => return Err(Err::Incomplete(e)),
+        Ok((i2, _)) => {
+          // infinite loop check: the parsers must always consume
+          if i2.input_len() == len {
+            return Err(infinite_loop::<OM, _, _>(i, ErrorKind::Many0));
           }
-        }
-        Err(e) => {
-          return Err(e);
+
+          i = i2;
         }
       }
     }
-
-    Ok((input, res))
   }
 }
 
-/// Repeats the embedded parser, counting the results
+/// Trailing separator policy of [separated_list]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Trailing {
+  /// A separator after the last element is not part of the list, like in
+  /// [separated_list0]
+  Forbid,
+  /// A separator after the last element is consumed if present, like in `[1, 2, 3,]`
+  Allow,
+  /// Every element must be followed by a separator, like in `a; b; c;`. An element
+  /// without a separator is not part of the list.
+  Require,
+}
+
+/// Alternates between two parsers to produce a list of elements, with a number of
+/// elements and a trailing separator policy.
 ///
-/// This stops on [`Err::Error`]. To instead chain an error up, see
-/// [`cut`][crate::combinator::cut].
+/// This stops when either parser returns [`Err::Error`], or when the maximum number of
+/// elements is reached. It fails if the number of elements is not within the range. To
+/// instead chain an error up, see [`cut`][crate::combinator::cut].
 ///
 /// # Arguments
-/// * `f` The parser to apply.
+/// * `range` Constrains the number of elements.
+///   * A range without an upper bound `a..` allows the parser to run until it fails.
+///   * A single `usize` value is equivalent to `value..=value`.
+///   * An empty range is invalid.
+/// * `trailing` Selects how a separator after the last element is handled.
+/// * `sep` Parses the separator between list elements.
+/// * `f` Parses the elements of the list.
 ///
-/// *Note*: if the parser passed in accepts empty inputs (like `alpha0` or `digit0`), `many0` will
-/// return an error, to prevent going into an infinite loop
+/// *Note*: if the separator and element parsers together accept empty inputs,
+/// `separated_list` will return an error with [ErrorKind::SeparatedList], to prevent
+/// going into an infinite loop.
 ///
 /// ```rust
-/// # use nom::{Err, error::ErrorKind, Needed, IResult, Parser};
-/// use nom::multi::many0_count;
-/// use nom::bytes::complete::tag;
+/// # use nom::{Err, error::{Error, ErrorKind}, Needed, IResult, Parser};
+/// use nom::multi::{separated_list, Trailing};
+/// use nom::character::complete::{alpha1, char};
 ///
-/// fn parser(s: &str) -> IResult<&str, usize> {
-///   many0_count(tag("abc")).parse(s)
+/// fn array(s: &str) -> IResult<&str, Vec<&str>> {
+///   separated_list(0.., Trailing::Allow, char(','), alpha1).parse(s)
 /// }
 ///
-/// assert_eq!(parser("abcabc"), Ok(("", 2)));
-/// assert_eq!(parser("abc123"), Ok(("123", 1)));
-/// assert_eq!(parser("123123"), Ok(("123123", 0)));
-/// assert_eq!(parser(""), Ok(("", 0)));
+/// assert_eq!(array("a,b,c]"), Ok(("]", vec!["a", "b", "c"])));
+/// assert_eq!(array("a,b,c,]"), Ok(("]", vec!["a", "b", "c"])));
+/// assert_eq!(array("]"), Ok(("]", vec![])));
+///
+/// fn statements(s: &str) -> IResult<&str, Vec<&str>> {
+///   separated_list(1..=3, Trailing::Require, char(';'), alpha1).parse(s)
+/// }
+///
+/// assert_eq!(statements("a;b;c"), Ok(("c", vec!["a", "b"])));
+/// assert_eq!(statements("a;b;c;d;"), Ok(("d;", vec!["a", "b", "c"])));
+/// // the separator after "a" is missing
+/// assert_eq!(statements("a"), Err(Err::Error(Error::new("", ErrorKind::Char))));
 /// ```
-pub fn many0_count<I, E, F>(parser: F) -> impl Parser<I, Output = usize, Error = E>
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "alloc")))]
+pub fn separated_list<I, E, F, G, R>(
+  range: R,
+  trailing: Trailing,
+  sep: G,
+  f: F,
+) -> impl Parser<I, Output = Vec<<F as Parser<I>>::Output>, Error = E>
 where
   I: Clone + Input,
   F: Parser<I, Error = E>,
+  G: Parser<I, Error = E>,
   E: ParseError<I>,
+  R: NomRange<usize>,
 {
-  Many0Count { parser }
+  SeparatedList {
+    parser: f,
+    separator: sep,
+    range,
+    trailing,
+  }
 }
 
-/// Parser implementation for the [many0_count] combinator
-pub struct Many0Count<F> {
+#[cfg(feature = "alloc")]
+/// Parser implementation for the [separated_list] combinator
+pub struct SeparatedList<F, G, R> {
   parser: F,
+  separator: G,
+  range: R,
+  trailing: Trailing,
 }
 
-impl<I, F> Parser<I> for Many0Count<F>
+#[cfg(feature = "alloc")]
+impl<I, E: ParseError<I>, F, G, R> Parser<I> for SeparatedList<F, G, R>
 where
   I: Clone + Input,
-  F: Parser<I>,
+  F: Parser<I, Error = E>,
+  G: Parser<I, Error = E>,
+  R: NomRange<usize>,
 {
-  type Output = usize;
-  type Error = <F as Parser<I>>::Error;
+  type Output = Vec<<F as Parser<I>>::Output>;
+  type Error = E;
 
   fn process<OM: OutputMode>(
     &mut self,
     mut input: I,
   ) -> crate::PResult<OM, I, Self::Output, Self::Error> {
+    if self.range.is_inverted() {
+      return Err(Err::Failure(E::from_error_kind(
+        input,
+        ErrorKind::SeparatedList,
+      )));
+    }
+
+    let mut res = OM::Output::bind(|| reserve("separated_list", 0));
     let mut count = 0;
+    let mut iterations = self.range.bounded_iter();
+
+    // the list ends at `end` when a parser returns an error
+    let (end, e) = loop {
+      if iterations.next().is_none() {
+        // the maximum number of elements was reached
+        if count > 0 && self.trailing == Trailing::Allow {
+          match self
+            .separator
+            .process::<OutputM<Check, Check, OM::Incomplete>>(input.clone())
+          {
+            Err(Err::Error(_)) => {}
+            Err(Err::Failure(e)) => return Err(Err::Failure(e)),
+            Err(Err::Incomplete(e)) => return Err(Err::Incomplete(e)),
+            Ok((i1, _)) => input = i1,
+          }
+        }
+        return Ok((input, res));
+      }
 
-    loop {
-      let input_ = input.clone();
       let len = input.input_len();
-      match self
+
+      // the separator before the element
+      let i1 = if count == 0 || self.trailing == Trailing::Require {
+        input.clone()
+      } else {
+        match self
+          .separator
+          .process::<OutputM<Check, OM::Error, OM::Incomplete>>(input.clone())
+        {
+          Err(Err::Error(e)) => break (input, e),
+          Err(Err::Failure(e)) => return Err(Err::Failure(e)),
+          Err(Err::Incomplete(e)) => return Err(Err::Incomplete(e)),
+          Ok((i1, _)) => i1,
+        }
+      };
+
+      let (i2, o) = match self
         .parser
-        .process::<OutputM<Check, Check, OM::Incomplete>>(input_)
+        .process::<OutputM<OM::Output, OM::Error, OM::Incomplete>>(i1.clone())
       {
-        Ok((i, _)) => {
-          // infinite loop check: the parser must always consume
-          if i.input_len() == len {
-            return Err(Err::Error(OM::Error::bind(|| {
-              <F as Parser<I>>::Error::from_error_kind(input, ErrorKind::Many0Count)
-            })));
-          }
+        // a separator already parsed is kept as trailing separator
+        Err(Err::Error(e)) if self.trailing == Trailing::Allow => break (i1, e),
+        Err(Err::Error(e)) => break (input, e),
+        Err(Err::Failure(e)) => return Err(Err::Failure(e)),
+        Err(Err::Incomplete(e)) => return Err(Err::Incomplete(e)),
+        Ok(res) => res,
+      };
 
-          input = i;
-          count += 1;
+      // the separator after the element
+      let i2 = if self.trailing == Trailing::Require {
+        match self
+          .separator
+          .process::<OutputM<Check, OM::Error, OM::Incomplete>>(i2)
+        {
+          Err(Err::Error(e)) => break (input, e),
+          Err(Err::Failure(e)) => return Err(Err::Failure(e)),
+          Err(Err::Incomplete(e)) => return Err(Err::Incomplete(e)),
+          Ok((i3, _)) => i3,
         }
+      } else {
+        i2
+      };
 
-        Err(Err::Error(_)) => return Ok((input, OM::Output::bind(|| count))),
-        Err(Err::Failure(e)) => return Err(Err::Failure(e)),
-        Err(Err::Incomplete(i)) => return Err(Err::Incomplete(i)),
+      // infinite loop check: the parsers must always consume
+      if i2.input_len() == len {
+        return Err(infinite_loop::<OM, _, _>(input, ErrorKind::SeparatedList));
       }
+
+      res = OM::Output::combine(res, o, |mut res, o| {
+        push("separated_list", &mut res, o);
+        res
+      });
+      input = i2;
+      count += 1;
+    };
+
+    if self.range.contains(&count) {
+      Ok((end, res))
+    } else {
+      Err(Err::Error(OM::Error::map(e, |e| {
+        E::append(end, ErrorKind::SeparatedList, e)
+      })))
     }
   }
 }
 
-/// Runs the embedded parser, counting the results.
-///
-/// This stops on [`Err::Error`] if there is at least one result. To instead chain an error up,
-/// see [`cut`][crate::combinator::cut].
+/// Splits the input into elements separated by `sep`, parsing each of them independently
+/// and returning their results.
 ///
-/// # Arguments
-/// * `f` The parser to apply.
+/// An element must extend to the next separator or to the end of the input. If `f` fails on
+/// it, with [`Err::Error`] or [`Err::Failure`], or does not reach a separator, the element
+/// is recorded as an error, and the parse resumes after the next separator found in the
+/// input. This way, every bad element of a record is reported, instead of stopping at the
+/// first one. An error is built with [ErrorKind::Eof] for elements followed by unexpected
+/// data.
 ///
-/// *Note*: If the parser passed to `many1` accepts empty inputs
-/// (like `alpha0` or `digit0`), `many1` will return an error,
-/// to prevent going into an infinite loop.
+/// As a bad element can extend to the end of the input, this is meant to be applied to
+/// one record at a time, for example with [`map_parser`][crate::combinator::map_parser].
+/// In streaming mode, it returns `Err::Incomplete` as it cannot know if the record is
+/// complete.
 ///
+/// # Arguments
+/// * `sep` Parses the separator between elements.
+/// * `f` Parses an element.
 /// ```rust
 /// # use nom::{Err, error::{Error, ErrorKind}, Needed, IResult, Parser};
-/// use nom::multi::many1_count;
-/// use nom::bytes::complete::tag;
+/// use nom::multi::separated_list_results;
+/// use nom::character::complete::{char, u8};
 ///
-/// fn parser(s: &str) -> IResult<&str, usize> {
-///   many1_count(tag("abc")).parse(s)
+/// fn record(s: &str) -> IResult<&str, Vec<Result<u8, Error<&str>>>> {
+///   separated_list_results(char(','), u8).parse_complete(s)
 /// }
 ///
-/// assert_eq!(parser("abcabc"), Ok(("", 2)));
-/// assert_eq!(parser("abc123"), Ok(("123", 1)));
-/// assert_eq!(parser("123123"), Err(Err::Error(Error::new("123123", ErrorKind::Many1Count))));
-/// assert_eq!(parser(""), Err(Err::Error(Error::new("", ErrorKind::Many1Count))));
+/// assert_eq!(
+///   record("1,x,300,4y,,5"),
+///   Ok((
+///     "",
+///     vec![
+///       Ok(1),
+///       Err(Error::new("x,300,4y,,5", ErrorKind::Digit)),
+///       Err(Error::new("300,4y,,5", ErrorKind::Digit)),
+///       Err(Error::new("y,,5", ErrorKind::Eof)),
+///       Err(Error::new(",5", ErrorKind::Digit)),
+///       Ok(5),
+///     ]
+///   ))
+/// );
 /// ```
-pub fn many1_count<I, E, F>(parser: F) -> impl Parser<I, Output = usize, Error = E>
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "alloc")))]
+pub fn separated_list_results<I, E, F, G>(
+  sep: G,
+  f: F,
+) -> impl Parser<I, Output = Vec<Result<<F as Parser<I>>::Output, E>>, Error = E>
 where
   I: Clone + Input,
   F: Parser<I, Error = E>,
+  G: Parser<I, Error = E>,
   E: ParseError<I>,
 {
-  Many1Count { parser }
+  SeparatedListResults {
+    parser: f,
+    separator: sep,
+  }
 }
 
-/// Parser implementation for the [many1_count] combinator
-pub struct Many1Count<F> {
+#[cfg(feature = "alloc")]
+/// Parser implementation for the [separated_list_results] combinator
+pub struct SeparatedListResults<F, G> {
   parser: F,
+  separator: G,
 }
 
-impl<I, F> Parser<I> for Many1Count<F>
+#[cfg(feature = "alloc")]
+impl<I, E: ParseError<I>, F, G> Parser<I> for SeparatedListResults<F, G>
 where
   I: Clone + Input,
-  F: Parser<I>,
+  F: Parser<I, Error = E>,
+  G: Parser<I, Error = E>,
 {
-  type Output = usize;
-  type Error = <F as Parser<I>>::Error;
+  type Output = Vec<Result<<F as Parser<I>>::Output, E>>;
+  type Error = E;
 
   fn process<OM: OutputMode>(
     &mut self,
-    input: I,
+    mut i: I,
   ) -> crate::PResult<OM, I, Self::Output, Self::Error> {
-    let mut count = 0;
-
-    match self
-      .parser
-      .process::<OutputM<Check, Check, OM::Incomplete>>(input.clone())
-    {
-      Err(Err::Error(_)) => Err(Err::Error(OM::Error::bind(move || {
-        <F as Parser<I>>::Error::from_error_kind(input, ErrorKind::Many1Count)
-      }))),
-      Err(Err::Failure(e)) => Err(Err::Failure(e)),
-      Err(Err::Incomplete(i)) => Err(Err::Incomplete(i)),
-      Ok((mut input, _)) => {
-        count += 1;
+    let mut res = OM::Output::bind(|| reserve("separated_list_results", 0));
 
-        loop {
-          let input_ = input.clone();
-          let len = input.input_len();
+    loop {
+      let len = i.input_len();
+      // the element errors are only built if the results are used
+      let (element, bad) = match self
+        .parser
+        .process::<OutputM<OM::Output, OM::Output, OM::Incomplete>>(i.clone())
+      {
+        Err(Err::Incomplete(e)) => return Err(Err::Incomplete(e)),
+        Err(Err::Error(e)) => (OM::Output::map(e, Err), i.clone()),
+        Err(Err::Failure(e)) => (OM::Output::bind(|| Err(e)), i.clone()),
+        Ok((rest, o)) => {
+          if rest.input_len() == 0 {
+            if i.is_streaming::<OM>() {
+              return Err(Err::Incomplete(Needed::Unknown));
+            }
+            res = OM::Output::combine(res, o, |mut res, o| {
+              push("separated_list_results", &mut res, Ok(o));
+              res
+            });
+            return Ok((rest, res));
+          }
           match self
-            .parser
-            .process::<OutputM<Check, Check, OM::Incomplete>>(input_)
+            .separator
+            .process::<OutputM<Check, Check, OM::Incomplete>>(rest.clone())
           {
-            Ok((i, _)) => {
-              // infinite loop check: the parser must always consume
-              if i.input_len() == len {
-                return Err(Err::Error(OM::Error::bind(|| {
-                  <F as Parser<I>>::Error::from_error_kind(input, ErrorKind::Many1Count)
-                })));
+            Err(Err::Incomplete(e)) => return Err(Err::Incomplete(e)),
+            Err(_) => (
+              OM::Output::bind(|| Err(E::from_error_kind(rest.clone(), ErrorKind::Eof))),
+              rest,
+            ),
+            Ok((next, _)) => {
+              // infinite loop check: the element and separator must consume
+              if next.input_len() == len {
+                return Err(infinite_loop::<OM, _, _>(i, ErrorKind::SeparatedList));
               }
-
-              input = i;
-              count += 1;
+              res = OM::Output::combine(res, o, |mut res, o| {
+                push("separated_list_results", &mut res, Ok(o));
+                res
+              });
+              i = next;
+              continue;
             }
+          }
+        }
+      };
 
-            Err(Err::Error(_)) => return Ok((input, OM::Output::bind(|| count))),
-            Err(Err::Failure(e)) => return Err(Err::Failure(e)),
-            Err(Err::Incomplete(i)) => return Err(Err::Incomplete(i)),
+      res = OM::Output::combine(res, element, |mut res, element| {
+        push("separated_list_results", &mut res, element);
+        res
+      });
+
+      // skips the bad element up to the next separator
+      let mut next = None;
+      for (index, _) in bad.iter_indices() {
+        match self
+          .separator
+          .process::<OutputM<Check, Check, OM::Incomplete>>(bad.take_from(index))
+        {
+          Err(Err::Error(_)) | Err(Err::Failure(_)) => {}
+          Err(Err::Incomplete(e)) => return Err(Err::Incomplete(e)),
+          Ok((after, _)) => {
+            next = Some(after);
+            break;
           }
         }
       }
+
+      match next {
+        None if i.is_streaming::<OM>() => return Err(Err::Incomplete(Needed::Unknown)),
+        None => return Ok((bad.take_from(bad.input_len()), res)),
+        Some(next) => {
+          if next.input_len() == len {
+            return Err(Err::Error(OM::Error::bind(|| {
+              E::from_error_kind(i, ErrorKind::SeparatedList)
+            })));
+          }
+          i = next;
+        }
+      }
     }
   }
 }
 
-/// Runs the embedded parser `count` times, gathering the results in a `Vec`
+/// Alternates between two parsers like [separated_list1], folding the elements from the
+/// left as they are parsed.
+///
+/// `f` is called with the accumulated value, the output of the separator and the next
+/// element, so `1-2-3` is folded as `f(f(1, '-', 2), '-', 3)`. The first element is the
+/// initial value of the accumulator. Nothing is allocated.
+///
+/// This stops when either parser returns [`Err::Error`] and returns the accumulated value.
+/// To instead chain an error up, see [`cut`][crate::combinator::cut].
 ///
 /// # Arguments
-/// * `f` The parser to apply.
-/// * `count` How often to apply the parser.
+/// * `parser` Parses the elements of the list.
+/// * `separator` Parses the separator between list elements.
+/// * `f` Combines the accumulator, a separator and the next element.
 /// ```rust
 /// # use nom::{Err, error::{Error, ErrorKind}, Needed, IResult, Parser};
-/// use nom::multi::count;
-/// use nom::bytes::complete::tag;
+/// use nom::multi::separated_foldl1;
+/// use nom::character::complete::{i64, one_of};
 ///
-/// fn parser(s: &str) -> IResult<&str, Vec<&str>> {
-///   count(tag("abc"), 2).parse(s)
+/// fn parser(s: &str) -> IResult<&str, i64> {
+///   separated_foldl1(i64, one_of("+-"), |acc, op, n| if op == '+' { acc + n } else { acc - n })
+///     .parse(s)
 /// }
 ///
-/// assert_eq!(parser("abcabc"), Ok(("", vec!["abc", "abc"])));
-/// assert_eq!(parser("abc123"), Err(Err::Error(Error::new("123", ErrorKind::Tag))));
-/// assert_eq!(parser("123123"), Err(Err::Error(Error::new("123123", ErrorKind::Tag))));
-/// assert_eq!(parser(""), Err(Err::Error(Error::new("", ErrorKind::Tag))));
-/// assert_eq!(parser("abcabcabc"), Ok(("abc", vec!["abc", "abc"])));
+/// assert_eq!(parser("10-2-3+1"), Ok(("", 6)));
+/// assert_eq!(parser("10-2-"), Ok(("-", 8)));
+/// assert_eq!(parser("-"), Err(Err::Error(Error::new("-", ErrorKind::Digit))));
 /// ```
-#[cfg(feature = "alloc")]
-#[cfg_attr(feature = "docsrs", doc(cfg(feature = "alloc")))]
-pub fn count<I, F>(
+pub fn separated_foldl1<I, E, F, G, H>(
   parser: F,
-  count: usize,
-) -> impl Parser<I, Output = Vec<<F as Parser<I>>::Output>, Error = <F as Parser<I>>::Error>
+  separator: G,
+  f: H,
+) -> impl Parser<I, Output = <F as Parser<I>>::Output, Error = E>
 where
-  I: Clone,
-  F: Parser<I>,
+  I: Clone + Input,
+  F: Parser<I, Error = E>,
+  G: Parser<I, Error = E>,
+  H: FnMut(
+    <F as Parser<I>>::Output,
+    <G as Parser<I>>::Output,
+    <F as Parser<I>>::Output,
+  ) -> <F as Parser<I>>::Output,
+  E: ParseError<I>,
 {
-  Count { parser, count }
+  SeparatedFoldl1 {
+    parser,
+    separator,
+    f,
+  }
 }
 
-#[cfg(feature = "alloc")]
-/// Parser implementation for the [count] combinator
-pub struct Count<F> {
+/// Parser implementation for the [separated_foldl1] combinator
+pub struct SeparatedFoldl1<F, G, H> {
   parser: F,
-  count: usize,
+  separator: G,
+  f: H,
 }
 
-#[cfg(feature = "alloc")]
-impl<I, F> Parser<I> for Count<F>
+impl<I, E: ParseError<I>, F, G, H> Parser<I> for SeparatedFoldl1<F, G, H>
 where
-  I: Clone,
-  F: Parser<I>,
+  I: Clone + Input,
+  F: Parser<I, Error = E>,
+  G: Parser<I, Error = E>,
+  H: FnMut(
+    <F as Parser<I>>::Output,
+    <G as Parser<I>>::Output,
+    <F as Parser<I>>::Output,
+  ) -> <F as Parser<I>>::Output,
 {
-  type Output = Vec<<F as Parser<I>>::Output>;
-  type Error = <F as Parser<I>>::Error;
+  type Output = <F as Parser<I>>::Output;
+  type Error = E;
 
   fn process<OM: OutputMode>(&mut self, i: I) -> crate::PResult<OM, I, Self::Output, Self::Error> {
-    let mut input = i.clone();
-    let max_initial_capacity = MAX_INITIAL_CAPACITY_BYTES
-      / crate::lib::std::mem::size_of::<<F as Parser<I>>::Output>().max(1);
-    let mut res = OM::Output::bind(|| {
-      crate::lib::std::vec::Vec::with_capacity(self.count.min(max_initial_capacity))
-    });
+    let (mut i, mut acc) = self.parser.process::<OM>(i)?;
 
-    for _ in 0..self.count {
-      let input_ = input.clone();
-      match self.parser.process::<OM>(input_) {
-        Ok((i, o)) => {
-          res = OM::Output::combine(res, o, |mut res, o| {
-            res.push(o);
-            res
-          });
-          input = i;
-        }
-        Err(Err::Error(e)) => {
-          return Err(Err::Error(OM::Error::map(e, |e| {
-            <F as Parser<I>>::Error::append(i, ErrorKind::Count, e)
-          })));
-        }
-        Err(e) => {
-          return Err(e);
+    loop {
+      let len = i.input_len();
+      match self
+        .separator
+        .process::<OutputM<OM::Output, Check, OM::Incomplete>>(i.clone())
+      {
+        Err(Err::Error(_)) => return Ok((i, acc)),
+        Err(Err::Failure(e)) => return Err(Err::Failure(e)),
+        Err(Err::Incomplete(e)) => return Err(Err::Incomplete(e)),
+        Ok((i1, sep)) => {
+          match self
+            .parser
+            .process::<OutputM<OM::Output, Check, OM::Incomplete>>(i1)
+          {
+            Err(Err::Error(_)) => return Ok((i, acc)),
+            Err(Err::Failure(e)) => return Err(Err::Failure(e)),
+            Err(Err::Incomplete(e)) => return Err(Err::Incomplete(e)),
+            Ok((i2, o)) => {
+              // infinite loop check: the parser must always consume
+              if i2.input_len() == len {
+                return Err(infinite_loop::<OM, _, _>(i, ErrorKind::SeparatedList));
+              }
+
+              let f = &mut self.f;
+              let next = OM::Output::combine(sep, o, |sep, o| (sep, o));
+              acc = OM::Output::combine(acc, next, |acc, (sep, o)| f(acc, sep, o));
+              i = i2;
+            }
+          }
         }
       }
     }
-
-    Ok((input, res))
   }
 }
 
-/// Runs the embedded parser repeatedly, filling the given slice with results.
+/// Alternates between two parsers like [separated_list1], folding the elements from the
+/// right.
 ///
-/// This parser fails if the input runs out before the given slice is full.
+/// `f` is called with an element, the output of the following separator and the folded
+/// value of the rest of the list, so `2^3^2` is folded as `f(2, '^', f(3, '^', 2))`. The
+/// elements and separators are kept until the end of the list, then folded from the last
+/// one.
+///
+/// This stops when either parser returns [`Err::Error`] and returns the folded value.
+/// To instead chain an error up, see [`cut`][crate::combinator::cut].
 ///
 /// # Arguments
-/// * `f` The parser to apply.
-/// * `buf` The slice to fill
+/// * `parser` Parses the elements of the list.
+/// * `separator` Parses the separator between list elements.
+/// * `f` Combines an element, a separator and the folded rest of the list.
 /// ```rust
 /// # use nom::{Err, error::{Error, ErrorKind}, Needed, IResult, Parser};
-/// use nom::multi::fill;
-/// use nom::bytes::complete::tag;
+/// use nom::multi::separated_foldr1;
+/// use nom::character::complete::{char, u32};
 ///
-/// fn parser(s: &str) -> IResult<&str, [&str; 2]> {
-///   let mut buf = ["", ""];
-///   let (rest, ()) = fill(tag("abc"), &mut buf).parse(s)?;
-///   Ok((rest, buf))
+/// fn parser(s: &str) -> IResult<&str, u32> {
+///   separated_foldr1(u32, char('^'), |base, _, exp| base.pow(exp)).parse(s)
 /// }
 ///
-/// assert_eq!(parser("abcabc"), Ok(("", ["abc", "abc"])));
-/// assert_eq!(parser("abc123"), Err(Err::Error(Error::new("123", ErrorKind::Tag))));
-/// assert_eq!(parser("123123"), Err(Err::Error(Error::new("123123", ErrorKind::Tag))));
-/// assert_eq!(parser(""), Err(Err::Error(Error::new("", ErrorKind::Tag))));
-/// assert_eq!(parser("abcabcabc"), Ok(("abc", ["abc", "abc"])));
+/// assert_eq!(parser("2^3^2"), Ok(("", 512)));
+/// assert_eq!(parser("2^3^"), Ok(("^", 8)));
+/// assert_eq!(parser("^2"), Err(Err::Error(Error::new("^2", ErrorKind::Digit))));
 /// ```
-pub fn fill<'a, I, E, F>(
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "alloc")))]
+pub fn separated_foldr1<I, E, F, G, H>(
   parser: F,
-  buf: &'a mut [<F as Parser<I>>::Output],
-) -> impl Parser<I, Output = (), Error = E> + 'a
+  separator: G,
+  f: H,
+) -> impl Parser<I, Output = <F as Parser<I>>::Output, Error = E>
 where
-  I: Clone,
-  F: Parser<I, Error = E> + 'a,
+  I: Clone + Input,
+  F: Parser<I, Error = E>,
+  G: Parser<I, Error = E>,
+  H: FnMut(
+    <F as Parser<I>>::Output,
+    <G as Parser<I>>::Output,
+    <F as Parser<I>>::Output,
+  ) -> <F as Parser<I>>::Output,
   E: ParseError<I>,
 {
-  Fill { parser, buf }
+  SeparatedFoldr1 {
+    parser,
+    separator,
+    f,
+  }
 }
 
-/// Parser implementation for the [fill] combinator
-pub struct Fill<'a, F, O> {
+#[cfg(feature = "alloc")]
+/// Parser implementation for the [separated_foldr1] combinator
+pub struct SeparatedFoldr1<F, G, H> {
   parser: F,
-  buf: &'a mut [O],
+  separator: G,
+  f: H,
 }
 
-impl<'a, I, F, O> Parser<I> for Fill<'a, F, O>
+#[cfg(feature = "alloc")]
+impl<I, E: ParseError<I>, F, G, H> Parser<I> for SeparatedFoldr1<F, G, H>
 where
-  I: Clone,
-  F: Parser<I, Output = O>,
+  I: Clone + Input,
+  F: Parser<I, Error = E>,
+  G: Parser<I, Error = E>,
+  H: FnMut(
+    <F as Parser<I>>::Output,
+    <G as Parser<I>>::Output,
+    <F as Parser<I>>::Output,
+  ) -> <F as Parser<I>>::Output,
 {
-  type Output = ();
-  type Error = <F as Parser<I>>::Error;
+  type Output = <F as Parser<I>>::Output;
+  type Error = E;
 
   fn process<OM: OutputMode>(&mut self, i: I) -> crate::PResult<OM, I, Self::Output, Self::Error> {
-    let mut input = i.clone();
+    let (mut i, first) = self.parser.process::<OM>(i)?;
+    // elements and the separators preceding them
+    let mut res = OM::Output::map(first, |first| (first, reserve("separated_foldr1", 0)));
 
-    for elem in self.buf.iter_mut() {
-      let input_ = input.clone();
-      match self.parser.process::<OM>(input_) {
-        Ok((i, o)) => {
-          OM::Output::map(o, |o| *elem = o);
-          input = i;
+    loop {
+      let len = i.input_len();
+      match self
+        .separator
+        .process::<OutputM<OM::Output, Check, OM::Incomplete>>(i.clone())
+      {
+        Err(Err::Error(_)) => break,
+        Err(Err::Failure(e)) => return Err(Err::Failure(e)),
+        Err(Err::Incomplete(e)) => return Err(Err::Incomplete(e)),
+        Ok((i1, sep)) => {
+          match self
+            .parser
+            .process::<OutputM<OM::Output, Check, OM::Incomplete>>(i1)
+          {
+            Err(Err::Error(_)) => break,
+            Err(Err::Failure(e)) => return Err(Err::Failure(e)),
+            Err(Err::Incomplete(e)) => return Err(Err::Incomplete(e)),
+            Ok((i2, o)) => {
+              // infinite loop check: the parser must always consume
+              if i2.input_len() == len {
+                return Err(infinite_loop::<OM, _, _>(i, ErrorKind::SeparatedList));
+              }
+
+              let next = OM::Output::combine(sep, o, |sep, o| (sep, o));
+              res = OM::Output::combine(res, next, |mut res, next| {
+                push("separated_foldr1", &mut res.1, next);
+                res
+              });
+              i = i2;
+            }
+          }
+        }
+      }
+    }
+
+    let f = &mut self.f;
+    Ok((
+      i,
+      OM::Output::map(res, |(first, mut rest)| {
+        let (mut sep, mut acc) = match rest.pop() {
+          Some(last) => last,
+          None => return first,
+        };
+        while let Some((previous_sep, o)) = rest.pop() {
+          acc = f(o, sep, acc);
+          sep = previous_sep;
+        }
+        f(first, sep, acc)
+      }),
+    ))
+  }
+}
+
+/// Repeats the embedded parser `m..=n` times
+///
+/// This stops before `n` when the parser returns [`Err::Error`]  and returns the results that were accumulated. To instead chain an error up, see
+/// [`cut`][crate::combinator::cut].
+///
+/// # Arguments
+/// * `m` The minimum number of iterations.
+/// * `n` The maximum number of iterations.
+/// * `f` The parser to apply.
+///
+/// *Note*: If the parser passed to `many1` accepts empty inputs
+/// (like `alpha0` or `digit0`), `many1` will return an error,
+/// to prevent going into an infinite loop.
+///
+/// If `m` or `n` are read from untrusted data, check them with
+/// [Parser::with_allocation_limit] first.
+///
+/// ```rust
+/// # use nom::{Err, error::ErrorKind, Needed, IResult, Parser};
+/// use nom::multi::many_m_n;
+/// use nom::bytes::complete::tag;
+///
+/// fn parser(s: &str) -> IResult<&str, Vec<&str>> {
+///   many_m_n(0, 2, tag("abc")).parse(s)
+/// }
+///
+/// assert_eq!(parser("abcabc"), Ok(("", vec!["abc", "abc"])));
+/// assert_eq!(parser("abc123"), Ok(("123", vec!["abc"])));
+/// assert_eq!(parser("123123"), Ok(("123123", vec![])));
+/// assert_eq!(parser(""), Ok(("", vec![])));
+/// assert_eq!(parser("abcabcabc"), Ok(("abc", vec!["abc", "abc"])));
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "alloc")))]
+pub fn many_m_n<I, E, F>(
+  min: usize,
+  max: usize,
+  parser: F,
+) -> impl Parser<I, Output = Vec<<F as Parser<I>>::Output>, Error = E>
+where
+  I: Clone + Input,
+  F: Parser<I, Error = E>,
+  E: ParseError<I>,
+{
+  ManyMN { parser, min, max }
+}
+
+#[cfg(feature = "alloc")]
+/// Parser implementation for the [many_m_n] combinator
+pub struct ManyMN<F> {
+  parser: F,
+  min: usize,
+  max: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl<I, F> Parser<I> for ManyMN<F>
+where
+  I: Clone + Input,
+  F: Parser<I>,
+{
+  type Output = Vec<<F as Parser<I>>::Output>;
+  type Error = <F as Parser<I>>::Error;
+
+  fn process<OM: OutputMode>(
+    &mut self,
+    mut input: I,
+  ) -> crate::PResult<OM, I, Self::Output, Self::Error> {
+    if self.min > self.max {
+      return Err(Err::Failure(<F as Parser<I>>::Error::from_error_kind(
+        input,
+        ErrorKind::ManyMN,
+      )));
+    }
+
+    let max_initial_capacity = MAX_INITIAL_CAPACITY_BYTES
+      / crate::lib::std::mem::size_of::<<F as Parser<I>>::Output>().max(1);
+    let mut res = OM::Output::bind(|| reserve("many_m_n", self.min.min(max_initial_capacity)));
+    for count in 0..self.max {
+      let len = input.input_len();
+      match self.parser.process::<OM>(input.clone()) {
+        Ok((tail, value)) => {
+          // infinite loop check: the parser must always consume
+          if tail.input_len() == len {
+            return Err(infinite_loop::<OM, _, _>(input, ErrorKind::ManyMN));
+          }
+
+          res = OM::Output::combine(res, value, |mut res, value| {
+            push("many_m_n", &mut res, value);
+            res
+          });
+          input = tail;
         }
         Err(Err::Error(e)) => {
-          return Err(Err::Error(OM::Error::map(e, |e| {
-            <F as Parser<I>>::Error::append(i, ErrorKind::Count, e)
-          })));
+          if count < self.min {
+            return Err(Err::Error(OM::Error::map(e, |e| {
+              <F as Parser<I>>::Error::append(input, ErrorKind::ManyMN, e)
+            })));
+          } else {
+            return Ok((input, res));
+          }
         }
         Err(e) => {
           return Err(e);
@@ -1002,254 +1638,1910 @@ where
       }
     }
 
-    Ok((input, OM::Output::bind(|| ())))
+    Ok((input, res))
   }
 }
 
-/// Repeats the embedded parser, calling `g` to gather the results.
+/// Repeats the embedded parser, counting the results
 ///
 /// This stops on [`Err::Error`]. To instead chain an error up, see
 /// [`cut`][crate::combinator::cut].
 ///
 /// # Arguments
 /// * `f` The parser to apply.
-/// * `init` A function returning the initial value.
-/// * `g` The function that combines a result of `f` with
-///       the current accumulator.
 ///
 /// *Note*: if the parser passed in accepts empty inputs (like `alpha0` or `digit0`), `many0` will
 /// return an error, to prevent going into an infinite loop
 ///
 /// ```rust
 /// # use nom::{Err, error::ErrorKind, Needed, IResult, Parser};
-/// use nom::multi::fold_many0;
+/// use nom::multi::many0_count;
 /// use nom::bytes::complete::tag;
 ///
-/// fn parser(s: &str) -> IResult<&str, Vec<&str>> {
-///   fold_many0(
-///     tag("abc"),
-///     Vec::new,
-///     |mut acc: Vec<_>, item| {
-///       acc.push(item);
-///       acc
-///     }
-///   ).parse(s)
+/// fn parser(s: &str) -> IResult<&str, usize> {
+///   many0_count(tag("abc")).parse(s)
 /// }
 ///
-/// assert_eq!(parser("abcabc"), Ok(("", vec!["abc", "abc"])));
-/// assert_eq!(parser("abc123"), Ok(("123", vec!["abc"])));
-/// assert_eq!(parser("123123"), Ok(("123123", vec![])));
-/// assert_eq!(parser(""), Ok(("", vec![])));
+/// assert_eq!(parser("abcabc"), Ok(("", 2)));
+/// assert_eq!(parser("abc123"), Ok(("123", 1)));
+/// assert_eq!(parser("123123"), Ok(("123123", 0)));
+/// assert_eq!(parser(""), Ok(("", 0)));
+/// ```
+pub fn many0_count<I, E, F>(parser: F) -> impl Parser<I, Output = usize, Error = E>
+where
+  I: Clone + Input,
+  F: Parser<I, Error = E>,
+  E: ParseError<I>,
+{
+  Many0Count { parser }
+}
+
+/// Parser implementation for the [many0_count] combinator
+pub struct Many0Count<F> {
+  parser: F,
+}
+
+impl<I, F> Parser<I> for Many0Count<F>
+where
+  I: Clone + Input,
+  F: Parser<I>,
+{
+  type Output = usize;
+  type Error = <F as Parser<I>>::Error;
+
+  fn process<OM: OutputMode>(
+    &mut self,
+    mut input: I,
+  ) -> crate::PResult<OM, I, Self::Output, Self::Error> {
+    let mut count = 0;
+
+    loop {
+      let input_ = input.clone();
+      let len = input.input_len();
+      match self
+        .parser
+        .process::<OutputM<Check, Check, OM::Incomplete>>(input_)
+      {
+        Ok((i, _)) => {
+          // infinite loop check: the parser must always consume
+          if i.input_len() == len {
+            return Err(infinite_loop::<OM, _, _>(input, ErrorKind::Many0Count));
+          }
+
+          input = i;
+          count += 1;
+        }
+
+        Err(Err::Error(_)) => return Ok((input, OM::Output::bind(|| count))),
+        Err(Err::Failure(e)) => return Err(Err::Failure(e)),
+        Err(Err::Incomplete(i)) => return Err(Err::Incomplete(i)),
+      }
+    }
+  }
+}
+
+/// Runs the embedded parser, counting the results.
+///
+/// This stops on [`Err::Error`] if there is at least one result. To instead chain an error up,
+/// see [`cut`][crate::combinator::cut].
+///
+/// # Arguments
+/// * `f` The parser to apply.
+///
+/// *Note*: If the parser passed to `many1` accepts empty inputs
+/// (like `alpha0` or `digit0`), `many1` will return an error,
+/// to prevent going into an infinite loop.
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Needed, IResult, Parser};
+/// use nom::multi::many1_count;
+/// use nom::bytes::complete::tag;
+///
+/// fn parser(s: &str) -> IResult<&str, usize> {
+///   many1_count(tag("abc")).parse(s)
+/// }
+///
+/// assert_eq!(parser("abcabc"), Ok(("", 2)));
+/// assert_eq!(parser("abc123"), Ok(("123", 1)));
+/// assert_eq!(parser("123123"), Err(Err::Error(Error::new("123123", ErrorKind::Many1Count))));
+/// assert_eq!(parser(""), Err(Err::Error(Error::new("", ErrorKind::Many1Count))));
+/// ```
+pub fn many1_count<I, E, F>(parser: F) -> impl Parser<I, Output = usize, Error = E>
+where
+  I: Clone + Input,
+  F: Parser<I, Error = E>,
+  E: ParseError<I>,
+{
+  Many1Count { parser }
+}
+
+/// Parser implementation for the [many1_count] combinator
+pub struct Many1Count<F> {
+  parser: F,
+}
+
+impl<I, F> Parser<I> for Many1Count<F>
+where
+  I: Clone + Input,
+  F: Parser<I>,
+{
+  type Output = usize;
+  type Error = <F as Parser<I>>::Error;
+
+  fn process<OM: OutputMode>(
+    &mut self,
+    input: I,
+  ) -> crate::PResult<OM, I, Self::Output, Self::Error> {
+    let mut count = 0;
+
+    match self
+      .parser
+      .process::<OutputM<Check, Check, OM::Incomplete>>(input.clone())
+    {
+      Err(Err::Error(_)) => Err(Err::Error(OM::Error::bind(move || {
+        <F as Parser<I>>::Error::from_error_kind(input, ErrorKind::Many1Count)
+      }))),
+      Err(Err::Failure(e)) => Err(Err::Failure(e)),
+      Err(Err::Incomplete(i)) => Err(Err::Incomplete(i)),
+      Ok((mut input, _)) => {
+        count += 1;
+
+        loop {
+          let input_ = input.clone();
+          let len = input.input_len();
+          match self
+            .parser
+            .process::<OutputM<Check, Check, OM::Incomplete>>(input_)
+          {
+            Ok((i, _)) => {
+              // infinite loop check: the parser must always consume
+              if i.input_len() == len {
+                return Err(infinite_loop::<OM, _, _>(input, ErrorKind::Many1Count));
+              }
+
+              input = i;
+              count += 1;
+            }
+
+            Err(Err::Error(_)) => return Ok((input, OM::Output::bind(|| count))),
+            Err(Err::Failure(e)) => return Err(Err::Failure(e)),
+            Err(Err::Incomplete(i)) => return Err(Err::Incomplete(i)),
+          }
+        }
+      }
+    }
+  }
+}
+
+/// Runs the embedded parser `count` times, gathering the results in a `Vec`
+///
+/// If `count` is read from untrusted data, check it with
+/// [Parser::with_allocation_limit] first.
+///
+/// # Arguments
+/// * `f` The parser to apply.
+/// * `count` How often to apply the parser.
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Needed, IResult, Parser};
+/// use nom::multi::count;
+/// use nom::bytes::complete::tag;
+///
+/// fn parser(s: &str) -> IResult<&str, Vec<&str>> {
+///   count(tag("abc"), 2).parse(s)
+/// }
+///
+/// assert_eq!(parser("abcabc"), Ok(("", vec!["abc", "abc"])));
+/// assert_eq!(parser("abc123"), Err(Err::Error(Error::new("123", ErrorKind::Tag))));
+/// assert_eq!(parser("123123"), Err(Err::Error(Error::new("123123", ErrorKind::Tag))));
+/// assert_eq!(parser(""), Err(Err::Error(Error::new("", ErrorKind::Tag))));
+/// assert_eq!(parser("abcabcabc"), Ok(("abc", vec!["abc", "abc"])));
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "alloc")))]
+pub fn count<I, F>(
+  parser: F,
+  count: usize,
+) -> impl Parser<I, Output = Vec<<F as Parser<I>>::Output>, Error = <F as Parser<I>>::Error>
+where
+  I: Clone,
+  F: Parser<I>,
+{
+  Count { parser, count }
+}
+
+#[cfg(feature = "alloc")]
+/// Parser implementation for the [count] combinator
+pub struct Count<F> {
+  parser: F,
+  count: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl<I, F> Parser<I> for Count<F>
+where
+  I: Clone,
+  F: Parser<I>,
+{
+  type Output = Vec<<F as Parser<I>>::Output>;
+  type Error = <F as Parser<I>>::Error;
+
+  fn process<OM: OutputMode>(&mut self, i: I) -> crate::PResult<OM, I, Self::Output, Self::Error> {
+    let mut input = i.clone();
+    let max_initial_capacity = MAX_INITIAL_CAPACITY_BYTES
+      / crate::lib::std::mem::size_of::<<F as Parser<I>>::Output>().max(1);
+    let mut res = OM::Output::bind(|| reserve("count", self.count.min(max_initial_capacity)));
+
+    for _ in 0..self.count {
+      let input_ = input.clone();
+      match self.parser.process::<OM>(input_) {
+        Ok((i, o)) => {
+          res = OM::Output::combine(res, o, |mut res, o| {
+            push("count", &mut res, o);
+            res
+          });
+          input = i;
+        }
+        Err(Err::Error(e)) => {
+          return Err(Err::Error(OM::Error::map(e, |e| {
+            <F as Parser<I>>::Error::append(i, ErrorKind::Count, e)
+          })));
+        }
+        Err(e) => {
+          return Err(e);
+        }
+      }
+    }
+
+    Ok((input, res))
+  }
+}
+
+/// Runs the embedded parser repeatedly, filling the given slice with results.
+///
+/// This parser fails if the input runs out before the given slice is full.
+///
+/// # Arguments
+/// * `f` The parser to apply.
+/// * `buf` The slice to fill
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Needed, IResult, Parser};
+/// use nom::multi::fill;
+/// use nom::bytes::complete::tag;
+///
+/// fn parser(s: &str) -> IResult<&str, [&str; 2]> {
+///   let mut buf = ["", ""];
+///   let (rest, ()) = fill(tag("abc"), &mut buf).parse(s)?;
+///   Ok((rest, buf))
+/// }
+///
+/// assert_eq!(parser("abcabc"), Ok(("", ["abc", "abc"])));
+/// assert_eq!(parser("abc123"), Err(Err::Error(Error::new("123", ErrorKind::Tag))));
+/// assert_eq!(parser("123123"), Err(Err::Error(Error::new("123123", ErrorKind::Tag))));
+/// assert_eq!(parser(""), Err(Err::Error(Error::new("", ErrorKind::Tag))));
+/// assert_eq!(parser("abcabcabc"), Ok(("abc", ["abc", "abc"])));
+/// ```
+pub fn fill<'a, I, E, F>(
+  parser: F,
+  buf: &'a mut [<F as Parser<I>>::Output],
+) -> impl Parser<I, Output = (), Error = E> + 'a
+where
+  I: Clone,
+  F: Parser<I, Error = E> + 'a,
+  E: ParseError<I>,
+{
+  Fill { parser, buf }
+}
+
+/// Parser implementation for the [fill] combinator
+pub struct Fill<'a, F, O> {
+  parser: F,
+  buf: &'a mut [O],
+}
+
+impl<'a, I, F, O> Parser<I> for Fill<'a, F, O>
+where
+  I: Clone,
+  F: Parser<I, Output = O>,
+{
+  type Output = ();
+  type Error = <F as Parser<I>>::Error;
+
+  fn process<OM: OutputMode>(&mut self, i: I) -> crate::PResult<OM, I, Self::Output, Self::Error> {
+    let mut input = i.clone();
+
+    for elem in self.buf.iter_mut() {
+      let input_ = input.clone();
+      match self.parser.process::<OM>(input_) {
+        Ok((i, o)) => {
+          OM::Output::map(o, |o| *elem = o);
+          input = i;
+        }
+        Err(Err::Error(e)) => {
+          return Err(Err::Error(OM::Error::map(e, |e| {
+            <F as Parser<I>>::Error::append(i, ErrorKind::Count, e)
+          })));
+        }
+        Err(e) => {
+          return Err(e);
+        }
+      }
+    }
+
+    Ok((input, OM::Output::bind(|| ())))
+  }
+}
+
+/// Runs the embedded parser `N` times, gathering the results in an array
+///
+/// Unlike [count], this does not allocate, so it is available without the `alloc` feature.
+///
+/// # Arguments
+/// * `f` The parser to apply.
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Needed, IResult, Parser};
+/// use nom::multi::array;
+/// use nom::bytes::complete::tag;
+///
+/// fn parser(s: &str) -> IResult<&str, [&str; 2]> {
+///   array(tag("abc")).parse(s)
+/// }
+///
+/// assert_eq!(parser("abcabc"), Ok(("", ["abc", "abc"])));
+/// assert_eq!(parser("abc123"), Err(Err::Error(Error::new("123", ErrorKind::Tag))));
+/// assert_eq!(parser("123123"), Err(Err::Error(Error::new("123123", ErrorKind::Tag))));
+/// assert_eq!(parser(""), Err(Err::Error(Error::new("", ErrorKind::Tag))));
+/// assert_eq!(parser("abcabcabc"), Ok(("abc", ["abc", "abc"])));
+/// ```
+pub fn array<const N: usize, I, F>(
+  parser: F,
+) -> impl Parser<I, Output = [<F as Parser<I>>::Output; N], Error = <F as Parser<I>>::Error>
+where
+  I: Clone,
+  F: Parser<I>,
+{
+  Array { parser }
+}
+
+/// Parser implementation for the [array] combinator
+pub struct Array<F, const N: usize> {
+  parser: F,
+}
+
+impl<I, F, const N: usize> Parser<I> for Array<F, N>
+where
+  I: Clone,
+  F: Parser<I>,
+{
+  type Output = [<F as Parser<I>>::Output; N];
+  type Error = <F as Parser<I>>::Error;
+
+  fn process<OM: OutputMode>(&mut self, i: I) -> crate::PResult<OM, I, Self::Output, Self::Error> {
+    let mut input = i.clone();
+    let mut res = OM::Output::bind(|| core::array::from_fn::<_, N, _>(|_| None));
+
+    for index in 0..N {
+      let input_ = input.clone();
+      match self.parser.process::<OM>(input_) {
+        Ok((i, o)) => {
+          res = OM::Output::combine(res, o, |mut res, o| {
+            res[index] = Some(o);
+            res
+          });
+          input = i;
+        }
+        Err(Err::Error(e)) => {
+          return Err(Err::Error(OM::Error::map(e, |e| {
+            <F as Parser<I>>::Error::append(i, ErrorKind::Count, e)
+          })));
+        }
+        Err(e) => {
+          return Err(e);
+        }
+      }
+    }
+
+    // every element was set by the loop
+    Ok((input, OM::Output::map(res, |res| res.map(Option::unwrap))))
+  }
+}
+
+/// Repeats the embedded parser, calling `g` to gather the results.
+///
+/// This stops on [`Err::Error`]. To instead chain an error up, see
+/// [`cut`][crate::combinator::cut].
+///
+/// # Arguments
+/// * `f` The parser to apply.
+/// * `init` A function returning the initial value.
+/// * `g` The function that combines a result of `f` with
+///       the current accumulator.
+///
+/// *Note*: if the parser passed in accepts empty inputs (like `alpha0` or `digit0`), `many0` will
+/// return an error, to prevent going into an infinite loop
+///
+/// ```rust
+/// # use nom::{Err, error::ErrorKind, Needed, IResult, Parser};
+/// use nom::multi::fold_many0;
+/// use nom::bytes::complete::tag;
+///
+/// fn parser(s: &str) -> IResult<&str, Vec<&str>> {
+///   fold_many0(
+///     tag("abc"),
+///     Vec::new,
+///     |mut acc: Vec<_>, item| {
+///       acc.push(item);
+///       acc
+///     }
+///   ).parse(s)
+/// }
+///
+/// assert_eq!(parser("abcabc"), Ok(("", vec!["abc", "abc"])));
+/// assert_eq!(parser("abc123"), Ok(("123", vec!["abc"])));
+/// assert_eq!(parser("123123"), Ok(("123123", vec![])));
+/// assert_eq!(parser(""), Ok(("", vec![])));
+/// ```
+pub fn fold_many0<I, E, F, G, H, R>(
+  parser: F,
+  init: H,
+  g: G,
+) -> impl Parser<I, Output = R, Error = E>
+where
+  I: Clone + Input,
+  F: Parser<I, Error = E>,
+  G: FnMut(R, <F as Parser<I>>::Output) -> R,
+  H: FnMut() -> R,
+  E: ParseError<I>,
+{
+  FoldMany0 {
+    parser,
+    g,
+    init,
+    r: PhantomData,
+  }
+}
+
+/// Parser implementation for the [fold_many0] combinator
+pub struct FoldMany0<F, G, Init, R> {
+  parser: F,
+  g: G,
+  init: Init,
+  r: PhantomData<R>,
+}
+
+impl<I, F, G, Init, R> Parser<I> for FoldMany0<F, G, Init, R>
+where
+  I: Clone + Input,
+  F: Parser<I>,
+  G: FnMut(R, <F as Parser<I>>::Output) -> R,
+  Init: FnMut() -> R,
+{
+  type Output = R;
+  type Error = <F as Parser<I>>::Error;
+
+  fn process<OM: OutputMode>(&mut self, i: I) -> crate::PResult<OM, I, Self::Output, Self::Error> {
+    let mut res = OM::Output::bind(|| (self.init)());
+    let mut input = i;
+
+    loop {
+      let i_ = input.clone();
+      let len = input.input_len();
+      match self.parser.process::<OM>(i_) {
+        Ok((i, o)) => {
+          // infinite loop check: the parser must always consume
+          if i.input_len() == len {
+            return Err(infinite_loop::<OM, _, _>(input, ErrorKind::Many0));
+          }
+
+          res = OM::Output::combine(res, o, |res, o| (self.g)(res, o));
+          input = i;
+        }
+        Err(Err::Error(_)) => {
+          return Ok((input, res));
+        }
+        Err(e) => {
+          return Err(e);
+        }
+      }
+    }
+  }
+}
+
+/// Repeats the embedded parser, calling `g` to gather the results.
+///
+/// This stops on [`Err::Error`] if there is at least one result. To instead chain an error up,
+/// see [`cut`][crate::combinator::cut].
+///
+/// # Arguments
+/// * `f` The parser to apply.
+/// * `init` A function returning the initial value.
+/// * `g` The function that combines a result of `f` with
+///       the current accumulator.
+///
+/// *Note*: If the parser passed to `many1` accepts empty inputs
+/// (like `alpha0` or `digit0`), `many1` will return an error,
+/// to prevent going into an infinite loop.
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Needed, IResult, Parser};
+/// use nom::multi::fold_many1;
+/// use nom::bytes::complete::tag;
+///
+/// fn parser(s: &str) -> IResult<&str, Vec<&str>> {
+///   fold_many1(
+///     tag("abc"),
+///     Vec::new,
+///     |mut acc: Vec<_>, item| {
+///       acc.push(item);
+///       acc
+///     }
+///   ).parse(s)
+/// }
+///
+/// assert_eq!(parser("abcabc"), Ok(("", vec!["abc", "abc"])));
+/// assert_eq!(parser("abc123"), Ok(("123", vec!["abc"])));
+/// assert_eq!(parser("123123"), Err(Err::Error(Error::new("123123", ErrorKind::Many1))));
+/// assert_eq!(parser(""), Err(Err::Error(Error::new("", ErrorKind::Many1))));
+/// ```
+pub fn fold_many1<I, E, F, G, H, R>(
+  parser: F,
+  init: H,
+  g: G,
+) -> impl Parser<I, Output = R, Error = E>
+where
+  I: Clone + Input,
+  F: Parser<I, Error = E>,
+  G: FnMut(R, <F as Parser<I>>::Output) -> R,
+  H: FnMut() -> R,
+  E: ParseError<I>,
+{
+  FoldMany1 {
+    parser,
+    g,
+    init,
+    r: PhantomData,
+  }
+}
+
+/// Parser implementation for the [fold_many1] combinator
+pub struct FoldMany1<F, G, Init, R> {
+  parser: F,
+  g: G,
+  init: Init,
+  r: PhantomData<R>,
+}
+
+impl<I, F, G, Init, R> Parser<I> for FoldMany1<F, G, Init, R>
+where
+  I: Clone + Input,
+  F: Parser<I>,
+  G: FnMut(R, <F as Parser<I>>::Output) -> R,
+  Init: FnMut() -> R,
+{
+  type Output = R;
+  type Error = <F as Parser<I>>::Error;
+
+  fn process<OM: OutputMode>(&mut self, i: I) -> crate::PResult<OM, I, Self::Output, Self::Error> {
+    let mut res = OM::Output::bind(|| (self.init)());
+    let input = i.clone();
+
+    match self.parser.process::<OM>(input) {
+      Err(Err::Error(_)) => Err(Err::Error(OM::Error::bind(|| {
+        <F as Parser<I>>::Error::from_error_kind(i, ErrorKind::Many1)
+      }))),
+      Err(e) => Err(e),
+      Ok((i1, o1)) => {
+        res = OM::Output::combine(res, o1, |res, o| (self.g)(res, o));
+
+        let mut input = i1;
+        loop {
+          let i_ = input.clone();
+          let len = input.input_len();
+          match self.parser.process::<OM>(i_) {
+            Ok((i, o)) => {
+              // infinite loop check: the parser must always consume
+              if i.input_len() == len {
+                return Err(infinite_loop::<OM, _, _>(input, ErrorKind::Many1));
+              }
+
+              res = OM::Output::combine(res, o, |res, o| (self.g)(res, o));
+              input = i;
+            }
+            Err(Err::Error(_)) => {
+              return Ok((input, res));
+            }
+            Err(e) => {
+              return Err(e);
+            }
+          }
+        }
+      }
+    }
+  }
+}
+
+/// Repeats the embedded parser `m..=n` times, calling `g` to gather the results
+///
+/// This stops before `n` when the parser returns [`Err::Error`]. To instead chain an error up, see
+/// [`cut`][crate::combinator::cut].
+///
+/// # Arguments
+/// * `m` The minimum number of iterations.
+/// * `n` The maximum number of iterations.
+/// * `f` The parser to apply.
+/// * `init` A function returning the initial value.
+/// * `g` The function that combines a result of `f` with
+///       the current accumulator.
+///
+/// *Note*: If the parser passed to `many1` accepts empty inputs
+/// (like `alpha0` or `digit0`), `many1` will return an error,
+/// to prevent going into an infinite loop.
+///
+/// ```rust
+/// # use nom::{Err, error::ErrorKind, Needed, IResult, Parser};
+/// use nom::multi::fold_many_m_n;
+/// use nom::bytes::complete::tag;
+///
+/// fn parser(s: &str) -> IResult<&str, Vec<&str>> {
+///   fold_many_m_n(
+///     0,
+///     2,
+///     tag("abc"),
+///     Vec::new,
+///     |mut acc: Vec<_>, item| {
+///       acc.push(item);
+///       acc
+///     }
+///   ).parse(s)
+/// }
+///
+/// assert_eq!(parser("abcabc"), Ok(("", vec!["abc", "abc"])));
+/// assert_eq!(parser("abc123"), Ok(("123", vec!["abc"])));
+/// assert_eq!(parser("123123"), Ok(("123123", vec![])));
+/// assert_eq!(parser(""), Ok(("", vec![])));
+/// assert_eq!(parser("abcabcabc"), Ok(("abc", vec!["abc", "abc"])));
+/// ```
+pub fn fold_many_m_n<I, E, F, G, H, R>(
+  min: usize,
+  max: usize,
+  parser: F,
+  init: H,
+  g: G,
+) -> impl Parser<I, Output = R, Error = E>
+where
+  I: Clone + Input,
+  F: Parser<I, Error = E>,
+  G: FnMut(R, <F as Parser<I>>::Output) -> R,
+  H: FnMut() -> R,
+  E: ParseError<I>,
+{
+  FoldManyMN {
+    parser,
+    g,
+    init,
+    min,
+    max,
+    r: PhantomData,
+  }
+}
+
+/// Parser implementation for the [fold_many_m_n] combinator
+pub struct FoldManyMN<F, G, Init, R> {
+  parser: F,
+  g: G,
+  init: Init,
+  r: PhantomData<R>,
+  min: usize,
+  max: usize,
+}
+
+impl<I, F, G, Init, R> Parser<I> for FoldManyMN<F, G, Init, R>
+where
+  I: Clone + Input,
+  F: Parser<I>,
+  G: FnMut(R, <F as Parser<I>>::Output) -> R,
+  Init: FnMut() -> R,
+{
+  type Output = R;
+  type Error = <F as Parser<I>>::Error;
+
+  fn process<OM: OutputMode>(
+    &mut self,
+    mut input: I,
+  ) -> crate::PResult<OM, I, Self::Output, Self::Error> {
+    if self.min > self.max {
+      return Err(Err::Error(OM::Error::bind(|| {
+        <F as Parser<I>>::Error::from_error_kind(input, ErrorKind::ManyMN)
+      })));
+    }
+
+    let mut res = OM::Output::bind(|| (self.init)());
+    for count in 0..self.max {
+      let len = input.input_len();
+      match self.parser.process::<OM>(input.clone()) {
+        Ok((tail, value)) => {
+          // infinite loop check: the parser must always consume
+          if tail.input_len() == len {
+            return Err(infinite_loop::<OM, _, _>(tail, ErrorKind::ManyMN));
+          }
+
+          res = OM::Output::combine(res, value, |res, o| (self.g)(res, o));
+          input = tail;
+        }
+        Err(Err::Error(err)) => {
+          if count < self.min {
+            return Err(Err::Error(OM::Error::map(err, |err| {
+              <F as Parser<I>>::Error::append(input, ErrorKind::ManyMN, err)
+            })));
+          } else {
+            break;
+          }
+        }
+        Err(e) => return Err(e),
+      }
+    }
+
+    Ok((input, res))
+  }
+}
+
+/// Gets a number from the parser and returns a
+/// subslice of the input of that size.
+/// If the parser returns `Incomplete`,
+/// `length_data` will return an error.
+/// # Arguments
+/// * `f` The parser to apply.
+/// ```rust
+/// # use nom::{Err, error::ErrorKind, Needed, IResult, Parser};
+/// use nom::number::complete::be_u16;
+/// use nom::multi::length_data;
+/// use nom::bytes::complete::tag;
+///
+/// fn parser(s: &[u8]) -> IResult<&[u8], &[u8]> {
+///   length_data(be_u16).parse(s)
+/// }
+///
+/// assert_eq!(parser(b"\x00\x03abcefg"), Ok((&b"efg"[..], &b"abc"[..])));
+/// assert_eq!(parser(b"\x00\x03a"), Err(Err::Incomplete(Needed::new(2))));
+/// ```
+pub fn length_data<I, E, F>(f: F) -> impl Parser<I, Output = I, Error = E>
+where
+  I: Input,
+  <F as Parser<I>>::Output: ToUsize,
+  F: Parser<I, Error = E>,
+  E: ParseError<I>,
+{
+  f.flat_map(|size| take(size))
+}
+
+/// Like [length_data], but returns an error with [ErrorKind::TooLarge] if the length is
+/// larger than `max`.
+///
+/// With untrusted data, this avoids waiting for, or accepting, more data than the format
+/// allows: in streaming mode, a length prefix of `0xFFFFFFFF` would otherwise request
+/// gigabytes of input. See [Parser::with_allocation_limit].
+///
+/// # Arguments
+/// * `max` The maximum length.
+/// * `f` The parser to apply.
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Needed, IResult, Parser};
+/// use nom::number::complete::be_u32;
+/// use nom::multi::length_data_bounded;
+///
+/// fn parser(s: &[u8]) -> IResult<&[u8], &[u8]> {
+///   length_data_bounded(16, be_u32).parse(s)
+/// }
+///
+/// assert_eq!(parser(b"\x00\x00\x00\x03abcefg"), Ok((&b"efg"[..], &b"abc"[..])));
+/// assert_eq!(
+///   parser(b"\xff\xff\xff\xffabc"),
+///   Err(Err::Error(Error::new(&b"\xff\xff\xff\xffabc"[..], ErrorKind::TooLarge)))
+/// );
+/// ```
+pub fn length_data_bounded<I, E, F>(max: usize, f: F) -> impl Parser<I, Output = I, Error = E>
+where
+  I: Clone + Input,
+  <F as Parser<I>>::Output: ToUsize,
+  F: Parser<I, Error = E>,
+  E: ParseError<I>,
+{
+  f.with_allocation_limit(max).flat_map(|size| take(size))
+}
+
+/// Gets a number from the first parser,
+/// takes a subslice of the input of that size,
+/// then applies the second parser on that subslice.
+/// If the second parser returns `Incomplete`,
+/// `length_value` will return an error.
+/// # Arguments
+/// * `f` The parser to apply.
+/// * `g` The parser to apply on the subslice.
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Needed, IResult, Parser};
+/// use nom::number::complete::be_u16;
+/// use nom::multi::length_value;
+/// use nom::bytes::complete::tag;
+///
+/// fn parser(s: &[u8]) -> IResult<&[u8], &[u8]> {
+///   length_value(be_u16, tag("abc")).parse(s)
+/// }
+///
+/// assert_eq!(parser(b"\x00\x03abcefg"), Ok((&b"efg"[..], &b"abc"[..])));
+/// assert_eq!(parser(b"\x00\x03123123"), Err(Err::Error(Error::new(&b"123"[..], ErrorKind::Tag))));
+/// assert_eq!(parser(b"\x00\x03a"), Err(Err::Incomplete(Needed::new(2))));
+/// ```
+pub fn length_value<I, E, F, G>(
+  f: F,
+  g: G,
+) -> impl Parser<I, Output = <G as Parser<I>>::Output, Error = E>
+where
+  I: Clone + Input,
+  <F as Parser<I>>::Output: ToUsize,
+  F: Parser<I, Error = E>,
+  G: Parser<I, Error = E>,
+  E: ParseError<I>,
+{
+  /*f.flat_map(|size| {
+    println!("got size: {size}");
+    take(size)
+  })
+  .and_then(g)*/
+  LengthValue {
+    length: f,
+    parser: g,
+    exact: false,
+    align: 1,
+    e: PhantomData,
+  }
+}
+
+/// Gets a number from the first parser,
+/// takes a subslice of the input of that size,
+/// then applies the second parser on that subslice, which must consume all of it.
+///
+/// Unlike [length_value], a value shorter than its declared length is rejected with an
+/// [ErrorKind::LengthValue] error, positioned on the leftover bytes: the length of the
+/// error input is the number of bytes the second parser did not consume.
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Needed, IResult, Parser};
+/// use nom::number::complete::{be_u16, u8};
+/// use nom::multi::length_value_exact;
+///
+/// fn parser(s: &[u8]) -> IResult<&[u8], u16> {
+///   length_value_exact(u8, be_u16).parse(s)
+/// }
+///
+/// assert_eq!(parser(b"\x02\x00\x01efg"), Ok((&b"efg"[..], 1)));
+/// assert_eq!(
+///   parser(b"\x04\x00\x01\xff\xffefg"),
+///   Err(Err::Error(Error::new(&b"\xff\xff"[..], ErrorKind::LengthValue)))
+/// );
+/// assert_eq!(parser(b"\x02\x00"), Err(Err::Incomplete(Needed::new(1))));
+/// ```
+pub fn length_value_exact<I, E, F, G>(
+  f: F,
+  g: G,
+) -> impl Parser<I, Output = <G as Parser<I>>::Output, Error = E>
+where
+  I: Clone + Input,
+  <F as Parser<I>>::Output: ToUsize,
+  F: Parser<I, Error = E>,
+  G: Parser<I, Error = E>,
+  E: ParseError<I>,
+{
+  LengthValue {
+    length: f,
+    parser: g,
+    exact: true,
+    align: 1,
+    e: PhantomData,
+  }
+}
+
+/// Gets a number from the first parser,
+/// takes a subslice of the input of that size,
+/// then applies the second parser on that subslice,
+/// and discards the padding following it up to the next multiple of `align` bytes.
+///
+/// The padding is not counted in the length, as in XDR opaque data or RIFF chunks. Like
+/// with [length_value], the second parser does not have to consume the whole subslice.
+///
+/// # Panics
+///
+/// Panics if `align` is 0.
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Needed, IResult, Parser};
+/// use nom::number::complete::be_u32;
+/// use nom::multi::length_value_padded;
+/// use nom::bytes::complete::tag;
+///
+/// fn parser(s: &[u8]) -> IResult<&[u8], &[u8]> {
+///   length_value_padded(4, be_u32, tag("abcde")).parse(s)
+/// }
+///
+/// assert_eq!(
+///   parser(b"\x00\x00\x00\x05abcde\x00\x00\x00efg"),
+///   Ok((&b"efg"[..], &b"abcde"[..]))
+/// );
+/// assert_eq!(
+///   parser(b"\x00\x00\x00\x05abcde\x00"),
+///   Err(Err::Incomplete(Needed::new(2)))
+/// );
+/// ```
+pub fn length_value_padded<I, E, F, G>(
+  align: usize,
+  f: F,
+  g: G,
+) -> impl Parser<I, Output = <G as Parser<I>>::Output, Error = E>
+where
+  I: Clone + Input,
+  <F as Parser<I>>::Output: ToUsize,
+  F: Parser<I, Error = E>,
+  G: Parser<I, Error = E>,
+  E: ParseError<I>,
+{
+  assert!(
+    align > 0,
+    "length_value_padded: the alignment must not be 0"
+  );
+  LengthValue {
+    length: f,
+    parser: g,
+    exact: false,
+    align,
+    e: PhantomData,
+  }
+}
+
+/// Gets a number from the first parser,
+/// takes a subslice of the input of that size,
+/// then applies the second parser on that subslice,
+/// returning the subslice along with the value.
+///
+/// This keeps the raw bytes of a value, to compute a checksum or to store it unparsed
+/// for later. Like with [length_value], the second parser does not have to consume the
+/// whole subslice.
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Needed, IResult, Parser};
+/// use nom::number::complete::{be_u16, u8};
+/// use nom::multi::length_value_raw;
+///
+/// fn parser(s: &[u8]) -> IResult<&[u8], (&[u8], u16)> {
+///   length_value_raw(u8, be_u16).parse(s)
+/// }
+///
+/// assert_eq!(
+///   parser(b"\x03\x00\x01\xffefg"),
+///   Ok((&b"efg"[..], (&b"\x00\x01\xff"[..], 1)))
+/// );
+/// assert_eq!(parser(b"\x03\x00"), Err(Err::Incomplete(Needed::new(2))));
+/// ```
+pub fn length_value_raw<I, E, F, G>(
+  f: F,
+  g: G,
+) -> impl Parser<I, Output = (I, <G as Parser<I>>::Output), Error = E>
+where
+  I: Clone + Input,
+  <F as Parser<I>>::Output: ToUsize,
+  F: Parser<I, Error = E>,
+  G: Parser<I, Error = E>,
+  E: ParseError<I>,
+{
+  LengthValueRaw {
+    inner: LengthValue {
+      length: f,
+      parser: g,
+      exact: false,
+      align: 1,
+      e: PhantomData,
+    },
+  }
+}
+
+/// Parser implementation for the [length_value], [length_value_exact] and
+/// [length_value_padded] combinators
+pub struct LengthValue<F, G, E> {
+  length: F,
+  parser: G,
+  /// whether the parser must consume the whole value
+  exact: bool,
+  /// alignment of the end of the value, the padding is skipped
+  align: usize,
+  e: PhantomData<E>,
+}
+
+impl<F, G, E> LengthValue<F, G, E> {
+  /// Applies the parser to the value, returning the remaining input, the value
+  /// and the output
+  #[allow(clippy::type_complexity)]
+  fn value<I, OM: OutputMode>(
+    &mut self,
+    input: I,
+  ) -> Result<
+    (
+      I,
+      I,
+      <<OM as OutputMode>::Output as Mode>::Output<<G as Parser<I>>::Output>,
+    ),
+    Err<E, <<OM as OutputMode>::Error as Mode>::Output<E>>,
+  >
+  where
+    I: Clone + Input,
+    F: Parser<I, Error = E>,
+    G: Parser<I, Error = E>,
+    <F as Parser<I>>::Output: ToUsize,
+    E: ParseError<I>,
+  {
+    let (i, length) = self
+      .length
+      .process::<OutputM<Emit, OM::Error, OM::Incomplete>>(input)?;
+
+    let length: usize = length.to_usize();
+    let padded = match length % self.align {
+      0 => Some(length),
+      r => length.checked_add(self.align - r),
+    };
+
+    let needed = match padded {
+      Some(padded) => padded.checked_sub(i.input_len()),
+      None => Some(usize::MAX),
+    };
+    if let Some(needed) = needed.and_then(NonZeroUsize::new) {
+      return Err(Err::Incomplete(Needed::Size(needed)));
+    }
+
+    let (rest, value) = i.take_split(padded.unwrap_or(length));
+    let (_, value) = value.take_split(length);
+    match self.parser.process::<OM>(value.clone()) {
+      Err(Err::Incomplete(_)) => Err(Err::Error(OM::Error::bind(|| {
+        E::from_error_kind(value, ErrorKind::Complete)
+      }))),
+      Err(e) => Err(e),
+      Ok((leftover, _)) if self.exact && leftover.input_len() > 0 => {
+        Err(Err::Error(OM::Error::bind(|| {
+          E::from_error_kind(leftover, ErrorKind::LengthValue)
+        })))
+      }
+      Ok((_, o)) => Ok((rest, value, o)),
+    }
+  }
+}
+
+impl<I, F, G, E> Parser<I> for LengthValue<F, G, E>
+where
+  I: Clone + Input,
+  F: Parser<I, Error = E>,
+  G: Parser<I, Error = E>,
+  <F as Parser<I>>::Output: ToUsize,
+  E: ParseError<I>,
+{
+  type Output = <G as Parser<I>>::Output;
+  type Error = E;
+
+  fn process<OM: OutputMode>(
+    &mut self,
+    input: I,
+  ) -> crate::PResult<OM, I, Self::Output, Self::Error> {
+    let (rest, _, o) = self.value::<I, OM>(input)?;
+    Ok((rest, o))
+  }
+}
+
+/// Parser implementation for the [length_value_raw] combinator
+pub struct LengthValueRaw<F, G, E> {
+  inner: LengthValue<F, G, E>,
+}
+
+impl<I, F, G, E> Parser<I> for LengthValueRaw<F, G, E>
+where
+  I: Clone + Input,
+  F: Parser<I, Error = E>,
+  G: Parser<I, Error = E>,
+  <F as Parser<I>>::Output: ToUsize,
+  E: ParseError<I>,
+{
+  type Output = (I, <G as Parser<I>>::Output);
+  type Error = E;
+
+  fn process<OM: OutputMode>(
+    &mut self,
+    input: I,
+  ) -> crate::PResult<OM, I, Self::Output, Self::Error> {
+    let (rest, value, o) = self.inner.value::<I, OM>(input)?;
+    Ok((rest, OM::Output::map(o, |o| (value, o))))
+  }
+}
+
+/// Gets a number from the first parser,
+/// then applies the second parser that many times.
+/// # Arguments
+/// * `f` The parser to apply to obtain the count.
+/// * `g` The parser to apply repeatedly.
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Needed, IResult, Parser};
+/// use nom::number::complete::u8;
+/// use nom::multi::length_count;
+/// use nom::bytes::complete::tag;
+/// use nom::combinator::map;
+///
+/// fn parser(s: &[u8]) -> IResult<&[u8], Vec<&[u8]>> {
+///   length_count(map(u8, |i| {
+///      println!("got number: {}", i);
+///      i
+///   }), tag("abc")).parse(s)
+/// }
+///
+/// assert_eq!(parser(&b"\x02abcabcabc"[..]), Ok(((&b"abc"[..], vec![&b"abc"[..], &b"abc"[..]]))));
+/// assert_eq!(parser(b"\x03123123123"), Err(Err::Error(Error::new(&b"123123123"[..], ErrorKind::Tag))));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn length_count<I, E, F, G>(
+  f: F,
+  g: G,
+) -> impl Parser<I, Output = Vec<<G as Parser<I>>::Output>, Error = E>
+where
+  I: Clone,
+  <F as Parser<I>>::Output: ToUsize,
+  F: Parser<I, Error = E>,
+  G: Parser<I, Error = E>,
+  E: ParseError<I>,
+{
+  LengthCount {
+    length: f,
+    parser: g,
+    e: PhantomData,
+  }
+}
+
+#[cfg(feature = "alloc")]
+/// Parser implementation for the [length_count] combinator
+pub struct LengthCount<F, G, E> {
+  length: F,
+  parser: G,
+  e: PhantomData<E>,
+}
+
+#[cfg(feature = "alloc")]
+impl<I, F, G, E> Parser<I> for LengthCount<F, G, E>
+where
+  I: Clone,
+  F: Parser<I, Error = E>,
+  G: Parser<I, Error = E>,
+  <F as Parser<I>>::Output: ToUsize,
+  E: ParseError<I>,
+{
+  type Output = Vec<<G as Parser<I>>::Output>;
+  type Error = E;
+
+  fn process<OM: OutputMode>(
+    &mut self,
+    input: I,
+  ) -> crate::PResult<OM, I, Self::Output, Self::Error> {
+    match self
+      .length
+      .process::<OutputM<Emit, OM::Error, OM::Incomplete>>(input)
+    {
+      Err(e) => Err(e),
+      Ok((i, count)) => {
+        let count = count.to_usize();
+        let mut input = i.clone();
+        let max_initial_capacity = MAX_INITIAL_CAPACITY_BYTES
+          / crate::lib::std::mem::size_of::<<F as Parser<I>>::Output>().max(1);
+        let mut res = OM::Output::bind(|| reserve("length_count", count.min(max_initial_capacity)));
+
+        for _ in 0..count {
+          let input_ = input.clone();
+          match self.parser.process::<OM>(input_) {
+            Ok((i, o)) => {
+              res = OM::Output::combine(res, o, |mut res, o| {
+                push("length_count", &mut res, o);
+                res
+              });
+              input = i;
+            }
+            Err(Err::Error(e)) => {
+              return Err(Err::Error(OM::Error::map(e, |e| {
+                <F as Parser<I>>::Error::append(i, ErrorKind::Count, e)
+              })));
+            }
+            Err(e) => {
+              return Err(e);
+            }
+          }
+        }
+
+        Ok((input, res))
+      }
+    }
+  }
+}
+
+/// Like [length_count], but returns an error with [ErrorKind::TooLarge] if the count is
+/// larger than `max`.
+///
+/// The count is checked before the results are allocated and before the second parser
+/// is applied, so a count prefix of `0xFFFFFFFF` cannot make the parser grow a vector of
+/// billions of elements, which is possible with an element parser that consumes little
+/// or no input. See [Parser::with_allocation_limit].
+///
+/// # Arguments
+/// * `max` The maximum count.
+/// * `f` The parser to apply to obtain the count.
+/// * `g` The parser to apply repeatedly.
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Needed, IResult, Parser};
+/// use nom::number::complete::be_u32;
+/// use nom::multi::length_count_bounded;
+/// use nom::bytes::complete::tag;
+///
+/// fn parser(s: &[u8]) -> IResult<&[u8], Vec<&[u8]>> {
+///   length_count_bounded(16, be_u32, tag("abc")).parse(s)
+/// }
+///
+/// assert_eq!(parser(b"\x00\x00\x00\x02abcabcabc"), Ok((&b"abc"[..], vec![&b"abc"[..], &b"abc"[..]])));
+/// assert_eq!(
+///   parser(b"\xff\xff\xff\xffabc"),
+///   Err(Err::Error(Error::new(&b"\xff\xff\xff\xffabc"[..], ErrorKind::TooLarge)))
+/// );
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "alloc")))]
+pub fn length_count_bounded<I, E, F, G>(
+  max: usize,
+  f: F,
+  g: G,
+) -> impl Parser<I, Output = Vec<<G as Parser<I>>::Output>, Error = E>
+where
+  I: Clone,
+  <F as Parser<I>>::Output: ToUsize,
+  F: Parser<I, Error = E>,
+  G: Parser<I, Error = E>,
+  E: ParseError<I>,
+{
+  length_count(f.with_allocation_limit(max), g)
+}
+
+/// Decodes run-length encoded data: repeats a count parser followed by a value parser,
+/// and pushes each value in the result `Vec` as many times as its count.
+///
+/// This stops on [`Err::Error`] from the count or value parsers and returns the values
+/// decoded before the failing run, like [many0]. It returns an error with
+/// [ErrorKind::TooLarge] if the decoded data would contain more than `max` values, which
+/// is checked before expanding a run.
+///
+/// On streaming data, the end of the runs can only be found when the count parser
+/// fails, so this returns `Incomplete` at the end of the available data. If the decoded
+/// length is known, as for the pixels of an image, use [rle_exact] instead.
+///
+/// # Arguments
+/// * `max` The maximum number of decoded values.
+/// * `count` The parser for the length of a run.
+/// * `value` The parser for the repeated value.
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Needed, IResult, Parser};
+/// use nom::number::complete::u8;
+/// use nom::multi::rle;
+///
+/// fn parser(s: &[u8]) -> IResult<&[u8], Vec<u8>> {
+///   rle(8, u8, u8).parse(s)
+/// }
+///
+/// assert_eq!(parser(b"\x03a\x00b\x02c"), Ok((&b""[..], b"aaacc".to_vec())));
+/// assert_eq!(parser(b"\x03a\x01"), Ok((&b"\x01"[..], b"aaa".to_vec())));
+/// assert_eq!(
+///   parser(b"\x03a\x09b"),
+///   Err(Err::Error(Error::new(&b"\x09b"[..], ErrorKind::TooLarge)))
+/// );
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "alloc")))]
+pub fn rle<I, E, F, G>(max: usize, count: F, value: G) -> Rle<F, G>
+where
+  I: Clone + Input,
+  F: Parser<I, Error = E>,
+  G: Parser<I, Error = E>,
+  <F as Parser<I>>::Output: ToUsize,
+  <G as Parser<I>>::Output: Clone,
+  E: ParseError<I>,
+{
+  Rle {
+    count,
+    value,
+    max,
+    exact: false,
+  }
+}
+
+/// Decodes exactly `len` values of run-length encoded data, see [rle].
+///
+/// This stops after the run reaching `len` values without applying the count parser
+/// again, so it can be used on streaming data. It returns an error with
+/// [ErrorKind::TooLarge] if a run goes past `len` values, and the errors of the count
+/// and value parsers if the runs end before.
+///
+/// # Arguments
+/// * `len` The number of decoded values.
+/// * `count` The parser for the length of a run.
+/// * `value` The parser for the repeated value.
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Needed, IResult, Parser};
+/// use nom::number::streaming::u8;
+/// use nom::multi::rle_exact;
+///
+/// fn parser(s: &[u8]) -> IResult<&[u8], Vec<u8>> {
+///   rle_exact(5, u8, u8).parse(s)
+/// }
+///
+/// assert_eq!(parser(b"\x03a\x02crest"), Ok((&b"rest"[..], b"aaacc".to_vec())));
+/// assert_eq!(parser(b"\x03a\x02c"), Ok((&b""[..], b"aaacc".to_vec())));
+/// assert_eq!(parser(b"\x03a"), Err(Err::Incomplete(Needed::new(1))));
+/// assert_eq!(
+///   parser(b"\x03a\x03c"),
+///   Err(Err::Error(Error::new(&b"\x03c"[..], ErrorKind::TooLarge)))
+/// );
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "alloc")))]
+pub fn rle_exact<I, E, F, G>(len: usize, count: F, value: G) -> Rle<F, G>
+where
+  I: Clone + Input,
+  F: Parser<I, Error = E>,
+  G: Parser<I, Error = E>,
+  <F as Parser<I>>::Output: ToUsize,
+  <G as Parser<I>>::Output: Clone,
+  E: ParseError<I>,
+{
+  Rle {
+    count,
+    value,
+    max: len,
+    exact: true,
+  }
+}
+
+#[cfg(feature = "alloc")]
+/// Parser implementation for the [rle] and [rle_exact] combinators
+pub struct Rle<F, G> {
+  count: F,
+  value: G,
+  max: usize,
+  exact: bool,
+}
+
+#[cfg(feature = "alloc")]
+impl<I, E, F, G> Parser<I> for Rle<F, G>
+where
+  I: Clone + Input,
+  F: Parser<I, Error = E>,
+  G: Parser<I, Error = E>,
+  <F as Parser<I>>::Output: ToUsize,
+  <G as Parser<I>>::Output: Clone,
+  E: ParseError<I>,
+{
+  type Output = Vec<<G as Parser<I>>::Output>;
+  type Error = E;
+
+  fn process<OM: OutputMode>(
+    &mut self,
+    mut input: I,
+  ) -> crate::PResult<OM, I, Self::Output, Self::Error> {
+    let max_initial_capacity = MAX_INITIAL_CAPACITY_BYTES
+      / crate::lib::std::mem::size_of::<<G as Parser<I>>::Output>().max(1);
+    let mut res = OM::Output::bind(|| {
+      let capacity = if self.exact { self.max } else { 4 };
+      reserve("rle", capacity.min(max_initial_capacity))
+    });
+    let mut decoded = 0;
+
+    loop {
+      if self.exact && decoded == self.max {
+        return Ok((input, res));
+      }
+
+      let len = input.input_len();
+      let (i, count) = match self
+        .count
+        .process::<OutputM<Emit, OM::Error, OM::Incomplete>>(input.clone())
+      {
+        Ok(res) => res,
+        Err(Err::Error(_)) if !self.exact => return Ok((input, res)),
+        Err(e) => return Err(e),
+      };
+      let count = count.to_usize();
+      if count > self.max - decoded {
+        return Err(Err::Error(OM::Error::bind(|| {
+          E::from_error_kind(input, ErrorKind::TooLarge)
+        })));
+      }
+
+      let (i, value) = match self.value.process::<OM>(i) {
+        Ok(res) => res,
+        Err(Err::Error(_)) if !self.exact => return Ok((input, res)),
+        Err(e) => return Err(e),
+      };
+      // infinite loop check: the runs must always consume
+      if i.input_len() == len {
+        return Err(infinite_loop::<OM, _, _>(input, ErrorKind::Count));
+      }
+
+      input = i;
+      decoded += count;
+      res = OM::Output::combine(res, value, |mut res, value| {
+        extend(
+          "rle",
+          &mut res,
+          crate::lib::std::iter::repeat(value).take(count),
+        );
+        res
+      });
+    }
+  }
+}
+
+/// Overflow policy of [delta_decode]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Overflow {
+  /// A value outside of the bounds of the type is an error with [ErrorKind::TooLarge]
+  Error,
+  /// Values wrap around at the bounds of the type, as in formats storing deltas modulo
+  /// the integer size
+  Wrap,
+  /// Values stop at the bounds of the type
+  Saturate,
+}
+
+/// Parses a sequence of deltas and returns the absolute values in a `Vec`, each value
+/// being the previous one plus its delta, starting from `init`.
+///
+/// This stops on [`Err::Error`] and returns the values that were decoded, like [many0].
+/// The `overflow` policy decides what happens when a value goes past the bounds of its
+/// type.
+///
+/// # Arguments
+/// * `parser` The parser for the deltas.
+/// * `init` The value the first delta applies to.
+/// * `overflow` The [Overflow] policy.
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Needed, IResult, Parser};
+/// use nom::number::complete::i8;
+/// use nom::multi::{delta_decode, Overflow};
+///
+/// fn parser(s: &[u8]) -> IResult<&[u8], Vec<i8>> {
+///   delta_decode(i8, 100, Overflow::Error).parse(s)
+/// }
+///
+/// assert_eq!(parser(&[0x01, 0x02, 0xfd]), Ok((&b""[..], vec![101, 103, 100])));
+/// assert_eq!(
+///   parser(&[0x01, 0x7f]),
+///   Err(Err::Error(Error::new(&[0x7f][..], ErrorKind::TooLarge)))
+/// );
+///
+/// let res: IResult<_, _> = delta_decode(i8, 100, Overflow::Wrap).parse(&[0x01, 0x7f][..]);
+/// assert_eq!(res, Ok((&b""[..], vec![101, -28])));
+/// let res: IResult<_, _> = delta_decode(i8, 100, Overflow::Saturate).parse(&[0x01, 0x7f][..]);
+/// assert_eq!(res, Ok((&b""[..], vec![101, 127])));
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "alloc")))]
+pub fn delta_decode<I, E, F>(
+  parser: F,
+  init: <F as Parser<I>>::Output,
+  overflow: Overflow,
+) -> DeltaDecode<F, <F as Parser<I>>::Output>
+where
+  I: Clone + Input,
+  F: Parser<I, Error = E>,
+  <F as Parser<I>>::Output: Delta,
+  E: ParseError<I>,
+{
+  DeltaDecode {
+    parser,
+    init,
+    overflow,
+  }
+}
+
+#[cfg(feature = "alloc")]
+/// Parser implementation for the [delta_decode] combinator
+pub struct DeltaDecode<F, T> {
+  parser: F,
+  init: T,
+  overflow: Overflow,
+}
+
+#[cfg(feature = "alloc")]
+impl<I, E, F, T> Parser<I> for DeltaDecode<F, T>
+where
+  I: Clone + Input,
+  F: Parser<I, Output = T, Error = E>,
+  T: Delta,
+  E: ParseError<I>,
+{
+  type Output = Vec<T>;
+  type Error = E;
+
+  fn process<OM: OutputMode>(
+    &mut self,
+    mut input: I,
+  ) -> crate::PResult<OM, I, Self::Output, Self::Error> {
+    let mut res = OM::Output::bind(|| reserve("delta_decode", 4));
+    let mut value = self.init;
+
+    loop {
+      let len = input.input_len();
+      // the deltas are needed to check for overflows even without output
+      let (i, delta) = match self
+        .parser
+        .process::<OutputM<Emit, OM::Error, OM::Incomplete>>(input.clone())
+      {
+        Ok(res) => res,
+        Err(Err::Error(_)) => return Ok((input, res)),
+        Err(e) => return Err(e),
+      };
+      // infinite loop check: the parser must always consume
+      if i.input_len() == len {
+        return Err(infinite_loop::<OM, _, _>(input, ErrorKind::Many0));
+      }
+
+      value = match self.overflow {
+        Overflow::Error => match value.checked_add_delta(delta) {
+          Some(value) => value,
+          None => {
+            return Err(Err::Error(OM::Error::bind(|| {
+              E::from_error_kind(input, ErrorKind::TooLarge)
+            })))
+          }
+        },
+        Overflow::Wrap => value.wrapping_add_delta(delta),
+        Overflow::Saturate => value.saturating_add_delta(delta),
+      };
+
+      input = i;
+      res = OM::Output::map(res, |mut res| {
+        push("delta_decode", &mut res, value);
+        res
+      });
+    }
+  }
+}
+
+/// Repeats the embedded parser and collects the results in a type implementing `Extend + Default`.
+/// Fails if the amount of time the embedded parser is run is not
+/// within the specified range.
+/// # Arguments
+/// * `range` Constrains the number of iterations.
+///   * A range without an upper bound `a..` is equivalent to a range of `a..=usize::MAX`.
+///   * A single `usize` value is equivalent to `value..=value`.
+///   * An empty range is invalid.
+/// * `parse` The parser to apply.
+///
+/// ```rust
+/// # #[macro_use] extern crate nom;
+/// # use nom::{Err, error::ErrorKind, Needed, IResult, Parser};
+/// use nom::multi::many;
+/// use nom::bytes::complete::tag;
+///
+/// fn parser(s: &str) -> IResult<&str, Vec<&str>> {
+///   many(0..=2, tag("abc")).parse(s)
+/// }
+///
+/// assert_eq!(parser("abcabc"), Ok(("", vec!["abc", "abc"])));
+/// assert_eq!(parser("abc123"), Ok(("123", vec!["abc"])));
+/// assert_eq!(parser("123123"), Ok(("123123", vec![])));
+/// assert_eq!(parser(""), Ok(("", vec![])));
+/// assert_eq!(parser("abcabcabc"), Ok(("abc", vec!["abc", "abc"])));
+/// ```
+///
+/// This is not limited to `Vec`, other collections like `HashMap`
+/// can be used:
+///
+/// ```rust
+/// # #[macro_use] extern crate nom;
+/// # use nom::{Err, error::ErrorKind, Needed, IResult, Parser};
+/// use nom::multi::many;
+/// use nom::bytes::complete::{tag, take_while};
+/// use nom::sequence::{separated_pair, terminated};
+/// use nom::AsChar;
+///
+/// use std::collections::HashMap;
+///
+/// fn key_value(s: &str) -> IResult<&str, HashMap<&str, &str>> {
+///   many(0.., terminated(
+///     separated_pair(
+///       take_while(AsChar::is_alpha),
+///       tag("="),
+///       take_while(AsChar::is_alpha)
+///     ),
+///     tag(";")
+///   )).parse(s)
+/// }
+///
+/// assert_eq!(
+///   key_value("a=b;c=d;"),
+///   Ok(("", HashMap::from([("a", "b"), ("c", "d")])))
+/// );
+/// ```
+///
+/// If more control is needed on the default value, [fold] can
+/// be used instead:
+///
+/// ```rust
+/// # #[macro_use] extern crate nom;
+/// # use nom::{Err, error::ErrorKind, Needed, IResult, Parser};
+/// use nom::multi::fold;
+/// use nom::bytes::complete::tag;
+///
+///
+/// fn parser(s: &str) -> IResult<&str, Vec<&str>> {
+///   fold(
+///     0..=4,
+///     tag("abc"),
+///     // preallocates a vector of the max size
+///     || Vec::with_capacity(4),
+///     |mut acc: Vec<_>, item| {
+///       acc.push(item);
+///       acc
+///     }
+///   ).parse(s)
+/// }
+///
+///
+/// assert_eq!(parser("abcabcabcabc"), Ok(("", vec!["abc", "abc", "abc", "abc"])));
 /// ```
-pub fn fold_many0<I, E, F, G, H, R>(
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "alloc")))]
+pub fn many<I, E, Collection, F, G>(
+  range: G,
   parser: F,
-  init: H,
-  g: G,
-) -> impl Parser<I, Output = R, Error = E>
+) -> impl Parser<I, Output = Collection, Error = E>
 where
   I: Clone + Input,
   F: Parser<I, Error = E>,
-  G: FnMut(R, <F as Parser<I>>::Output) -> R,
-  H: FnMut() -> R,
+  Collection: Extend<<F as Parser<I>>::Output> + Default,
   E: ParseError<I>,
+  G: NomRange<usize>,
 {
-  FoldMany0 {
+  Many {
     parser,
-    g,
-    init,
-    r: PhantomData,
+    range,
+    c: PhantomData,
   }
 }
 
-/// Parser implementation for the [fold_many0] combinator
-pub struct FoldMany0<F, G, Init, R> {
+/// Parser implementation for the [many] combinator
+pub struct Many<F, R, Collection> {
   parser: F,
-  g: G,
-  init: Init,
-  r: PhantomData<R>,
+  range: R,
+  c: PhantomData<Collection>,
 }
 
-impl<I, F, G, Init, R> Parser<I> for FoldMany0<F, G, Init, R>
+impl<I, F, R, Collection> Parser<I> for Many<F, R, Collection>
 where
   I: Clone + Input,
   F: Parser<I>,
-  G: FnMut(R, <F as Parser<I>>::Output) -> R,
-  Init: FnMut() -> R,
+  Collection: Extend<<F as Parser<I>>::Output> + Default,
+  R: NomRange<usize>,
 {
-  type Output = R;
+  type Output = Collection;
   type Error = <F as Parser<I>>::Error;
 
-  fn process<OM: OutputMode>(&mut self, i: I) -> crate::PResult<OM, I, Self::Output, Self::Error> {
-    let mut res = OM::Output::bind(|| (self.init)());
-    let mut input = i;
+  fn process<OM: OutputMode>(
+    &mut self,
+    mut input: I,
+  ) -> crate::PResult<OM, I, Self::Output, Self::Error> {
+    if self.range.is_inverted() {
+      return Err(Err::Failure(<F as Parser<I>>::Error::from_error_kind(
+        input,
+        ErrorKind::Many,
+      )));
+    }
 
-    loop {
-      let i_ = input.clone();
+    let mut res = OM::Output::bind(Collection::default);
+
+    for count in self.range.bounded_iter() {
       let len = input.input_len();
-      match self.parser.process::<OM>(i_) {
-        Ok((i, o)) => {
+      match self.parser.process::<OM>(input.clone()) {
+        Ok((tail, value)) => {
           // infinite loop check: the parser must always consume
-          if i.input_len() == len {
-            return Err(Err::Error(OM::Error::bind(|| {
-              <F as Parser<I>>::Error::from_error_kind(input, ErrorKind::Many0)
-            })));
+          if tail.input_len() == len {
+            return Err(infinite_loop::<OM, _, _>(input, ErrorKind::Many));
           }
 
-          res = OM::Output::combine(res, o, |res, o| (self.g)(res, o));
-          input = i;
+          res = OM::Output::combine(res, value, |mut res, value| {
+            res.extend(Some(value));
+            res
+          });
+          input = tail;
         }
-        Err(Err::Error(_)) => {
-          return Ok((input, res));
+        Err(Err::Error(e)) => {
+          if !self.range.contains(&count) {
+            return Err(Err::Error(OM::Error::map(e, |e| {
+              <F as Parser<I>>::Error::append(input, ErrorKind::Many, e)
+            })));
+          } else {
+            return Ok((input, res));
+          }
         }
         Err(e) => {
           return Err(e);
         }
       }
     }
+
+    Ok((input, res))
   }
 }
 
-/// Repeats the embedded parser, calling `g` to gather the results.
+/// Repeats the embedded parser like [many], adding the index of the element to
+/// the errors it returns.
 ///
-/// This stops on [`Err::Error`] if there is at least one result. To instead chain an error up,
-/// see [`cut`][crate::combinator::cut].
+/// When an element fails, its error gets a context made of `label` and the index of the
+/// element, starting at 0, like `element 17`. It is added with
+/// [ContextError::add_lazy_context], so it is only formatted by the errors keeping it,
+/// when the repetition fails. An element returning `Err::Error` after enough elements
+/// just ends the repetition, without context.
 ///
 /// # Arguments
-/// * `f` The parser to apply.
-/// * `init` A function returning the initial value.
-/// * `g` The function that combines a result of `f` with
-///       the current accumulator.
-///
-/// *Note*: If the parser passed to `many1` accepts empty inputs
-/// (like `alpha0` or `digit0`), `many1` will return an error,
-/// to prevent going into an infinite loop.
-///
+/// * `range` Constrains the number of iterations, like in [many].
+/// * `label` The name of the elements in the contexts.
+/// * `parser` The parser to apply.
 /// ```rust
-/// # use nom::{Err, error::{Error, ErrorKind}, Needed, IResult, Parser};
-/// use nom::multi::fold_many1;
-/// use nom::bytes::complete::tag;
+/// # use nom::{Err, error::ErrorKind, IResult, Parser};
+/// use nom::bytes::tag;
+/// use nom::character::complete::digit1;
+/// use nom::combinator::cut;
+/// use nom::multi::many_labeled;
+/// use nom::sequence::terminated;
+/// use nom_language::error::{VerboseError, VerboseErrorKind};
 ///
-/// fn parser(s: &str) -> IResult<&str, Vec<&str>> {
-///   fold_many1(
-///     tag("abc"),
-///     Vec::new,
-///     |mut acc: Vec<_>, item| {
-///       acc.push(item);
-///       acc
-///     }
-///   ).parse(s)
+/// fn parser(s: &str) -> IResult<&str, Vec<&str>, VerboseError<&str>> {
+///   many_labeled(0.., "element", terminated(digit1, cut(tag(",")))).parse(s)
 /// }
 ///
-/// assert_eq!(parser("abcabc"), Ok(("", vec!["abc", "abc"])));
-/// assert_eq!(parser("abc123"), Ok(("123", vec!["abc"])));
-/// assert_eq!(parser("123123"), Err(Err::Error(Error::new("123123", ErrorKind::Many1))));
-/// assert_eq!(parser(""), Err(Err::Error(Error::new("", ErrorKind::Many1))));
+/// assert_eq!(parser("1,2,3,"), Ok(("", vec!["1", "2", "3"])));
+/// assert_eq!(
+///   parser("1,2,3;"),
+///   Err(Err::Failure(VerboseError {
+///     errors: vec![
+///       (";", VerboseErrorKind::Nom(ErrorKind::Tag)),
+///       ("3;", VerboseErrorKind::Label("element 2".to_string())),
+///     ]
+///   }))
+/// );
 /// ```
-pub fn fold_many1<I, E, F, G, H, R>(
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "alloc")))]
+pub fn many_labeled<I, E, Collection, F, G>(
+  range: G,
+  label: &'static str,
   parser: F,
-  init: H,
-  g: G,
-) -> impl Parser<I, Output = R, Error = E>
+) -> impl Parser<I, Output = Collection, Error = E>
 where
   I: Clone + Input,
   F: Parser<I, Error = E>,
-  G: FnMut(R, <F as Parser<I>>::Output) -> R,
-  H: FnMut() -> R,
-  E: ParseError<I>,
+  Collection: Extend<<F as Parser<I>>::Output> + Default,
+  E: ParseError<I> + ContextError<I>,
+  G: NomRange<usize>,
 {
-  FoldMany1 {
+  ManyLabeled {
     parser,
-    g,
-    init,
-    r: PhantomData,
+    range,
+    label,
+    c: PhantomData,
   }
 }
 
-/// Parser implementation for the [fold_many1] combinator
-pub struct FoldMany1<F, G, Init, R> {
+/// Parser implementation for the [many_labeled] combinator
+pub struct ManyLabeled<F, R, Collection> {
   parser: F,
-  g: G,
-  init: Init,
-  r: PhantomData<R>,
+  range: R,
+  label: &'static str,
+  c: PhantomData<Collection>,
 }
 
-impl<I, F, G, Init, R> Parser<I> for FoldMany1<F, G, Init, R>
+/// Context of the element at `index`, formatted on demand
+struct Label {
+  label: &'static str,
+  index: usize,
+}
+
+impl fmt::Display for Label {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{} {}", self.label, self.index)
+  }
+}
+
+impl<I, F, R, Collection> Parser<I> for ManyLabeled<F, R, Collection>
 where
   I: Clone + Input,
   F: Parser<I>,
-  G: FnMut(R, <F as Parser<I>>::Output) -> R,
-  Init: FnMut() -> R,
+  <F as Parser<I>>::Error: ContextError<I>,
+  Collection: Extend<<F as Parser<I>>::Output> + Default,
+  R: NomRange<usize>,
 {
-  type Output = R;
+  type Output = Collection;
   type Error = <F as Parser<I>>::Error;
 
-  fn process<OM: OutputMode>(&mut self, i: I) -> crate::PResult<OM, I, Self::Output, Self::Error> {
-    let mut res = OM::Output::bind(|| (self.init)());
-    let input = i.clone();
+  fn process<OM: OutputMode>(
+    &mut self,
+    mut input: I,
+  ) -> crate::PResult<OM, I, Self::Output, Self::Error> {
+    if self.range.is_inverted() {
+      return Err(Err::Failure(<F as Parser<I>>::Error::from_error_kind(
+        input,
+        ErrorKind::Many,
+      )));
+    }
 
-    match self.parser.process::<OM>(input) {
-      Err(Err::Error(_)) => Err(Err::Error(OM::Error::bind(|| {
-        <F as Parser<I>>::Error::from_error_kind(i, ErrorKind::Many1)
-      }))),
-      Err(e) => Err(e),
-      Ok((i1, o1)) => {
-        res = OM::Output::combine(res, o1, |res, o| (self.g)(res, o));
+    let mut res = OM::Output::bind(Collection::default);
 
-        let mut input = i1;
-        loop {
-          let i_ = input.clone();
-          let len = input.input_len();
-          match self.parser.process::<OM>(i_) {
-            Ok((i, o)) => {
-              // infinite loop check: the parser must always consume
-              if i.input_len() == len {
-                return Err(Err::Error(OM::Error::bind(|| {
-                  <F as Parser<I>>::Error::from_error_kind(input, ErrorKind::Many1)
-                })));
-              }
+    for count in self.range.bounded_iter() {
+      let len = input.input_len();
+      let label = Label {
+        label: self.label,
+        index: count,
+      };
+      match self.parser.process::<OM>(input.clone()) {
+        Ok((tail, value)) => {
+          // infinite loop check: the parser must always consume
+          if tail.input_len() == len {
+            return Err(infinite_loop::<OM, _, _>(input, ErrorKind::Many));
+          }
 
-              res = OM::Output::combine(res, o, |res, o| (self.g)(res, o));
-              input = i;
-            }
-            Err(Err::Error(_)) => {
-              return Ok((input, res));
-            }
-            Err(e) => {
-              return Err(e);
-            }
+          res = OM::Output::combine(res, value, |mut res, value| {
+            res.extend(Some(value));
+            res
+          });
+          input = tail;
+        }
+        Err(Err::Error(e)) => {
+          if !self.range.contains(&count) {
+            return Err(Err::Error(OM::Error::map(e, |e| {
+              let e = <F as Parser<I>>::Error::add_lazy_context(input.clone(), &label, e);
+              <F as Parser<I>>::Error::append(input, ErrorKind::Many, e)
+            })));
+          } else {
+            return Ok((input, res));
           }
         }
+        Err(Err::Failure(e)) => {
+          return Err(Err::Failure(<F as Parser<I>>::Error::add_lazy_context(
+            input, &label, e,
+          )));
+        }
+        Err(e) => {
+          return Err(e);
+        }
       }
     }
+
+    Ok((input, res))
   }
 }
 
-/// Repeats the embedded parser `m..=n` times, calling `g` to gather the results
-///
-/// This stops before `n` when the parser returns [`Err::Error`]. To instead chain an error up, see
-/// [`cut`][crate::combinator::cut].
+/// Applies a parser and accumulates the results using a given
+/// function and initial value.
+/// Fails if the amount of time the embedded parser is run is not
+/// within the specified range.
 ///
 /// # Arguments
-/// * `m` The minimum number of iterations.
-/// * `n` The maximum number of iterations.
-/// * `f` The parser to apply.
+/// * `range` Constrains the number of iterations.
+///   * A range without an upper bound `a..` allows the parser to run until it fails.
+///   * A single `usize` value is equivalent to `value..=value`.
+///   * An empty range is invalid.
+/// * `parse` The parser to apply.
 /// * `init` A function returning the initial value.
-/// * `g` The function that combines a result of `f` with
+/// * `fold` The function that combines a result of `f` with
 ///       the current accumulator.
-///
-/// *Note*: If the parser passed to `many1` accepts empty inputs
-/// (like `alpha0` or `digit0`), `many1` will return an error,
-/// to prevent going into an infinite loop.
-///
 /// ```rust
+/// # #[macro_use] extern crate nom;
 /// # use nom::{Err, error::ErrorKind, Needed, IResult, Parser};
-/// use nom::multi::fold_many_m_n;
+/// use nom::multi::fold;
 /// use nom::bytes::complete::tag;
 ///
 /// fn parser(s: &str) -> IResult<&str, Vec<&str>> {
-///   fold_many_m_n(
-///     0,
-///     2,
+///   fold(
+///     0..=2,
 ///     tag("abc"),
 ///     Vec::new,
 ///     |mut acc: Vec<_>, item| {
@@ -1265,12 +3557,11 @@ where
 /// assert_eq!(parser(""), Ok(("", vec![])));
 /// assert_eq!(parser("abcabcabc"), Ok(("abc", vec!["abc", "abc"])));
 /// ```
-pub fn fold_many_m_n<I, E, F, G, H, R>(
-  min: usize,
-  max: usize,
+pub fn fold<I, E, F, G, H, J, R>(
+  range: J,
   parser: F,
   init: H,
-  g: G,
+  fold: G,
 ) -> impl Parser<I, Output = R, Error = E>
 where
   I: Clone + Input,
@@ -1278,66 +3569,64 @@ where
   G: FnMut(R, <F as Parser<I>>::Output) -> R,
   H: FnMut() -> R,
   E: ParseError<I>,
+  J: NomRange<usize>,
 {
-  FoldManyMN {
+  Fold {
     parser,
-    g,
     init,
-    min,
-    max,
-    r: PhantomData,
+    fold,
+    range,
   }
 }
 
-/// Parser implementation for the [fold_many_m_n] combinator
-pub struct FoldManyMN<F, G, Init, R> {
+/// Parser implementation for the [fold] combinator
+pub struct Fold<F, G, H, Range> {
   parser: F,
-  g: G,
-  init: Init,
-  r: PhantomData<R>,
-  min: usize,
-  max: usize,
+  init: H,
+  fold: G,
+  range: Range,
 }
 
-impl<I, F, G, Init, R> Parser<I> for FoldManyMN<F, G, Init, R>
+impl<I, F, G, H, Range, Res> Parser<I> for Fold<F, G, H, Range>
 where
   I: Clone + Input,
   F: Parser<I>,
-  G: FnMut(R, <F as Parser<I>>::Output) -> R,
-  Init: FnMut() -> R,
+  G: FnMut(Res, <F as Parser<I>>::Output) -> Res,
+  H: FnMut() -> Res,
+  Range: NomRange<usize>,
 {
-  type Output = R;
+  type Output = Res;
   type Error = <F as Parser<I>>::Error;
 
   fn process<OM: OutputMode>(
     &mut self,
     mut input: I,
   ) -> crate::PResult<OM, I, Self::Output, Self::Error> {
-    if self.min > self.max {
-      return Err(Err::Error(OM::Error::bind(|| {
-        <F as Parser<I>>::Error::from_error_kind(input, ErrorKind::ManyMN)
-      })));
+    if self.range.is_inverted() {
+      return Err(Err::Failure(<F as Parser<I>>::Error::from_error_kind(
+        input,
+        ErrorKind::Fold,
+      )));
     }
 
-    let mut res = OM::Output::bind(|| (self.init)());
-    for count in 0..self.max {
+    let mut acc = OM::Output::bind(|| (self.init)());
+
+    for count in self.range.saturating_iter() {
       let len = input.input_len();
       match self.parser.process::<OM>(input.clone()) {
         Ok((tail, value)) => {
           // infinite loop check: the parser must always consume
           if tail.input_len() == len {
-            return Err(Err::Error(OM::Error::bind(|| {
-              <F as Parser<I>>::Error::from_error_kind(tail, ErrorKind::ManyMN)
-            })));
+            return Err(infinite_loop::<OM, _, _>(tail, ErrorKind::Fold));
           }
 
-          res = OM::Output::combine(res, value, |res, o| (self.g)(res, o));
+          acc = OM::Output::combine(acc, value, |acc, value| (self.fold)(acc, value));
           input = tail;
         }
         Err(Err::Error(err)) => {
-          if count < self.min {
+          if !self.range.contains(&count) {
             return Err(Err::Error(OM::Error::map(err, |err| {
-              <F as Parser<I>>::Error::append(input, ErrorKind::ManyMN, err)
+              <F as Parser<I>>::Error::append(input, ErrorKind::Fold, err)
             })));
           } else {
             break;
@@ -1347,354 +3636,310 @@ where
       }
     }
 
-    Ok((input, res))
+    Ok((input, acc))
   }
 }
 
-/// Gets a number from the parser and returns a
-/// subslice of the input of that size.
-/// If the parser returns `Incomplete`,
-/// `length_data` will return an error.
-/// # Arguments
-/// * `f` The parser to apply.
-/// ```rust
-/// # use nom::{Err, error::ErrorKind, Needed, IResult, Parser};
-/// use nom::number::complete::be_u16;
-/// use nom::multi::length_data;
-/// use nom::bytes::complete::tag;
+/// Repeats the embedded parser like [many0], gathering the results in any collection
+/// implementing [Default] and [Extend].
 ///
-/// fn parser(s: &[u8]) -> IResult<&[u8], &[u8]> {
-///   length_data(be_u16).parse(s)
-/// }
+/// This stops on [`Err::Error`] and returns the results that were accumulated. The
+/// collection is created with [Default::default], so its allocation strategy is chosen
+/// by the caller: a `HashMap` or `BTreeMap` built from key-value pairs, a `String`
+/// from characters, or a fixed capacity vector type that does not allocate.
+///
+/// *Note*: if the parser passed in accepts empty inputs (like `alpha0` or `digit0`),
+/// `collect_many` will return an error with [ErrorKind::Many0], to prevent going into
+/// an infinite loop
 ///
-/// assert_eq!(parser(b"\x00\x03abcefg"), Ok((&b"efg"[..], &b"abc"[..])));
-/// assert_eq!(parser(b"\x00\x03a"), Err(Err::Incomplete(Needed::new(2))));
-/// ```
-pub fn length_data<I, E, F>(f: F) -> impl Parser<I, Output = I, Error = E>
-where
-  I: Input,
-  <F as Parser<I>>::Output: ToUsize,
-  F: Parser<I, Error = E>,
-  E: ParseError<I>,
-{
-  f.flat_map(|size| take(size))
-}
-
-/// Gets a number from the first parser,
-/// takes a subslice of the input of that size,
-/// then applies the second parser on that subslice.
-/// If the second parser returns `Incomplete`,
-/// `length_value` will return an error.
-/// # Arguments
-/// * `f` The parser to apply.
-/// * `g` The parser to apply on the subslice.
 /// ```rust
-/// # use nom::{Err, error::{Error, ErrorKind}, Needed, IResult, Parser};
-/// use nom::number::complete::be_u16;
-/// use nom::multi::length_value;
-/// use nom::bytes::complete::tag;
+/// # use nom::{Err, error::ErrorKind, Needed, IResult, Parser};
+/// use nom::multi::collect_many;
+/// use nom::character::complete::{alpha1, char, digit1};
+/// use nom::sequence::{separated_pair, terminated};
 ///
-/// fn parser(s: &[u8]) -> IResult<&[u8], &[u8]> {
-///   length_value(be_u16, tag("abc")).parse(s)
+/// use std::collections::BTreeMap;
+///
+/// fn parser(s: &str) -> IResult<&str, BTreeMap<&str, &str>> {
+///   collect_many(terminated(separated_pair(alpha1, char('='), digit1), char(';'))).parse(s)
 /// }
 ///
-/// assert_eq!(parser(b"\x00\x03abcefg"), Ok((&b"efg"[..], &b"abc"[..])));
-/// assert_eq!(parser(b"\x00\x03123123"), Err(Err::Error(Error::new(&b"123"[..], ErrorKind::Tag))));
-/// assert_eq!(parser(b"\x00\x03a"), Err(Err::Incomplete(Needed::new(2))));
+/// assert_eq!(parser("b=2;a=1;"), Ok(("", BTreeMap::from([("a", "1"), ("b", "2")]))));
+/// assert_eq!(parser("a=1;b"), Ok(("b", BTreeMap::from([("a", "1")]))));
+/// assert_eq!(parser(""), Ok(("", BTreeMap::new())));
 /// ```
-pub fn length_value<I, E, F, G>(
-  f: F,
-  g: G,
-) -> impl Parser<I, Output = <G as Parser<I>>::Output, Error = E>
+pub fn collect_many<I, E, Collection, F>(
+  parser: F,
+) -> impl Parser<I, Output = Collection, Error = E>
 where
   I: Clone + Input,
-  <F as Parser<I>>::Output: ToUsize,
   F: Parser<I, Error = E>,
-  G: Parser<I, Error = E>,
+  Collection: Extend<<F as Parser<I>>::Output> + Default,
   E: ParseError<I>,
 {
-  /*f.flat_map(|size| {
-    println!("got size: {size}");
-    take(size)
-  })
-  .and_then(g)*/
-  LengthValue {
-    length: f,
-    parser: g,
-    e: PhantomData,
+  CollectMany {
+    parser,
+    c: PhantomData,
   }
 }
 
-/// Parser implementation for the [length_value] combinator
-pub struct LengthValue<F, G, E> {
-  length: F,
-  parser: G,
-  e: PhantomData<E>,
+/// Parser implementation for the [collect_many] combinator
+pub struct CollectMany<F, Collection> {
+  parser: F,
+  c: PhantomData<Collection>,
 }
 
-impl<I, F, G, E> Parser<I> for LengthValue<F, G, E>
+impl<I, F, Collection> Parser<I> for CollectMany<F, Collection>
 where
   I: Clone + Input,
-  F: Parser<I, Error = E>,
-  G: Parser<I, Error = E>,
-  <F as Parser<I>>::Output: ToUsize,
-  E: ParseError<I>,
+  F: Parser<I>,
+  Collection: Extend<<F as Parser<I>>::Output> + Default,
 {
-  type Output = <G as Parser<I>>::Output;
-  type Error = E;
+  type Output = Collection;
+  type Error = <F as Parser<I>>::Error;
 
   fn process<OM: OutputMode>(
     &mut self,
-    input: I,
+    mut i: I,
   ) -> crate::PResult<OM, I, Self::Output, Self::Error> {
-    let (i, length) = self
-      .length
-      .process::<OutputM<Emit, OM::Error, OM::Incomplete>>(input)?;
+    let mut acc = OM::Output::bind(Collection::default);
+    loop {
+      let len = i.input_len();
+      match self
+        .parser
+        .process::<OutputM<OM::Output, Check, OM::Incomplete>>(i.clone())
+      {
+        Err(Err::Error(_)) => return Ok((i, acc)),
+        Err(Err::Failure(e)) => return Err(Err::Failure(e)),
+        Err(Err::Incomplete(e)) => return Err(Err::Incomplete(e)),
+        Ok((i1, o)) => {
+          // infinite loop check: the parser must always consume
+          if i1.input_len() == len {
+            return Err(infinite_loop::<OM, _, _>(i, ErrorKind::Many0));
+          }
 
-    let length: usize = length.to_usize();
+          i = i1;
 
-    if let Some(needed) = length
-      .checked_sub(i.input_len())
-      .and_then(NonZeroUsize::new)
-    {
-      Err(Err::Incomplete(Needed::Size(needed)))
-    } else {
-      let (rest, i) = i.take_split(length);
-      match self.parser.process::<OM>(i.clone()) {
-        Err(Err::Incomplete(_)) => Err(Err::Error(OM::Error::bind(|| {
-          E::from_error_kind(i, ErrorKind::Complete)
-        }))),
-        Err(e) => Err(e),
-        Ok((_, o)) => Ok((rest, o)),
+          acc = OM::Output::combine(acc, o, |mut acc, o| {
+            acc.extend(Some(o));
+            acc
+          })
+        }
       }
     }
   }
 }
 
-/// Gets a number from the first parser,
-/// then applies the second parser that many times.
+/// Alternates between two parsers like [separated_list0], gathering the elements in
+/// any collection implementing [Default] and [Extend].
+///
+/// This stops when either parser returns [`Err::Error`] and returns the results that
+/// were accumulated. As with [collect_many], the collection is created with
+/// [Default::default], so parsing `key=value` pairs straight into a map needs no
+/// intermediate `Vec`.
+///
 /// # Arguments
-/// * `f` The parser to apply to obtain the count.
-/// * `g` The parser to apply repeatedly.
+/// * `sep` Parses the separator between list elements.
+/// * `f` Parses the elements of the list.
+///
 /// ```rust
-/// # use nom::{Err, error::{Error, ErrorKind}, Needed, IResult, Parser};
-/// use nom::number::complete::u8;
-/// use nom::multi::length_count;
-/// use nom::bytes::complete::tag;
-/// use nom::combinator::map;
+/// # use nom::{Err, error::ErrorKind, Needed, IResult, Parser};
+/// use nom::multi::collect_separated;
+/// use nom::character::complete::{alpha1, char, digit1};
+/// use nom::sequence::separated_pair;
 ///
-/// fn parser(s: &[u8]) -> IResult<&[u8], Vec<&[u8]>> {
-///   length_count(map(u8, |i| {
-///      println!("got number: {}", i);
-///      i
-///   }), tag("abc")).parse(s)
+/// use std::collections::HashMap;
+///
+/// fn parser(s: &str) -> IResult<&str, HashMap<&str, &str>> {
+///   collect_separated(char('&'), separated_pair(alpha1, char('='), digit1)).parse(s)
 /// }
 ///
-/// assert_eq!(parser(&b"\x02abcabcabc"[..]), Ok(((&b"abc"[..], vec![&b"abc"[..], &b"abc"[..]]))));
-/// assert_eq!(parser(b"\x03123123123"), Err(Err::Error(Error::new(&b"123123123"[..], ErrorKind::Tag))));
+/// assert_eq!(parser("a=1&b=2"), Ok(("", HashMap::from([("a", "1"), ("b", "2")]))));
+/// // later values replace earlier ones, as with `HashMap::extend`
+/// assert_eq!(parser("a=1&a=2&"), Ok(("&", HashMap::from([("a", "2")]))));
+/// assert_eq!(parser(""), Ok(("", HashMap::new())));
 /// ```
-#[cfg(feature = "alloc")]
-pub fn length_count<I, E, F, G>(
+pub fn collect_separated<I, E, Collection, F, G>(
+  sep: G,
   f: F,
-  g: G,
-) -> impl Parser<I, Output = Vec<<G as Parser<I>>::Output>, Error = E>
+) -> impl Parser<I, Output = Collection, Error = E>
 where
-  I: Clone,
-  <F as Parser<I>>::Output: ToUsize,
+  I: Clone + Input,
   F: Parser<I, Error = E>,
   G: Parser<I, Error = E>,
+  Collection: Extend<<F as Parser<I>>::Output> + Default,
   E: ParseError<I>,
 {
-  LengthCount {
-    length: f,
-    parser: g,
-    e: PhantomData,
+  CollectSeparated {
+    parser: f,
+    separator: sep,
+    c: PhantomData,
   }
 }
 
-#[cfg(feature = "alloc")]
-/// Parser implementation for the [length_count] combinator
-pub struct LengthCount<F, G, E> {
-  length: F,
-  parser: G,
-  e: PhantomData<E>,
+/// Parser implementation for the [collect_separated] combinator
+pub struct CollectSeparated<F, G, Collection> {
+  parser: F,
+  separator: G,
+  c: PhantomData<Collection>,
 }
 
-#[cfg(feature = "alloc")]
-impl<I, F, G, E> Parser<I> for LengthCount<F, G, E>
+impl<I, E: ParseError<I>, F, G, Collection> Parser<I> for CollectSeparated<F, G, Collection>
 where
-  I: Clone,
+  I: Clone + Input,
   F: Parser<I, Error = E>,
   G: Parser<I, Error = E>,
-  <F as Parser<I>>::Output: ToUsize,
-  E: ParseError<I>,
+  Collection: Extend<<F as Parser<I>>::Output> + Default,
 {
-  type Output = Vec<<G as Parser<I>>::Output>;
+  type Output = Collection;
   type Error = E;
 
   fn process<OM: OutputMode>(
     &mut self,
-    input: I,
+    mut i: I,
   ) -> crate::PResult<OM, I, Self::Output, Self::Error> {
+    let mut res = OM::Output::bind(Collection::default);
+
     match self
-      .length
-      .process::<OutputM<Emit, OM::Error, OM::Incomplete>>(input)
+      .parser
+      .process::<OutputM<OM::Output, Check, OM::Incomplete>>(i.clone())
     {
-      Err(e) => Err(e),
-      Ok((i, count)) => {
-        let count = count.to_usize();
-        let mut input = i.clone();
-        let max_initial_capacity = MAX_INITIAL_CAPACITY_BYTES
-          / crate::lib::std::mem::size_of::<<F as Parser<I>>::Output>().max(1);
-        let mut res = OM::Output::bind(|| {
-          crate::lib::std::vec::Vec::with_capacity(count.min(max_initial_capacity))
+      Err(Err::Error(_)) => return Ok((i, res)),
+      Err(Err::Failure(e)) => return Err(Err::Failure(e)),
+      Err(Err::Incomplete(e)) => return Err(Err::Incomplete(e)),
+      Ok((i1, o)) => {
+        res = OM::Output::combine(res, o, |mut res, o| {
+          res.extend(Some(o));
+          res
         });
+        i = i1;
+      }
+    }
+
+    loop {
+      let len = i.input_len();
+      match self
+        .separator
+        .process::<OutputM<Check, Check, OM::Incomplete>>(i.clone())
+      {
+        Err(Err::Error(_)) => return Ok((i, res)),
+        Err(Err::Failure(e)) => return Err(Err::Failure(e)),
+        Err(Err::Incomplete(e)) => return Err(Err::Incomplete(e)),
+        Ok((i1, _)) => {
+          match self
+            .parser
+            .process::<OutputM<OM::Output, Check, OM::Incomplete>>(i1.clone())
+          {
+            Err(Err::Error(_)) => return Ok((i, res)),
+            Err(Err::Failure(e)) => return Err(Err::Failure(e)),
+            Err(Err::Incomplete(e)) => return Err(Err::Incomplete(e)),
+            Ok((i2, o)) => {
+              // infinite loop check: the parser must always consume
+              if i2.input_len() == len {
+                return Err(infinite_loop::<OM, _, _>(i, ErrorKind::SeparatedList));
+              }
 
-        for _ in 0..count {
-          let input_ = input.clone();
-          match self.parser.process::<OM>(input_) {
-            Ok((i, o)) => {
               res = OM::Output::combine(res, o, |mut res, o| {
-                res.push(o);
+                res.extend(Some(o));
                 res
               });
-              input = i;
-            }
-            Err(Err::Error(e)) => {
-              return Err(Err::Error(OM::Error::map(e, |e| {
-                <F as Parser<I>>::Error::append(i, ErrorKind::Count, e)
-              })));
-            }
-            Err(e) => {
-              return Err(e);
+
+              i = i2;
             }
           }
         }
-
-        Ok((input, res))
       }
     }
   }
 }
 
-/// Repeats the embedded parser and collects the results in a type implementing `Extend + Default`.
-/// Fails if the amount of time the embedded parser is run is not
-/// within the specified range.
-/// # Arguments
-/// * `range` Constrains the number of iterations.
-///   * A range without an upper bound `a..` is equivalent to a range of `a..=usize::MAX`.
-///   * A single `usize` value is equivalent to `value..=value`.
-///   * An empty range is invalid.
-/// * `parse` The parser to apply.
-///
-/// ```rust
-/// # #[macro_use] extern crate nom;
-/// # use nom::{Err, error::ErrorKind, Needed, IResult, Parser};
-/// use nom::multi::many;
-/// use nom::bytes::complete::tag;
-///
-/// fn parser(s: &str) -> IResult<&str, Vec<&str>> {
-///   many(0..=2, tag("abc")).parse(s)
-/// }
+/// Information about a repeated key, reported by [unique_by] through
+/// [FromExternalError](crate::error::FromExternalError).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DuplicateKey<I, K> {
+  /// The repeated key
+  pub key: K,
+  /// Position of the first item with that key
+  pub first: I,
+  /// Position of the second item with that key
+  pub second: I,
+}
+
+impl<I, K: fmt::Debug> fmt::Display for DuplicateKey<I, K> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "duplicate key {:?}", self.key)
+  }
+}
+
+/// Repeats the embedded parser like [many], and checks that every item has a different key.
 ///
-/// assert_eq!(parser("abcabc"), Ok(("", vec!["abc", "abc"])));
-/// assert_eq!(parser("abc123"), Ok(("123", vec!["abc"])));
-/// assert_eq!(parser("123123"), Ok(("123123", vec![])));
-/// assert_eq!(parser(""), Ok(("", vec![])));
-/// assert_eq!(parser("abcabcabc"), Ok(("abc", vec!["abc", "abc"])));
-/// ```
+/// The `key` function extracts the key of each parsed item. If two items share the
+/// same key, `unique_by` returns an error with [ErrorKind::Unique] at the position of
+/// the second item. The error is built with
+/// [FromExternalError](crate::error::FromExternalError) from a [DuplicateKey] value
+/// holding the key and the positions of both items, so a custom error type can report
+/// them.
 ///
-/// This is not limited to `Vec`, other collections like `HashMap`
-/// can be used:
+/// # Arguments
+/// * `range` Constrains the number of iterations, like in [many].
+/// * `parser` The parser to apply.
+/// * `key` The function extracting the key from a parsed item.
 ///
 /// ```rust
-/// # #[macro_use] extern crate nom;
-/// # use nom::{Err, error::ErrorKind, Needed, IResult, Parser};
-/// use nom::multi::many;
-/// use nom::bytes::complete::{tag, take_while};
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+/// use nom::multi::unique_by;
+/// use nom::character::complete::{alpha1, char, digit1};
 /// use nom::sequence::{separated_pair, terminated};
-/// use nom::AsChar;
-///
-/// use std::collections::HashMap;
-///
-/// fn key_value(s: &str) -> IResult<&str, HashMap<&str, &str>> {
-///   many(0.., terminated(
-///     separated_pair(
-///       take_while(AsChar::is_alpha),
-///       tag("="),
-///       take_while(AsChar::is_alpha)
-///     ),
-///     tag(";")
-///   )).parse(s)
-/// }
-///
-/// assert_eq!(
-///   key_value("a=b;c=d;"),
-///   Ok(("", HashMap::from([("a", "b"), ("c", "d")])))
-/// );
-/// ```
-///
-/// If more control is needed on the default value, [fold] can
-/// be used instead:
-///
-/// ```rust
-/// # #[macro_use] extern crate nom;
-/// # use nom::{Err, error::ErrorKind, Needed, IResult, Parser};
-/// use nom::multi::fold;
-/// use nom::bytes::complete::tag;
-///
 ///
-/// fn parser(s: &str) -> IResult<&str, Vec<&str>> {
-///   fold(
-///     0..=4,
-///     tag("abc"),
-///     // preallocates a vector of the max size
-///     || Vec::with_capacity(4),
-///     |mut acc: Vec<_>, item| {
-///       acc.push(item);
-///       acc
-///     }
-///   ).parse(s)
+/// fn headers(s: &str) -> IResult<&str, Vec<(&str, &str)>> {
+///   unique_by(
+///     0..,
+///     terminated(separated_pair(alpha1, char('='), digit1), char(';')),
+///     |(name, _): &(&str, &str)| name.to_ascii_lowercase(),
+///   )
+///   .parse(s)
 /// }
 ///
-///
-/// assert_eq!(parser("abcabcabcabc"), Ok(("", vec!["abc", "abc", "abc", "abc"])));
+/// assert_eq!(headers("a=1;b=2;"), Ok(("", vec![("a", "1"), ("b", "2")])));
+/// assert_eq!(headers("a=1;b=2;A=3;"), Err(Err::Error(Error::new("A=3;", ErrorKind::Unique))));
 /// ```
 #[cfg(feature = "alloc")]
 #[cfg_attr(feature = "docsrs", doc(cfg(feature = "alloc")))]
-pub fn many<I, E, Collection, F, G>(
-  range: G,
+pub fn unique_by<I, E, F, G, K, R>(
+  range: R,
   parser: F,
-) -> impl Parser<I, Output = Collection, Error = E>
+  key: G,
+) -> impl Parser<I, Output = Vec<<F as Parser<I>>::Output>, Error = E>
 where
   I: Clone + Input,
   F: Parser<I, Error = E>,
-  Collection: Extend<<F as Parser<I>>::Output> + Default,
-  E: ParseError<I>,
-  G: NomRange<usize>,
+  G: FnMut(&<F as Parser<I>>::Output) -> K,
+  K: Ord,
+  E: ParseError<I> + FromExternalError<I, DuplicateKey<I, K>>,
+  R: NomRange<usize>,
 {
-  Many {
-    parser,
-    range,
-    c: PhantomData,
-  }
+  UniqueBy { parser, key, range }
 }
 
-/// Parser implementation for the [many] combinator
-pub struct Many<F, R, Collection> {
+#[cfg(feature = "alloc")]
+/// Parser implementation for the [unique_by] combinator
+pub struct UniqueBy<F, G, R> {
   parser: F,
+  key: G,
   range: R,
-  c: PhantomData<Collection>,
 }
 
-impl<I, F, R, Collection> Parser<I> for Many<F, R, Collection>
+#[cfg(feature = "alloc")]
+impl<I, F, G, K, R> Parser<I> for UniqueBy<F, G, R>
 where
   I: Clone + Input,
   F: Parser<I>,
-  Collection: Extend<<F as Parser<I>>::Output> + Default,
+  G: FnMut(&<F as Parser<I>>::Output) -> K,
+  K: Ord,
+  <F as Parser<I>>::Error: FromExternalError<I, DuplicateKey<I, K>>,
   R: NomRange<usize>,
 {
-  type Output = Collection;
+  type Output = Vec<<F as Parser<I>>::Output>;
   type Error = <F as Parser<I>>::Error;
 
   fn process<OM: OutputMode>(
@@ -1704,25 +3949,43 @@ where
     if self.range.is_inverted() {
       return Err(Err::Failure(<F as Parser<I>>::Error::from_error_kind(
         input,
-        ErrorKind::Many,
+        ErrorKind::Unique,
       )));
     }
 
-    let mut res = OM::Output::bind(Collection::default);
+    let mut seen = BTreeMap::new();
+    let mut res = OM::Output::bind(|| reserve("unique_by", 0));
 
     for count in self.range.bounded_iter() {
       let len = input.input_len();
-      match self.parser.process::<OM>(input.clone()) {
+      match self
+        .parser
+        .process::<OutputM<Emit, OM::Error, OM::Incomplete>>(input.clone())
+      {
         Ok((tail, value)) => {
           // infinite loop check: the parser must always consume
           if tail.input_len() == len {
+            return Err(infinite_loop::<OM, _, _>(input, ErrorKind::Unique));
+          }
+
+          let key = (self.key)(&value);
+          if let Some(first) = seen.get(&key).cloned() {
             return Err(Err::Error(OM::Error::bind(|| {
-              <F as Parser<I>>::Error::from_error_kind(input, ErrorKind::Many)
+              <F as Parser<I>>::Error::from_external_error(
+                input.clone(),
+                ErrorKind::Unique,
+                DuplicateKey {
+                  key,
+                  first,
+                  second: input,
+                },
+              )
             })));
           }
+          seen.insert(key, input);
 
-          res = OM::Output::combine(res, value, |mut res, value| {
-            res.extend(Some(value));
+          res = OM::Output::map(res, |mut res| {
+            push("unique_by", &mut res, value);
             res
           });
           input = tail;
@@ -1730,15 +3993,13 @@ where
         Err(Err::Error(e)) => {
           if !self.range.contains(&count) {
             return Err(Err::Error(OM::Error::map(e, |e| {
-              <F as Parser<I>>::Error::append(input, ErrorKind::Many, e)
+              <F as Parser<I>>::Error::append(input, ErrorKind::Unique, e)
             })));
           } else {
             return Ok((input, res));
           }
         }
-        Err(e) => {
-          return Err(e);
-        }
+        Err(e) => return Err(e),
       }
     }
 
@@ -1746,83 +4007,102 @@ where
   }
 }
 
-/// Applies a parser and accumulates the results using a given
-/// function and initial value.
-/// Fails if the amount of time the embedded parser is run is not
-/// within the specified range.
+/// Repeats the embedded parser like [many], and checks that the items arrive in order.
+///
+/// `compare` is called with each pair of consecutive items, and must return `true` if
+/// they are in the expected order. Otherwise, `sorted_by` returns an error with
+/// [ErrorKind::Sorted] at the position of the first out of order item.
 ///
 /// # Arguments
-/// * `range` Constrains the number of iterations.
-///   * A range without an upper bound `a..` allows the parser to run until it fails.
-///   * A single `usize` value is equivalent to `value..=value`.
-///   * An empty range is invalid.
-/// * `parse` The parser to apply.
-/// * `init` A function returning the initial value.
-/// * `fold` The function that combines a result of `f` with
-///       the current accumulator.
+/// * `range` Constrains the number of iterations, like in [many].
+/// * `parser` The parser to apply.
+/// * `compare` The function checking the order of two consecutive items.
+///
 /// ```rust
-/// # #[macro_use] extern crate nom;
-/// # use nom::{Err, error::ErrorKind, Needed, IResult, Parser};
-/// use nom::multi::fold;
-/// use nom::bytes::complete::tag;
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+/// use nom::multi::sorted_by;
+/// use nom::character::complete::{char, u32};
+/// use nom::sequence::terminated;
 ///
-/// fn parser(s: &str) -> IResult<&str, Vec<&str>> {
-///   fold(
-///     0..=2,
-///     tag("abc"),
-///     Vec::new,
-///     |mut acc: Vec<_>, item| {
-///       acc.push(item);
-///       acc
-///     }
-///   ).parse(s)
+/// // timestamps must be strictly increasing
+/// fn timestamps(s: &str) -> IResult<&str, Vec<u32>> {
+///   sorted_by(0.., terminated(u32, char(';')), |prev, next| prev < next).parse(s)
 /// }
 ///
-/// assert_eq!(parser("abcabc"), Ok(("", vec!["abc", "abc"])));
-/// assert_eq!(parser("abc123"), Ok(("123", vec!["abc"])));
-/// assert_eq!(parser("123123"), Ok(("123123", vec![])));
-/// assert_eq!(parser(""), Ok(("", vec![])));
-/// assert_eq!(parser("abcabcabc"), Ok(("abc", vec!["abc", "abc"])));
+/// assert_eq!(timestamps("1;5;12;"), Ok(("", vec![1, 5, 12])));
+/// assert_eq!(timestamps("1;5;5;12;"), Err(Err::Error(Error::new("5;12;", ErrorKind::Sorted))));
 /// ```
-pub fn fold<I, E, F, G, H, J, R>(
-  range: J,
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "alloc")))]
+pub fn sorted_by<I, E, F, G, R>(
+  range: R,
   parser: F,
-  init: H,
-  fold: G,
-) -> impl Parser<I, Output = R, Error = E>
+  compare: G,
+) -> impl Parser<I, Output = Vec<<F as Parser<I>>::Output>, Error = E>
 where
   I: Clone + Input,
   F: Parser<I, Error = E>,
-  G: FnMut(R, <F as Parser<I>>::Output) -> R,
-  H: FnMut() -> R,
+  G: FnMut(&<F as Parser<I>>::Output, &<F as Parser<I>>::Output) -> bool,
   E: ParseError<I>,
-  J: NomRange<usize>,
+  R: NomRange<usize>,
 {
-  Fold {
+  SortedBy {
     parser,
-    init,
-    fold,
+    compare,
     range,
   }
 }
 
-/// Parser implementation for the [fold] combinator
-pub struct Fold<F, G, H, Range> {
+/// Repeats the embedded parser like [many], and checks that the items never decrease.
+///
+/// This is equivalent to [sorted_by] with `|prev, next| prev <= next`. It returns an error
+/// with [ErrorKind::Sorted] at the position of the first item lower than the previous one.
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+/// use nom::multi::monotonic;
+/// use nom::number::complete::be_u16;
+///
+/// fn offsets(s: &[u8]) -> IResult<&[u8], Vec<u16>> {
+///   monotonic(0.., be_u16).parse(s)
+/// }
+///
+/// assert_eq!(offsets(&[0, 1, 0, 1, 0, 4]), Ok((&[][..], vec![1, 1, 4])));
+/// assert_eq!(offsets(&[0, 4, 0, 1, 0, 5]), Err(Err::Error(Error::new(&[0, 1, 0, 5][..], ErrorKind::Sorted))));
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "alloc")))]
+pub fn monotonic<I, E, F, R>(
+  range: R,
   parser: F,
-  init: H,
-  fold: G,
-  range: Range,
+) -> impl Parser<I, Output = Vec<<F as Parser<I>>::Output>, Error = E>
+where
+  I: Clone + Input,
+  F: Parser<I, Error = E>,
+  <F as Parser<I>>::Output: PartialOrd,
+  E: ParseError<I>,
+  R: NomRange<usize>,
+{
+  sorted_by(range, parser, |prev, next| prev <= next)
 }
 
-impl<I, F, G, H, Range, Res> Parser<I> for Fold<F, G, H, Range>
+#[cfg(feature = "alloc")]
+/// Parser implementation for the [sorted_by] combinator
+pub struct SortedBy<F, G, R> {
+  parser: F,
+  compare: G,
+  range: R,
+}
+
+#[cfg(feature = "alloc")]
+impl<I, F, G, R> Parser<I> for SortedBy<F, G, R>
 where
   I: Clone + Input,
   F: Parser<I>,
-  G: FnMut(Res, <F as Parser<I>>::Output) -> Res,
-  H: FnMut() -> Res,
-  Range: NomRange<usize>,
+  G: FnMut(&<F as Parser<I>>::Output, &<F as Parser<I>>::Output) -> bool,
+  R: NomRange<usize>,
 {
-  type Output = Res;
+  type Output = Vec<<F as Parser<I>>::Output>;
   type Error = <F as Parser<I>>::Error;
 
   fn process<OM: OutputMode>(
@@ -1832,30 +4112,45 @@ where
     if self.range.is_inverted() {
       return Err(Err::Failure(<F as Parser<I>>::Error::from_error_kind(
         input,
-        ErrorKind::Fold,
+        ErrorKind::Sorted,
       )));
     }
 
-    let mut acc = OM::Output::bind(|| (self.init)());
+    // the last item is kept out of the result until the next one is compared to it
+    let mut last = None;
+    let mut res = OM::Output::bind(|| reserve("sorted_by", 0));
 
-    for count in self.range.saturating_iter() {
+    for count in self.range.bounded_iter() {
       let len = input.input_len();
-      match self.parser.process::<OM>(input.clone()) {
+      match self
+        .parser
+        .process::<OutputM<Emit, OM::Error, OM::Incomplete>>(input.clone())
+      {
         Ok((tail, value)) => {
           // infinite loop check: the parser must always consume
           if tail.input_len() == len {
-            return Err(Err::Error(OM::Error::bind(|| {
-              <F as Parser<I>>::Error::from_error_kind(tail, ErrorKind::Fold)
-            })));
+            return Err(infinite_loop::<OM, _, _>(input, ErrorKind::Sorted));
           }
 
-          acc = OM::Output::combine(acc, value, |acc, value| (self.fold)(acc, value));
+          if let Some(prev) = last.take() {
+            if !(self.compare)(&prev, &value) {
+              return Err(Err::Error(OM::Error::bind(|| {
+                <F as Parser<I>>::Error::from_error_kind(input, ErrorKind::Sorted)
+              })));
+            }
+            res = OM::Output::map(res, |mut res| {
+              push("sorted_by", &mut res, prev);
+              res
+            });
+          }
+
+          last = Some(value);
           input = tail;
         }
-        Err(Err::Error(err)) => {
+        Err(Err::Error(e)) => {
           if !self.range.contains(&count) {
-            return Err(Err::Error(OM::Error::map(err, |err| {
-              <F as Parser<I>>::Error::append(input, ErrorKind::Fold, err)
+            return Err(Err::Error(OM::Error::map(e, |e| {
+              <F as Parser<I>>::Error::append(input, ErrorKind::Sorted, e)
             })));
           } else {
             break;
@@ -1865,6 +4160,13 @@ where
       }
     }
 
-    Ok((input, acc))
+    if let Some(last) = last {
+      res = OM::Output::map(res, |mut res| {
+        push("sorted_by", &mut res, last);
+        res
+      });
+    }
+
+    Ok((input, res))
   }
 }
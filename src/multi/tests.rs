@@ -1,4 +1,7 @@
-use super::{length_data, length_value, many0_count, many1_count};
+use super::{
+  length_data, length_value, length_value_exact, length_value_padded, length_value_raw,
+  many0_count, many1_count,
+};
 use crate::{
   bytes::streaming::tag,
   character::streaming::digit1 as digit,
@@ -13,11 +16,20 @@ use crate::{
 use crate::{
   lib::std::vec::Vec,
   multi::{
-    count, fold, fold_many0, fold_many1, fold_many_m_n, length_count, many, many0, many1, many_m_n,
-    many_till, separated_list0, separated_list1,
+    count, fold, fold_many0, fold_many1, fold_many_m_n, length_count, many, many0, many1,
+    many_labeled, many_m_n, many_till, separated_list0, separated_list1,
   },
 };
 
+/// Error of the infinite loop checks, which depends on the `checked` feature
+fn infinite_loop<I, E: ParseError<I>>(input: I, kind: ErrorKind) -> Err<E> {
+  if cfg!(feature = "checked") {
+    Err::Failure(E::from_error_kind(input, ErrorKind::InfiniteLoop))
+  } else {
+    Err::Error(E::from_error_kind(input, kind))
+  }
+}
+
 #[test]
 #[cfg(feature = "alloc")]
 fn separated_list0_test() {
@@ -63,7 +75,7 @@ fn separated_list0_test() {
 
   assert_eq!(
     empty_both(i),
-    Err(Err::Error(error_position!(i, ErrorKind::SeparatedList)))
+    Err(infinite_loop(i, ErrorKind::SeparatedList))
   );
   let res4 = vec![&b"abcd"[..], &b"abcd"[..]];
   assert_eq!(multi(e), Ok((&b",ef"[..], res4)));
@@ -105,7 +117,7 @@ fn separated_list1_test() {
   );
   assert_eq!(
     empty_both(f),
-    Err(Err::Error(error_position!(f, ErrorKind::SeparatedList)))
+    Err(infinite_loop(f, ErrorKind::SeparatedList))
   );
   let res3 = vec![&b"abcd"[..], &b"abcd"[..]];
   assert_eq!(multi(d), Ok((&b",ef"[..], res3)));
@@ -136,10 +148,7 @@ fn many0_test() {
   assert_eq!(multi(&b""[..]), Err(Err::Incomplete(Needed::new(4))));
   assert_eq!(
     multi_empty(&b"abcdef"[..]),
-    Err(Err::Error(error_position!(
-      &b"abcdef"[..],
-      ErrorKind::Many0
-    )))
+    Err(infinite_loop(&b"abcdef"[..], ErrorKind::Many0))
   );
 }
 
@@ -166,6 +175,68 @@ fn many1_test() {
   assert_eq!(multi(d), Err(Err::Incomplete(Needed::new(2))));
 }
 
+#[test]
+#[cfg(feature = "alloc")]
+fn many_labeled_test() {
+  use crate::combinator::cut;
+  use crate::error::ContextError;
+  use crate::lib::std::{fmt, string::String, string::ToString};
+
+  #[derive(Debug, PartialEq)]
+  struct Labels<'a> {
+    input: &'a [u8],
+    kind: ErrorKind,
+    labels: Vec<String>,
+  }
+
+  impl<'a> ParseError<&'a [u8]> for Labels<'a> {
+    fn from_error_kind(input: &'a [u8], kind: ErrorKind) -> Self {
+      Labels {
+        input,
+        kind,
+        labels: Vec::new(),
+      }
+    }
+
+    fn append(_: &'a [u8], _: ErrorKind, other: Self) -> Self {
+      other
+    }
+  }
+
+  impl<'a> ContextError<&'a [u8]> for Labels<'a> {
+    fn add_lazy_context(_: &'a [u8], ctx: &dyn fmt::Display, mut other: Self) -> Self {
+      other.labels.push(ctx.to_string());
+      other
+    }
+  }
+
+  fn multi(i: &[u8]) -> IResult<&[u8], Vec<&[u8]>, Labels<'_>> {
+    many_labeled(2.., "item", pair(tag("ab"), cut(tag("cd"))).map(|(a, _)| a)).parse(i)
+  }
+
+  assert_eq!(
+    multi(&b"abcdabcdef"[..]),
+    Ok((&b"ef"[..], vec![&b"ab"[..], &b"ab"[..]]))
+  );
+  assert_eq!(
+    multi(&b"abcdabcdabef"[..]),
+    Err(Err::Failure(Labels {
+      input: &b"ef"[..],
+      kind: ErrorKind::Tag,
+      labels: vec!["item 2".to_string()],
+    }))
+  );
+  assert_eq!(
+    multi(&b"abcdef"[..]),
+    Err(Err::Error(Labels {
+      input: &b"ef"[..],
+      kind: ErrorKind::Tag,
+      labels: vec!["item 1".to_string()],
+    }))
+  );
+  assert_eq!(multi(&b"abcdab"[..]), Err(Err::Incomplete(Needed::new(2))));
+}
+
 #[test]
 #[cfg(feature = "alloc")]
 fn many_till_test() {
@@ -192,6 +263,52 @@ fn many_till_test() {
   );
 }
 
+#[test]
+#[cfg(feature = "alloc")]
+fn many_till_m_n_test() {
+  use crate::multi::{fold_many_till, many_till_m_n};
+
+  #[allow(clippy::type_complexity)]
+  fn multi(i: &[u8]) -> IResult<&[u8], (Vec<&[u8]>, &[u8])> {
+    many_till_m_n(1, 2, tag("abcd"), tag("efgh")).parse(i)
+  }
+
+  let res = (vec![&b"abcd"[..], &b"abcd"[..]], &b"efgh"[..]);
+  assert_eq!(multi(&b"abcdabcdefghabcd"[..]), Ok((&b"abcd"[..], res)));
+  assert_eq!(
+    multi(&b"efgh"[..]),
+    Err(Err::Error(error_position!(
+      &b"efgh"[..],
+      ErrorKind::ManyTill
+    )))
+  );
+  assert_eq!(
+    multi(&b"abcdabcdabcd"[..]),
+    Err(Err::Error(error_position!(
+      &b"abcd"[..],
+      ErrorKind::ManyTill
+    )))
+  );
+  assert_eq!(
+    multi(&b"abcdazerty"[..]),
+    Err(Err::Error(error_node_position!(
+      &b"azerty"[..],
+      ErrorKind::ManyTill,
+      error_position!(&b"azerty"[..], ErrorKind::Tag)
+    )))
+  );
+  assert_eq!(multi(&b"abcdab"[..]), Err(Err::Incomplete(Needed::new(2))));
+
+  // counts the elements without collecting them
+  let mut count = fold_many_till(tag("abcd"), tag("efgh"), || 0, |n, _| n + 1);
+  let res: IResult<&[u8], (usize, &[u8])> = count.parse(&b"abcdabcdefgh"[..]);
+  assert_eq!(res, Ok((&b""[..], (2, &b"efgh"[..]))));
+
+  let mut empty = fold_many_till(tag(""), tag("efgh"), || 0, |n: usize, _| n + 1);
+  let res: IResult<&[u8], (usize, &[u8])> = empty.parse(&b"abcd"[..]);
+  assert_eq!(res, Err(infinite_loop(&b"abcd"[..], ErrorKind::ManyTill)));
+}
+
 #[test]
 #[cfg(feature = "std")]
 fn infinite_many() {
@@ -418,6 +535,48 @@ fn length_value_test() {
   assert_eq!(length_value_2(&i4), Ok((&i4[4..], (5, 6))));
 }
 
+#[test]
+fn length_value_variants_test() {
+  fn exact(i: &[u8]) -> IResult<&[u8], (u8, u8)> {
+    length_value_exact(be_u8, (be_u8, be_u8)).parse(i)
+  }
+  fn padded(i: &[u8]) -> IResult<&[u8], u16> {
+    length_value_padded(4, be_u8, be_u16).parse(i)
+  }
+  fn raw(i: &[u8]) -> IResult<&[u8], (&[u8], u8)> {
+    length_value_raw(be_u8, be_u8).parse(i)
+  }
+
+  let i1 = [2, 5, 6, 7];
+  assert_eq!(exact(&i1), Ok((&i1[3..], (5, 6))));
+  let i2 = [4, 5, 6, 7, 8, 9];
+  assert_eq!(
+    exact(&i2),
+    Err(Err::Error(error_position!(
+      &i2[3..5],
+      ErrorKind::LengthValue
+    )))
+  );
+  assert_eq!(exact(&i2[..3]), Err(Err::Incomplete(Needed::new(2))));
+
+  let i3 = [3, 5, 6, 7, 0, 9];
+  assert_eq!(padded(&i3), Ok((&i3[5..], 1286)));
+  assert_eq!(padded(&i3[..4]), Err(Err::Incomplete(Needed::new(1))));
+  let i4 = [4, 5, 6, 7, 8, 9];
+  assert_eq!(padded(&i4), Ok((&i4[5..], 1286)));
+  assert_eq!(
+    padded(&[0, 9]),
+    Err(Err::Error(error_position!(&b""[..], ErrorKind::Complete)))
+  );
+
+  let i5 = [2, 5, 6, 7];
+  assert_eq!(raw(&i5), Ok((&i5[3..], (&i5[1..3], 5))));
+  assert_eq!(
+    raw(&[0, 9][..]),
+    Err(Err::Error(error_position!(&b""[..], ErrorKind::Complete)))
+  );
+}
+
 #[test]
 #[cfg(feature = "alloc")]
 fn fold_many0_test() {
@@ -443,10 +602,7 @@ fn fold_many0_test() {
   assert_eq!(multi(&b""[..]), Err(Err::Incomplete(Needed::new(4))));
   assert_eq!(
     multi_empty(&b"abcdef"[..]),
-    Err(Err::Error(error_position!(
-      &b"abcdef"[..],
-      ErrorKind::Many0
-    )))
+    Err(infinite_loop(&b"abcdef"[..], ErrorKind::Many0))
   );
 }
 
@@ -579,10 +735,7 @@ fn many_test() {
   }
 
   let a = &b"abcdef"[..];
-  assert_eq!(
-    many_error(a),
-    Err(Err::Error(error_position!(a, ErrorKind::Many)))
-  );
+  assert_eq!(many_error(a), Err(infinite_loop(a, ErrorKind::Many)));
 
   fn many_invalid(i: &[u8]) -> IResult<&[u8], Vec<&[u8]>> {
     many(crate::lib::std::ops::Range::default(), tag("a")).parse(i)
@@ -734,10 +887,7 @@ fn fold_test() {
   }
 
   let a = &b"abcdef"[..];
-  assert_eq!(
-    fold_error(a),
-    Err(Err::Error(error_position!(a, ErrorKind::Fold)))
-  );
+  assert_eq!(fold_error(a), Err(infinite_loop(a, ErrorKind::Fold)));
 
   fn fold_invalid(i: &[u8]) -> IResult<&[u8], Vec<&[u8]>> {
     fold(
@@ -857,3 +1007,517 @@ fn fold_test() {
   assert_eq!(fold_never(a), Ok((&b"AAA"[..], Vec::new())));
   assert_eq!(fold_never(b), Ok((&b"B"[..], Vec::new())));
 }
+
+#[test]
+#[cfg(feature = "alloc")]
+fn unique_by_test() {
+  use crate::error::FromExternalError;
+  use crate::multi::{unique_by, DuplicateKey};
+  use crate::sequence::terminated;
+
+  #[derive(Debug, PartialEq)]
+  enum KeyError<'a> {
+    Nom(&'a [u8], ErrorKind),
+    Duplicate(DuplicateKey<&'a [u8], u8>),
+  }
+
+  impl<'a> ParseError<&'a [u8]> for KeyError<'a> {
+    fn from_error_kind(input: &'a [u8], kind: ErrorKind) -> Self {
+      KeyError::Nom(input, kind)
+    }
+
+    fn append(_: &'a [u8], _: ErrorKind, other: Self) -> Self {
+      other
+    }
+  }
+
+  impl<'a> FromExternalError<&'a [u8], DuplicateKey<&'a [u8], u8>> for KeyError<'a> {
+    fn from_external_error(
+      _input: &'a [u8],
+      _kind: ErrorKind,
+      e: DuplicateKey<&'a [u8], u8>,
+    ) -> Self {
+      KeyError::Duplicate(e)
+    }
+  }
+
+  fn items(i: &[u8]) -> IResult<&[u8], Vec<(u8, u8)>, KeyError<'_>> {
+    unique_by(1..=3, terminated(pair(be_u8, be_u8), tag(";")), |item| {
+      item.0
+    })
+    .parse(i)
+  }
+
+  assert_eq!(items(&b"a1;b2;c"[..]), Err(Err::Incomplete(Needed::new(1))));
+  assert_eq!(
+    items(&b"a1;b2;c3;d4;"[..]),
+    Ok((&b"d4;"[..], vec![(b'a', b'1'), (b'b', b'2'), (b'c', b'3')]))
+  );
+  assert_eq!(
+    items(&b"a1;b2;a3;"[..]),
+    Err(Err::Error(KeyError::Duplicate(DuplicateKey {
+      key: b'a',
+      first: &b"a1;b2;a3;"[..],
+      second: &b"a3;"[..],
+    })))
+  );
+  assert_eq!(
+    items(&b"a1,"[..]),
+    Err(Err::Error(KeyError::Nom(&b","[..], ErrorKind::Tag)))
+  );
+
+  fn default_error(i: &[u8]) -> IResult<&[u8], Vec<&[u8]>> {
+    unique_by(0.., terminated(digit, tag(",")), |d: &&[u8]| d.len()).parse(i)
+  }
+
+  assert_eq!(
+    default_error(&b"1,22,a"[..]),
+    Ok((&b"a"[..], vec![&b"1"[..], &b"22"[..]]))
+  );
+  assert_eq!(
+    default_error(&b"1,22,3,a"[..]),
+    Err(Err::Error(error_position!(&b"3,a"[..], ErrorKind::Unique)))
+  );
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn sorted_by_test() {
+  use crate::multi::{monotonic, sorted_by};
+  use crate::sequence::terminated;
+
+  fn increasing(i: &[u8]) -> IResult<&[u8], Vec<u8>> {
+    sorted_by(1..=3, be_u8, |prev, next| prev < next).parse(i)
+  }
+
+  assert_eq!(
+    increasing(&[1, 2][..]),
+    Err(Err::Incomplete(Needed::new(1)))
+  );
+  assert_eq!(increasing(&[1, 2, 3, 4][..]), Ok((&[4][..], vec![1, 2, 3])));
+  assert_eq!(
+    increasing(&[1, 3, 2, 4][..]),
+    Err(Err::Error(error_position!(&[2, 4][..], ErrorKind::Sorted)))
+  );
+  assert_eq!(
+    increasing(&[1, 1][..]),
+    Err(Err::Error(error_position!(&[1][..], ErrorKind::Sorted)))
+  );
+  assert_eq!(increasing(&[][..]), Err(Err::Incomplete(Needed::new(1))));
+
+  fn non_decreasing(i: &[u8]) -> IResult<&[u8], Vec<&[u8]>> {
+    monotonic(0.., terminated(digit, tag(","))).parse(i)
+  }
+
+  assert_eq!(
+    non_decreasing(&b"1,1,23,a"[..]),
+    Ok((&b"a"[..], vec![&b"1"[..], &b"1"[..], &b"23"[..]]))
+  );
+  assert_eq!(
+    non_decreasing(&b"1,3,23,a"[..]),
+    Err(Err::Error(error_position!(&b"23,a"[..], ErrorKind::Sorted)))
+  );
+  assert_eq!(non_decreasing(&b"a"[..]), Ok((&b"a"[..], vec![])));
+}
+
+#[test]
+fn array_test() {
+  use super::array;
+  use crate::bytes::complete::take_array;
+  use crate::combinator::recognize;
+
+  fn header(i: &[u8]) -> IResult<&[u8], ([u16; 2], &[u8; 2])> {
+    (array(be_u16), take_array()).parse(i)
+  }
+
+  assert_eq!(
+    header(&b"\x00\x01\x00\x02ab;"[..]),
+    Ok((&b";"[..], ([1, 2], b"ab")))
+  );
+  assert_eq!(
+    header(&b"\x00\x01\x00"[..]),
+    Err(Err::Incomplete(Needed::new(1)))
+  );
+  assert_eq!(
+    array::<2, _, _>(tag::<_, _, (_, ErrorKind)>("ab")).parse(&b"abcd"[..]),
+    Err(Err::Error((&b"cd"[..], ErrorKind::Tag)))
+  );
+
+  // the outputs are not built when they are not used
+  assert_eq!(
+    recognize(array::<3, _, _>(be_u8::<_, (_, ErrorKind)>)).parse(&b"abcd"[..]),
+    Ok((&b"d"[..], &b"abc"[..]))
+  );
+  assert_eq!(
+    array::<0, _, _>(be_u8::<_, (_, ErrorKind)>).parse(&b""[..]),
+    Ok((&b""[..], []))
+  );
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn separated_fold_test() {
+  use super::{separated_foldl1, separated_foldr1};
+  use crate::character::complete::{alpha1, one_of};
+  use crate::combinator::recognize;
+  use crate::lib::std::string::String;
+
+  fn tree(l: String, op: char, r: String) -> String {
+    format!("({}{}{})", l, op, r)
+  }
+
+  let item = |i| alpha1::<_, (_, ErrorKind)>(i).map(|(i, o): (&str, &str)| (i, String::from(o)));
+
+  let mut left = separated_foldl1(item, one_of("+-"), tree);
+  assert_eq!(
+    left.parse("a+b-c+d;"),
+    Ok((";", String::from("(((a+b)-c)+d)")))
+  );
+  assert_eq!(left.parse("a+"), Ok(("+", String::from("a"))));
+
+  let mut right = separated_foldr1(item, one_of("+-"), tree);
+  assert_eq!(
+    right.parse("a+b-c+d;"),
+    Ok((";", String::from("(a+(b-(c+d)))")))
+  );
+  assert_eq!(right.parse("a+b1"), Ok(("1", String::from("(a+b)"))));
+  assert_eq!(right.parse("+a"), Err(Err::Error(("+a", ErrorKind::Alpha))));
+
+  // the fold function is not called when the output is not used
+  let mut checked = recognize(separated_foldl1(item, one_of("+-"), |_, _, _| -> String {
+    unreachable!()
+  }));
+  assert_eq!(checked.parse("a-b c"), Ok((" c", "a-b")));
+
+  // the separator and the element must consume
+  let mut empty = separated_foldl1(tag::<_, _, (_, ErrorKind)>(""), tag(""), |a, _, _| a);
+  assert_eq!(
+    empty.parse(&b"abc"[..]),
+    Err(infinite_loop(&b"abc"[..], ErrorKind::SeparatedList))
+  );
+  let mut streaming = separated_foldr1(tag::<_, _, (_, ErrorKind)>("ab"), tag(","), |a, _, _| a);
+  assert_eq!(
+    streaming.parse(&b"ab,a"[..]),
+    Err(Err::Incomplete(Needed::new(1)))
+  );
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn separated_list_results_test() {
+  use super::separated_list_results;
+  use crate::bytes::complete::{is_not, tag as complete_tag};
+  use crate::character::complete::char;
+  use crate::combinator::{cut, recognize};
+  use crate::sequence::delimited;
+
+  type Results<'a> = Vec<Result<&'a str, (&'a str, ErrorKind)>>;
+
+  // quoted fields can contain the separator
+  fn field(i: &str) -> IResult<&str, &str, (&str, ErrorKind)> {
+    match delimited(char('"'), cut(is_not("\"")), char('"')).parse(i) {
+      Err(Err::Error(_)) => is_not(",\"").parse(i),
+      res => res,
+    }
+  }
+
+  let mut record = separated_list_results(char(','), field);
+  let res: IResult<&str, Results<'_>, (&str, ErrorKind)> = record.parse_complete("a,\"b,c\",\"d,e");
+  assert_eq!(
+    res,
+    Ok((
+      "",
+      vec![
+        Ok("a"),
+        Ok("b,c"),
+        Err(("\"d,e", ErrorKind::IsNot)),
+        Ok("e")
+      ]
+    ))
+  );
+  assert_eq!(
+    record.parse_complete(""),
+    Ok(("", vec![Err(("", ErrorKind::IsNot))]))
+  );
+  assert_eq!(record.parse("a,b"), Err(Err::Incomplete(Needed::Unknown)));
+
+  // the results are not built when they are not used
+  assert_eq!(
+    recognize(separated_list_results(char(','), field)).parse_complete("a,,b"),
+    Ok(("", "a,,b"))
+  );
+
+  // the separator must consume
+  assert_eq!(
+    separated_list_results(complete_tag::<_, _, (_, ErrorKind)>(""), is_not(","))
+      .parse_complete("a"),
+    Ok(("", vec![Ok("a")]))
+  );
+  assert_eq!(
+    separated_list_results(complete_tag::<_, _, (_, ErrorKind)>(""), is_not(","))
+      .parse_complete(",a"),
+    Err(Err::Error((",a", ErrorKind::SeparatedList)))
+  );
+}
+
+#[test]
+fn collect_test() {
+  use super::{collect_many, collect_separated};
+  use crate::bytes::complete::tag as complete_tag;
+  use crate::character::complete::u32 as complete_u32;
+  use crate::combinator::success;
+  use crate::sequence::terminated;
+
+  // a collection that does not allocate
+  #[derive(Debug, Default, PartialEq)]
+  struct Sum(u32);
+  impl Extend<u32> for Sum {
+    fn extend<T: IntoIterator<Item = u32>>(&mut self, iter: T) {
+      self.0 += iter.into_iter().sum::<u32>();
+    }
+  }
+
+  fn sum_many(i: &str) -> IResult<&str, Sum> {
+    collect_many(terminated(complete_u32, complete_tag(";"))).parse(i)
+  }
+  fn sum_separated(i: &str) -> IResult<&str, Sum> {
+    collect_separated(complete_tag("+"), complete_u32).parse(i)
+  }
+
+  assert_eq!(sum_many("1;2;3;x"), Ok(("x", Sum(6))));
+  assert_eq!(sum_many(""), Ok(("", Sum(0))));
+  assert_eq!(sum_separated("1+2+3+"), Ok(("+", Sum(6))));
+  assert_eq!(sum_separated("x"), Ok(("x", Sum(0))));
+
+  // streaming parsers ask for more data
+  let mut streaming =
+    collect_many::<_, (&str, ErrorKind), Sum, _>(crate::character::streaming::u32);
+  assert_eq!(streaming.parse("12"), Err(Err::Incomplete(Needed::new(1))));
+
+  // infinite loop checks
+  assert_eq!(
+    collect_many::<_, _, Sum, _>(success::<_, _, (&str, ErrorKind)>(1)).parse("a"),
+    Err(infinite_loop("a", ErrorKind::Many0))
+  );
+  assert_eq!(
+    collect_separated::<_, _, Sum, _, _>(complete_tag::<_, _, (&str, ErrorKind)>(""), success(1))
+      .parse("a"),
+    Err(infinite_loop("a", ErrorKind::SeparatedList))
+  );
+
+  #[cfg(feature = "alloc")]
+  {
+    use crate::character::complete::{alpha1, char};
+    use crate::lib::std::collections::BTreeMap;
+    use crate::sequence::separated_pair;
+
+    let mut map = collect_separated(
+      char::<_, (&str, ErrorKind)>(','),
+      separated_pair(alpha1, char('='), complete_u32),
+    );
+    let res: IResult<_, BTreeMap<&str, u32>, _> = map.parse("b=2,a=1,b=3");
+    assert_eq!(res, Ok(("", BTreeMap::from([("a", 1), ("b", 3)]))));
+  }
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn separated_list_test() {
+  use super::{separated_list, Trailing};
+  use crate::bytes::complete::tag as complete_tag;
+  use crate::character::complete::{alpha1, char};
+
+  type Res<'a> = IResult<&'a str, Vec<&'a str>, (&'a str, ErrorKind)>;
+
+  let mut forbid = separated_list(1.., Trailing::Forbid, char(','), alpha1);
+  let res: Res<'_> = forbid.parse("a,b,");
+  assert_eq!(res, Ok((",", vec!["a", "b"])));
+  assert_eq!(
+    forbid.parse(",a"),
+    Err(Err::Error((",a", ErrorKind::Alpha)))
+  );
+
+  let mut allow = separated_list(0..=2, Trailing::Allow, char(','), alpha1);
+  let res: Res<'_> = allow.parse("a,b,");
+  assert_eq!(res, Ok(("", vec!["a", "b"])));
+  assert_eq!(allow.parse("a,b"), Ok(("", vec!["a", "b"])));
+  assert_eq!(allow.parse("a,b,c"), Ok(("c", vec!["a", "b"])));
+  assert_eq!(allow.parse(",a"), Ok((",a", vec![])));
+
+  let mut require = separated_list(2, Trailing::Require, char(';'), alpha1);
+  let res: Res<'_> = require.parse("a;b;c;");
+  assert_eq!(res, Ok(("c;", vec!["a", "b"])));
+  assert_eq!(require.parse("a;b"), Err(Err::Error(("", ErrorKind::Char))));
+
+  // streaming parsers ask for more data
+  let mut streaming = separated_list(
+    0..,
+    Trailing::Allow,
+    char(','),
+    crate::character::streaming::alpha1,
+  );
+  let res: Res<'_> = streaming.parse("a,b");
+  assert_eq!(res, Err(Err::Incomplete(Needed::new(1))));
+
+  // invalid range and infinite loop check
+  #[allow(clippy::reversed_empty_ranges)]
+  let res: Res<'_> = separated_list(3..=2, Trailing::Forbid, char(','), alpha1).parse("a");
+  assert_eq!(res, Err(Err::Failure(("a", ErrorKind::SeparatedList))));
+  let res: Res<'_> =
+    separated_list(0.., Trailing::Require, complete_tag(""), complete_tag("")).parse("a");
+  assert_eq!(res, Err(infinite_loop("a", ErrorKind::SeparatedList)));
+}
+
+#[test]
+fn length_bounded_test() {
+  use super::length_data_bounded;
+  use crate::number::streaming::be_u32;
+
+  type Res<'a, O> = IResult<&'a [u8], O, (&'a [u8], ErrorKind)>;
+
+  // a large length is rejected instead of asking for more data
+  let mut data = length_data_bounded(4, be_u32);
+  let res: Res<'_, &[u8]> = data.parse(&b"\x00\x00\x00\x04ab"[..]);
+  assert_eq!(res, Err(Err::Incomplete(Needed::new(2))));
+  let input = &b"\xff\xff\xff\xffab"[..];
+  let res: Res<'_, &[u8]> = data.parse(input);
+  assert_eq!(res, Err(Err::Error((input, ErrorKind::TooLarge))));
+  let res: Res<'_, &[u8]> = data.parse(&b"\x00\x00\x00\x01ab"[..]);
+  assert_eq!(res, Ok((&b"b"[..], &b"a"[..])));
+
+  #[cfg(feature = "alloc")]
+  {
+    use super::length_count_bounded;
+    use crate::combinator::success;
+
+    // the element parser does not consume, so only the limit stops the count
+    let mut elements = length_count_bounded(3, be_u16, success(()));
+    let res: Res<'_, Vec<()>> = elements.parse(&b"\x00\x03"[..]);
+    assert_eq!(res, Ok((&b""[..], vec![(), (), ()])));
+    let input = &b"\xff\xff"[..];
+    let res: Res<'_, Vec<()>> = elements.parse(input);
+    assert_eq!(res, Err(Err::Error((input, ErrorKind::TooLarge))));
+
+    // `count` with a count read from the input
+    let res: Res<'_, Vec<u8>> = be_u8
+      .with_allocation_limit(2)
+      .flat_map(|n| count(be_u8, n as usize))
+      .parse(&b"\x03\x01\x02\x03"[..]);
+    assert_eq!(
+      res,
+      Err(Err::Error((&b"\x03\x01\x02\x03"[..], ErrorKind::TooLarge)))
+    );
+  }
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn infinite_loop_test() {
+  use crate::bytes::complete::tag as complete_tag;
+  use crate::combinator::opt;
+
+  // `many0(opt(..))` never stops consuming nothing
+  let mut parser = opt(many0(opt(complete_tag::<_, _, (&str, ErrorKind)>("a"))));
+
+  #[cfg(not(feature = "checked"))]
+  assert_eq!(parser.parse("aab"), Ok(("aab", None)));
+  #[cfg(feature = "checked")]
+  assert_eq!(
+    parser.parse("aab"),
+    Err(Err::Failure(("b", ErrorKind::InfiniteLoop)))
+  );
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn rle_test() {
+  use crate::combinator::{recognize, success};
+  use crate::multi::{rle, rle_exact};
+
+  type Res<'a, O> = IResult<&'a [u8], O, (&'a [u8], ErrorKind)>;
+
+  // the count parser cannot tell where the runs end on streaming data
+  let res: Res<'_, Vec<u8>> = rle(16, be_u8, be_u8).parse(&b"\x02a\x01b"[..]);
+  assert_eq!(res, Err(Err::Incomplete(Needed::new(1))));
+  let res: Res<'_, Vec<u8>> =
+    rle(16, crate::number::be_u8(), be_u8).parse_complete(&b"\x02a\x01b"[..]);
+  assert_eq!(res, Ok((&b""[..], b"aab".to_vec())));
+
+  // the limit is checked before expanding the run
+  let res: Res<'_, Vec<u8>> = rle(4, be_u8, be_u8).parse(&b"\x02a\x03b"[..]);
+  assert_eq!(res, Err(Err::Error((&b"\x03b"[..], ErrorKind::TooLarge))));
+
+  // without output, nothing is allocated
+  let res: Res<'_, &[u8]> = recognize(rle_exact(usize::MAX, be_u8, be_u8)).parse(&b"\xff\x00"[..]);
+  assert_eq!(res, Err(Err::Incomplete(Needed::new(1))));
+
+  // the runs end before the expected length
+  let res: Res<'_, Vec<u8>> = rle_exact(4, be_u8, be_u8).parse(&b"\x02a"[..]);
+  assert_eq!(res, Err(Err::Incomplete(Needed::new(1))));
+  let res: Res<'_, Vec<&[u8]>> = rle_exact(4, be_u8, tag("a")).parse(&b"\x02a\x02b"[..]);
+  assert_eq!(res, Err(Err::Error((&b"b"[..], ErrorKind::Tag))));
+  let res: Res<'_, Vec<u8>> = rle_exact(0, be_u8, be_u8).parse(&b""[..]);
+  assert_eq!(res, Ok((&b""[..], Vec::new())));
+
+  let res: Res<'_, Vec<u8>> =
+    rle(4, crate::number::be_u8(), success(0)).parse_complete(&b"\x01"[..]);
+  assert_eq!(res, Ok((&b""[..], vec![0])));
+  let res: Res<'_, Vec<u8>> = rle(4, success(1u8), success(0)).parse(&b"a"[..]);
+  assert_eq!(res, Err(infinite_loop(&b"a"[..], ErrorKind::Count)));
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn delta_decode_test() {
+  use crate::combinator::{recognize, success};
+  use crate::multi::{delta_decode, Overflow};
+
+  type Res<'a, O> = IResult<&'a [u8], O, (&'a [u8], ErrorKind)>;
+
+  let res: Res<'_, Vec<u16>> =
+    delta_decode(be_u16, 1000, Overflow::Error).parse(&b"\x00\x0a\x00\x14\x01"[..]);
+  assert_eq!(res, Err(Err::Incomplete(Needed::new(1))));
+  let res: Res<'_, Vec<u16>> = delta_decode(crate::number::be_u16(), 1000, Overflow::Error)
+    .parse_complete(&b"\x00\x0a\x00\x14\x01"[..]);
+  assert_eq!(res, Ok((&b"\x01"[..], vec![1010, 1030])));
+  let res: Res<'_, Vec<u16>> =
+    delta_decode(crate::number::be_u16(), 0, Overflow::Error).parse_complete(&b""[..]);
+  assert_eq!(res, Ok((&b""[..], vec![])));
+
+  // overflows are checked without output too
+  let res: Res<'_, &[u8]> =
+    recognize(delta_decode(be_u8, 255, Overflow::Error)).parse(&b"\x01"[..]);
+  assert_eq!(res, Err(Err::Error((&b"\x01"[..], ErrorKind::TooLarge))));
+  let res: Res<'_, Vec<u8>> = delta_decode(be_u8, 255, Overflow::Saturate).parse(&b"\x01\x00"[..]);
+  assert_eq!(res, Err(Err::Incomplete(Needed::new(1))));
+
+  let res: Res<'_, Vec<u8>> = delta_decode(success(1u8), 0, Overflow::Wrap).parse(&b"a"[..]);
+  assert_eq!(res, Err(infinite_loop(&b"a"[..], ErrorKind::Many0)));
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn terminated_records_test() {
+  use crate::character::complete::{alphanumeric0, char, line_ending, not_line_ending};
+  use crate::combinator::success;
+  use crate::multi::terminated_records;
+
+  type Res<'a, O> = IResult<&'a str, O, (&'a str, ErrorKind)>;
+
+  let mut csv = terminated_records(separated_list0(char(','), alphanumeric0), line_ending);
+  let res: Res<'_, Vec<Vec<&str>>> = csv.parse_complete("a,\r\n,b\n");
+  assert_eq!(res, Ok(("", vec![vec!["a", ""], vec!["", "b"]])));
+  // a blank line in the middle is a record, not the end of the input
+  let res: Res<'_, Vec<Vec<&str>>> = csv.parse_complete("a\n\n");
+  assert_eq!(res, Ok(("", vec![vec!["a"], vec![""]])));
+
+  // the terminator is required on streaming data, where the record may go on
+  let mut numbers = terminated_records(crate::character::digit1(), crate::character::char('\n'));
+  let res: Res<'_, Vec<&str>> = numbers.parse("12\n34");
+  assert_eq!(res, Err(Err::Incomplete(Needed::new(1))));
+  let res: Res<'_, Vec<&str>> = numbers.parse_complete("12\n34");
+  assert_eq!(res, Ok(("", vec!["12", "34"])));
+
+  let res: Res<'_, Vec<&str>> = terminated_records(not_line_ending, success("")).parse("ab");
+  assert_eq!(res, Err(infinite_loop("", ErrorKind::Many0)));
+}
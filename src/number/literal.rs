@@ -0,0 +1,575 @@
+//! Numeric literals of programming languages
+//!
+//! [c] and [rust] recognize the integer and floating point literals of these languages,
+//! with their sign, radix prefix, digit separators and type suffix, and return them as a
+//! [NumericLiteral]. They reject the literals that have no digit after their prefix, a
+//! misplaced separator, an unknown suffix, or a value that does not fit in a `u128` or in
+//! the type of their suffix.
+//!
+//! ```rust
+//! # use nom::{Err, error::{Error, ErrorKind}, Parser};
+//! use nom::number::literal::{rust, NumericLiteral};
+//!
+//! let (_, lit) = rust::<_, Error<_>>().parse_complete("0xDEAD_BEEFu32").unwrap();
+//! assert_eq!(lit, NumericLiteral {
+//!   negative: false,
+//!   radix: 16,
+//!   digits: "DEAD_BEEF",
+//!   suffix: Some("u32"),
+//!   float: false,
+//! });
+//! assert_eq!(lit.value(), Some(0xDEAD_BEEF));
+//!
+//! assert_eq!(
+//!   rust::<_, Error<_>>().parse_complete("256u8"),
+//!   Err(Err::Error(Error::new("256u8", ErrorKind::TooLarge)))
+//! );
+//! ```
+
+use core::marker::PhantomData;
+
+use crate::error::{ErrorKind, ParseError};
+#[cfg(feature = "alloc")]
+use crate::lib::std::string::String;
+use crate::{AsChar, Err, Input, Mode, Needed, Parser};
+
+/// Numeric literal recognized by [c] or [rust]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NumericLiteral<I> {
+  /// Whether the literal starts with `-`
+  pub negative: bool,
+  /// Radix of the digits: 2, 8, 10 or 16
+  pub radix: u32,
+  /// Digits of the literal with their separators, without the sign, prefix and suffix.
+  /// For floating point literals, this includes the fraction and the exponent.
+  pub digits: I,
+  /// Type suffix, like `u8` or `ULL`
+  pub suffix: Option<I>,
+  /// Whether this is a floating point literal, with a fraction, an exponent or a float
+  /// suffix
+  pub float: bool,
+}
+
+impl<I> NumericLiteral<I>
+where
+  I: Input,
+  <I as Input>::Item: AsChar,
+{
+  /// Returns the value of an integer literal without its sign, or `None` for a floating
+  /// point literal
+  pub fn value(&self) -> Option<u128> {
+    if self.float {
+      return None;
+    }
+
+    self
+      .digits
+      .iter_elements()
+      .filter_map(|c| c.as_char().to_digit(self.radix))
+      .try_fold(0u128, |value, digit| {
+        value
+          .checked_mul(self.radix as u128)?
+          .checked_add(digit as u128)
+      })
+  }
+
+  /// Returns the value of the literal with its sign, rounded to the nearest `f64`
+  #[cfg(feature = "alloc")]
+  #[cfg_attr(feature = "docsrs", doc(cfg(feature = "alloc")))]
+  pub fn to_f64(&self) -> Option<f64> {
+    let value = if self.float {
+      let text: String = self
+        .digits
+        .iter_elements()
+        .map(|c| c.as_char())
+        .filter(|c| c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E' | '+' | '-'))
+        .collect();
+      text.parse().ok()?
+    } else {
+      self.value()? as f64
+    };
+
+    Some(if self.negative { -value } else { value })
+  }
+}
+
+/// Language of the literals
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Syntax {
+  C,
+  Rust,
+}
+
+/// Recognizes a numeric literal of C
+///
+/// It accepts:
+/// * an optional `-`
+/// * a `0x`, `0o` or `0b` prefix in any case, or a leading `0` for octal numbers
+/// * digits with `'` separators between them, like in C23
+/// * for decimal numbers, a fraction with at least one digit and an exponent
+/// * an integer suffix made of `u` and `l` or `ll` in any case, like `UL` or `llu`, or
+///   a `f` or `l` float suffix for floating point literals
+///
+/// The number ends at the first character that cannot continue it, so in `1.x`, the
+/// literal is `1`.
+///
+/// *Streaming version*: returns `Err::Incomplete` if the input ends in the literal.
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Needed, Parser};
+/// use nom::number::literal::c;
+///
+/// fn parse(s: &str) -> Result<(u32, Option<u128>, Option<&str>), Err<Error<&str>>> {
+///   c().parse_complete(s).map(|(_, lit)| (lit.radix, lit.value(), lit.suffix))
+/// }
+///
+/// assert_eq!(parse("0x1F'FFull"), Ok((16, Some(0x1FFF), Some("ull"))));
+/// assert_eq!(parse("017"), Ok((8, Some(0o17), None)));
+/// assert_eq!(parse("1.5e3f"), Ok((10, None, Some("f"))));
+/// assert_eq!(parse("0x"), Err(Err::Error(Error::new("0x", ErrorKind::HexDigit))));
+/// assert_eq!(parse("09"), Err(Err::Error(Error::new("09", ErrorKind::OctDigit))));
+/// assert_eq!(parse("1lL"), Err(Err::Error(Error::new("1lL", ErrorKind::Suffix))));
+/// assert_eq!(c::<_, Error<_>>().parse("12"), Err(Err::Incomplete(Needed::new(1))));
+/// ```
+pub fn c<I, E: ParseError<I>>() -> impl Parser<I, Output = NumericLiteral<I>, Error = E>
+where
+  I: Input,
+  <I as Input>::Item: AsChar,
+{
+  Literal {
+    syntax: Syntax::C,
+    e: PhantomData,
+  }
+}
+
+/// Recognizes a numeric literal of Rust
+///
+/// It accepts:
+/// * an optional `-`
+/// * a `0x`, `0o` or `0b` prefix
+/// * digits with `_` separators anywhere after the first digit or the prefix
+/// * for decimal numbers, a fraction with at least one digit and an exponent
+/// * an integer type suffix like `u8` or `isize`, or a `f32` or `f64` suffix for decimal
+///   numbers
+///
+/// The value of an integer literal with a suffix must fit in its type, counting the sign.
+/// The size of `usize` and `isize` is the one of the target.
+///
+/// *Streaming version*: returns `Err::Incomplete` if the input ends in the literal.
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Needed, Parser};
+/// use nom::number::literal::rust;
+///
+/// fn parse(s: &str) -> Result<(u32, Option<u128>, Option<&str>), Err<Error<&str>>> {
+///   rust().parse_complete(s).map(|(_, lit)| (lit.radix, lit.value(), lit.suffix))
+/// }
+///
+/// assert_eq!(parse("0b1010_1010"), Ok((2, Some(0b1010_1010), None)));
+/// assert_eq!(parse("-128i8"), Ok((10, Some(128), Some("i8"))));
+/// assert_eq!(parse("1f32"), Ok((10, None, Some("f32"))));
+/// assert_eq!(parse("0o_"), Err(Err::Error(Error::new("0o_", ErrorKind::OctDigit))));
+/// assert_eq!(parse("128i8"), Err(Err::Error(Error::new("128i8", ErrorKind::TooLarge))));
+/// assert_eq!(parse("-1u8"), Err(Err::Error(Error::new("-1u8", ErrorKind::TooLarge))));
+/// assert_eq!(parse("1.5u8"), Err(Err::Error(Error::new("1.5u8", ErrorKind::Suffix))));
+/// ```
+pub fn rust<I, E: ParseError<I>>() -> impl Parser<I, Output = NumericLiteral<I>, Error = E>
+where
+  I: Input,
+  <I as Input>::Item: AsChar,
+{
+  Literal {
+    syntax: Syntax::Rust,
+    e: PhantomData,
+  }
+}
+
+/// Parser implementation for [c] and [rust]
+pub struct Literal<E> {
+  syntax: Syntax,
+  e: PhantomData<E>,
+}
+
+impl<I, E: ParseError<I>> Parser<I> for Literal<E>
+where
+  I: Input,
+  <I as Input>::Item: AsChar,
+{
+  type Output = NumericLiteral<I>;
+  type Error = E;
+
+  fn process<OM: crate::OutputMode>(
+    &mut self,
+    input: I,
+  ) -> crate::PResult<OM, I, Self::Output, Self::Error> {
+    let mut scanner = Scanner {
+      input: &input,
+      pos: 0,
+      streaming: input.is_streaming::<OM>(),
+      syntax: self.syntax,
+    };
+
+    match scanner.literal() {
+      Ok(literal) => Ok((input.take_from(scanner.pos), OM::Output::bind(|| literal))),
+      Err(Fail::Incomplete) => Err(Err::Incomplete(Needed::new(1))),
+      Err(Fail::Error(kind)) => Err(Err::Error(OM::Error::bind(|| {
+        E::from_error_kind(input, kind)
+      }))),
+    }
+  }
+}
+
+/// Reasons for rejecting a literal
+enum Fail {
+  /// The input ended in the literal, on streaming data
+  Incomplete,
+  Error(ErrorKind),
+}
+
+struct Scanner<'a, I> {
+  input: &'a I,
+  pos: usize,
+  streaming: bool,
+  syntax: Syntax,
+}
+
+impl<I> Scanner<'_, I>
+where
+  I: Input,
+  <I as Input>::Item: AsChar,
+{
+  /// Returns the `n`th character after the current position
+  fn peek_nth(&self, n: usize) -> Result<Option<char>, Fail> {
+    match self.input.take_from(self.pos).iter_elements().nth(n) {
+      None if self.streaming => Err(Fail::Incomplete),
+      c => Ok(c.map(|c| c.as_char())),
+    }
+  }
+
+  fn peek(&self) -> Result<Option<char>, Fail> {
+    self.peek_nth(0)
+  }
+
+  fn bump(&mut self) {
+    if let Some(c) = self.input.take_from(self.pos).iter_elements().next() {
+      self.pos += c.len();
+    }
+  }
+
+  /// Consumes digits in `radix` with their separators, and returns the number of digits
+  fn digits(&mut self, radix: u32) -> Result<usize, Fail> {
+    let mut count = 0;
+    let mut after_separator = false;
+    loop {
+      match self.peek()? {
+        Some(c) if c.is_digit(radix) => {
+          count += 1;
+          after_separator = false;
+        }
+        Some('_') if self.syntax == Syntax::Rust => {}
+        Some('\'') if self.syntax == Syntax::C && count > 0 && !after_separator => {
+          after_separator = true;
+        }
+        _ => break,
+      }
+      self.bump();
+    }
+
+    // C separators must be between two digits
+    if after_separator {
+      return Err(Fail::Error(digit_kind(radix)));
+    }
+    Ok(count)
+  }
+
+  fn literal(&mut self) -> Result<NumericLiteral<I>, Fail> {
+    let negative = self.peek()? == Some('-');
+    if negative {
+      self.bump();
+    }
+    if !self.peek()?.map_or(false, |c| c.is_ascii_digit()) {
+      return Err(Fail::Error(ErrorKind::Digit));
+    }
+
+    let mut radix = 10;
+    if self.peek()? == Some('0') {
+      let prefix = match (self.syntax, self.peek_nth(1)?) {
+        (Syntax::C, Some('x' | 'X')) | (Syntax::Rust, Some('x')) => Some(16),
+        (Syntax::C, Some('o' | 'O')) | (Syntax::Rust, Some('o')) => Some(8),
+        (Syntax::C, Some('b' | 'B')) | (Syntax::Rust, Some('b')) => Some(2),
+        _ => None,
+      };
+      if let Some(prefix) = prefix {
+        radix = prefix;
+        self.bump();
+        self.bump();
+      }
+    }
+
+    let mut start = self.pos;
+    let count = self.digits(radix)?;
+    if count == 0 {
+      return Err(Fail::Error(digit_kind(radix)));
+    }
+
+    let mut float = false;
+    if radix == 10 {
+      if self.peek()? == Some('.') && self.peek_nth(1)?.map_or(false, |c| c.is_ascii_digit()) {
+        self.bump();
+        self.digits(10)?;
+        float = true;
+      }
+      if matches!(self.peek()?, Some('e' | 'E')) {
+        self.bump();
+        if matches!(self.peek()?, Some('+' | '-')) {
+          self.bump();
+        }
+        if self.digits(10)? == 0 {
+          return Err(Fail::Error(ErrorKind::Float));
+        }
+        float = true;
+      }
+    }
+    let end = self.pos;
+
+    // legacy C octal literals start with a 0
+    if self.syntax == Syntax::C && radix == 10 && !float && count > 1 {
+      let mut digits = self
+        .input
+        .take_from(start)
+        .take(end - start)
+        .iter_elements();
+      if digits.next().map(|c| c.as_char()) == Some('0') {
+        if digits.any(|c| matches!(c.as_char(), '8' | '9')) {
+          return Err(Fail::Error(ErrorKind::OctDigit));
+        }
+        radix = 8;
+        start += 1;
+      }
+    }
+
+    let mut suffix = [0u8; 5];
+    let mut suffix_len = 0;
+    while let Some(c) = self.peek()? {
+      if !(c.is_ascii_alphanumeric() || c == '_') {
+        break;
+      }
+      if suffix_len == suffix.len() {
+        return Err(Fail::Error(ErrorKind::Suffix));
+      }
+      suffix[suffix_len] = c as u8;
+      suffix_len += 1;
+      self.bump();
+    }
+
+    let ty = match self.syntax {
+      Syntax::C => c_suffix(&suffix[..suffix_len], float),
+      Syntax::Rust => rust_suffix(&suffix[..suffix_len], float, radix),
+    };
+    let ty = ty.ok_or(Fail::Error(ErrorKind::Suffix))?;
+    if let Suffix::Float = ty {
+      float = true;
+    }
+
+    let literal = NumericLiteral {
+      negative,
+      radix,
+      digits: self.input.take_from(start).take(end - start),
+      suffix: if suffix_len > 0 {
+        Some(self.input.take_from(end).take(self.pos - end))
+      } else {
+        None
+      },
+      float,
+    };
+
+    if !float {
+      let value = literal.value().ok_or(Fail::Error(ErrorKind::TooLarge))?;
+      if let Suffix::Integer { bits, signed } = ty {
+        let max = if signed {
+          (1u128 << (bits - 1)) - 1 + negative as u128
+        } else if negative && value != 0 {
+          0
+        } else {
+          u128::MAX >> (128 - bits)
+        };
+        if value > max {
+          return Err(Fail::Error(ErrorKind::TooLarge));
+        }
+      }
+    }
+
+    Ok(literal)
+  }
+}
+
+/// Error for the lack of digits in `radix`
+fn digit_kind(radix: u32) -> ErrorKind {
+  match radix {
+    2 => ErrorKind::BinDigit,
+    8 => ErrorKind::OctDigit,
+    16 => ErrorKind::HexDigit,
+    _ => ErrorKind::Digit,
+  }
+}
+
+/// Type given by a suffix
+enum Suffix {
+  /// No suffix, or a suffix without a checked range
+  Any,
+  Float,
+  Integer {
+    bits: u32,
+    signed: bool,
+  },
+}
+
+fn c_suffix(suffix: &[u8], float: bool) -> Option<Suffix> {
+  if float {
+    return match suffix {
+      b"" | b"f" | b"F" | b"l" | b"L" => Some(Suffix::Float),
+      _ => None,
+    };
+  }
+
+  let long = match suffix {
+    [b'u' | b'U', long @ ..] | [long @ .., b'u' | b'U'] => long,
+    long => long,
+  };
+  match long {
+    b"" | b"l" | b"L" | b"ll" | b"LL" => Some(Suffix::Any),
+    _ => None,
+  }
+}
+
+fn rust_suffix(suffix: &[u8], float: bool, radix: u32) -> Option<Suffix> {
+  let (signed, bits) = match suffix {
+    b"" => return Some(Suffix::Any),
+    b"f32" | b"f64" if radix == 10 => return Some(Suffix::Float),
+    _ if float => return None,
+    [b'u', bits @ ..] => (false, bits),
+    [b'i', bits @ ..] => (true, bits),
+    _ => return None,
+  };
+  let bits = match bits {
+    b"8" => 8,
+    b"16" => 16,
+    b"32" => 32,
+    b"64" => 64,
+    b"128" => 128,
+    b"size" => usize::BITS,
+    _ => return None,
+  };
+
+  Some(Suffix::Integer { bits, signed })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::error::Error;
+
+  #[test]
+  fn literals() {
+    let mut c = c::<_, Error<_>>();
+    let mut rust = rust::<_, Error<_>>();
+
+    assert_eq!(
+      rust.parse_complete("-1_000.5e-3f64;"),
+      Ok((
+        ";",
+        NumericLiteral {
+          negative: true,
+          radix: 10,
+          digits: "1_000.5e-3",
+          suffix: Some("f64"),
+          float: true,
+        }
+      ))
+    );
+    assert_eq!(
+      rust.parse_complete("0").map(|(_, lit)| lit.value()),
+      Ok(Some(0))
+    );
+    assert_eq!(
+      rust.parse_complete("340282366920938463463374607431768211456"),
+      Err(Err::Error(Error::new(
+        "340282366920938463463374607431768211456",
+        ErrorKind::TooLarge
+      )))
+    );
+    assert_eq!(
+      rust
+        .parse_complete("340282366920938463463374607431768211455u128")
+        .map(|(_, lit)| lit.value()),
+      Ok(Some(u128::MAX))
+    );
+    // the field or method access after an integer is not a fraction
+    assert_eq!(
+      rust
+        .parse_complete("1.max(2)")
+        .map(|(rest, lit)| (rest, lit.float)),
+      Ok((".max(2)", false))
+    );
+    assert_eq!(
+      rust
+        .parse_complete("0x1f32")
+        .map(|(_, lit)| (lit.value(), lit.suffix)),
+      Ok((Some(0x1f32), None))
+    );
+    assert_eq!(
+      rust.parse_complete("0b12"),
+      Err(Err::Error(Error::new("0b12", ErrorKind::Suffix)))
+    );
+    assert_eq!(
+      rust.parse_complete("1e+"),
+      Err(Err::Error(Error::new("1e+", ErrorKind::Float)))
+    );
+    assert_eq!(
+      rust.parse_complete("x"),
+      Err(Err::Error(Error::new("x", ErrorKind::Digit)))
+    );
+    assert_eq!(rust.parse("1u"), Err(Err::Incomplete(Needed::new(1))));
+
+    assert_eq!(
+      c.parse_complete("0")
+        .map(|(_, lit)| (lit.radix, lit.digits)),
+      Ok((10, "0"))
+    );
+    assert_eq!(
+      c.parse_complete("0755")
+        .map(|(_, lit)| (lit.radix, lit.digits)),
+      Ok((8, "755"))
+    );
+    assert_eq!(
+      c.parse_complete("09.5")
+        .map(|(_, lit)| (lit.radix, lit.float)),
+      Ok((10, true))
+    );
+    assert_eq!(
+      c.parse_complete("1'000LLU").map(|(_, lit)| lit.value()),
+      Ok(Some(1000))
+    );
+    assert_eq!(
+      c.parse_complete("1''0"),
+      Err(Err::Error(Error::new("1''0", ErrorKind::Digit)))
+    );
+    assert_eq!(
+      c.parse_complete("1.5u"),
+      Err(Err::Error(Error::new("1.5u", ErrorKind::Suffix)))
+    );
+    assert_eq!(
+      c.parse_complete("0XffUu"),
+      Err(Err::Error(Error::new("0XffUu", ErrorKind::Suffix)))
+    );
+  }
+
+  #[test]
+  #[cfg(feature = "alloc")]
+  fn literal_to_f64() {
+    let mut rust = rust::<_, Error<_>>();
+
+    let (_, lit) = rust.parse_complete("-1_0.2_5e1").unwrap();
+    assert_eq!(lit.to_f64(), Some(-102.5));
+    let (_, lit) = rust.parse_complete("0xffu8").unwrap();
+    assert_eq!(lit.to_f64(), Some(255.0));
+  }
+}
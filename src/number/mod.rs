@@ -7,15 +7,20 @@ use core::{
 
 use crate::{
   branch::alt,
-  character::{char, digit1},
+  bytes::take_while,
+  character::{char, digit1, one_of, satisfy, separated_digits, SeparatedDigitsError},
   combinator::{cut, map, opt, recognize},
   error::{make_error, ErrorKind, ParseError},
-  sequence::pair,
-  AsBytes, AsChar, Compare, Either, Emit, Err, Input, IsStreaming, Mode, Needed, Offset, OutputM,
-  Parser,
+  sequence::{pair, preceded},
+  AsBytes, AsChar, Compare, Either, Emit, Err, Input, Mode, Needed, Offset, OutputM, Parser,
+  Strictness,
 };
 
+#[cfg(feature = "alloc")]
+use crate::lib::std::string::String;
+
 pub mod complete;
+pub mod literal;
 pub mod streaming;
 
 /// Configurable endianness
@@ -46,13 +51,52 @@ where
   }
 }
 
-/// Big endian unsigned integer parser
-struct BeUint<Uint, E> {
+/// Big endian unsigned integer parser, like [be_u16]
+///
+/// It can be built in `const` contexts with [BeUint::new], so it can be stored in
+/// constants and statics.
+///
+/// ```rust
+/// # use nom::{Err, Needed, Parser};
+/// use nom::number::BeUint;
+///
+/// const LENGTH: BeUint<u16, ()> = BeUint::new();
+///
+/// let mut length = LENGTH;
+/// assert_eq!(length.parse(&b"\x01\x02abc"[..]), Ok((&b"abc"[..], 0x0102)));
+/// assert_eq!(length.parse(&b"\x01"[..]), Err(Err::Incomplete(Needed::new(1))));
+/// ```
+pub struct BeUint<Uint, E> {
   bound: usize,
   e: PhantomData<E>,
   u: PhantomData<Uint>,
 }
 
+impl<Uint, E> BeUint<Uint, E> {
+  /// Creates a parser reading `size_of::<Uint>()` bytes, in big endian order
+  pub const fn new() -> Self {
+    BeUint {
+      bound: core::mem::size_of::<Uint>(),
+      e: PhantomData,
+      u: PhantomData,
+    }
+  }
+}
+
+impl<Uint, E> Default for BeUint<Uint, E> {
+  fn default() -> Self {
+    BeUint::new()
+  }
+}
+
+impl<Uint, E> Clone for BeUint<Uint, E> {
+  fn clone(&self) -> Self {
+    *self
+  }
+}
+
+impl<Uint, E> Copy for BeUint<Uint, E> {}
+
 impl<I, Uint, E: ParseError<I>> Parser<I> for BeUint<Uint, E>
 where
   I: Input<Item = u8>,
@@ -67,7 +111,7 @@ where
     input: I,
   ) -> crate::PResult<OM, I, Self::Output, Self::Error> {
     if input.input_len() < self.bound {
-      if OM::Incomplete::is_streaming() {
+      if input.is_streaming::<OM>() {
         Err(Err::Incomplete(Needed::new(self.bound - input.input_len())))
       } else {
         Err(Err::Error(OM::Error::bind(|| {
@@ -383,7 +427,7 @@ where
     input: I,
   ) -> crate::PResult<OM, I, Self::Output, Self::Error> {
     if input.input_len() < self.bound {
-      if OM::Incomplete::is_streaming() {
+      if input.is_streaming::<OM>() {
         Err(Err::Incomplete(Needed::new(self.bound - input.input_len())))
       } else {
         Err(Err::Error(OM::Error::bind(|| {
@@ -1409,6 +1453,315 @@ where
   }
 }
 
+/// Recognizes a floating point number in text, with the accepted format depending on
+/// `strictness`
+///
+/// In strict mode, the number follows the JSON grammar: an optional `-`, an integer part
+/// without leading zeros, then an optional fraction and exponent, which must have digits.
+/// In lenient mode, it accepts the same numbers as [double]: a `+` sign, leading zeros,
+/// missing integer or fraction digits, and `nan`, `inf` or `infinity` in any case. It can
+/// also start with spaces or tabs.
+///
+/// It returns an error with [ErrorKind::Float] if the number has a leading zero in strict
+/// mode, or cannot be represented by the output type.
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Parser, Strictness};
+/// use nom::number::decimal;
+///
+/// let mut strict = decimal::<f64, _, Error<_>>(Strictness::Strict);
+/// assert_eq!(strict.parse_complete("-0.5e3,"), Ok((",", -500.0)));
+/// assert_eq!(strict.parse_complete("05"), Err(Err::Error(Error::new("05", ErrorKind::Float))));
+/// assert!(strict.parse_complete(".5").is_err());
+/// assert!(strict.parse_complete("+5").is_err());
+///
+/// let mut lenient = decimal::<f32, _, Error<_>>(Strictness::Lenient);
+/// assert_eq!(lenient.parse_complete(" +05."), Ok(("", 5.0)));
+/// assert_eq!(lenient.parse_complete(".5"), Ok(("", 0.5)));
+/// assert!(lenient.parse_complete("Infinity").unwrap().1.is_infinite());
+/// ```
+pub fn decimal<O, T, E: ParseError<T>>(
+  strictness: Strictness,
+) -> impl Parser<T, Output = O, Error = E>
+where
+  T: Clone + Offset,
+  T: Input + crate::traits::ParseTo<O> + Compare<&'static str>,
+  <T as Input>::Item: AsChar,
+{
+  Decimal {
+    strictness,
+    o: PhantomData,
+    e: PhantomData,
+  }
+}
+
+/// Parser implementation for [decimal]
+pub struct Decimal<O, E> {
+  strictness: Strictness,
+  o: PhantomData<O>,
+  e: PhantomData<E>,
+}
+
+impl<I, O, E: ParseError<I>> Parser<I> for Decimal<O, E>
+where
+  I: Clone + Offset,
+  I: Input + crate::traits::ParseTo<O> + Compare<&'static str>,
+  <I as Input>::Item: AsChar,
+{
+  type Output = O;
+  type Error = E;
+
+  fn process<OM: crate::OutputMode>(
+    &mut self,
+    input: I,
+  ) -> crate::PResult<OM, I, Self::Output, Self::Error> {
+    let (i, s) = match self.strictness {
+      Strictness::Strict => {
+        let (i, s) = recognize_json_float()
+          .process::<OutputM<Emit, OM::Error, OM::Incomplete>>(input.clone())?;
+        // the integer part stops after a leading zero
+        if i.iter_elements().next().map_or(false, |c| c.is_dec_digit()) {
+          return Err(Err::Error(OM::Error::bind(|| {
+            E::from_error_kind(input, ErrorKind::Float)
+          })));
+        }
+        (i, s)
+      }
+      Strictness::Lenient => preceded(
+        take_while(|c: <I as Input>::Item| matches!(c.as_char(), ' ' | '\t')),
+        recognize_float_or_exceptions(),
+      )
+      .process::<OutputM<Emit, OM::Error, OM::Incomplete>>(input)?,
+    };
+
+    match s.parse_to() {
+      Some(f) => Ok((i, OM::Output::bind(|| f))),
+      None => Err(Err::Error(OM::Error::bind(|| {
+        E::from_error_kind(i, ErrorKind::Float)
+      }))),
+    }
+  }
+}
+
+/// Recognizes a hex-encoded `u32` with digit separators, like `DEAD_BEEF`
+///
+/// `separator` is allowed between two digits only, like in
+/// [integer_sep](crate::character::integer_sep). A prefix like `0x` can be matched before
+/// it with [preceded].
+///
+/// It returns an error with [ErrorKind::IsA] if there is no digit, if a separator is
+/// misplaced, or if the number does not fit in a `u32`.
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Needed, Parser};
+/// use nom::bytes::complete::tag;
+/// use nom::number::hex_u32_sep;
+/// use nom::sequence::preceded;
+///
+/// let mut parser = preceded(tag("0x"), hex_u32_sep::<_, Error<_>>('_'));
+/// assert_eq!(parser.parse_complete("0xDEAD_beef;"), Ok((";", 0xDEAD_BEEF)));
+/// assert_eq!(parser.parse_complete("0x1_0000_0000"), Err(Err::Error(Error::new("1_0000_0000", ErrorKind::IsA))));
+/// assert_eq!(parser.parse_complete("0xAB__CD"), Err(Err::Error(Error::new("AB__CD", ErrorKind::IsA))));
+/// assert_eq!(parser.parse("0xAB_CD"), Err(Err::Incomplete(Needed::new(1))));
+/// ```
+pub fn hex_u32_sep<I, E: ParseError<I>>(separator: char) -> impl Parser<I, Output = u32, Error = E>
+where
+  I: Input,
+  <I as Input>::Item: AsChar,
+{
+  HexU32Sep {
+    separator,
+    e: PhantomData,
+  }
+}
+
+/// Parser implementation for [hex_u32_sep]
+pub struct HexU32Sep<E> {
+  separator: char,
+  e: PhantomData<E>,
+}
+
+impl<I, E: ParseError<I>> Parser<I> for HexU32Sep<E>
+where
+  I: Input,
+  <I as Input>::Item: AsChar,
+{
+  type Output = u32;
+  type Error = E;
+
+  fn process<OM: crate::OutputMode>(
+    &mut self,
+    input: I,
+  ) -> crate::PResult<OM, I, Self::Output, Self::Error> {
+    let mut value = 0u32;
+    let res = separated_digits(
+      &input,
+      16,
+      self.separator,
+      input.is_streaming::<OM>(),
+      |digit| match value.checked_mul(16) {
+        Some(v) => {
+          value = v + digit;
+          true
+        }
+        None => false,
+      },
+    );
+
+    match res {
+      Err(SeparatedDigitsError::Incomplete) => Err(Err::Incomplete(Needed::new(1))),
+      Ok((len, digits)) if digits > 0 => Ok((input.take_from(len), OM::Output::bind(|| value))),
+      _ => Err(Err::Error(OM::Error::bind(|| {
+        E::from_error_kind(input, ErrorKind::IsA)
+      }))),
+    }
+  }
+}
+
+/// Recognizes a floating point number in text with digit separators, like `1_000.000_1`,
+/// and returns a f64
+///
+/// It accepts the same numbers as [double], except `nan` and `inf`, with `separator`
+/// allowed between two digits of the integer part, the fraction or the exponent, like in
+/// [integer_sep](crate::character::integer_sep).
+///
+/// It returns an error with [ErrorKind::Float] if there is no digit, or if a separator is
+/// misplaced.
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Needed, Parser};
+/// use nom::number::double_sep;
+///
+/// let mut parser = double_sep::<_, Error<_>>('_');
+/// assert_eq!(parser.parse_complete("-1_000.250_5e1_0;"), Ok((";", -1_000.250_5e1_0)));
+/// assert_eq!(parser.parse_complete(".5"), Ok(("", 0.5)));
+/// assert_eq!(parser.parse_complete("1_.5"), Err(Err::Error(Error::new("1_.5", ErrorKind::Float))));
+/// assert_eq!(parser.parse_complete("1._5"), Err(Err::Error(Error::new("1._5", ErrorKind::Float))));
+/// assert_eq!(parser.parse("1_000.5"), Err(Err::Incomplete(Needed::new(1))));
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "alloc")))]
+pub fn double_sep<I, E: ParseError<I>>(separator: char) -> impl Parser<I, Output = f64, Error = E>
+where
+  I: Input,
+  <I as Input>::Item: AsChar,
+{
+  DoubleSep {
+    separator,
+    e: PhantomData,
+  }
+}
+
+/// Parser implementation for [double_sep]
+#[cfg(feature = "alloc")]
+pub struct DoubleSep<E> {
+  separator: char,
+  e: PhantomData<E>,
+}
+
+#[cfg(feature = "alloc")]
+impl<I, E: ParseError<I>> Parser<I> for DoubleSep<E>
+where
+  I: Input,
+  <I as Input>::Item: AsChar,
+{
+  type Output = f64;
+  type Error = E;
+
+  fn process<OM: crate::OutputMode>(
+    &mut self,
+    input: I,
+  ) -> crate::PResult<OM, I, Self::Output, Self::Error> {
+    let streaming = input.is_streaming::<OM>();
+    let error = |input: I| {
+      Err(Err::Error(OM::Error::bind(|| {
+        E::from_error_kind(input, ErrorKind::Float)
+      })))
+    };
+
+    // the number without its separators
+    let mut text = String::new();
+    let mut pos = 0;
+
+    let digits = |text: &mut String, pos: &mut usize| {
+      let res = separated_digits(&input.take_from(*pos), 10, self.separator, streaming, |d| {
+        text.push(core::char::from_digit(d, 10).unwrap_or('0'));
+        true
+      });
+      res.map(|(len, digits)| {
+        *pos += len;
+        digits
+      })
+    };
+    // the next character, which may still come on streaming data
+    let peek = |pos: usize| match input.take_from(pos).iter_elements().next() {
+      None if streaming => Err(SeparatedDigitsError::Incomplete),
+      c => Ok(c.map(|c| (c.as_char(), c.len()))),
+    };
+
+    let mut parse = || {
+      if let Some((sign @ ('+' | '-'), len)) = peek(pos)? {
+        text.push(sign);
+        pos += len;
+      }
+
+      let mut count = digits(&mut text, &mut pos)?;
+      if let Some(('.', len)) = peek(pos)? {
+        text.push('.');
+        pos += len;
+        count += digits(&mut text, &mut pos)?;
+      }
+      if count == 0 {
+        return Err(SeparatedDigitsError::Invalid);
+      }
+
+      if let Some((e @ ('e' | 'E'), len)) = peek(pos)? {
+        text.push(e);
+        pos += len;
+        if let Some((sign @ ('+' | '-'), len)) = peek(pos)? {
+          text.push(sign);
+          pos += len;
+        }
+        if digits(&mut text, &mut pos)? == 0 {
+          return Err(SeparatedDigitsError::Invalid);
+        }
+      }
+
+      Ok(pos)
+    };
+
+    match parse() {
+      Err(SeparatedDigitsError::Incomplete) => Err(Err::Incomplete(Needed::new(1))),
+      Err(SeparatedDigitsError::Invalid) => error(input.clone()),
+      Ok(pos) => match text.parse() {
+        Ok(f) => Ok((input.take_from(pos), OM::Output::bind(|| f))),
+        Err(_) => error(input.clone()),
+      },
+    }
+  }
+}
+
+/// Recognizes a float with the JSON grammar, stopping after a leading zero
+fn recognize_json_float<T, E: ParseError<T>>() -> impl Parser<T, Output = T, Error = E>
+where
+  T: Clone + Offset,
+  T: Input,
+  <T as Input>::Item: AsChar,
+{
+  recognize((
+    opt(char('-')),
+    alt((
+      map(char('0'), |_| ()),
+      map(
+        (satisfy(|c| ('1'..='9').contains(&c)), opt(digit1())),
+        |_| (),
+      ),
+    )),
+    opt((char('.'), cut(digit1()))),
+    opt((one_of("eE"), opt(one_of("+-")), cut(digit1()))),
+  ))
+}
+
 #[cfg(test)]
 #[cfg(feature = "std")]
 mod tests {
@@ -1479,4 +1832,68 @@ mod tests {
     assert!(inf.is_infinite());
     assert!(i.is_empty());*/
   }
+
+  #[test]
+  fn decimal_strictness() {
+    let mut strict = decimal::<f64, _, (_, ErrorKind)>(Strictness::Strict);
+    assert_parse!(strict.parse_complete("0"), Ok(("", 0.0)));
+    assert_parse!(strict.parse_complete("-12.5E+2]"), Ok(("]", -1250.0)));
+    assert_parse!(strict.parse_complete("1e5x"), Ok(("x", 100000.0)));
+    assert_parse!(
+      strict.parse_complete("-00.1"),
+      Err(Err::Error(("-00.1", ErrorKind::Float)))
+    );
+    assert_parse!(
+      strict.parse_complete("1."),
+      Err(Err::Failure(("", ErrorKind::Digit)))
+    );
+    assert_parse!(
+      strict.parse_complete("inf"),
+      Err(Err::Error(("inf", ErrorKind::Satisfy)))
+    );
+    assert_parse!(
+      strict.parse_complete(" 1"),
+      Err(Err::Error((" 1", ErrorKind::Satisfy)))
+    );
+    assert_parse!(strict.parse("0"), Err(Err::Incomplete(Needed::new(1))));
+
+    let mut lenient = decimal::<f64, _, (_, ErrorKind)>(Strictness::Lenient);
+    assert_parse!(lenient.parse_complete("\t-00.1"), Ok(("", -0.1)));
+    assert_parse!(lenient.parse_complete("+1.e2 "), Ok((" ", 100.0)));
+    assert_parse!(
+      decimal(Strictness::Lenient).parse_complete(&b"12.5;"[..]),
+      Ok((&b";"[..], 12.5))
+    );
+    assert!(lenient.parse_complete("NaN").unwrap().1.is_nan());
+  }
+
+  #[test]
+  fn digit_separators() {
+    let mut hex = hex_u32_sep::<_, (_, ErrorKind)>('_');
+    assert_eq!(hex.parse_complete("ffff_ffff"), Ok(("", u32::MAX)));
+    assert_eq!(hex.parse_complete("0_1g"), Ok(("g", 1)));
+    assert_eq!(
+      hex.parse_complete("_1"),
+      Err(Err::Error(("_1", ErrorKind::IsA)))
+    );
+    assert_eq!(hex.parse("12_"), Err(Err::Incomplete(Needed::new(1))));
+
+    let mut double = double_sep::<_, (_, ErrorKind)>('_');
+    assert_eq!(double.parse_complete("1_0."), Ok(("", 10.0)));
+    assert_eq!(double.parse_complete("+1E-1_0x"), Ok(("x", 1e-10)));
+    assert_eq!(
+      double.parse_complete("1e"),
+      Err(Err::Error(("1e", ErrorKind::Float)))
+    );
+    assert_eq!(
+      double.parse_complete("."),
+      Err(Err::Error((".", ErrorKind::Float)))
+    );
+    assert_eq!(
+      double.parse_complete("_1"),
+      Err(Err::Error(("_1", ErrorKind::Float)))
+    );
+    assert_eq!(double.parse("1e+"), Err(Err::Incomplete(Needed::new(1))));
+    assert_eq!(double.parse("1_0;"), Ok((";", 10.0)));
+  }
 }
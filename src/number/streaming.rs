@@ -1456,6 +1456,76 @@ where
   }
 }
 
+/// Recognizes floating point number in text format and returns it with the f32, like
+/// [float], keeping the original text for formatters and editors.
+///
+/// *Streaming version*: Will return `Err(nom::Err::Incomplete(_))` if there is not enough data.
+/// ```rust
+/// # use nom::{Err, error::ErrorKind, Needed};
+/// use nom::number::streaming::float_with_str;
+///
+/// let parser = |s| {
+///   float_with_str::<_, (_, ErrorKind)>(s)
+/// };
+///
+/// assert_eq!(parser("11e-1;"), Ok((";", ("11e-1", 1.1))));
+/// assert_eq!(parser("123E-02;"), Ok((";", ("123E-02", 1.23))));
+/// assert_eq!(parser("123"), Err(Err::Incomplete(Needed::new(1))));
+/// assert_eq!(parser("abc"), Err(Err::Error(("abc", ErrorKind::Float))));
+/// ```
+pub fn float_with_str<T, E: ParseError<T>>(input: T) -> IResult<T, (T, f32), E>
+where
+  T: Clone + Offset,
+  T: Input + crate::traits::ParseTo<f32> + Compare<&'static str>,
+  <T as Input>::Item: AsChar + Clone,
+  T: AsBytes,
+  T: for<'a> Compare<&'a [u8]>,
+{
+  let (i, s) = recognize_float_or_exceptions(input)?;
+  match s.parse_to() {
+    Some(f) => Ok((i, (s, f))),
+    None => Err(crate::Err::Error(E::from_error_kind(
+      i,
+      crate::error::ErrorKind::Float,
+    ))),
+  }
+}
+
+/// Recognizes floating point number in text format and returns it with the f64, like
+/// [double], keeping the original text for formatters and editors.
+///
+/// *Streaming version*: Will return `Err(nom::Err::Incomplete(_))` if there is not enough data.
+/// ```rust
+/// # use nom::{Err, error::ErrorKind, Needed};
+/// use nom::number::streaming::double_with_str;
+///
+/// let parser = |s| {
+///   double_with_str::<_, (_, ErrorKind)>(s)
+/// };
+///
+/// assert_eq!(parser("11e-1;"), Ok((";", ("11e-1", 1.1))));
+/// assert_eq!(parser("123E-02;"), Ok((";", ("123E-02", 1.23))));
+/// assert_eq!(parser("123"), Err(Err::Incomplete(Needed::new(1))));
+/// assert_eq!(parser("abc"), Err(Err::Error(("abc", ErrorKind::Float))));
+/// ```
+pub fn double_with_str<T, E: ParseError<T>>(input: T) -> IResult<T, (T, f64), E>
+where
+  T: Clone + Offset,
+  T: Input + crate::traits::ParseTo<f64> + Compare<&'static str>,
+  <T as Input>::Item: AsChar + Clone,
+  T: AsBytes,
+  T: for<'a> Compare<&'a [u8]>,
+{
+  let (i, s) = recognize_float_or_exceptions(input)?;
+  match s.parse_to() {
+    Some(f) => Ok((i, (s, f))),
+    None => Err(crate::Err::Error(E::from_error_kind(
+      i,
+      crate::error::ErrorKind::Float,
+    ))),
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
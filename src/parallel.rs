@@ -0,0 +1,284 @@
+//! Parsing large record oriented inputs on several threads
+//!
+//! Logs, CSV files and other formats made of independent records can be parsed in
+//! parallel: [split] cuts the input in chunks ending on record boundaries, and
+//! [parse_records] applies a record parser on each chunk on its own thread, then
+//! reassembles the outputs in the order of the input.
+//!
+//! The boundaries are found with a parser recognizing the end of a record, tried at each
+//! position after the point where a chunk would ideally end. For formats where the
+//! delimiter can also appear inside a record, like a newline in a quoted CSV field, the
+//! boundary parser must recognize something that can only appear between records, like
+//! a newline followed by the timestamp starting every log line.
+//!
+//! With the `rayon` feature, the chunks are parsed on the [rayon](https://crates.io/crates/rayon)
+//! thread pool. Otherwise, a thread is spawned for each chunk.
+//!
+//! ```rust
+//! # use nom::{IResult, Parser};
+//! use nom::bytes::tag;
+//! use nom::character::digit1;
+//! use nom::combinator::map_res;
+//! use nom::parallel::parse_records;
+//! use nom::sequence::terminated;
+//!
+//! fn number(input: &[u8]) -> IResult<&[u8], u32> {
+//!   map_res(terminated(digit1(), tag("\n")), |digits: &[u8]| {
+//!     std::str::from_utf8(digits).unwrap().parse::<u32>()
+//!   })
+//!   .parse(input)
+//! }
+//!
+//! let input = (0..1000).map(|n| format!("{}\n", n)).collect::<String>();
+//! let numbers = parse_records(input.as_bytes(), 4, tag("\n"), || number).unwrap();
+//! assert_eq!(numbers, (0..1000).collect::<Vec<u32>>());
+//! ```
+
+use crate::error::{ErrorKind, ParseError};
+use crate::internal::{Err, Parser};
+use crate::lib::std::vec::Vec;
+
+/// Splits `input` in at most `count` chunks, each ending after a match of `boundary`
+///
+/// The input is divided in `count` parts of the same size, then each part is extended
+/// until the end of the first match of `boundary` found at or after its end. If no
+/// boundary is found, the rest of the input goes to the last chunk. `boundary` is applied
+/// in complete mode, and matches that do not consume any input are ignored.
+///
+/// The chunks are contiguous and cover the whole input. There are fewer than `count` of
+/// them if the input has too few boundaries, and none if the input is empty.
+///
+/// ```rust
+/// use nom::bytes::tag;
+/// use nom::error::Error;
+/// use nom::parallel::split;
+///
+/// let input = &b"a\nbb\nccc\ndddd\n"[..];
+/// assert_eq!(
+///   split(input, 3, tag::<_, _, Error<_>>("\n")),
+///   [&b"a\nbb\n"[..], &b"ccc\n"[..], &b"dddd\n"[..]]
+/// );
+/// ```
+pub fn split<'a, B>(input: &'a [u8], count: usize, mut boundary: B) -> Vec<&'a [u8]>
+where
+  B: Parser<&'a [u8]>,
+{
+  let count = count.max(1);
+  let mut chunks = Vec::with_capacity(count);
+  let mut start = 0;
+
+  for k in 1..count {
+    let mut position = (input.len() / count * k).max(start);
+    let mut end = None;
+    while position < input.len() {
+      if let Ok((rest, _)) = boundary.parse_complete(&input[position..]) {
+        if rest.len() < input.len() - position {
+          end = Some(input.len() - rest.len());
+          break;
+        }
+      }
+      position += 1;
+    }
+
+    match end {
+      Some(end) => {
+        chunks.push(&input[start..end]);
+        start = end;
+      }
+      None => break,
+    }
+  }
+
+  if start < input.len() {
+    chunks.push(&input[start..]);
+  }
+  chunks
+}
+
+/// Splits `input` in at most `count` chunks with [split], and applies a record parser
+/// repeatedly on each chunk, in parallel
+///
+/// `make_parser` is called once for each chunk, to build the record parser on the thread
+/// parsing it. The record parser is applied in complete mode until the chunk is consumed,
+/// and the outputs of all chunks are returned in the order of the input.
+///
+/// If a record cannot be parsed, the error of the first chunk that failed is returned,
+/// even if a later chunk failed first. Its input positions borrow `input`, so their
+/// offsets in the input can be computed with [Offset](crate::Offset). A record parser
+/// that succeeds without consuming input returns an error with [ErrorKind::Many0].
+///
+/// ```rust
+/// # use nom::{Err, IResult, Parser};
+/// use nom::bytes::{tag, take_until};
+/// use nom::error::{Error, ErrorKind};
+/// use nom::parallel::parse_records;
+/// use nom::sequence::terminated;
+/// use nom::Offset;
+///
+/// fn line(input: &[u8]) -> IResult<&[u8], &[u8]> {
+///   terminated(take_until("\n"), tag("\n")).parse_complete(input)
+/// }
+///
+/// let input = &b"first\nsecond\nthird\nunterminated"[..];
+/// let e = parse_records(input, 2, tag("\n"), || line).unwrap_err();
+/// assert_eq!(e.code, ErrorKind::TakeUntil);
+/// assert_eq!(input.offset(e.input), 19);
+/// ```
+pub fn parse_records<'a, B, F, P>(
+  input: &'a [u8],
+  count: usize,
+  boundary: B,
+  make_parser: F,
+) -> Result<Vec<P::Output>, P::Error>
+where
+  B: Parser<&'a [u8], Error = P::Error>,
+  F: Fn() -> P + Sync,
+  P: Parser<&'a [u8]>,
+  P::Output: Send,
+  P::Error: ParseError<&'a [u8]> + Send,
+{
+  let chunks = split(input, count, boundary);
+  let results = run(&chunks, |chunk| records(chunk, make_parser()));
+
+  let mut outputs = Vec::new();
+  for result in results {
+    outputs.extend(result?);
+  }
+  Ok(outputs)
+}
+
+/// Applies `parser` until `input` is consumed
+fn records<'a, P>(mut input: &'a [u8], mut parser: P) -> Result<Vec<P::Output>, P::Error>
+where
+  P: Parser<&'a [u8]>,
+  P::Error: ParseError<&'a [u8]>,
+{
+  let mut outputs = Vec::new();
+  while !input.is_empty() {
+    match parser.parse_complete(input) {
+      Ok((rest, _)) if rest.len() == input.len() => {
+        return Err(P::Error::from_error_kind(input, ErrorKind::Many0));
+      }
+      Ok((rest, output)) => {
+        outputs.push(output);
+        input = rest;
+      }
+      Err(Err::Error(e)) | Err(Err::Failure(e)) => return Err(e),
+      Err(Err::Incomplete(_)) => {
+        return Err(P::Error::from_error_kind(input, ErrorKind::Complete));
+      }
+    }
+  }
+  Ok(outputs)
+}
+
+/// Applies `f` on each chunk on the rayon thread pool, returning the results in order
+#[cfg(feature = "rayon")]
+fn run<'a, F, T>(chunks: &[&'a [u8]], f: F) -> Vec<T>
+where
+  F: Fn(&'a [u8]) -> T + Sync,
+  T: Send,
+{
+  use rayon::prelude::*;
+
+  chunks.par_iter().map(|chunk| f(chunk)).collect()
+}
+
+/// Applies `f` on each chunk on its own thread, returning the results in order
+#[cfg(not(feature = "rayon"))]
+fn run<'a, F, T>(chunks: &[&'a [u8]], f: F) -> Vec<T>
+where
+  F: Fn(&'a [u8]) -> T + Sync,
+  T: Send,
+{
+  let f = &f;
+  std::thread::scope(|scope| {
+    let handles = chunks
+      .iter()
+      .map(|&chunk| scope.spawn(move || f(chunk)))
+      .collect::<Vec<_>>();
+    handles
+      .into_iter()
+      .map(|handle| match handle.join() {
+        Ok(result) => result,
+        Err(panic) => std::panic::resume_unwind(panic),
+      })
+      .collect()
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::bytes::{tag, take_until};
+  use crate::character::{char, digit1};
+  use crate::combinator::{opt, peek};
+  use crate::error::Error;
+  use crate::sequence::terminated;
+  use crate::traits::Offset;
+  use crate::IResult;
+
+  fn line(input: &[u8]) -> IResult<&[u8], &[u8]> {
+    terminated(take_until("\n"), tag("\n")).parse(input)
+  }
+
+  #[test]
+  fn chunks() {
+    let input = &b"a\nbb\nccc\ndddd\n"[..];
+    for count in 0..20 {
+      let chunks = split(input, count, tag::<_, _, Error<_>>("\n"));
+      assert!(chunks.len() <= count.max(1));
+      assert_eq!(chunks.concat(), input);
+      assert!(chunks.iter().all(|chunk| chunk.ends_with(b"\n")));
+    }
+
+    // no boundary after the split points
+    assert_eq!(
+      split(&b"abc\ndefghijkl"[..], 4, tag::<_, _, Error<_>>("\n")),
+      [&b"abc\n"[..], &b"defghijkl"[..]]
+    );
+    assert!(split(&b""[..], 4, tag::<_, _, Error<_>>("\n")).is_empty());
+
+    // the boundary can look at the start of the next record: the `\n` inside the quoted
+    // field is not followed by a line number
+    let input = &b"1,\"a\nb\"\n2,c\n"[..];
+    assert_eq!(
+      split(input, 3, tag::<_, _, Error<_>>("\n"))[0],
+      &b"1,\"a\n"[..]
+    );
+    let boundary = terminated(char::<_, Error<_>>('\n'), peek(digit1()));
+    assert_eq!(
+      split(input, 3, boundary),
+      [&b"1,\"a\nb\"\n"[..], &b"2,c\n"[..]]
+    );
+  }
+
+  #[test]
+  fn records_in_order() {
+    let input = (0..500)
+      .map(|n| format!("line {}\n", n))
+      .collect::<String>();
+    let expected = input.as_bytes().split(|b| *b == b'\n').collect::<Vec<_>>();
+    for count in [1, 2, 3, 7, 64] {
+      let lines = parse_records(input.as_bytes(), count, tag("\n"), || line).unwrap();
+      assert_eq!(lines, expected[..500]);
+    }
+  }
+
+  #[test]
+  fn record_errors() {
+    // the first error in the input is returned
+    let input = &b"1\n2\nx\n4\n5\ny\n"[..];
+    let number = || terminated(digit1::<_, Error<_>>(), tag("\n"));
+    for count in 1..6 {
+      let e = parse_records(input, count, tag("\n"), number).unwrap_err();
+      assert_eq!((input.offset(e.input), e.code), (4, ErrorKind::Digit));
+    }
+
+    let e = parse_records(&b"a\n"[..], 1, tag("\n"), || {
+      opt(tag::<_, _, Error<_>>("b"))
+    })
+    .unwrap_err();
+    assert_eq!(e, Error::new(&b"a\n"[..], ErrorKind::Many0));
+  }
+}
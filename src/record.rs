@@ -0,0 +1,101 @@
+//! Parsing of fixed layout binary records
+//!
+//! Binary formats describe most of their structures as a sequence of fields, each
+//! one a number of a given size and endianness, a fixed size array, or data whose
+//! length was read in a previous field. The [NomParse] trait associates such a type
+//! with its parser, so it can be used as a field of another record, or with the
+//! [record()] combinator.
+//!
+//! With the `derive` feature, `#[derive(NomParse)]` generates the implementation
+//! from the declaration of a struct or an enum, with `#[nom(...)]` attributes for
+//! the endianness, the tags, the length of the fields and the conditional fields.
+//! See the documentation of the `nom-parse-derive` crate for the list of attributes.
+//!
+//! ```rust
+//! # use nom::{Err, error::{Error, ErrorKind, ParseError}, IResult, Parser};
+//! use nom::number::{be_u16, u8};
+//! use nom::record::{record, NomParse};
+//! use nom::{Emit, OutputM, OutputMode, PResult};
+//!
+//! #[derive(Debug, PartialEq)]
+//! struct Version {
+//!   major: u8,
+//!   minor: u16,
+//! }
+//!
+//! impl<I: nom::Input<Item = u8>, E: ParseError<I>> NomParse<I, E> for Version {
+//!   fn process<OM: OutputMode>(input: I) -> PResult<OM, I, Self, E> {
+//!     (u8(), be_u16())
+//!       .map(|(major, minor)| Version { major, minor })
+//!       .process::<OM>(input)
+//!   }
+//! }
+//!
+//! assert_eq!(
+//!   Version::parse(&b"\x01\x00\x02"[..]),
+//!   Ok::<_, Err<Error<_>>>((&b""[..], Version { major: 1, minor: 2 }))
+//! );
+//!
+//! let mut versions = (record::<Version, _, Error<_>>(), record::<Version, _, _>());
+//! assert_eq!(
+//!   versions.parse(&b"\x01\x00\x02\x03\x00\x04"[..]),
+//!   Ok((&b""[..], (Version { major: 1, minor: 2 }, Version { major: 3, minor: 4 })))
+//! );
+//! ```
+
+use core::marker::PhantomData;
+
+use crate::error::ParseError;
+use crate::internal::{Complete, Emit, IResult, OutputM, OutputMode, PResult, Parser, Streaming};
+
+#[cfg(feature = "derive")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "derive")))]
+pub use nom_parse_derive::NomParse;
+
+/// Types that can be parsed from an input of type `I`, with errors of type `E`
+///
+/// This is usually implemented with `#[derive(NomParse)]`, with the `derive` feature.
+pub trait NomParse<I, E>: Sized {
+  /// Parses a value, following the output mode `OM` like [Parser::process]
+  fn process<OM: OutputMode>(input: I) -> PResult<OM, I, Self, E>;
+
+  /// Parses a value in streaming mode
+  fn parse(input: I) -> IResult<I, Self, E> {
+    Self::process::<OutputM<Emit, Emit, Streaming>>(input)
+  }
+
+  /// Parses a value in complete mode
+  fn parse_complete(input: I) -> IResult<I, Self, E> {
+    Self::process::<OutputM<Emit, Emit, Complete>>(input)
+  }
+}
+
+/// Returns a parser for a type implementing [NomParse]
+pub fn record<T, I, E>() -> Record<T, E>
+where
+  T: NomParse<I, E>,
+  E: ParseError<I>,
+{
+  Record {
+    output: PhantomData,
+  }
+}
+
+/// Parser implementation for [record()]
+pub struct Record<T, E> {
+  output: PhantomData<fn() -> (T, E)>,
+}
+
+impl<I, T, E> Parser<I> for Record<T, E>
+where
+  T: NomParse<I, E>,
+  E: ParseError<I>,
+{
+  type Output = T;
+  type Error = E;
+
+  #[inline]
+  fn process<OM: OutputMode>(&mut self, input: I) -> PResult<OM, I, Self::Output, Self::Error> {
+    T::process::<OM>(input)
+  }
+}
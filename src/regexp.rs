@@ -0,0 +1,358 @@
+//! Parsers applying regular expressions from the [regex] crate
+//!
+//! They work on `&str` inputs with [regex::Regex], and on `&[u8]` inputs with
+//! [regex::bytes::Regex], through the [Pattern] trait. Like the other parsers, they
+//! follow the mode they are called with: in streaming mode, a match reaching the end of the
+//! input could extend over the data that did not arrive yet, so they return `Incomplete`
+//! instead.
+//!
+//! ```rust
+//! # use nom::{Err, error::{Error, ErrorKind}, Needed, Parser};
+//! use nom::regexp::re_find;
+//! use regex::Regex;
+//!
+//! let mut date = re_find::<_, _, Error<_>>(Regex::new(r"\d{4}-\d{2}-\d{2}").unwrap());
+//! assert_eq!(date.parse_complete("on 2024-01-31."), Ok((".", "2024-01-31")));
+//! assert_eq!(
+//!   date.parse_complete("on the 31st."),
+//!   Err(Err::Error(Error::new("on the 31st.", ErrorKind::RegexpFind)))
+//! );
+//! assert_eq!(date.parse("on 2024-01-31"), Err(Err::Incomplete(Needed::Unknown)));
+//! ```
+
+use core::marker::PhantomData;
+
+use crate::error::{ErrorKind, ParseError};
+use crate::internal::{Err, Mode, Needed, OutputMode, PResult, Parser};
+use crate::lib::std::vec::Vec;
+use crate::traits::Input;
+
+/// Regular expressions applicable to an input type
+///
+/// The positions are byte offsets in the input.
+pub trait Pattern<I> {
+  /// Returns the start and end of the leftmost first match in the input
+  fn find(&self, input: &I) -> Option<(usize, usize)>;
+
+  /// Returns the start and end of the successive non overlapping matches in the input
+  fn find_all(&self, input: &I) -> Vec<(usize, usize)>;
+
+  /// Returns the start and end of the capture groups of the leftmost first match in
+  /// the input, the first group being the whole match, and groups that did not
+  /// participate in the match being `None`
+  fn captures(&self, input: &I) -> Option<Vec<Option<(usize, usize)>>>;
+}
+
+impl<'a> Pattern<&'a str> for regex::Regex {
+  fn find(&self, input: &&'a str) -> Option<(usize, usize)> {
+    regex::Regex::find(self, input).map(|m| (m.start(), m.end()))
+  }
+
+  fn find_all(&self, input: &&'a str) -> Vec<(usize, usize)> {
+    self
+      .find_iter(input)
+      .map(|m| (m.start(), m.end()))
+      .collect()
+  }
+
+  fn captures(&self, input: &&'a str) -> Option<Vec<Option<(usize, usize)>>> {
+    regex::Regex::captures(self, input)
+      .map(|c| c.iter().map(|m| m.map(|m| (m.start(), m.end()))).collect())
+  }
+}
+
+impl<'a> Pattern<&'a [u8]> for regex::bytes::Regex {
+  fn find(&self, input: &&'a [u8]) -> Option<(usize, usize)> {
+    regex::bytes::Regex::find(self, input).map(|m| (m.start(), m.end()))
+  }
+
+  fn find_all(&self, input: &&'a [u8]) -> Vec<(usize, usize)> {
+    self
+      .find_iter(input)
+      .map(|m| (m.start(), m.end()))
+      .collect()
+  }
+
+  fn captures(&self, input: &&'a [u8]) -> Option<Vec<Option<(usize, usize)>>> {
+    regex::bytes::Regex::captures(self, input)
+      .map(|c| c.iter().map(|m| m.map(|m| (m.start(), m.end()))).collect())
+  }
+}
+
+impl<I, R: Pattern<I> + ?Sized> Pattern<I> for &R {
+  fn find(&self, input: &I) -> Option<(usize, usize)> {
+    (**self).find(input)
+  }
+
+  fn find_all(&self, input: &I) -> Vec<(usize, usize)> {
+    (**self).find_all(input)
+  }
+
+  fn captures(&self, input: &I) -> Option<Vec<Option<(usize, usize)>>> {
+    (**self).captures(input)
+  }
+}
+
+/// Returns the slice of the input between `start` and `end`
+fn slice<I: Input>(input: &I, start: usize, end: usize) -> I {
+  input.take_from(start).take(end - start)
+}
+
+/// Returns the first match of the regular expression in the input, and the input
+/// following it
+///
+/// # Streaming Specific
+/// *Streaming version* will return `Err::Incomplete(Needed::Unknown)` if there is no
+/// match, or if the match reaches the end of the input.
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Needed, IResult, Parser};
+/// use nom::regexp::re_find;
+/// use regex::bytes::Regex;
+///
+/// fn version(i: &[u8]) -> IResult<&[u8], &[u8]> {
+///   re_find(Regex::new(r"v\d+").unwrap()).parse_complete(i)
+/// }
+///
+/// assert_eq!(version(b"lib v12 ok"), Ok((&b" ok"[..], &b"v12"[..])));
+/// assert_eq!(version(b"lib v12"), Ok((&b""[..], &b"v12"[..])));
+/// assert_eq!(
+///   version(b"lib"),
+///   Err(Err::Error(Error::new(&b"lib"[..], ErrorKind::RegexpFind)))
+/// );
+/// ```
+pub fn re_find<I, R, E>(re: R) -> impl Parser<I, Output = I, Error = E>
+where
+  I: Input,
+  R: Pattern<I>,
+  E: ParseError<I>,
+{
+  ReFind { re, e: PhantomData }
+}
+
+/// Parser implementation for [re_find]
+pub struct ReFind<R, E> {
+  re: R,
+  e: PhantomData<E>,
+}
+
+impl<I, R, E> Parser<I> for ReFind<R, E>
+where
+  I: Input,
+  R: Pattern<I>,
+  E: ParseError<I>,
+{
+  type Output = I;
+  type Error = E;
+
+  fn process<OM: OutputMode>(&mut self, i: I) -> PResult<OM, I, Self::Output, Self::Error> {
+    match self.re.find(&i) {
+      Some((_, end)) if end == i.input_len() && i.is_streaming::<OM>() => {
+        Err(Err::Incomplete(Needed::Unknown))
+      }
+      Some((start, end)) => Ok((i.take_from(end), OM::Output::bind(|| slice(&i, start, end)))),
+      None if i.is_streaming::<OM>() => Err(Err::Incomplete(Needed::Unknown)),
+      None => Err(Err::Error(OM::Error::bind(|| {
+        E::from_error_kind(i, ErrorKind::RegexpFind)
+      }))),
+    }
+  }
+}
+
+/// Returns the capture groups of the first match of the regular expression in the input,
+/// and the input following the match
+///
+/// The first element is the whole match, followed by the groups that participated in
+/// the match.
+///
+/// # Streaming Specific
+/// *Streaming version* will return `Err::Incomplete(Needed::Unknown)` if there is no
+/// match, or if the match reaches the end of the input.
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Needed, IResult, Parser};
+/// use nom::regexp::re_capture;
+/// use regex::Regex;
+///
+/// fn assignment(i: &str) -> IResult<&str, Vec<&str>> {
+///   re_capture(Regex::new(r"(\w+)\s*=\s*(\d+)").unwrap()).parse_complete(i)
+/// }
+///
+/// assert_eq!(assignment("let a = 12;"), Ok((";", vec!["a = 12", "a", "12"])));
+/// assert_eq!(
+///   assignment("let a;"),
+///   Err(Err::Error(Error::new("let a;", ErrorKind::RegexpCapture)))
+/// );
+/// ```
+pub fn re_capture<I, R, E>(re: R) -> impl Parser<I, Output = Vec<I>, Error = E>
+where
+  I: Input,
+  R: Pattern<I>,
+  E: ParseError<I>,
+{
+  ReCapture { re, e: PhantomData }
+}
+
+/// Parser implementation for [re_capture]
+pub struct ReCapture<R, E> {
+  re: R,
+  e: PhantomData<E>,
+}
+
+impl<I, R, E> Parser<I> for ReCapture<R, E>
+where
+  I: Input,
+  R: Pattern<I>,
+  E: ParseError<I>,
+{
+  type Output = Vec<I>;
+  type Error = E;
+
+  fn process<OM: OutputMode>(&mut self, i: I) -> PResult<OM, I, Self::Output, Self::Error> {
+    let groups = match self.re.captures(&i) {
+      Some(groups) => groups,
+      None if i.is_streaming::<OM>() => return Err(Err::Incomplete(Needed::Unknown)),
+      None => {
+        return Err(Err::Error(OM::Error::bind(|| {
+          E::from_error_kind(i, ErrorKind::RegexpCapture)
+        })))
+      }
+    };
+
+    // the first group is the whole match, and always participates
+    let end = groups.first().copied().flatten().map_or(0, |(_, end)| end);
+    if end == i.input_len() && i.is_streaming::<OM>() {
+      return Err(Err::Incomplete(Needed::Unknown));
+    }
+
+    Ok((
+      i.take_from(end),
+      OM::Output::bind(|| {
+        groups
+          .into_iter()
+          .flatten()
+          .map(|(start, end)| slice(&i, start, end))
+          .collect()
+      }),
+    ))
+  }
+}
+
+/// Returns all the successive non overlapping matches of the regular expression in the
+/// input, and the input following the last one
+///
+/// # Streaming Specific
+/// *Streaming version* will return `Err::Incomplete(Needed::Unknown)` if there is no
+/// match, or if the last match reaches the end of the input.
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Needed, IResult, Parser};
+/// use nom::regexp::re_matches;
+/// use regex::Regex;
+///
+/// fn numbers(i: &str) -> IResult<&str, Vec<&str>> {
+///   re_matches(Regex::new(r"\d+").unwrap()).parse_complete(i)
+/// }
+///
+/// assert_eq!(numbers("1, 22 and 333."), Ok((".", vec!["1", "22", "333"])));
+/// assert_eq!(
+///   numbers("none"),
+///   Err(Err::Error(Error::new("none", ErrorKind::RegexpMatches)))
+/// );
+/// ```
+pub fn re_matches<I, R, E>(re: R) -> impl Parser<I, Output = Vec<I>, Error = E>
+where
+  I: Input,
+  R: Pattern<I>,
+  E: ParseError<I>,
+{
+  ReMatches { re, e: PhantomData }
+}
+
+/// Parser implementation for [re_matches]
+pub struct ReMatches<R, E> {
+  re: R,
+  e: PhantomData<E>,
+}
+
+impl<I, R, E> Parser<I> for ReMatches<R, E>
+where
+  I: Input,
+  R: Pattern<I>,
+  E: ParseError<I>,
+{
+  type Output = Vec<I>;
+  type Error = E;
+
+  fn process<OM: OutputMode>(&mut self, i: I) -> PResult<OM, I, Self::Output, Self::Error> {
+    let matches = self.re.find_all(&i);
+    match matches.last() {
+      Some(&(_, end)) if end == i.input_len() && i.is_streaming::<OM>() => {
+        Err(Err::Incomplete(Needed::Unknown))
+      }
+      Some(&(_, end)) => Ok((
+        i.take_from(end),
+        OM::Output::bind(|| {
+          matches
+            .into_iter()
+            .map(|(start, end)| slice(&i, start, end))
+            .collect()
+        }),
+      )),
+      None if i.is_streaming::<OM>() => Err(Err::Incomplete(Needed::Unknown)),
+      None => Err(Err::Error(OM::Error::bind(|| {
+        E::from_error_kind(i, ErrorKind::RegexpMatches)
+      }))),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::error::Error;
+  use crate::lib::std::vec;
+  use crate::sequence::preceded;
+
+  #[test]
+  fn regexp() {
+    let re = regex::Regex::new(r"[a-z]+").unwrap();
+    let bytes = regex::bytes::Regex::new(r"(\d+)(x)?").unwrap();
+
+    assert_eq!(
+      re_find::<_, _, Error<_>>(&re).parse_complete("12ab3"),
+      Ok(("3", "ab"))
+    );
+    assert_eq!(
+      re_find::<_, _, Error<_>>(&re).parse("12ab"),
+      Err(Err::Incomplete(Needed::Unknown))
+    );
+    assert_eq!(
+      re_find::<_, _, Error<_>>(&re).parse("12"),
+      Err(Err::Incomplete(Needed::Unknown))
+    );
+
+    assert_eq!(
+      re_capture::<_, _, Error<_>>(&bytes).parse_complete(&b"ab12;34x"[..]),
+      Ok((&b";34x"[..], vec![&b"12"[..], &b"12"[..]]))
+    );
+    assert_eq!(
+      re_capture::<_, _, Error<_>>(&bytes).parse(&b"12x;"[..]),
+      Ok((&b";"[..], vec![&b"12x"[..], &b"12"[..], &b"x"[..]]))
+    );
+    assert_eq!(
+      re_capture::<_, _, Error<_>>(&bytes).parse_complete(&b"ab"[..]),
+      Err(Err::Error(Error::new(&b"ab"[..], ErrorKind::RegexpCapture)))
+    );
+
+    assert_eq!(
+      re_matches::<_, _, Error<_>>(&bytes).parse(&b"1 2x 3;"[..]),
+      Ok((&b";"[..], vec![&b"1"[..], &b"2x"[..], &b"3"[..]]))
+    );
+    assert_eq!(
+      re_matches::<_, _, Error<_>>(&bytes).parse(&b"1 2x 3"[..]),
+      Err(Err::Incomplete(Needed::Unknown))
+    );
+
+    // the parsers compose with the others
+    let mut words = preceded(re_find::<_, _, Error<_>>(&re), re_matches(&re));
+    assert_eq!(words.parse_complete("a b c"), Ok(("", vec!["b", "c"])));
+  }
+}
@@ -0,0 +1,417 @@
+//! Primitives shared by the IETF grammars
+//!
+//! HTTP, MIME and many other protocols build their headers from the same rules, defined
+//! in section 5.6 of [RFC 9110](https://www.rfc-editor.org/rfc/rfc9110#section-5.6)
+//! (formerly section 3.2.6 and 7 of RFC 7230): tokens, quoted strings, optional
+//! whitespace, comma separated lists and `name=value` parameters.
+//!
+//! The parsers take the header value, as a `&str`, and borrow it whenever they can.
+//!
+//! ```rust
+//! # use nom::{error::Error, Parser};
+//! use nom::rfc::{list1, parameters, token};
+//! use nom::sequence::pair;
+//! use std::borrow::Cow;
+//!
+//! // Accept: text/html;level=1, , text/plain;charset="utf-8"
+//! let (_, media_types) = list1::<Error<_>, _>(pair(
+//!   (token(), nom::character::char('/'), token()),
+//!   parameters(),
+//! ))
+//! .parse_complete("text/html;level=1, , text/plain;charset=\"utf-8\"")
+//! .unwrap();
+//!
+//! assert_eq!(media_types.len(), 2);
+//! assert_eq!(media_types[1].0, ("text", '/', "plain"));
+//! assert_eq!(media_types[1].1, [("charset", Cow::Borrowed("utf-8"))]);
+//! ```
+
+use crate::branch::alt;
+use crate::bytes::{escaped_transform_cow, take_while, take_while1, take_while_m_n};
+use crate::character::char;
+use crate::combinator::{map, opt};
+use crate::error::{ErrorKind, ParseError};
+use crate::internal::{Check, Err, Mode, OutputM, OutputMode, PResult, Parser};
+use crate::lib::std::borrow::Cow;
+use crate::lib::std::vec::Vec;
+use crate::multi::fold_many0;
+use crate::sequence::{delimited, preceded, separated_pair};
+
+/// Recognizes a token: one or more `tchar`, which are the visible ASCII characters
+/// except the delimiters `"(),/:;<=>?@[\]{}`
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Parser};
+/// use nom::rfc::token;
+///
+/// assert_eq!(token::<Error<_>>().parse_complete("no-cache, private"), Ok((", private", "no-cache")));
+/// assert_eq!(
+///   token::<Error<_>>().parse_complete("\"quoted\""),
+///   Err(Err::Error(Error::new("\"quoted\"", ErrorKind::TakeWhile1)))
+/// );
+/// ```
+pub fn token<'a, E: ParseError<&'a str>>() -> impl Parser<&'a str, Output = &'a str, Error = E> {
+  take_while1(is_tchar)
+}
+
+/// Recognizes optional whitespace (`OWS`): zero or more spaces and horizontal tabs
+pub fn ows<'a, E: ParseError<&'a str>>() -> impl Parser<&'a str, Output = &'a str, Error = E> {
+  take_while(is_whitespace)
+}
+
+/// Recognizes required whitespace (`RWS`): one or more spaces and horizontal tabs
+pub fn rws<'a, E: ParseError<&'a str>>() -> impl Parser<&'a str, Output = &'a str, Error = E> {
+  take_while1(is_whitespace)
+}
+
+/// Recognizes "bad" whitespace (`BWS`)
+///
+/// It is written where the grammar tolerates whitespace that senders must not produce,
+/// like around the `=` of the `Transfer-Encoding` parameters. It recognizes the same
+/// input as [ows], the different name documents the intent of the grammar.
+pub fn bws<'a, E: ParseError<&'a str>>() -> impl Parser<&'a str, Output = &'a str, Error = E> {
+  ows()
+}
+
+/// Recognizes a quoted string, returning its content without the escaping backslashes
+///
+/// The content borrows the input, unless it contains a `quoted-pair` (a backslash
+/// followed by a character).
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Parser};
+/// use nom::rfc::quoted_string;
+/// use std::borrow::Cow;
+///
+/// assert_eq!(
+///   quoted_string::<Error<_>>().parse_complete("\"attachment\"; x"),
+///   Ok(("; x", Cow::Borrowed("attachment")))
+/// );
+/// assert_eq!(
+///   quoted_string::<Error<_>>().parse_complete(r#""say \"hi\"""#),
+///   Ok(("", Cow::Owned(String::from("say \"hi\""))))
+/// );
+/// // the closing quote is missing
+/// assert_eq!(
+///   quoted_string::<Error<_>>().parse_complete("\"abc"),
+///   Err(Err::Error(Error::new("", ErrorKind::Char)))
+/// );
+/// ```
+pub fn quoted_string<'a, E: ParseError<&'a str>>(
+) -> impl Parser<&'a str, Output = Cow<'a, str>, Error = E> {
+  delimited(
+    char('"'),
+    map(
+      opt(escaped_transform_cow(
+        take_while1(is_qdtext),
+        '\\',
+        take_while_m_n(1, 1, is_quoted_pair),
+      )),
+      |content| content.unwrap_or(Cow::Borrowed("")),
+    ),
+    char('"'),
+  )
+}
+
+/// Recognizes a parameter: a token name, a `=` and a value, which is either a token or a
+/// quoted string
+///
+/// No whitespace is allowed around the `=`.
+///
+/// ```rust
+/// # use nom::{error::Error, Parser};
+/// use nom::rfc::parameter;
+/// use std::borrow::Cow;
+///
+/// assert_eq!(
+///   parameter::<Error<_>>().parse_complete("charset=utf-8"),
+///   Ok(("", ("charset", Cow::Borrowed("utf-8"))))
+/// );
+/// assert_eq!(
+///   parameter::<Error<_>>().parse_complete("title=\"a \\\"b\\\"\""),
+///   Ok(("", ("title", Cow::Owned(String::from("a \"b\"")))))
+/// );
+/// ```
+pub fn parameter<'a, E: ParseError<&'a str>>(
+) -> impl Parser<&'a str, Output = (&'a str, Cow<'a, str>), Error = E> {
+  separated_pair(
+    token(),
+    char('='),
+    alt((quoted_string(), map(token(), Cow::Borrowed))),
+  )
+}
+
+/// Recognizes the parameters following a value, as in `text/plain; charset=utf-8`
+///
+/// Each parameter is preceded by optional whitespace, a `;` and optional whitespace, and
+/// can be empty: empty parameters are skipped.
+///
+/// ```rust
+/// # use nom::{error::Error, Parser};
+/// use nom::rfc::parameters;
+/// use std::borrow::Cow;
+///
+/// assert_eq!(
+///   parameters::<Error<_>>().parse_complete(" ; q=0.5;; level=1, next"),
+///   Ok((", next", vec![("q", Cow::Borrowed("0.5")), ("level", Cow::Borrowed("1"))]))
+/// );
+/// ```
+pub fn parameters<'a, E: ParseError<&'a str>>(
+) -> impl Parser<&'a str, Output = Vec<(&'a str, Cow<'a, str>)>, Error = E> {
+  fold_many0(
+    preceded((ows(), char(';'), ows()), opt(parameter())),
+    Vec::new,
+    |mut acc: Vec<_>, parameter| {
+      acc.extend(parameter);
+      acc
+    },
+  )
+}
+
+/// Recognizes a comma separated list (the `#rule`), which can be empty
+///
+/// The elements are separated by a comma, with optional whitespace around it. As
+/// recipients must do, empty elements are accepted and skipped: `", , a ,, b,"` is a
+/// list of two elements. The parser stops after the last element, or after the last
+/// comma if it is not followed by an element.
+///
+/// An error from `element` is treated as an empty element, a failure is returned.
+///
+/// ```rust
+/// # use nom::{error::Error, Parser};
+/// use nom::rfc::{list0, token};
+///
+/// let mut parser = list0::<Error<_>, _>(token());
+/// assert_eq!(parser.parse_complete(", gzip ,, br ;"), Ok((" ;", vec!["gzip", "br"])));
+/// assert_eq!(parser.parse_complete(";"), Ok((";", vec![])));
+/// ```
+pub fn list0<'a, E, F>(element: F) -> impl Parser<&'a str, Output = Vec<F::Output>, Error = E>
+where
+  E: ParseError<&'a str>,
+  F: Parser<&'a str, Error = E>,
+{
+  List {
+    element,
+    separator: (ows(), char(',')),
+    non_empty: false,
+  }
+}
+
+/// Recognizes a comma separated list (the `1#rule`) with at least one element
+///
+/// This works like [list0], but returns an error with [ErrorKind::SeparatedNonEmptyList]
+/// if the list has no element, even if it has empty ones.
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Parser};
+/// use nom::rfc::{list1, token};
+///
+/// let mut parser = list1::<Error<_>, _>(token());
+/// assert_eq!(parser.parse_complete("chunked"), Ok(("", vec!["chunked"])));
+/// assert_eq!(
+///   parser.parse_complete(" , ,"),
+///   Err(Err::Error(Error::new(" , ,", ErrorKind::SeparatedNonEmptyList)))
+/// );
+/// ```
+pub fn list1<'a, E, F>(element: F) -> impl Parser<&'a str, Output = Vec<F::Output>, Error = E>
+where
+  E: ParseError<&'a str>,
+  F: Parser<&'a str, Error = E>,
+{
+  List {
+    element,
+    separator: (ows(), char(',')),
+    non_empty: true,
+  }
+}
+
+/// Parser implementation for [list0] and [list1]
+struct List<F, S> {
+  element: F,
+  separator: S,
+  non_empty: bool,
+}
+
+impl<'a, E, F, S> Parser<&'a str> for List<F, S>
+where
+  E: ParseError<&'a str>,
+  F: Parser<&'a str, Error = E>,
+  S: Parser<&'a str, Error = E>,
+{
+  type Output = Vec<F::Output>;
+  type Error = E;
+
+  fn process<OM: OutputMode>(
+    &mut self,
+    input: &'a str,
+  ) -> PResult<OM, &'a str, Self::Output, Self::Error> {
+    let mut res = OM::Output::bind(Vec::new);
+    let mut empty = true;
+    let mut i = input;
+
+    loop {
+      // the element is optional, and can be preceded by whitespace
+      let (after_ows, _) = ows::<E>().process::<OutputM<Check, OM::Error, OM::Incomplete>>(i)?;
+      match self.element.process::<OM>(after_ows) {
+        Ok((rest, o)) => {
+          res = OM::Output::combine(res, o, |mut res, o| {
+            res.push(o);
+            res
+          });
+          empty = false;
+          i = rest;
+        }
+        Err(Err::Error(_)) => {}
+        Err(e) => return Err(e),
+      }
+
+      match self
+        .separator
+        .process::<OutputM<Check, OM::Error, OM::Incomplete>>(i)
+      {
+        Ok((rest, _)) => i = rest,
+        Err(Err::Error(_)) => break,
+        Err(e) => return Err(e),
+      }
+    }
+
+    if self.non_empty && empty {
+      return Err(Err::Error(OM::Error::bind(|| {
+        E::from_error_kind(input, ErrorKind::SeparatedNonEmptyList)
+      })));
+    }
+    Ok((i, res))
+  }
+}
+
+fn is_tchar(c: char) -> bool {
+  c.is_ascii_graphic() && !"\"(),/:;<=>?@[\\]{}".contains(c)
+}
+
+fn is_whitespace(c: char) -> bool {
+  c == ' ' || c == '\t'
+}
+
+fn is_obs_text(c: char) -> bool {
+  !c.is_ascii()
+}
+
+fn is_qdtext(c: char) -> bool {
+  is_whitespace(c) || (c.is_ascii_graphic() && c != '"' && c != '\\') || is_obs_text(c)
+}
+
+fn is_quoted_pair(c: char) -> bool {
+  is_whitespace(c) || c.is_ascii_graphic() || is_obs_text(c)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::error::Error;
+  use crate::Needed;
+
+  #[test]
+  fn tokens_and_whitespace() {
+    assert_eq!(
+      token::<Error<_>>().parse_complete("max-age=5"),
+      Ok(("=5", "max-age"))
+    );
+    assert_eq!(
+      token::<Error<_>>().parse_complete("!#$%&'*+-.^_`|~09azAZ"),
+      Ok(("", "!#$%&'*+-.^_`|~09azAZ"))
+    );
+    assert_eq!(
+      token::<Error<_>>().parse("abc"),
+      Err(Err::Incomplete(Needed::new(1)))
+    );
+    assert_eq!(ows::<Error<_>>().parse_complete(" \t x"), Ok(("x", " \t ")));
+    assert_eq!(ows::<Error<_>>().parse_complete("x"), Ok(("x", "")));
+    assert_eq!(
+      rws::<Error<_>>().parse_complete("x"),
+      Err(Err::Error(Error::new("x", ErrorKind::TakeWhile1)))
+    );
+  }
+
+  #[test]
+  fn quoted_strings() {
+    let mut parser = quoted_string::<Error<_>>();
+    assert_eq!(parser.parse_complete("\"\""), Ok(("", Cow::Borrowed(""))));
+    assert_eq!(
+      parser.parse_complete("\"a\tb c\"x"),
+      Ok(("x", Cow::Borrowed("a\tb c")))
+    );
+    assert_eq!(
+      parser.parse_complete("\"caf\u{e9}\""),
+      Ok(("", Cow::Borrowed("caf\u{e9}")))
+    );
+    assert_eq!(
+      parser.parse_complete(r#""a\\b\c""#),
+      Ok(("", Cow::Owned("a\\bc".into())))
+    );
+    // control characters are not allowed, even escaped
+    assert_eq!(
+      parser.parse_complete("\"a\nb\""),
+      Err(Err::Error(Error::new("\nb\"", ErrorKind::Char)))
+    );
+    assert!(parser.parse_complete("\"a\\\nb\"").is_err());
+    assert_eq!(parser.parse("\"abc"), Err(Err::Incomplete(Needed::new(1))));
+  }
+
+  #[test]
+  fn lists() {
+    let mut parser = list0::<Error<_>, _>(parameter());
+    assert_eq!(parser.parse_complete(""), Ok(("", vec![])));
+    assert_eq!(parser.parse_complete(" , ,"), Ok(("", vec![])));
+    assert_eq!(
+      parser.parse_complete("a=1 ,b=\"x, y\",, c=3 x"),
+      Ok((
+        " x",
+        vec![
+          ("a", Cow::Borrowed("1")),
+          ("b", Cow::Borrowed("x, y")),
+          ("c", Cow::Borrowed("3"))
+        ]
+      ))
+    );
+    // an invalid element ends the list after the last comma
+    assert_eq!(
+      parser.parse_complete("a=1, =2"),
+      Ok((" =2", vec![("a", Cow::Borrowed("1"))]))
+    );
+
+    let mut parser = list1::<Error<_>, _>(token());
+    assert_eq!(parser.parse_complete(",a"), Ok(("", vec!["a"])));
+    assert_eq!(
+      parser.parse_complete(""),
+      Err(Err::Error(Error::new("", ErrorKind::SeparatedNonEmptyList)))
+    );
+    // in streaming mode, the list could continue
+    assert_eq!(parser.parse("a, b"), Err(Err::Incomplete(Needed::new(1))));
+    assert_eq!(parser.parse("a, b;"), Ok((";", vec!["a", "b"])));
+  }
+
+  #[test]
+  fn media_type_parameters() {
+    assert_eq!(
+      (token(), char('/'), token(), parameters::<Error<_>>())
+        .parse_complete("text/html ; charset=\"utf-8\" ;;format=flowed"),
+      Ok((
+        "",
+        (
+          "text",
+          '/',
+          "html",
+          vec![
+            ("charset", Cow::Borrowed("utf-8")),
+            ("format", Cow::Borrowed("flowed"))
+          ]
+        )
+      ))
+    );
+    // no whitespace around the `=`
+    assert_eq!(
+      parameters::<Error<_>>().parse_complete("; a = b"),
+      Ok(("a = b", vec![]))
+    );
+  }
+}
@@ -3,9 +3,11 @@
 #[cfg(test)]
 mod tests;
 
-use crate::error::ParseError;
-use crate::internal::{IResult, Parser};
-use crate::{Check, OutputM, OutputMode, PResult};
+use crate::combinator::DepthLimit;
+use crate::error::{ContextError, ErrorKind, ParseError};
+use crate::internal::{Err, IResult, Parser};
+use crate::lib::std::fmt;
+use crate::{Check, Mode, OutputM, OutputMode, PResult};
 
 /// Gets an object from the first parser,
 /// then gets another object from the second parser.
@@ -74,8 +76,8 @@ where
 
 /// a
 pub struct Preceded<F, G> {
-  f: F,
-  g: G,
+  pub(crate) f: F,
+  pub(crate) g: G,
 }
 
 impl<I, E: ParseError<I>, F: Parser<I, Error = E>, G: Parser<I, Error = E>> Parser<I>
@@ -131,8 +133,8 @@ where
 
 /// a
 pub struct Terminated<F, G> {
-  f: F,
-  g: G,
+  pub(crate) f: F,
+  pub(crate) g: G,
 }
 
 impl<I, E: ParseError<I>, F: Parser<I, Error = E>, G: Parser<I, Error = E>> Parser<I>
@@ -222,9 +224,224 @@ where
   preceded(first, terminated(second, third))
 }
 
+/// Like [delimited], but counts one level of nesting in a [DepthLimit] while parsing
+/// the content and the closing delimiter.
+///
+/// The level is entered after `first` matched, so at the maximum depth, input that does
+/// not start with the opening delimiter still returns a recoverable error. If the
+/// maximum depth is exceeded, this returns a failure with
+/// [ErrorKind::Depth](crate::error::ErrorKind::Depth).
+///
+/// # Arguments
+/// * `limit` The nesting depth limit shared by the recursive parsers.
+/// * `first` The first parser to apply and discard.
+/// * `second` The second parser to apply.
+/// * `third` The third parser to apply and discard.
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+/// use nom::branch::alt;
+/// use nom::character::complete::{char, digit1};
+/// use nom::combinator::DepthLimit;
+/// use nom::sequence::delimited_with_depth;
+///
+/// fn value<'a>(limit: &DepthLimit, i: &'a str) -> IResult<&'a str, &'a str> {
+///   alt((
+///     delimited_with_depth(limit, char('('), |i| value(limit, i), char(')')),
+///     digit1,
+///   ))
+///   .parse(i)
+/// }
+///
+/// let limit = DepthLimit::new(2);
+/// assert_eq!(value(&limit, "((1))"), Ok(("", "1")));
+/// assert_eq!(value(&limit, "(((1)))"), Err(Err::Failure(Error::new("1)))", ErrorKind::Depth))));
+/// ```
+pub fn delimited_with_depth<'d, I, O, E: ParseError<I>, F, G, H>(
+  limit: &'d DepthLimit,
+  first: F,
+  second: G,
+  third: H,
+) -> impl Parser<I, Output = O, Error = E> + 'd
+where
+  F: Parser<I, Error = E> + 'd,
+  G: Parser<I, Output = O, Error = E> + 'd,
+  H: Parser<I, Error = E> + 'd,
+{
+  Preceded {
+    f: first,
+    g: limit.nest(Terminated {
+      f: second,
+      g: third,
+    }),
+  }
+}
+
+/// Like [delimited], but reports a missing closing delimiter with the position of the
+/// opening one.
+///
+/// If `close` fails after `open` and `inner` matched, the error has the kind
+/// [ErrorKind::Unclosed] at the position where the closing delimiter was expected, and the
+/// context `delimiter opened here` at the position of the opening delimiter, added with
+/// [ContextError::add_lazy_context]. If `open` fails where `close` matches, the error has
+/// the kind [ErrorKind::Unopened]. See [labeled_delimited] to name the delimiter.
+///
+/// # Arguments
+/// * `open` The opening delimiter.
+/// * `inner` The parser to apply between the delimiters.
+/// * `close` The closing delimiter.
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+/// use nom::character::complete::{char, digit1};
+/// use nom::sequence::balanced;
+///
+/// fn group(i: &str) -> IResult<&str, &str> {
+///   balanced(char('('), digit1, char(')')).parse(i)
+/// }
+///
+/// assert_eq!(group("(12)"), Ok(("", "12")));
+/// assert_eq!(group("(12;"), Err(Err::Error(Error::new(";", ErrorKind::Unclosed))));
+/// assert_eq!(group(")12"), Err(Err::Error(Error::new(")12", ErrorKind::Unopened))));
+/// assert_eq!(group("12"), Err(Err::Error(Error::new("12", ErrorKind::Char))));
+/// ```
+pub fn balanced<I, O, E, F, G, H>(
+  open: F,
+  inner: G,
+  close: H,
+) -> impl Parser<I, Output = O, Error = E>
+where
+  I: Clone,
+  E: ParseError<I> + ContextError<I>,
+  F: Parser<I, Error = E>,
+  G: Parser<I, Output = O, Error = E>,
+  H: Parser<I, Error = E>,
+{
+  labeled_delimited("delimiter", open, inner, close)
+}
+
+/// Like [balanced], with `label` naming the delimiter in the context added at the
+/// opening position, as in `parenthesis opened here`.
+///
+/// # Arguments
+/// * `label` The name of the delimiter.
+/// * `open` The opening delimiter.
+/// * `inner` The parser to apply between the delimiters.
+/// * `close` The closing delimiter.
+///
+/// ```rust
+/// # use nom::{Err, error::ErrorKind, IResult, Parser};
+/// use nom::character::complete::{char, digit1};
+/// use nom::sequence::labeled_delimited;
+/// use nom_language::error::{VerboseError, VerboseErrorKind};
+///
+/// fn group(i: &str) -> IResult<&str, &str, VerboseError<&str>> {
+///   labeled_delimited("parenthesis", char('('), digit1, char(')')).parse(i)
+/// }
+///
+/// assert_eq!(
+///   group("(12;"),
+///   Err(Err::Error(VerboseError {
+///     errors: vec![
+///       (";", VerboseErrorKind::Nom(ErrorKind::Unclosed)),
+///       ("(12;", VerboseErrorKind::Label("parenthesis opened here".to_string())),
+///     ]
+///   }))
+/// );
+/// ```
+pub fn labeled_delimited<I, O, E, F, G, H>(
+  label: &'static str,
+  open: F,
+  inner: G,
+  close: H,
+) -> impl Parser<I, Output = O, Error = E>
+where
+  I: Clone,
+  E: ParseError<I> + ContextError<I>,
+  F: Parser<I, Error = E>,
+  G: Parser<I, Output = O, Error = E>,
+  H: Parser<I, Error = E>,
+{
+  Balanced {
+    label,
+    open,
+    inner,
+    close,
+  }
+}
+
+/// Parser implementation for [balanced] and [labeled_delimited]
+pub struct Balanced<F, G, H> {
+  label: &'static str,
+  open: F,
+  inner: G,
+  close: H,
+}
+
+/// Context added at the position of the opening delimiter, formatted on demand
+struct Opened(&'static str);
+
+impl fmt::Display for Opened {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{} opened here", self.0)
+  }
+}
+
+impl<I, E, F, G, H> Parser<I> for Balanced<F, G, H>
+where
+  I: Clone,
+  E: ParseError<I> + ContextError<I>,
+  F: Parser<I, Error = E>,
+  G: Parser<I, Error = E>,
+  H: Parser<I, Error = E>,
+{
+  type Output = <G as Parser<I>>::Output;
+  type Error = E;
+
+  fn process<OM: OutputMode>(&mut self, input: I) -> PResult<OM, I, Self::Output, Self::Error> {
+    let i = match self
+      .open
+      .process::<OutputM<Check, OM::Error, OM::Incomplete>>(input.clone())
+    {
+      Ok((i, _)) => i,
+      Err(Err::Error(e)) => {
+        // a closing delimiter where an opening one was expected
+        let unopened = self
+          .close
+          .process::<OutputM<Check, Check, OM::Incomplete>>(input.clone())
+          .is_ok();
+        return Err(Err::Error(if unopened {
+          OM::Error::bind(|| E::from_error_kind(input, ErrorKind::Unopened))
+        } else {
+          e
+        }));
+      }
+      Err(e) => return Err(e),
+    };
+
+    let (i, o) = self.inner.process::<OM>(i)?;
+    match self
+      .close
+      .process::<OutputM<Check, OM::Error, OM::Incomplete>>(i.clone())
+    {
+      Ok((i, _)) => Ok((i, o)),
+      Err(Err::Error(_)) => Err(Err::Error(OM::Error::bind(|| {
+        let e = E::from_error_kind(i, ErrorKind::Unclosed);
+        E::add_lazy_context(input, &Opened(self.label), e)
+      }))),
+      Err(Err::Failure(e)) => Err(Err::Failure(E::add_lazy_context(
+        input,
+        &Opened(self.label),
+        e,
+      ))),
+      Err(Err::Incomplete(n)) => Err(Err::Incomplete(n)),
+    }
+  }
+}
+
 /// Helper trait for the tuple combinator.
 ///
-/// This trait is implemented for tuples of parsers of up to 21 elements.
+/// This trait is implemented for tuples of parsers of up to 64 elements.
 #[deprecated(since = "8.0.0", note = "`Parser` is directly implemented for tuples")]
 #[allow(deprecated)]
 pub trait Tuple<I, O, E> {
@@ -288,8 +505,16 @@ macro_rules! tuple_trait_inner(
   });
 );
 
-tuple_trait!(FnA A, FnB B, FnC C, FnD D, FnE E, FnF F, FnG G, FnH H, FnI I, FnJ J, FnK K, FnL L,
-  FnM M, FnN N, FnO O, FnP P, FnQ Q, FnR R, FnS S, FnT T, FnU U);
+tuple_trait!(
+  FnA A, FnB B, FnC C, FnD D, FnE E, FnF F, FnG G, FnH H,
+  FnI I, FnJ J, FnK K, FnL L, FnM M, FnN N, FnO O, FnP P,
+  FnQ Q, FnR R, FnS S, FnT T, FnU U, FnV V, FnW W, FnX X,
+  FnY Y, FnZ Z, FnA2 A2, FnB2 B2, FnC2 C2, FnD2 D2, FnE2 E2, FnF2 F2,
+  FnG2 G2, FnH2 H2, FnI2 I2, FnJ2 J2, FnK2 K2, FnL2 L2, FnM2 M2, FnN2 N2,
+  FnO2 O2, FnP2 P2, FnQ2 Q2, FnR2 R2, FnS2 S2, FnT2 T2, FnU2 U2, FnV2 V2,
+  FnW2 W2, FnX2 X2, FnY2 Y2, FnZ2 Z2, FnA3 A3, FnB3 B3, FnC3 C3, FnD3 D3,
+  FnE3 E3, FnF3 F3, FnG3 G3, FnH3 H3, FnI3 I3, FnJ3 J3, FnK3 K3, FnL3 L3
+);
 
 // Special case: implement `Tuple` for `()`, the unit type.
 // This can come up in macros which accept a variable number of arguments.
@@ -302,7 +527,7 @@ impl<I, E: ParseError<I>> Tuple<I, (), E> for () {
 }
 
 ///Applies a tuple of parsers one by one and returns their results as a tuple.
-///There is a maximum of 21 parsers
+///There is a maximum of 64 parsers
 /// ```rust
 /// # use nom::{Err, error::ErrorKind};
 /// use nom::sequence::tuple;
@@ -319,3 +544,95 @@ pub fn tuple<I, O, E: ParseError<I>, List: Tuple<I, O, E>>(
 ) -> impl FnMut(I) -> IResult<I, O, E> {
   move |i: I| l.parse_tuple(i)
 }
+
+/// Applies parsers in sequence and builds a struct or a tuple from their outputs.
+///
+/// Instead of destructuring a long tuple, each parser is written next to the field it
+/// fills, with the `Name { field: parser, ... }` syntax for structs, `Name(parser, ...)`
+/// for tuple structs, and `((parser, ...))` for tuples. The output of a parser written
+/// as `_: parser` is discarded. In a struct, the parsers can refer to the fields parsed
+/// before them, like a length used to read the following data.
+///
+/// The parsers are created on each call, and applied with [Parser::parse](crate::Parser::parse).
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+/// use nom::seq;
+/// use nom::bytes::complete::{tag, take};
+/// use nom::number::complete::{le_u16, u8};
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Header<'a> {
+///   version: u16,
+///   len: u8,
+///   name: &'a [u8],
+/// }
+///
+/// fn header(input: &[u8]) -> IResult<&[u8], Header<'_>> {
+///   seq!(Header {
+///     _: tag("MZ"),
+///     version: le_u16,
+///     _: take(2u8),
+///     len: u8,
+///     name: take(len),
+///   })
+///   .parse(input)
+/// }
+///
+/// fn pair(input: &[u8]) -> IResult<&[u8], (u8, u8)> {
+///   seq!((u8, _: tag(","), u8)).parse(input)
+/// }
+///
+/// assert_eq!(
+///   header(&b"MZ\x02\x00..\x03abcd"[..]),
+///   Ok((&b"d"[..], Header { version: 2, len: 3, name: &b"abc"[..] }))
+/// );
+/// assert_eq!(
+///   header(&b"PE\x02\x00"[..]),
+///   Err(Err::Error(Error::new(&b"PE\x02\x00"[..], ErrorKind::Tag)))
+/// );
+/// assert_eq!(pair(&b"\x01,\x02"[..]), Ok((&b""[..], (1, 2))));
+/// ```
+#[macro_export]
+macro_rules! seq (
+  (@step $input:ident; $parser:expr) => (
+    match $crate::Parser::parse(&mut $parser, $input) {
+      $crate::lib::std::result::Result::Ok(res) => res,
+      $crate::lib::std::result::Result::Err(e) => return $crate::lib::std::result::Result::Err(e),
+    }
+  );
+
+  (@struct $input:ident; [$($name:tt)*]; [$($done:ident)*]; _ : $parser:expr $(, $($rest:tt)*)?) => ({
+    let ($input, _) = $crate::seq!(@step $input; $parser);
+    $crate::seq!(@struct $input; [$($name)*]; [$($done)*]; $($($rest)*)?)
+  });
+  (@struct $input:ident; [$($name:tt)*]; [$($done:ident)*]; $field:ident : $parser:expr $(, $($rest:tt)*)?) => ({
+    let ($input, $field) = $crate::seq!(@step $input; $parser);
+    $crate::seq!(@struct $input; [$($name)*]; [$($done)* $field]; $($($rest)*)?)
+  });
+  (@struct $input:ident; [$($name:tt)*]; [$($done:ident)*];) => (
+    $crate::lib::std::result::Result::Ok(($input, $($name)* { $($done),* }))
+  );
+
+  (@tuple $input:ident; [$($name:tt)*]; [$($done:ident)*]; _ : $parser:expr $(, $($rest:tt)*)?) => ({
+    let ($input, _) = $crate::seq!(@step $input; $parser);
+    $crate::seq!(@tuple $input; [$($name)*]; [$($done)*]; $($($rest)*)?)
+  });
+  (@tuple $input:ident; [$($name:tt)*]; [$($done:ident)*]; $parser:expr $(, $($rest:tt)*)?) => ({
+    let ($input, output) = $crate::seq!(@step $input; $parser);
+    $crate::seq!(@tuple $input; [$($name)*]; [$($done)* output]; $($($rest)*)?)
+  });
+  (@tuple $input:ident; [$($name:tt)*]; [$($done:ident)*];) => (
+    $crate::lib::std::result::Result::Ok(($input, $($name)* ($($done,)*)))
+  );
+
+  (($($elements:tt)*)) => ({
+    move |input| $crate::seq!(@tuple input; []; []; $($elements)*)
+  });
+  ($($name:ident)::+ { $($fields:tt)* }) => ({
+    move |input| $crate::seq!(@struct input; [$($name)::+]; []; $($fields)*)
+  });
+  ($($name:ident)::+ ( $($elements:tt)* )) => ({
+    move |input| $crate::seq!(@tuple input; [$($name)::+]; []; $($elements)*)
+  });
+);
@@ -265,6 +265,27 @@ fn tuple_test() {
   );
 }
 
+#[test]
+fn high_arity_tuple_test() {
+  use crate::character::complete::anychar;
+
+  macro_rules! anychars (
+    ($($n:literal)*) => (($({ let _ = $n; anychar::<_, Error<&str>> },)*));
+  );
+
+  let mut parser = anychars!(
+    0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28 29 30 31
+    32 33 34 35 36 37 38 39 40 41 42 43 44 45 46 47 48 49 50 51 52 53 54 55 56 57 58 59 60 61 62 63
+  );
+  let input = "0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ-_!";
+  let (rest, res) = crate::Parser::parse(&mut parser, input).unwrap();
+  assert_eq!((rest, res.0, res.36, res.63), ("!", '0', 'A', '_'));
+  assert_eq!(
+    crate::Parser::parse(&mut parser, "0123").err(),
+    Some(Err::Error(error_position!("", ErrorKind::Eof)))
+  );
+}
+
 #[test]
 #[allow(deprecated)]
 fn unit_type() {
@@ -281,3 +302,156 @@ fn unit_type() {
     Ok(("", ()))
   );
 }
+
+#[test]
+fn seq_test() {
+  use crate::character::complete::{alpha1, digit1};
+
+  mod shapes {
+    #[derive(Debug, PartialEq)]
+    pub struct Point(pub u16, pub u16);
+
+    #[derive(Debug, PartialEq)]
+    pub struct Named<'a> {
+      pub name: &'a [u8],
+      pub point: Point,
+    }
+  }
+
+  fn point(i: &[u8]) -> IResult<&[u8], shapes::Point> {
+    crate::seq!(shapes::Point(be_u16, _: tag(","), be_u16)).parse(i)
+  }
+
+  fn named(i: &[u8]) -> IResult<&[u8], shapes::Named<'_>> {
+    crate::seq!(shapes::Named {
+      name: take(2u8),
+      _: tag(":"),
+      point: point,
+    })
+    .parse(i)
+  }
+
+  assert_eq!(
+    named(&b"ab:\x00\x01,\x00\x02;"[..]),
+    Ok((
+      &b";"[..],
+      shapes::Named {
+        name: &b"ab"[..],
+        point: shapes::Point(1, 2)
+      }
+    ))
+  );
+  assert_eq!(
+    named(&b"ab:\x00\x01,\x00"[..]),
+    Err(Err::Incomplete(Needed::new(1)))
+  );
+  assert_eq!(
+    point(&b"\x00\x01;\x00\x02"[..]),
+    Err(Err::Error(error_position!(
+      &b";\x00\x02"[..],
+      ErrorKind::Tag
+    )))
+  );
+
+  let mut single = crate::seq!((_: alpha1::<_, Error<_>>, digit1));
+  assert_eq!(single.parse("abc123;"), Ok((";", ("123",))));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn balanced_test() {
+  use crate::branch::alt;
+  use crate::character::complete::{char, digit1};
+  use crate::combinator::cut;
+  use crate::error::ContextError;
+  use crate::lib::std::fmt;
+  use crate::lib::std::string::{String, ToString};
+  use crate::lib::std::vec::Vec;
+
+  #[derive(Debug, PartialEq)]
+  struct Labels<'a> {
+    input: &'a str,
+    kind: ErrorKind,
+    labels: Vec<(&'a str, String)>,
+  }
+
+  impl<'a> ParseError<&'a str> for Labels<'a> {
+    fn from_error_kind(input: &'a str, kind: ErrorKind) -> Self {
+      Labels {
+        input,
+        kind,
+        labels: Vec::new(),
+      }
+    }
+
+    fn append(_: &'a str, _: ErrorKind, other: Self) -> Self {
+      other
+    }
+  }
+
+  impl<'a> ContextError<&'a str> for Labels<'a> {
+    fn add_lazy_context(input: &'a str, ctx: &dyn fmt::Display, mut other: Self) -> Self {
+      other.labels.push((input, ctx.to_string()));
+      other
+    }
+  }
+
+  fn paren(i: &str) -> IResult<&str, &str, Labels<'_>> {
+    labeled_delimited("parenthesis", char('('), value, char(')')).parse(i)
+  }
+
+  fn value(i: &str) -> IResult<&str, &str, Labels<'_>> {
+    alt((paren, balanced(char('['), value, cut(char(']'))), digit1)).parse(i)
+  }
+
+  assert_eq!(paren("([(1)])"), Ok(("", "1")));
+
+  // the unclosed delimiter is reported with its opening position
+  assert_eq!(
+    paren("([1];"),
+    Err(Err::Error(Labels {
+      input: ";",
+      kind: ErrorKind::Unclosed,
+      labels: vec![("([1];", "parenthesis opened here".to_string())],
+    }))
+  );
+
+  // failures of the closing delimiter are kept, with the context
+  assert_eq!(
+    paren("([1)"),
+    Err(Err::Failure(Labels {
+      input: ")",
+      kind: ErrorKind::Char,
+      labels: vec![("[1)", "delimiter opened here".to_string())],
+    }))
+  );
+
+  assert_eq!(
+    paren(")"),
+    Err(Err::Error(Labels {
+      input: ")",
+      kind: ErrorKind::Unopened,
+      labels: vec![],
+    }))
+  );
+  assert_eq!(
+    paren("]"),
+    Err(Err::Error(Labels {
+      input: "]",
+      kind: ErrorKind::Char,
+      labels: vec![],
+    }))
+  );
+
+  // in streaming mode, a missing closing delimiter at the end of the input is incomplete
+  let mut group = balanced(
+    crate::bytes::tag::<_, _, Error<_>>("("),
+    crate::bytes::take(2usize),
+    crate::bytes::tag(")"),
+  );
+  assert_eq!(group.parse("(12"), Err(Err::Incomplete(Needed::new(1))));
+  assert_eq!(
+    group.parse_complete("(12"),
+    Err(Err::Error(Error::new("", ErrorKind::Unclosed)))
+  );
+}
@@ -0,0 +1,818 @@
+//! Driver feeding parsers from a [Read] source
+//!
+//! [Stream] reads data into a buffer and applies a parser in streaming mode on it,
+//! reading more when the parser returns [Err::Incomplete]. The bytes consumed by each
+//! successful parse are dropped from the buffer, so only the item being parsed is held
+//! in memory, whatever the size of the source. Once the source is exhausted, the parser
+//! is applied in complete mode on the remaining data, with [Parser::parse_complete].
+//! Parsers that still return [Err::Incomplete], like functions calling
+//! [Parser::parse], produce a [StreamError::Truncated] error for a partial last item.
+//!
+//! The parser must be usable on buffers of any lifetime, like a function, and its
+//! output cannot borrow the buffer. The input positions of errors are converted with
+//! [MapInput] to offsets from the start of the stream.
+//!
+//! With the `flate2` and `zstd` features, [Stream::gzip], [Stream::zlib],
+//! [Stream::deflate] and [Stream::zstd] decompress the source on the fly, so that
+//! compressed logs and archives can be parsed without decompressing them first.
+//!
+//! With the `futures` feature, [from_async_reader] drives a parser in the same way from an
+//! asynchronous source, as a `Stream` of the parsed items.
+//!
+//! ```rust
+//! # use nom::{IResult, Parser};
+//! use nom::bytes::{tag, take_until};
+//! use nom::sequence::terminated;
+//! use nom::stream::Stream;
+//!
+//! fn line(input: &[u8]) -> IResult<&[u8], String> {
+//!   terminated(take_until("\n"), tag("\n"))
+//!     .map(|line| String::from_utf8_lossy(line).into_owned())
+//!     .parse(input)
+//! }
+//!
+//! let log = &b"GET /index.html\nGET /favicon.ico\n"[..];
+//! let mut stream = Stream::new(log).read_size(4);
+//! assert_eq!(stream.parse_next(line).unwrap(), Some("GET /index.html".to_string()));
+//! assert_eq!(stream.parse_next(line).unwrap(), Some("GET /favicon.ico".to_string()));
+//! assert_eq!(stream.parse_next(line).unwrap(), None);
+//! assert_eq!(stream.offset(), log.len());
+//! ```
+
+use crate::error::MapInput;
+use crate::internal::{Err, Needed, Parser};
+use crate::lib::std::fmt;
+use crate::lib::std::vec::Vec;
+use crate::traits::Offset;
+use std::io::{self, Read};
+
+#[cfg(feature = "futures")]
+use core::marker::PhantomData;
+#[cfg(feature = "futures")]
+use core::pin::Pin;
+#[cfg(feature = "futures")]
+use core::task::{Context, Poll};
+#[cfg(feature = "futures")]
+use futures_io::AsyncRead;
+
+/// Default number of bytes requested from the source by each read
+const DEFAULT_READ_SIZE: usize = 8 * 1024;
+
+/// Error returned by [Stream::parse_next]
+#[derive(Debug)]
+pub enum StreamError<E> {
+  /// Reading from the source failed
+  Io(io::Error),
+  /// The parser failed, with input positions converted to offsets in the stream
+  Parse(E),
+  /// The source ended in the middle of an item, reported for parsers that return
+  /// [Err::Incomplete] even in complete mode
+  Truncated {
+    /// Offset in the stream of the start of the item
+    offset: usize,
+  },
+  /// The parser needed more data than the limit set with [Stream::limit]
+  TooLarge {
+    /// Maximum number of bytes held in the buffer
+    limit: usize,
+  },
+  /// The parser kept returning [Err::Incomplete] after receiving the data it asked for,
+  /// more times than allowed by [Stream::max_stalls]
+  Stalled {
+    /// Offset in the stream of the start of the item
+    offset: usize,
+    /// Number of times the parser did not progress with the data it asked for
+    stalls: usize,
+  },
+}
+
+impl<E> From<io::Error> for StreamError<E> {
+  fn from(e: io::Error) -> Self {
+    StreamError::Io(e)
+  }
+}
+
+impl<E: fmt::Display> fmt::Display for StreamError<E> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      StreamError::Io(e) => write!(f, "read error: {}", e),
+      StreamError::Parse(e) => write!(f, "parse error: {}", e),
+      StreamError::Truncated { offset } => write!(f, "truncated item at offset {}", offset),
+      StreamError::TooLarge { limit } => {
+        write!(f, "item larger than the buffer limit of {} bytes", limit)
+      }
+      StreamError::Stalled { offset, stalls } => write!(
+        f,
+        "parser stalled {} times on the item at offset {}",
+        stalls, offset
+      ),
+    }
+  }
+}
+
+impl<E: fmt::Debug + fmt::Display> std::error::Error for StreamError<E> {
+  fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+    match self {
+      StreamError::Io(e) => Some(e),
+      _ => None,
+    }
+  }
+}
+
+/// Applies parsers on the data read from a [Read] source, see the [module](self)
+/// documentation
+pub struct Stream<R> {
+  reader: R,
+  buffer: Buffer,
+}
+
+impl<R: Read> Stream<R> {
+  /// Creates a driver reading from `reader`
+  pub fn new(reader: R) -> Self {
+    Stream {
+      reader,
+      buffer: Buffer::new(),
+    }
+  }
+
+  /// Sets the number of bytes requested from the source by each read, 8 KiB by default
+  pub fn read_size(mut self, read_size: usize) -> Self {
+    self.buffer.read_size = read_size.max(1);
+    self
+  }
+
+  /// Sets the maximum number of bytes held in the buffer, to bound the memory used by
+  /// an item that never ends
+  pub fn limit(mut self, limit: usize) -> Self {
+    self.buffer.limit = limit;
+    self
+  }
+
+  /// Sets how many times the parser can return [Err::Incomplete] for the same item after
+  /// receiving the data it asked for, before [StreamError::Stalled] is returned, unlimited
+  /// by default
+  ///
+  /// A parser asking for `Needed::Size(n)` stalls if it still returns `Incomplete` once `n`
+  /// more bytes are available, and a parser asking for `Needed::Unknown` stalls if it does
+  /// once any byte was added. Short reads from the source are not counted, but a parser
+  /// handling its item in several steps, like a length prefix then the data, stalls once
+  /// between each step, so the limit should leave room for them. This detects a
+  /// buggy parser or a malformed stream that would otherwise keep the driver reading
+  /// without ever producing an item, like a parser searching for a delimiter that never
+  /// comes on a source that never ends.
+  ///
+  /// ```rust
+  /// # use nom::{Err, IResult, Needed};
+  /// use nom::stream::{Stream, StreamError};
+  ///
+  /// // a buggy parser, always asking for one more byte
+  /// fn stuck(_: &[u8]) -> IResult<&[u8], ()> {
+  ///   Err(Err::Incomplete(Needed::new(1)))
+  /// }
+  ///
+  /// let mut stream = Stream::new(std::io::repeat(0)).read_size(16).max_stalls(3);
+  /// assert!(matches!(
+  ///   stream.parse_next(stuck),
+  ///   Err(StreamError::Stalled { offset: 0, stalls: 4 })
+  /// ));
+  /// ```
+  pub fn max_stalls(mut self, max_stalls: usize) -> Self {
+    self.buffer.max_stalls = max_stalls;
+    self
+  }
+
+  /// Returns the number of bytes consumed by the parsers so far
+  pub fn offset(&self) -> usize {
+    self.buffer.offset()
+  }
+
+  /// Returns the data read from the source but not consumed yet
+  pub fn buffered(&self) -> &[u8] {
+    self.buffer.buffered()
+  }
+
+  /// Returns the source
+  pub fn into_inner(self) -> R {
+    self.reader
+  }
+
+  /// Applies `parser` on the next item of the stream
+  ///
+  /// This returns `Ok(None)` if the source is exhausted and all of its data was
+  /// consumed.
+  pub fn parse_next<P, O, E>(&mut self, mut parser: P) -> Result<Option<O>, StreamError<E>>
+  where
+    P: for<'a> Parser<&'a [u8], Output = O>,
+    for<'a> <P as Parser<&'a [u8]>>::Error: MapInput<&'a [u8], Mapped<usize> = E>,
+  {
+    self.buffer.reset_stalls();
+
+    loop {
+      let needed = match self.buffer.step(&mut parser)? {
+        Step::Item(output) => return Ok(Some(output)),
+        Step::End => return Ok(None),
+        Step::Fill(needed) => needed,
+      };
+
+      let len = self.buffer.reserve(needed)?;
+      let res = loop {
+        match self.reader.read(self.buffer.spare(len)) {
+          Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+          res => break res,
+        }
+      };
+      self.buffer.commit(len, res)?;
+    }
+  }
+}
+
+/// Parser on `&'a [u8]` usable by the drivers, producing `O` and errors `E` on offsets
+///
+/// This is implemented for every parser whose errors can be converted with [MapInput],
+/// like [Error](crate::error::Error) to `Error<usize>`. Bounding a parser by
+/// `for<'a> StreamParser<'a, O, E>` states that it can be applied on buffers of any
+/// lifetime, and that its output does not borrow them.
+pub trait StreamParser<'a, O, E>: Parser<&'a [u8], Output = O> {
+  /// Converts the input positions of an error with `f`
+  fn map_error<F: FnMut(&'a [u8]) -> usize>(error: Self::Error, f: F) -> E;
+}
+
+impl<'a, P, O, E> StreamParser<'a, O, E> for P
+where
+  P: Parser<&'a [u8], Output = O>,
+  P::Error: MapInput<&'a [u8], Mapped<usize> = E>,
+{
+  fn map_error<F: FnMut(&'a [u8]) -> usize>(error: Self::Error, f: F) -> E {
+    error.map_input(f)
+  }
+}
+
+/// Result of applying the parser on the buffered data
+enum Step<O> {
+  /// The parser recognized an item
+  Item(O),
+  /// The source is exhausted and all of its data was consumed
+  End,
+  /// More data must be read from the source
+  Fill(Needed),
+}
+
+/// Data read from the source but not consumed yet, shared by the drivers
+struct Buffer {
+  buffer: Vec<u8>,
+  /// Position of the first unconsumed byte in `buffer`
+  start: usize,
+  /// Offset in the stream of the start of `buffer`
+  position: usize,
+  eof: bool,
+  read_size: usize,
+  limit: usize,
+  max_stalls: usize,
+  /// Number of times the parser did not progress on the current item
+  stalls: usize,
+  /// Number of available bytes that would satisfy the last `Incomplete` result
+  requested: Option<usize>,
+}
+
+impl Buffer {
+  fn new() -> Self {
+    Buffer {
+      buffer: Vec::new(),
+      start: 0,
+      position: 0,
+      eof: false,
+      read_size: DEFAULT_READ_SIZE,
+      limit: usize::MAX,
+      max_stalls: usize::MAX,
+      stalls: 0,
+      requested: None,
+    }
+  }
+
+  fn offset(&self) -> usize {
+    self.position + self.start
+  }
+
+  fn buffered(&self) -> &[u8] {
+    &self.buffer[self.start..]
+  }
+
+  fn reset_stalls(&mut self) {
+    self.stalls = 0;
+    self.requested = None;
+  }
+
+  /// Applies `parser` on the buffered data
+  fn step<P, O, E>(&mut self, parser: &mut P) -> Result<Step<O>, StreamError<E>>
+  where
+    P: for<'a> StreamParser<'a, O, E>,
+  {
+    if self.start == self.buffer.len() && !self.eof {
+      return Ok(Step::Fill(Needed::Unknown));
+    }
+
+    let data = &self.buffer[self.start..];
+    if data.is_empty() {
+      return Ok(Step::End);
+    }
+
+    let res = if self.eof {
+      parser.parse_complete(data)
+    } else {
+      parser.parse(data)
+    };
+
+    let offset = self.position + self.start;
+    match res {
+      Ok((rest, output)) => {
+        self.start += data.offset(rest);
+        self.stalls = 0;
+        self.requested = None;
+        Ok(Step::Item(output))
+      }
+      Err(Err::Incomplete(_)) if self.eof => Err(StreamError::Truncated { offset }),
+      Err(Err::Incomplete(needed)) => {
+        if self
+          .requested
+          .map_or(false, |requested| data.len() >= requested)
+        {
+          self.stalls += 1;
+          if self.stalls > self.max_stalls {
+            return Err(StreamError::Stalled {
+              offset,
+              stalls: self.stalls,
+            });
+          }
+        }
+        self.requested = Some(data.len().saturating_add(match needed {
+          Needed::Size(n) => n.get(),
+          Needed::Unknown => 1,
+        }));
+        Ok(Step::Fill(needed))
+      }
+      Err(Err::Error(e)) | Err(Err::Failure(e)) => Err(StreamError::Parse(P::map_error(e, |i| {
+        offset + data.offset(i)
+      }))),
+    }
+  }
+
+  /// Drops the consumed data and makes room to read at least `needed` more bytes,
+  /// returning the length of the data before that room
+  fn reserve<E>(&mut self, needed: Needed) -> Result<usize, StreamError<E>> {
+    if self.start > 0 {
+      self.buffer.drain(..self.start);
+      self.position += self.start;
+      self.start = 0;
+    }
+
+    let needed = match needed {
+      Needed::Size(n) => n.get(),
+      Needed::Unknown => 1,
+    };
+    if self.buffer.len().saturating_add(needed) > self.limit {
+      return Err(StreamError::TooLarge { limit: self.limit });
+    }
+
+    let len = self.buffer.len();
+    let size = self.read_size.max(needed).min(self.limit - len);
+    self.buffer.resize(len + size, 0);
+    Ok(len)
+  }
+
+  /// Returns the room made by [Buffer::reserve]
+  fn spare(&mut self, len: usize) -> &mut [u8] {
+    &mut self.buffer[len..]
+  }
+
+  /// Gives back the room made by [Buffer::reserve], when nothing could be read
+  #[cfg(feature = "futures")]
+  fn cancel(&mut self, len: usize) {
+    self.buffer.truncate(len);
+  }
+
+  /// Keeps the bytes read in the room made by [Buffer::reserve]
+  fn commit<E>(&mut self, len: usize, res: io::Result<usize>) -> Result<(), StreamError<E>> {
+    match res {
+      Ok(read) => {
+        self.buffer.truncate(len + read);
+        if read == 0 {
+          self.eof = true;
+        }
+        Ok(())
+      }
+      Err(e) => {
+        self.buffer.truncate(len);
+        Err(StreamError::Io(e))
+      }
+    }
+  }
+}
+
+#[cfg(feature = "flate2")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "flate2")))]
+impl<R: Read> Stream<flate2::read::MultiGzDecoder<R>> {
+  /// Creates a driver decompressing the gzip data read from `reader`
+  ///
+  /// Files made of several concatenated gzip members, like rotated logs, are read as
+  /// one stream.
+  pub fn gzip(reader: R) -> Self {
+    Stream::new(flate2::read::MultiGzDecoder::new(reader))
+  }
+}
+
+#[cfg(feature = "flate2")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "flate2")))]
+impl<R: Read> Stream<flate2::read::ZlibDecoder<R>> {
+  /// Creates a driver decompressing the zlib data read from `reader`
+  pub fn zlib(reader: R) -> Self {
+    Stream::new(flate2::read::ZlibDecoder::new(reader))
+  }
+}
+
+#[cfg(feature = "flate2")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "flate2")))]
+impl<R: Read> Stream<flate2::read::DeflateDecoder<R>> {
+  /// Creates a driver decompressing the raw deflate data read from `reader`
+  pub fn deflate(reader: R) -> Self {
+    Stream::new(flate2::read::DeflateDecoder::new(reader))
+  }
+}
+
+#[cfg(feature = "zstd")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "zstd")))]
+impl<R: Read> Stream<zstd::stream::read::Decoder<'static, io::BufReader<R>>> {
+  /// Creates a driver decompressing the zstd data read from `reader`
+  ///
+  /// This returns an error if the decompression context cannot be created.
+  pub fn zstd(reader: R) -> io::Result<Self> {
+    Ok(Stream::new(zstd::stream::read::Decoder::new(reader)?))
+  }
+}
+
+/// Creates a driver applying `parser` on the data read from an asynchronous `reader`
+///
+/// This is the asynchronous counterpart of [Stream]: the returned [AsyncStream] is a
+/// [futures_core::Stream] of the parsed items, which reads more data from `reader`
+/// whenever `parser` returns [Err::Incomplete], and applies `parser` in complete mode
+/// once `reader` is exhausted. Any [futures_io::AsyncRead] source can be used, including
+/// the buffered ones implementing `AsyncBufRead`.
+///
+/// ```rust
+/// # use nom::{IResult, Parser};
+/// use core::pin::Pin;
+/// use futures_core::Stream as _;
+/// use futures_io::AsyncRead;
+/// use nom::bytes::{tag, take_until};
+/// use nom::sequence::terminated;
+/// use nom::stream::{from_async_reader, StreamError};
+/// use nom::error::Error;
+///
+/// fn line(input: &[u8]) -> IResult<&[u8], String> {
+///   terminated(take_until("\n"), tag("\n"))
+///     .map(|line| String::from_utf8_lossy(line).into_owned())
+///     .parse(input)
+/// }
+///
+/// async fn lines<R: AsyncRead + Unpin>(
+///   reader: R,
+/// ) -> Result<Vec<String>, StreamError<Error<usize>>> {
+///   let mut stream = from_async_reader(reader, line);
+///   let mut lines = Vec::new();
+///   while let Some(line) = std::future::poll_fn(|cx| Pin::new(&mut stream).poll_next(cx)).await {
+///     lines.push(line?);
+///   }
+///   Ok(lines)
+/// }
+/// ```
+#[cfg(feature = "futures")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "futures")))]
+pub fn from_async_reader<R, P, O, E>(reader: R, parser: P) -> AsyncStream<R, P, O, E>
+where
+  R: AsyncRead + Unpin,
+  P: for<'a> StreamParser<'a, O, E>,
+{
+  AsyncStream {
+    reader,
+    parser,
+    buffer: Buffer::new(),
+    pending: None,
+    done: false,
+    item: PhantomData,
+  }
+}
+
+/// Applies a parser on the data read from an [AsyncRead] source, see [from_async_reader]
+///
+/// The stream ends after the first error, and the data that was not consumed is still
+/// available with [AsyncStream::buffered].
+#[cfg(feature = "futures")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "futures")))]
+pub struct AsyncStream<R, P, O, E> {
+  reader: R,
+  parser: P,
+  buffer: Buffer,
+  /// Data requested by the parser, when the last read was pending
+  pending: Option<Needed>,
+  done: bool,
+  item: PhantomData<fn() -> (O, E)>,
+}
+
+#[cfg(feature = "futures")]
+impl<R: AsyncRead + Unpin, P, O, E> AsyncStream<R, P, O, E> {
+  /// Sets the number of bytes requested from the source by each read, 8 KiB by default
+  pub fn read_size(mut self, read_size: usize) -> Self {
+    self.buffer.read_size = read_size.max(1);
+    self
+  }
+
+  /// Sets the maximum number of bytes held in the buffer, see [Stream::limit]
+  pub fn limit(mut self, limit: usize) -> Self {
+    self.buffer.limit = limit;
+    self
+  }
+
+  /// Sets how many times the parser can return [Err::Incomplete] for the same item after
+  /// receiving the data it asked for, see [Stream::max_stalls]
+  pub fn max_stalls(mut self, max_stalls: usize) -> Self {
+    self.buffer.max_stalls = max_stalls;
+    self
+  }
+
+  /// Returns the number of bytes consumed by the parser so far
+  pub fn offset(&self) -> usize {
+    self.buffer.offset()
+  }
+
+  /// Returns the data read from the source but not consumed yet
+  pub fn buffered(&self) -> &[u8] {
+    self.buffer.buffered()
+  }
+
+  /// Returns the source
+  pub fn into_inner(self) -> R {
+    self.reader
+  }
+}
+
+#[cfg(feature = "futures")]
+impl<R, P, O, E> futures_core::Stream for AsyncStream<R, P, O, E>
+where
+  R: AsyncRead + Unpin,
+  P: for<'a> StreamParser<'a, O, E> + Unpin,
+{
+  type Item = Result<O, StreamError<E>>;
+
+  fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+    let this = self.get_mut();
+    if this.done {
+      return Poll::Ready(None);
+    }
+
+    let res = loop {
+      let needed = match this.pending.take() {
+        Some(needed) => needed,
+        None => match this.buffer.step(&mut this.parser) {
+          Ok(Step::Item(output)) => return Poll::Ready(Some(Ok(output))),
+          Ok(Step::End) => break None,
+          Ok(Step::Fill(needed)) => needed,
+          Err(e) => break Some(e),
+        },
+      };
+
+      let len = match this.buffer.reserve(needed) {
+        Ok(len) => len,
+        Err(e) => break Some(e),
+      };
+      let res = match Pin::new(&mut this.reader).poll_read(cx, this.buffer.spare(len)) {
+        Poll::Ready(Err(e)) if e.kind() == io::ErrorKind::Interrupted => {
+          this.buffer.cancel(len);
+          this.pending = Some(needed);
+          continue;
+        }
+        Poll::Ready(res) => res,
+        Poll::Pending => {
+          this.buffer.cancel(len);
+          this.pending = Some(needed);
+          return Poll::Pending;
+        }
+      };
+      if let Err(e) = this.buffer.commit(len, res) {
+        break Some(e);
+      }
+    };
+
+    this.done = true;
+    Poll::Ready(res.map(Err))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::bytes::{tag, take};
+  use crate::error::{Error, ErrorKind};
+  use crate::number::be_u16;
+  use crate::IResult;
+
+  fn record(i: &[u8]) -> IResult<&[u8], Vec<u8>> {
+    be_u16()
+      .flat_map(take)
+      .map(|data: &[u8]| data.to_vec())
+      .parse(i)
+  }
+
+  #[test]
+  fn records() {
+    let data = &b"\x00\x03abc\x00\x00\x00\x05defgh"[..];
+    let mut stream = Stream::new(data).read_size(1);
+    let mut records = Vec::new();
+    while let Some(record) = stream.parse_next(record).unwrap() {
+      records.push(record);
+    }
+    assert_eq!(records, [b"abc".to_vec(), Vec::new(), b"defgh".to_vec()]);
+    assert_eq!(stream.offset(), data.len());
+  }
+
+  #[test]
+  fn errors() {
+    fn ab(i: &[u8]) -> IResult<&[u8], ()> {
+      tag("ab").map(|_| ()).parse(i)
+    }
+
+    let mut stream = Stream::new(&b"ababxb"[..]).read_size(3);
+    assert_eq!(stream.parse_next(ab).unwrap(), Some(()));
+    assert_eq!(stream.parse_next(ab).unwrap(), Some(()));
+    match stream.parse_next(ab) {
+      Err(StreamError::Parse(e)) => assert_eq!(e, Error::new(4, ErrorKind::Tag)),
+      res => panic!("unexpected result: {:?}", res),
+    }
+    assert_eq!(stream.buffered(), b"xb");
+
+    // the last record is truncated
+    let mut stream = Stream::new(&b"\x00\x03abc\x00\x04ab"[..]);
+    assert_eq!(stream.parse_next(record).unwrap(), Some(b"abc".to_vec()));
+    assert!(matches!(
+      stream.parse_next(record),
+      Err(StreamError::Truncated { offset: 5 })
+    ));
+    assert_eq!(stream.buffered(), b"\x00\x04ab");
+
+    let mut stream = Stream::new(&b"\x00\x10abcdefghijklmnop"[..]).limit(8);
+    assert!(matches!(
+      stream.parse_next(record),
+      Err(StreamError::TooLarge { limit: 8 })
+    ));
+  }
+
+  #[test]
+  fn stalls() {
+    // short reads are not stalls: the data arrives one byte at a time, and the parser only
+    // stalls when it asks for the data after reading the length
+    let data = &b"\x00\x05hello"[..];
+    let mut stream = Stream::new(data).read_size(1).max_stalls(1);
+    assert_eq!(stream.parse_next(record).unwrap(), Some(b"hello".to_vec()));
+    let mut stream = Stream::new(data).read_size(1).max_stalls(0);
+    assert!(matches!(
+      stream.parse_next(record),
+      Err(StreamError::Stalled {
+        offset: 0,
+        stalls: 1
+      })
+    ));
+
+    // a parser asking again for data it already has
+    fn greedy(i: &[u8]) -> IResult<&[u8], ()> {
+      match i.iter().position(|b| *b == b'\n') {
+        Some(_) => Err(Err::Incomplete(Needed::new(2))),
+        None => Err(Err::Incomplete(Needed::Unknown)),
+      }
+    }
+    let mut stream = Stream::new(&b"ab\ncdefghijklmnop"[..])
+      .read_size(2)
+      .max_stalls(2);
+    match stream.parse_next(greedy) {
+      Err(StreamError::Stalled { offset, stalls }) => {
+        assert_eq!((offset, stalls), (0, 3));
+        assert_eq!(
+          StreamError::<Error<usize>>::Stalled { offset, stalls }.to_string(),
+          "parser stalled 3 times on the item at offset 0"
+        );
+      }
+      res => panic!("unexpected result: {:?}", res),
+    }
+  }
+
+  #[cfg(feature = "futures")]
+  #[test]
+  fn async_reader() {
+    use futures_core::Stream as _;
+    use std::sync::Arc;
+    use std::task::{Wake, Waker};
+
+    // a source alternating pending and ready reads, returning at most 2 bytes
+    struct Slow<'a> {
+      data: &'a [u8],
+      ready: bool,
+    }
+
+    impl AsyncRead for Slow<'_> {
+      fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+      ) -> Poll<io::Result<usize>> {
+        self.ready = !self.ready;
+        if !self.ready {
+          cx.waker().wake_by_ref();
+          return Poll::Pending;
+        }
+        let read = buf.len().min(2).min(self.data.len());
+        buf[..read].copy_from_slice(&self.data[..read]);
+        self.data = &self.data[read..];
+        Poll::Ready(Ok(read))
+      }
+    }
+
+    struct Noop;
+    impl Wake for Noop {
+      fn wake(self: Arc<Self>) {}
+    }
+
+    type Record = fn(&[u8]) -> IResult<&[u8], Vec<u8>>;
+    type Item = Result<Vec<u8>, StreamError<Error<usize>>>;
+
+    fn collect<R: AsyncRead + Unpin>(
+      mut stream: AsyncStream<R, Record, Vec<u8>, Error<usize>>,
+    ) -> (Vec<Item>, usize) {
+      let waker = Waker::from(Arc::new(Noop));
+      let mut cx = Context::from_waker(&waker);
+      let mut items = Vec::new();
+      let mut pending = 0;
+      loop {
+        match Pin::new(&mut stream).poll_next(&mut cx) {
+          Poll::Ready(Some(item)) => items.push(item),
+          Poll::Ready(None) => return (items, pending),
+          Poll::Pending => pending += 1,
+        }
+      }
+    }
+
+    let data = &b"\x00\x03abc\x00\x00\x00\x05defgh"[..];
+    let (items, pending) = collect(from_async_reader(
+      Slow { data, ready: false },
+      record as Record,
+    ));
+    let items = items.into_iter().map(Result::unwrap).collect::<Vec<_>>();
+    assert_eq!(items, [b"abc".to_vec(), Vec::new(), b"defgh".to_vec()]);
+    // 7 reads return data and the last one the end of the source, with a pending read
+    // between each of them
+    assert_eq!(pending, 7);
+
+    // the stream ends after an error
+    let (items, _) = collect(from_async_reader(
+      Slow {
+        data: &b"\x00\x03abc\x00\x04ab"[..],
+        ready: false,
+      },
+      record as Record,
+    ));
+    assert_eq!(items.len(), 2);
+    assert!(matches!(items[0], Ok(ref abc) if abc == b"abc"));
+    assert!(matches!(
+      items[1],
+      Err(StreamError::Truncated { offset: 5 })
+    ));
+  }
+
+  #[cfg(feature = "flate2")]
+  #[test]
+  fn gzip() {
+    use std::io::Write;
+
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    for _ in 0..1000 {
+      encoder.write_all(b"\x00\x05hello").unwrap();
+    }
+    let compressed = encoder.finish().unwrap();
+
+    let mut stream = Stream::gzip(&compressed[..]).read_size(64).limit(64);
+    let mut count = 0;
+    while let Some(record) = stream.parse_next(record).unwrap() {
+      assert_eq!(record, b"hello");
+      count += 1;
+    }
+    assert_eq!(count, 1000);
+    assert_eq!(stream.offset(), 7000);
+  }
+
+  #[cfg(feature = "zstd")]
+  #[test]
+  fn zstd() {
+    let compressed = zstd::encode_all(&b"\x00\x02ab\x00\x03cde"[..], 0).unwrap();
+
+    let mut stream = Stream::zstd(&compressed[..]).unwrap();
+    assert_eq!(stream.parse_next(record).unwrap(), Some(b"ab".to_vec()));
+    assert_eq!(stream.parse_next(record).unwrap(), Some(b"cde".to_vec()));
+    assert_eq!(stream.parse_next(record).unwrap(), None);
+  }
+}
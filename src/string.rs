@@ -0,0 +1,734 @@
+//! Parsers for quoted strings with escape sequences
+//!
+//! [escaped_transform](crate::bytes::escaped_transform) always builds a new value,
+//! even when the string does not contain any escape sequence. The parsers of this
+//! module return a [Cow]: they borrow the input when the string has no escape
+//! sequence, and only allocate when one has to be replaced.
+//! [escaped_transform_cow](crate::bytes::escaped_transform_cow) does the same for
+//! custom escape sequences.
+//!
+//! The following escape sequences are recognized:
+//! * `\n`, `\r`, `\t`, `\0`, `\\`, `\'` and `\"`
+//! * `\xNN`: the character with the code point `NN`, written with 2 hexadecimal digits
+//! * `\u{NNNN}`: the character with the code point `NNNN`, written with 1 to 6
+//!   hexadecimal digits
+//!
+//! It also contains parsers for multi-line strings, [multiline_string] for blocks
+//! between triple quotes and [dedented_block] for blocks delimited by their
+//! indentation, which remove the indentation of their lines following a [Dedent] rule.
+
+use crate::error::{ErrorKind, ParseError};
+use crate::internal::{Err, IsStreaming, Mode, Needed, OutputMode, PResult, Parser};
+use crate::lib::std::borrow::Cow;
+use crate::lib::std::string::String;
+use core::marker::PhantomData;
+
+/// Recognizes a string between double quotes, replacing escape sequences.
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+/// use nom::string::double_quoted;
+/// use std::borrow::Cow;
+///
+/// fn parser(s: &str) -> IResult<&str, Cow<'_, str>> {
+///   double_quoted().parse_complete(s)
+/// }
+///
+/// assert_eq!(parser(r#""abc" def"#), Ok((" def", Cow::Borrowed("abc"))));
+/// assert_eq!(parser(r#""a\tb\u{e9}""#), Ok(("", Cow::Owned(String::from("a\tbé")))));
+/// assert_eq!(parser(r#""abc"#), Err(Err::Error(Error::new("", ErrorKind::Char))));
+/// assert_eq!(parser(r#"'abc'"#), Err(Err::Error(Error::new("'abc'", ErrorKind::Char))));
+/// ```
+pub fn double_quoted<'a, E: ParseError<&'a str>>(
+) -> impl Parser<&'a str, Output = Cow<'a, str>, Error = E> {
+  quoted('"')
+}
+
+/// Recognizes a string between single quotes, replacing escape sequences.
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+/// use nom::string::single_quoted;
+/// use std::borrow::Cow;
+///
+/// fn parser(s: &str) -> IResult<&str, Cow<'_, str>> {
+///   single_quoted().parse_complete(s)
+/// }
+///
+/// assert_eq!(parser("'abc' def"), Ok((" def", Cow::Borrowed("abc"))));
+/// assert_eq!(parser(r"'it\'s'"), Ok(("", Cow::Owned(String::from("it's")))));
+/// assert_eq!(parser(r"'\q'"), Err(Err::Error(Error::new(r"\q'", ErrorKind::EscapedTransform))));
+/// ```
+pub fn single_quoted<'a, E: ParseError<&'a str>>(
+) -> impl Parser<&'a str, Output = Cow<'a, str>, Error = E> {
+  quoted('\'')
+}
+
+fn quoted<'a, E: ParseError<&'a str>>(
+  quote: char,
+) -> impl Parser<&'a str, Output = Cow<'a, str>, Error = E> {
+  crate::sequence::delimited(
+    crate::character::char(quote),
+    escaped_cow(quote),
+    crate::character::char(quote),
+  )
+}
+
+/// Recognizes a string containing escape sequences, until an unescaped `delimiter`
+/// character, which is not consumed.
+///
+/// Along with the escape sequences listed in the [module documentation](self), the
+/// delimiter can be escaped with a backslash.
+///
+/// The output borrows the input if there is no escape sequence. An unknown or invalid
+/// escape sequence returns an error with [ErrorKind::EscapedTransform], at the position
+/// of the backslash.
+///
+/// *Streaming version*: if the delimiter is not found, or if the input ends in an escape
+/// sequence, this returns `Err::Incomplete`.
+///
+/// *Complete version*: if the delimiter is not found, this recognizes the whole input. If
+/// the input ends in an escape sequence, this returns an error.
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult, Needed, Parser};
+/// use nom::string::escaped_cow;
+/// use std::borrow::Cow;
+///
+/// fn parser(s: &str) -> IResult<&str, Cow<'_, str>> {
+///   escaped_cow('|').parse_complete(s)
+/// }
+///
+/// assert_eq!(parser("abc|def"), Ok(("|def", Cow::Borrowed("abc"))));
+/// assert_eq!(parser(r"a\|b\x41|"), Ok(("|", Cow::Owned(String::from("a|bA")))));
+/// assert_eq!(parser("abc"), Ok(("", Cow::Borrowed("abc"))));
+/// assert_eq!(parser(r"a\u{110000}"), Err(Err::Error(Error::new(r"\u{110000}", ErrorKind::EscapedTransform))));
+///
+/// assert_eq!(
+///   escaped_cow::<Error<_>>('|').parse("abc"),
+///   Err(Err::Incomplete(Needed::new(1)))
+/// );
+/// ```
+pub fn escaped_cow<'a, E: ParseError<&'a str>>(
+  delimiter: char,
+) -> impl Parser<&'a str, Output = Cow<'a, str>, Error = E> {
+  EscapedCow {
+    delimiter,
+    e: PhantomData,
+  }
+}
+
+/// Parser implementation for [escaped_cow]
+pub struct EscapedCow<E> {
+  delimiter: char,
+  e: PhantomData<E>,
+}
+
+/// Result of decoding one escape sequence
+enum Escape {
+  /// the escaped character, and the length of the escape sequence
+  Char(char, usize),
+  /// the input ends inside the escape sequence
+  Incomplete,
+  /// unknown or invalid escape sequence
+  Invalid,
+}
+
+/// Decodes the escape sequence at the start of `input`, which starts with a backslash
+fn escape(input: &str) -> Escape {
+  let bytes = input.as_bytes();
+  let c = match bytes.get(1) {
+    None => return Escape::Incomplete,
+    Some(c) => *c,
+  };
+
+  let c = match c {
+    b'n' => '\n',
+    b'r' => '\r',
+    b't' => '\t',
+    b'0' => '\0',
+    b'\\' => '\\',
+    b'\'' => '\'',
+    b'"' => '"',
+    b'x' => {
+      return match hex(&input[2..], 2) {
+        Escape::Char(c, _) => Escape::Char(c, 4),
+        other => other,
+      }
+    }
+    b'u' => {
+      if bytes.len() < 3 {
+        return Escape::Incomplete;
+      }
+      if bytes[2] != b'{' {
+        return Escape::Invalid;
+      }
+      let digits = bytes[3..]
+        .iter()
+        .take(7)
+        .take_while(|c| c.is_ascii_hexdigit())
+        .count();
+      if digits == 0 || digits > 6 {
+        return match bytes.get(3 + digits) {
+          None if digits == 0 => Escape::Incomplete,
+          _ => Escape::Invalid,
+        };
+      }
+      return match bytes.get(3 + digits) {
+        None => Escape::Incomplete,
+        Some(b'}') => match hex(&input[3..], digits) {
+          Escape::Char(c, _) => Escape::Char(c, 4 + digits),
+          other => other,
+        },
+        Some(_) => Escape::Invalid,
+      };
+    }
+    _ => return Escape::Invalid,
+  };
+
+  Escape::Char(c, 2)
+}
+
+/// Decodes the code point written with `digits` hexadecimal digits at the start of `input`
+fn hex(input: &str, digits: usize) -> Escape {
+  let bytes = input.as_bytes();
+  for i in 0..digits {
+    match bytes.get(i) {
+      None => return Escape::Incomplete,
+      Some(c) if !c.is_ascii_hexdigit() => return Escape::Invalid,
+      Some(_) => {}
+    }
+  }
+
+  match u32::from_str_radix(&input[..digits], 16)
+    .ok()
+    .and_then(core::char::from_u32)
+  {
+    Some(c) => Escape::Char(c, digits),
+    None => Escape::Invalid,
+  }
+}
+
+impl<'a, E: ParseError<&'a str>> Parser<&'a str> for EscapedCow<E> {
+  type Output = Cow<'a, str>;
+  type Error = E;
+
+  fn process<OM: OutputMode>(
+    &mut self,
+    input: &'a str,
+  ) -> PResult<OM, &'a str, Self::Output, Self::Error> {
+    // decoded string, only allocated once an escape sequence is found
+    let mut owned: Option<String> = None;
+    // start of the data that was not copied yet to `owned`
+    let mut start = 0;
+    let mut index = 0;
+
+    let end = loop {
+      let c = match input[index..].chars().next() {
+        None => {
+          if OM::Incomplete::is_streaming() {
+            return Err(Err::Incomplete(Needed::new(1)));
+          }
+          break index;
+        }
+        Some(c) => c,
+      };
+
+      if c == self.delimiter {
+        break index;
+      }
+
+      if c != '\\' {
+        index += c.len_utf8();
+        continue;
+      }
+
+      let escaped = if input[index + 1..].starts_with(self.delimiter) {
+        Escape::Char(self.delimiter, 1 + self.delimiter.len_utf8())
+      } else {
+        escape(&input[index..])
+      };
+
+      match escaped {
+        Escape::Char(c, len) => {
+          let s = owned.get_or_insert_with(String::new);
+          s.push_str(&input[start..index]);
+          s.push(c);
+          index += len;
+          start = index;
+        }
+        Escape::Incomplete if OM::Incomplete::is_streaming() => {
+          return Err(Err::Incomplete(Needed::Unknown));
+        }
+        Escape::Incomplete | Escape::Invalid => {
+          return Err(Err::Error(OM::Error::bind(|| {
+            E::from_error_kind(&input[index..], ErrorKind::EscapedTransform)
+          })));
+        }
+      }
+    };
+
+    let output = match owned {
+      None => Cow::Borrowed(&input[..end]),
+      Some(mut s) => {
+        s.push_str(&input[start..end]);
+        Cow::Owned(s)
+      }
+    };
+
+    Ok((&input[end..], OM::Output::bind(|| output)))
+  }
+}
+
+/// Rule removing the indentation of the lines of a multi-line string
+///
+/// Text formats indent the content of multi-line strings with the surrounding code,
+/// and remove that indentation from the value. [Dedent::apply] applies a rule to the
+/// content of a string, and [multiline_string] parses a string then applies it.
+///
+/// ```rust
+/// use nom::string::Dedent;
+///
+/// let content = "\n    fn main() {\n      run();\n    }\n    ";
+/// assert_eq!(Dedent::Common.apply(content), "fn main() {\n  run();\n}");
+///
+/// let content = "\n    |fn main() {\n    |  run();\n    |}";
+/// assert_eq!(Dedent::Margin('|').apply(content), "fn main() {\n  run();\n}");
+///
+/// assert_eq!(Dedent::Keep.apply("\n  a\n  b\n"), "  a\n  b\n");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Dedent {
+  /// Keeps the indentation, only removing a line break right after the opening
+  /// delimiter, like TOML multi-line strings
+  Keep,
+  /// Removes the indentation common to all the lines that are not blank, and the first
+  /// and last lines if they are blank, like Kotlin's `trimIndent`, Java text blocks or
+  /// Python's `textwrap.dedent`. Blank lines become empty.
+  Common,
+  /// Removes the whitespace at the start of every line up to this margin character, and
+  /// the margin character itself, and the first and last lines if they are blank, like
+  /// Kotlin's `trimMargin`. Lines without the margin character are kept as is.
+  Margin(char),
+}
+
+impl Dedent {
+  /// Applies the rule to the content of a string, borrowing it if nothing else than its
+  /// first and last lines was removed
+  pub fn apply<'a>(&self, content: &'a str) -> Cow<'a, str> {
+    let content = match self {
+      Dedent::Keep => {
+        let content = content.strip_prefix('\n').unwrap_or(content);
+        return Cow::Borrowed(content.strip_prefix("\r\n").unwrap_or(content));
+      }
+      Dedent::Common | Dedent::Margin(_) => trim_blank_ends(content),
+    };
+
+    match self {
+      Dedent::Keep => Cow::Borrowed(content),
+      Dedent::Common => {
+        let indent = content
+          .split('\n')
+          .filter(|line| !is_blank(line))
+          .map(indentation)
+          .min()
+          .unwrap_or(0);
+        if indent == 0
+          && !content
+            .split('\n')
+            .any(|line| is_blank(line) && !line.is_empty())
+        {
+          return Cow::Borrowed(content);
+        }
+        join_lines(content.split('\n').map(
+          |line| {
+            if is_blank(line) {
+              ""
+            } else {
+              &line[indent..]
+            }
+          },
+        ))
+      }
+      Dedent::Margin(margin) => join_lines(content.split('\n').map(|line| {
+        let trimmed = line.trim_start_matches(&[' ', '\t'][..]);
+        match trimmed.strip_prefix(*margin) {
+          Some(rest) => rest,
+          None => line,
+        }
+      })),
+    }
+  }
+}
+
+/// Returns `true` if the line only contains whitespace
+fn is_blank(line: &str) -> bool {
+  line.trim().is_empty()
+}
+
+/// Returns the length of the spaces and tabs at the start of the line
+fn indentation(line: &str) -> usize {
+  line.len() - line.trim_start_matches(&[' ', '\t'][..]).len()
+}
+
+/// Removes the first and the last lines of `content` if they are blank
+fn trim_blank_ends(content: &str) -> &str {
+  let content = match content.split_once('\n') {
+    Some((first, rest)) if is_blank(first) => rest,
+    _ => content,
+  };
+  match content.rsplit_once('\n') {
+    Some((rest, last)) if is_blank(last) => rest,
+    None if is_blank(content) => "",
+    _ => content,
+  }
+}
+
+/// Joins lines with `\n`
+fn join_lines<'a, L: Iterator<Item = &'a str>>(lines: L) -> Cow<'a, str> {
+  let mut s = String::new();
+  for (i, line) in lines.enumerate() {
+    if i > 0 {
+      s.push('\n');
+    }
+    s.push_str(line);
+  }
+  Cow::Owned(s)
+}
+
+/// Recognizes a multi-line string between two `delimiter`, like `"""` or `'''`, then
+/// removes its indentation with `dedent`.
+///
+/// The content is raw: escape sequences are not replaced, and it ends at the first
+/// occurrence of the delimiter.
+///
+/// *Streaming version*: if the closing delimiter is not found, this returns
+/// `Err::Incomplete`.
+///
+/// *Complete version*: if the closing delimiter is not found, this returns an error
+/// with [ErrorKind::TakeUntil].
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+/// use nom::string::{multiline_string, Dedent};
+/// use std::borrow::Cow;
+///
+/// fn parser(s: &str) -> IResult<&str, Cow<'_, str>> {
+///   multiline_string("\"\"\"", Dedent::Common).parse_complete(s)
+/// }
+///
+/// let input = r#""""
+///     SELECT *
+///       FROM users
+///     """;"#;
+/// assert_eq!(parser(input), Ok((";", Cow::Owned(String::from("SELECT *\n  FROM users")))));
+/// assert_eq!(parser(r#""""one line""""#), Ok(("", Cow::Borrowed("one line"))));
+/// assert_eq!(parser(r#""""abc"#), Err(Err::Error(Error::new("abc", ErrorKind::TakeUntil))));
+/// ```
+pub fn multiline_string<'a, E: ParseError<&'a str>>(
+  delimiter: &'static str,
+  dedent: Dedent,
+) -> impl Parser<&'a str, Output = Cow<'a, str>, Error = E> {
+  crate::sequence::delimited(
+    crate::bytes::tag(delimiter),
+    crate::bytes::take_until(delimiter),
+    crate::bytes::tag(delimiter),
+  )
+  .map(move |content| dedent.apply(content))
+}
+
+/// Recognizes a block of lines indented deeper than `parent` columns, and removes their
+/// indentation, like YAML block scalars or indentation sensitive languages.
+///
+/// The input starts at the beginning of the first line of the block. The indentation of
+/// the block is the one of its first line that is not blank, which must be deeper than
+/// `parent`, otherwise this returns an error with [ErrorKind::Space]. The block ends
+/// before the first line that is not blank and indented less than the block, and that
+/// indentation is removed from every line. Deeper indentation is kept, and blank lines
+/// become empty. Trailing blank lines are not part of the block.
+///
+/// The lines are joined with `\n`, and the line ending of the last line is consumed
+/// but not part of the output. Indentation is made of spaces and tabs, each counting for
+/// one column.
+///
+/// *Streaming version*: if the input ends before a line indented less than the block,
+/// this returns `Err::Incomplete`.
+///
+/// *Complete version*: the block can end at the end of the input.
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, IResult, Parser};
+/// use nom::bytes::complete::tag;
+/// use nom::sequence::preceded;
+/// use nom::string::dedented_block;
+/// use std::borrow::Cow;
+///
+/// fn script(s: &str) -> IResult<&str, Cow<'_, str>> {
+///   preceded(tag("script: |\n"), dedented_block(0)).parse_complete(s)
+/// }
+///
+/// let input = "script: |\n  cargo build\n\n  cargo test \\\n    --all\nnext: 1\n";
+/// assert_eq!(
+///   script(input),
+///   Ok(("next: 1\n", Cow::Owned(String::from("cargo build\n\ncargo test \\\n  --all"))))
+/// );
+/// assert_eq!(
+///   script("script: |\nnext: 1\n"),
+///   Err(Err::Error(Error::new("next: 1\n", ErrorKind::Space)))
+/// );
+/// ```
+pub fn dedented_block<'a, E: ParseError<&'a str>>(
+  parent: usize,
+) -> impl Parser<&'a str, Output = Cow<'a, str>, Error = E> {
+  DedentedBlock {
+    parent,
+    e: PhantomData,
+  }
+}
+
+/// Parser implementation for [dedented_block]
+pub struct DedentedBlock<E> {
+  parent: usize,
+  e: PhantomData<E>,
+}
+
+impl<'a, E: ParseError<&'a str>> Parser<&'a str> for DedentedBlock<E> {
+  type Output = Cow<'a, str>;
+  type Error = E;
+
+  fn process<OM: OutputMode>(
+    &mut self,
+    input: &'a str,
+  ) -> PResult<OM, &'a str, Self::Output, Self::Error> {
+    // indentation of the block, set by its first line that is not blank
+    let mut indent = None;
+    // end of the content, and start of the remaining input
+    let mut end = (0, 0);
+    let mut index = 0;
+
+    loop {
+      let line_end = input[index..].find('\n').map(|i| index + i);
+      let line = &input[index..line_end.unwrap_or(input.len())];
+      let next = line_end.map_or(input.len(), |i| i + 1);
+
+      // a line that is not blank and indented less than the block ends it, even if it
+      // is incomplete
+      let line_indent = indentation(line);
+      let outdented = !is_blank(line)
+        && match indent {
+          None => line_indent <= self.parent,
+          Some(indent) => line_indent < indent,
+        };
+      if outdented {
+        break;
+      }
+      if line_end.is_none() && OM::Incomplete::is_streaming() {
+        return Err(Err::Incomplete(Needed::Unknown));
+      }
+
+      if !is_blank(line) {
+        indent.get_or_insert(line_indent);
+        end = (index + line.len(), next);
+      }
+
+      index = next;
+      if line_end.is_none() {
+        break;
+      }
+    }
+
+    let indent = match indent {
+      Some(indent) => indent,
+      None => {
+        return Err(Err::Error(OM::Error::bind(|| {
+          E::from_error_kind(&input[end.1..], ErrorKind::Space)
+        })))
+      }
+    };
+
+    let content = &input[..end.0];
+    let output = OM::Output::bind(|| {
+      if !content.contains('\n') {
+        return Cow::Borrowed(&content[indent..]);
+      }
+      join_lines(
+        content
+          .split('\n')
+          .map(|line| if is_blank(line) { "" } else { &line[indent..] }),
+      )
+    });
+    Ok((&input[end.1..], output))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::error::Error;
+
+  fn complete(s: &str) -> crate::IResult<&str, Cow<'_, str>> {
+    escaped_cow('"').parse_complete(s)
+  }
+
+  fn streaming(s: &str) -> crate::IResult<&str, Cow<'_, str>> {
+    escaped_cow('"').parse(s)
+  }
+
+  #[test]
+  fn borrowed() {
+    assert!(matches!(
+      complete("abc\""),
+      Ok(("\"", Cow::Borrowed("abc")))
+    ));
+    assert!(matches!(
+      complete("été\""),
+      Ok(("\"", Cow::Borrowed("été")))
+    ));
+    assert!(matches!(complete(""), Ok(("", Cow::Borrowed("")))));
+    assert!(matches!(streaming("\""), Ok(("\"", Cow::Borrowed("")))));
+  }
+
+  #[test]
+  fn escapes() {
+    assert_eq!(
+      complete(r#"\n\r\t\0\\\'\" a""#),
+      Ok(("\"", Cow::Owned(String::from("\n\r\t\0\\'\" a"))))
+    );
+    assert_eq!(
+      complete(r#"\x41\x7a\xe9""#),
+      Ok(("\"", Cow::Owned(String::from("Azé"))))
+    );
+    assert_eq!(
+      complete(r#"\u{41}\u{1F600}\u{00e9}x""#),
+      Ok(("\"", Cow::Owned(String::from("A\u{1F600}éx"))))
+    );
+  }
+
+  #[test]
+  fn invalid_escapes() {
+    for s in [
+      r"\q",
+      r"\x4",
+      r"\x4g",
+      r"\u41",
+      r"\u{}",
+      r"\u{1234567}",
+      r"\u{d800}",
+      r"\u{41",
+      r"\u{41x}",
+      r"\",
+    ] {
+      assert_eq!(
+        complete(s),
+        Err(Err::Error(Error::new(s, ErrorKind::EscapedTransform))),
+        "{}",
+        s
+      );
+    }
+  }
+
+  #[test]
+  fn streaming_escapes() {
+    assert_eq!(streaming("abc"), Err(Err::Incomplete(Needed::new(1))));
+    for s in [r"\", r"\x", r"\x4", r"\u", r"\u{", r"\u{41"] {
+      assert_eq!(streaming(s), Err(Err::Incomplete(Needed::Unknown)), "{}", s);
+    }
+    assert_eq!(
+      streaming(r#"\q""#),
+      Err(Err::Error(Error::new(
+        r#"\q""#,
+        ErrorKind::EscapedTransform
+      )))
+    );
+  }
+
+  #[test]
+  fn quoted() {
+    assert_eq!(
+      double_quoted::<Error<_>>().parse_complete(r#""a'b\"" c"#),
+      Ok((" c", Cow::Owned(String::from("a'b\""))))
+    );
+    assert_eq!(
+      single_quoted::<Error<_>>().parse_complete(r#"'a"b' c"#),
+      Ok((" c", Cow::Borrowed("a\"b")))
+    );
+    assert_eq!(
+      double_quoted::<Error<_>>().parse(r#""abc"#),
+      Err(Err::Incomplete(Needed::new(1)))
+    );
+    assert_eq!(
+      double_quoted::<Error<_>>().parse_complete(r#""a\qc""#),
+      Err(Err::Error(Error::new(
+        r#"\qc""#,
+        ErrorKind::EscapedTransform
+      )))
+    );
+  }
+
+  #[test]
+  fn dedent() {
+    assert!(matches!(
+      Dedent::Common.apply("\n  abc\n"),
+      Cow::Owned(s) if s == "abc"
+    ));
+    assert!(matches!(
+      Dedent::Common.apply("\nabc\ndef\n  "),
+      Cow::Borrowed("abc\ndef")
+    ));
+    assert_eq!(Dedent::Common.apply("\n\t  a\n   \n\t    b\n"), "a\n\n  b");
+    assert_eq!(Dedent::Common.apply("  "), "");
+    assert_eq!(Dedent::Common.apply("  a\r\n   b\r\n"), "a\r\n b\r");
+    assert_eq!(
+      Dedent::Margin('>').apply("  > a\n  >b\n  c\n  "),
+      " a\nb\n  c"
+    );
+    assert!(matches!(
+      Dedent::Keep.apply("\r\n a "),
+      Cow::Borrowed(" a ")
+    ));
+    assert!(matches!(Dedent::Keep.apply(" \na"), Cow::Borrowed(" \na")));
+  }
+
+  #[test]
+  fn multiline() {
+    let mut toml = multiline_string::<Error<_>>("\'\'\'", Dedent::Keep);
+    assert_eq!(
+      toml.parse_complete("\'\'\'\n  a\n  b\'\'\' = 1"),
+      Ok((" = 1", Cow::Borrowed("  a\n  b")))
+    );
+    assert_eq!(
+      toml.parse("\'\'\'\n  a"),
+      Err(Err::Incomplete(Needed::Unknown))
+    );
+  }
+
+  #[test]
+  fn indented() {
+    let mut block = dedented_block::<Error<_>>(2);
+    assert_eq!(
+      block.parse_complete("    a\n     b\n\n    c\n\n  d\n"),
+      Ok(("\n  d\n", Cow::Owned(String::from("a\n b\n\nc"))))
+    );
+    assert_eq!(
+      block.parse_complete("\n    a\n    b"),
+      Ok(("", Cow::Owned(String::from("\na\nb"))))
+    );
+    assert_eq!(block.parse_complete("   a\n"), Ok(("", Cow::Borrowed("a"))));
+    assert_eq!(
+      block.parse_complete("  a\n"),
+      Err(Err::Error(Error::new("  a\n", ErrorKind::Space)))
+    );
+    assert_eq!(
+      block.parse_complete(""),
+      Err(Err::Error(Error::new("", ErrorKind::Space)))
+    );
+    assert_eq!(
+      block.parse("    a\n    b\n"),
+      Err(Err::Incomplete(Needed::Unknown))
+    );
+    assert_eq!(
+      block.parse("    a\n    b\nc"),
+      Ok(("c", Cow::Owned(String::from("a\nb"))))
+    );
+  }
+}
@@ -0,0 +1,611 @@
+//! Helpers to test parsers
+//!
+//! The [assert_parses](crate::assert_parses), [assert_errors_at](crate::assert_errors_at)
+//! and [assert_incomplete](crate::assert_incomplete) macros apply a parser to an input
+//! and check the result. When it does not match, the panic message shows the part of
+//! the input where the parser stopped: a snippet of the text for `&str`, or a hex dump
+//! for `&[u8]`, with a marker under the position.
+//!
+//! The parser is applied with [Parser::parse](crate::Parser::parse), so parsers that
+//! are generic over the streaming mode, like those of the [character](crate::character)
+//! module, run in streaming mode.
+//!
+//! [Corpus] runs a parser over a directory of input files, and compares the results with
+//! stored expectations.
+//!
+//! ```rust
+//! use nom::{assert_errors_at, assert_incomplete, assert_parses, Needed};
+//! use nom::character::complete::{alpha1, digit1};
+//! use nom::bytes::streaming::tag;
+//! use nom::error::{Error, ErrorKind};
+//!
+//! assert_parses!(alpha1::<_, Error<_>>, "abc123", "abc");
+//! assert_parses!(alpha1::<_, Error<_>>, "abc123", "abc", "123");
+//! assert_errors_at!(digit1::<_, Error<_>>, "abc123", 0, ErrorKind::Digit);
+//! assert_incomplete!(tag::<_, _, Error<_>>("abc"), &b"ab"[..], Needed::new(1));
+//! ```
+
+use crate::error::{ErrorKind, ParseError};
+use crate::internal::{Err, IResult, Needed};
+use crate::lib::std::fmt::{self, Debug};
+use crate::lib::std::string::String;
+use crate::lib::std::vec::Vec;
+use crate::traits::{HexDisplay, Offset};
+use std::ffi::OsStr;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// Number of bytes per line in the hex dumps
+const HEX_WIDTH: usize = 16;
+
+/// Number of characters shown on each side of the position in text snippets
+const TEXT_CONTEXT: usize = 24;
+
+/// Input types that can show the part of the input around a position
+pub trait Snippet {
+  /// Returns the part of the input around `offset`, marking the position
+  fn snippet(&self, offset: usize) -> String;
+}
+
+impl Snippet for &str {
+  fn snippet(&self, offset: usize) -> String {
+    let mut start = offset.saturating_sub(TEXT_CONTEXT);
+    while !self.is_char_boundary(start) {
+      start -= 1;
+    }
+    let mut end = (offset + TEXT_CONTEXT).min(self.len());
+    while !self.is_char_boundary(end) {
+      end += 1;
+    }
+
+    // the quote opening the debug representation is not counted
+    let column = format!("{:?}", &self[start..offset]).chars().count() - 1;
+    format!("    {:?}\n    {:column$}^", &self[start..end], "")
+  }
+}
+
+impl Snippet for &[u8] {
+  fn snippet(&self, offset: usize) -> String {
+    let row = offset / HEX_WIDTH;
+    let start = row.saturating_sub(1) * HEX_WIDTH;
+
+    let mut res = String::new();
+    let dump = self.to_hex_window(offset, 1);
+    for (i, line) in dump.lines().enumerate() {
+      res.push_str("    ");
+      res.push_str(line);
+      res.push('\n');
+      if start / HEX_WIDTH + i == row {
+        let column = 3 * (offset % HEX_WIDTH);
+        res.push_str(&format!("    {:8}\t{:column$}^^\n", "", ""));
+      }
+    }
+    // the position is at the end of the input
+    if offset == self.len() && offset % HEX_WIDTH == 0 {
+      res.push_str(&format!("    {:08x}\t^^ end of input\n", offset));
+    }
+    res.pop();
+    res
+  }
+}
+
+/// Errors that record the input position and the [ErrorKind] of the failing parser
+pub trait ErrorPosition<I> {
+  /// Input position of the error
+  fn position(&self) -> &I;
+  /// Kind of the error
+  fn kind(&self) -> ErrorKind;
+}
+
+impl<I> ErrorPosition<I> for crate::error::Error<I> {
+  fn position(&self) -> &I {
+    &self.input
+  }
+
+  fn kind(&self) -> ErrorKind {
+    self.code
+  }
+}
+
+impl<I> ErrorPosition<I> for (I, ErrorKind) {
+  fn position(&self) -> &I {
+    &self.0
+  }
+
+  fn kind(&self) -> ErrorKind {
+    self.1
+  }
+}
+
+/// Checks that `result` succeeded with `expected`, and left `remaining` if it is not `None`.
+///
+/// This is called by [assert_parses](crate::assert_parses).
+#[track_caller]
+pub fn check_parses<I, O, E>(input: I, result: IResult<I, O, E>, expected: O, remaining: Option<I>)
+where
+  I: Offset + Snippet + PartialEq + Debug,
+  O: PartialEq + Debug,
+  E: Debug,
+{
+  match result {
+    Ok((rest, output)) => {
+      let offset = input.offset(&rest);
+      if output != expected {
+        panic!(
+          "unexpected parser output\n  expected: {:?}\n     found: {:?}\nthe parser stopped at offset {}:\n{}",
+          expected,
+          output,
+          offset,
+          input.snippet(offset)
+        );
+      }
+      if let Some(remaining) = remaining {
+        if rest != remaining {
+          panic!(
+            "unexpected remaining input\n  expected: {:?}\n     found: {:?}\nthe parser stopped at offset {}:\n{}",
+            remaining,
+            rest,
+            offset,
+            input.snippet(offset)
+          );
+        }
+      }
+    }
+    Err(e) => panic!(
+      "expected the parser to succeed with {:?}, got {:?}",
+      expected, e
+    ),
+  }
+}
+
+/// Checks that `result` is an error or failure of kind `kind` at `offset` in `input`.
+///
+/// This is called by [assert_errors_at](crate::assert_errors_at).
+#[track_caller]
+pub fn check_errors_at<I, O, E>(input: I, result: IResult<I, O, E>, offset: usize, kind: ErrorKind)
+where
+  I: Offset + Snippet,
+  O: Debug,
+  E: ErrorPosition<I> + ParseError<I> + Debug,
+{
+  match result {
+    Ok((rest, output)) => {
+      let at = input.offset(&rest);
+      panic!(
+        "expected an error {:?} at offset {}, the parser succeeded with {:?}, stopping at offset {}:\n{}",
+        kind,
+        offset,
+        output,
+        at,
+        input.snippet(at)
+      );
+    }
+    Err(Err::Incomplete(n)) => panic!(
+      "expected an error {:?} at offset {}, got Incomplete({:?})",
+      kind, offset, n
+    ),
+    Err(Err::Error(e)) | Err(Err::Failure(e)) => {
+      let at = input.offset(e.position());
+      if at != offset || e.kind() != kind {
+        panic!(
+          "unexpected error\n  expected: {:?} at offset {}\n     found: {:?} at offset {}\n{}\nerror: {:?}",
+          kind,
+          offset,
+          e.kind(),
+          at,
+          input.snippet(at),
+          e
+        );
+      }
+    }
+  }
+}
+
+/// Checks that `result` is `Err::Incomplete(needed)`.
+///
+/// This is called by [assert_incomplete](crate::assert_incomplete).
+#[track_caller]
+pub fn check_incomplete<I, O, E>(input: I, result: IResult<I, O, E>, needed: Needed)
+where
+  I: Offset + Snippet,
+  O: Debug,
+  E: Debug,
+{
+  match result {
+    Err(Err::Incomplete(n)) if n == needed => {}
+    Err(Err::Incomplete(n)) => panic!(
+      "unexpected needed data\n  expected: {:?}\n     found: {:?}",
+      needed, n
+    ),
+    Ok((rest, output)) => {
+      let at = input.offset(&rest);
+      panic!(
+        "expected Incomplete({:?}), the parser succeeded with {:?}, stopping at offset {}:\n{}",
+        needed,
+        output,
+        at,
+        input.snippet(at)
+      );
+    }
+    Err(e) => panic!("expected Incomplete({:?}), got {:?}", needed, e),
+  }
+}
+
+/// Runs a parser over a directory of input files, comparing its results with stored
+/// expectations
+///
+/// Every file of the directory is an input, except the `.expected` files: the expected
+/// result for `name` is stored in `name.expected`. The function given to [Corpus::run]
+/// parses an input and renders the result as a string, usually with `{:?}`, and that
+/// string is compared with the expectation.
+///
+/// In update mode, the expectations that are missing or different are written instead,
+/// to record new cases or accept a change in the grammar. A common setup enables it with
+/// an environment variable.
+///
+/// ```rust,no_run
+/// use nom::Parser;
+/// use nom::character::complete::{alpha1, digit1};
+/// use nom::error::Error;
+/// use nom::test::Corpus;
+///
+/// let report = Corpus::new("tests/corpus")
+///   .update(std::env::var_os("UPDATE_EXPECTED").is_some())
+///   .run(|input| {
+///     let input = std::str::from_utf8(input).unwrap();
+///     format!("{:?}", (alpha1::<_, Error<_>>, digit1).parse_complete(input))
+///   })
+///   .unwrap();
+///
+/// println!("{}", report);
+/// report.assert_passed();
+/// ```
+#[derive(Debug, Clone)]
+pub struct Corpus {
+  dir: PathBuf,
+  update: bool,
+}
+
+impl Corpus {
+  /// Creates a runner for the inputs of `dir`
+  pub fn new<P: Into<PathBuf>>(dir: P) -> Self {
+    Corpus {
+      dir: dir.into(),
+      update: false,
+    }
+  }
+
+  /// Writes the expectations that are missing or different instead of reporting them
+  pub fn update(mut self, update: bool) -> Self {
+    self.update = update;
+    self
+  }
+
+  /// Applies `f` to every input of the directory, sorted by name, and compares the
+  /// rendered results with the expectations
+  ///
+  /// This returns an error if the directory or a file cannot be read or written.
+  pub fn run<F>(&self, mut f: F) -> io::Result<CorpusReport>
+  where
+    F: FnMut(&[u8]) -> String,
+  {
+    let mut inputs = Vec::new();
+    for entry in fs::read_dir(&self.dir)? {
+      let path = entry?.path();
+      if path.is_file() && path.extension() != Some(OsStr::new(EXPECTED_EXTENSION)) {
+        inputs.push(path);
+      }
+    }
+    inputs.sort();
+
+    let start = Instant::now();
+    let mut cases = Vec::with_capacity(inputs.len());
+    for path in inputs {
+      let input = fs::read(&path)?;
+      let case_start = Instant::now();
+      let found = f(&input);
+      let time = case_start.elapsed();
+
+      let mut expected_path = path.clone().into_os_string();
+      expected_path.push(".");
+      expected_path.push(EXPECTED_EXTENSION);
+      let expected_path = PathBuf::from(expected_path);
+
+      let expected = match fs::read_to_string(&expected_path) {
+        Ok(expected) => Some(expected),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e),
+      };
+
+      let status = match expected {
+        Some(expected) if expected == found => CaseStatus::Passed,
+        _ if self.update => {
+          fs::write(&expected_path, &found)?;
+          CaseStatus::Updated
+        }
+        Some(expected) => CaseStatus::Failed { expected, found },
+        None => CaseStatus::Missing { found },
+      };
+
+      cases.push(CaseResult { path, status, time });
+    }
+
+    Ok(CorpusReport {
+      cases,
+      time: start.elapsed(),
+    })
+  }
+}
+
+/// Extension of the files storing the expected results of a [Corpus]
+const EXPECTED_EXTENSION: &str = "expected";
+
+/// Result of a [Corpus] run
+#[derive(Debug, Clone)]
+pub struct CorpusReport {
+  /// Results of the inputs, sorted by path
+  pub cases: Vec<CaseResult>,
+  /// Time spent in the whole run, including reading and writing the files
+  pub time: Duration,
+}
+
+/// Result of one input of a [Corpus]
+#[derive(Debug, Clone)]
+pub struct CaseResult {
+  /// Path of the input file
+  pub path: PathBuf,
+  /// Comparison with the expectation
+  pub status: CaseStatus,
+  /// Time spent in the parsing function
+  pub time: Duration,
+}
+
+/// Comparison of a [Corpus] result with its expectation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CaseStatus {
+  /// The result matches the expectation
+  Passed,
+  /// The result is different from the expectation
+  Failed {
+    /// Expected result
+    expected: String,
+    /// Rendered result
+    found: String,
+  },
+  /// There is no expectation for this input
+  Missing {
+    /// Rendered result
+    found: String,
+  },
+  /// The expectation was written in update mode
+  Updated,
+}
+
+impl CorpusReport {
+  /// Returns true if every result matches its expectation, or was updated
+  pub fn passed(&self) -> bool {
+    self.failures().next().is_none()
+  }
+
+  /// Returns the results that are failed or missing an expectation
+  pub fn failures(&self) -> impl Iterator<Item = &CaseResult> {
+    self.cases.iter().filter(|c| {
+      matches!(
+        c.status,
+        CaseStatus::Failed { .. } | CaseStatus::Missing { .. }
+      )
+    })
+  }
+
+  /// Returns the time spent parsing all the inputs
+  pub fn parse_time(&self) -> Duration {
+    self.cases.iter().map(|c| c.time).sum()
+  }
+
+  /// Panics with the expected and rendered results of the failures, if there are any
+  #[track_caller]
+  pub fn assert_passed(&self) {
+    if self.passed() {
+      return;
+    }
+
+    let mut message = String::new();
+    for case in self.failures() {
+      match &case.status {
+        CaseStatus::Failed { expected, found } => message.push_str(&format!(
+          "{}: unexpected result\n  expected: {}\n     found: {}\n",
+          case.path.display(),
+          expected,
+          found
+        )),
+        CaseStatus::Missing { found } => message.push_str(&format!(
+          "{}: no expectation\n     found: {}\n",
+          case.path.display(),
+          found
+        )),
+        _ => {}
+      }
+    }
+    panic!("{}{}", message, self);
+  }
+}
+
+impl fmt::Display for CorpusReport {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let count = |f: fn(&CaseStatus) -> bool| self.cases.iter().filter(|c| f(&c.status)).count();
+    write!(
+      f,
+      "{} inputs: {} passed, {} failed, {} missing, {} updated, parsed in {:?} ({:?} in total)",
+      self.cases.len(),
+      count(|s| matches!(s, CaseStatus::Passed)),
+      count(|s| matches!(s, CaseStatus::Failed { .. })),
+      count(|s| matches!(s, CaseStatus::Missing { .. })),
+      count(|s| matches!(s, CaseStatus::Updated)),
+      self.parse_time(),
+      self.time
+    )
+  }
+}
+
+/// Asserts that a parser succeeds on an input with the expected output, and optionally
+/// the expected remaining input.
+///
+/// See the [test module](crate::test) for an example.
+#[macro_export]
+macro_rules! assert_parses {
+  ($parser:expr, $input:expr, $expected:expr $(,)?) => {{
+    let input = $input;
+    let mut parser = $parser;
+    let result = $crate::Parser::parse(&mut parser, input);
+    $crate::test::check_parses(input, result, $expected, None);
+  }};
+  ($parser:expr, $input:expr, $expected:expr, $remaining:expr $(,)?) => {{
+    let input = $input;
+    let mut parser = $parser;
+    let result = $crate::Parser::parse(&mut parser, input);
+    $crate::test::check_parses(input, result, $expected, Some($remaining));
+  }};
+}
+
+/// Asserts that a parser returns an error or failure of the expected kind, at an offset
+/// in the input.
+///
+/// See the [test module](crate::test) for an example.
+#[macro_export]
+macro_rules! assert_errors_at {
+  ($parser:expr, $input:expr, $offset:expr, $kind:expr $(,)?) => {{
+    let input = $input;
+    let mut parser = $parser;
+    let result = $crate::Parser::parse(&mut parser, input);
+    $crate::test::check_errors_at(input, result, $offset, $kind);
+  }};
+}
+
+/// Asserts that a parser returns `Err::Incomplete` with the expected [Needed](crate::Needed).
+///
+/// See the [test module](crate::test) for an example.
+#[macro_export]
+macro_rules! assert_incomplete {
+  ($parser:expr, $input:expr, $needed:expr $(,)?) => {{
+    let input = $input;
+    let mut parser = $parser;
+    let result = $crate::Parser::parse(&mut parser, input);
+    $crate::test::check_incomplete(input, result, $needed);
+  }};
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::bytes::streaming::{tag, take};
+  use crate::character::complete::{alpha1, digit1};
+  use crate::error::Error;
+
+  #[test]
+  fn snippets() {
+    assert_eq!("abc\ndef".snippet(4), "    \"abc\\ndef\"\n          ^");
+    assert_eq!("été".snippet(2), "    \"été\"\n      ^");
+
+    let input = &b"0123456789abcdef0123456789abcdef0123456789abcdef"[..];
+    let snippet = input.snippet(20);
+    let lines: crate::lib::std::vec::Vec<_> = snippet.lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert!(lines[0].starts_with("    00000000\t30 31"));
+    assert!(lines[1].starts_with("    00000010\t30 31"));
+    assert_eq!(lines[2], "            \t            ^^");
+    assert!(lines[3].starts_with("    00000020\t30 31"));
+  }
+
+  #[test]
+  fn assertions() {
+    assert_parses!(alpha1::<_, Error<_>>, "abc123", "abc", "123");
+    assert_parses!(take::<_, _, (_, ErrorKind)>(2u8), &b"abc"[..], &b"ab"[..]);
+    assert_errors_at!(digit1::<_, (_, ErrorKind)>, "abc", 0, ErrorKind::Digit);
+    assert_errors_at!((alpha1::<_, Error<_>>, digit1), "abc;", 3, ErrorKind::Digit);
+    assert_incomplete!(tag::<_, _, Error<_>>("abc"), "a", Needed::new(2));
+  }
+
+  #[test]
+  #[should_panic(expected = "unexpected remaining input")]
+  fn parses_remaining() {
+    assert_parses!(alpha1::<_, Error<_>>, "abc123", "abc", "23");
+  }
+
+  #[test]
+  #[should_panic(expected = "found: Alpha at offset 3")]
+  fn errors_at_offset() {
+    assert_errors_at!((alpha1::<_, Error<_>>, alpha1), "abc;", 2, ErrorKind::Alpha);
+  }
+
+  #[test]
+  #[should_panic(expected = "expected Incomplete(Size(1))")]
+  fn incomplete_success() {
+    assert_incomplete!(tag::<_, _, Error<_>>("abc"), "abcd", Needed::new(1));
+  }
+
+  #[test]
+  fn corpus() {
+    use crate::Parser;
+
+    let dir = std::env::temp_dir().join(format!("nom-corpus-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("a"), "abc").unwrap();
+    fs::write(dir.join("a.expected"), "Ok((\"\", \"abc\"))").unwrap();
+    fs::write(dir.join("b"), "123").unwrap();
+    fs::write(dir.join("b.expected"), "Ok").unwrap();
+    fs::write(dir.join("c"), "de").unwrap();
+
+    let run = |corpus: Corpus| {
+      corpus
+        .run(|input| {
+          format!(
+            "{:?}",
+            alpha1::<_, (&[u8], ErrorKind)>
+              .parse(input)
+              .map(|(i, o)| (
+                crate::lib::std::str::from_utf8(i).unwrap(),
+                crate::lib::std::str::from_utf8(o).unwrap()
+              ))
+              .map_err(|_| ())
+          )
+        })
+        .unwrap()
+    };
+
+    let report = run(Corpus::new(&dir));
+    let status: Vec<_> = report.cases.iter().map(|c| c.status.clone()).collect();
+    assert_eq!(
+      status,
+      [
+        CaseStatus::Passed,
+        CaseStatus::Failed {
+          expected: String::from("Ok"),
+          found: String::from("Err(())")
+        },
+        CaseStatus::Missing {
+          found: String::from("Ok((\"\", \"de\"))")
+        },
+      ]
+    );
+    assert!(!report.passed());
+    assert_eq!(report.failures().count(), 2);
+    assert!(report
+      .to_string()
+      .starts_with("3 inputs: 1 passed, 1 failed, 1 missing, 0 updated"));
+
+    let report = run(Corpus::new(&dir).update(true));
+    assert!(report.passed());
+    assert_eq!(
+      fs::read_to_string(dir.join("c.expected")).unwrap(),
+      "Ok((\"\", \"de\"))"
+    );
+    let report = run(Corpus::new(&dir));
+    assert!(report.cases.iter().all(|c| c.status == CaseStatus::Passed));
+    report.assert_passed();
+
+    fs::remove_dir_all(&dir).unwrap();
+  }
+}
@@ -4,9 +4,10 @@ use core::str::CharIndices;
 
 use crate::error::{ErrorKind, ParseError};
 use crate::internal::{Err, IResult, Needed};
+use crate::lib::std::fmt;
 use crate::lib::std::iter::Copied;
 use crate::lib::std::ops::{
-  Bound, Range, RangeBounds, RangeFrom, RangeFull, RangeInclusive, RangeTo, RangeToInclusive,
+  Bound, Deref, Range, RangeBounds, RangeFrom, RangeFull, RangeInclusive, RangeTo, RangeToInclusive,
 };
 use crate::lib::std::slice::Iter;
 use crate::lib::std::str::from_utf8;
@@ -14,7 +15,10 @@ use crate::lib::std::str::Chars;
 use crate::lib::std::str::FromStr;
 use crate::IsStreaming;
 use crate::Mode;
+use core::cell::{Cell, RefCell};
 
+#[cfg(feature = "alloc")]
+use crate::lib::std::borrow::{Cow, ToOwned};
 #[cfg(feature = "alloc")]
 use crate::lib::std::string::String;
 #[cfg(feature = "alloc")]
@@ -59,6 +63,16 @@ pub trait Input: Clone + Sized {
   /// Get the byte offset from the element's position in the stream
   fn slice_index(&self, count: usize) -> Result<usize, Needed>;
 
+  /// Indicates whether more data may follow this input, in which case parsers return
+  /// [Err::Incomplete] when they need more data
+  ///
+  /// Most inputs leave it to the [OutputMode](crate::OutputMode) of the parser, while
+  /// [Partial] carries it in the input.
+  #[inline]
+  fn is_streaming<OM: crate::OutputMode>(&self) -> bool {
+    OM::Incomplete::is_streaming()
+  }
+
   /// Looks for the first element of the input type for which the condition returns true,
   /// and returns the input up to this position.
   ///
@@ -148,7 +162,7 @@ pub trait Input: Clone + Sized {
     match self.position(predicate) {
       Some(n) => Ok((self.take_from(n), OM::Output::bind(|| self.take(n)))),
       None => {
-        if OM::Incomplete::is_streaming() {
+        if self.is_streaming::<OM>() {
           Err(Err::Incomplete(Needed::new(1)))
         } else {
           let len = self.input_len();
@@ -173,7 +187,7 @@ pub trait Input: Clone + Sized {
       }))),
       Some(n) => Ok((self.take_from(n), OM::Output::bind(|| self.take(n)))),
       None => {
-        if OM::Incomplete::is_streaming() {
+        if self.is_streaming::<OM>() {
           Err(Err::Incomplete(Needed::new(1)))
         } else {
           let len = self.input_len();
@@ -462,7 +476,10 @@ impl<'a> Input for &'a str {
         let (str1, str2) = self.split_at(i);
         Ok((str2, str1))
       }
-      None => Ok(self.split_at(0)),
+      None => {
+        let (str1, str2) = self.split_at(self.len());
+        Ok((str2, str1))
+      }
     }
   }
 
@@ -565,178 +582,1395 @@ impl<'a> Input for &'a str {
   }
 }
 
-/// Useful functions to calculate the offset between slices and show a hexdump of a slice
-pub trait Offset {
-  /// Offset between the first byte of self and the first byte of the argument
-  /// the argument must be a part of self, otherwise this can fail with arithmetic
-  /// underflows as it compares byte offsets
-  fn offset(&self, second: &Self) -> usize;
+/// Character decoded from UTF-16 input, the element of [Utf16]
+///
+/// Surrogate pairs are decoded as one character. An unpaired surrogate is decoded as
+/// U+FFFD, the replacement character, with a length of 1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Utf16Char {
+  c: char,
+  len: usize,
 }
 
-impl Offset for [u8] {
-  fn offset(&self, second: &Self) -> usize {
-    let fst = self.as_ptr();
-    let snd = second.as_ptr();
+impl Utf16Char {
+  fn decode(units: &[u16]) -> Option<Utf16Char> {
+    let c = core::char::decode_utf16(units.iter().take(2).copied()).next()?;
+    Some(match c {
+      Ok(c) => Utf16Char {
+        c,
+        len: c.len_utf16(),
+      },
+      Err(_) => Utf16Char {
+        c: core::char::REPLACEMENT_CHARACTER,
+        len: 1,
+      },
+    })
+  }
+}
 
-    snd as usize - fst as usize
+/// Iterator over the characters of [Utf16]
+pub struct Utf16Chars<'a> {
+  units: &'a [u16],
+}
+
+impl<'a> Iterator for Utf16Chars<'a> {
+  type Item = Utf16Char;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let c = Utf16Char::decode(self.units)?;
+    self.units = &self.units[c.len..];
+    Some(c)
   }
 }
 
-impl<'a> Offset for &'a [u8] {
-  fn offset(&self, second: &Self) -> usize {
-    let fst = self.as_ptr();
-    let snd = second.as_ptr();
+/// Iterator over the characters of [Utf16] and their positions, counted in code units
+pub struct Utf16CharIndices<'a> {
+  offset: usize,
+  chars: Utf16Chars<'a>,
+}
 
-    snd as usize - fst as usize
+impl<'a> Iterator for Utf16CharIndices<'a> {
+  type Item = (usize, Utf16Char);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let c = self.chars.next()?;
+    let index = self.offset;
+    self.offset += c.len;
+    Some((index, c))
   }
 }
 
-impl Offset for str {
-  fn offset(&self, second: &Self) -> usize {
-    let fst = self.as_ptr();
-    let snd = second.as_ptr();
+/// UTF-16 input, like Windows wide strings
+///
+/// This wraps a `&[u16]` of code units. The elements are the decoded characters, and
+/// positions are counted in code units, so a character outside of the basic multilingual
+/// plane has a length of 2. `&[u16]` does not implement [Input] directly, because a second
+/// slice implementation would prevent the element type of `&[0x00][..]` from being
+/// inferred as `u8`.
+///
+/// ```rust
+/// # use nom::{error::Error, Parser};
+/// use nom::Utf16;
+/// use nom::bytes::complete::tag;
+/// use nom::character::complete::alpha1;
+///
+/// let units: Vec<u16> = "MZ\u{1F600}header".encode_utf16().collect();
+/// let input = Utf16::new(&units);
+///
+/// let (rest, _) = tag::<_, _, Error<_>>("MZ\u{1F600}").parse(input).unwrap();
+/// let (_, name) = alpha1::<_, Error<_>>(rest).unwrap();
+/// assert_eq!(name.as_slice(), &units[4..]);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Utf16<'a>(&'a [u16]);
 
-    snd as usize - fst as usize
+impl<'a> Utf16<'a> {
+  /// Wraps a slice of UTF-16 code units
+  pub fn new(units: &'a [u16]) -> Self {
+    Utf16(units)
+  }
+
+  /// Returns the wrapped code units
+  pub fn as_slice(&self) -> &'a [u16] {
+    self.0
   }
 }
 
-impl<'a> Offset for &'a str {
-  fn offset(&self, second: &Self) -> usize {
-    let fst = self.as_ptr();
-    let snd = second.as_ptr();
+impl<'a> Input for Utf16<'a> {
+  type Item = Utf16Char;
+  type Iter = Utf16Chars<'a>;
+  type IterIndices = Utf16CharIndices<'a>;
 
-    snd as usize - fst as usize
+  fn input_len(&self) -> usize {
+    self.0.len()
+  }
+
+  #[inline]
+  fn take(&self, index: usize) -> Self {
+    Utf16(&self.0[0..index])
+  }
+
+  #[inline]
+  fn take_from(&self, index: usize) -> Self {
+    Utf16(&self.0[index..])
+  }
+
+  #[inline]
+  fn take_split(&self, index: usize) -> (Self, Self) {
+    let (prefix, suffix) = self.0.split_at(index);
+    (Utf16(suffix), Utf16(prefix))
+  }
+
+  fn position<P>(&self, predicate: P) -> Option<usize>
+  where
+    P: Fn(Self::Item) -> bool,
+  {
+    self
+      .iter_indices()
+      .find(|(_, c)| predicate(*c))
+      .map(|(index, _)| index)
+  }
+
+  #[inline]
+  fn iter_elements(&self) -> Self::Iter {
+    Utf16Chars { units: self.0 }
+  }
+
+  #[inline]
+  fn iter_indices(&self) -> Self::IterIndices {
+    Utf16CharIndices {
+      offset: 0,
+      chars: self.iter_elements(),
+    }
+  }
+
+  fn slice_index(&self, count: usize) -> Result<usize, Needed> {
+    let mut cnt = 0;
+    for (index, _) in self.iter_indices() {
+      if cnt == count {
+        return Ok(index);
+      }
+      cnt += 1;
+    }
+    if cnt == count {
+      return Ok(self.0.len());
+    }
+    Err(Needed::Unknown)
   }
 }
 
-/// Helper trait for types that can be viewed as a byte slice
-pub trait AsBytes {
-  /// Casts the input type to a byte slice
-  fn as_bytes(&self) -> &[u8];
+/// Input split over several byte slices, like vectored I/O buffers or the two halves of
+/// a ring buffer
+///
+/// This wraps a slice of segments, which can be `&[u8]`, [std::io::IoSlice], `Vec<u8>`, or
+/// any type dereferencing to `[u8]`, and parses them as if they were concatenated, without
+/// copying them. A token can span several segments. Positions are counted in bytes from the
+/// start of the first segment, and the parsed parts are returned as `Segmented` too: use
+/// [Segmented::segments] to get their bytes, or [Segmented::as_contiguous] if they fit in
+/// one segment.
+///
+/// ```rust
+/// # use nom::{error::Error, Parser};
+/// use std::io::IoSlice;
+/// use nom::Segmented;
+/// use nom::bytes::complete::{tag, take_until};
+/// use nom::number::complete::be_u32;
+///
+/// let header = [b'P', b'K', 0, 0];
+/// let body = *b"\x01\x02name\r\nrest";
+/// let buffers = [IoSlice::new(&header), IoSlice::new(&body)];
+/// let input = Segmented::new(&buffers);
+///
+/// let (rest, (_, length)) = (tag::<_, _, Error<_>>("PK"), be_u32).parse(input).unwrap();
+/// assert_eq!(length, 0x0102);
+/// let (rest, name) = take_until::<_, _, Error<_>>("\r\n").parse(rest).unwrap();
+/// assert_eq!(name.as_contiguous(), Some(&b"name"[..]));
+/// assert_eq!(rest.segments().collect::<Vec<_>>(), [&b"\r\nrest"[..]]);
+/// ```
+pub struct Segmented<'a, S = &'a [u8]> {
+  /// remaining part of the current segment
+  first: &'a [u8],
+  /// segments following the current one
+  rest: &'a [S],
+  /// number of bytes of the input, which can end before the last segment
+  len: usize,
+  /// position of the input from the start of the first segment
+  position: usize,
 }
 
-impl<'a> AsBytes for &'a str {
-  #[inline(always)]
-  fn as_bytes(&self) -> &[u8] {
-    (*self).as_bytes()
+impl<'a, S: Deref<Target = [u8]>> Segmented<'a, S> {
+  /// Wraps a slice of segments
+  pub fn new(segments: &'a [S]) -> Self {
+    Segmented {
+      first: &[],
+      rest: segments,
+      len: segments.iter().map(|s| s.len()).sum(),
+      position: 0,
+    }
+  }
+
+  /// Returns an iterator over the non empty parts of the segments covered by the input
+  pub fn segments(&self) -> Segments<'a, S> {
+    Segments {
+      first: self.first,
+      rest: self.rest,
+      remaining: self.len,
+    }
+  }
+
+  /// Returns the input as one slice, if it does not span several segments
+  pub fn as_contiguous(&self) -> Option<&'a [u8]> {
+    let mut segments = self.segments();
+    match (segments.next(), segments.next()) {
+      (None, _) => Some(&[]),
+      (Some(segment), None) => Some(segment),
+      (Some(_), Some(_)) => None,
+    }
   }
 }
 
-impl AsBytes for str {
-  #[inline(always)]
-  fn as_bytes(&self) -> &[u8] {
-    self.as_ref()
+impl<S> Clone for Segmented<'_, S> {
+  fn clone(&self) -> Self {
+    *self
   }
 }
 
-impl<'a> AsBytes for &'a [u8] {
-  #[inline(always)]
-  fn as_bytes(&self) -> &[u8] {
-    self
+impl<S> Copy for Segmented<'_, S> {}
+
+impl<S: Deref<Target = [u8]>> fmt::Debug for Segmented<'_, S> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_tuple("Segmented")
+      .field(&SegmentsDebug(self.segments()))
+      .finish()
   }
 }
 
-impl AsBytes for [u8] {
-  #[inline(always)]
-  fn as_bytes(&self) -> &[u8] {
-    self
+struct SegmentsDebug<'a, S>(Segments<'a, S>);
+
+impl<S: Deref<Target = [u8]>> fmt::Debug for SegmentsDebug<'_, S> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_list().entries(self.0.clone()).finish()
   }
 }
 
-impl<'a, const N: usize> AsBytes for &'a [u8; N] {
-  #[inline(always)]
-  fn as_bytes(&self) -> &[u8] {
-    self.as_slice()
+impl<S: Deref<Target = [u8]>> PartialEq for Segmented<'_, S> {
+  fn eq(&self, other: &Self) -> bool {
+    self.len == other.len && self.iter_elements().eq(other.iter_elements())
   }
 }
 
-impl<const N: usize> AsBytes for [u8; N] {
-  #[inline(always)]
-  fn as_bytes(&self) -> &[u8] {
-    self
+impl<S: Deref<Target = [u8]>> Eq for Segmented<'_, S> {}
+
+/// Iterator over the segments of [Segmented]
+pub struct Segments<'a, S> {
+  first: &'a [u8],
+  rest: &'a [S],
+  remaining: usize,
+}
+
+impl<S> Clone for Segments<'_, S> {
+  fn clone(&self) -> Self {
+    Segments {
+      first: self.first,
+      rest: self.rest,
+      remaining: self.remaining,
+    }
   }
 }
 
-/// Transforms common types to a char for basic token parsing
-#[allow(clippy::len_without_is_empty)]
-pub trait AsChar: Copy {
-  /// makes a char from self
-  fn as_char(self) -> char;
+impl<'a, S: Deref<Target = [u8]>> Iterator for Segments<'a, S> {
+  type Item = &'a [u8];
 
-  /// Tests that self is an alphabetic character
-  ///
-  /// Warning: for `&str` it recognizes alphabetic
-  /// characters outside of the 52 ASCII letters
-  fn is_alpha(self) -> bool;
+  fn next(&mut self) -> Option<Self::Item> {
+    while self.remaining > 0 {
+      if !self.first.is_empty() {
+        let len = self.first.len().min(self.remaining);
+        let segment = &self.first[..len];
+        self.first = &[];
+        self.remaining -= len;
+        return Some(segment);
+      }
+      let (segment, rest) = self.rest.split_first()?;
+      self.first = segment;
+      self.rest = rest;
+    }
+    None
+  }
+}
 
-  /// Tests that self is an alphabetic character
-  /// or a decimal digit
-  fn is_alphanum(self) -> bool;
-  /// Tests that self is a decimal digit
-  fn is_dec_digit(self) -> bool;
-  /// Tests that self is an hex digit
-  fn is_hex_digit(self) -> bool;
-  /// Tests that self is an octal digit
-  fn is_oct_digit(self) -> bool;
-  /// Tests that self is a binary digit
-  fn is_bin_digit(self) -> bool;
-  /// Gets the len in bytes for self
-  fn len(self) -> usize;
-  /// Tests that self is ASCII space or tab
-  fn is_space(self) -> bool;
-  /// Tests if byte is ASCII newline: \n
-  fn is_newline(self) -> bool;
+/// Iterator over the bytes of [Segmented]
+pub struct SegmentedBytes<'a, S> {
+  current: Iter<'a, u8>,
+  rest: &'a [S],
+  remaining: usize,
 }
 
-impl AsChar for u8 {
-  #[inline]
-  fn as_char(self) -> char {
-    self as char
+impl<S: Deref<Target = [u8]>> Iterator for SegmentedBytes<'_, S> {
+  type Item = u8;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.remaining == 0 {
+      return None;
+    }
+    loop {
+      if let Some(b) = self.current.next() {
+        self.remaining -= 1;
+        return Some(*b);
+      }
+      let (segment, rest) = self.rest.split_first()?;
+      self.current = segment.iter();
+      self.rest = rest;
+    }
   }
-  #[inline]
-  fn is_alpha(self) -> bool {
-    matches!(self, 0x41..=0x5A | 0x61..=0x7A)
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (self.remaining, Some(self.remaining))
   }
-  #[inline]
-  fn is_alphanum(self) -> bool {
-    self.is_alpha() || self.is_dec_digit()
+}
+
+impl<'a, S: Deref<Target = [u8]>> Input for Segmented<'a, S> {
+  type Item = u8;
+  type Iter = SegmentedBytes<'a, S>;
+  type IterIndices = Enumerate<Self::Iter>;
+
+  fn input_len(&self) -> usize {
+    self.len
   }
+
   #[inline]
-  fn is_dec_digit(self) -> bool {
-    matches!(self, 0x30..=0x39)
+  fn take(&self, index: usize) -> Self {
+    assert!(index <= self.len, "index out of range for Segmented input");
+    Segmented {
+      len: index,
+      ..*self
+    }
   }
-  #[inline]
-  fn is_hex_digit(self) -> bool {
-    matches!(self, 0x30..=0x39 | 0x41..=0x46 | 0x61..=0x66)
+
+  fn take_from(&self, index: usize) -> Self {
+    assert!(index <= self.len, "index out of range for Segmented input");
+    let mut first = self.first;
+    let mut rest = self.rest;
+    let mut skip = index;
+    while skip > first.len() {
+      skip -= first.len();
+      // the total length was checked above, so there is a following segment
+      let (segment, tail) = rest.split_first().unwrap();
+      first = segment;
+      rest = tail;
+    }
+    Segmented {
+      first: &first[skip..],
+      rest,
+      len: self.len - index,
+      position: self.position + index,
+    }
   }
+
   #[inline]
-  fn is_oct_digit(self) -> bool {
-    matches!(self, 0x30..=0x37)
+  fn take_split(&self, index: usize) -> (Self, Self) {
+    (self.take_from(index), self.take(index))
   }
+
   #[inline]
-  fn is_bin_digit(self) -> bool {
-    matches!(self, 0x30..=0x31)
+  fn position<P>(&self, predicate: P) -> Option<usize>
+  where
+    P: Fn(Self::Item) -> bool,
+  {
+    self.iter_elements().position(predicate)
   }
+
   #[inline]
-  fn len(self) -> usize {
-    1
+  fn iter_elements(&self) -> Self::Iter {
+    SegmentedBytes {
+      current: self.first.iter(),
+      rest: self.rest,
+      remaining: self.len,
+    }
   }
+
   #[inline]
-  fn is_space(self) -> bool {
-    self == b' ' || self == b'\t'
+  fn iter_indices(&self) -> Self::IterIndices {
+    self.iter_elements().enumerate()
   }
-  fn is_newline(self) -> bool {
-    self == b'\n'
+
+  #[inline]
+  fn slice_index(&self, count: usize) -> Result<usize, Needed> {
+    if self.len >= count {
+      Ok(count)
+    } else {
+      Err(Needed::new(count - self.len))
+    }
   }
 }
-impl<'a> AsChar for &'a u8 {
-  #[inline]
-  fn as_char(self) -> char {
-    *self as char
+
+#[cfg(feature = "bytes")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "bytes")))]
+/// [bytes::Bytes] is parsed without copying: the parts returned by parsers are
+/// reference counted slices of the same buffer, which can be kept after the input is
+/// dropped.
+///
+/// `BytesMut` does not implement [Input], because it cannot share its buffer without a
+/// mutable reference. [bytes::BytesMut::freeze] converts it to `Bytes` without copying.
+///
+/// ```rust
+/// # use nom::{error::Error, Parser};
+/// use bytes::Bytes;
+/// use nom::bytes::complete::{tag, take_until};
+///
+/// let input = Bytes::from_static(b"GET /index.html HTTP/1.1\r\n");
+/// let (_, path) = (tag::<_, _, Error<_>>("GET "), take_until("HTTP"))
+///   .map(|(_, path)| path)
+///   .parse(input.clone())
+///   .unwrap();
+/// drop(input);
+/// assert_eq!(path, Bytes::from_static(b"/index.html "));
+/// ```
+impl Input for bytes::Bytes {
+  type Item = u8;
+  type Iter = bytes::buf::IntoIter<bytes::Bytes>;
+  type IterIndices = Enumerate<Self::Iter>;
+
+  fn input_len(&self) -> usize {
+    self.len()
   }
+
   #[inline]
-  fn is_alpha(self) -> bool {
-    matches!(*self, 0x41..=0x5A | 0x61..=0x7A)
+  fn take(&self, index: usize) -> Self {
+    self.slice(..index)
+  }
+
+  #[inline]
+  fn take_from(&self, index: usize) -> Self {
+    self.slice(index..)
+  }
+
+  #[inline]
+  fn take_split(&self, index: usize) -> (Self, Self) {
+    (self.slice(index..), self.slice(..index))
+  }
+
+  #[inline]
+  fn position<P>(&self, predicate: P) -> Option<usize>
+  where
+    P: Fn(Self::Item) -> bool,
+  {
+    self.iter().position(|b| predicate(*b))
+  }
+
+  #[inline]
+  fn iter_elements(&self) -> Self::Iter {
+    self.clone().into_iter()
+  }
+
+  #[inline]
+  fn iter_indices(&self) -> Self::IterIndices {
+    self.iter_elements().enumerate()
+  }
+
+  #[inline]
+  fn slice_index(&self, count: usize) -> Result<usize, Needed> {
+    if self.len() >= count {
+      Ok(count)
+    } else {
+      Err(Needed::new(count - self.len()))
+    }
+  }
+}
+
+/// Input wrapper carrying whether more data may follow, so that a single grammar can be
+/// used on partial and complete data
+///
+/// nom has `streaming` and `complete` versions of most parsers. The parsers of the parent
+/// modules, like [tag](crate::bytes::tag) or [take_while](crate::bytes::take_while), take
+/// their behaviour from the [OutputMode](crate::OutputMode) given by the caller instead. With
+/// a `Partial` input, they take it from the input: they return [Err::Incomplete] if it may be
+/// followed by more data, and errors once it is marked complete with [Partial::complete],
+/// whether they are called through [Parser::parse](crate::Parser::parse) or
+/// [Parser::parse_complete](crate::Parser::parse_complete).
+///
+/// Parsers written for the wrapped input type can be used inside such a grammar with
+/// [on_partial](crate::combinator::on_partial).
+///
+/// ```rust
+/// # use nom::{Err, error::{Error, ErrorKind}, Needed, Parser};
+/// use nom::Partial;
+/// use nom::bytes::{tag, take_while1};
+///
+/// fn word<'a>() -> impl Parser<Partial<&'a str>, Output = Partial<&'a str>, Error = Error<Partial<&'a str>>> {
+///   (take_while1(|c: char| c.is_alphabetic()), tag(";")).map(|(word, _)| word)
+/// }
+///
+/// // more data can follow
+/// assert_eq!(word().parse(Partial::new("abc")), Err(Err::Incomplete(Needed::new(1))));
+///
+/// // the whole input is there
+/// let input = Partial::new("abc").complete();
+/// assert_eq!(
+///   word().parse(input),
+///   Err(Err::Error(Error::new(Partial::new("").complete(), ErrorKind::Tag)))
+/// );
+/// let (_, w) = word().parse(Partial::new("abc;").complete()).unwrap();
+/// assert_eq!(w.into_inner(), "abc");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Partial<I> {
+  input: I,
+  partial: bool,
+}
+
+impl<I> Partial<I> {
+  /// Wraps an input which can be followed by more data
+  pub fn new(input: I) -> Self {
+    Partial {
+      input,
+      partial: true,
+    }
+  }
+
+  /// Marks the input as complete, as when the last chunk of data was received
+  pub fn complete(self) -> Self {
+    Partial {
+      input: self.input,
+      partial: false,
+    }
+  }
+
+  /// Returns `true` if more data may follow this input
+  pub fn is_partial(&self) -> bool {
+    self.partial
+  }
+
+  /// Returns the wrapped input
+  pub fn into_inner(self) -> I {
+    self.input
+  }
+
+  /// Applies `f` to the wrapped input, keeping the completeness
+  pub fn map<U, F: FnOnce(I) -> U>(self, f: F) -> Partial<U> {
+    Partial {
+      input: f(self.input),
+      partial: self.partial,
+    }
+  }
+}
+
+impl<I> Deref for Partial<I> {
+  type Target = I;
+
+  fn deref(&self) -> &I {
+    &self.input
+  }
+}
+
+impl<I: fmt::Display> fmt::Display for Partial<I> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    self.input.fmt(f)
+  }
+}
+
+impl<I: Input> Input for Partial<I> {
+  type Item = I::Item;
+  type Iter = I::Iter;
+  type IterIndices = I::IterIndices;
+
+  #[inline]
+  fn input_len(&self) -> usize {
+    self.input.input_len()
+  }
+
+  #[inline]
+  fn take(&self, index: usize) -> Self {
+    Partial {
+      input: self.input.take(index),
+      partial: self.partial,
+    }
+  }
+
+  #[inline]
+  fn take_from(&self, index: usize) -> Self {
+    Partial {
+      input: self.input.take_from(index),
+      partial: self.partial,
+    }
+  }
+
+  #[inline]
+  fn take_split(&self, index: usize) -> (Self, Self) {
+    let (suffix, prefix) = self.input.take_split(index);
+    (
+      Partial {
+        input: suffix,
+        partial: self.partial,
+      },
+      Partial {
+        input: prefix,
+        partial: self.partial,
+      },
+    )
+  }
+
+  #[inline]
+  fn position<P>(&self, predicate: P) -> Option<usize>
+  where
+    P: Fn(Self::Item) -> bool,
+  {
+    self.input.position(predicate)
+  }
+
+  #[inline]
+  fn iter_elements(&self) -> Self::Iter {
+    self.input.iter_elements()
+  }
+
+  #[inline]
+  fn iter_indices(&self) -> Self::IterIndices {
+    self.input.iter_indices()
+  }
+
+  #[inline]
+  fn slice_index(&self, count: usize) -> Result<usize, Needed> {
+    self.input.slice_index(count)
+  }
+
+  #[inline]
+  fn is_streaming<OM: crate::OutputMode>(&self) -> bool {
+    self.partial
+  }
+}
+
+/// Input wrapper giving the parsers access to a mutable state, like an interner, a symbol
+/// table or feature flags
+///
+/// The state is borrowed from a [RefCell] by all the copies of the input, so the changes
+/// made by a parser are seen by the parsers applied after it.
+/// [with_state](crate::combinator::with_state) and
+/// [map_with_state](crate::combinator::map_with_state) give access to it in a grammar. The
+/// other input traits are implemented by the wrapped input, so the parsers of the crate can
+/// be applied to a `Stateful` input, and return `Stateful` slices.
+///
+/// The changes are not undone when a parser backtracks, unless it is wrapped with
+/// [atomic](crate::combinator::atomic): the state is then cloned before the parser runs, and
+/// restored if it fails.
+///
+/// ```rust
+/// # use nom::{IResult, Parser};
+/// use nom::Stateful;
+/// use nom::character::complete::{alpha1, char};
+/// use nom::combinator::map_with_state;
+/// use nom::multi::separated_list1;
+/// use std::cell::RefCell;
+/// use std::collections::HashMap;
+///
+/// type Input<'a, 's> = Stateful<'s, &'a str, HashMap<&'a str, usize>>;
+///
+/// // interns the identifiers, returning their index
+/// fn symbol<'a, 's>(i: Input<'a, 's>) -> IResult<Input<'a, 's>, usize> {
+///   map_with_state(alpha1, |name: Input<'a, 's>, symbols| {
+///     let next = symbols.len();
+///     *symbols.entry(name.into_inner()).or_insert(next)
+///   })
+///   .parse(i)
+/// }
+///
+/// let symbols = RefCell::new(HashMap::new());
+/// let (_, indexes) = separated_list1(char(','), symbol)
+///   .parse(Stateful::new("a,b,a,c", &symbols))
+///   .unwrap();
+/// assert_eq!(indexes, [0, 1, 0, 2]);
+/// assert_eq!(symbols.borrow().len(), 3);
+/// ```
+pub struct Stateful<'s, I, S> {
+  input: I,
+  state: &'s RefCell<S>,
+}
+
+impl<'s, I, S> Stateful<'s, I, S> {
+  /// Wraps an input, with the state the parsers will use
+  pub fn new(input: I, state: &'s RefCell<S>) -> Self {
+    Stateful { input, state }
+  }
+
+  /// Returns the state
+  pub fn state(&self) -> &'s RefCell<S> {
+    self.state
+  }
+
+  /// Returns the wrapped input
+  pub fn into_inner(self) -> I {
+    self.input
+  }
+
+  fn with_input(&self, input: I) -> Self {
+    Stateful {
+      input,
+      state: self.state,
+    }
+  }
+}
+
+impl<I: Clone, S> Clone for Stateful<'_, I, S> {
+  fn clone(&self) -> Self {
+    self.with_input(self.input.clone())
+  }
+}
+
+impl<I: Copy, S> Copy for Stateful<'_, I, S> {}
+
+/// Only the inputs are compared, the states must be the same
+impl<I: PartialEq, S> PartialEq for Stateful<'_, I, S> {
+  fn eq(&self, other: &Self) -> bool {
+    self.input == other.input && core::ptr::eq(self.state, other.state)
+  }
+}
+
+/// Only the input is shown, since the state may be borrowed
+impl<I: fmt::Debug, S> fmt::Debug for Stateful<'_, I, S> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_tuple("Stateful").field(&self.input).finish()
+  }
+}
+
+impl<I, S> Deref for Stateful<'_, I, S> {
+  type Target = I;
+
+  fn deref(&self) -> &I {
+    &self.input
+  }
+}
+
+impl<I: fmt::Display, S> fmt::Display for Stateful<'_, I, S> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    self.input.fmt(f)
+  }
+}
+
+impl<I: Input, S> Input for Stateful<'_, I, S> {
+  type Item = I::Item;
+  type Iter = I::Iter;
+  type IterIndices = I::IterIndices;
+
+  #[inline]
+  fn input_len(&self) -> usize {
+    self.input.input_len()
+  }
+
+  #[inline]
+  fn take(&self, index: usize) -> Self {
+    self.with_input(self.input.take(index))
+  }
+
+  #[inline]
+  fn take_from(&self, index: usize) -> Self {
+    self.with_input(self.input.take_from(index))
+  }
+
+  #[inline]
+  fn take_split(&self, index: usize) -> (Self, Self) {
+    let (suffix, prefix) = self.input.take_split(index);
+    (self.with_input(suffix), self.with_input(prefix))
+  }
+
+  #[inline]
+  fn position<P>(&self, predicate: P) -> Option<usize>
+  where
+    P: Fn(Self::Item) -> bool,
+  {
+    self.input.position(predicate)
+  }
+
+  #[inline]
+  fn iter_elements(&self) -> Self::Iter {
+    self.input.iter_elements()
+  }
+
+  #[inline]
+  fn iter_indices(&self) -> Self::IterIndices {
+    self.input.iter_indices()
+  }
+
+  #[inline]
+  fn slice_index(&self, count: usize) -> Result<usize, Needed> {
+    self.input.slice_index(count)
+  }
+
+  #[inline]
+  fn is_streaming<OM: crate::OutputMode>(&self) -> bool {
+    self.input.is_streaming::<OM>()
+  }
+}
+
+/// Amount of work left for the parsers applied on a [Fueled] input
+///
+/// Each access to a `Fueled` input burns one unit of fuel. Once there is none left, the
+/// fuel is exhausted, and stays so until it is refilled.
+#[derive(Debug)]
+pub struct Fuel {
+  remaining: Cell<usize>,
+  exhausted: Cell<bool>,
+}
+
+impl Fuel {
+  /// Creates `amount` units of fuel
+  pub fn new(amount: usize) -> Self {
+    Fuel {
+      remaining: Cell::new(amount),
+      exhausted: Cell::new(false),
+    }
+  }
+
+  /// Units of fuel left
+  pub fn remaining(&self) -> usize {
+    self.remaining.get()
+  }
+
+  /// Returns `true` if an access to the input was denied because there was no fuel left
+  pub fn is_exhausted(&self) -> bool {
+    self.exhausted.get()
+  }
+
+  /// Sets the fuel to `amount` units
+  pub fn refill(&self, amount: usize) {
+    self.remaining.set(amount);
+    self.exhausted.set(false);
+  }
+
+  /// Burns one unit of fuel, returns `false` if there is none left
+  #[inline]
+  fn burn(&self) -> bool {
+    match self.remaining.get() {
+      0 => {
+        self.exhausted.set(true);
+        false
+      }
+      remaining => {
+        self.remaining.set(remaining - 1);
+        true
+      }
+    }
+  }
+}
+
+/// Input wrapper bounding the work done by the parsers, for untrusted input
+///
+/// Every access of a parser to the input, like getting its length, comparing it to a tag
+/// or searching for a character, burns one unit of a shared [Fuel]. A parser scanning
+/// the input, like `take_while`, burns fuel for each call, not for each element. Once the
+/// fuel is exhausted, the input looks empty and streaming to the parsers, so they all
+/// return [Err::Incomplete](crate::Err::Incomplete) or an error without doing more work,
+/// and the parse unwinds quickly, even through backtracking combinators.
+/// [with_fuel](crate::combinator::with_fuel) refills the fuel, and turns the result of a
+/// parse that exhausted it into a failure with [ErrorKind::Budget].
+///
+/// The other input traits are implemented by the wrapped input, so the parsers of the
+/// crate can be applied to a `Fueled` input, and return `Fueled` slices.
+pub struct Fueled<'f, I> {
+  input: I,
+  fuel: &'f Fuel,
+}
+
+impl<'f, I> Fueled<'f, I> {
+  /// Wraps an input, with the fuel the parsers will burn
+  pub fn new(input: I, fuel: &'f Fuel) -> Self {
+    Fueled { input, fuel }
+  }
+
+  /// Returns the fuel
+  pub fn fuel(&self) -> &'f Fuel {
+    self.fuel
+  }
+
+  /// Returns the wrapped input
+  pub fn into_inner(self) -> I {
+    self.input
+  }
+
+  fn with_input(&self, input: I) -> Self {
+    Fueled {
+      input,
+      fuel: self.fuel,
+    }
+  }
+}
+
+impl<I: Input> Fueled<'_, I> {
+  /// Returns the wrapped input if there is fuel left, an empty input otherwise
+  #[inline]
+  fn view(&self) -> I {
+    if self.fuel.burn() {
+      self.input.clone()
+    } else {
+      self.input.take(0)
+    }
+  }
+}
+
+impl<I: Clone> Clone for Fueled<'_, I> {
+  fn clone(&self) -> Self {
+    self.with_input(self.input.clone())
+  }
+}
+
+impl<I: Copy> Copy for Fueled<'_, I> {}
+
+/// Only the inputs are compared, the fuels must be the same
+impl<I: PartialEq> PartialEq for Fueled<'_, I> {
+  fn eq(&self, other: &Self) -> bool {
+    self.input == other.input && core::ptr::eq(self.fuel, other.fuel)
+  }
+}
+
+impl<I: fmt::Debug> fmt::Debug for Fueled<'_, I> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_tuple("Fueled").field(&self.input).finish()
+  }
+}
+
+impl<I> Deref for Fueled<'_, I> {
+  type Target = I;
+
+  fn deref(&self) -> &I {
+    &self.input
+  }
+}
+
+impl<I: fmt::Display> fmt::Display for Fueled<'_, I> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    self.input.fmt(f)
+  }
+}
+
+/// The positions given to `take`, `take_from` and `take_split` were computed by an earlier
+/// access, so they are applied even if the fuel is exhausted
+impl<I: Input> Input for Fueled<'_, I> {
+  type Item = I::Item;
+  type Iter = I::Iter;
+  type IterIndices = I::IterIndices;
+
+  #[inline]
+  fn input_len(&self) -> usize {
+    self.view().input_len()
+  }
+
+  #[inline]
+  fn take(&self, index: usize) -> Self {
+    self.fuel.burn();
+    self.with_input(self.input.take(index))
+  }
+
+  #[inline]
+  fn take_from(&self, index: usize) -> Self {
+    self.fuel.burn();
+    self.with_input(self.input.take_from(index))
+  }
+
+  #[inline]
+  fn take_split(&self, index: usize) -> (Self, Self) {
+    self.fuel.burn();
+    let (suffix, prefix) = self.input.take_split(index);
+    (self.with_input(suffix), self.with_input(prefix))
+  }
+
+  #[inline]
+  fn position<P>(&self, predicate: P) -> Option<usize>
+  where
+    P: Fn(Self::Item) -> bool,
+  {
+    self.view().position(predicate)
+  }
+
+  #[inline]
+  fn iter_elements(&self) -> Self::Iter {
+    self.view().iter_elements()
+  }
+
+  #[inline]
+  fn iter_indices(&self) -> Self::IterIndices {
+    self.view().iter_indices()
+  }
+
+  #[inline]
+  fn slice_index(&self, count: usize) -> Result<usize, Needed> {
+    self.view().slice_index(count)
+  }
+
+  #[inline]
+  fn is_streaming<OM: crate::OutputMode>(&self) -> bool {
+    self.fuel.is_exhausted() || self.input.is_streaming::<OM>()
+  }
+}
+
+/// Inputs holding state outside of their value, like a symbol table or a nesting depth
+/// shared through a `Cell`, which must be restored when a parser backtracks
+///
+/// Cloning an input saves its position, but not the state it refers to. A parser wrapped
+/// with [atomic](crate::combinator::atomic) takes a checkpoint before running, and rewinds
+/// the input to it if it fails, so the state changes of the failed branch are undone.
+/// Inputs without such state implement this trait with an empty checkpoint.
+pub trait Rewind {
+  /// Saved state of the input
+  type Checkpoint;
+
+  /// Saves the current state
+  fn checkpoint(&self) -> Self::Checkpoint;
+
+  /// Restores the state saved by [Rewind::checkpoint]
+  fn rewind(&self, checkpoint: Self::Checkpoint);
+}
+
+macro_rules! stateless_rewind {
+  ($($(#[$attr:meta])* $t:ty),*) => {
+    $(
+      $(#[$attr])*
+      impl Rewind for $t {
+        type Checkpoint = ();
+
+        #[inline]
+        fn checkpoint(&self) {}
+
+        #[inline]
+        fn rewind(&self, _checkpoint: ()) {}
+      }
+    )*
+  };
+}
+
+stateless_rewind! {
+  &[u8],
+  &str,
+  Utf16<'_>,
+  #[cfg(feature = "bytes")]
+  #[cfg_attr(feature = "docsrs", doc(cfg(feature = "bytes")))]
+  bytes::Bytes
+}
+
+impl<S> Rewind for Segmented<'_, S> {
+  type Checkpoint = ();
+
+  #[inline]
+  fn checkpoint(&self) {}
+
+  #[inline]
+  fn rewind(&self, _checkpoint: ()) {}
+}
+
+impl<I: Rewind> Rewind for Partial<I> {
+  type Checkpoint = I::Checkpoint;
+
+  #[inline]
+  fn checkpoint(&self) -> Self::Checkpoint {
+    self.input.checkpoint()
+  }
+
+  #[inline]
+  fn rewind(&self, checkpoint: Self::Checkpoint) {
+    self.input.rewind(checkpoint)
+  }
+}
+
+impl<I: Rewind, S: Clone> Rewind for Stateful<'_, I, S> {
+  type Checkpoint = (I::Checkpoint, S);
+
+  #[inline]
+  fn checkpoint(&self) -> Self::Checkpoint {
+    (self.input.checkpoint(), self.state.borrow().clone())
+  }
+
+  #[inline]
+  fn rewind(&self, (checkpoint, state): Self::Checkpoint) {
+    self.input.rewind(checkpoint);
+    *self.state.borrow_mut() = state;
+  }
+}
+
+/// The fuel burnt by a failed branch is not given back
+impl<I: Rewind> Rewind for Fueled<'_, I> {
+  type Checkpoint = I::Checkpoint;
+
+  #[inline]
+  fn checkpoint(&self) -> Self::Checkpoint {
+    self.input.checkpoint()
+  }
+
+  #[inline]
+  fn rewind(&self, checkpoint: Self::Checkpoint) {
+    self.input.rewind(checkpoint)
+  }
+}
+
+/// Conversion of an input of exactly `N` bytes to an array, used by
+/// [take_array](crate::bytes::take_array)
+///
+/// A `&[u8]` is converted to a reference to an array of the same buffer, and inputs
+/// that are not contiguous in memory are copied to an array.
+pub trait ToArray<const N: usize>: Input<Item = u8> {
+  /// Type of the array
+  type Array;
+
+  /// Converts the input, which must hold `N` bytes
+  fn to_array(&self) -> Self::Array;
+}
+
+impl<'a, const N: usize> ToArray<N> for &'a [u8] {
+  type Array = &'a [u8; N];
+
+  fn to_array(&self) -> Self::Array {
+    (*self).try_into().expect("input should hold N bytes")
+  }
+}
+
+impl<const N: usize, S: Deref<Target = [u8]>> ToArray<N> for Segmented<'_, S> {
+  type Array = [u8; N];
+
+  fn to_array(&self) -> Self::Array {
+    let mut array = [0; N];
+    for (b, input) in array.iter_mut().zip(self.iter_elements()) {
+      *b = input;
+    }
+    array
+  }
+}
+
+#[cfg(feature = "bytes")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "bytes")))]
+impl<const N: usize> ToArray<N> for bytes::Bytes {
+  type Array = [u8; N];
+
+  fn to_array(&self) -> Self::Array {
+    self[..].try_into().expect("input should hold N bytes")
+  }
+}
+
+/// Useful functions to calculate the offset between slices and show a hexdump of a slice
+pub trait Offset {
+  /// Offset between the first byte of self and the first byte of the argument
+  /// the argument must be a part of self, otherwise this can fail with arithmetic
+  /// underflows as it compares byte offsets
+  fn offset(&self, second: &Self) -> usize;
+}
+
+impl Offset for [u8] {
+  fn offset(&self, second: &Self) -> usize {
+    let fst = self.as_ptr();
+    let snd = second.as_ptr();
+
+    snd as usize - fst as usize
+  }
+}
+
+impl<'a> Offset for &'a [u8] {
+  fn offset(&self, second: &Self) -> usize {
+    let fst = self.as_ptr();
+    let snd = second.as_ptr();
+
+    snd as usize - fst as usize
+  }
+}
+
+impl Offset for str {
+  fn offset(&self, second: &Self) -> usize {
+    let fst = self.as_ptr();
+    let snd = second.as_ptr();
+
+    snd as usize - fst as usize
+  }
+}
+
+impl<'a> Offset for &'a str {
+  fn offset(&self, second: &Self) -> usize {
+    let fst = self.as_ptr();
+    let snd = second.as_ptr();
+
+    snd as usize - fst as usize
+  }
+}
+
+impl Offset for Utf16<'_> {
+  fn offset(&self, second: &Self) -> usize {
+    let fst = self.0.as_ptr();
+    let snd = second.0.as_ptr();
+
+    (snd as usize - fst as usize) / 2
+  }
+}
+
+impl<S> Offset for Segmented<'_, S> {
+  fn offset(&self, second: &Self) -> usize {
+    second.position - self.position
+  }
+}
+
+#[cfg(feature = "bytes")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "bytes")))]
+impl Offset for bytes::Bytes {
+  fn offset(&self, second: &Self) -> usize {
+    let fst = self.as_ptr();
+    let snd = second.as_ptr();
+
+    snd as usize - fst as usize
+  }
+}
+
+impl<I: Offset> Offset for Partial<I> {
+  fn offset(&self, second: &Self) -> usize {
+    self.input.offset(&second.input)
+  }
+}
+
+impl<I: Offset, S> Offset for Stateful<'_, I, S> {
+  fn offset(&self, second: &Self) -> usize {
+    self.input.offset(&second.input)
+  }
+}
+
+impl<I: Offset> Offset for Fueled<'_, I> {
+  fn offset(&self, second: &Self) -> usize {
+    self.input.offset(&second.input)
+  }
+}
+
+/// Helper trait for types that can be viewed as a byte slice
+pub trait AsBytes {
+  /// Casts the input type to a byte slice
+  fn as_bytes(&self) -> &[u8];
+}
+
+#[cfg(feature = "bytes")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "bytes")))]
+impl AsBytes for bytes::Bytes {
+  #[inline(always)]
+  fn as_bytes(&self) -> &[u8] {
+    self
+  }
+}
+
+impl<'a> AsBytes for &'a str {
+  #[inline(always)]
+  fn as_bytes(&self) -> &[u8] {
+    (*self).as_bytes()
+  }
+}
+
+impl AsBytes for str {
+  #[inline(always)]
+  fn as_bytes(&self) -> &[u8] {
+    self.as_ref()
+  }
+}
+
+impl<'a> AsBytes for &'a [u8] {
+  #[inline(always)]
+  fn as_bytes(&self) -> &[u8] {
+    self
+  }
+}
+
+impl AsBytes for [u8] {
+  #[inline(always)]
+  fn as_bytes(&self) -> &[u8] {
+    self
+  }
+}
+
+impl<'a, const N: usize> AsBytes for &'a [u8; N] {
+  #[inline(always)]
+  fn as_bytes(&self) -> &[u8] {
+    self.as_slice()
+  }
+}
+
+impl<const N: usize> AsBytes for [u8; N] {
+  #[inline(always)]
+  fn as_bytes(&self) -> &[u8] {
+    self
+  }
+}
+
+impl<I: AsBytes> AsBytes for Partial<I> {
+  #[inline(always)]
+  fn as_bytes(&self) -> &[u8] {
+    self.input.as_bytes()
+  }
+}
+
+impl<I: AsBytes, S> AsBytes for Stateful<'_, I, S> {
+  #[inline(always)]
+  fn as_bytes(&self) -> &[u8] {
+    self.input.as_bytes()
+  }
+}
+
+impl<I: AsBytes> AsBytes for Fueled<'_, I> {
+  #[inline(always)]
+  fn as_bytes(&self) -> &[u8] {
+    let bytes = self.input.as_bytes();
+    if self.fuel.burn() {
+      bytes
+    } else {
+      &bytes[..0]
+    }
+  }
+}
+
+/// Transforms common types to a char for basic token parsing
+#[allow(clippy::len_without_is_empty)]
+pub trait AsChar: Copy {
+  /// makes a char from self
+  fn as_char(self) -> char;
+
+  /// Tests that self is an alphabetic character
+  ///
+  /// Warning: for `&str` it recognizes alphabetic
+  /// characters outside of the 52 ASCII letters
+  fn is_alpha(self) -> bool;
+
+  /// Tests that self is an alphabetic character
+  /// or a decimal digit
+  fn is_alphanum(self) -> bool;
+  /// Tests that self is a decimal digit
+  fn is_dec_digit(self) -> bool;
+  /// Tests that self is an hex digit
+  fn is_hex_digit(self) -> bool;
+  /// Tests that self is an octal digit
+  fn is_oct_digit(self) -> bool;
+  /// Tests that self is a binary digit
+  fn is_bin_digit(self) -> bool;
+  /// Gets the len in bytes for self
+  fn len(self) -> usize;
+  /// Tests that self is ASCII space or tab
+  fn is_space(self) -> bool;
+  /// Tests if byte is ASCII newline: \n
+  fn is_newline(self) -> bool;
+}
+
+impl AsChar for u8 {
+  #[inline]
+  fn as_char(self) -> char {
+    self as char
+  }
+  #[inline]
+  fn is_alpha(self) -> bool {
+    matches!(self, 0x41..=0x5A | 0x61..=0x7A)
+  }
+  #[inline]
+  fn is_alphanum(self) -> bool {
+    self.is_alpha() || self.is_dec_digit()
+  }
+  #[inline]
+  fn is_dec_digit(self) -> bool {
+    matches!(self, 0x30..=0x39)
+  }
+  #[inline]
+  fn is_hex_digit(self) -> bool {
+    matches!(self, 0x30..=0x39 | 0x41..=0x46 | 0x61..=0x66)
+  }
+  #[inline]
+  fn is_oct_digit(self) -> bool {
+    matches!(self, 0x30..=0x37)
+  }
+  #[inline]
+  fn is_bin_digit(self) -> bool {
+    matches!(self, 0x30..=0x31)
+  }
+  #[inline]
+  fn len(self) -> usize {
+    1
+  }
+  #[inline]
+  fn is_space(self) -> bool {
+    self == b' ' || self == b'\t'
+  }
+  fn is_newline(self) -> bool {
+    self == b'\n'
+  }
+}
+impl<'a> AsChar for &'a u8 {
+  #[inline]
+  fn as_char(self) -> char {
+    *self as char
+  }
+  #[inline]
+  fn is_alpha(self) -> bool {
+    matches!(*self, 0x41..=0x5A | 0x61..=0x7A)
   }
   #[inline]
   fn is_alphanum(self) -> bool {
@@ -855,6 +2089,49 @@ impl<'a> AsChar for &'a char {
   }
 }
 
+impl AsChar for Utf16Char {
+  #[inline]
+  fn as_char(self) -> char {
+    self.c
+  }
+  #[inline]
+  fn is_alpha(self) -> bool {
+    self.c.is_alpha()
+  }
+  #[inline]
+  fn is_alphanum(self) -> bool {
+    self.c.is_alphanum()
+  }
+  #[inline]
+  fn is_dec_digit(self) -> bool {
+    self.c.is_dec_digit()
+  }
+  #[inline]
+  fn is_hex_digit(self) -> bool {
+    self.c.is_hex_digit()
+  }
+  #[inline]
+  fn is_oct_digit(self) -> bool {
+    self.c.is_oct_digit()
+  }
+  #[inline]
+  fn is_bin_digit(self) -> bool {
+    self.c.is_bin_digit()
+  }
+  #[inline]
+  fn len(self) -> usize {
+    self.len
+  }
+  #[inline]
+  fn is_space(self) -> bool {
+    self.c.is_space()
+  }
+  #[inline]
+  fn is_newline(self) -> bool {
+    self.c.is_newline()
+  }
+}
+
 /// Indicates whether a comparison was successful, an error, or
 /// if more data was needed
 #[derive(Debug, Eq, PartialEq)]
@@ -877,8 +2154,189 @@ pub trait Compare<T> {
   /// Warning: for `&str`, the comparison is done
   /// by lowercasing both strings and comparing
   /// the result. This is a temporary solution until
-  /// a better one appears
+  /// a better one appears. With the `unicode` feature,
+  /// both strings are compared after case folding.
   fn compare_no_case(&self, t: T) -> CompareResult;
+
+  /// Compares self to another value for equality
+  /// independently of the case, and returns the length
+  /// of the matching part of self if it can differ from
+  /// the length of `t`.
+  ///
+  /// With the `unicode` feature, a `&str` can match a
+  /// string of a different length, like `"STRASSE"` and
+  /// `"straße"`. The default implementation calls
+  /// [Compare::compare_no_case] and returns `None`.
+  fn compare_no_case_len(&self, t: T) -> (CompareResult, Option<usize>) {
+    (self.compare_no_case(t), None)
+  }
+
+  /// Compares self to another value for equality, and returns
+  /// the length of `t` counted in elements of self if it
+  /// differs from its own length.
+  ///
+  /// A `&str` compared to UTF-16 input ([Utf16]) is measured
+  /// in UTF-16 code units instead of bytes. The default
+  /// implementation calls [Compare::compare] and returns `None`.
+  fn compare_len(&self, t: T) -> (CompareResult, Option<usize>) {
+    (self.compare(t), None)
+  }
+}
+
+/// Length of a tag, in elements of the input it is compared to
+///
+/// This is the length of the inputs, and of the byte arrays that can be used as tags for
+/// `&[u8]` inputs, like `b"GIF8"`.
+pub trait TagLength {
+  /// Returns the length of the tag
+  fn tag_len(&self) -> usize;
+}
+
+impl<T: Input> TagLength for T {
+  #[inline(always)]
+  fn tag_len(&self) -> usize {
+    self.input_len()
+  }
+}
+
+impl<const N: usize> TagLength for [u8; N] {
+  #[inline(always)]
+  fn tag_len(&self) -> usize {
+    N
+  }
+}
+
+impl<const N: usize> TagLength for &[u8; N] {
+  #[inline(always)]
+  fn tag_len(&self) -> usize {
+    N
+  }
+}
+
+/// Characters for which the full case folding differs from the lowercase mapping,
+/// from Unicode's `CaseFolding.txt`, sorted by code point
+///
+/// The Greek letters with a iota subscript are not included.
+#[cfg(feature = "unicode")]
+const SPECIAL_CASE_FOLDING: &[(char, &[char])] = &[
+  ('\u{B5}', &['\u{3BC}']),
+  ('\u{DF}', &['s', 's']),
+  ('\u{149}', &['\u{2BC}', 'n']),
+  ('\u{17F}', &['s']),
+  ('\u{1F0}', &['j', '\u{30C}']),
+  ('\u{345}', &['\u{3B9}']),
+  ('\u{390}', &['\u{3B9}', '\u{308}', '\u{301}']),
+  ('\u{3B0}', &['\u{3C5}', '\u{308}', '\u{301}']),
+  ('\u{3C2}', &['\u{3C3}']),
+  ('\u{3D0}', &['\u{3B2}']),
+  ('\u{3D1}', &['\u{3B8}']),
+  ('\u{3D5}', &['\u{3C6}']),
+  ('\u{3D6}', &['\u{3C0}']),
+  ('\u{3F0}', &['\u{3BA}']),
+  ('\u{3F1}', &['\u{3C1}']),
+  ('\u{3F5}', &['\u{3B5}']),
+  ('\u{587}', &['\u{565}', '\u{582}']),
+  ('\u{1E96}', &['h', '\u{331}']),
+  ('\u{1E97}', &['t', '\u{308}']),
+  ('\u{1E98}', &['w', '\u{30A}']),
+  ('\u{1E99}', &['y', '\u{30A}']),
+  ('\u{1E9A}', &['a', '\u{2BE}']),
+  ('\u{1E9B}', &['\u{1E61}']),
+  ('\u{1E9E}', &['s', 's']),
+  ('\u{1FBE}', &['\u{3B9}']),
+  ('\u{FB00}', &['f', 'f']),
+  ('\u{FB01}', &['f', 'i']),
+  ('\u{FB02}', &['f', 'l']),
+  ('\u{FB03}', &['f', 'f', 'i']),
+  ('\u{FB04}', &['f', 'f', 'l']),
+  ('\u{FB05}', &['s', 't']),
+  ('\u{FB06}', &['s', 't']),
+  ('\u{FB13}', &['\u{574}', '\u{576}']),
+  ('\u{FB14}', &['\u{574}', '\u{565}']),
+  ('\u{FB15}', &['\u{574}', '\u{56B}']),
+  ('\u{FB16}', &['\u{57E}', '\u{576}']),
+  ('\u{FB17}', &['\u{574}', '\u{56D}']),
+];
+
+/// Iterator over the case folding of a character
+#[cfg(feature = "unicode")]
+pub(crate) enum CaseFold {
+  Special(core::slice::Iter<'static, char>),
+  Lowercase(core::char::ToLowercase),
+}
+
+#[cfg(feature = "unicode")]
+impl CaseFold {
+  /// Full case folding of `c`
+  pub(crate) fn new(c: char) -> Self {
+    match SPECIAL_CASE_FOLDING.binary_search_by_key(&c, |(c, _)| *c) {
+      Ok(index) => CaseFold::Special(SPECIAL_CASE_FOLDING[index].1.iter()),
+      Err(_) => CaseFold::Lowercase(c.to_lowercase()),
+    }
+  }
+
+  fn empty() -> Self {
+    CaseFold::Special([].iter())
+  }
+
+  fn is_empty(&self) -> bool {
+    match self {
+      CaseFold::Special(it) => it.len() == 0,
+      CaseFold::Lowercase(it) => it.len() == 0,
+    }
+  }
+}
+
+#[cfg(feature = "unicode")]
+impl Iterator for CaseFold {
+  type Item = char;
+
+  fn next(&mut self) -> Option<char> {
+    match self {
+      CaseFold::Special(it) => it.next().copied(),
+      CaseFold::Lowercase(it) => it.next(),
+    }
+  }
+}
+
+/// Compares the case folding of `input` and `tag`, returns the length of the
+/// matching part of `input`
+#[cfg(feature = "unicode")]
+fn compare_case_folded(input: &str, tag: &str) -> (CompareResult, usize) {
+  let mut tag = tag.chars().flat_map(CaseFold::new);
+  let mut input_chars = input.chars();
+  // folding of the current input character
+  let mut input_fold = CaseFold::empty();
+  // end of the current input character
+  let mut position = 0;
+
+  loop {
+    let t = match tag.next() {
+      // the tag must not end in the middle of the folding of an input character
+      None if input_fold.is_empty() => return (CompareResult::Ok, position),
+      None => return (CompareResult::Error, 0),
+      Some(t) => t,
+    };
+
+    let c = match input_fold.next() {
+      Some(c) => c,
+      None => match input_chars.next() {
+        None => return (CompareResult::Incomplete, 0),
+        Some(c) => {
+          position += c.len_utf8();
+          input_fold = CaseFold::new(c);
+          match input_fold.next() {
+            Some(c) => c,
+            None => return (CompareResult::Error, 0),
+          }
+        }
+      },
+    };
+
+    if c != t {
+      return (CompareResult::Error, 0);
+    }
+  }
 }
 
 fn lowercase_byte(c: u8) -> u8 {
@@ -938,7 +2396,19 @@ impl<'a, 'b> Compare<&'b str> for &'a str {
     self.as_bytes().compare(t.as_bytes())
   }
 
+  #[cfg(feature = "unicode")]
+  fn compare_no_case(&self, t: &'b str) -> CompareResult {
+    compare_case_folded(self, t).0
+  }
+
+  #[cfg(feature = "unicode")]
+  fn compare_no_case_len(&self, t: &'b str) -> (CompareResult, Option<usize>) {
+    let (res, len) = compare_case_folded(self, t);
+    (res, Some(len))
+  }
+
   //FIXME: this version is too simple and does not use the current locale
+  #[cfg(not(feature = "unicode"))]
   #[inline(always)]
   fn compare_no_case(&self, t: &'b str) -> CompareResult {
     let pos = self
@@ -970,6 +2440,346 @@ impl<'a, 'b> Compare<&'b [u8]> for &'a str {
   }
 }
 
+impl<'b> Compare<Utf16<'b>> for Utf16<'_> {
+  fn compare(&self, t: Utf16<'b>) -> CompareResult {
+    let pos = self.0.iter().zip(t.0.iter()).position(|(a, b)| a != b);
+
+    match pos {
+      Some(_) => CompareResult::Error,
+      None if self.0.len() >= t.0.len() => CompareResult::Ok,
+      None => CompareResult::Incomplete,
+    }
+  }
+
+  fn compare_no_case(&self, t: Utf16<'b>) -> CompareResult {
+    let tag = t.iter_elements().map(AsChar::as_char);
+    compare_utf16_no_case(*self, tag).0
+  }
+}
+
+impl<'b> Compare<&'b str> for Utf16<'_> {
+  fn compare(&self, t: &'b str) -> CompareResult {
+    let mut units = self.0.iter();
+    for expected in t.encode_utf16() {
+      match units.next() {
+        Some(unit) if *unit == expected => {}
+        Some(_) => return CompareResult::Error,
+        None => return CompareResult::Incomplete,
+      }
+    }
+    CompareResult::Ok
+  }
+
+  fn compare_no_case(&self, t: &'b str) -> CompareResult {
+    compare_utf16_no_case(*self, t.chars()).0
+  }
+
+  fn compare_no_case_len(&self, t: &'b str) -> (CompareResult, Option<usize>) {
+    let (res, len) = compare_utf16_no_case(*self, t.chars());
+    (res, Some(len))
+  }
+
+  fn compare_len(&self, t: &'b str) -> (CompareResult, Option<usize>) {
+    (self.compare(t), Some(t.encode_utf16().count()))
+  }
+}
+
+impl<'b, S: Deref<Target = [u8]>> Compare<&'b [u8]> for Segmented<'_, S> {
+  fn compare(&self, t: &'b [u8]) -> CompareResult {
+    if self.iter_elements().zip(t).any(|(a, b)| a != *b) {
+      CompareResult::Error
+    } else if self.len < t.len() {
+      CompareResult::Incomplete
+    } else {
+      CompareResult::Ok
+    }
+  }
+
+  fn compare_no_case(&self, t: &'b [u8]) -> CompareResult {
+    if self
+      .iter_elements()
+      .zip(t)
+      .any(|(a, b)| lowercase_byte(a) != lowercase_byte(*b))
+    {
+      CompareResult::Error
+    } else if self.len < t.len() {
+      CompareResult::Incomplete
+    } else {
+      CompareResult::Ok
+    }
+  }
+}
+
+impl<'b, S: Deref<Target = [u8]>> Compare<&'b str> for Segmented<'_, S> {
+  fn compare(&self, t: &'b str) -> CompareResult {
+    self.compare(AsBytes::as_bytes(t))
+  }
+
+  fn compare_no_case(&self, t: &'b str) -> CompareResult {
+    self.compare_no_case(AsBytes::as_bytes(t))
+  }
+}
+
+#[cfg(feature = "bytes")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "bytes")))]
+impl<'b> Compare<&'b [u8]> for bytes::Bytes {
+  #[inline(always)]
+  fn compare(&self, t: &'b [u8]) -> CompareResult {
+    (&self[..]).compare(t)
+  }
+
+  #[inline(always)]
+  fn compare_no_case(&self, t: &'b [u8]) -> CompareResult {
+    (&self[..]).compare_no_case(t)
+  }
+}
+
+#[cfg(feature = "bytes")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "bytes")))]
+impl<'b> Compare<&'b str> for bytes::Bytes {
+  #[inline(always)]
+  fn compare(&self, t: &'b str) -> CompareResult {
+    (&self[..]).compare(AsBytes::as_bytes(t))
+  }
+
+  #[inline(always)]
+  fn compare_no_case(&self, t: &'b str) -> CompareResult {
+    (&self[..]).compare_no_case(AsBytes::as_bytes(t))
+  }
+}
+
+/// Compares UTF-16 input to characters by lowercasing them, returning the length of the
+/// matched input in code units, or the length of the tag if the input is too short
+fn compare_utf16_no_case<T>(input: Utf16<'_>, tag: T) -> (CompareResult, usize)
+where
+  T: Iterator<Item = char>,
+{
+  let mut chars = input.iter_elements();
+  let mut matched = 0;
+  let mut tag_len = 0;
+  let mut incomplete = false;
+
+  for expected in tag {
+    tag_len += expected.len_utf16();
+    if incomplete {
+      continue;
+    }
+    match chars.next() {
+      Some(c) if c.c.to_lowercase().eq(expected.to_lowercase()) => matched += c.len,
+      Some(_) => return (CompareResult::Error, matched),
+      None => incomplete = true,
+    }
+  }
+
+  if incomplete {
+    (CompareResult::Incomplete, tag_len)
+  } else {
+    (CompareResult::Ok, matched)
+  }
+}
+
+impl<I: Compare<T>, T> Compare<T> for Partial<I> {
+  #[inline]
+  fn compare(&self, t: T) -> CompareResult {
+    self.input.compare(t)
+  }
+
+  #[inline]
+  fn compare_no_case(&self, t: T) -> CompareResult {
+    self.input.compare_no_case(t)
+  }
+
+  #[inline]
+  fn compare_no_case_len(&self, t: T) -> (CompareResult, Option<usize>) {
+    self.input.compare_no_case_len(t)
+  }
+
+  #[inline]
+  fn compare_len(&self, t: T) -> (CompareResult, Option<usize>) {
+    self.input.compare_len(t)
+  }
+}
+
+impl<I: Compare<T>, T, S> Compare<T> for Stateful<'_, I, S> {
+  #[inline]
+  fn compare(&self, t: T) -> CompareResult {
+    self.input.compare(t)
+  }
+
+  #[inline]
+  fn compare_no_case(&self, t: T) -> CompareResult {
+    self.input.compare_no_case(t)
+  }
+
+  #[inline]
+  fn compare_no_case_len(&self, t: T) -> (CompareResult, Option<usize>) {
+    self.input.compare_no_case_len(t)
+  }
+
+  #[inline]
+  fn compare_len(&self, t: T) -> (CompareResult, Option<usize>) {
+    self.input.compare_len(t)
+  }
+}
+
+impl<I: Input + Compare<T>, T> Compare<T> for Fueled<'_, I> {
+  #[inline]
+  fn compare(&self, t: T) -> CompareResult {
+    self.view().compare(t)
+  }
+
+  #[inline]
+  fn compare_no_case(&self, t: T) -> CompareResult {
+    self.view().compare_no_case(t)
+  }
+
+  #[inline]
+  fn compare_no_case_len(&self, t: T) -> (CompareResult, Option<usize>) {
+    self.view().compare_no_case_len(t)
+  }
+
+  #[inline]
+  fn compare_len(&self, t: T) -> (CompareResult, Option<usize>) {
+    self.view().compare_len(t)
+  }
+}
+
+/// Equivalence rule between the input and a tag, for [tag_with](crate::bytes::tag_with)
+///
+/// Grammars can state how their identifiers are compared, instead of normalizing the whole
+/// input before parsing. nom provides [Exact], [AsciiCaseInsensitive], [UnicodeCaseFold] and,
+/// with the `normalization` feature, [Nfc] and [Nfkc].
+pub trait Comparator<I, T> {
+  /// Compares the start of `input` to `tag`, like [Compare::compare_len], and returns the
+  /// length of the matching part of `input` if it can differ from the length of `tag`
+  fn compare(&self, input: &I, tag: T) -> (CompareResult, Option<usize>);
+}
+
+/// Compares the input and the tag element by element, like [tag](crate::bytes::tag)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Exact;
+
+impl<I: Compare<T>, T> Comparator<I, T> for Exact {
+  #[inline]
+  fn compare(&self, input: &I, tag: T) -> (CompareResult, Option<usize>) {
+    input.compare_len(tag)
+  }
+}
+
+/// Compares the bytes of the input and the tag, ignoring the case of ASCII letters only
+///
+/// Unlike [UnicodeCaseFold], the other characters must be equal, so `"é"` does not match `"É"`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AsciiCaseInsensitive;
+
+impl<I: AsBytes, T: AsBytes> Comparator<I, T> for AsciiCaseInsensitive {
+  #[inline]
+  fn compare(&self, input: &I, tag: T) -> (CompareResult, Option<usize>) {
+    (input.as_bytes().compare_no_case(tag.as_bytes()), None)
+  }
+}
+
+/// Compares the input and the tag independently of the case, like
+/// [tag_no_case](crate::bytes::tag_no_case)
+///
+/// With the `unicode` feature, `&str` inputs are compared after full case folding, so
+/// `"STRASSE"` matches `"straße"`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct UnicodeCaseFold;
+
+impl<I: Compare<T>, T> Comparator<I, T> for UnicodeCaseFold {
+  #[inline]
+  fn compare(&self, input: &I, tag: T) -> (CompareResult, Option<usize>) {
+    input.compare_no_case_len(tag)
+  }
+}
+
+/// Compares `&str` inputs and tags under canonical equivalence, as if both were in NFC, with
+/// the `normalization` feature
+///
+/// `"e\u{301}"` matches `"é"`, and the tag cannot end before the combining marks following a
+/// character of the input, so `"e"` does not match `"e\u{301}"`.
+#[cfg(feature = "normalization")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "normalization")))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Nfc;
+
+/// Compares `&str` inputs and tags under compatibility equivalence, as if both were in NFKC,
+/// with the `normalization` feature
+///
+/// Like [Nfc], but the compatibility characters also match their plain forms, so `"ﬁle"`
+/// matches `"file"` and `"²"` matches `"2"`.
+#[cfg(feature = "normalization")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "normalization")))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Nfkc;
+
+macro_rules! normalization_comparator {
+  ($name:ident, $compatibility:expr) => {
+    #[cfg(feature = "normalization")]
+    impl<'a, 'b> Comparator<&'a str, &'b str> for $name {
+      fn compare(&self, input: &&'a str, tag: &'b str) -> (CompareResult, Option<usize>) {
+        let (res, len) = compare_normalized(input, tag, $compatibility);
+        (res, Some(len))
+      }
+    }
+
+    #[cfg(feature = "normalization")]
+    impl<'a, 'b> Comparator<Partial<&'a str>, &'b str> for $name {
+      fn compare(&self, input: &Partial<&'a str>, tag: &'b str) -> (CompareResult, Option<usize>) {
+        self.compare(&input.input, tag)
+      }
+    }
+  };
+}
+
+normalization_comparator!(Nfc, false);
+normalization_comparator!(Nfkc, true);
+
+/// Compares the decompositions of `input` and `tag`, returns the length of the matching
+/// part of `input`
+///
+/// Canonical reordering only moves combining marks between two starters, so the input is
+/// decomposed one starter and its combining marks at a time, and the tag must end between
+/// two of these segments.
+#[cfg(feature = "normalization")]
+fn compare_normalized(input: &str, tag: &str, compatibility: bool) -> (CompareResult, usize) {
+  use unicode_normalization::char::canonical_combining_class;
+  use unicode_normalization::{Decompositions, UnicodeNormalization};
+
+  fn decompose(s: &str, compatibility: bool) -> Decompositions<Chars<'_>> {
+    if compatibility {
+      s.nfkd()
+    } else {
+      s.nfd()
+    }
+  }
+
+  let mut tag = decompose(tag, compatibility).peekable();
+  let mut position = 0;
+
+  while tag.peek().is_some() {
+    let rest = &input[position..];
+    if rest.is_empty() {
+      return (CompareResult::Incomplete, 0);
+    }
+
+    let end = rest
+      .char_indices()
+      .skip(1)
+      .find(|(_, c)| canonical_combining_class(*c) == 0)
+      .map_or(rest.len(), |(i, _)| i);
+    for c in decompose(&rest[..end], compatibility) {
+      if tag.next() != Some(c) {
+        return (CompareResult::Error, 0);
+      }
+    }
+    position += end;
+  }
+
+  (CompareResult::Ok, position)
+}
+
 /// Look for a token in self
 pub trait FindToken<T> {
   /// Returns true if self contains the token
@@ -1024,6 +2834,18 @@ impl<'a, 'b> FindToken<&'a char> for &'b [char] {
   }
 }
 
+impl<I: FindToken<T>, T> FindToken<T> for Partial<I> {
+  fn find_token(&self, token: T) -> bool {
+    self.input.find_token(token)
+  }
+}
+
+impl<I: FindToken<T>, T, S> FindToken<T> for Stateful<'_, I, S> {
+  fn find_token(&self, token: T) -> bool {
+    self.input.find_token(token)
+  }
+}
+
 /// Look for a substring in self
 pub trait FindSubstring<T> {
   /// Returns the byte position of the substring if it is found
@@ -1070,6 +2892,69 @@ impl<'a, 'b> FindSubstring<&'b str> for &'a [u8] {
   }
 }
 
+impl<'b, S: Deref<Target = [u8]>> FindSubstring<&'b [u8]> for Segmented<'_, S> {
+  fn find_substring(&self, substr: &'b [u8]) -> Option<usize> {
+    let substr_first = match substr.first() {
+      Some(b) => *b,
+      None => return Some(0),
+    };
+
+    let mut segment = self.first;
+    let mut rest = self.rest;
+    // position of `segment` in self
+    let mut offset = 0;
+    while offset + substr.len() <= self.len {
+      let segment_len = segment.len().min(self.len - offset);
+      let mut start = 0;
+      while let Some(position) = memchr::memchr(substr_first, &segment[start..segment_len]) {
+        let index = offset + start + position;
+        if index + substr.len() > self.len {
+          return None;
+        }
+        // the candidate can continue in the following segments
+        let candidate = SegmentedBytes {
+          current: segment[start + position..].iter(),
+          rest,
+          remaining: substr.len(),
+        };
+        if candidate.eq(substr.iter().copied()) {
+          return Some(index);
+        }
+        start += position + 1;
+      }
+
+      offset += segment_len;
+      let (next, tail) = rest.split_first()?;
+      segment = next;
+      rest = tail;
+    }
+
+    None
+  }
+}
+
+impl<'b, S: Deref<Target = [u8]>> FindSubstring<&'b str> for Segmented<'_, S> {
+  fn find_substring(&self, substr: &'b str) -> Option<usize> {
+    self.find_substring(AsBytes::as_bytes(substr))
+  }
+}
+
+#[cfg(feature = "bytes")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "bytes")))]
+impl<'b> FindSubstring<&'b [u8]> for bytes::Bytes {
+  fn find_substring(&self, substr: &'b [u8]) -> Option<usize> {
+    (&self[..]).find_substring(substr)
+  }
+}
+
+#[cfg(feature = "bytes")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "bytes")))]
+impl<'b> FindSubstring<&'b str> for bytes::Bytes {
+  fn find_substring(&self, substr: &'b str) -> Option<usize> {
+    (&self[..]).find_substring(AsBytes::as_bytes(substr))
+  }
+}
+
 impl<'a, 'b> FindSubstring<&'b str> for &'a str {
   //returns byte index
   fn find_substring(&self, substr: &'b str) -> Option<usize> {
@@ -1077,6 +2962,80 @@ impl<'a, 'b> FindSubstring<&'b str> for &'a str {
   }
 }
 
+/// Look for the first occurrence of any substring from a set in self
+///
+/// This is implemented for arrays and slices of substrings, by looking for each of
+/// them with [FindSubstring]. With the `aho-corasick` feature, it is also implemented
+/// for a prebuilt [aho_corasick::AhoCorasick] automaton, which is faster when there are
+/// many substrings, or when the same set is used repeatedly.
+pub trait FindSubstringSet<T> {
+  /// Returns the byte position of the first occurrence of any of the substrings
+  fn find_substring_set(&self, set: T) -> Option<usize>;
+}
+
+impl<I, T, const N: usize> FindSubstringSet<[T; N]> for I
+where
+  I: FindSubstring<T>,
+{
+  fn find_substring_set(&self, set: [T; N]) -> Option<usize> {
+    set
+      .into_iter()
+      .filter_map(|substr| self.find_substring(substr))
+      .min()
+  }
+}
+
+impl<'a, I, T> FindSubstringSet<&'a [T]> for I
+where
+  I: FindSubstring<T>,
+  T: Clone,
+{
+  fn find_substring_set(&self, set: &'a [T]) -> Option<usize> {
+    set
+      .iter()
+      .filter_map(|substr| self.find_substring(substr.clone()))
+      .min()
+  }
+}
+
+/// The automaton should be built with a leftmost match kind: with the default
+/// `MatchKind::Standard`, the reported match is the one that ends first.
+#[cfg(feature = "aho-corasick")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "aho-corasick")))]
+impl<'b> FindSubstringSet<&'b aho_corasick::AhoCorasick> for &[u8] {
+  fn find_substring_set(&self, set: &'b aho_corasick::AhoCorasick) -> Option<usize> {
+    set.find(*self).map(|m| m.start())
+  }
+}
+
+/// The automaton should be built with a leftmost match kind: with the default
+/// `MatchKind::Standard`, the reported match is the one that ends first.
+#[cfg(feature = "aho-corasick")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "aho-corasick")))]
+impl<'b> FindSubstringSet<&'b aho_corasick::AhoCorasick> for &str {
+  fn find_substring_set(&self, set: &'b aho_corasick::AhoCorasick) -> Option<usize> {
+    set.find(*self).map(|m| m.start())
+  }
+}
+
+impl<I: FindSubstring<T>, T> FindSubstring<T> for Partial<I> {
+  fn find_substring(&self, substr: T) -> Option<usize> {
+    self.input.find_substring(substr)
+  }
+}
+
+impl<I: FindSubstring<T>, T, S> FindSubstring<T> for Stateful<'_, I, S> {
+  fn find_substring(&self, substr: T) -> Option<usize> {
+    self.input.find_substring(substr)
+  }
+}
+
+impl<I: Input + FindSubstring<T>, T> FindSubstring<T> for Fueled<'_, I> {
+  fn find_substring(&self, substr: T) -> Option<usize> {
+    self.view().find_substring(substr)
+  }
+}
+
 /// Used to integrate `str`'s `parse()` method
 pub trait ParseTo<R> {
   /// Succeeds if `parse()` succeeded. The byte slice implementation
@@ -1090,16 +3049,42 @@ impl<'a, R: FromStr> ParseTo<R> for &'a [u8] {
   }
 }
 
+#[cfg(feature = "bytes")]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "bytes")))]
+impl<R: FromStr> ParseTo<R> for bytes::Bytes {
+  fn parse_to(&self) -> Option<R> {
+    from_utf8(self).ok().and_then(|s| s.parse().ok())
+  }
+}
+
 impl<'a, R: FromStr> ParseTo<R> for &'a str {
   fn parse_to(&self) -> Option<R> {
     self.parse().ok()
   }
 }
 
-impl<'a, const N: usize> Compare<[u8; N]> for &'a [u8] {
+impl<I: ParseTo<R>, R> ParseTo<R> for Partial<I> {
+  fn parse_to(&self) -> Option<R> {
+    self.input.parse_to()
+  }
+}
+
+impl<I: ParseTo<R>, R, S> ParseTo<R> for Stateful<'_, I, S> {
+  fn parse_to(&self) -> Option<R> {
+    self.input.parse_to()
+  }
+}
+
+impl<I: ParseTo<R>, R> ParseTo<R> for Fueled<'_, I> {
+  fn parse_to(&self) -> Option<R> {
+    self.input.parse_to()
+  }
+}
+
+impl<const N: usize> Compare<[u8; N]> for &[u8] {
   #[inline(always)]
   fn compare(&self, t: [u8; N]) -> CompareResult {
-    self.compare(&t[..])
+    self.compare(&t)
   }
 
   #[inline(always)]
@@ -1108,14 +3093,21 @@ impl<'a, const N: usize> Compare<[u8; N]> for &'a [u8] {
   }
 }
 
-impl<'a, 'b, const N: usize> Compare<&'b [u8; N]> for &'a [u8] {
+impl<const N: usize> Compare<&[u8; N]> for &[u8] {
+  /// Compares the first `N` bytes as an array, in a single comparison instead of a loop
+  /// on the bytes
   #[inline(always)]
-  fn compare(&self, t: &'b [u8; N]) -> CompareResult {
-    self.compare(&t[..])
+  fn compare(&self, t: &[u8; N]) -> CompareResult {
+    match self.get(..N).map(<&[u8; N]>::try_from) {
+      Some(Ok(head)) if head == t => CompareResult::Ok,
+      Some(_) => CompareResult::Error,
+      None if t.starts_with(self) => CompareResult::Incomplete,
+      None => CompareResult::Error,
+    }
   }
 
   #[inline(always)]
-  fn compare_no_case(&self, t: &'b [u8; N]) -> CompareResult {
+  fn compare_no_case(&self, t: &[u8; N]) -> CompareResult {
     self.compare_no_case(&t[..])
   }
 }
@@ -1126,7 +3118,7 @@ impl<const N: usize> FindToken<u8> for [u8; N] {
   }
 }
 
-impl<'a, const N: usize> FindToken<&'a u8> for [u8; N] {
+impl<const N: usize> FindToken<&u8> for [u8; N] {
   fn find_token(&self, token: &u8) -> bool {
     self.find_token(*token)
   }
@@ -1209,6 +3201,39 @@ impl ExtendInto for &str {
   }
 }
 
+#[cfg(feature = "alloc")]
+impl<S: Deref<Target = [u8]>> ExtendInto for Segmented<'_, S> {
+  type Item = u8;
+  type Extender = Vec<u8>;
+
+  #[inline]
+  fn new_builder(&self) -> Vec<u8> {
+    Vec::with_capacity(self.len)
+  }
+  #[inline]
+  fn extend_into(&self, acc: &mut Vec<u8>) {
+    for segment in self.segments() {
+      acc.extend_from_slice(segment);
+    }
+  }
+}
+
+#[cfg(all(feature = "alloc", feature = "bytes"))]
+#[cfg_attr(feature = "docsrs", doc(cfg(feature = "bytes")))]
+impl ExtendInto for bytes::Bytes {
+  type Item = u8;
+  type Extender = Vec<u8>;
+
+  #[inline]
+  fn new_builder(&self) -> Vec<u8> {
+    Vec::new()
+  }
+  #[inline]
+  fn extend_into(&self, acc: &mut Vec<u8>) {
+    acc.extend_from_slice(self);
+  }
+}
+
 #[cfg(feature = "alloc")]
 impl ExtendInto for char {
   type Item = char;
@@ -1224,6 +3249,40 @@ impl ExtendInto for char {
   }
 }
 
+/// Input slices that can be borrowed in a [Cow], used by the unescaping combinators
+/// to avoid allocating when the input has no escape sequence
+///
+/// The owned variant is the [ExtendInto::Extender] of the input, so a value built with
+/// [ExtendInto] can be returned as [Cow::Owned].
+#[cfg(feature = "alloc")]
+pub trait AsCow<'a>: ExtendInto {
+  /// The borrowed type, like `str` for `&str`
+  type Target: ?Sized + ToOwned<Owned = Self::Extender> + 'a;
+
+  /// Borrows the input
+  fn as_cow(&self) -> Cow<'a, Self::Target>;
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> AsCow<'a> for &'a str {
+  type Target = str;
+
+  #[inline]
+  fn as_cow(&self) -> Cow<'a, str> {
+    Cow::Borrowed(*self)
+  }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> AsCow<'a> for &'a [u8] {
+  type Target = [u8];
+
+  #[inline]
+  fn as_cow(&self) -> Cow<'a, [u8]> {
+    Cow::Borrowed(*self)
+  }
+}
+
 /// Helper trait to convert numbers to usize.
 ///
 /// By default, usize implements `From<u8>` and `From<u16>` but not
@@ -1272,7 +3331,49 @@ impl ToUsize for u64 {
   }
 }
 
+/// Integer types accumulated by [delta_decode](crate::multi::delta_decode), with the
+/// operations of its [Overflow](crate::multi::Overflow) policies
+pub trait Delta: Copy {
+  /// Adds `delta`, returning `None` on overflow
+  fn checked_add_delta(self, delta: Self) -> Option<Self>;
+  /// Adds `delta`, wrapping around at the bounds of the type
+  fn wrapping_add_delta(self, delta: Self) -> Self;
+  /// Adds `delta`, saturating at the bounds of the type
+  fn saturating_add_delta(self, delta: Self) -> Self;
+}
+
+macro_rules! delta_impl {
+  ($($t:ty)*) => {
+    $(
+      impl Delta for $t {
+        #[inline]
+        fn checked_add_delta(self, delta: Self) -> Option<Self> {
+          self.checked_add(delta)
+        }
+
+        #[inline]
+        fn wrapping_add_delta(self, delta: Self) -> Self {
+          self.wrapping_add(delta)
+        }
+
+        #[inline]
+        fn saturating_add_delta(self, delta: Self) -> Self {
+          self.saturating_add(delta)
+        }
+      }
+    )*
+  };
+}
+
+delta_impl! { u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize }
+
 /// Equivalent From implementation to avoid orphan rules in bits parsers
+///
+/// [bits](crate::bits::bits) and [bytes](crate::bits::bytes) use it to convert the errors
+/// between the bit level input `(I, usize)` and the byte level input `I`. It is implemented
+/// in both directions for the error types of nom and nom-language. Other error types can
+/// implement it, or be converted with a closure by [bits_with](crate::bits::bits_with) and
+/// [bytes_with](crate::bits::bytes_with) instead.
 pub trait ErrorConvert<E> {
   /// Transform to another error type
   fn convert(self) -> E;
@@ -1290,7 +3391,7 @@ impl<I> ErrorConvert<((I, usize), ErrorKind)> for (I, ErrorKind) {
   }
 }
 
-use crate::error;
+use crate::error::{self, MapInput};
 impl<I> ErrorConvert<error::Error<I>> for error::Error<(I, usize)> {
   fn convert(self) -> error::Error<I> {
     error::Error {
@@ -1313,6 +3414,18 @@ impl ErrorConvert<()> for () {
   fn convert(self) {}
 }
 
+impl<I, const N: usize> ErrorConvert<error::StackError<I, N>> for error::StackError<(I, usize), N> {
+  fn convert(self) -> error::StackError<I, N> {
+    self.map_input(|i| i.0)
+  }
+}
+
+impl<I, const N: usize> ErrorConvert<error::StackError<(I, usize), N>> for error::StackError<I, N> {
+  fn convert(self) -> error::StackError<(I, usize), N> {
+    self.map_input(|i| (i, 0))
+  }
+}
+
 #[cfg(feature = "std")]
 #[cfg_attr(feature = "docsrs", doc(cfg(feature = "std")))]
 /// Helper trait to show a byte slice as a hex dump
@@ -1324,11 +3437,252 @@ pub trait HexDisplay {
   /// Converts the value of `self` to a hex dump beginning at `from` address, returning the owned
   /// `String`.
   fn to_hex_from(&self, chunk_size: usize, from: usize) -> String;
+
+  /// Converts the value of `self` to a hex dump laid out by `format`, returning the owned
+  /// `String`.
+  ///
+  /// ```rust
+  /// use nom::{HexDisplay, HexFormat};
+  ///
+  /// let dump = b"nom\x00\x01".to_hex_with(&HexFormat::new().width(4).offset(0x10).ascii(false));
+  /// assert_eq!(dump, "00000010\t6e 6f 6d 00 \n00000014\t01          \n");
+  /// ```
+  ///
+  /// The default implementation calls [to_hex_from](HexDisplay::to_hex_from), and removes
+  /// the ASCII column, after the second tab of each line, if `format` disables it.
+  fn to_hex_with(&self, format: &HexFormat) -> String {
+    let dump = self.to_hex_from(format.width, format.offset);
+    if format.ascii {
+      return dump;
+    }
+
+    let mut hex = String::with_capacity(dump.len());
+    for line in dump.lines() {
+      let end = line
+        .match_indices('\t')
+        .nth(1)
+        .map_or(line.len(), |(i, _)| i);
+      hex.push_str(&line[..end]);
+      hex.push('\n');
+    }
+    hex
+  }
+
+  /// Converts the lines of `self` around the byte at `offset` to a hex dump, with
+  /// `context_lines` lines before and after the line of that byte, returning the owned
+  /// `String`.
+  ///
+  /// The lines have 16 bytes and keep their address in `self`, to look at the position
+  /// of an error in a large buffer without dumping all of it.
+  ///
+  /// ```rust
+  /// use nom::HexDisplay;
+  ///
+  /// let data = [0u8; 1 << 20];
+  /// assert_eq!(
+  ///   data.to_hex_window(0x1234, 0),
+  ///   "00001230\t00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 \t................\n"
+  /// );
+  /// assert_eq!(data.to_hex_window(0x1234, 2).lines().count(), 5);
+  /// ```
+  ///
+  /// The default implementation keeps the lines of the whole dump of
+  /// [to_hex](HexDisplay::to_hex), the implementations for `[u8]` and `str` only dump
+  /// the window.
+  fn to_hex_window(&self, offset: usize, context_lines: usize) -> String {
+    let dump = self.to_hex(16);
+    let lines = dump.lines().collect::<Vec<_>>();
+    let row = (offset / 16).min(lines.len().saturating_sub(1));
+    let start = row.saturating_sub(context_lines);
+    let end = row
+      .saturating_add(context_lines)
+      .saturating_add(1)
+      .min(lines.len());
+
+    let mut window = String::new();
+    for line in &lines[start..end] {
+      window.push_str(line);
+      window.push('\n');
+    }
+    window
+  }
+
+  /// Shows the `expected` and `actual` values side by side as hex dumps laid out by
+  /// `format`, returning the owned `String`.
+  ///
+  /// Each line shows the same offset of both values, separated by `==` if they are equal
+  /// on that line, and by `!=` otherwise. This is meant for the messages of test
+  /// assertions on binary formats.
+  ///
+  /// ```rust
+  /// use nom::{HexDisplay, HexFormat};
+  ///
+  /// let diff = b"abcdefgh"[..].hex_diff(&b"abcdefxhi"[..], &HexFormat::new().width(4));
+  /// assert_eq!(
+  ///   diff,
+  ///   "00000000  61 62 63 64  abcd == 61 62 63 64  abcd\n\
+  ///    00000004  65 66 67 68  efgh != 65 66 78 68  efxh\n\
+  ///    00000008                    != 69           i   \n"
+  /// );
+  /// ```
+  ///
+  /// The default implementation compares the lines of the dumps of
+  /// [to_hex_with](HexDisplay::to_hex_with), so its sides are not padded to the same width.
+  fn hex_diff(&self, actual: &Self, format: &HexFormat) -> String {
+    let expected = self.to_hex_with(format);
+    let found = actual.to_hex_with(format);
+    let mut expected = expected.lines();
+    let mut found = found.lines();
+
+    let mut diff = String::new();
+    let mut address = format.offset;
+    loop {
+      // each line starts with its address and a tab
+      let (e, f) = match (expected.next(), found.next()) {
+        (None, None) => break,
+        (e, f) => (
+          e.map_or("", |l| l.split_once('\t').map_or(l, |(_, rest)| rest)),
+          f.map_or("", |l| l.split_once('\t').map_or(l, |(_, rest)| rest)),
+        ),
+      };
+      let separator = if e == f { "==" } else { "!=" };
+      diff.push_str(&format!("{:08x}  {} {} {}\n", address, e, separator, f));
+      address += format.width;
+    }
+    diff
+  }
+}
+
+/// Layout of the hex dumps of [HexDisplay]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HexFormat {
+  width: usize,
+  offset: usize,
+  ascii: bool,
+}
+
+impl HexFormat {
+  /// Creates a layout of 16 bytes per line, starting at address 0, with an ASCII column
+  pub fn new() -> Self {
+    HexFormat {
+      width: 16,
+      offset: 0,
+      ascii: true,
+    }
+  }
+
+  /// Sets the number of bytes per line
+  ///
+  /// # Panics
+  ///
+  /// Panics if `width` is 0.
+  pub fn width(mut self, width: usize) -> Self {
+    assert!(width > 0, "HexFormat: the width must not be 0");
+    self.width = width;
+    self
+  }
+
+  /// Sets the address of the first byte
+  pub fn offset(mut self, offset: usize) -> Self {
+    self.offset = offset;
+    self
+  }
+
+  /// Sets whether the bytes are also shown as ASCII characters after their hex values
+  pub fn ascii(mut self, ascii: bool) -> Self {
+    self.ascii = ascii;
+    self
+  }
+
+  /// Writes the hex dump of `bytes` laid out by this format to `w`
+  ///
+  /// This is the dump returned by [HexDisplay::to_hex_with], written without
+  /// allocating, so it is available in `no_std` builds and can go directly to a
+  /// `Formatter`.
+  ///
+  /// ```rust
+  /// use nom::HexFormat;
+  ///
+  /// let mut dump = String::new();
+  /// HexFormat::new().width(4).write(&mut dump, b"nom\x00\x01").unwrap();
+  /// assert_eq!(dump, "00000000\t6e 6f 6d 00 \tnom.\n00000004\t01          \t.\n");
+  /// ```
+  pub fn write<W: fmt::Write>(&self, w: &mut W, bytes: &[u8]) -> fmt::Result {
+    let mut address = self.offset;
+    for chunk in bytes.chunks(self.width) {
+      write!(w, "{:08x}\t", address)?;
+      address += self.width;
+
+      for byte in chunk {
+        write!(w, "{:02x} ", byte)?;
+      }
+      for _ in chunk.len()..self.width {
+        w.write_str("   ")?;
+      }
+
+      if self.ascii {
+        w.write_char('\t')?;
+        write_text(w, chunk)?;
+      }
+      w.write_char('\n')?;
+    }
+    Ok(())
+  }
+}
+
+/// Writes the bytes of `chunk` as UTF-8 text, replacing the control characters with `.`
+/// and the invalid sequences with `U+FFFD`
+fn write_text<W: fmt::Write>(w: &mut W, chunk: &[u8]) -> fmt::Result {
+  // control characters are never part of a multibyte sequence, so they split the text
+  for (i, text) in chunk
+    .split(|byte| !matches!(byte, 32..=126 | 128..=255))
+    .enumerate()
+  {
+    if i > 0 {
+      w.write_char('.')?;
+    }
+
+    let mut rest = text;
+    loop {
+      match from_utf8(rest) {
+        Ok(valid) => {
+          w.write_str(valid)?;
+          break;
+        }
+        Err(e) => {
+          let (valid, invalid) = rest.split_at(e.valid_up_to());
+          w.write_str(from_utf8(valid).unwrap_or_default())?;
+          w.write_char(char::REPLACEMENT_CHARACTER)?;
+          rest = &invalid[e.error_len().unwrap_or(invalid.len())..];
+        }
+      }
+    }
+  }
+  Ok(())
+}
+
+impl Default for HexFormat {
+  fn default() -> Self {
+    HexFormat::new()
+  }
 }
 
 #[cfg(feature = "std")]
 static CHARS: &[u8] = b"0123456789abcdef";
 
+/// Writes the hex values of `chunk`, padded to `width` bytes
+#[cfg(feature = "std")]
+fn push_hex(v: &mut Vec<u8>, chunk: &[u8], width: usize) {
+  for &byte in chunk {
+    v.push(CHARS[(byte >> 4) as usize]);
+    v.push(CHARS[(byte & 0xf) as usize]);
+    v.push(b' ');
+  }
+  for _ in chunk.len()..width {
+    v.extend_from_slice(b"   ");
+  }
+}
+
 #[cfg(feature = "std")]
 impl HexDisplay for [u8] {
   #[allow(unused_variables)]
@@ -1338,38 +3692,53 @@ impl HexDisplay for [u8] {
 
   #[allow(unused_variables)]
   fn to_hex_from(&self, chunk_size: usize, from: usize) -> String {
-    let mut v = Vec::with_capacity(self.len() * 3);
-    let mut i = from;
-    for chunk in self.chunks(chunk_size) {
-      let s = format!("{:08x}", i);
-      for &ch in s.as_bytes().iter() {
-        v.push(ch);
-      }
-      v.push(b'\t');
+    self.to_hex_with(&HexFormat::new().width(chunk_size).offset(from))
+  }
+
+  fn to_hex_with(&self, format: &HexFormat) -> String {
+    let mut dump = String::with_capacity(self.len() * 4);
+    // writing to a `String` cannot fail
+    let _ = format.write(&mut dump, self);
+    dump
+  }
 
-      i += chunk_size;
+  fn to_hex_window(&self, offset: usize, context_lines: usize) -> String {
+    let row = offset.min(self.len()) / 16;
+    let start = row.saturating_sub(context_lines) * 16;
+    let end = row
+      .saturating_add(context_lines)
+      .saturating_add(1)
+      .saturating_mul(16)
+      .min(self.len());
+    self[start..end].to_hex_from(16, start)
+  }
 
-      for &byte in chunk {
-        v.push(CHARS[(byte >> 4) as usize]);
-        v.push(CHARS[(byte & 0xf) as usize]);
+  fn hex_diff(&self, actual: &Self, format: &HexFormat) -> String {
+    // the ASCII columns are padded to keep the sides aligned, so they only show ASCII
+    // characters
+    let side = |v: &mut Vec<u8>, chunk: &[u8]| {
+      push_hex(v, chunk, format.width);
+      if format.ascii {
         v.push(b' ');
-      }
-      if chunk_size > chunk.len() {
-        for j in 0..(chunk_size - chunk.len()) {
-          v.push(b' ');
-          v.push(b' ');
+        for &byte in chunk {
+          v.push(if matches!(byte, 32..=126) { byte } else { b'.' });
+        }
+        for _ in chunk.len()..format.width {
           v.push(b' ');
         }
       }
-      v.push(b'\t');
+    };
 
-      for &byte in chunk {
-        if matches!(byte, 32..=126 | 128..=255) {
-          v.push(byte);
-        } else {
-          v.push(b'.');
-        }
-      }
+    let len = self.len().max(actual.len());
+    let mut v = Vec::with_capacity(len * 8);
+    for start in (0..len).step_by(format.width) {
+      let expected = &self[start.min(self.len())..(start + format.width).min(self.len())];
+      let found = &actual[start.min(actual.len())..(start + format.width).min(actual.len())];
+
+      v.extend_from_slice(format!("{:08x}  ", format.offset + start).as_bytes());
+      side(&mut v, expected);
+      v.extend_from_slice(if expected == found { b" == " } else { b" != " });
+      side(&mut v, found);
       v.push(b'\n');
     }
 
@@ -1388,6 +3757,18 @@ impl HexDisplay for str {
   fn to_hex_from(&self, chunk_size: usize, from: usize) -> String {
     self.as_bytes().to_hex_from(chunk_size, from)
   }
+
+  fn to_hex_with(&self, format: &HexFormat) -> String {
+    self.as_bytes().to_hex_with(format)
+  }
+
+  fn to_hex_window(&self, offset: usize, context_lines: usize) -> String {
+    self.as_bytes().to_hex_window(offset, context_lines)
+  }
+
+  fn hex_diff(&self, actual: &Self, format: &HexFormat) -> String {
+    self.as_bytes().hex_diff(actual.as_bytes(), format)
+  }
 }
 
 /// A saturating iterator for usize.
@@ -1629,6 +4010,51 @@ impl NomRange<usize> for usize {
 mod tests {
   use super::*;
 
+  #[test]
+  fn test_utf16() {
+    use crate::bytes::complete::{tag, tag_no_case, take};
+    use crate::character::complete::{alpha1, char};
+    use crate::error::Error;
+    use crate::Parser;
+
+    let units: Vec<u16> = "MZ\u{1F600}é abc".encode_utf16().collect();
+    let input = Utf16::new(&units);
+    let at = |index: usize| Utf16::new(&units[index..]);
+
+    let (rest, mz) = tag::<_, _, Error<_>>("MZ").parse(input).unwrap();
+    assert_eq!(mz.as_slice(), &units[..2]);
+    assert_eq!(input.offset(&rest), 2);
+
+    // the emoji is a surrogate pair, counted as one character of 2 code units
+    let (rest, emoji) = take::<_, _, Error<_>>(1usize).parse(rest).unwrap();
+    assert_eq!(emoji.input_len(), 2);
+    let (rest, _) = tag::<_, _, Error<_>>("\u{E9}").parse(rest).unwrap();
+    let (rest, _) = char::<_, Error<_>>(' ').parse(rest).unwrap();
+    assert_eq!(alpha1::<_, Error<_>>(rest), Ok((at(9), at(6))));
+
+    assert_eq!(
+      tag_no_case::<_, _, Error<_>>("mz\u{1F600}\u{C9}").parse(input),
+      Ok((at(5), Utf16::new(&units[..5])))
+    );
+    assert_eq!(
+      input.compare("MZ\u{1F600}é abc!"),
+      CompareResult::Incomplete
+    );
+    assert_eq!(input.compare("MA"), CompareResult::Error);
+    assert_eq!(input.compare(Utf16::new(&units[..2])), CompareResult::Ok);
+    assert_eq!(
+      input.compare_no_case(Utf16::new(&[0x6D, 0x7A])),
+      CompareResult::Ok
+    );
+
+    // unpaired surrogates are decoded as replacement characters
+    let broken = [0xD800, 0x61];
+    let mut chars = Utf16::new(&broken).iter_elements();
+    assert_eq!(chars.next().map(AsChar::as_char), Some('\u{FFFD}'));
+    assert_eq!(chars.next().map(AsChar::as_char), Some('a'));
+    assert_eq!(chars.next(), None);
+  }
+
   #[test]
   fn test_offset_u8() {
     let s = b"abcd123";
@@ -1678,4 +4104,222 @@ mod tests {
 
     assert!(a.slice_index(8).is_err());
   }
+
+  #[test]
+  #[cfg(feature = "unicode")]
+  fn test_special_case_folding_sorted() {
+    assert!(SPECIAL_CASE_FOLDING.windows(2).all(|w| w[0].0 < w[1].0));
+    assert!(CaseFold::new('\u{1E9E}').eq(['s', 's']));
+    assert!(CaseFold::new('A').eq(['a']));
+  }
+
+  #[test]
+  fn test_segmented() {
+    use crate::bytes::complete::{tag, tag_no_case, take, take_until, take_while1};
+    use crate::bytes::streaming;
+    use crate::error::Error;
+    use crate::number::complete::be_u16;
+    use crate::Parser;
+
+    let segments: [&[u8]; 4] = [b"GE", b"", b"T /ind", b"ex HTTP/1.1\r\n\x01\x02"];
+    let input = Segmented::new(&segments);
+    assert_eq!(input.input_len(), 23);
+
+    // tokens can span several segments
+    let (rest, method) = tag::<_, _, Error<_>>("GET").parse(input).unwrap();
+    assert_eq!(method.segments().collect::<Vec<_>>(), [&b"GE"[..], b"T"]);
+    assert_eq!(method.as_contiguous(), None);
+    assert_eq!(input.offset(&rest), 3);
+
+    let (rest, _) = take::<_, _, Error<_>>(1usize).parse(rest).unwrap();
+    let (rest, path) = take_while1::<_, _, Error<_>>(|b| b != b' ')
+      .parse(rest)
+      .unwrap();
+    assert_eq!(path.iter_elements().collect::<Vec<_>>(), b"/index");
+    let (rest, version) = take_until::<_, _, Error<_>>("\r\n").parse(rest).unwrap();
+    assert_eq!(version.as_contiguous(), Some(&b" HTTP/1.1"[..]));
+    let (rest, _) = tag_no_case::<_, _, Error<_>>("\r\n").parse(rest).unwrap();
+    assert_eq!(be_u16::<_, Error<_>>(rest).map(|(_, n)| n), Ok(0x0102));
+
+    assert_eq!(input.find_substring("T /i"), Some(2));
+    assert_eq!(input.find_substring("1.1\r"), Some(16));
+    assert_eq!(input.take(10).find_substring("HTTP"), None);
+    assert_eq!(input.compare("GET /index!"), CompareResult::Error);
+    assert_eq!(input.take(5).compare("GET /i"), CompareResult::Incomplete);
+    assert_eq!(
+      streaming::tag::<_, _, Error<_>>("GET /in").parse(input.take(5)),
+      Err(Err::Incomplete(Needed::new(2)))
+    );
+
+    // equality compares the bytes, whatever the segments
+    let other: [&[u8]; 2] = [b"GET /", b"index"];
+    assert_eq!(input.take(10), Segmented::new(&other));
+    assert_ne!(input.take(9), Segmented::new(&other));
+  }
+
+  #[test]
+  #[cfg(feature = "bytes")]
+  fn test_bytes() {
+    use crate::bytes::complete::{tag, take_while1};
+    use crate::error::Error;
+    use crate::number::complete::be_u16;
+    use crate::number::double;
+    use crate::Parser;
+    use bytes::Bytes;
+
+    let input = Bytes::from(b"\x00\x05value1.5;".to_vec());
+    let (rest, len) = be_u16::<_, Error<_>>(input.clone()).unwrap();
+    assert_eq!(len, 5);
+    let (rest, name) = take_while1::<_, _, Error<_>>(|b: u8| b.is_ascii_alphabetic())
+      .parse(rest)
+      .unwrap();
+    assert_eq!(name, Bytes::from_static(b"value"));
+    assert_eq!(input.offset(&name), 2);
+    // the parsed parts share the buffer of the input
+    assert_eq!(name.as_ptr(), input[2..].as_ptr());
+
+    let (rest, value) = double::<_, Error<_>>().parse_complete(rest).unwrap();
+    assert_eq!(value, 1.5);
+    assert_eq!(rest.compare(";!"), CompareResult::Incomplete);
+    assert_eq!(
+      tag::<_, _, Error<_>>(";").parse(rest),
+      Ok((Bytes::new(), Bytes::from_static(b";")))
+    );
+    assert_eq!(input.find_substring("1.5"), Some(7));
+  }
+
+  #[test]
+  fn test_partial() {
+    use crate::bytes::{tag, take, take_while};
+    use crate::character::char;
+    use crate::error::Error;
+    use crate::{Err, Parser};
+
+    type E<'a> = Error<Partial<&'a [u8]>>;
+
+    let input = Partial::new(&b"ab"[..]);
+    // the mode of the caller does not matter
+    assert_eq!(
+      tag::<_, _, E<'_>>("abc").parse_complete(input),
+      Err(Err::Incomplete(Needed::new(1)))
+    );
+    assert_eq!(
+      take_while::<_, _, E<'_>>(|c: u8| c.is_ascii_alphabetic()).parse_complete(input),
+      Err(Err::Incomplete(Needed::new(1)))
+    );
+    assert_eq!(
+      char::<_, E<'_>>('a').parse(Partial::new(&b""[..])),
+      Err(Err::Incomplete(Needed::new(1)))
+    );
+
+    let input = input.complete();
+    assert!(!input.is_partial());
+    assert_eq!(
+      tag::<_, _, E<'_>>("abc").parse(input),
+      Err(Err::Error(Error::new(input, ErrorKind::Tag)))
+    );
+    let (rest, word) = take_while::<_, _, E<'_>>(|c: u8| c.is_ascii_alphabetic())
+      .parse(input)
+      .unwrap();
+    assert_eq!((*rest, *word), (&b""[..], &b"ab"[..]));
+    // the parts keep the completeness of the input
+    assert!(!rest.is_partial());
+
+    let (rest, a) = (char::<_, E<'_>>('a'), take(1u8))
+      .parse(Partial::new(&b"abc"[..]))
+      .unwrap();
+    assert_eq!(
+      (rest.into_inner(), a.1.into_inner()),
+      (&b"c"[..], &b"b"[..])
+    );
+  }
+
+  #[test]
+  #[cfg(feature = "std")]
+  fn hex_display() {
+    let data = &b"hello\nworld"[..];
+    assert_eq!(
+      data.to_hex(8),
+      "00000000\t68 65 6c 6c 6f 0a 77 6f \thello.wo\n00000008\t72 6c 64                \trld\n"
+    );
+    assert_eq!(
+      data[8..].to_hex_from(8, 8),
+      "00000008\t72 6c 64                \trld\n"
+    );
+    assert_eq!(
+      "ab".to_hex_with(&HexFormat::new().ascii(false).width(2)),
+      "00000000\t61 62 \n"
+    );
+
+    let format = HexFormat::new().width(2).offset(0x100).ascii(false);
+    assert_eq!(
+      data[..4].hex_diff(&data[..4], &format),
+      "00000100  68 65  == 68 65 \n00000102  6c 6c  == 6c 6c \n"
+    );
+    assert_eq!(
+      data[..3].hex_diff(&data[1..3], &format),
+      "00000100  68 65  != 65 6c \n00000102  6c     !=       \n"
+    );
+    assert_eq!(b"".hex_diff(b"", &format), "");
+
+    // the text column replaces invalid UTF-8 sequences
+    assert_eq!(
+      "é\u{1}".as_bytes()[..].to_hex(3),
+      "00000000\tc3 a9 01 \té.\n"
+    );
+    assert_eq!(
+      b"\xc3a\xe2\x82"[..].to_hex(4),
+      "00000000\tc3 61 e2 82 \t\u{fffd}a\u{fffd}\n"
+    );
+
+    let data = (0..100u8).collect::<Vec<_>>();
+    assert_eq!(data.to_hex_window(40, 1), data[16..64].to_hex_from(16, 16));
+    assert_eq!(data.to_hex_window(0, 2), data[..48].to_hex(16));
+    assert_eq!(data.to_hex_window(99, 1), data[80..].to_hex_from(16, 80));
+    assert_eq!(data.to_hex_window(1000, 0), data[96..].to_hex_from(16, 96));
+    assert_eq!(data.to_hex_window(50, usize::MAX), data.to_hex(16));
+    assert_eq!(b"".to_hex_window(0, 1), "");
+  }
+
+  #[test]
+  #[cfg(feature = "std")]
+  fn hex_display_defaults() {
+    // an implementation providing only the required methods
+    struct Dump(Vec<u8>);
+    impl HexDisplay for Dump {
+      fn to_hex(&self, chunk_size: usize) -> String {
+        self.0.to_hex(chunk_size)
+      }
+
+      fn to_hex_from(&self, chunk_size: usize, from: usize) -> String {
+        self.0.to_hex_from(chunk_size, from)
+      }
+    }
+
+    let data = b"hello\nworld".to_vec();
+    let dump = Dump(data.clone());
+    for format in [
+      HexFormat::new(),
+      HexFormat::new().width(4).offset(0x10),
+      HexFormat::new().width(3).ascii(false),
+    ] {
+      assert_eq!(dump.to_hex_with(&format), data.to_hex_with(&format));
+    }
+    let long = (0..50u8).collect::<Vec<_>>();
+    let long_dump = Dump(long.clone());
+    for (offset, context_lines) in [(0, 0), (20, 1), (40, 0), (49, 2), (100, 1), (3, usize::MAX)] {
+      assert_eq!(
+        long_dump.to_hex_window(offset, context_lines),
+        long.to_hex_window(offset, context_lines)
+      );
+    }
+
+    let format = HexFormat::new().width(4).offset(0x10);
+    assert_eq!(
+      dump.hex_diff(&Dump(b"hello\nwo".to_vec()), &format),
+      "00000010  68 65 6c 6c \thell == 68 65 6c 6c \thell\n\
+       00000014  6f 0a 77 6f \to.wo == 6f 0a 77 6f \to.wo\n\
+       00000018  72 6c 64    \trld != \n"
+    );
+  }
 }